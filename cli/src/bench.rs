@@ -0,0 +1,233 @@
+// `printnanny bench camera` - run pipeline legs across a matrix of
+// resolutions/framerates/encoders against the local gstd instance, measuring
+// CPU, dropped buffers, and startup latency, and emit a JSON report so users
+// can pick settings matched to their Pi and camera.
+use std::fs::read_to_string;
+use std::time::Instant;
+
+use anyhow::Result;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tokio::time::{sleep, Duration};
+
+use printnanny_gst_pipelines::factory::{GstPipelineState, PrintNannyPipelineFactory};
+use printnanny_gst_pipelines::gst_client::gstd_types;
+use printnanny_settings::printnanny::PrintNannySettings;
+
+const BENCH_PIPELINE: &str = "bench_camera";
+const BENCH_VIDEORATE: &str = "bench_videorate";
+const PROC_STAT_PATH: &str = "/proc/stat";
+const STARTUP_TIMEOUT_MS: u64 = 15000;
+
+// benchmark matrix: resolution x framerate x encoder leg
+const RESOLUTIONS: [(i32, i32); 3] = [(640, 480), (1280, 720), (1920, 1080)];
+const FRAMERATES: [i32; 2] = [15, 30];
+// None benchmarks the camera leg without an encoder
+const ENCODERS: [Option<&str>; 2] = [None, Some("v4l2h264enc")];
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BenchResult {
+    pub width: i32,
+    pub height: i32,
+    pub framerate: i32,
+    pub encoder: Option<String>,
+    pub duration_seconds: u64,
+    pub startup_latency_ms: u64,
+    pub cpu_percent: f64,
+    pub buffers_in: i32,
+    pub buffers_out: i32,
+    pub dropped_buffers: i32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BenchReport {
+    pub camera: String,
+    pub results: Vec<BenchResult>,
+}
+
+// busy and total jiffies from the aggregate cpu line of /proc/stat
+fn parse_proc_stat(content: &str) -> Option<(u64, u64)> {
+    let line = content.lines().find(|line| line.starts_with("cpu "))?;
+    let fields: Vec<u64> = line
+        .split_whitespace()
+        .skip(1)
+        .filter_map(|field| field.parse().ok())
+        .collect();
+    if fields.len() < 4 {
+        return None;
+    }
+    let idle = fields[3] + fields.get(4).unwrap_or(&0);
+    let total: u64 = fields.iter().sum();
+    Some((total - idle, total))
+}
+
+fn property_i32(response: &gstd_types::Response) -> Option<i32> {
+    match &response.response {
+        gstd_types::ResponseT::Property(prop) => match prop.value {
+            gstd_types::PropertyValue::Integer(value) => Some(value),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+pub struct BenchCommand;
+
+impl BenchCommand {
+    fn bench_description(
+        camera_name: &str,
+        width: i32,
+        height: i32,
+        framerate: i32,
+        encoder: Option<&str>,
+    ) -> String {
+        let encode_leg = match encoder {
+            Some(encoder) => format!(
+                "! v4l2convert ! {encoder} extra-controls=controls,repeat_sequence_header=1 ! h264parse "
+            ),
+            None => "! v4l2convert ".to_string(),
+        };
+        format!(
+            "libcamerasrc camera-name={camera_name} \
+            ! capsfilter caps=video/x-raw,width={width},height={height},framerate={framerate}/1,format=YUY2 \
+            ! videorate name={BENCH_VIDEORATE} \
+            {encode_leg}! fakesink sync=false"
+        )
+    }
+
+    async fn read_videorate_counter(
+        factory: &PrintNannyPipelineFactory,
+        property: &str,
+    ) -> Result<i32> {
+        let client = factory.gst_client();
+        let response = client
+            .pipeline(BENCH_PIPELINE)
+            .element(BENCH_VIDEORATE)
+            .property(property)
+            .await?;
+        property_i32(&response).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Failed to read {} property from element {}",
+                property,
+                BENCH_VIDEORATE
+            )
+        })
+    }
+
+    async fn run_bench(
+        factory: &PrintNannyPipelineFactory,
+        camera_name: &str,
+        width: i32,
+        height: i32,
+        framerate: i32,
+        encoder: Option<&str>,
+        duration_seconds: u64,
+    ) -> Result<BenchResult> {
+        let client = factory.gst_client();
+        // remove any leftover bench pipeline from a previous run
+        if client.pipeline(BENCH_PIPELINE).delete().await.is_ok() {
+            info!("Deleted existing pipeline={}", BENCH_PIPELINE);
+        }
+
+        let description = Self::bench_description(camera_name, width, height, framerate, encoder);
+        info!("Benchmarking pipeline: {}", description);
+        let pipeline = client.pipeline(BENCH_PIPELINE);
+        pipeline.create(&description).await?;
+
+        let started = Instant::now();
+        pipeline.play().await?;
+        while factory.pipeline_state(BENCH_PIPELINE).await != GstPipelineState::Playing {
+            if started.elapsed() > Duration::from_millis(STARTUP_TIMEOUT_MS) {
+                pipeline.delete().await?;
+                anyhow::bail!(
+                    "Pipeline {} did not reach PLAYING within {}ms",
+                    BENCH_PIPELINE,
+                    STARTUP_TIMEOUT_MS
+                );
+            }
+            sleep(Duration::from_millis(100)).await;
+        }
+        let startup_latency_ms = started.elapsed().as_millis() as u64;
+
+        let (busy_before, total_before) = parse_proc_stat(&read_to_string(PROC_STAT_PATH)?)
+            .ok_or_else(|| anyhow::anyhow!("Failed to parse {}", PROC_STAT_PATH))?;
+        sleep(Duration::from_secs(duration_seconds)).await;
+        let (busy_after, total_after) = parse_proc_stat(&read_to_string(PROC_STAT_PATH)?)
+            .ok_or_else(|| anyhow::anyhow!("Failed to parse {}", PROC_STAT_PATH))?;
+        let cpu_percent = match total_after.saturating_sub(total_before) {
+            0 => 0.0,
+            total => (busy_after.saturating_sub(busy_before)) as f64 / total as f64 * 100.0,
+        };
+
+        let buffers_in = Self::read_videorate_counter(factory, "in").await?;
+        let buffers_out = Self::read_videorate_counter(factory, "out").await?;
+        let dropped_buffers = Self::read_videorate_counter(factory, "drop").await?;
+
+        pipeline.stop().await?;
+        pipeline.delete().await?;
+
+        Ok(BenchResult {
+            width,
+            height,
+            framerate,
+            encoder: encoder.map(String::from),
+            duration_seconds,
+            startup_latency_ms,
+            cpu_percent,
+            buffers_in,
+            buffers_out,
+            dropped_buffers,
+        })
+    }
+
+    async fn camera(args: &clap::ArgMatches) -> Result<()> {
+        let address = args.value_of("http-address").unwrap();
+        let port: i32 = args.value_of_t("http-port").unwrap();
+        let duration_seconds: u64 = args.value_of_t("duration").unwrap();
+        let factory = PrintNannyPipelineFactory::new(address.into(), port);
+
+        let settings = PrintNannySettings::new().await?;
+        let camera_name = settings.video_stream.camera.device_name.clone();
+
+        let mut results = Vec::new();
+        for (width, height) in RESOLUTIONS {
+            for framerate in FRAMERATES {
+                for encoder in ENCODERS {
+                    match Self::run_bench(
+                        &factory,
+                        &camera_name,
+                        width,
+                        height,
+                        framerate,
+                        encoder,
+                        duration_seconds,
+                    )
+                    .await
+                    {
+                        Ok(result) => results.push(result),
+                        // camera may not support every mode in the matrix;
+                        // record what ran and keep going
+                        Err(e) => warn!(
+                            "Skipping bench width={} height={} framerate={} encoder={:?} error={}",
+                            width, height, framerate, encoder, e
+                        ),
+                    }
+                }
+            }
+        }
+
+        let report = BenchReport {
+            camera: camera_name,
+            results,
+        };
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        Ok(())
+    }
+
+    pub async fn handle(args: &clap::ArgMatches) -> Result<()> {
+        match args.subcommand() {
+            Some(("camera", args)) => Self::camera(args).await,
+            _ => unimplemented!(),
+        }
+    }
+}
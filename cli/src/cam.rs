@@ -2,8 +2,17 @@ use std::io;
 use std::io::Write;
 
 use anyhow::{Ok, Result};
+use log::{info, warn};
 
 use printnanny_gst_pipelines::factory::PrintNannyPipelineFactory;
+use printnanny_services::camera_conflict;
+use printnanny_services::fluent::FluentArgs;
+use printnanny_services::localization::Localizer;
+use printnanny_services::stream_token::{issue_stream_token, StreamEndpoint};
+use printnanny_settings::hooks::HookEvent;
+use printnanny_settings::printnanny::PrintNannySettings;
+use printnanny_settings::stream_proxy::render_stream_proxy_conf;
+use printnanny_settings::vcs::VersionControlledSettings;
 use printnanny_settings::{cam::CameraVideoSource, SettingsFormat};
 
 pub struct CameraCommand;
@@ -26,8 +35,30 @@ impl CameraCommand {
     async fn start_pipelines(args: &clap::ArgMatches) -> Result<()> {
         let address = args.value_of("http-address").unwrap();
         let port: i32 = args.value_of_t("http-port").unwrap();
+        let report = camera_conflict::detect_camera_conflicts().await?;
+        if !report.is_clear() {
+            if args.is_present("takeover") {
+                camera_conflict::stop_conflicting_units(&report).await?;
+            } else {
+                for unit in &report.conflicting_units {
+                    warn!("{} is active and may be holding the camera device. Re-run with --takeover to stop it, or: sudo systemctl stop {}", unit, unit);
+                }
+            }
+        }
         let factory = PrintNannyPipelineFactory::new(address.into(), port);
-        factory.start_pipelines().await?;
+        if let Err(e) = factory.start_pipelines().await {
+            // camera_error hooks fire before the error propagates, so users
+            // can wire up notifications for cameras dying mid-print
+            if let core::result::Result::Ok(settings) = PrintNannySettings::new().await {
+                printnanny_services::hooks::run_hooks(
+                    &settings,
+                    HookEvent::CameraError,
+                    &serde_json::json!({ "error": e.to_string() }),
+                )
+                .await;
+            }
+            return Err(e);
+        }
         Ok(())
     }
 
@@ -50,9 +81,89 @@ impl CameraCommand {
     //     Ok(())
     // }
 
+    // print glass-to-glass latency estimates for the live view legs
+    async fn latency() -> Result<()> {
+        let settings = PrintNannySettings::new().await?;
+        let report = printnanny_services::latency::measure_latency(&settings);
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        Ok(())
+    }
+
+    // mint a short-lived signed token gating access to local stream endpoints
+    async fn share(args: &clap::ArgMatches) -> Result<()> {
+        let ttl_seconds: u64 = args.value_of_t("ttl-seconds")?;
+        let endpoints: Vec<StreamEndpoint> = args.values_of_t("endpoint")?;
+        let settings = PrintNannySettings::new().await?;
+        let token = issue_stream_token(
+            &settings.paths,
+            std::time::Duration::from_secs(ttl_seconds),
+            endpoints,
+        )?;
+        println!("{}", serde_json::to_string_pretty(&token)?);
+        Ok(())
+    }
+
+    // render the proxy config enforcing viewer auth on the camera endpoints
+    async fn proxy_config(args: &clap::ArgMatches) -> Result<()> {
+        let settings = PrintNannySettings::new().await?;
+        let conf = render_stream_proxy_conf(&settings);
+        match args.is_present("apply") {
+            true => {
+                let stream_proxy_settings = settings.to_stream_proxy_settings();
+                stream_proxy_settings
+                    .save_and_commit(
+                        &conf,
+                        Some("Rendered stream proxy config from PrintNannySettings".to_string()),
+                    )
+                    .await?;
+                info!(
+                    "Committed {} and reloaded nginx.service",
+                    stream_proxy_settings.settings_file.display()
+                );
+            }
+            false => println!("{}", conf),
+        }
+        Ok(())
+    }
+
+    async fn privacy(args: &clap::ArgMatches) -> Result<()> {
+        let enabled = match args.value_of("state").unwrap() {
+            "enable" => true,
+            "disable" => false,
+            state => unimplemented!("privacy is not implemented for state: {}", state),
+        };
+        let mut settings = PrintNannySettings::new().await?;
+        settings.video_stream.privacy_mode = enabled;
+        let content = settings.to_toml_string()?;
+        let now = std::time::SystemTime::now();
+        settings
+            .save_and_commit(
+                &content,
+                Some(format!(
+                    "PrintNannySettings.video_stream.privacy_mode={} updated at {:?}",
+                    enabled, now
+                )),
+            )
+            .await?;
+
+        let factory = PrintNannyPipelineFactory::default();
+        factory.set_privacy_mode(enabled).await?;
+        let mut args = FluentArgs::new();
+        args.set("enabled", enabled.to_string());
+        println!(
+            "{}",
+            Localizer::new(&settings.locale).msg_with_args("privacy-mode", Some(&args))
+        );
+        Ok(())
+    }
+
     pub async fn handle(args: &clap::ArgMatches) -> Result<()> {
         match args.subcommand() {
+            Some(("latency", _args)) => Self::latency().await,
             Some(("list", args)) => Self::list(args).await,
+            Some(("privacy", args)) => Self::privacy(args).await,
+            Some(("proxy-config", args)) => Self::proxy_config(args).await,
+            Some(("share", args)) => Self::share(args).await,
             // Some(("start-multifilesink-listener", args)) => {
             //     Self::start_multifilesink_listener(args).await
             // }
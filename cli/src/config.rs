@@ -0,0 +1,88 @@
+use anyhow::{bail, Result};
+use std::io::{self, Write};
+
+use printnanny_services::fluent::FluentArgs;
+use printnanny_services::localization::Localizer;
+use printnanny_settings::printnanny::PrintNannySettings;
+use printnanny_settings::validation::validate_settings;
+use printnanny_settings::vcs::VersionControlledSettings;
+use printnanny_settings::SettingsFormat;
+
+pub struct ConfigCommand;
+
+// parse a raw CLI value as a TOML literal (40, true, 1.5, [1,2]), falling back to a string
+fn parse_toml_value(raw: &str) -> toml::Value {
+    let doc = format!("value = {}", raw);
+    match doc.parse::<toml::Value>() {
+        Ok(toml::Value::Table(mut table)) => table
+            .remove("value")
+            .unwrap_or_else(|| toml::Value::String(raw.to_string())),
+        _ => toml::Value::String(raw.to_string()),
+    }
+}
+
+fn set_dotted_path(root: &mut toml::Value, key: &str, value: toml::Value) -> Result<()> {
+    let mut value = Some(value);
+    let mut current = root;
+    let mut segments = key.split('.').peekable();
+    while let Some(segment) = segments.next() {
+        let table = match current.as_table_mut() {
+            Some(table) => table,
+            None => bail!("config key {} does not address a table", key),
+        };
+        if segments.peek().is_none() {
+            table.insert(segment.to_string(), value.take().unwrap());
+            return Ok(());
+        }
+        current = table
+            .entry(segment.to_string())
+            .or_insert_with(|| toml::Value::Table(toml::value::Table::new()));
+    }
+    bail!("Expected non-empty config key")
+}
+
+impl ConfigCommand {
+    pub async fn handle(sub_m: &clap::ArgMatches) -> Result<()> {
+        match sub_m.subcommand() {
+            Some(("get", args)) => {
+                let key = args.value_of("key").unwrap();
+                let f: SettingsFormat = args.value_of_t("format").unwrap();
+                let data = PrintNannySettings::find_value(key).await?;
+                let v = match f {
+                    SettingsFormat::Json => serde_json::to_vec_pretty(&data)?,
+                    SettingsFormat::Toml => toml::ser::to_vec(&data)?,
+                    _ => unimplemented!("config get is not implemented for format: {}", f),
+                };
+                io::stdout().write_all(&v)?;
+            }
+            Some(("set", args)) => {
+                let pair = args.value_of("pair").unwrap();
+                let (key, raw) = match pair.split_once('=') {
+                    Some((key, raw)) => (key, raw),
+                    None => bail!("Expected KEY=VALUE, received: {}", pair),
+                };
+                let settings = PrintNannySettings::new().await?;
+                let mut doc = toml::Value::try_from(&settings)?;
+                set_dotted_path(&mut doc, key, parse_toml_value(raw))?;
+                let updated: PrintNannySettings = doc.try_into()?;
+                validate_settings(&updated)?;
+                let content = updated.to_toml_string()?;
+                updated
+                    .save_and_commit(
+                        &content,
+                        Some(format!("PrintNannySettings.{} set to {}", key, raw)),
+                    )
+                    .await?;
+                let mut args = FluentArgs::new();
+                args.set("key", key);
+                args.set("value", raw);
+                println!(
+                    "{}",
+                    Localizer::new(&updated.locale).msg_with_args("config-set", Some(&args))
+                );
+            }
+            _ => panic!("Expected get|set subcommand"),
+        };
+        Ok(())
+    }
+}
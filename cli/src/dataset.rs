@@ -0,0 +1,29 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::ArgMatches;
+
+use printnanny_services::dataset::export_dataset;
+use printnanny_settings::printnanny::PrintNannySettings;
+
+pub struct DatasetCommand;
+
+impl DatasetCommand {
+    async fn export(args: &ArgMatches) -> Result<()> {
+        let settings = PrintNannySettings::new().await?;
+        let output = PathBuf::from(args.value_of("output").unwrap());
+        let sample_every: usize = args.value_of_t("sample_every")?;
+        let hours: i64 = args.value_of_t("hours")?;
+
+        let manifest = export_dataset(&settings, &output, sample_every, hours)?;
+        println!("{}", serde_json::to_string_pretty(&manifest)?);
+        Ok(())
+    }
+
+    pub async fn handle(args: &ArgMatches) -> Result<()> {
+        match args.subcommand() {
+            Some(("export", args)) => Self::export(args).await,
+            _ => unimplemented!(),
+        }
+    }
+}
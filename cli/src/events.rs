@@ -0,0 +1,111 @@
+use anyhow::Result;
+use clap::ArgMatches;
+use futures_util::StreamExt;
+use log::{debug, warn};
+use std::path::PathBuf;
+
+use printnanny_nats_client::client::{try_init_nats_client_with_tls, NatsTlsOptions};
+use printnanny_services::fluent::FluentArgs;
+use printnanny_services::localization::Localizer;
+use printnanny_settings::printnanny::PrintNannySettings;
+use printnanny_settings::sys_info;
+
+pub struct EventsCommand;
+
+impl EventsCommand {
+    fn default_subject() -> String {
+        let hostname = sys_info::hostname().unwrap_or_else(|_| "localhost".into());
+        format!("pi.{}.>", hostname.to_lowercase())
+    }
+
+    // pretty-print a single NATS message, falling back to lossy utf8 when payload is not JSON
+    fn format_payload(payload: &[u8], raw_json: bool) -> String {
+        match serde_json::from_slice::<serde_json::Value>(payload) {
+            Ok(value) => match raw_json {
+                true => value.to_string(),
+                false => serde_json::to_string_pretty(&value)
+                    .unwrap_or_else(|_| String::from_utf8_lossy(payload).to_string()),
+            },
+            Err(_) => String::from_utf8_lossy(payload).to_string(),
+        }
+    }
+
+    async fn listen(args: &ArgMatches) -> Result<()> {
+        let settings = PrintNannySettings::new().await?;
+        let default_subject = Self::default_subject();
+        let subject = args
+            .value_of("subject")
+            .unwrap_or(&default_subject)
+            .to_string()
+            .to_lowercase();
+        let nats_server_uri = args.value_of("nats_server_uri").unwrap();
+        let require_tls = nats_server_uri.contains("tls");
+        let raw_json = args.is_present("json");
+
+        // prefer --nats-creds, falling back to device credentials issued by PrintNanny Cloud
+        let nats_creds = args
+            .value_of("nats_creds")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| settings.paths.cloud_nats_creds());
+        let nats_creds = match nats_creds.exists() {
+            true => Some(nats_creds),
+            false => {
+                warn!(
+                    "NATS credentials not found at {}, connecting anonymously",
+                    nats_creds.display()
+                );
+                None
+            }
+        };
+
+        // TLS material (CA, mutual TLS cert/key) comes from the [nats] settings table
+        let mut tls = NatsTlsOptions::from(&settings.nats);
+        tls.require_tls = tls.require_tls || require_tls;
+        let nats_client = try_init_nats_client_with_tls(nats_server_uri, &nats_creds, &tls).await?;
+        let mut subscriber = nats_client.subscribe(subject.clone()).await.map_err(|e| {
+            anyhow::anyhow!("Failed to subscribe to subject={} error={}", subject, e)
+        })?;
+        warn!(
+            "Listening on {} where subject={}",
+            nats_server_uri, &subject
+        );
+
+        let localizer = Localizer::new(&settings.locale);
+        while let Some(message) = subscriber.next().await {
+            debug!("Received NATS message: {:?}", message);
+            let payload = Self::format_payload(message.payload.as_ref(), raw_json);
+            match raw_json {
+                true => println!(
+                    "{}",
+                    serde_json::json!({
+                        "subject": message.subject,
+                        "reply": message.reply,
+                        "payload": payload,
+                    })
+                ),
+                false => {
+                    let mut args = FluentArgs::new();
+                    args.set("subject", message.subject.to_string());
+                    println!("{}", localizer.msg_with_args("events-subject", Some(&args)));
+                    if let Some(reply) = &message.reply {
+                        let mut args = FluentArgs::new();
+                        args.set("reply", reply.to_string());
+                        println!(
+                            "    {}",
+                            localizer.msg_with_args("events-reply", Some(&args))
+                        );
+                    }
+                    println!("{}", payload);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn handle(args: &clap::ArgMatches) -> Result<()> {
+        match args.subcommand() {
+            Some(("listen", args)) => Self::listen(args).await,
+            _ => unimplemented!(),
+        }
+    }
+}
@@ -1,4 +1,11 @@
+pub mod bench;
 pub mod cam;
 pub mod cloud_data;
+pub mod config;
+pub mod dataset;
+pub mod events;
+pub mod nats;
 pub mod os;
 pub mod settings;
+pub mod timelapse;
+pub mod user;
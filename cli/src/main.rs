@@ -2,22 +2,31 @@
 
 use anyhow::{ Result };
 use env_logger::Builder;
-use log::{ LevelFilter, error};
+use log::{ LevelFilter, error, warn};
 use clap::{ 
     Arg, Command
 };
 use git_version::git_version;
 
+use printnanny_services::fluent::FluentArgs;
 use printnanny_services::printnanny_api::ApiService;
+use printnanny_services::localization::Localizer;
 use printnanny_services::setup::printnanny_os_init;
 use printnanny_settings::{SettingsFormat};
 use printnanny_services::janus::{ JanusAdminEndpoint, janus_admin_api_call };
 use printnanny_settings::printnanny::PrintNannySettings;
 
+use printnanny_cli::bench::BenchCommand;
 use printnanny_cli::cam::CameraCommand;
+use printnanny_cli::config::ConfigCommand;
 use printnanny_cli::settings::{SettingsCommand};
 use printnanny_cli::cloud_data::CloudDataCommand;
+use printnanny_cli::dataset::DatasetCommand;
+use printnanny_cli::events::EventsCommand;
+use printnanny_cli::nats::NatsCommand;
 use printnanny_cli::os::{OsCommand};
+use printnanny_cli::timelapse::TimelapseCommand;
+use printnanny_cli::user::UserCommand;
 
 use printnanny_gst_pipelines::factory::H264_RECORDING_PIPELINE;
 
@@ -37,17 +46,80 @@ async fn main() -> Result<()> {
         .multiple_occurrences(true)
         .help("Sets the level of verbosity. Info: -v Debug: -vv Trace: -vvv"))
 
+        // bench
+        .subcommand(Command::new("bench")
+            .author(crate_authors!())
+            .about("Benchmark PrintNanny pipeline configurations")
+            .version(GIT_VERSION)
+            .subcommand_required(true)
+            .subcommand(Command::new("camera")
+                .author(crate_authors!())
+                .about(crate_description!())
+                .version(GIT_VERSION)
+                .about("Benchmark camera/encoder pipeline legs and emit a JSON report")
+                .arg(
+                    Arg::new("http-address")
+                    .takes_value(true)
+                    .long("http-address")
+                    .default_value("127.0.0.1")
+                    .help("Attach to the server through a given address"))
+                .arg(
+                        Arg::new("http-port")
+                        .takes_value(true)
+                        .long("http-port")
+                        .default_value("5001")
+                        .help("Attach to the server through a given port"))
+                .arg(
+                        Arg::new("duration")
+                        .takes_value(true)
+                        .long("duration")
+                        .default_value("10")
+                        .help("Seconds to run each pipeline configuration")
+            )))
         // cam
         .subcommand(Command::new("cam")
             .author(crate_authors!())
             .about("Interact with PrintNanny camera/device APIs")
             .version(GIT_VERSION)
             .subcommand_required(true)
+            .subcommand(Command::new("latency")
+                .author(crate_authors!())
+                .about(crate_description!())
+                .version(GIT_VERSION)
+                .about("Print glass-to-glass latency estimates for the live view"))
+            .subcommand(Command::new("proxy-config")
+                .author(crate_authors!())
+                .about(crate_description!())
+                .version(GIT_VERSION)
+                .about("Render the stream proxy config enforcing viewer auth on camera endpoints")
+                .arg(Arg::new("apply")
+                    .long("apply")
+                    .takes_value(false)
+                    .help("Write the rendered config to the settings repo and reload nginx.service")
+            ))
+            .subcommand(Command::new("share")
+                .author(crate_authors!())
+                .about(crate_description!())
+                .version(GIT_VERSION)
+                .about("Mint a short-lived signed token granting access to local stream endpoints")
+                .arg(Arg::new("ttl-seconds")
+                    .long("ttl-seconds")
+                    .takes_value(true)
+                    .default_value("3600")
+                    .help("Seconds until the token expires"))
+                .arg(Arg::new("endpoint")
+                    .long("endpoint")
+                    .takes_value(true)
+                    .multiple_occurrences(true)
+                    .possible_values(["hls", "mjpeg", "webrtc", "rtsp"])
+                    .default_values(&["hls"])
+                    .help("Stream endpoint(s) the token grants access to")
+            ))
             .subcommand(Command::new("list")
                 .author(crate_authors!())
                 .about(crate_description!())
                 .version(GIT_VERSION)
-                .about("List devices/cameras compatible with PrintNanny Vision")      
+                .about("List devices/cameras compatible with PrintNanny Vision")
                 .arg(Arg::new("format")
                 .short('f')
                 .long("format")
@@ -72,7 +144,12 @@ async fn main() -> Result<()> {
                         .takes_value(true)
                         .long("http-port")
                         .default_value("5001")
-                        .help("Attach to the server through a given port")
+                        .help("Attach to the server through a given port"))
+                .arg(
+                    Arg::new("takeover")
+                    .takes_value(false)
+                    .long("takeover")
+                    .help("Stop conflicting camera daemon units (crowsnest, ustreamer, motion) before starting pipelines")
             ))
             .subcommand(Command::new("stop-pipelines")
                 .author(crate_authors!())
@@ -110,6 +187,16 @@ async fn main() -> Result<()> {
                         .default_value("5001")
                         .help("Attach to the server through a given port")
             ))
+            .subcommand(Command::new("privacy")
+                .author(crate_authors!())
+                .about(crate_description!())
+                .version(GIT_VERSION)
+                .about("Toggle privacy mode (camera blackout slate)")
+                .arg(Arg::new("state")
+                    .required(true)
+                    .possible_values(["enable", "disable"])
+                    .help("Enable or disable privacy mode")
+            ))
             .subcommand(Command::new("start-multifilesink-listener")
                 .author(crate_authors!())
                 .about(crate_description!())
@@ -136,6 +223,18 @@ async fn main() -> Result<()> {
                 )
             )
         )
+        .subcommand(Command::new("clone")
+            .author(crate_authors!())
+            .about("Produce a seed archive for provisioning another Pi with the same settings profile")
+            .version(GIT_VERSION)
+            .arg(Arg::new("target")
+                .long("target")
+                .takes_value(true)
+                .required(true)
+                .help("Path of the seed archive to write")
+            )
+        )
+
         .subcommand(Command::new("crash-report")
             .author(crate_authors!())
             .about("Submit a crash report via PrintNanny Cloud API")
@@ -145,7 +244,136 @@ async fn main() -> Result<()> {
                 .long("id")
                 .short('i')
                 .help("Provide an id to attach system logs to a specific report")
-            ) 
+            )
+        )
+
+        // dataset export
+        .subcommand(Command::new("dataset")
+            .author(crate_authors!())
+            .about("Export sampled frames and metadata for offline labeling/training")
+            .version(GIT_VERSION)
+            .subcommand_required(true)
+            .subcommand(Command::new("export")
+                .author(crate_authors!())
+                .about(crate_description!())
+                .version(GIT_VERSION)
+                .about("Bundle sampled snapshot frames, detection scores, and recording metadata into a dataset archive")
+                .arg(Arg::new("output")
+                    .long("output")
+                    .short('o')
+                    .takes_value(true)
+                    .default_value("printnanny-dataset.zip")
+                    .help("Path of the dataset archive to write"))
+                .arg(Arg::new("sample_every")
+                    .long("sample-every")
+                    .takes_value(true)
+                    .default_value("10")
+                    .help("Sample every Nth snapshot frame"))
+                .arg(Arg::new("hours")
+                    .long("hours")
+                    .takes_value(true)
+                    .default_value("24")
+                    .help("Include detection scores logged in the last N hours")
+            )))
+
+        .subcommand(Command::new("decommission")
+            .author(crate_authors!())
+            .about("Wipe credentials, settings, database, and media before reselling or returning the device")
+            .version(GIT_VERSION)
+            .arg(Arg::new("force")
+                .long("force")
+                .takes_value(false)
+                .help("Confirm the wipe; without this flag nothing is removed")
+            )
+            .arg(Arg::new("overwrite-free-space")
+                .long("overwrite-free-space")
+                .takes_value(false)
+                .help("Also overwrite free space so deleted flash blocks cannot be recovered (slow)")
+            )
+        )
+
+        .subcommand(Command::new("doctor")
+            .author(crate_authors!())
+            .about("Capture environment info for reproducible bug reports")
+            .version(GIT_VERSION)
+            .arg(Arg::new("profile")
+                .long("profile")
+                .takes_value(false)
+                .help("Record a redacted snapshot of effective settings layers, showing which file/env var provided each value")
+            )
+            .arg(Arg::new("paths")
+                .long("paths")
+                .takes_value(false)
+                .help("Probe every directory the services write to and report read-only paths (read-only-root/overlayfs images)")
+            )
+            .arg(Arg::new("camera")
+                .long("camera")
+                .takes_value(false)
+                .help("Detect other camera daemons (crowsnest, ustreamer, motion) holding the camera device")
+            )
+        )
+
+        .subcommand(Command::new("export")
+            .author(crate_authors!())
+            .about("Export edge db tables as analysis-ready files")
+            .version(GIT_VERSION)
+            .arg(Arg::new("table")
+                .long("table")
+                .takes_value(true)
+                .required(true)
+                .possible_values(["jobs", "detections", "bandwidth"])
+                .help("Table to export: print job history, model evaluation detections, or daily bandwidth rollups"))
+            .arg(Arg::new("format")
+                .long("format")
+                .takes_value(true)
+                .default_value("csv")
+                .possible_values(["csv", "parquet"])
+                .help("Output format (parquet is not supported yet)"))
+            .arg(Arg::new("output")
+                .long("output")
+                .short('o')
+                .takes_value(true)
+                .help("Directory to write the export into, defaults to the data dir"))
+        )
+
+        // import octopi
+        .subcommand(Command::new("import")
+            .author(crate_authors!())
+            .about("Import settings from an existing printer setup")
+            .version(GIT_VERSION)
+            .subcommand_required(true)
+            .subcommand(Command::new("octopi")
+                .author(crate_authors!())
+                .about(crate_description!())
+                .version(GIT_VERSION)
+                .about("Pull settings and plugin list from an existing OctoPi/OctoPrint instance")
+                .arg(Arg::new("host")
+                    .long("host")
+                    .takes_value(true)
+                    .required(true)
+                    .help("Hostname or IP of the OctoPrint instance to import from"))
+                .arg(Arg::new("port")
+                    .long("port")
+                    .takes_value(true)
+                    .default_value("80")
+                    .help("OctoPrint HTTP port"))
+                .arg(Arg::new("api_key")
+                    .long("api-key")
+                    .takes_value(true)
+                    .required(true)
+                    .help("OctoPrint API key (Settings > API on the source instance)"))
+            )
+            .subcommand(Command::new("klipper")
+                .author(crate_authors!())
+                .about(crate_description!())
+                .version(GIT_VERSION)
+                .about("Import printer.cfg, moonraker.conf, and included cfg fragments from an existing Klipper/Mainsail setup")
+                .arg(Arg::new("source")
+                    .long("source")
+                    .takes_value(true)
+                    .required(true)
+                    .help("Mounted SD card path or scp-style user@host:path pointing at the config dir or rootfs"))
+            )
         )
 
         .subcommand(Command::new("init")
@@ -154,6 +382,80 @@ async fn main() -> Result<()> {
             .version(GIT_VERSION))
 
 
+        // events listen
+        .subcommand(Command::new("events")
+            .author(crate_authors!())
+            .about("Observe PrintNanny NATS command/event traffic")
+            .version(GIT_VERSION)
+            .subcommand_required(true)
+            .subcommand(Command::new("listen")
+                .author(crate_authors!())
+                .about(crate_description!())
+                .version(GIT_VERSION)
+                .about("Subscribe to NATS subjects and pretty-print all traffic")
+                .arg(Arg::new("subject")
+                    .long("subject")
+                    .takes_value(true)
+                    .help("Subject filter, defaults to pi.{hostname}.>"))
+                .arg(Arg::new("nats_server_uri")
+                    .long("nats-server-uri")
+                    .takes_value(true)
+                    .default_value("nats://localhost:4223")
+                    .help("NATS server uri"))
+                .arg(Arg::new("nats_creds")
+                    .long("nats-creds")
+                    .takes_value(true)
+                    .help("Path to NATS credentials, defaults to device credentials"))
+                .arg(Arg::new("json")
+                    .long("json")
+                    .takes_value(false)
+                    .help("Print one raw JSON object per message instead of pretty-printing"))
+            )
+        )
+
+        // nats publish
+        .subcommand(Command::new("nats")
+            .author(crate_authors!())
+            .about("Publish raw NATS requests from JSON/TOML files")
+            .version(GIT_VERSION)
+            .subcommand_required(true)
+            .subcommand(Command::new("publish")
+                .author(crate_authors!())
+                .about(crate_description!())
+                .version(GIT_VERSION)
+                .about("Validate a typed request file, publish it, and wait for the reply")
+                .arg(Arg::new("file")
+                    .required(true)
+                    .help("Path to a JSON/TOML file containing a NatsRequest"))
+                .arg(Arg::new("format")
+                    .short('f')
+                    .long("format")
+                    .takes_value(true)
+                    .possible_values(SettingsFormat::possible_values())
+                    .default_value("json")
+                    .help("Request file format"))
+                .arg(Arg::new("nats_server_uri")
+                    .long("nats-server-uri")
+                    .takes_value(true)
+                    .default_value("nats://localhost:4223")
+                    .help("NATS server uri"))
+                .arg(Arg::new("nats_creds")
+                    .long("nats-creds")
+                    .takes_value(true)
+                    .help("Path to NATS credentials, defaults to device credentials"))
+            )
+            .subcommand(Command::new("server-config")
+                .author(crate_authors!())
+                .about(crate_description!())
+                .version(GIT_VERSION)
+                .about("Render the embedded/leaf-node nats-server configuration from settings")
+                .arg(Arg::new("apply")
+                    .long("apply")
+                    .takes_value(false)
+                    .help("Commit the rendered config to the settings repo and restart nats-server.service"))
+            )
+        )
+
         // janus-admin
         .subcommand(Command::new("janus-admin")
             .author(crate_authors!())
@@ -270,7 +572,42 @@ async fn main() -> Result<()> {
                     .possible_values(SettingsFormat::possible_values())
                     .default_value("json")
                     .help("Output format")
-                )            
+                )
+            ))
+        // config <get|set>
+        .subcommand(Command::new("config")
+            .author(crate_authors!())
+            .about(crate_description!())
+            .version(GIT_VERSION)
+            .subcommand_required(true)
+            .about("Read/write individual PrintNanny settings keys by dotted path")
+            .subcommand(Command::new("get")
+                .author(crate_authors!())
+                .about(crate_description!())
+                .version(GIT_VERSION)
+                .about("Print a single settings value addressed by dotted path")
+                .arg(Arg::new("key")
+                    .required(true)
+                    .help("Dotted settings path, e.g. video_stream.detection.nms_threshold")
+                )
+                .arg(Arg::new("format")
+                    .short('f')
+                    .long("format")
+                    .takes_value(true)
+                    .possible_values(SettingsFormat::possible_values())
+                    .default_value("json")
+                    .help("Output format")
+                )
+            )
+            .subcommand(Command::new("set")
+                .author(crate_authors!())
+                .about(crate_description!())
+                .version(GIT_VERSION)
+                .about("Set a single settings value, validate, and commit the result")
+                .arg(Arg::new("pair")
+                    .required(true)
+                    .help("KEY=VALUE pair, e.g. video_stream.detection.nms_threshold=40")
+                )
             ))
         // os <issue|motd>
         .subcommand(Command::new("os")
@@ -302,7 +639,107 @@ async fn main() -> Result<()> {
                 Command::new("shutdown")
                 .about("Cleanup tasks that run before shutdown/restart/halt (final.target)")
             )
-        );
+        )
+        // soak
+        .subcommand(Command::new("soak")
+            .author(crate_authors!())
+            .about("Run a synthetic load/soak test and print a pass/fail stability report")
+            .version(GIT_VERSION)
+            .arg(Arg::new("hours")
+                .long("hours")
+                .takes_value(true)
+                .required(true)
+                .help("Duration of the soak run in hours, e.g. 0.5 or 24")
+            )
+        )
+        // timelapse <render>
+        .subcommand(Command::new("timelapse")
+            .author(crate_authors!())
+            .about("Assemble captured timelapse frames into a video")
+            .version(GIT_VERSION)
+            .subcommand_required(true)
+            .subcommand(Command::new("render")
+                .author(crate_authors!())
+                .version(GIT_VERSION)
+                .about("Render a frame directory into an mp4 using the configured speed ramp and filters")
+                .arg(Arg::new("frames")
+                    .long("frames")
+                    .takes_value(true)
+                    .required(true)
+                    .help("Directory of captured JPEG frames"))
+                .arg(Arg::new("output")
+                    .long("output")
+                    .short('o')
+                    .takes_value(true)
+                    .required(true)
+                    .help("Path of the mp4 to write"))
+                .arg(Arg::new("profile")
+                    .long("profile")
+                    .takes_value(true)
+                    .help("Print profile whose render overrides apply (PrintNannySettings.timelapse.profiles)"))
+            ))
+        // user <add|list|remove|passwd>
+        .subcommand(Command::new("user")
+            .author(crate_authors!())
+            .about("Manage local dashboard/API users")
+            .version(GIT_VERSION)
+            .subcommand_required(true)
+            .subcommand(Command::new("add")
+                .about("Create a local user")
+                .arg(Arg::new("username")
+                    .required(true)
+                    .takes_value(true))
+                .arg(Arg::new("role")
+                    .long("role")
+                    .takes_value(true)
+                    .possible_values(["admin", "operator", "viewer"])
+                    .default_value("admin")
+                    .help("Role granted to the user"))
+                .arg(Arg::new("password")
+                    .long("password")
+                    .takes_value(true)
+                    .help("Password (read from stdin when omitted)"))
+            )
+            .subcommand(Command::new("list")
+                .about("List local users")
+            )
+            .subcommand(Command::new("remove")
+                .about("Delete a local user and revoke their sessions")
+                .arg(Arg::new("username")
+                    .required(true)
+                    .takes_value(true))
+            )
+            .subcommand(Command::new("passwd")
+                .about("Change a local user's password")
+                .arg(Arg::new("username")
+                    .required(true)
+                    .takes_value(true))
+                .arg(Arg::new("password")
+                    .long("password")
+                    .takes_value(true)
+                    .help("Password (read from stdin when omitted)"))
+            )
+        )
+        // version
+        .subcommand(Command::new("version")
+            .author(crate_authors!())
+            .about("Show version and component compatibility info")
+            .version(GIT_VERSION)
+            .arg(Arg::new("all")
+                .long("all")
+                .takes_value(false)
+                .help("Report versions of every PrintNanny component and flag known-incompatible combinations")
+            ))
+        // restore
+        .subcommand(Command::new("restore")
+            .author(crate_authors!())
+            .about("Restore settings and edge db from a snapshot")
+            .version(GIT_VERSION)
+            .arg(Arg::new("pre-update")
+                .long("pre-update")
+                .takes_value(false)
+                .help("Restore the snapshot taken before the last OTA update")
+            ));
     
     
     let app_m = app.get_matches();
@@ -327,9 +764,18 @@ async fn main() -> Result<()> {
     };
 
     match app_m.subcommand() {
+        Some(("bench", sub_m)) => {
+            BenchCommand::handle(sub_m).await?;
+        },
         Some(("cam", sub_m)) => {
             CameraCommand::handle(sub_m).await?;
         },
+        Some(("clone", sub_m)) => {
+            let settings = PrintNannySettings::new().await?;
+            let target = std::path::PathBuf::from(sub_m.value_of("target").unwrap());
+            let manifest = printnanny_services::clone::export_seed(&settings, &target)?;
+            println!("{}", serde_json::to_string_pretty(&manifest)?);
+        },
         Some(("crash-report", sub_m)) => {
             let id = sub_m.value_of("id");
 
@@ -349,9 +795,108 @@ async fn main() -> Result<()> {
                 None => api_service.crash_report_create(None, None, None, None, None, None, settings.paths.crash_report_paths()).await
             }?;
             let report_json = serde_json::to_string_pretty(&report)?;
-            println!("Submitted crash report:");
+            println!("{}", Localizer::new(&settings.locale).msg("crash-report-submitted"));
             println!("{}", report_json);
         },
+        Some(("dataset", sub_m)) => {
+            DatasetCommand::handle(sub_m).await?;
+        },
+        Some(("decommission", sub_m)) => {
+            if !sub_m.is_present("force") {
+                error!(
+                    "{}",
+                    Localizer::from_settings()
+                        .await
+                        .msg("decommission-requires-force")
+                );
+                std::process::exit(1);
+            }
+            let settings = PrintNannySettings::new().await?;
+            let overwrite = sub_m.is_present("overwrite-free-space");
+            let report = tokio::task::spawn_blocking(move || {
+                printnanny_services::decommission::decommission(&settings, overwrite)
+            })
+            .await??;
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        },
+        Some(("doctor", sub_m)) => {
+            if sub_m.is_present("profile") {
+                let profile = printnanny_settings::provenance::settings_provenance().await?;
+                println!("{}", serde_json::to_string_pretty(&profile)?);
+            } else if sub_m.is_present("paths") {
+                let settings = PrintNannySettings::new().await?;
+                let checks = printnanny_services::doctor::audit_writable_paths(&settings);
+                println!("{}", serde_json::to_string_pretty(&checks)?);
+            } else if sub_m.is_present("camera") {
+                let report = printnanny_services::camera_conflict::detect_camera_conflicts().await?;
+                let localizer = Localizer::from_settings().await;
+                for unit in &report.conflicting_units {
+                    let mut args = FluentArgs::new();
+                    args.set("unit", unit.to_string());
+                    warn!(
+                        "{}",
+                        localizer.msg_with_args("doctor-camera-conflict", Some(&args))
+                    );
+                }
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                error!(
+                    "{}",
+                    Localizer::from_settings().await.msg("doctor-requires-flag")
+                );
+            }
+        },
+        Some(("export", sub_m)) => {
+            let table: printnanny_services::export::ExportTable =
+                sub_m.value_of("table").unwrap().parse()?;
+            let format: printnanny_services::export::ExportFormat =
+                sub_m.value_of("format").unwrap().parse()?;
+            let output_dir = sub_m.value_of("output").map(std::path::PathBuf::from);
+            let settings = PrintNannySettings::new().await?;
+            let output =
+                printnanny_services::export::export(&settings, table, format, output_dir)?;
+            println!("{}", output.display());
+        },
+        Some(("import", sub_m)) => {
+            if let Some(("klipper", klipper_m)) = sub_m.subcommand() {
+                let source = klipper_m.value_of("source").unwrap();
+                let report = printnanny_services::klipper_import::import_klipper(source).await?;
+                let localizer = Localizer::from_settings().await;
+                for skipped in &report.skipped {
+                    let mut args = FluentArgs::new();
+                    args.set("section", skipped.section.to_string());
+                    args.set("reason", skipped.reason.to_string());
+                    warn!("{}", localizer.msg_with_args("import-skipped", Some(&args)));
+                }
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else if let Some(("octopi", octopi_m)) = sub_m.subcommand() {
+                let host = octopi_m.value_of("host").unwrap();
+                let port: u16 = octopi_m.value_of_t("port").unwrap_or_else(|e| e.exit());
+                let api_key = octopi_m.value_of("api_key").unwrap();
+                let report =
+                    printnanny_services::octopi_import::import_octopi(host, port, api_key).await?;
+                let localizer = Localizer::from_settings().await;
+                for skipped in &report.skipped {
+                    let mut args = FluentArgs::new();
+                    args.set("section", skipped.section.to_string());
+                    args.set("reason", skipped.reason.to_string());
+                    warn!("{}", localizer.msg_with_args("import-skipped", Some(&args)));
+                }
+                for plugin in &report.plugins_to_install {
+                    let mut args = FluentArgs::new();
+                    args.set(
+                        "plugin",
+                        plugin.name.as_deref().unwrap_or(&plugin.key).to_string(),
+                    );
+                    args.set("host", report.host.to_string());
+                    warn!(
+                        "{}",
+                        localizer.msg_with_args("import-plugin-not-bundled", Some(&args))
+                    );
+                }
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            }
+        },
         Some(("init", _sub_m)) => {
             printnanny_os_init().await?;
         }
@@ -359,13 +904,96 @@ async fn main() -> Result<()> {
         Some(("settings", subm)) => {
             SettingsCommand::handle(subm).await?;
         },
+        Some(("config", subm)) => {
+            ConfigCommand::handle(subm).await?;
+        },
         Some(("cloud", subm)) => {
             CloudDataCommand::handle(subm).await?;
         },
+        Some(("events", subm)) => {
+            EventsCommand::handle(subm).await?;
+        },
+        Some(("nats", subm)) => {
+            NatsCommand::handle(subm).await?;
+        },
 
         Some(("os", subm)) => {
             OsCommand::handle(subm).await?;
         },
+        Some(("restore", sub_m)) => {
+            match sub_m.is_present("pre-update") {
+                true => {
+                    let snapshot = printnanny_services::pre_update::restore().await?;
+                    let snapshot_json = serde_json::to_string_pretty(&snapshot)?;
+                    println!(
+                        "{}",
+                        Localizer::from_settings()
+                            .await
+                            .msg("restore-pre-update-done")
+                    );
+                    println!("{}", snapshot_json);
+                },
+                false => {
+                    error!(
+                        "{}",
+                        Localizer::from_settings()
+                            .await
+                            .msg("restore-requires-flag")
+                    );
+                }
+            }
+        },
+        Some(("soak", sub_m)) => {
+            let hours: f64 = sub_m.value_of_t("hours").unwrap_or_else(|e| e.exit());
+            let settings = PrintNannySettings::new().await?;
+            let report = printnanny_services::soak::soak(&settings, hours).await?;
+            let localizer = Localizer::new(&settings.locale);
+            for check in report.checks.iter().filter(|check| !check.passed) {
+                let mut args = FluentArgs::new();
+                args.set("name", check.name.to_string());
+                args.set("detail", check.detail.to_string());
+                error!(
+                    "{}",
+                    localizer.msg_with_args("soak-check-failed", Some(&args))
+                );
+            }
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            if !report.passed {
+                std::process::exit(1);
+            }
+        },
+        Some(("timelapse", subm)) => {
+            TimelapseCommand::handle(subm).await?;
+        },
+        Some(("user", subm)) => {
+            UserCommand::handle(subm).await?;
+        },
+        Some(("version", sub_m)) => {
+            if sub_m.is_present("all") {
+                let report = printnanny_services::version::version_report(
+                    env!("CARGO_PKG_VERSION"),
+                    GIT_VERSION,
+                    printnanny_nats_apps::VERSION,
+                )
+                .await;
+                let localizer = Localizer::from_settings().await;
+                for i in &report.incompatibilities {
+                    let mut args = FluentArgs::new();
+                    args.set("component", i.component.to_string());
+                    args.set("version", i.version.to_string());
+                    args.set("other", i.other.to_string());
+                    args.set("other_version", i.other_version.to_string());
+                    args.set("reason", i.reason.to_string());
+                    warn!(
+                        "{}",
+                        localizer.msg_with_args("version-incompatible", Some(&args))
+                    );
+                }
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                println!("{} {}", env!("CARGO_PKG_VERSION"), GIT_VERSION);
+            }
+        },
         Some(("janus-admin", sub_m)) => {
             let endpoint: JanusAdminEndpoint = sub_m.value_of_t("endpoint").unwrap_or_else(|e| e.exit());
             let res = janus_admin_api_call(
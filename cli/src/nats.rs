@@ -0,0 +1,117 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+use clap::ArgMatches;
+use log::{info, warn};
+use tokio::fs;
+
+use printnanny_nats_apps::request_reply::NatsRequest;
+use printnanny_nats_client::client::{try_init_nats_client_with_tls, NatsTlsOptions};
+use printnanny_settings::nats_server::render_nats_server_conf;
+use printnanny_settings::printnanny::PrintNannySettings;
+use printnanny_settings::sys_info;
+use printnanny_settings::vcs::VersionControlledSettings;
+use printnanny_settings::SettingsFormat;
+
+pub struct NatsCommand;
+
+impl NatsCommand {
+    // validate file contents against the NatsRequest schema before publishing
+    fn parse_request(content: &str, format: SettingsFormat) -> Result<NatsRequest> {
+        let request = match format {
+            SettingsFormat::Json => serde_json::from_str::<NatsRequest>(content)?,
+            SettingsFormat::Toml => toml::de::from_str::<NatsRequest>(content)?,
+            _ => unimplemented!("nats publish is not implemented for format: {}", format),
+        };
+        Ok(request)
+    }
+
+    // extract the serde subject_pattern tag and substitute {pi_id} with device hostname
+    fn to_subject(request: &NatsRequest) -> Result<String> {
+        let value = serde_json::to_value(request)?;
+        let subject_pattern = value
+            .get("subject_pattern")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Failed to read subject_pattern from NatsRequest"))?;
+        let hostname = sys_info::hostname().unwrap_or_else(|_| "localhost".into());
+        Ok(subject_pattern.replacen("{pi_id}", &hostname.to_lowercase(), 1))
+    }
+
+    async fn publish(args: &ArgMatches) -> Result<()> {
+        let settings = PrintNannySettings::new().await?;
+        let file = PathBuf::from(args.value_of("file").unwrap());
+        let format: SettingsFormat = args.value_of_t("format").unwrap();
+        let nats_server_uri = args.value_of("nats_server_uri").unwrap();
+        let require_tls = nats_server_uri.contains("tls");
+
+        let content = fs::read_to_string(&file).await?;
+        let request = Self::parse_request(&content, format)?;
+        let subject = Self::to_subject(&request)?;
+        info!("Validated NatsRequest: {:?}", request);
+
+        let nats_creds = args
+            .value_of("nats_creds")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| settings.paths.cloud_nats_creds());
+        let nats_creds = match nats_creds.exists() {
+            true => Some(nats_creds),
+            false => {
+                warn!(
+                    "NATS credentials not found at {}, connecting anonymously",
+                    nats_creds.display()
+                );
+                None
+            }
+        };
+
+        // TLS material (CA, mutual TLS cert/key) comes from the [nats] settings table
+        let mut tls = NatsTlsOptions::from(&settings.nats);
+        tls.require_tls = tls.require_tls || require_tls;
+        let nats_client = try_init_nats_client_with_tls(nats_server_uri, &nats_creds, &tls).await?;
+        let payload = serde_json::to_vec(&request)?;
+        info!("Publishing request to subject={}", &subject);
+        let reply = nats_client
+            .request(subject.clone(), payload.into())
+            .await
+            .map_err(|e| anyhow!("Error requesting subject={} error={}", subject, e))?;
+
+        let reply = match serde_json::from_slice::<serde_json::Value>(reply.payload.as_ref()) {
+            Ok(value) => serde_json::to_string_pretty(&value)?,
+            Err(_) => String::from_utf8_lossy(reply.payload.as_ref()).to_string(),
+        };
+        println!("{}", reply);
+        Ok(())
+    }
+
+    // render the embedded/leaf-node nats-server config; --apply commits it to
+    // the settings repo and restarts nats-server.service via the post_save hook
+    async fn server_config(args: &ArgMatches) -> Result<()> {
+        let settings = PrintNannySettings::new().await?;
+        let conf = render_nats_server_conf(&settings);
+        match args.is_present("apply") {
+            true => {
+                let nats_server_settings = settings.to_nats_server_settings();
+                nats_server_settings
+                    .save_and_commit(
+                        &conf,
+                        Some("Rendered nats-server config from PrintNannySettings".to_string()),
+                    )
+                    .await?;
+                info!(
+                    "Committed {} and restarted nats-server.service",
+                    nats_server_settings.settings_file.display()
+                );
+            }
+            false => println!("{}", conf),
+        }
+        Ok(())
+    }
+
+    pub async fn handle(args: &clap::ArgMatches) -> Result<()> {
+        match args.subcommand() {
+            Some(("publish", args)) => Self::publish(args).await,
+            Some(("server-config", args)) => Self::server_config(args).await,
+            _ => unimplemented!(),
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::ArgMatches;
+
+use printnanny_services::timelapse::render_timelapse;
+use printnanny_settings::printnanny::PrintNannySettings;
+
+pub struct TimelapseCommand;
+
+impl TimelapseCommand {
+    async fn render(args: &ArgMatches) -> Result<()> {
+        let settings = PrintNannySettings::new().await?;
+        let frames = PathBuf::from(args.value_of("frames").unwrap());
+        let output = PathBuf::from(args.value_of("output").unwrap());
+        let profile = args.value_of("profile");
+
+        let rendered = render_timelapse(&settings, &frames, &output, profile).await?;
+        println!("{}", rendered.display());
+        Ok(())
+    }
+
+    pub async fn handle(args: &ArgMatches) -> Result<()> {
+        match args.subcommand() {
+            Some(("render", args)) => Self::render(args).await,
+            _ => unimplemented!(),
+        }
+    }
+}
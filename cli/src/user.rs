@@ -0,0 +1,86 @@
+use std::io::BufRead;
+
+use anyhow::Result;
+
+use printnanny_edge_db::local_auth::{LocalUser, LocalUserRole};
+use printnanny_services::auth;
+use printnanny_services::fluent::FluentArgs;
+use printnanny_services::localization::Localizer;
+use printnanny_settings::printnanny::PrintNannySettings;
+
+pub struct UserCommand;
+
+impl UserCommand {
+    // --password flag, falling back to reading one line from stdin so the
+    // password stays out of shell history
+    fn read_password(args: &clap::ArgMatches, localizer: &Localizer) -> Result<String> {
+        match args.value_of("password") {
+            Some(password) => Ok(password.to_string()),
+            None => {
+                eprintln!("{} ", localizer.msg("user-password-prompt"));
+                let mut password = String::new();
+                std::io::stdin().lock().read_line(&mut password)?;
+                Ok(password.trim_end_matches(['\r', '\n']).to_string())
+            }
+        }
+    }
+
+    async fn add(args: &clap::ArgMatches) -> Result<()> {
+        let settings = PrintNannySettings::new().await?;
+        let sqlite_connection = settings.paths.db().display().to_string();
+        let username = args.value_of("username").unwrap();
+        let role: LocalUserRole = args.value_of_t("role")?;
+        let password = Self::read_password(args, &Localizer::new(&settings.locale))?;
+        let user = auth::create_user(&sqlite_connection, username, &password, role)?;
+        println!("{}", serde_json::to_string_pretty(&user)?);
+        Ok(())
+    }
+
+    async fn list() -> Result<()> {
+        let settings = PrintNannySettings::new().await?;
+        let sqlite_connection = settings.paths.db().display().to_string();
+        let users = LocalUser::list(&sqlite_connection)?;
+        println!("{}", serde_json::to_string_pretty(&users)?);
+        Ok(())
+    }
+
+    async fn remove(args: &clap::ArgMatches) -> Result<()> {
+        let settings = PrintNannySettings::new().await?;
+        let sqlite_connection = settings.paths.db().display().to_string();
+        let username = args.value_of("username").unwrap();
+        let deleted = LocalUser::delete(&sqlite_connection, username)?;
+        let mut fluent_args = FluentArgs::new();
+        fluent_args.set("count", deleted.to_string());
+        println!(
+            "{}",
+            Localizer::new(&settings.locale).msg_with_args("user-deleted", Some(&fluent_args))
+        );
+        Ok(())
+    }
+
+    async fn passwd(args: &clap::ArgMatches) -> Result<()> {
+        let settings = PrintNannySettings::new().await?;
+        let sqlite_connection = settings.paths.db().display().to_string();
+        let username = args.value_of("username").unwrap();
+        let localizer = Localizer::new(&settings.locale);
+        let password = Self::read_password(args, &localizer)?;
+        auth::set_password(&sqlite_connection, username, &password)?;
+        let mut fluent_args = FluentArgs::new();
+        fluent_args.set("username", username.to_string());
+        println!(
+            "{}",
+            localizer.msg_with_args("user-password-updated", Some(&fluent_args))
+        );
+        Ok(())
+    }
+
+    pub async fn handle(args: &clap::ArgMatches) -> Result<()> {
+        match args.subcommand() {
+            Some(("add", args)) => Self::add(args).await,
+            Some(("list", _args)) => Self::list().await,
+            Some(("remove", args)) => Self::remove(args).await,
+            Some(("passwd", args)) => Self::passwd(args).await,
+            _ => unimplemented!(),
+        }
+    }
+}
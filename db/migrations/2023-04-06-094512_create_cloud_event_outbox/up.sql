@@ -0,0 +1,7 @@
+CREATE TABLE cloud_event_outbox (
+  id VARCHAR PRIMARY KEY NOT NULL,
+  created_dt DATETIME NOT NULL,
+  subject VARCHAR NOT NULL,
+  payload TEXT NOT NULL,
+  sent_dt DATETIME
+)
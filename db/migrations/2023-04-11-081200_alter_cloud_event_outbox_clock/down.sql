@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE cloud_event_outbox DROP COLUMN seq;
+ALTER TABLE cloud_event_outbox DROP COLUMN clock_suspect;
@@ -0,0 +1,2 @@
+ALTER TABLE cloud_event_outbox ADD COLUMN seq BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE cloud_event_outbox ADD COLUMN clock_suspect BOOLEAN NOT NULL DEFAULT FALSE;
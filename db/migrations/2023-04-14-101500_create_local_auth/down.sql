@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE local_sessions;
+DROP TABLE local_users;
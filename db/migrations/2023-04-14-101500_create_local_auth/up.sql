@@ -0,0 +1,14 @@
+CREATE TABLE local_users (
+  id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+  created_dt DATETIME NOT NULL,
+  username VARCHAR NOT NULL UNIQUE,
+  password_hash VARCHAR NOT NULL,
+  role VARCHAR NOT NULL
+);
+
+CREATE TABLE local_sessions (
+  id VARCHAR PRIMARY KEY NOT NULL,
+  user_id INTEGER NOT NULL REFERENCES local_users (id) ON DELETE CASCADE,
+  created_dt DATETIME NOT NULL,
+  expires_dt DATETIME NOT NULL
+);
@@ -0,0 +1,8 @@
+CREATE TABLE bandwidth_usage (
+  id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+  day VARCHAR NOT NULL,
+  subsystem VARCHAR NOT NULL,
+  bytes_sent BIGINT NOT NULL DEFAULT 0,
+  bytes_received BIGINT NOT NULL DEFAULT 0,
+  UNIQUE(day, subsystem)
+);
@@ -0,0 +1 @@
+DROP TABLE detection_feedback;
@@ -0,0 +1,9 @@
+CREATE TABLE detection_feedback (
+  id VARCHAR PRIMARY KEY NOT NULL,
+  created_dt DATETIME NOT NULL,
+  detection_ts BIGINT NOT NULL,
+  label VARCHAR NOT NULL,
+  note TEXT,
+  frame_path VARCHAR,
+  uploaded_dt DATETIME
+);
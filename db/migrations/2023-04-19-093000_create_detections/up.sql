@@ -0,0 +1,12 @@
+CREATE TABLE detections (
+  id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+  created_dt DATETIME NOT NULL,
+  model_id VARCHAR NOT NULL,
+  rt BIGINT,
+  nozzle_count INTEGER,
+  nozzle_mean DOUBLE,
+  print_count INTEGER,
+  print_mean DOUBLE,
+  raft_count INTEGER,
+  raft_mean DOUBLE
+);
@@ -0,0 +1,8 @@
+CREATE TABLE scheduled_commands (
+  id VARCHAR PRIMARY KEY NOT NULL,
+  created_dt DATETIME NOT NULL,
+  execute_at_dt DATETIME NOT NULL,
+  command TEXT NOT NULL,
+  executed_dt DATETIME,
+  result TEXT
+);
@@ -0,0 +1,11 @@
+CREATE TABLE jobs (
+  id VARCHAR PRIMARY KEY NOT NULL,
+  created_dt DATETIME NOT NULL,
+  updated_dt DATETIME NOT NULL,
+  job_type TEXT NOT NULL,
+  status TEXT NOT NULL,
+  progress_percent INTEGER NOT NULL,
+  detail TEXT,
+  finished_dt DATETIME,
+  result TEXT
+);
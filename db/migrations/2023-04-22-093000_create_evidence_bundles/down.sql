@@ -0,0 +1 @@
+DROP TABLE evidence_bundles;
@@ -0,0 +1,11 @@
+CREATE TABLE evidence_bundles (
+  id VARCHAR PRIMARY KEY NOT NULL,
+  created_dt DATETIME NOT NULL,
+  dir TEXT NOT NULL,
+  event_rt BIGINT,
+  spaghetti_mean DOUBLE,
+  adhesion_mean DOUBLE,
+  video_recording_id VARCHAR,
+  snapshot_files TEXT NOT NULL,
+  clip_files TEXT NOT NULL
+);
@@ -0,0 +1,11 @@
+CREATE TABLE heartbeat_samples (
+  id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+  sample_dt DATETIME NOT NULL,
+  resolution VARCHAR NOT NULL,
+  cpu_usage_usec BIGINT,
+  cpu_percent DOUBLE,
+  rss_bytes BIGINT NOT NULL,
+  swap_bytes BIGINT NOT NULL,
+  enclosure_temperature DOUBLE,
+  hls_latency_ms BIGINT
+);
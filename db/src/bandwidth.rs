@@ -0,0 +1,124 @@
+// Per-subsystem bandwidth accounting with daily rollups. Subsystems report
+// byte deltas as traffic happens; rows are keyed on (day, subsystem) so the
+// table stays small enough to query on every pi.{pi_id}.stats.bandwidth
+// request, and users on LTE hotspots can see what is eating their data.
+use chrono::Utc;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::connection::establish_sqlite_connection;
+use crate::schema::bandwidth_usage;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BandwidthSubsystem {
+    // HLS/WebRTC/RTSP legs streamed off-device
+    LiveStream,
+    // video recording uploads and settings sync
+    CloudSync,
+    // NATS events, heartbeats and crash reports
+    Telemetry,
+    // OS/software update downloads
+    Ota,
+}
+
+impl BandwidthSubsystem {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BandwidthSubsystem::LiveStream => "live_stream",
+            BandwidthSubsystem::CloudSync => "cloud_sync",
+            BandwidthSubsystem::Telemetry => "telemetry",
+            BandwidthSubsystem::Ota => "ota",
+        }
+    }
+}
+
+impl std::str::FromStr for BandwidthSubsystem {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "live_stream" => Ok(BandwidthSubsystem::LiveStream),
+            "cloud_sync" => Ok(BandwidthSubsystem::CloudSync),
+            "telemetry" => Ok(BandwidthSubsystem::Telemetry),
+            "ota" => Ok(BandwidthSubsystem::Ota),
+            other => Err(format!("Invalid bandwidth subsystem: {}", other)),
+        }
+    }
+}
+
+#[derive(Queryable, Identifiable, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[diesel(table_name = bandwidth_usage)]
+pub struct BandwidthUsage {
+    pub id: i32,
+    // rollup day, UTC "YYYY-MM-DD"; lexicographic order is date order
+    pub day: String,
+    pub subsystem: String,
+    pub bytes_sent: i64,
+    pub bytes_received: i64,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = bandwidth_usage)]
+pub struct NewBandwidthUsage<'a> {
+    pub day: &'a str,
+    pub subsystem: &'a str,
+    pub bytes_sent: &'a i64,
+    pub bytes_received: &'a i64,
+}
+
+fn today() -> String {
+    Utc::now().format("%Y-%m-%d").to_string()
+}
+
+impl BandwidthUsage {
+    // add byte deltas to today's rollup row, creating it on first use
+    pub fn record(
+        connection_str: &str,
+        usage_subsystem: BandwidthSubsystem,
+        sent: i64,
+        received: i64,
+    ) -> Result<(), diesel::result::Error> {
+        use crate::schema::bandwidth_usage::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        let current_day = today();
+        let updated = diesel::update(
+            bandwidth_usage
+                .filter(day.eq(&current_day))
+                .filter(subsystem.eq(usage_subsystem.as_str())),
+        )
+        .set((
+            bytes_sent.eq(bytes_sent + sent),
+            bytes_received.eq(bytes_received + received),
+        ))
+        .execute(connection)?;
+        if updated == 0 {
+            let row = NewBandwidthUsage {
+                day: &current_day,
+                subsystem: usage_subsystem.as_str(),
+                bytes_sent: &sent,
+                bytes_received: &received,
+            };
+            diesel::insert_into(bandwidth_usage)
+                .values(&row)
+                .execute(connection)?;
+        }
+        Ok(())
+    }
+
+    // daily rollups for the last `days` days (inclusive of today), oldest first
+    pub fn list_recent(
+        connection_str: &str,
+        days: i64,
+    ) -> Result<Vec<BandwidthUsage>, diesel::result::Error> {
+        use crate::schema::bandwidth_usage::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        let start_day = (Utc::now() - chrono::Duration::days(days.saturating_sub(1).max(0)))
+            .format("%Y-%m-%d")
+            .to_string();
+        bandwidth_usage
+            .filter(day.ge(start_day))
+            .order((day.asc(), subsystem.asc()))
+            .load::<BandwidthUsage>(connection)
+    }
+}
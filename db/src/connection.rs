@@ -1,13 +1,57 @@
+use std::collections::HashMap;
 use std::error::Error;
+use std::sync::Mutex;
 
-use diesel::prelude::*;
+use diesel::connection::SimpleConnection;
+use diesel::migration::MigrationSource;
+use diesel::r2d2::{ConnectionManager, CustomizeConnection, Pool, PooledConnection};
 use diesel::sqlite::SqliteConnection;
 use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
 
 pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!();
 
-pub fn establish_sqlite_connection(database_path: &str) -> SqliteConnection {
-    SqliteConnection::establish(database_path).expect("Failed to initialize sqlite db connection")
+pub type DbPool = Pool<ConnectionManager<SqliteConnection>>;
+pub type DbPooledConnection = PooledConnection<ConnectionManager<SqliteConnection>>;
+
+// one pool per database path, shared by every handler in the process
+static POOLS: Mutex<Option<HashMap<String, DbPool>>> = Mutex::new(None);
+
+// sqlite tuning applied to every pooled connection:
+// WAL allows concurrent readers while a writer is active, and busy_timeout
+// retries instead of immediately returning SQLITE_BUSY
+#[derive(Debug, Clone, Copy)]
+struct SqliteConnectionCustomizer;
+
+impl CustomizeConnection<SqliteConnection, diesel::r2d2::Error> for SqliteConnectionCustomizer {
+    fn on_acquire(&self, connection: &mut SqliteConnection) -> Result<(), diesel::r2d2::Error> {
+        connection
+            .batch_execute(
+                "PRAGMA busy_timeout = 5000; PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL; PRAGMA foreign_keys = ON;",
+            )
+            .map_err(diesel::r2d2::Error::QueryError)
+    }
+}
+
+pub fn sqlite_connection_pool(database_path: &str) -> DbPool {
+    let mut guard = POOLS.lock().unwrap();
+    let pools = guard.get_or_insert_with(HashMap::new);
+    if let Some(pool) = pools.get(database_path) {
+        return pool.clone();
+    }
+    let manager = ConnectionManager::<SqliteConnection>::new(database_path);
+    let pool = Pool::builder()
+        .max_size(8)
+        .connection_customizer(Box::new(SqliteConnectionCustomizer))
+        .build(manager)
+        .expect("Failed to initialize sqlite db connection pool");
+    pools.insert(database_path.to_string(), pool.clone());
+    pool
+}
+
+pub fn establish_sqlite_connection(database_path: &str) -> DbPooledConnection {
+    sqlite_connection_pool(database_path)
+        .get()
+        .expect("Failed to initialize sqlite db connection")
 }
 
 pub fn run_migrations(database_path: &str) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
@@ -15,3 +59,21 @@ pub fn run_migrations(database_path: &str) -> Result<(), Box<dyn Error + Send +
     connection.run_pending_migrations(MIGRATIONS)?;
     Ok(())
 }
+
+// newest embedded migration, reported as the edge db schema version by
+// `printnanny version --all`
+pub fn embedded_schema_version() -> Option<String> {
+    let migrations = MigrationSource::<diesel::sqlite::Sqlite>::migrations(&MIGRATIONS).ok()?;
+    migrations
+        .iter()
+        .map(|m| m.name().version().to_string())
+        .max()
+}
+
+pub fn applied_migration_versions(
+    database_path: &str,
+) -> Result<Vec<String>, Box<dyn Error + Send + Sync + 'static>> {
+    let connection = &mut establish_sqlite_connection(database_path);
+    let versions = connection.applied_migrations()?;
+    Ok(versions.into_iter().map(|v| v.to_string()).collect())
+}
@@ -0,0 +1,133 @@
+// User-labeled detection events (false positive/negative) pending upload to
+// the cloud training pipeline. The captured frame stays on disk next to the
+// row until the upload task enqueues it; rows survive restarts so feedback
+// given offline is not lost.
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::connection::establish_sqlite_connection;
+use crate::schema::detection_feedback;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DetectionFeedbackLabel {
+    // detection fired but nothing was wrong
+    FalsePositive,
+    // a failure happened without a detection
+    FalseNegative,
+    // detection was correct; useful as a positive training sample
+    Confirmed,
+}
+
+impl DetectionFeedbackLabel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DetectionFeedbackLabel::FalsePositive => "false_positive",
+            DetectionFeedbackLabel::FalseNegative => "false_negative",
+            DetectionFeedbackLabel::Confirmed => "confirmed",
+        }
+    }
+}
+
+impl std::str::FromStr for DetectionFeedbackLabel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "false_positive" => Ok(DetectionFeedbackLabel::FalsePositive),
+            "false_negative" => Ok(DetectionFeedbackLabel::FalseNegative),
+            "confirmed" => Ok(DetectionFeedbackLabel::Confirmed),
+            other => Err(format!("Invalid detection feedback label: {}", other)),
+        }
+    }
+}
+
+#[derive(Queryable, Identifiable, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[diesel(table_name = detection_feedback)]
+pub struct DetectionFeedback {
+    pub id: String,
+    pub created_dt: DateTime<Utc>,
+    // nanosecond pipeline timestamp of the detection event being labeled
+    pub detection_ts: i64,
+    pub label: String,
+    pub note: Option<String>,
+    // captured frame under paths.data()/detection-feedback/
+    pub frame_path: Option<String>,
+    pub uploaded_dt: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = detection_feedback)]
+pub struct NewDetectionFeedback<'a> {
+    pub id: &'a str,
+    pub created_dt: &'a DateTime<Utc>,
+    pub detection_ts: &'a i64,
+    pub label: &'a str,
+    pub note: Option<&'a str>,
+    pub frame_path: Option<&'a str>,
+}
+
+impl DetectionFeedback {
+    pub fn create(
+        connection_str: &str,
+        feedback_ts: i64,
+        feedback_label: DetectionFeedbackLabel,
+        feedback_note: Option<&str>,
+        feedback_frame_path: Option<&str>,
+    ) -> Result<DetectionFeedback, diesel::result::Error> {
+        let connection = &mut establish_sqlite_connection(connection_str);
+        let row_id = uuid::Uuid::new_v4().to_string();
+        let now = Utc::now();
+        let row = NewDetectionFeedback {
+            id: &row_id,
+            created_dt: &now,
+            detection_ts: &feedback_ts,
+            label: feedback_label.as_str(),
+            note: feedback_note,
+            frame_path: feedback_frame_path,
+        };
+        diesel::insert_into(detection_feedback::table)
+            .values(&row)
+            .execute(connection)?;
+        info!(
+            "Created DetectionFeedback id={} label={}",
+            row_id,
+            feedback_label.as_str()
+        );
+        Self::get_by_id(connection_str, &row_id)
+    }
+
+    pub fn get_by_id(
+        connection_str: &str,
+        row_id: &str,
+    ) -> Result<DetectionFeedback, diesel::result::Error> {
+        use crate::schema::detection_feedback::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        detection_feedback
+            .filter(id.eq(row_id))
+            .first::<DetectionFeedback>(connection)
+    }
+
+    // rows waiting for upload, oldest first
+    pub fn get_pending(
+        connection_str: &str,
+    ) -> Result<Vec<DetectionFeedback>, diesel::result::Error> {
+        use crate::schema::detection_feedback::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        detection_feedback
+            .filter(uploaded_dt.is_null())
+            .order(created_dt.asc())
+            .load::<DetectionFeedback>(connection)
+    }
+
+    pub fn mark_uploaded(connection_str: &str, row_id: &str) -> Result<(), diesel::result::Error> {
+        use crate::schema::detection_feedback::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        diesel::update(detection_feedback.filter(id.eq(row_id)))
+            .set(uploaded_dt.eq(Some(Utc::now())))
+            .execute(connection)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+// Windowed detection outputs logged during model A/B evaluation, tagged by
+// model id so the primary and candidate tflite models can be compared over
+// the same footage. Rows are only written while evaluation mode is enabled,
+// so the table stays empty during normal operation.
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::connection::establish_sqlite_connection;
+use crate::schema::detections;
+
+#[derive(Queryable, Identifiable, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[diesel(table_name = detections)]
+pub struct Detection {
+    pub id: i32,
+    pub created_dt: DateTime<Utc>,
+    pub model_id: String,
+    // pipeline running time of the last windowed frame (nanoseconds)
+    pub rt: Option<i64>,
+    pub nozzle_count: Option<i32>,
+    pub nozzle_mean: Option<f64>,
+    pub print_count: Option<i32>,
+    pub print_mean: Option<f64>,
+    pub raft_count: Option<i32>,
+    pub raft_mean: Option<f64>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = detections)]
+pub struct NewDetection<'a> {
+    pub created_dt: DateTime<Utc>,
+    pub model_id: &'a str,
+    pub rt: Option<i64>,
+    pub nozzle_count: Option<i32>,
+    pub nozzle_mean: Option<f64>,
+    pub print_count: Option<i32>,
+    pub print_mean: Option<f64>,
+    pub raft_count: Option<i32>,
+    pub raft_mean: Option<f64>,
+}
+
+impl Detection {
+    pub fn create_many(
+        connection_str: &str,
+        rows: &[NewDetection],
+    ) -> Result<usize, diesel::result::Error> {
+        use crate::schema::detections::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        diesel::insert_into(detections)
+            .values(rows)
+            .execute(connection)
+    }
+
+    // all rows logged since `since`, oldest first
+    pub fn list_since(
+        connection_str: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<Detection>, diesel::result::Error> {
+        use crate::schema::detections::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        detections
+            .filter(created_dt.ge(since))
+            .order(created_dt.asc())
+            .load::<Detection>(connection)
+    }
+
+    // evaluation runs can accumulate rows quickly; callers clear the table
+    // when starting a fresh comparison window
+    pub fn delete_all(connection_str: &str) -> Result<usize, diesel::result::Error> {
+        use crate::schema::detections::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        diesel::delete(detections).execute(connection)
+    }
+}
@@ -0,0 +1,92 @@
+// Evidence bundles captured when a failure event fires: a short pre/post-roll
+// clip (recording fragments around the event) plus a handful of snapshots,
+// stored together so an alert can be judged without scrubbing the full
+// recording. The file lists are stored as JSON arrays of absolute paths.
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::connection::establish_sqlite_connection;
+use crate::schema::evidence_bundles;
+
+#[derive(Queryable, Identifiable, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[diesel(table_name = evidence_bundles)]
+pub struct EvidenceBundle {
+    pub id: String,
+    pub created_dt: DateTime<Utc>,
+    pub dir: String,
+    // pipeline running time of the detection event (nanoseconds)
+    pub event_rt: Option<i64>,
+    pub spaghetti_mean: Option<f64>,
+    pub adhesion_mean: Option<f64>,
+    // recording the clip fragments were copied from, when one was active
+    pub video_recording_id: Option<String>,
+    // JSON arrays of absolute paths
+    pub snapshot_files: String,
+    pub clip_files: String,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = evidence_bundles)]
+pub struct NewEvidenceBundle<'a> {
+    pub id: &'a str,
+    pub created_dt: &'a DateTime<Utc>,
+    pub dir: &'a str,
+    pub event_rt: Option<i64>,
+    pub spaghetti_mean: Option<f64>,
+    pub adhesion_mean: Option<f64>,
+    pub video_recording_id: Option<&'a str>,
+    pub snapshot_files: &'a str,
+    pub clip_files: &'a str,
+}
+
+impl EvidenceBundle {
+    pub fn insert(
+        connection_str: &str,
+        row: NewEvidenceBundle,
+    ) -> Result<EvidenceBundle, diesel::result::Error> {
+        use crate::schema::evidence_bundles::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        diesel::insert_into(evidence_bundles)
+            .values(&row)
+            .execute(connection)?;
+        let result = evidence_bundles
+            .filter(id.eq(row.id))
+            .first::<EvidenceBundle>(connection)?;
+        info!("Created EvidenceBundle id={} dir={}", result.id, result.dir);
+        Ok(result)
+    }
+
+    pub fn get_by_id(
+        connection_str: &str,
+        row_id: &str,
+    ) -> Result<EvidenceBundle, diesel::result::Error> {
+        use crate::schema::evidence_bundles::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        evidence_bundles
+            .filter(id.eq(row_id))
+            .first::<EvidenceBundle>(connection)
+    }
+
+    // newest bundles first
+    pub fn list_recent(
+        connection_str: &str,
+        limit: i64,
+    ) -> Result<Vec<EvidenceBundle>, diesel::result::Error> {
+        use crate::schema::evidence_bundles::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        evidence_bundles
+            .order(created_dt.desc())
+            .limit(limit)
+            .load::<EvidenceBundle>(connection)
+    }
+
+    pub fn snapshot_file_list(&self) -> Vec<String> {
+        serde_json::from_str(&self.snapshot_files).unwrap_or_default()
+    }
+
+    pub fn clip_file_list(&self) -> Vec<String> {
+        serde_json::from_str(&self.clip_files).unwrap_or_default()
+    }
+}
@@ -0,0 +1,208 @@
+// Ring buffer of heartbeat resource samples powering local dashboard trend
+// charts. Raw one-minute samples are kept for 24 hours, then downsampled into
+// five-minute rollups retained for 30 days, so the table stays bounded on
+// devices that run for months between reboots.
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::connection::establish_sqlite_connection;
+use crate::schema::heartbeat_samples;
+
+pub const RESOLUTION_RAW: &str = "raw";
+pub const RESOLUTION_5M: &str = "5m";
+
+// raw samples older than this are rolled up into 5-minute buckets
+pub const RAW_RETENTION_HOURS: i64 = 24;
+// rollup rows older than this are deleted
+pub const ROLLUP_RETENTION_DAYS: i64 = 30;
+
+const ROLLUP_BUCKET_SECONDS: i64 = 300;
+
+#[derive(Queryable, Identifiable, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[diesel(table_name = heartbeat_samples)]
+pub struct HeartbeatSample {
+    pub id: i32,
+    pub sample_dt: DateTime<Utc>,
+    // "raw" or "5m"
+    pub resolution: String,
+    // cumulative systemd cpu counter, raw rows only; used to compute the
+    // delta-based cpu_percent of the next raw sample
+    pub cpu_usage_usec: Option<i64>,
+    pub cpu_percent: Option<f64>,
+    pub rss_bytes: i64,
+    pub swap_bytes: i64,
+    pub enclosure_temperature: Option<f64>,
+    // glass-to-glass HLS latency estimate; None while the leg produces no output
+    pub hls_latency_ms: Option<i64>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = heartbeat_samples)]
+pub struct NewHeartbeatSample<'a> {
+    pub sample_dt: DateTime<Utc>,
+    pub resolution: &'a str,
+    pub cpu_usage_usec: Option<i64>,
+    pub cpu_percent: Option<f64>,
+    pub rss_bytes: i64,
+    pub swap_bytes: i64,
+    pub enclosure_temperature: Option<f64>,
+    pub hls_latency_ms: Option<i64>,
+}
+
+// floor `dt` to the start of its 5-minute rollup bucket
+pub fn bucket_start(dt: DateTime<Utc>) -> DateTime<Utc> {
+    let ts = dt.timestamp() - dt.timestamp().rem_euclid(ROLLUP_BUCKET_SECONDS);
+    Utc.timestamp_opt(ts, 0).unwrap()
+}
+
+impl HeartbeatSample {
+    pub fn insert(
+        connection_str: &str,
+        row: &NewHeartbeatSample,
+    ) -> Result<usize, diesel::result::Error> {
+        use crate::schema::heartbeat_samples::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        diesel::insert_into(heartbeat_samples)
+            .values(row)
+            .execute(connection)
+    }
+
+    // the most recent raw sample, used to compute the cpu delta for the next one
+    pub fn latest_raw(
+        connection_str: &str,
+    ) -> Result<Option<HeartbeatSample>, diesel::result::Error> {
+        use crate::schema::heartbeat_samples::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        heartbeat_samples
+            .filter(resolution.eq(RESOLUTION_RAW))
+            .order(sample_dt.desc())
+            .first::<HeartbeatSample>(connection)
+            .optional()
+    }
+
+    // all samples (raw and rollup) since `since`, oldest first
+    pub fn list_since(
+        connection_str: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<HeartbeatSample>, diesel::result::Error> {
+        use crate::schema::heartbeat_samples::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        heartbeat_samples
+            .filter(sample_dt.ge(since))
+            .order(sample_dt.asc())
+            .load::<HeartbeatSample>(connection)
+    }
+
+    // downsample raw rows older than RAW_RETENTION_HOURS into 5-minute
+    // averages, then delete the raw rows that were rolled up
+    pub fn rollup(
+        connection_str: &str,
+        now: DateTime<Utc>,
+    ) -> Result<usize, diesel::result::Error> {
+        use crate::schema::heartbeat_samples::dsl::*;
+        let cutoff = now - Duration::hours(RAW_RETENTION_HOURS);
+        let connection = &mut establish_sqlite_connection(connection_str);
+        let expired = heartbeat_samples
+            .filter(resolution.eq(RESOLUTION_RAW))
+            .filter(sample_dt.lt(cutoff))
+            .order(sample_dt.asc())
+            .load::<HeartbeatSample>(connection)?;
+        if expired.is_empty() {
+            return Ok(0);
+        }
+
+        let mut rolled = 0;
+        let mut bucket: Vec<&HeartbeatSample> = Vec::new();
+        let flush = |bucket: &mut Vec<&HeartbeatSample>,
+                     connection: &mut SqliteConnection|
+         -> Result<(), diesel::result::Error> {
+            if bucket.is_empty() {
+                return Ok(());
+            }
+            let row = average_bucket(bucket);
+            diesel::insert_into(heartbeat_samples)
+                .values(&row)
+                .execute(connection)?;
+            bucket.clear();
+            Ok(())
+        };
+        for sample in &expired {
+            if let Some(first) = bucket.first() {
+                if bucket_start(sample.sample_dt) != bucket_start(first.sample_dt) {
+                    flush(&mut bucket, connection)?;
+                }
+            }
+            bucket.push(sample);
+            rolled += 1;
+        }
+        flush(&mut bucket, connection)?;
+
+        diesel::delete(
+            heartbeat_samples
+                .filter(resolution.eq(RESOLUTION_RAW))
+                .filter(sample_dt.lt(cutoff)),
+        )
+        .execute(connection)?;
+        Ok(rolled)
+    }
+
+    // delete rollup rows older than ROLLUP_RETENTION_DAYS
+    pub fn prune(connection_str: &str, now: DateTime<Utc>) -> Result<usize, diesel::result::Error> {
+        use crate::schema::heartbeat_samples::dsl::*;
+        let cutoff = now - Duration::days(ROLLUP_RETENTION_DAYS);
+        let connection = &mut establish_sqlite_connection(connection_str);
+        diesel::delete(
+            heartbeat_samples
+                .filter(resolution.eq(RESOLUTION_5M))
+                .filter(sample_dt.lt(cutoff)),
+        )
+        .execute(connection)
+    }
+}
+
+fn average_bucket<'a>(bucket: &[&'a HeartbeatSample]) -> NewHeartbeatSample<'a> {
+    let count = bucket.len() as f64;
+    let mean_opt = |values: Vec<f64>| -> Option<f64> {
+        if values.is_empty() {
+            None
+        } else {
+            Some(values.iter().sum::<f64>() / values.len() as f64)
+        }
+    };
+    NewHeartbeatSample {
+        sample_dt: bucket_start(bucket[0].sample_dt),
+        resolution: RESOLUTION_5M,
+        // cumulative counters are meaningless once averaged
+        cpu_usage_usec: None,
+        cpu_percent: mean_opt(bucket.iter().filter_map(|s| s.cpu_percent).collect()),
+        rss_bytes: (bucket.iter().map(|s| s.rss_bytes as f64).sum::<f64>() / count) as i64,
+        swap_bytes: (bucket.iter().map(|s| s.swap_bytes as f64).sum::<f64>() / count) as i64,
+        enclosure_temperature: mean_opt(
+            bucket
+                .iter()
+                .filter_map(|s| s.enclosure_temperature)
+                .collect(),
+        ),
+        hls_latency_ms: mean_opt(
+            bucket
+                .iter()
+                .filter_map(|s| s.hls_latency_ms.map(|v| v as f64))
+                .collect(),
+        )
+        .map(|v| v as i64),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_start_floors_to_five_minutes() {
+        let dt = Utc.with_ymd_and_hms(2023, 4, 23, 9, 7, 42).unwrap();
+        let expected = Utc.with_ymd_and_hms(2023, 4, 23, 9, 5, 0).unwrap();
+        assert_eq!(bucket_start(dt), expected);
+        assert_eq!(bucket_start(expected), expected);
+    }
+}
@@ -0,0 +1,190 @@
+// Persistent job rows for long-running operations (OTA updates, video sync,
+// model downloads). The operation creates a row, updates progress as it
+// advances, and finishes it with an outcome, so a requester that disconnects
+// can still query the result via pi.{pi_id}.jobs.get / pi.{pi_id}.jobs.list.
+// Every transition also enqueues a progress event in the cloud event outbox
+// within the same sqlite transaction, so progress is streamed with the same
+// at-least-once delivery as other outbox events.
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::connection::establish_sqlite_connection;
+use crate::outbox::CloudEventOutbox;
+use crate::schema::jobs;
+
+pub const JOB_PROGRESS_SUBJECT: &str = "pi.{pi_id}.event.jobs.progress";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Running,
+    Success,
+    Failed,
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Running => "running",
+            JobStatus::Success => "success",
+            JobStatus::Failed => "failed",
+        }
+    }
+}
+
+impl FromStr for JobStatus {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "running" => Ok(JobStatus::Running),
+            "success" => Ok(JobStatus::Success),
+            "failed" => Ok(JobStatus::Failed),
+            other => Err(format!("Invalid JobStatus: {}", other)),
+        }
+    }
+}
+
+#[derive(Queryable, Identifiable, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[diesel(table_name = jobs)]
+pub struct Job {
+    pub id: String,
+    pub created_dt: DateTime<Utc>,
+    pub updated_dt: DateTime<Utc>,
+    // e.g. "ota", "video_sync", "model_download"
+    pub job_type: String,
+    pub status: String,
+    pub progress_percent: i32,
+    // human-readable description of the current stage
+    pub detail: Option<String>,
+    pub finished_dt: Option<DateTime<Utc>>,
+    pub result: Option<String>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = jobs)]
+pub struct NewJob<'a> {
+    pub id: &'a str,
+    pub created_dt: &'a DateTime<Utc>,
+    pub updated_dt: &'a DateTime<Utc>,
+    pub job_type: &'a str,
+    pub status: &'a str,
+    pub progress_percent: &'a i32,
+    pub detail: Option<&'a str>,
+}
+
+impl Job {
+    // write the row and enqueue its progress event in one transaction
+    fn enqueue_progress_event(
+        connection: &mut SqliteConnection,
+        row_id: &str,
+    ) -> Result<Job, diesel::result::Error> {
+        use crate::schema::jobs::dsl::*;
+        let row = jobs.filter(id.eq(row_id)).first::<Job>(connection)?;
+        let payload = serde_json::to_string(&row)
+            .map_err(|e| diesel::result::Error::SerializationError(Box::new(e)))?;
+        CloudEventOutbox::insert(connection, JOB_PROGRESS_SUBJECT, &payload)?;
+        Ok(row)
+    }
+
+    pub fn create(
+        connection_str: &str,
+        new_job_type: &str,
+        new_detail: Option<&str>,
+    ) -> Result<Job, diesel::result::Error> {
+        let connection = &mut establish_sqlite_connection(connection_str);
+        let row_id = uuid::Uuid::new_v4().to_string();
+        let now = Utc::now();
+        let row = NewJob {
+            id: &row_id,
+            created_dt: &now,
+            updated_dt: &now,
+            job_type: new_job_type,
+            status: JobStatus::Running.as_str(),
+            progress_percent: &0,
+            detail: new_detail,
+        };
+        let job = connection.transaction::<_, diesel::result::Error, _>(|connection| {
+            diesel::insert_into(jobs::table)
+                .values(&row)
+                .execute(connection)?;
+            Self::enqueue_progress_event(connection, &row_id)
+        })?;
+        info!("Created Job id={} job_type={}", row_id, new_job_type);
+        Ok(job)
+    }
+
+    pub fn update_progress(
+        connection_str: &str,
+        row_id: &str,
+        new_progress_percent: i32,
+        new_detail: Option<&str>,
+    ) -> Result<Job, diesel::result::Error> {
+        use crate::schema::jobs::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        connection.transaction::<_, diesel::result::Error, _>(|connection| {
+            diesel::update(jobs.filter(id.eq(row_id)))
+                .set((
+                    updated_dt.eq(Utc::now()),
+                    progress_percent.eq(new_progress_percent),
+                    detail.eq(new_detail),
+                ))
+                .execute(connection)?;
+            Self::enqueue_progress_event(connection, row_id)
+        })
+    }
+
+    pub fn finish(
+        connection_str: &str,
+        row_id: &str,
+        new_status: JobStatus,
+        new_result: &str,
+    ) -> Result<Job, diesel::result::Error> {
+        use crate::schema::jobs::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        let now = Utc::now();
+        let job = connection.transaction::<_, diesel::result::Error, _>(|connection| {
+            diesel::update(jobs.filter(id.eq(row_id)))
+                .set((
+                    updated_dt.eq(now),
+                    status.eq(new_status.as_str()),
+                    finished_dt.eq(Some(now)),
+                    result.eq(new_result),
+                ))
+                .execute(connection)?;
+            Self::enqueue_progress_event(connection, row_id)
+        })?;
+        info!(
+            "Finished Job id={} status={} result={}",
+            row_id,
+            new_status.as_str(),
+            new_result
+        );
+        Ok(job)
+    }
+
+    pub fn get_by_id(connection_str: &str, row_id: &str) -> Result<Job, diesel::result::Error> {
+        use crate::schema::jobs::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        jobs.filter(id.eq(row_id)).first::<Job>(connection)
+    }
+
+    // newest first
+    pub fn get_all(connection_str: &str) -> Result<Vec<Job>, diesel::result::Error> {
+        use crate::schema::jobs::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        jobs.order(created_dt.desc()).load::<Job>(connection)
+    }
+
+    // rows still running, oldest first
+    pub fn get_running(connection_str: &str) -> Result<Vec<Job>, diesel::result::Error> {
+        use crate::schema::jobs::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        jobs.filter(finished_dt.is_null())
+            .order(created_dt.asc())
+            .load::<Job>(connection)
+    }
+}
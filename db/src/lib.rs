@@ -1,8 +1,17 @@
+pub mod bandwidth;
 pub mod cloud;
 pub mod connection;
+pub mod detection_feedback;
+pub mod detections;
+pub mod evidence;
+pub mod heartbeat_samples;
 pub mod janus;
+pub mod jobs;
+pub mod local_auth;
 pub mod nats_app;
 pub mod octoprint;
+pub mod outbox;
+pub mod scheduled_command;
 pub mod schema;
 pub mod sql_types;
 pub mod user;
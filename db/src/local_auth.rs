@@ -0,0 +1,202 @@
+// Local user store backing dashboard/API authentication. Rows only hold
+// argon2 password hashes (hashing itself lives in printnanny-services so this
+// crate stays a thin ORM layer); sessions are opaque uuid tokens with a
+// server-side expiry, revocable by deleting the row.
+use chrono::{DateTime, Duration, Utc};
+use diesel::prelude::*;
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::connection::establish_sqlite_connection;
+use crate::schema::{local_sessions, local_users};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LocalUserRole {
+    // full settings/user management access
+    Admin,
+    // start/stop prints and recordings, no user management
+    Operator,
+    // read-only dashboard and stream access
+    Viewer,
+}
+
+impl LocalUserRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LocalUserRole::Admin => "admin",
+            LocalUserRole::Operator => "operator",
+            LocalUserRole::Viewer => "viewer",
+        }
+    }
+}
+
+impl std::str::FromStr for LocalUserRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "admin" => Ok(LocalUserRole::Admin),
+            "operator" => Ok(LocalUserRole::Operator),
+            "viewer" => Ok(LocalUserRole::Viewer),
+            other => Err(format!("Invalid role: {}", other)),
+        }
+    }
+}
+
+#[derive(Queryable, Identifiable, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[diesel(table_name = local_users)]
+pub struct LocalUser {
+    pub id: i32,
+    pub created_dt: DateTime<Utc>,
+    pub username: String,
+    // argon2 PHC string; never serialized into API payloads
+    #[serde(skip_serializing)]
+    pub password_hash: String,
+    pub role: String,
+}
+
+impl LocalUser {
+    pub fn role(&self) -> Option<LocalUserRole> {
+        self.role.parse().ok()
+    }
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = local_users)]
+pub struct NewLocalUser<'a> {
+    pub created_dt: &'a DateTime<Utc>,
+    pub username: &'a str,
+    pub password_hash: &'a str,
+    pub role: &'a str,
+}
+
+#[derive(Queryable, Identifiable, Insertable, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[diesel(table_name = local_sessions)]
+pub struct LocalSession {
+    pub id: String,
+    pub user_id: i32,
+    pub created_dt: DateTime<Utc>,
+    pub expires_dt: DateTime<Utc>,
+}
+
+impl LocalUser {
+    pub fn create(
+        connection_str: &str,
+        username: &str,
+        password_hash: &str,
+        role: LocalUserRole,
+    ) -> Result<LocalUser, diesel::result::Error> {
+        let connection = &mut establish_sqlite_connection(connection_str);
+        let now = Utc::now();
+        let row = NewLocalUser {
+            created_dt: &now,
+            username,
+            password_hash,
+            role: role.as_str(),
+        };
+        diesel::insert_into(local_users::table)
+            .values(&row)
+            .execute(connection)?;
+        info!("Created LocalUser username={} role={:?}", username, role);
+        LocalUser::get_by_username(connection_str, username)
+            .map(|user| user.expect("LocalUser row missing after insert"))
+    }
+
+    pub fn get_by_username(
+        connection_str: &str,
+        name: &str,
+    ) -> Result<Option<LocalUser>, diesel::result::Error> {
+        use crate::schema::local_users::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        local_users
+            .filter(username.eq(name))
+            .first::<LocalUser>(connection)
+            .optional()
+    }
+
+    pub fn list(connection_str: &str) -> Result<Vec<LocalUser>, diesel::result::Error> {
+        use crate::schema::local_users::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        local_users
+            .order(username.asc())
+            .load::<LocalUser>(connection)
+    }
+
+    pub fn update_password_hash(
+        connection_str: &str,
+        name: &str,
+        new_password_hash: &str,
+    ) -> Result<(), diesel::result::Error> {
+        use crate::schema::local_users::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        diesel::update(local_users.filter(username.eq(name)))
+            .set(password_hash.eq(new_password_hash))
+            .execute(connection)?;
+        Ok(())
+    }
+
+    // sessions are removed by the ON DELETE CASCADE foreign key
+    pub fn delete(connection_str: &str, name: &str) -> Result<usize, diesel::result::Error> {
+        use crate::schema::local_users::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        let deleted = diesel::delete(local_users.filter(username.eq(name))).execute(connection)?;
+        info!("Deleted LocalUser username={}", name);
+        Ok(deleted)
+    }
+}
+
+impl LocalSession {
+    pub fn create(
+        connection_str: &str,
+        session_user_id: i32,
+        ttl: Duration,
+    ) -> Result<LocalSession, diesel::result::Error> {
+        let connection = &mut establish_sqlite_connection(connection_str);
+        let now = Utc::now();
+        let row = LocalSession {
+            id: uuid::Uuid::new_v4().to_string(),
+            user_id: session_user_id,
+            created_dt: now,
+            expires_dt: now + ttl,
+        };
+        diesel::insert_into(local_sessions::table)
+            .values(&row)
+            .execute(connection)?;
+        Ok(row)
+    }
+
+    // resolve a session token to its user; expired sessions resolve to None
+    pub fn get_user(
+        connection_str: &str,
+        session_id: &str,
+    ) -> Result<Option<LocalUser>, diesel::result::Error> {
+        use crate::schema::local_sessions::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        let session = local_sessions
+            .filter(id.eq(session_id))
+            .filter(expires_dt.gt(Utc::now()))
+            .first::<LocalSession>(connection)
+            .optional()?;
+        match session {
+            Some(session) => crate::schema::local_users::dsl::local_users
+                .filter(crate::schema::local_users::dsl::id.eq(session.user_id))
+                .first::<LocalUser>(connection)
+                .optional(),
+            None => Ok(None),
+        }
+    }
+
+    // logout: revoke a single session token
+    pub fn delete(connection_str: &str, session_id: &str) -> Result<usize, diesel::result::Error> {
+        use crate::schema::local_sessions::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        diesel::delete(local_sessions.filter(id.eq(session_id))).execute(connection)
+    }
+
+    pub fn delete_expired(connection_str: &str) -> Result<usize, diesel::result::Error> {
+        use crate::schema::local_sessions::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        diesel::delete(local_sessions.filter(expires_dt.le(Utc::now()))).execute(connection)
+    }
+}
@@ -0,0 +1,115 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::sqlite::SqliteConnection;
+use log::info;
+use serde::{Deserialize, Serialize};
+use uuid;
+
+use crate::connection::establish_sqlite_connection;
+use crate::schema::cloud_event_outbox;
+
+// subject templates use the same {pi_id} placeholder as NatsRequest/NatsReply
+// subject patterns; the relay substitutes the hostname before publishing
+pub const VIDEO_RECORDING_FINISHED_SUBJECT: &str = "pi.{pi_id}.event.recording.finished";
+
+// set by the time sync probe when the system clock can not be trusted (dead RTC
+// battery, chrony unsynchronized, skew above threshold); rows enqueued while
+// suspect carry clock_suspect=true so subscribers order by seq instead of created_dt
+static CLOCK_SUSPECT: AtomicBool = AtomicBool::new(false);
+
+pub fn set_clock_suspect(suspect: bool) {
+    CLOCK_SUSPECT.store(suspect, Ordering::Relaxed);
+}
+
+pub fn clock_suspect() -> bool {
+    CLOCK_SUSPECT.load(Ordering::Relaxed)
+}
+
+// Transactional outbox row: enqueued in the same sqlite transaction as the
+// local state change it describes, published to NATS by a relay task and then
+// marked sent. Delivery is at-least-once across crashes; subscribers can
+// dedupe on the row id.
+#[derive(Queryable, Identifiable, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[diesel(table_name = cloud_event_outbox)]
+pub struct CloudEventOutbox {
+    pub id: String,
+    pub created_dt: DateTime<Utc>,
+    pub subject: String,
+    pub payload: String,
+    pub sent_dt: Option<DateTime<Utc>>,
+    // monotonic sequence assigned in the insert transaction, trustworthy even
+    // when created_dt is not
+    pub seq: i64,
+    pub clock_suspect: bool,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = cloud_event_outbox)]
+pub struct NewCloudEventOutbox<'a> {
+    pub id: &'a str,
+    pub created_dt: &'a DateTime<Utc>,
+    pub subject: &'a str,
+    pub payload: &'a str,
+    pub seq: &'a i64,
+    pub clock_suspect: &'a bool,
+}
+
+impl CloudEventOutbox {
+    // insert using the caller's open connection, so the event is written in the
+    // same transaction as the state change it describes
+    pub fn insert(
+        connection: &mut SqliteConnection,
+        event_subject: &str,
+        event_payload: &str,
+    ) -> Result<(), diesel::result::Error> {
+        use crate::schema::cloud_event_outbox::dsl::{cloud_event_outbox as outbox_dsl, seq};
+        let row_id = uuid::Uuid::new_v4().to_string();
+        let now = Utc::now();
+        let next_seq: i64 = outbox_dsl
+            .select(diesel::dsl::max(seq))
+            .first::<Option<i64>>(connection)?
+            .unwrap_or(0)
+            + 1;
+        let suspect = clock_suspect();
+        let row = NewCloudEventOutbox {
+            id: &row_id,
+            created_dt: &now,
+            subject: event_subject,
+            payload: event_payload,
+            seq: &next_seq,
+            clock_suspect: &suspect,
+        };
+        diesel::insert_into(cloud_event_outbox::table)
+            .values(&row)
+            .execute(connection)?;
+        info!(
+            "Inserted CloudEventOutbox id={} subject={}",
+            row_id, event_subject
+        );
+        Ok(())
+    }
+
+    pub fn get_unsent(
+        connection_str: &str,
+    ) -> Result<Vec<CloudEventOutbox>, diesel::result::Error> {
+        use crate::schema::cloud_event_outbox::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        cloud_event_outbox
+            .filter(sent_dt.is_null())
+            .order(seq.asc())
+            .load::<CloudEventOutbox>(connection)
+    }
+
+    pub fn mark_sent(connection_str: &str, row_id: &str) -> Result<(), diesel::result::Error> {
+        use crate::schema::cloud_event_outbox::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        let now = Utc::now();
+        diesel::update(cloud_event_outbox.filter(id.eq(row_id)))
+            .set(sent_dt.eq(&now))
+            .execute(connection)?;
+        info!("Marked CloudEventOutbox id={} as sent", row_id);
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+// Cloud commands (reboot, swupdate, unit restart) scheduled for later
+// execution, e.g. "reboot tonight at 3am device-local time". The command
+// payload is stored as JSON so rows survive worker restarts; the executor in
+// printnanny_nats_apps::device_command polls for due rows and marks them
+// executed with the outcome.
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::connection::establish_sqlite_connection;
+use crate::schema::scheduled_commands;
+
+#[derive(Queryable, Identifiable, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[diesel(table_name = scheduled_commands)]
+pub struct ScheduledCommand {
+    pub id: String,
+    pub created_dt: DateTime<Utc>,
+    pub execute_at_dt: DateTime<Utc>,
+    // JSON-serialized command payload
+    pub command: String,
+    pub executed_dt: Option<DateTime<Utc>>,
+    pub result: Option<String>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = scheduled_commands)]
+pub struct NewScheduledCommand<'a> {
+    pub id: &'a str,
+    pub created_dt: &'a DateTime<Utc>,
+    pub execute_at_dt: &'a DateTime<Utc>,
+    pub command: &'a str,
+}
+
+impl ScheduledCommand {
+    pub fn create(
+        connection_str: &str,
+        execute_at: &DateTime<Utc>,
+        command_json: &str,
+    ) -> Result<ScheduledCommand, diesel::result::Error> {
+        let connection = &mut establish_sqlite_connection(connection_str);
+        let row_id = uuid::Uuid::new_v4().to_string();
+        let now = Utc::now();
+        let row = NewScheduledCommand {
+            id: &row_id,
+            created_dt: &now,
+            execute_at_dt: execute_at,
+            command: command_json,
+        };
+        diesel::insert_into(scheduled_commands::table)
+            .values(&row)
+            .execute(connection)?;
+        info!(
+            "Created ScheduledCommand id={} execute_at_dt={}",
+            row_id, execute_at
+        );
+        Self::get_by_id(connection_str, &row_id)
+    }
+
+    pub fn get_by_id(
+        connection_str: &str,
+        row_id: &str,
+    ) -> Result<ScheduledCommand, diesel::result::Error> {
+        use crate::schema::scheduled_commands::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        scheduled_commands
+            .filter(id.eq(row_id))
+            .first::<ScheduledCommand>(connection)
+    }
+
+    // rows not yet executed, soonest first
+    pub fn get_pending(
+        connection_str: &str,
+    ) -> Result<Vec<ScheduledCommand>, diesel::result::Error> {
+        use crate::schema::scheduled_commands::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        scheduled_commands
+            .filter(executed_dt.is_null())
+            .order(execute_at_dt.asc())
+            .load::<ScheduledCommand>(connection)
+    }
+
+    // pending rows whose execute_at_dt has passed, soonest first
+    pub fn get_due(connection_str: &str) -> Result<Vec<ScheduledCommand>, diesel::result::Error> {
+        use crate::schema::scheduled_commands::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        scheduled_commands
+            .filter(executed_dt.is_null())
+            .filter(execute_at_dt.le(Utc::now()))
+            .order(execute_at_dt.asc())
+            .load::<ScheduledCommand>(connection)
+    }
+
+    pub fn mark_executed(
+        connection_str: &str,
+        row_id: &str,
+        command_result: &str,
+    ) -> Result<(), diesel::result::Error> {
+        use crate::schema::scheduled_commands::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        diesel::update(scheduled_commands.filter(id.eq(row_id)))
+            .set((executed_dt.eq(Some(Utc::now())), result.eq(command_result)))
+            .execute(connection)?;
+        Ok(())
+    }
+
+    pub fn cancel(connection_str: &str, row_id: &str) -> Result<usize, diesel::result::Error> {
+        use crate::schema::scheduled_commands::dsl::*;
+        let connection = &mut establish_sqlite_connection(connection_str);
+        diesel::delete(
+            scheduled_commands
+                .filter(id.eq(row_id))
+                .filter(executed_dt.is_null()),
+        )
+        .execute(connection)
+    }
+}
@@ -1,5 +1,66 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::sqlite::sql_types::*;
+
+    bandwidth_usage (id) {
+        id -> Integer,
+        day -> Text,
+        subsystem -> Text,
+        bytes_sent -> BigInt,
+        bytes_received -> BigInt,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::sqlite::sql_types::*;
+
+    cloud_event_outbox (id) {
+        id -> Text,
+        created_dt -> TimestamptzSqlite,
+        subject -> Text,
+        payload -> Text,
+        sent_dt -> Nullable<TimestamptzSqlite>,
+        seq -> BigInt,
+        clock_suspect -> Bool,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::sqlite::sql_types::*;
+
+    detection_feedback (id) {
+        id -> Text,
+        created_dt -> TimestamptzSqlite,
+        detection_ts -> BigInt,
+        label -> Text,
+        note -> Nullable<Text>,
+        frame_path -> Nullable<Text>,
+        uploaded_dt -> Nullable<TimestamptzSqlite>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::sqlite::sql_types::*;
+
+    detections (id) {
+        id -> Integer,
+        created_dt -> TimestamptzSqlite,
+        model_id -> Text,
+        rt -> Nullable<BigInt>,
+        nozzle_count -> Nullable<Integer>,
+        nozzle_mean -> Nullable<Double>,
+        print_count -> Nullable<Integer>,
+        print_mean -> Nullable<Double>,
+        raft_count -> Nullable<Integer>,
+        raft_mean -> Nullable<Double>,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use diesel::sqlite::sql_types::*;
@@ -18,6 +79,82 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::sqlite::sql_types::*;
+
+    evidence_bundles (id) {
+        id -> Text,
+        created_dt -> TimestamptzSqlite,
+        dir -> Text,
+        event_rt -> Nullable<BigInt>,
+        spaghetti_mean -> Nullable<Double>,
+        adhesion_mean -> Nullable<Double>,
+        video_recording_id -> Nullable<Text>,
+        snapshot_files -> Text,
+        clip_files -> Text,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::sqlite::sql_types::*;
+
+    heartbeat_samples (id) {
+        id -> Integer,
+        sample_dt -> TimestamptzSqlite,
+        resolution -> Text,
+        cpu_usage_usec -> Nullable<BigInt>,
+        cpu_percent -> Nullable<Double>,
+        rss_bytes -> BigInt,
+        swap_bytes -> BigInt,
+        enclosure_temperature -> Nullable<Double>,
+        hls_latency_ms -> Nullable<BigInt>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::sqlite::sql_types::*;
+
+    jobs (id) {
+        id -> Text,
+        created_dt -> TimestamptzSqlite,
+        updated_dt -> TimestamptzSqlite,
+        job_type -> Text,
+        status -> Text,
+        progress_percent -> Integer,
+        detail -> Nullable<Text>,
+        finished_dt -> Nullable<TimestamptzSqlite>,
+        result -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::sqlite::sql_types::*;
+
+    local_sessions (id) {
+        id -> Text,
+        user_id -> Integer,
+        created_dt -> TimestamptzSqlite,
+        expires_dt -> TimestamptzSqlite,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::sqlite::sql_types::*;
+
+    local_users (id) {
+        id -> Integer,
+        created_dt -> TimestamptzSqlite,
+        username -> Text,
+        password_hash -> Text,
+        role -> Text,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use diesel::sqlite::sql_types::*;
@@ -74,6 +211,20 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::sqlite::sql_types::*;
+
+    scheduled_commands (id) {
+        id -> Text,
+        created_dt -> TimestamptzSqlite,
+        execute_at_dt -> TimestamptzSqlite,
+        command -> Text,
+        executed_dt -> Nullable<TimestamptzSqlite>,
+        result -> Nullable<Text>,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use diesel::sqlite::sql_types::*;
@@ -141,13 +292,24 @@ diesel::table! {
     }
 }
 
+diesel::joinable!(local_sessions -> local_users (user_id));
 diesel::joinable!(video_recording_parts -> video_recordings (video_recording_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    bandwidth_usage,
+    cloud_event_outbox,
+    detection_feedback,
+    detections,
     email_alert_settings,
+    evidence_bundles,
+    heartbeat_samples,
+    jobs,
+    local_sessions,
+    local_users,
     nats_apps,
     octoprint_servers,
     pis,
+    scheduled_commands,
     users,
     video_recording_parts,
     video_recordings,
@@ -11,6 +11,7 @@ use printnanny_api_client::models;
 use printnanny_os_models;
 
 use crate::connection::establish_sqlite_connection;
+use crate::outbox::CloudEventOutbox;
 use crate::schema::video_recording_parts;
 use crate::schema::video_recordings;
 
@@ -182,9 +183,23 @@ impl VideoRecording {
                 recording_start: None,
                 gcode_file_name: None,
             };
-            diesel::update(video_recordings.filter(recording_end.is_null()))
-                .set(row)
-                .execute(connection)?;
+            // enqueue "recording finished" outbox events in the same transaction,
+            // so a crash can not finish the recording without the event (or vice versa)
+            connection.transaction::<_, diesel::result::Error, _>(|connection| {
+                diesel::update(video_recordings.filter(recording_end.is_null()))
+                    .set(row)
+                    .execute(connection)?;
+                for recording in &unfinished_recordings {
+                    let payload = serde_json::to_string(recording)
+                        .map_err(|e| diesel::result::Error::SerializationError(Box::new(e)))?;
+                    CloudEventOutbox::insert(
+                        connection,
+                        crate::outbox::VIDEO_RECORDING_FINISHED_SUBJECT,
+                        &payload,
+                    )?;
+                }
+                Ok(())
+            })?;
         } else {
             info!("No unfinished VideoRecordings found");
         }
@@ -1,4 +1,5 @@
 use std::fs;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use clap::ArgMatches;
@@ -11,16 +12,93 @@ use printnanny_settings::cam::VideoStreamSettings;
 use printnanny_settings::printnanny::PrintNannySettings;
 use printnanny_settings::printnanny_os_models::CameraSettings;
 
+use crate::handle::PipelineHandle;
+
 pub const CAMERA_PIPELINE: &str = "camera";
 pub const H264_ENCODING_PIPELINE: &str = "h264_encode";
 pub const RTP_PIPELINE: &str = "rtp";
 pub const INFERENCE_PIPELINE: &str = "tflite_inference";
 pub const BB_PIPELINE: &str = "bounding_boxes";
+// decoded detection-box frames for compositing onto the main stream
+pub const BB_OVERLAY_PIPELINE: &str = "bounding_boxes_overlay";
 pub const DF_WINDOW_PIPELINE: &str = "df";
+// model A/B evaluation legs: a second tensor_filter running the candidate
+// model on sampled frames from the same camera interpipe
+pub const CANDIDATE_INFERENCE_PIPELINE: &str = "tflite_inference_candidate";
+pub const CANDIDATE_DF_WINDOW_PIPELINE: &str = "df_candidate";
+// candidate dataframes are published on a separate subject so downstream
+// consumers can tag rows by model without parsing the payload
+pub const CANDIDATE_DF_NATS_SUBJECT: &str = "pi.qc.df.candidate";
 pub const SNAPSHOT_PIPELINE: &str = "snapshot";
 pub const HLS_PIPELINE: &str = "hls";
+pub const SRT_PIPELINE: &str = "srt";
 pub const H264_RECORDING_PIPELINE: &str = "h264_record";
+pub const H264_WATERMARK_PIPELINE: &str = "h264_encode_watermark";
+pub const H264_OVERLAY_PIPELINE: &str = "h264_encode_overlay";
 pub const H264_SPLITMUXSINK: &str = "h264_splitmuxsink";
+// circular pre-roll leg: a small splitmuxsink ring that always holds the last
+// ~PREROLL_RING_SECONDS of H.264, flushed to disk on demand by the evidence
+// bundle capture and the clip NATS request
+pub const PREROLL_PIPELINE: &str = "h264_preroll";
+pub const PREROLL_SPLITMUXSINK: &str = "preroll_splitmuxsink";
+// tmpfs-backed, so the always-on ring never wears the SD card
+pub const PREROLL_DIR: &str = "/var/run/printnanny-preroll";
+pub const PREROLL_FRAGMENT_SECONDS: u64 = 2;
+pub const PREROLL_RING_SECONDS: u64 = 60;
+pub const TENSOR_FRAMERATE_CAPSFILTER: &str = "tensor_framerate_capsfilter";
+// user-supplied custom graph legs (video_stream.custom_graph), numbered by
+// their index in the fragments list
+pub const CUSTOM_GRAPH_PIPELINE_PREFIX: &str = "custom_graph_";
+
+// pipelines the element error monitor polls; optional legs that are not
+// instantiated in the current configuration 404 and are skipped
+pub const MANAGED_PIPELINES: &[&str] = &[
+    CAMERA_PIPELINE,
+    H264_ENCODING_PIPELINE,
+    PREROLL_PIPELINE,
+    RTP_PIPELINE,
+    INFERENCE_PIPELINE,
+    BB_PIPELINE,
+    DF_WINDOW_PIPELINE,
+    SNAPSHOT_PIPELINE,
+    HLS_PIPELINE,
+    SRT_PIPELINE,
+    H264_RECORDING_PIPELINE,
+    H264_WATERMARK_PIPELINE,
+    H264_OVERLAY_PIPELINE,
+    BB_OVERLAY_PIPELINE,
+    CANDIDATE_INFERENCE_PIPELINE,
+    CANDIDATE_DF_WINDOW_PIPELINE,
+];
+
+// detection elements addressable via gstd for runtime threshold tuning
+pub const BB_TENSOR_DECODER: &str = "bb_tensor_decoder";
+pub const BB_OVERLAY_TENSOR_DECODER: &str = "bb_overlay_tensor_decoder";
+pub const DF_DATAFRAME_AGG: &str = "df_dataframe_agg";
+pub const CANDIDATE_DF_DATAFRAME_AGG: &str = "df_candidate_dataframe_agg";
+
+// bcm2835 stateful h264 encoder node; present on Raspberry Pi OS images
+pub const V4L2_H264_ENCODER_DEVICE: &str = "/dev/video11";
+
+// bcm2835 ISP resizer node; when present v4l2convert scales and converts
+// colorspace on the ISP instead of the CPU
+pub const V4L2_ISP_DEVICE: &str = "/dev/video12";
+
+// DRM render node used to probe for a usable GLES stack
+pub const DRI_RENDER_DEVICE: &str = "/dev/dri/renderD128";
+
+// how the inference leg scales frames down to tensor resolution and converts
+// them to RGB; CPU-side conversion at 320x320 costs a full core, so the ISP
+// and GLES paths are preferred when the hardware is present
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PreprocessBackend {
+    // Pi ISP via v4l2convert: scale + RGB conversion in one m2m pass
+    Isp,
+    // GLES via glcolorconvert/glcolorscale on the render node
+    Gles,
+    // videoscale/videoconvert in system memory
+    Cpu,
+}
 
 #[derive(Clone, Debug)]
 pub struct PrintNannyPipelineFactory {
@@ -80,33 +158,76 @@ impl PrintNannyPipelineFactory {
         GstClient::build(&self.uri).expect("Failed to build GstClient")
     }
 
+    // typed handle for a named gstd pipeline; the single interface used by the
+    // factory, the recording watchdog poll, and the NATS tune handlers
+    pub fn pipeline(&self, pipeline_name: &str) -> PipelineHandle {
+        PipelineHandle::new(self.gst_client(), pipeline_name)
+    }
+
     pub async fn pipeline_state(&self, pipeline_name: &str) -> GstPipelineState {
-        let client = self.gst_client();
-        match client.pipeline(pipeline_name).state().await {
-            Ok(state_res) => match state_res.response {
-                gst_client::gstd_types::ResponseT::Property(prop) => match prop.value {
-                    gst_client::gstd_types::PropertyValue::String(state) => {
-                        GstPipelineState::from(state.as_ref())
-                    }
-                    _ => GstPipelineState::Null,
-                },
-                _ => GstPipelineState::Null,
-            },
-            Err(e) => {
-                // H264_RECORDING_PIPELINE state is polled every N seconds, 404ing when pipeline doesn't exist
-                // log these at the debug! level, and all other pipelines at the error! level
-                match pipeline_name {
-                    H264_RECORDING_PIPELINE => debug!(
-                        "Error getting gst pipeline state name={} error={}",
-                        pipeline_name, e
-                    ),
-                    _ => error!(
-                        "Error getting gst pipeline state name={} error={}",
-                        pipeline_name, e
-                    ),
-                }
-                GstPipelineState::Null
-            }
+        self.pipeline(pipeline_name).state().await
+    }
+
+    // probe for the v4l2 encoder node; when present the camera -> encoder leg
+    // negotiates DMABuf/NV12 end-to-end so the m2m blocks share buffers instead
+    // of copying frames through system memory (~20% CPU at 1080p30)
+    fn zero_copy_supported() -> bool {
+        let supported = std::path::Path::new(V4L2_H264_ENCODER_DEVICE).exists();
+        if !supported {
+            warn!(
+                "{} not found, falling back to system-memory camera caps",
+                V4L2_H264_ENCODER_DEVICE
+            );
+        }
+        supported
+    }
+
+    // probe for hardware-accelerated tensor preprocessing, preferring the ISP
+    // (one m2m pass) over GLES over the CPU fallback
+    fn preprocess_backend() -> PreprocessBackend {
+        if std::path::Path::new(V4L2_ISP_DEVICE).exists() {
+            return PreprocessBackend::Isp;
+        }
+        if std::path::Path::new(DRI_RENDER_DEVICE).exists() {
+            return PreprocessBackend::Gles;
+        }
+        warn!(
+            "Neither {} nor {} found, falling back to CPU tensor preprocessing",
+            V4L2_ISP_DEVICE, DRI_RENDER_DEVICE
+        );
+        PreprocessBackend::Cpu
+    }
+
+    // scale-to-tensor-resolution + RGB conversion stage of the inference legs
+    fn preprocess_fragment(
+        backend: PreprocessBackend,
+        tensor_format: &str,
+        tensor_width: i32,
+        tensor_height: i32,
+    ) -> String {
+        let caps = format!(
+            "video/x-raw,format={tensor_format},width={tensor_width},height={tensor_height}"
+        );
+        match backend {
+            PreprocessBackend::Isp => format!("! v4l2convert ! capsfilter caps={caps}"),
+            PreprocessBackend::Gles => format!(
+                "! glupload ! glcolorconvert ! glcolorscale ! gldownload ! capsfilter caps={caps}"
+            ),
+            PreprocessBackend::Cpu => format!("! videoscale ! capsfilter caps={caps}"),
+        }
+    }
+
+    fn dmabuf_caps(settings: &VideoStreamSettings) -> String {
+        settings
+            .gst_camera_nv12_caps()
+            .replace("video/x-raw", "video/x-raw(memory:DMABuf)")
+    }
+
+    // caps announced by the camera interpipesink and expected by its listeners
+    fn camera_interpipe_caps(settings: &VideoStreamSettings, zero_copy: bool) -> String {
+        match zero_copy {
+            true => Self::dmabuf_caps(settings),
+            false => settings.gst_camera_caps(),
         }
     }
 
@@ -174,87 +295,411 @@ impl PrintNannyPipelineFactory {
         Ok(())
     }
 
+    // crop/rotate/flip corrections applied in system memory right after the
+    // camera source; frames are scaled back to the configured caps so
+    // downstream interpipe listeners negotiate the same caps either way.
+    // Empty for the identity transform, leaving the description untouched.
+    fn camera_transform_description(settings: &VideoStreamSettings, caps: &str) -> String {
+        let transform = &settings.transform;
+        if transform.is_identity() {
+            return "".to_string();
+        }
+        format!(
+            "{fragment}! videoscale ! capsfilter caps={caps} ",
+            fragment = transform.gst_transform_fragment()
+        )
+    }
+
+    // pure description builders: deterministic functions of their arguments so
+    // golden tests (tests/descriptions.rs) can assert the exact strings handed
+    // to gstd. make_* wrappers resolve the runtime inputs (zero-copy probe,
+    // hostname) and submit the description.
+    pub fn camera_pipeline_description(
+        pipeline_name: &str,
+        settings: &VideoStreamSettings,
+        zero_copy: bool,
+    ) -> String {
+        let interpipesink = Self::to_interpipesink_name(pipeline_name);
+
+        match zero_copy {
+            true => format!(
+                "libcamerasrc camera-name={camera_name} \
+                ! capsfilter caps={nv12_caps} \
+                {transform}\
+                ! v4l2convert capture-io-mode=dmabuf \
+                ! capsfilter caps={dmabuf_caps} \
+                ! interpipesink name={interpipesink} sync=true async=false",
+                camera_name = settings.camera.device_name,
+                nv12_caps = settings.gst_camera_nv12_caps(),
+                transform =
+                    Self::camera_transform_description(settings, &settings.gst_camera_nv12_caps()),
+                dmabuf_caps = Self::dmabuf_caps(settings),
+            ),
+            false => format!(
+                "libcamerasrc camera-name={camera_name} \
+                ! capsfilter caps={caps} \
+                {transform}\
+                ! v4l2convert \
+                ! interpipesink name={interpipesink} sync=true async=false",
+                camera_name = settings.camera.device_name,
+                caps = settings.gst_camera_caps(),
+                transform =
+                    Self::camera_transform_description(settings, &settings.gst_camera_caps()),
+            ),
+        }
+    }
+
     async fn make_camera_pipeline(
         &self,
         pipeline_name: &str,
         settings: &VideoStreamSettings,
     ) -> Result<gst_client::resources::Pipeline> {
+        let description =
+            Self::camera_pipeline_description(pipeline_name, settings, Self::zero_copy_supported());
+        self.make_pipeline(pipeline_name, &description).await
+    }
+
+    // "privacy" slate: replaces the live camera leg with a black videotestsrc so
+    // downstream pipelines keep running without exposing camera frames
+    pub fn privacy_slate_pipeline_description(
+        pipeline_name: &str,
+        settings: &VideoStreamSettings,
+        zero_copy: bool,
+    ) -> String {
         let interpipesink = Self::to_interpipesink_name(pipeline_name);
-        let caps = settings.gst_camera_caps();
 
-        let description = format!(
-            "libcamerasrc camera-name={camera_name} \
-            ! capsfilter caps={caps} \
-            ! v4l2convert \
-            ! interpipesink name={interpipesink} sync=true async=false",
-            camera_name = settings.camera.device_name,
+        match zero_copy {
+            true => format!(
+                "videotestsrc is-live=true pattern=black \
+                ! capsfilter caps={nv12_caps} \
+                ! v4l2convert capture-io-mode=dmabuf \
+                ! capsfilter caps={dmabuf_caps} \
+                ! interpipesink name={interpipesink} sync=true async=false",
+                nv12_caps = settings.gst_camera_nv12_caps(),
+                dmabuf_caps = Self::dmabuf_caps(settings),
+            ),
+            false => format!(
+                "videotestsrc is-live=true pattern=black \
+                ! capsfilter caps={caps} \
+                ! v4l2convert \
+                ! interpipesink name={interpipesink} sync=true async=false",
+                caps = settings.gst_camera_caps(),
+            ),
+        }
+    }
+
+    async fn make_privacy_slate_pipeline(
+        &self,
+        pipeline_name: &str,
+        settings: &VideoStreamSettings,
+    ) -> Result<gst_client::resources::Pipeline> {
+        let description = Self::privacy_slate_pipeline_description(
+            pipeline_name,
+            settings,
+            Self::zero_copy_supported(),
         );
         self.make_pipeline(pipeline_name, &description).await
     }
 
-    async fn make_jpeg_snapshot_pipeline(
-        &self,
+    // swap the camera source leg between live camera and privacy slate
+    pub async fn set_privacy_mode(&self, enabled: bool) -> Result<()> {
+        let settings = PrintNannySettings::new().await?;
+        match self.delete_pipeline(CAMERA_PIPELINE).await {
+            Ok(_) => info!("Deleted existing pipeline={CAMERA_PIPELINE}"),
+            Err(e) => info!("Failed to delete pipeline={CAMERA_PIPELINE} error={}", e),
+        };
+        let pipeline = match enabled {
+            true => {
+                self.make_privacy_slate_pipeline(CAMERA_PIPELINE, &settings.video_stream)
+                    .await?
+            }
+            false => {
+                self.make_camera_pipeline(CAMERA_PIPELINE, &settings.video_stream)
+                    .await?
+            }
+        };
+        pipeline.pause().await?;
+        pipeline.play().await?;
+        info!(
+            "Set privacy_mode={} on pipeline={}",
+            enabled, CAMERA_PIPELINE
+        );
+        Ok(())
+    }
+
+    // rebuild the overlay legs and the HLS pipeline after toggling
+    // VideoStreamSettings.hls_detection_overlay
+    pub async fn set_hls_detection_overlay(&self, enabled: bool) -> Result<()> {
+        let settings = PrintNannySettings::new().await?;
+        for pipeline_name in [HLS_PIPELINE, H264_OVERLAY_PIPELINE, BB_OVERLAY_PIPELINE] {
+            match self.delete_pipeline(pipeline_name).await {
+                Ok(_) => info!("Deleted existing pipeline={pipeline_name}"),
+                Err(e) => info!("Failed to delete pipeline={pipeline_name} error={}", e),
+            };
+        }
+        self.sync_optional_pipelines(settings.video_stream).await?;
+        info!(
+            "Set hls_detection_overlay={} on pipeline={}",
+            enabled, HLS_PIPELINE
+        );
+        Ok(())
+    }
+
+    pub fn jpeg_snapshot_pipeline_description(
         pipeline_name: &str,
         listen_to: &str,
         settings: &VideoStreamSettings,
-    ) -> Result<gst_client::resources::Pipeline> {
+        zero_copy: bool,
+    ) -> String {
         let interpipesrc = Self::to_interpipesrc_name(pipeline_name);
         let listen_to = Self::to_interpipesink_name(listen_to);
 
         let filesink_location = settings.snapshot.path.as_str();
 
         let max_buffers = 30;
-        let caps = settings.gst_camera_caps();
-        let description = format!("interpipesrc name={interpipesrc} listen-to={listen_to} accept-events=false accept-eos-event=false is-live=true allow-renegotiation=false max-buffers={max_buffers} leaky-type=2 caps={caps} \
-            ! v4l2jpegenc ! multifilesink location={filesink_location} max-files={max_buffers}",
+        let caps = Self::camera_interpipe_caps(settings, zero_copy);
+        let import_mode = match zero_copy {
+            true => " output-io-mode=dmabuf-import",
+            false => "",
+        };
+        format!("interpipesrc name={interpipesrc} listen-to={listen_to} accept-events=false accept-eos-event=false is-live=true allow-renegotiation=false max-buffers={max_buffers} leaky-type=2 caps={caps} \
+            ! v4l2jpegenc{import_mode} ! multifilesink location={filesink_location} max-files={max_buffers}",
+        )
+    }
+
+    async fn make_jpeg_snapshot_pipeline(
+        &self,
+        pipeline_name: &str,
+        listen_to: &str,
+        settings: &VideoStreamSettings,
+    ) -> Result<gst_client::resources::Pipeline> {
+        let description = Self::jpeg_snapshot_pipeline_description(
+            pipeline_name,
+            listen_to,
+            settings,
+            Self::zero_copy_supported(),
         );
         self.make_pipeline(pipeline_name, &description).await
     }
 
+    pub fn h264_encode_pipeline_description(
+        pipeline_name: &str,
+        listen_to: &str,
+        settings: &VideoStreamSettings,
+        zero_copy: bool,
+    ) -> String {
+        let listen_to = Self::to_interpipesink_name(listen_to);
+        let interpipesrc = Self::to_interpipesrc_name(pipeline_name);
+        let interpipesink = Self::to_interpipesink_name(pipeline_name);
+
+        let caps: String = Self::camera_interpipe_caps(settings, zero_copy);
+        // videoflip runs in system memory, so a non-identity stream transform
+        // leaves the DMABuf sharing path before the encoder (same conversion
+        // the watermark leg uses)
+        let stream_transform = &settings.stream_transform;
+        let (transform, import_mode) = match (stream_transform.is_identity(), zero_copy) {
+            (true, true) => ("".to_string(), " output-io-mode=dmabuf-import"),
+            (true, false) => ("".to_string(), ""),
+            (false, true) => (
+                format!(
+                    "! v4l2convert output-io-mode=dmabuf-import ! capsfilter caps={} {}",
+                    settings.gst_camera_nv12_caps(),
+                    stream_transform.gst_transform_fragment()
+                ),
+                "",
+            ),
+            (false, false) => (stream_transform.gst_transform_fragment(), ""),
+        };
+        format!("interpipesrc name={interpipesrc} listen-to={listen_to} accept-events=false accept-eos-event=false is-live=true allow-renegotiation=true caps={caps} \
+            {transform}\
+            ! v4l2h264enc{import_mode} extra-controls=controls,repeat_sequence_header=1 \
+            ! h264parse name={pipeline_name}_h264parse \
+            ! capssetter caps=video/x-h264,level=(string)4,profile=(string)high \
+            ! interpipesink name={interpipesink} sync=false async=false forward-events=true forward-eos=true",
+        )
+    }
+
     async fn make_h264_encode_pipeline(
         &self,
         pipeline_name: &str,
         listen_to: &str,
         settings: &VideoStreamSettings,
     ) -> Result<gst_client::resources::Pipeline> {
+        let description = Self::h264_encode_pipeline_description(
+            pipeline_name,
+            listen_to,
+            settings,
+            Self::zero_copy_supported(),
+        );
+        self.make_pipeline(pipeline_name, &description).await
+    }
+
+    // textoverlay/clockoverlay fragment rendered before the encoder
+    fn watermark_overlay_description(settings: &VideoStreamSettings, hostname: &str) -> String {
+        let watermark = &settings.watermark;
+        let valignment = watermark.valignment.as_str();
+        let halignment = watermark.halignment.as_str();
+        let time_format = watermark.time_format.as_str();
+
+        let device_name_overlay = match watermark.show_device_name {
+            true => {
+                format!("! textoverlay text={hostname} valignment={valignment} halignment={halignment} ")
+            }
+            false => "".to_string(),
+        };
+        format!("{device_name_overlay}! clockoverlay time-format=\"{time_format}\" valignment={valignment} halignment=right ")
+    }
+
+    pub fn h264_watermark_encode_pipeline_description(
+        pipeline_name: &str,
+        listen_to: &str,
+        settings: &VideoStreamSettings,
+        zero_copy: bool,
+        hostname: &str,
+    ) -> String {
         let listen_to = Self::to_interpipesink_name(listen_to);
         let interpipesrc = Self::to_interpipesrc_name(pipeline_name);
         let interpipesink = Self::to_interpipesink_name(pipeline_name);
 
-        let caps: String = settings.gst_camera_caps();
-        let description = format!("interpipesrc name={interpipesrc} listen-to={listen_to} accept-events=false accept-eos-event=false is-live=true allow-renegotiation=true caps={caps} \
+        // overlays are skipped when this leg exists only to re-orient
+        // recordings (recording_transform without any watermark output)
+        let overlay = match settings.watermark.enabled_hls || settings.watermark.enabled_recording {
+            true => Self::watermark_overlay_description(settings, hostname),
+            false => "".to_string(),
+        };
+        let caps: String = Self::camera_interpipe_caps(settings, zero_copy);
+        // overlays render in system memory, so the shared DMABuf is converted
+        // back before the textoverlay/clockoverlay leg
+        let to_sysmem = match zero_copy {
+            true => format!(
+                "! v4l2convert output-io-mode=dmabuf-import ! capsfilter caps={} ",
+                settings.gst_camera_nv12_caps()
+            ),
+            false => "".to_string(),
+        };
+        // rotate/flip before the overlays so watermark text keeps its own
+        // orientation in the corrected frame
+        let transform = settings.recording_transform.gst_transform_fragment();
+        format!("interpipesrc name={interpipesrc} listen-to={listen_to} accept-events=false accept-eos-event=false is-live=true allow-renegotiation=true caps={caps} \
+            {to_sysmem}{transform}{overlay}\
             ! v4l2h264enc extra-controls=controls,repeat_sequence_header=1 \
             ! h264parse name={pipeline_name}_h264parse \
             ! capssetter caps=video/x-h264,level=(string)4,profile=(string)high \
             ! interpipesink name={interpipesink} sync=false async=false forward-events=true forward-eos=true",
-        );
-        self.make_pipeline(pipeline_name, &description).await
+        )
     }
 
-    async fn make_rtp_pipeline(
+    // watermarked variant of the h264 encode leg, shared by HLS and recording outputs
+    async fn make_h264_watermark_encode_pipeline(
         &self,
         pipeline_name: &str,
         listen_to: &str,
         settings: &VideoStreamSettings,
     ) -> Result<gst_client::resources::Pipeline> {
+        let hostname =
+            printnanny_settings::sys_info::hostname().unwrap_or_else(|_| "printnanny".into());
+        let description = Self::h264_watermark_encode_pipeline_description(
+            pipeline_name,
+            listen_to,
+            settings,
+            Self::zero_copy_supported(),
+            &hostname,
+        );
+        self.make_pipeline(pipeline_name, &description).await
+    }
+
+    // multiudpsink client list: the default local destination plus any
+    // configured fan-out destinations
+    fn rtp_clients(settings: &VideoStreamSettings) -> String {
+        let mut clients = vec![format!("127.0.0.1:{}", settings.rtp.video_udp_port)];
+        clients.extend(
+            settings
+                .rtp_destinations
+                .iter()
+                .map(|destination| format!("{}:{}", destination.host, destination.port)),
+        );
+        clients.join(",")
+    }
+
+    pub fn rtp_pipeline_description(
+        pipeline_name: &str,
+        listen_to: &str,
+        settings: &VideoStreamSettings,
+    ) -> String {
         let listen_to = Self::to_interpipesink_name(listen_to);
         let interpipesrc = Self::to_interpipesrc_name(pipeline_name);
 
-        let port = settings.rtp.video_udp_port;
+        let clients = Self::rtp_clients(settings);
 
-        let description = format!("interpipesrc name={interpipesrc} listen-to={listen_to} accept-events=false accept-eos-event=false is-live=true allow-renegotiation=true format=3 \
+        format!("interpipesrc name={interpipesrc} listen-to={listen_to} accept-events=false accept-eos-event=false is-live=true allow-renegotiation=true format=3 \
             ! rtph264pay config-interval=1 aggregate-mode=zero-latency pt=96 \
             ! queue2 \
-            ! udpsink port={port}");
+            ! multiudpsink name={pipeline_name}_udpsink clients={clients}")
+    }
+
+    async fn make_rtp_pipeline(
+        &self,
+        pipeline_name: &str,
+        listen_to: &str,
+        settings: &VideoStreamSettings,
+    ) -> Result<gst_client::resources::Pipeline> {
+        let description = Self::rtp_pipeline_description(pipeline_name, listen_to, settings);
         self.make_pipeline(pipeline_name, &description).await
     }
 
-    async fn make_hls_pipeline(
+    // update the rtp fan-out destinations on the running pipeline via gstd
+    pub async fn set_rtp_destinations(&self, settings: &VideoStreamSettings) -> Result<()> {
+        let clients = Self::rtp_clients(settings);
+        let element = format!("{RTP_PIPELINE}_udpsink");
+        self.pipeline(RTP_PIPELINE)
+            .set_property(&element, "clients", &clients)
+            .await?;
+        info!("Set clients={} on pipeline={}", clients, RTP_PIPELINE);
+        Ok(())
+    }
+
+    // SRT relay leg for remote monitoring over lossy networks: mpegts-muxed
+    // h264 pushed through srtsink, which retransmits lost packets within the
+    // configured latency window (raw RTP over UDP just drops them)
+    pub fn srt_pipeline_description(
+        pipeline_name: &str,
+        listen_to: &str,
+        settings: &VideoStreamSettings,
+    ) -> String {
+        let listen_to = Self::to_interpipesink_name(listen_to);
+        let interpipesrc = Self::to_interpipesrc_name(pipeline_name);
+
+        let srt = &settings.srt;
+        let uri = srt.uri.as_str();
+        let latency = srt.latency_ms;
+        let passphrase = match srt.passphrase.is_empty() {
+            true => "".to_string(),
+            false => format!(" passphrase={}", srt.passphrase),
+        };
+
+        // h264parse config-interval=-1 inserts SPS/PPS on every IDR so a
+        // monitor connecting mid-stream can decode
+        format!("interpipesrc name={interpipesrc} listen-to={listen_to} accept-events=false accept-eos-event=false is-live=true allow-renegotiation=true format=3 \
+            ! h264parse config-interval=-1 \
+            ! mpegtsmux \
+            ! srtsink name={pipeline_name}_srtsink uri={uri} latency={latency} wait-for-connection=false{passphrase}")
+    }
+
+    async fn make_srt_pipeline(
         &self,
         pipeline_name: &str,
         listen_to: &str,
         settings: &VideoStreamSettings,
     ) -> Result<gst_client::resources::Pipeline> {
+        let description = Self::srt_pipeline_description(pipeline_name, listen_to, settings);
+        self.make_pipeline(pipeline_name, &description).await
+    }
+
+    pub fn hls_pipeline_description(
+        pipeline_name: &str,
+        listen_to: &str,
+        settings: &VideoStreamSettings,
+    ) -> String {
         let listen_to = Self::to_interpipesink_name(listen_to);
         let interpipesrc = Self::to_interpipesrc_name(pipeline_name);
 
@@ -275,48 +720,245 @@ impl PrintNannyPipelineFactory {
         let framerate_n = settings.camera.framerate_n;
         let target_duration = (60 / framerate_n) + 1; // v4l2-ctl --list-ctrls-menu -d 11 -> h264_i_frame_period default sends a key unit every 60 frames
 
-        let description = format!("interpipesrc name={interpipesrc} listen-to={listen_to} accept-events=false accept-eos-event=false is-live=true allow-renegotiation=true format=3 \
-            ! hlssink2 playlist-length=8 max-files=10 target-duration={target_duration} location={hls_segments_location} playlist-location={hls_playlist_location} playlist-root={hls_playlist_root} send-keyframe-requests=false");
-        self.make_pipeline(pipeline_name, &description).await
+        format!("interpipesrc name={interpipesrc} listen-to={listen_to} accept-events=false accept-eos-event=false is-live=true allow-renegotiation=true format=3 \
+            ! hlssink2 playlist-length=8 max-files=10 target-duration={target_duration} location={hls_segments_location} playlist-location={hls_playlist_location} playlist-root={hls_playlist_root} send-keyframe-requests=false")
     }
 
-    async fn make_inference_pipeline(
+    async fn make_hls_pipeline(
         &self,
         pipeline_name: &str,
         listen_to: &str,
         settings: &VideoStreamSettings,
     ) -> Result<gst_client::resources::Pipeline> {
+        let description = Self::hls_pipeline_description(pipeline_name, listen_to, settings);
+        self.make_pipeline(pipeline_name, &description).await
+    }
+
+    pub fn inference_pipeline_description(
+        pipeline_name: &str,
+        listen_to: &str,
+        settings: &VideoStreamSettings,
+        zero_copy: bool,
+        backend: PreprocessBackend,
+    ) -> String {
         let listen_to = Self::to_interpipesink_name(listen_to);
         let interpipesrc = Self::to_interpipesrc_name(pipeline_name);
         let interpipesink = Self::to_interpipesink_name(pipeline_name);
 
         let tensor_format = "RGB"; // model expects pixel data to be in RGB format
-        let caps: String = settings.gst_camera_caps();
+        let caps: String = Self::camera_interpipe_caps(settings, zero_copy);
+        let import_mode = match zero_copy {
+            true => " output-io-mode=dmabuf-import",
+            false => "",
+        };
 
         let detection_settings = &*settings.detection;
         let tensor_width = detection_settings.tensor_width;
         let tensor_height = detection_settings.tensor_height;
+        let tensor_framerate = detection_settings.tensor_framerate;
         let tflite_model_file = detection_settings.model_file.as_str();
+        let preprocess =
+            Self::preprocess_fragment(backend, tensor_format, tensor_width, tensor_height);
+
+        // the videorate caps carry the stride as a fractional framerate, so
+        // detection runs on every Nth frame of the tensor_framerate stream
+        let frame_stride = settings.inference.frame_stride.max(1);
+        // batch N frames per tensor_filter invocation when the model accepts a
+        // batched input tensor
+        let batch = match settings.inference.batch_frames > 1 {
+            true => format!(" frames-per-tensor={}", settings.inference.batch_frames),
+            false => "".to_string(),
+        };
 
         let max_buffers = 3;
-        let description = format!("interpipesrc name={interpipesrc} listen-to={listen_to} accept-events=false accept-eos-event=false is-live=true allow-renegotiation=false max-buffers={max_buffers} leaky-type=2 caps={caps} \
-            ! v4l2convert ! videoscale ! capsfilter caps=video/x-raw,format={tensor_format},width={tensor_width},height={tensor_height} \
-            ! tensor_converter \
+        format!("interpipesrc name={interpipesrc} listen-to={listen_to} accept-events=false accept-eos-event=false is-live=true allow-renegotiation=false max-buffers={max_buffers} leaky-type=2 caps={caps} \
+            ! v4l2convert{import_mode} \
+            ! videorate drop-only=true ! capsfilter name={tensor_framerate_capsfilter} caps=video/x-raw,framerate={tensor_framerate}/{frame_stride} \
+            {preprocess} \
+            ! tensor_converter{batch} \
             ! tensor_transform mode=arithmetic option=typecast:uint8,add:0,div:1 \
             ! capsfilter caps=other/tensors,format=static \
             ! tensor_filter framework=tensorflow2-lite model={tflite_model_file} \
             ! interpipesink name={interpipesink} sync=false async=false",
+            tensor_framerate_capsfilter = TENSOR_FRAMERATE_CAPSFILTER,
+        )
+    }
+
+    async fn make_inference_pipeline(
+        &self,
+        pipeline_name: &str,
+        listen_to: &str,
+        settings: &VideoStreamSettings,
+    ) -> Result<gst_client::resources::Pipeline> {
+        let description = Self::inference_pipeline_description(
+            pipeline_name,
+            listen_to,
+            settings,
+            Self::zero_copy_supported(),
+            Self::preprocess_backend(),
         );
+        self.make_pipeline(pipeline_name, &description).await
+    }
+
+    // same shape as inference_pipeline_description, but running the candidate
+    // model on sampled frames so the comparison leg stays cheap
+    pub fn candidate_inference_pipeline_description(
+        pipeline_name: &str,
+        listen_to: &str,
+        settings: &VideoStreamSettings,
+        zero_copy: bool,
+        backend: PreprocessBackend,
+    ) -> String {
+        let listen_to = Self::to_interpipesink_name(listen_to);
+        let interpipesrc = Self::to_interpipesrc_name(pipeline_name);
+        let interpipesink = Self::to_interpipesink_name(pipeline_name);
 
+        let tensor_format = "RGB"; // model expects pixel data to be in RGB format
+        let caps: String = Self::camera_interpipe_caps(settings, zero_copy);
+        let import_mode = match zero_copy {
+            true => " output-io-mode=dmabuf-import",
+            false => "",
+        };
+
+        let detection_settings = &*settings.detection;
+        let tensor_width = detection_settings.tensor_width;
+        let tensor_height = detection_settings.tensor_height;
+        let sample_framerate = settings.model_evaluation.sample_framerate;
+        let tflite_model_file = settings.model_evaluation.candidate_model_file.as_str();
+        let preprocess =
+            Self::preprocess_fragment(backend, tensor_format, tensor_width, tensor_height);
+
+        let max_buffers = 3;
+        format!("interpipesrc name={interpipesrc} listen-to={listen_to} accept-events=false accept-eos-event=false is-live=true allow-renegotiation=false max-buffers={max_buffers} leaky-type=2 caps={caps} \
+            ! v4l2convert{import_mode} \
+            ! videorate drop-only=true ! capsfilter caps=video/x-raw,framerate={sample_framerate}/1 \
+            {preprocess} \
+            ! tensor_converter \
+            ! tensor_transform mode=arithmetic option=typecast:uint8,add:0,div:1 \
+            ! capsfilter caps=other/tensors,format=static \
+            ! tensor_filter framework=tensorflow2-lite model={tflite_model_file} \
+            ! interpipesink name={interpipesink} sync=false async=false")
+    }
+
+    async fn make_candidate_inference_pipeline(
+        &self,
+        pipeline_name: &str,
+        listen_to: &str,
+        settings: &VideoStreamSettings,
+    ) -> Result<gst_client::resources::Pipeline> {
+        let description = Self::candidate_inference_pipeline_description(
+            pipeline_name,
+            listen_to,
+            settings,
+            Self::zero_copy_supported(),
+            Self::preprocess_backend(),
+        );
         self.make_pipeline(pipeline_name, &description).await
     }
 
-    async fn make_bounding_box_pipeline(
+    pub fn candidate_df_pipeline_description(
+        pipeline_name: &str,
+        listen_to: &str,
+        settings: &VideoStreamSettings,
+    ) -> String {
+        let listen_to = Self::to_interpipesink_name(listen_to);
+        let interpipesrc = Self::to_interpipesrc_name(pipeline_name);
+        let detection = &(*settings.detection);
+
+        let nms_threshold = detection.nms_threshold as f32 / 100_f32;
+        let nats_server_uri = detection.nats_server_uri.as_str();
+
+        format!("interpipesrc name={interpipesrc} listen-to={listen_to} accept-events=false accept-eos-event=false is-live=true allow-renegotiation=false \
+            ! tensor_decoder name=df_candidate_tensor_decoder mode=custom-code option1=printnanny_bb_dataframe_decoder \
+            ! dataframe_agg name={dataframe_agg} filter-threshold={nms_threshold} output-type=json \
+            ! nats_sink nats-address={nats_server_uri} nats-subject={nats_subject}",
+            dataframe_agg = CANDIDATE_DF_DATAFRAME_AGG,
+            nats_subject = CANDIDATE_DF_NATS_SUBJECT,
+        )
+    }
+
+    async fn make_candidate_df_pipeline(
         &self,
         pipeline_name: &str,
         listen_to: &str,
         settings: &VideoStreamSettings,
     ) -> Result<gst_client::resources::Pipeline> {
+        let description =
+            Self::candidate_df_pipeline_description(pipeline_name, listen_to, settings);
+        self.make_pipeline(pipeline_name, &description).await
+    }
+
+    // update the inference framerate on the running pipeline via gstd,
+    // preserving the configured frame stride
+    pub async fn set_tensor_framerate(&self, framerate: i32, frame_stride: i32) -> Result<()> {
+        let caps = format!(
+            "video/x-raw,framerate={framerate}/{frame_stride}",
+            frame_stride = frame_stride.max(1)
+        );
+        self.pipeline(INFERENCE_PIPELINE)
+            .set_property(TENSOR_FRAMERATE_CAPSFILTER, "caps", &caps)
+            .await?;
+        info!(
+            "Set tensor_framerate={} on pipeline={}",
+            framerate, INFERENCE_PIPELINE
+        );
+        Ok(())
+    }
+
+    // retune the detection threshold on the running pipelines via gstd, so
+    // sensitivity can be adjusted live without a pipeline restart. Returns the
+    // names of the pipelines that were updated.
+    pub async fn set_detection_threshold(&self, nms_threshold: i32) -> Result<Vec<String>> {
+        // dataframe_agg expects a 0-1 score, tensor_decoder the 0-100 integer
+        let filter_threshold = format!("{}", nms_threshold as f32 / 100_f32);
+        let option3 = format!("0:1:2:3,{nms_threshold}");
+        let targets = [
+            (
+                DF_WINDOW_PIPELINE,
+                DF_DATAFRAME_AGG,
+                "filter-threshold",
+                &filter_threshold,
+            ),
+            (
+                CANDIDATE_DF_WINDOW_PIPELINE,
+                CANDIDATE_DF_DATAFRAME_AGG,
+                "filter-threshold",
+                &filter_threshold,
+            ),
+            (BB_PIPELINE, BB_TENSOR_DECODER, "option3", &option3),
+            (
+                BB_OVERLAY_PIPELINE,
+                BB_OVERLAY_TENSOR_DECODER,
+                "option3",
+                &option3,
+            ),
+        ];
+        let mut updated = Vec::new();
+        for (pipeline_name, element, property, value) in targets {
+            let pipeline = self.pipeline(pipeline_name);
+            // skip legs that don't exist in the current configuration (404s as Null)
+            if pipeline.state().await == GstPipelineState::Null {
+                debug!(
+                    "Skipping detection threshold update for missing pipeline={}",
+                    pipeline_name
+                );
+                continue;
+            }
+            pipeline.set_property(element, property, value).await?;
+            info!(
+                "Set {}={} on pipeline={} element={}",
+                property, value, pipeline_name, element
+            );
+            updated.push(pipeline_name.to_string());
+        }
+        Ok(updated)
+    }
+
+    pub fn bounding_box_pipeline_description(
+        pipeline_name: &str,
+        listen_to: &str,
+        settings: &VideoStreamSettings,
+    ) -> String {
         let listen_to = Self::to_interpipesink_name(listen_to);
         let interpipesrc = Self::to_interpipesrc_name(pipeline_name);
 
@@ -338,8 +980,8 @@ impl PrintNannyPipelineFactory {
         let caps: String = settings.gst_tensor_decoder_caps();
         let camera = &*settings.camera;
 
-        let description = format!("interpipesrc name={interpipesrc} listen-to={listen_to} accept-events=true accept-eos-event=false is-live=true allow-renegotiation=true \
-            ! tensor_decoder name=bb_tensor_decoder mode=bounding_boxes option1=mobilenet-ssd-postprocess option2={tflite_label_file} option3=0:1:2:3,{nms_threshold} option4={video_width}:{video_height} option5={tensor_width}:{tensor_height} \
+        format!("interpipesrc name={interpipesrc} listen-to={listen_to} accept-events=true accept-eos-event=false is-live=true allow-renegotiation=true \
+            ! tensor_decoder name={tensor_decoder} mode=bounding_boxes option1=mobilenet-ssd-postprocess option2={tflite_label_file} option3=0:1:2:3,{nms_threshold} option4={video_width}:{video_height} option5={tensor_width}:{tensor_height} \
             ! queue \
             ! v4l2convert \
             ! capsfilter caps={caps} \
@@ -354,17 +996,123 @@ impl PrintNannyPipelineFactory {
             tensor_width=detection.tensor_width,
             video_width=camera.width,
             video_height=camera.height,
+            tensor_decoder=BB_TENSOR_DECODER,
+        )
+    }
 
-        );
+    async fn make_bounding_box_pipeline(
+        &self,
+        pipeline_name: &str,
+        listen_to: &str,
+        settings: &VideoStreamSettings,
+    ) -> Result<gst_client::resources::Pipeline> {
+        let description =
+            Self::bounding_box_pipeline_description(pipeline_name, listen_to, settings);
         self.make_pipeline(pipeline_name, &description).await
     }
 
-    async fn make_df_pipeline(
+    // decoded detection-box frames published on an interpipe instead of the
+    // encoded UDP leg, so the overlay encoder can composite them onto the
+    // main stream
+    pub fn bb_overlay_pipeline_description(
+        pipeline_name: &str,
+        listen_to: &str,
+        settings: &VideoStreamSettings,
+    ) -> String {
+        let listen_to = Self::to_interpipesink_name(listen_to);
+        let interpipesrc = Self::to_interpipesrc_name(pipeline_name);
+        let interpipesink = Self::to_interpipesink_name(pipeline_name);
+
+        let detection = &(*settings.detection);
+        let caps: String = settings.gst_tensor_decoder_caps();
+        let camera = &*settings.camera;
+
+        format!("interpipesrc name={interpipesrc} listen-to={listen_to} accept-events=true accept-eos-event=false is-live=true allow-renegotiation=true \
+            ! tensor_decoder name={tensor_decoder} mode=bounding_boxes option1=mobilenet-ssd-postprocess option2={tflite_label_file} option3=0:1:2:3,{nms_threshold} option4={video_width}:{video_height} option5={tensor_width}:{tensor_height} \
+            ! queue \
+            ! v4l2convert \
+            ! capsfilter caps={caps} \
+            ! interpipesink name={interpipesink} sync=false async=false",
+            nms_threshold=detection.nms_threshold,
+            tflite_label_file=detection.label_file,
+            tensor_height=detection.tensor_height,
+            tensor_width=detection.tensor_width,
+            video_width=camera.width,
+            video_height=camera.height,
+            tensor_decoder=BB_OVERLAY_TENSOR_DECODER,
+        )
+    }
+
+    async fn make_bb_overlay_pipeline(
         &self,
         pipeline_name: &str,
         listen_to: &str,
         settings: &VideoStreamSettings,
     ) -> Result<gst_client::resources::Pipeline> {
+        let description = Self::bb_overlay_pipeline_description(pipeline_name, listen_to, settings);
+        self.make_pipeline(pipeline_name, &description).await
+    }
+
+    // encoder leg compositing the detection-box frames over the camera leg,
+    // feeding HLS so the normal dashboard player shows what the model sees
+    pub fn h264_overlay_encode_pipeline_description(
+        pipeline_name: &str,
+        camera_listen_to: &str,
+        boxes_listen_to: &str,
+        settings: &VideoStreamSettings,
+        zero_copy: bool,
+    ) -> String {
+        let camera_listen_to = Self::to_interpipesink_name(camera_listen_to);
+        let boxes_listen_to = Self::to_interpipesink_name(boxes_listen_to);
+        let interpipesrc = Self::to_interpipesrc_name(pipeline_name);
+        let interpipesink = Self::to_interpipesink_name(pipeline_name);
+
+        let caps: String = Self::camera_interpipe_caps(settings, zero_copy);
+        // the compositor runs in system memory, so the shared DMABuf is
+        // converted back before the compositing leg (same conversion the
+        // watermark leg uses)
+        let to_sysmem = match zero_copy {
+            true => format!(
+                "! v4l2convert output-io-mode=dmabuf-import ! capsfilter caps={} ",
+                settings.gst_camera_nv12_caps()
+            ),
+            false => "".to_string(),
+        };
+        format!("interpipesrc name={interpipesrc} listen-to={camera_listen_to} accept-events=false accept-eos-event=false is-live=true allow-renegotiation=true caps={caps} \
+            {to_sysmem}! overlay_compositor.sink_0 \
+            interpipesrc name={interpipesrc}_boxes listen-to={boxes_listen_to} accept-events=false accept-eos-event=false is-live=true allow-renegotiation=true \
+            ! videoconvert ! overlay_compositor.sink_1 \
+            compositor name=overlay_compositor sink_1::zorder=1 \
+            ! v4l2convert \
+            ! v4l2h264enc extra-controls=controls,repeat_sequence_header=1 \
+            ! h264parse name={pipeline_name}_h264parse \
+            ! capssetter caps=video/x-h264,level=(string)4,profile=(string)high \
+            ! interpipesink name={interpipesink} sync=false async=false forward-events=true forward-eos=true",
+        )
+    }
+
+    async fn make_h264_overlay_encode_pipeline(
+        &self,
+        pipeline_name: &str,
+        camera_listen_to: &str,
+        boxes_listen_to: &str,
+        settings: &VideoStreamSettings,
+    ) -> Result<gst_client::resources::Pipeline> {
+        let description = Self::h264_overlay_encode_pipeline_description(
+            pipeline_name,
+            camera_listen_to,
+            boxes_listen_to,
+            settings,
+            Self::zero_copy_supported(),
+        );
+        self.make_pipeline(pipeline_name, &description).await
+    }
+
+    pub fn df_pipeline_description(
+        pipeline_name: &str,
+        listen_to: &str,
+        settings: &VideoStreamSettings,
+    ) -> String {
         let listen_to = Self::to_interpipesink_name(listen_to);
         let interpipesrc = Self::to_interpipesrc_name(pipeline_name);
         let detection = &(*settings.detection);
@@ -372,12 +1120,43 @@ impl PrintNannyPipelineFactory {
         let nms_threshold = detection.nms_threshold as f32 / 100_f32;
         let nats_server_uri = detection.nats_server_uri.as_str();
 
-        let description = format!("interpipesrc name={interpipesrc} listen-to={listen_to} accept-events=false accept-eos-event=false is-live=true allow-renegotiation=false \
+        format!("interpipesrc name={interpipesrc} listen-to={listen_to} accept-events=false accept-eos-event=false is-live=true allow-renegotiation=false \
             ! tensor_decoder name=df_tensor_decoder mode=custom-code option1=printnanny_bb_dataframe_decoder \
-            ! dataframe_agg filter-threshold={nms_threshold} output-type=json \
-            ! nats_sink nats-address={nats_server_uri}");
+            ! dataframe_agg name={dataframe_agg} filter-threshold={nms_threshold} output-type=json \
+            ! nats_sink nats-address={nats_server_uri}",
+            dataframe_agg = DF_DATAFRAME_AGG,
+        )
+    }
+
+    async fn make_df_pipeline(
+        &self,
+        pipeline_name: &str,
+        listen_to: &str,
+        settings: &VideoStreamSettings,
+    ) -> Result<gst_client::resources::Pipeline> {
+        let description = Self::df_pipeline_description(pipeline_name, listen_to, settings);
         self.make_pipeline(pipeline_name, &description).await
     }
+
+    pub fn recording_pipeline_description(
+        pipeline_name: &str,
+        listen_to: &str,
+        filename: &str,
+        filesink_name: &str,
+    ) -> String {
+        let interpipesrc = Self::to_interpipesrc_name(pipeline_name);
+        let listen_to = Self::to_interpipesink_name(listen_to);
+
+        let location = format!("{filename}/%d.mp4");
+        let max_files = 50;
+
+        let max_bytes = 10000000; // 10MB (bytes)
+
+        format!("interpipesrc name={interpipesrc} listen-to={listen_to} accept-events=false accept-eos-event=true is-live=true allow-renegotiation=true format=3 stream-sync=passthrough-ts \
+            ! queue \
+            ! splitmuxsink muxer=mpegtsmux name={filesink_name} max-files={max_files} location={location} max-size-bytes={max_bytes} send-keyframe-requests=false")
+    }
+
     async fn make_recording_pipeline(
         &self,
         pipeline_name: &str,
@@ -386,9 +1165,6 @@ impl PrintNannyPipelineFactory {
         filesink_name: &str,
         _camera: &CameraSettings,
     ) -> Result<gst_client::resources::Pipeline> {
-        let interpipesrc = Self::to_interpipesrc_name(pipeline_name);
-        let listen_to = Self::to_interpipesink_name(listen_to);
-
         // ensure directory exists
         match fs::create_dir_all(filename) {
             Ok(_) => {
@@ -399,30 +1175,126 @@ impl PrintNannyPipelineFactory {
             }
         };
 
-        let location = format!("{filename}/%d.mp4");
-        let max_files = 50;
+        let description =
+            Self::recording_pipeline_description(pipeline_name, listen_to, filename, filesink_name);
+        self.make_pipeline(pipeline_name, &description).await
+    }
 
-        let max_bytes = 10000000; // 10MB (bytes)
+    pub fn preroll_pipeline_description(pipeline_name: &str, listen_to: &str) -> String {
+        let interpipesrc = Self::to_interpipesrc_name(pipeline_name);
+        let listen_to = Self::to_interpipesink_name(listen_to);
+
+        let location = format!("{PREROLL_DIR}/%05d.ts");
+        let max_files = PREROLL_RING_SECONDS / PREROLL_FRAGMENT_SECONDS;
+        // nanoseconds; splitmuxsink drops the oldest fragment once max-files
+        // is reached, so the directory is a rolling window
+        let max_size_time = PREROLL_FRAGMENT_SECONDS * 1_000_000_000;
 
-        let description = format!("interpipesrc name={interpipesrc} listen-to={listen_to} accept-events=false accept-eos-event=true is-live=true allow-renegotiation=true format=3 stream-sync=passthrough-ts \
+        format!("interpipesrc name={interpipesrc} listen-to={listen_to} accept-events=false accept-eos-event=true is-live=true allow-renegotiation=true format=3 stream-sync=passthrough-ts \
             ! queue \
-            ! splitmuxsink muxer=mpegtsmux name={filesink_name} max-files={max_files} location={location} max-size-bytes={max_bytes} send-keyframe-requests=false");
+            ! splitmuxsink muxer=mpegtsmux name={PREROLL_SPLITMUXSINK} max-files={max_files} location={location} max-size-time={max_size_time} send-keyframe-requests=true")
+    }
+
+    async fn make_preroll_pipeline(
+        &self,
+        pipeline_name: &str,
+        listen_to: &str,
+    ) -> Result<gst_client::resources::Pipeline> {
+        // ensure directory exists
+        match fs::create_dir_all(PREROLL_DIR) {
+            Ok(_) => {
+                info!("Created directory={}", PREROLL_DIR);
+            }
+            Err(e) => {
+                error!("Error creating directory={} error={}", PREROLL_DIR, e);
+            }
+        };
+
+        let description = Self::preroll_pipeline_description(pipeline_name, listen_to);
         self.make_pipeline(pipeline_name, &description).await
     }
 
+    // newest ring fragments covering roughly the last `seconds`, oldest first;
+    // the open fragment is included since mpegts remains playable mid-write
+    fn newest_preroll_fragments(dir: &Path, seconds: u64) -> Result<Vec<PathBuf>> {
+        let count = (seconds / PREROLL_FRAGMENT_SECONDS + 1) as usize;
+        let mut fragments: Vec<(std::time::SystemTime, PathBuf)> = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().extension().map(|ext| ext == "ts") == Some(true))
+            .filter_map(|entry| {
+                let modified = entry.metadata().ok()?.modified().ok()?;
+                Some((modified, entry.path()))
+            })
+            .collect();
+        fragments.sort();
+        let skip = fragments.len().saturating_sub(count);
+        Ok(fragments
+            .into_iter()
+            .skip(skip)
+            .map(|(_, path)| path)
+            .collect())
+    }
+
+    // copy the last `seconds` of the pre-roll ring into dest_dir, returning
+    // the copied paths oldest first
+    pub fn flush_preroll_clip(seconds: u64, dest_dir: &Path) -> Result<Vec<PathBuf>> {
+        let fragments = Self::newest_preroll_fragments(Path::new(PREROLL_DIR), seconds)?;
+        fs::create_dir_all(dest_dir)?;
+        let mut copied = Vec::new();
+        for src in fragments {
+            let file_name = src.file_name().unwrap_or_default().to_string_lossy();
+            let dest = dest_dir.join(format!("preroll-{file_name}"));
+            fs::copy(&src, &dest)?;
+            copied.push(dest);
+        }
+        info!(
+            "Flushed {} pre-roll fragments to {}",
+            copied.len(),
+            dest_dir.display()
+        );
+        Ok(copied)
+    }
+
+    // set the viewer-facing stream legs to PAUSED/PLAYING without tearing
+    // anything down: the camera and encoder legs keep running, so resume is
+    // sub-second and recordings/detection are unaffected. Returns the names of
+    // the pipelines whose state was changed.
+    pub async fn set_stream_paused(&self, paused: bool) -> Result<Vec<String>> {
+        let mut changed = Vec::new();
+        for pipeline_name in [RTP_PIPELINE, HLS_PIPELINE] {
+            let pipeline = self.pipeline(pipeline_name);
+            // skip legs that don't exist in the current configuration (404s as Null)
+            let state = pipeline.state().await;
+            match (paused, state) {
+                (true, GstPipelineState::Playing) => {
+                    pipeline.pause().await?;
+                    info!("Paused stream pipeline name={}", pipeline_name);
+                    changed.push(pipeline_name.to_string());
+                }
+                (false, GstPipelineState::Paused) => {
+                    pipeline.play().await?;
+                    info!("Resumed stream pipeline name={}", pipeline_name);
+                    changed.push(pipeline_name.to_string());
+                }
+                (_, state) => debug!(
+                    "Leaving stream pipeline name={} state={:?} unchanged",
+                    pipeline_name, state
+                ),
+            }
+        }
+        Ok(changed)
+    }
+
     pub async fn stop_pipeline(&self, pipeline_name: &str) -> Result<()> {
         info!("Attempting to stop Gstreamer pipeline: {}", &pipeline_name);
-        let client = GstClient::build(&self.uri).expect("Failed to build GstClient");
-        let pipeline = client.pipeline(pipeline_name);
-        pipeline.stop().await?;
+        self.pipeline(pipeline_name).stop().await?;
         info!("Success! Stopped Gstreamer pipeline: {}", &pipeline_name);
         Ok(())
     }
 
     pub async fn start_pipeline(&self, pipeline_name: &str) -> Result<()> {
         info!("Attempting to start Gstreamer pipeline: {}", &pipeline_name);
-        let client = GstClient::build(&self.uri).expect("Failed to build GstClient");
-        let pipeline = client.pipeline(pipeline_name);
+        let pipeline = self.pipeline(pipeline_name);
         pipeline.pause().await?;
         pipeline.play().await?;
         info!("Success! Started Gstreamer pipeline: {}", &pipeline_name);
@@ -430,8 +1302,35 @@ impl PrintNannyPipelineFactory {
     }
 
     pub async fn sync_optional_pipelines(&self, settings: VideoStreamSettings) -> Result<()> {
+        if settings.hls_detection_overlay {
+            let bb_overlay_pipeline = self
+                .make_bb_overlay_pipeline(BB_OVERLAY_PIPELINE, INFERENCE_PIPELINE, &settings)
+                .await?;
+            bb_overlay_pipeline.pause().await?;
+            bb_overlay_pipeline.play().await?;
+            let overlay_pipeline = self
+                .make_h264_overlay_encode_pipeline(
+                    H264_OVERLAY_PIPELINE,
+                    CAMERA_PIPELINE,
+                    BB_OVERLAY_PIPELINE,
+                    &settings,
+                )
+                .await?;
+            overlay_pipeline.pause().await?;
+            overlay_pipeline.play().await?;
+        }
+        // detection overlay wins over the watermark leg, which wins over the
+        // plain encoder leg
+        let hls_listen_to = match (
+            settings.hls_detection_overlay,
+            settings.watermark.enabled_hls,
+        ) {
+            (true, _) => H264_OVERLAY_PIPELINE,
+            (false, true) => H264_WATERMARK_PIPELINE,
+            (false, false) => H264_ENCODING_PIPELINE,
+        };
         let hls_pipeline = self
-            .make_hls_pipeline(HLS_PIPELINE, H264_ENCODING_PIPELINE, &settings)
+            .make_hls_pipeline(HLS_PIPELINE, hls_listen_to, &settings)
             .await?;
         let hls_settings = &*(settings).hls;
         if hls_settings.enabled {
@@ -456,10 +1355,18 @@ impl PrintNannyPipelineFactory {
             ),
         };
 
+        // recordings are re-encoded by the watermark leg when either a
+        // watermark or a recording-only orientation is configured
+        let recording_listen_to = match settings.video_stream.watermark.enabled_recording
+            || !settings.video_stream.recording_transform.is_identity()
+        {
+            true => H264_WATERMARK_PIPELINE,
+            false => H264_ENCODING_PIPELINE,
+        };
         let pipeline = self
             .make_recording_pipeline(
                 H264_RECORDING_PIPELINE,
-                H264_ENCODING_PIPELINE,
+                recording_listen_to,
                 filename,
                 H264_SPLITMUXSINK,
                 &camera,
@@ -495,14 +1402,28 @@ impl PrintNannyPipelineFactory {
 
         let video_settings = settings.video_stream;
 
-        let camera_pipeline = self
-            .make_camera_pipeline(CAMERA_PIPELINE, &video_settings)
-            .await?;
+        let camera_pipeline = match video_settings.privacy_mode {
+            true => {
+                self.make_privacy_slate_pipeline(CAMERA_PIPELINE, &video_settings)
+                    .await?
+            }
+            false => {
+                self.make_camera_pipeline(CAMERA_PIPELINE, &video_settings)
+                    .await?
+            }
+        };
 
         let h264_pipeline = self
             .make_h264_encode_pipeline(H264_ENCODING_PIPELINE, CAMERA_PIPELINE, &video_settings)
             .await?;
 
+        // ring buffer holding the last ~minute of H.264, flushed on demand
+        // for evidence bundles and clip requests; listens to the plain
+        // encoder leg, which always runs
+        let preroll_pipeline = self
+            .make_preroll_pipeline(PREROLL_PIPELINE, H264_ENCODING_PIPELINE)
+            .await?;
+
         let rtp_pipeline = self
             .make_rtp_pipeline(RTP_PIPELINE, H264_ENCODING_PIPELINE, &video_settings)
             .await?;
@@ -526,6 +1447,7 @@ impl PrintNannyPipelineFactory {
         let mut pipelines = vec![
             camera_pipeline,
             h264_pipeline,
+            preroll_pipeline,
             rtp_pipeline,
             inference_pipeline,
             bb_pipeline,
@@ -533,15 +1455,95 @@ impl PrintNannyPipelineFactory {
             snapshot_pipeline,
         ];
 
+        if video_settings.model_evaluation.enabled {
+            let candidate_inference_pipeline = self
+                .make_candidate_inference_pipeline(
+                    CANDIDATE_INFERENCE_PIPELINE,
+                    CAMERA_PIPELINE,
+                    &video_settings,
+                )
+                .await?;
+            let candidate_df_pipeline = self
+                .make_candidate_df_pipeline(
+                    CANDIDATE_DF_WINDOW_PIPELINE,
+                    CANDIDATE_INFERENCE_PIPELINE,
+                    &video_settings,
+                )
+                .await?;
+            pipelines.push(candidate_inference_pipeline);
+            pipelines.push(candidate_df_pipeline);
+        }
+
+        let watermark = &video_settings.watermark;
+        if watermark.enabled_hls
+            || watermark.enabled_recording
+            || !video_settings.recording_transform.is_identity()
+        {
+            let watermark_pipeline = self
+                .make_h264_watermark_encode_pipeline(
+                    H264_WATERMARK_PIPELINE,
+                    CAMERA_PIPELINE,
+                    &video_settings,
+                )
+                .await?;
+            pipelines.push(watermark_pipeline);
+        }
+
+        if video_settings.hls_detection_overlay {
+            let bb_overlay_pipeline = self
+                .make_bb_overlay_pipeline(BB_OVERLAY_PIPELINE, INFERENCE_PIPELINE, &video_settings)
+                .await?;
+            let overlay_pipeline = self
+                .make_h264_overlay_encode_pipeline(
+                    H264_OVERLAY_PIPELINE,
+                    CAMERA_PIPELINE,
+                    BB_OVERLAY_PIPELINE,
+                    &video_settings,
+                )
+                .await?;
+            pipelines.push(bb_overlay_pipeline);
+            pipelines.push(overlay_pipeline);
+        }
+
         let hls_settings = &*(video_settings).hls;
 
         if hls_settings.enabled {
+            let hls_listen_to = match (video_settings.hls_detection_overlay, watermark.enabled_hls)
+            {
+                (true, _) => H264_OVERLAY_PIPELINE,
+                (false, true) => H264_WATERMARK_PIPELINE,
+                (false, false) => H264_ENCODING_PIPELINE,
+            };
             let hls_pipeline = self
-                .make_hls_pipeline(HLS_PIPELINE, H264_ENCODING_PIPELINE, &video_settings)
+                .make_hls_pipeline(HLS_PIPELINE, hls_listen_to, &video_settings)
                 .await?;
             pipelines.push(hls_pipeline);
         }
 
+        if video_settings.srt.enabled {
+            let srt_pipeline = self
+                .make_srt_pipeline(SRT_PIPELINE, H264_ENCODING_PIPELINE, &video_settings)
+                .await?;
+            pipelines.push(srt_pipeline);
+        }
+
+        // user-supplied custom graph legs; fragments are linted (denylist,
+        // element existence, parse dry-run) before they can be saved to
+        // settings, so a create failure here is unexpected — skip the leg
+        // instead of taking the managed graph down with it
+        if video_settings.custom_graph.enabled {
+            for (i, fragment) in video_settings.custom_graph.fragments.iter().enumerate() {
+                let pipeline_name = format!("{}{}", CUSTOM_GRAPH_PIPELINE_PREFIX, i);
+                match self.make_pipeline(&pipeline_name, fragment).await {
+                    Ok(pipeline) => pipelines.push(pipeline),
+                    Err(e) => error!(
+                        "Failed to create custom graph pipeline name={} error={}",
+                        pipeline_name, e
+                    ),
+                }
+            }
+        }
+
         for pipeline in pipelines.iter() {
             info!("Setting pipeline name={} state=PAUSED", pipeline.name);
             pipeline.pause().await?;
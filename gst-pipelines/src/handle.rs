@@ -0,0 +1,304 @@
+// Typed wrapper around a gstd pipeline resource. A PipelineHandle caches the
+// last-known pipeline state, retries transient HTTP failures against gstd, and
+// records element errors read from the gstd bus, so the factory, the recording
+// watchdog, and the NATS tune handlers share one interface instead of ad hoc
+// client calls.
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::Mutex;
+
+use anyhow::Result;
+use gst_client::gstd_types;
+use gst_client::reqwest;
+use gst_client::GstClient;
+use log::{debug, warn};
+use tokio::time::{sleep, Duration};
+
+use crate::factory::GstPipelineState;
+
+// transient gstd failures (daemon restarting, socket not yet bound) are
+// retried this many times before the error is surfaced
+pub const HANDLE_RETRY_ATTEMPTS: u32 = 3;
+pub const HANDLE_RETRY_BACKOFF_MS: u64 = 250;
+// per-pipeline ring of the most recent element errors
+const MAX_ELEMENT_ERRORS: usize = 32;
+
+// last-known state and element errors are shared by every handle pointing at
+// the same pipeline name, so short-lived handles (one per factory call) still
+// see a coherent history
+static PIPELINE_STATES: Mutex<Option<HashMap<String, GstPipelineState>>> = Mutex::new(None);
+static ELEMENT_ERRORS: Mutex<Option<HashMap<String, VecDeque<PipelineElementError>>>> =
+    Mutex::new(None);
+
+// error message read from the gstd bus, attributed to the emitting element
+#[derive(Clone, Debug, PartialEq)]
+pub struct PipelineElementError {
+    pub source: String,
+    pub message: String,
+    pub debug: String,
+    pub timestamp: String,
+}
+
+impl From<&gstd_types::Bus> for PipelineElementError {
+    fn from(bus: &gstd_types::Bus) -> Self {
+        Self {
+            source: bus.source.clone(),
+            message: bus.message.clone(),
+            debug: bus.debug.clone(),
+            timestamp: bus.timestamp.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PipelineHandle {
+    pub name: String,
+    client: GstClient,
+}
+
+impl PipelineHandle {
+    pub fn new(client: GstClient, name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            client,
+        }
+    }
+
+    fn pipeline(&self) -> gst_client::resources::Pipeline {
+        self.client.pipeline(&self.name)
+    }
+
+    // last state observed by any handle for this pipeline, without a gstd query
+    pub fn cached_state(&self) -> Option<GstPipelineState> {
+        let mut states = PIPELINE_STATES.lock().unwrap();
+        states
+            .get_or_insert_with(HashMap::new)
+            .get(&self.name)
+            .cloned()
+    }
+
+    fn record_state(&self, state: Option<GstPipelineState>) {
+        let mut states = PIPELINE_STATES.lock().unwrap();
+        let states = states.get_or_insert_with(HashMap::new);
+        match state {
+            Some(state) => {
+                states.insert(self.name.clone(), state);
+            }
+            None => {
+                states.remove(&self.name);
+            }
+        }
+    }
+
+    // most recent element errors recorded from the gstd bus for this pipeline
+    pub fn element_errors(&self) -> Vec<PipelineElementError> {
+        let mut errors = ELEMENT_ERRORS.lock().unwrap();
+        errors
+            .get_or_insert_with(HashMap::new)
+            .get(&self.name)
+            .map(|ring| ring.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn record_element_error(&self, error: PipelineElementError) {
+        let mut errors = ELEMENT_ERRORS.lock().unwrap();
+        let ring = errors
+            .get_or_insert_with(HashMap::new)
+            .entry(self.name.clone())
+            .or_default();
+        // drop duplicate redeliveries of the same bus message
+        if ring.back() == Some(&error) {
+            return;
+        }
+        if ring.len() == MAX_ELEMENT_ERRORS {
+            ring.pop_front();
+        }
+        ring.push_back(error);
+    }
+
+    // BadStatus is a definitive gstd answer (404 missing pipeline, 409
+    // conflict) and is surfaced immediately; request-level failures are
+    // retried with a linear backoff
+    async fn with_retries<F, Fut>(
+        &self,
+        operation: &str,
+        op: F,
+    ) -> Result<gstd_types::Response, gst_client::Error>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<gstd_types::Response, gst_client::Error>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match op().await {
+                Ok(response) => return Ok(response),
+                Err(e @ gst_client::Error::BadStatus(_, _)) => return Err(e),
+                Err(e) => {
+                    if attempt >= HANDLE_RETRY_ATTEMPTS {
+                        return Err(e);
+                    }
+                    warn!(
+                        "Retrying {} on pipeline={} attempt={}/{} error={}",
+                        operation, self.name, attempt, HANDLE_RETRY_ATTEMPTS, e
+                    );
+                    sleep(Duration::from_millis(
+                        HANDLE_RETRY_BACKOFF_MS * attempt as u64,
+                    ))
+                    .await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    // query gstd for the current state, updating the last-known cache. A 404
+    // clears the cache entry (the pipeline no longer exists); other errors
+    // fall back to the last-known state so pollers ride out gstd restarts.
+    pub async fn state(&self) -> GstPipelineState {
+        let pipeline = self.pipeline();
+        match self.with_retries("state", || pipeline.state()).await {
+            Ok(state_res) => {
+                let state = match state_res.response {
+                    gstd_types::ResponseT::Property(prop) => match prop.value {
+                        gstd_types::PropertyValue::String(state) => {
+                            GstPipelineState::from(state.as_ref())
+                        }
+                        _ => GstPipelineState::Null,
+                    },
+                    _ => GstPipelineState::Null,
+                };
+                self.record_state(Some(state.clone()));
+                state
+            }
+            Err(gst_client::Error::BadStatus(reqwest::StatusCode::NOT_FOUND, _)) => {
+                debug!("Pipeline name={} not found in gstd", self.name);
+                self.record_state(None);
+                GstPipelineState::Null
+            }
+            Err(e) => {
+                let cached = self.cached_state();
+                warn!(
+                    "Error getting gst pipeline state name={} error={}, using last-known state={:?}",
+                    self.name, e, cached
+                );
+                cached.unwrap_or(GstPipelineState::Null)
+            }
+        }
+    }
+
+    pub async fn play(&self) -> Result<(), gst_client::Error> {
+        let pipeline = self.pipeline();
+        self.with_retries("play", || pipeline.play()).await?;
+        self.record_state(Some(GstPipelineState::Playing));
+        Ok(())
+    }
+
+    pub async fn pause(&self) -> Result<(), gst_client::Error> {
+        let pipeline = self.pipeline();
+        self.with_retries("pause", || pipeline.pause()).await?;
+        self.record_state(Some(GstPipelineState::Paused));
+        Ok(())
+    }
+
+    pub async fn stop(&self) -> Result<(), gst_client::Error> {
+        let pipeline = self.pipeline();
+        self.with_retries("stop", || pipeline.stop()).await?;
+        self.record_state(Some(GstPipelineState::Null));
+        Ok(())
+    }
+
+    pub async fn delete(&self) -> Result<(), gst_client::Error> {
+        let pipeline = self.pipeline();
+        self.with_retries("delete", || pipeline.delete()).await?;
+        self.record_state(None);
+        Ok(())
+    }
+
+    pub async fn set_property(
+        &self,
+        element: &str,
+        property: &str,
+        value: &str,
+    ) -> Result<(), gst_client::Error> {
+        let element = self.pipeline().element(element);
+        self.with_retries("set_property", || element.set_property(property, value))
+            .await?;
+        Ok(())
+    }
+
+    // drain pending error messages from the gstd bus into the per-pipeline
+    // ring, returning the newly recorded errors
+    pub async fn poll_element_errors(
+        &self,
+    ) -> Result<Vec<PipelineElementError>, gst_client::Error> {
+        let bus = self.pipeline().bus();
+        bus.set_filter("error").await?;
+        // non-blocking read: return immediately when no message is pending
+        bus.set_timeout(0).await?;
+        let mut recorded = Vec::new();
+        while let gstd_types::ResponseT::Bus(Some(message)) = bus.read().await?.response {
+            let error = PipelineElementError::from(&message);
+            warn!(
+                "Recorded element error on pipeline={} source={} message={}",
+                self.name, error.source, error.message
+            );
+            self.record_element_error(error.clone());
+            recorded.push(error);
+        }
+        Ok(recorded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_handle(name: &str) -> PipelineHandle {
+        let client = GstClient::build("http://127.0.0.1:5002").expect("Failed to build GstClient");
+        PipelineHandle::new(client, name)
+    }
+
+    fn element_error(message: &str) -> PipelineElementError {
+        PipelineElementError {
+            source: "v4l2h264enc0".to_string(),
+            message: message.to_string(),
+            debug: "gstv4l2videoenc.c(123)".to_string(),
+            timestamp: "0:00:01.000000000".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_cached_state_shared_between_handles() {
+        // statics are keyed by pipeline name, so use one unique to this test
+        let handle = test_handle("test_cached_state");
+        assert_eq!(handle.cached_state(), None);
+        handle.record_state(Some(GstPipelineState::Playing));
+        // a second handle for the same pipeline sees the cached state
+        assert_eq!(
+            test_handle("test_cached_state").cached_state(),
+            Some(GstPipelineState::Playing)
+        );
+        // a 404 clears the cache entry
+        handle.record_state(None);
+        assert_eq!(handle.cached_state(), None);
+    }
+
+    #[test]
+    fn test_element_error_ring_caps_and_dedupes() {
+        let handle = test_handle("test_element_errors");
+        // duplicate redeliveries of the same bus message are dropped
+        handle.record_element_error(element_error("not negotiated"));
+        handle.record_element_error(element_error("not negotiated"));
+        assert_eq!(handle.element_errors().len(), 1);
+        // ring holds the most recent MAX_ELEMENT_ERRORS entries
+        for n in 0..(MAX_ELEMENT_ERRORS * 2) {
+            handle.record_element_error(element_error(&format!("error {}", n)));
+        }
+        let errors = handle.element_errors();
+        assert_eq!(errors.len(), MAX_ELEMENT_ERRORS);
+        assert_eq!(
+            errors.last().unwrap().message,
+            format!("error {}", MAX_ELEMENT_ERRORS * 2 - 1)
+        );
+    }
+}
@@ -1,3 +1,7 @@
 pub mod factory;
+pub mod handle;
 
 pub use gst_client;
+
+// reported by `printnanny version --all`
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -0,0 +1,401 @@
+// Golden tests for the pipeline description strings handed to gstd.
+//
+// The description builders are pure functions of settings plus the zero-copy
+// probe result, so the exact strings are asserted against committed fixtures
+// under fixtures/descriptions/. A typo in a format! template (a stray `|`
+// link, a misspelled property name) shows up here as a diff instead of a
+// runtime parse error on the device.
+//
+// After an intentional change, regenerate the fixtures and review the diff:
+//
+//   cargo test -p printnanny-gst-pipelines --test descriptions -- --ignored
+use std::fs;
+use std::path::PathBuf;
+
+use printnanny_settings::cam::{RtpDestination, VideoStreamSettings};
+
+use printnanny_gst_pipelines::factory::{
+    PreprocessBackend, PrintNannyPipelineFactory, BB_OVERLAY_PIPELINE, BB_PIPELINE,
+    CAMERA_PIPELINE, CANDIDATE_DF_WINDOW_PIPELINE, CANDIDATE_INFERENCE_PIPELINE,
+    DF_WINDOW_PIPELINE, H264_ENCODING_PIPELINE, H264_OVERLAY_PIPELINE, H264_RECORDING_PIPELINE,
+    H264_SPLITMUXSINK, H264_WATERMARK_PIPELINE, HLS_PIPELINE, INFERENCE_PIPELINE, RTP_PIPELINE,
+    SNAPSHOT_PIPELINE, SRT_PIPELINE,
+};
+
+// fixed hostname so the watermark fixtures don't depend on the test host
+const HOSTNAME: &str = "printnanny";
+const RECORDING_DIR: &str = "/home/printnanny/.local/share/printnanny/video/1";
+
+fn fixture_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("fixtures")
+        .join("descriptions")
+}
+
+// every description builder, with the zero-copy variants the factory can
+// produce at runtime (.dmabuf = /dev/video11 present, .sysmem = absent)
+fn golden_cases() -> Vec<(&'static str, String)> {
+    type F = PrintNannyPipelineFactory;
+    let settings = VideoStreamSettings::default();
+
+    // the watermark leg is only created when a watermark output is enabled
+    let mut watermarked = VideoStreamSettings::default();
+    watermarked.watermark.enabled_recording = true;
+
+    let mut no_device_name = VideoStreamSettings::default();
+    no_device_name.watermark.enabled_recording = true;
+    no_device_name.watermark.show_device_name = false;
+
+    // upside-down mounted camera corrected for the live stream only
+    let mut stream_flipped = VideoStreamSettings::default();
+    stream_flipped.stream_transform.rotation = 180;
+
+    // recording re-oriented without any watermark output enabled
+    let mut recording_flipped = VideoStreamSettings::default();
+    recording_flipped.recording_transform.rotation = 90;
+    recording_flipped.recording_transform.flip_horizontal = true;
+
+    // rtp fan-out: local Janus port plus a LAN recorder and a remote relay
+    let mut fanout = VideoStreamSettings::default();
+    fanout.rtp_destinations = vec![
+        RtpDestination {
+            host: "192.168.1.50".into(),
+            port: 20001,
+        },
+        RtpDestination {
+            host: "relay.example.com".into(),
+            port: 5004,
+        },
+    ];
+
+    // encrypted SRT relay for a remote OBS/monitoring setup
+    let mut srt_encrypted = VideoStreamSettings::default();
+    srt_encrypted.srt.uri = "srt://relay.example.com:8890?mode=caller".into();
+    srt_encrypted.srt.latency_ms = 500;
+    srt_encrypted.srt.passphrase = "correcthorsebattery".into();
+
+    // low-power device: detect on every 4th frame, batched 2 per invocation
+    let mut strided = VideoStreamSettings::default();
+    strided.inference.frame_stride = 4;
+    strided.inference.batch_frames = 2;
+
+    // wide-angle lens correction: crop distorted edges, rotate for mounting
+    let mut transformed = VideoStreamSettings::default();
+    transformed.transform.crop_top = 8;
+    transformed.transform.crop_bottom = 8;
+    transformed.transform.crop_left = 16;
+    transformed.transform.crop_right = 16;
+    transformed.transform.rotation = 180;
+
+    vec![
+        (
+            "camera.dmabuf",
+            F::camera_pipeline_description(CAMERA_PIPELINE, &settings, true),
+        ),
+        (
+            "camera.sysmem",
+            F::camera_pipeline_description(CAMERA_PIPELINE, &settings, false),
+        ),
+        (
+            "camera.transform.dmabuf",
+            F::camera_pipeline_description(CAMERA_PIPELINE, &transformed, true),
+        ),
+        (
+            "camera.transform.sysmem",
+            F::camera_pipeline_description(CAMERA_PIPELINE, &transformed, false),
+        ),
+        (
+            "privacy_slate.dmabuf",
+            F::privacy_slate_pipeline_description(CAMERA_PIPELINE, &settings, true),
+        ),
+        (
+            "privacy_slate.sysmem",
+            F::privacy_slate_pipeline_description(CAMERA_PIPELINE, &settings, false),
+        ),
+        (
+            "jpeg_snapshot.dmabuf",
+            F::jpeg_snapshot_pipeline_description(
+                SNAPSHOT_PIPELINE,
+                CAMERA_PIPELINE,
+                &settings,
+                true,
+            ),
+        ),
+        (
+            "jpeg_snapshot.sysmem",
+            F::jpeg_snapshot_pipeline_description(
+                SNAPSHOT_PIPELINE,
+                CAMERA_PIPELINE,
+                &settings,
+                false,
+            ),
+        ),
+        (
+            "h264_encode.dmabuf",
+            F::h264_encode_pipeline_description(
+                H264_ENCODING_PIPELINE,
+                CAMERA_PIPELINE,
+                &settings,
+                true,
+            ),
+        ),
+        (
+            "h264_encode.sysmem",
+            F::h264_encode_pipeline_description(
+                H264_ENCODING_PIPELINE,
+                CAMERA_PIPELINE,
+                &settings,
+                false,
+            ),
+        ),
+        (
+            "h264_encode.stream_transform.dmabuf",
+            F::h264_encode_pipeline_description(
+                H264_ENCODING_PIPELINE,
+                CAMERA_PIPELINE,
+                &stream_flipped,
+                true,
+            ),
+        ),
+        (
+            "h264_encode.stream_transform.sysmem",
+            F::h264_encode_pipeline_description(
+                H264_ENCODING_PIPELINE,
+                CAMERA_PIPELINE,
+                &stream_flipped,
+                false,
+            ),
+        ),
+        (
+            "h264_watermark_encode.dmabuf",
+            F::h264_watermark_encode_pipeline_description(
+                H264_WATERMARK_PIPELINE,
+                CAMERA_PIPELINE,
+                &watermarked,
+                true,
+                HOSTNAME,
+            ),
+        ),
+        (
+            "h264_watermark_encode.sysmem",
+            F::h264_watermark_encode_pipeline_description(
+                H264_WATERMARK_PIPELINE,
+                CAMERA_PIPELINE,
+                &watermarked,
+                false,
+                HOSTNAME,
+            ),
+        ),
+        (
+            "h264_watermark_encode.recording_transform",
+            F::h264_watermark_encode_pipeline_description(
+                H264_WATERMARK_PIPELINE,
+                CAMERA_PIPELINE,
+                &recording_flipped,
+                false,
+                HOSTNAME,
+            ),
+        ),
+        (
+            "h264_watermark_encode.no_device_name",
+            F::h264_watermark_encode_pipeline_description(
+                H264_WATERMARK_PIPELINE,
+                CAMERA_PIPELINE,
+                &no_device_name,
+                false,
+                HOSTNAME,
+            ),
+        ),
+        (
+            "rtp",
+            F::rtp_pipeline_description(RTP_PIPELINE, H264_ENCODING_PIPELINE, &settings),
+        ),
+        (
+            "rtp.fanout",
+            F::rtp_pipeline_description(RTP_PIPELINE, H264_ENCODING_PIPELINE, &fanout),
+        ),
+        (
+            "srt",
+            F::srt_pipeline_description(SRT_PIPELINE, H264_ENCODING_PIPELINE, &settings),
+        ),
+        (
+            "srt.passphrase",
+            F::srt_pipeline_description(SRT_PIPELINE, H264_ENCODING_PIPELINE, &srt_encrypted),
+        ),
+        (
+            "hls",
+            F::hls_pipeline_description(HLS_PIPELINE, H264_ENCODING_PIPELINE, &settings),
+        ),
+        (
+            "inference.dmabuf",
+            F::inference_pipeline_description(
+                INFERENCE_PIPELINE,
+                CAMERA_PIPELINE,
+                &settings,
+                true,
+                PreprocessBackend::Isp,
+            ),
+        ),
+        (
+            "inference.sysmem",
+            F::inference_pipeline_description(
+                INFERENCE_PIPELINE,
+                CAMERA_PIPELINE,
+                &settings,
+                false,
+                PreprocessBackend::Cpu,
+            ),
+        ),
+        (
+            "inference.stride_batch",
+            F::inference_pipeline_description(
+                INFERENCE_PIPELINE,
+                CAMERA_PIPELINE,
+                &strided,
+                false,
+                PreprocessBackend::Cpu,
+            ),
+        ),
+        (
+            "inference.gles",
+            F::inference_pipeline_description(
+                INFERENCE_PIPELINE,
+                CAMERA_PIPELINE,
+                &settings,
+                false,
+                PreprocessBackend::Gles,
+            ),
+        ),
+        (
+            "candidate_inference.dmabuf",
+            F::candidate_inference_pipeline_description(
+                CANDIDATE_INFERENCE_PIPELINE,
+                CAMERA_PIPELINE,
+                &settings,
+                true,
+                PreprocessBackend::Isp,
+            ),
+        ),
+        (
+            "candidate_inference.sysmem",
+            F::candidate_inference_pipeline_description(
+                CANDIDATE_INFERENCE_PIPELINE,
+                CAMERA_PIPELINE,
+                &settings,
+                false,
+                PreprocessBackend::Cpu,
+            ),
+        ),
+        (
+            "candidate_df",
+            F::candidate_df_pipeline_description(
+                CANDIDATE_DF_WINDOW_PIPELINE,
+                CANDIDATE_INFERENCE_PIPELINE,
+                &settings,
+            ),
+        ),
+        (
+            "bounding_box",
+            F::bounding_box_pipeline_description(BB_PIPELINE, INFERENCE_PIPELINE, &settings),
+        ),
+        (
+            "df",
+            F::df_pipeline_description(DF_WINDOW_PIPELINE, INFERENCE_PIPELINE, &settings),
+        ),
+        (
+            "bounding_box_overlay",
+            F::bb_overlay_pipeline_description(BB_OVERLAY_PIPELINE, INFERENCE_PIPELINE, &settings),
+        ),
+        (
+            "h264_overlay_encode.dmabuf",
+            F::h264_overlay_encode_pipeline_description(
+                H264_OVERLAY_PIPELINE,
+                CAMERA_PIPELINE,
+                BB_OVERLAY_PIPELINE,
+                &settings,
+                true,
+            ),
+        ),
+        (
+            "h264_overlay_encode.sysmem",
+            F::h264_overlay_encode_pipeline_description(
+                H264_OVERLAY_PIPELINE,
+                CAMERA_PIPELINE,
+                BB_OVERLAY_PIPELINE,
+                &settings,
+                false,
+            ),
+        ),
+        (
+            "recording",
+            F::recording_pipeline_description(
+                H264_RECORDING_PIPELINE,
+                H264_ENCODING_PIPELINE,
+                RECORDING_DIR,
+                H264_SPLITMUXSINK,
+            ),
+        ),
+    ]
+}
+
+#[test_log::test]
+fn test_descriptions_match_golden() {
+    for (name, description) in golden_cases() {
+        let path = fixture_dir().join(format!("{name}.txt"));
+        let expected = fs::read_to_string(&path).unwrap_or_else(|e| {
+            panic!(
+                "Failed to read golden fixture {} ({e}); regenerate with: \
+                cargo test -p printnanny-gst-pipelines --test descriptions -- --ignored",
+                path.display()
+            )
+        });
+        assert_eq!(
+            description,
+            expected.trim_end_matches('\n'),
+            "Pipeline description for case={name} diverged from {}; if the change is \
+            intentional, regenerate with: \
+            cargo test -p printnanny-gst-pipelines --test descriptions -- --ignored",
+            path.display()
+        );
+    }
+}
+
+// gst-launch syntax guards that hold regardless of settings: catches the
+// stray-`|`-instead-of-`!` class of template typo before it reaches gstd
+#[test_log::test]
+fn test_descriptions_are_well_formed() {
+    for (name, description) in golden_cases() {
+        assert!(
+            !description.contains('|'),
+            "case={name} contains `|`; gst-launch links elements with `!`: {description}"
+        );
+        assert!(
+            !description.contains("! !"),
+            "case={name} contains an empty link: {description}"
+        );
+        assert!(
+            !description.trim_end().ends_with('!'),
+            "case={name} ends with a dangling link: {description}"
+        );
+        for element in description.split('!').skip(1) {
+            assert!(
+                !element.trim().is_empty(),
+                "case={name} contains an empty element between links: {description}"
+            );
+        }
+    }
+}
+
+// rewrites the fixture corpus from the current builders; run after an
+// intentional description change, then review the diff before committing
+#[test_log::test]
+#[ignore]
+fn regenerate_golden_descriptions() {
+    let dir = fixture_dir();
+    fs::create_dir_all(&dir).expect("Failed to create fixture directory");
+    for (name, description) in golden_cases() {
+        let path = dir.join(format!("{name}.txt"));
+        fs::write(&path, format!("{description}\n"))
+            .unwrap_or_else(|e| panic!("Failed to write {}: {e}", path.display()));
+    }
+}
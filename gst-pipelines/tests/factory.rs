@@ -0,0 +1,150 @@
+// Integration tests for PrintNannyPipelineFactory against a real gstd
+// instance. Gated behind a feature flag because gstd (and the interpipe
+// plugin the factory descriptions rely on) are only present on a PrintNanny
+// OS image or a development host with the GStreamer stack installed:
+//
+//   cargo test -p printnanny-gst-pipelines --features gstd-integration-tests
+//
+// Each test spawns its own gstd on a dedicated port so tests can run in
+// parallel without sharing pipeline namespaces.
+#![cfg(feature = "gstd-integration-tests")]
+
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+use printnanny_gst_pipelines::factory::{
+    GstPipelineState, PrintNannyPipelineFactory, CAMERA_PIPELINE, SNAPSHOT_PIPELINE,
+};
+
+const GSTD_ADDRESS: &str = "127.0.0.1";
+const STATE_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct GstdFixture {
+    child: Child,
+    factory: PrintNannyPipelineFactory,
+}
+
+impl GstdFixture {
+    async fn start(port: i32) -> Self {
+        let child = Command::new("gstd")
+            .args([
+                "--http-address",
+                GSTD_ADDRESS,
+                "--http-port",
+                &port.to_string(),
+            ])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("Failed to spawn gstd; install gstreamer-daemon to run these tests");
+        let factory = PrintNannyPipelineFactory::new(GSTD_ADDRESS.to_string(), port);
+        // wait for the gstd HTTP API to accept requests
+        let deadline = Instant::now() + STATE_TIMEOUT;
+        while factory.gst_client().pipelines().await.is_err() {
+            if Instant::now() > deadline {
+                panic!("gstd HTTP API on port {} never became available", port);
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        Self { child, factory }
+    }
+}
+
+impl Drop for GstdFixture {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+async fn wait_for_state(
+    factory: &PrintNannyPipelineFactory,
+    pipeline_name: &str,
+    expected: GstPipelineState,
+) -> bool {
+    let deadline = Instant::now() + STATE_TIMEOUT;
+    while Instant::now() < deadline {
+        if factory.pipeline_state(pipeline_name).await == expected {
+            return true;
+        }
+        tokio::time::sleep(Duration::from_millis(250)).await;
+    }
+    false
+}
+
+// camera leg + downstream interpipe consumer, as the factory wires them but
+// sourced from videotestsrc so no camera hardware is required
+async fn create_test_pipelines(factory: &PrintNannyPipelineFactory) {
+    let client = factory.gst_client();
+    client
+        .pipeline(CAMERA_PIPELINE)
+        .create(&format!(
+            "videotestsrc is-live=true \
+            ! capsfilter caps=video/x-raw,width=320,height=240,framerate=15/1 \
+            ! interpipesink name={CAMERA_PIPELINE}_sink sync=true async=false"
+        ))
+        .await
+        .expect("Failed to create camera pipeline");
+    client
+        .pipeline(SNAPSHOT_PIPELINE)
+        .create(&format!(
+            "interpipesrc name={SNAPSHOT_PIPELINE}_src listen-to={CAMERA_PIPELINE}_sink \
+            is-live=true accept-events=false accept-eos-event=false allow-renegotiation=false \
+            ! fakesink sync=false"
+        ))
+        .await
+        .expect("Failed to create snapshot pipeline");
+}
+
+#[test_log::test(tokio::test)]
+async fn test_pipelines_reach_playing() {
+    let fixture = GstdFixture::start(5011).await;
+    let factory = &fixture.factory;
+    create_test_pipelines(factory).await;
+
+    factory.start_pipeline(CAMERA_PIPELINE).await.unwrap();
+    factory.start_pipeline(SNAPSHOT_PIPELINE).await.unwrap();
+
+    assert!(wait_for_state(factory, CAMERA_PIPELINE, GstPipelineState::Playing).await);
+    assert!(wait_for_state(factory, SNAPSHOT_PIPELINE, GstPipelineState::Playing).await);
+}
+
+#[test_log::test(tokio::test)]
+async fn test_duplicate_pipeline_create_conflicts() {
+    let fixture = GstdFixture::start(5012).await;
+    let factory = &fixture.factory;
+    let client = factory.gst_client();
+
+    let description = "videotestsrc is-live=true ! fakesink sync=false";
+    client
+        .pipeline(CAMERA_PIPELINE)
+        .create(description)
+        .await
+        .expect("Failed to create pipeline");
+    // gstd refuses a second pipeline with the same name; the factory treats
+    // this as idempotent (see make_pipeline), so assert on the raw client
+    let result = client.pipeline(CAMERA_PIPELINE).create(description).await;
+    assert!(result.is_err());
+
+    // the original pipeline is still intact and playable
+    factory.start_pipeline(CAMERA_PIPELINE).await.unwrap();
+    assert!(wait_for_state(factory, CAMERA_PIPELINE, GstPipelineState::Playing).await);
+}
+
+#[test_log::test(tokio::test)]
+async fn test_stop_pipeline_returns_to_null() {
+    let fixture = GstdFixture::start(5013).await;
+    let factory = &fixture.factory;
+    let client = factory.gst_client();
+
+    client
+        .pipeline(CAMERA_PIPELINE)
+        .create("videotestsrc is-live=true ! fakesink sync=false")
+        .await
+        .expect("Failed to create pipeline");
+    factory.start_pipeline(CAMERA_PIPELINE).await.unwrap();
+    assert!(wait_for_state(factory, CAMERA_PIPELINE, GstPipelineState::Playing).await);
+
+    factory.stop_pipeline(CAMERA_PIPELINE).await.unwrap();
+    assert!(wait_for_state(factory, CAMERA_PIPELINE, GstPipelineState::Null).await);
+}
@@ -0,0 +1,177 @@
+// Feedback controller for the inference framerate: when CPU temperature or
+// load crosses the configured thresholds the tensor_framerate is halved (via a
+// gstd property update on the running pipeline) so detection degrades
+// gracefully instead of starving the encoder; when headroom returns the rate is
+// stepped back up to the configured value. Restore margins are wider than the
+// degrade thresholds so the controller does not oscillate around them.
+use std::fs::read_to_string;
+use std::time::Duration;
+
+use anyhow::Result;
+use log::{debug, error, warn};
+use serde::{Deserialize, Serialize};
+
+use printnanny_gst_pipelines::factory::{PrintNannyPipelineFactory, INFERENCE_PIPELINE};
+use printnanny_nats_client::client::try_init_nats_client;
+use printnanny_services::metadata::EventMetadata;
+use printnanny_settings::cam::AdaptiveFramerateSettings;
+use printnanny_settings::printnanny::PrintNannySettings;
+use printnanny_settings::sys_info;
+
+const CONTROL_INTERVAL: Duration = Duration::from_secs(30);
+const THERMAL_ZONE_PATH: &str = "/sys/class/thermal/thermal_zone0/temp";
+const LOADAVG_PATH: &str = "/proc/loadavg";
+
+// hysteresis margins between degrading and restoring
+const TEMP_RESTORE_MARGIN_CELSIUS: i32 = 5;
+const LOAD_RESTORE_MARGIN_PERCENT: i32 = 15;
+
+// published on pi.{pi_id}.event.camera.tensor_framerate when the controller
+// changes the inference framerate
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TensorFramerateChangedEvent {
+    pub metadata: EventMetadata,
+    pub pipeline: String,
+    pub old_framerate: i32,
+    pub new_framerate: i32,
+    pub cpu_temp_celsius: i32,
+    pub load_percent: i32,
+}
+
+// /sys/class/thermal/thermal_zone0/temp reports millidegrees celsius
+fn parse_cpu_temp_celsius(content: &str) -> Option<i32> {
+    let millidegrees: i32 = content.trim().parse().ok()?;
+    Some(millidegrees / 1000)
+}
+
+// 1-minute load average from /proc/loadavg, per core, as a percentage
+fn parse_load_percent(content: &str, cores: usize) -> Option<i32> {
+    let load_1m: f64 = content.split_whitespace().next()?.parse().ok()?;
+    Some((load_1m / cores as f64 * 100.0) as i32)
+}
+
+fn target_framerate(
+    current: i32,
+    configured: i32,
+    cpu_temp_celsius: i32,
+    load_percent: i32,
+    settings: &AdaptiveFramerateSettings,
+) -> i32 {
+    let overloaded = cpu_temp_celsius >= settings.cpu_temp_threshold_celsius
+        || load_percent >= settings.load_threshold_percent;
+    let headroom = cpu_temp_celsius
+        < settings.cpu_temp_threshold_celsius - TEMP_RESTORE_MARGIN_CELSIUS
+        && load_percent < settings.load_threshold_percent - LOAD_RESTORE_MARGIN_PERCENT;
+    if overloaded {
+        std::cmp::max(1, current / 2)
+    } else if headroom {
+        std::cmp::min(configured, current + 1)
+    } else {
+        current
+    }
+}
+
+async fn run_control_cycle(current: Option<i32>) -> Result<Option<i32>> {
+    let settings = PrintNannySettings::new().await?;
+    let adaptive = &settings.video_stream.adaptive_framerate;
+    if !adaptive.enabled {
+        return Ok(None);
+    }
+    let configured = settings.video_stream.detection.tensor_framerate;
+    let current = current.unwrap_or(configured);
+
+    let cpu_temp_celsius = parse_cpu_temp_celsius(&read_to_string(THERMAL_ZONE_PATH)?)
+        .ok_or_else(|| anyhow::anyhow!("Failed to parse {}", THERMAL_ZONE_PATH))?;
+    let cores = std::thread::available_parallelism()?.get();
+    let load_percent = parse_load_percent(&read_to_string(LOADAVG_PATH)?, cores)
+        .ok_or_else(|| anyhow::anyhow!("Failed to parse {}", LOADAVG_PATH))?;
+
+    let next = target_framerate(
+        current,
+        configured,
+        cpu_temp_celsius,
+        load_percent,
+        adaptive,
+    );
+    if next == current {
+        debug!(
+            "Adaptive framerate steady at {} cpu_temp={}C load={}%",
+            current, cpu_temp_celsius, load_percent
+        );
+        return Ok(Some(current));
+    }
+
+    let factory = PrintNannyPipelineFactory::default();
+    factory
+        .set_tensor_framerate(next, settings.video_stream.inference.frame_stride)
+        .await?;
+    warn!(
+        "Adaptive framerate changed {} -> {} cpu_temp={}C load={}%",
+        current, next, cpu_temp_celsius, load_percent
+    );
+
+    let hostname = sys_info::hostname().unwrap_or_else(|_| "localhost".to_string());
+    let nats_client = try_init_nats_client(
+        &settings.video_stream.detection.nats_server_uri,
+        &None,
+        false,
+    )
+    .await?;
+    let subject = format!("pi.{hostname}.event.camera.tensor_framerate");
+    let event = TensorFramerateChangedEvent {
+        metadata: EventMetadata::new(),
+        pipeline: INFERENCE_PIPELINE.to_string(),
+        old_framerate: current,
+        new_framerate: next,
+        cpu_temp_celsius,
+        load_percent,
+    };
+    let payload = serde_json::to_vec(&event)?;
+    nats_client.publish(subject, payload.into()).await?;
+    Ok(Some(next))
+}
+
+pub async fn run_adaptive_framerate_controller() {
+    let mut interval = tokio::time::interval(CONTROL_INTERVAL);
+    let mut current: Option<i32> = None;
+    loop {
+        interval.tick().await;
+        match run_control_cycle(current).await {
+            Ok(next) => current = next,
+            Err(e) => error!("Adaptive framerate control cycle failed error={}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_parse_cpu_temp_celsius() {
+        assert_eq!(parse_cpu_temp_celsius("60172\n"), Some(60));
+        assert_eq!(parse_cpu_temp_celsius("not a number"), None);
+    }
+
+    #[test_log::test]
+    fn test_parse_load_percent() {
+        assert_eq!(
+            parse_load_percent("2.00 1.50 1.00 2/300 12345\n", 4),
+            Some(50)
+        );
+        assert_eq!(parse_load_percent("", 4), None);
+    }
+
+    #[test_log::test]
+    fn test_target_framerate_degrades_and_restores() {
+        let settings = AdaptiveFramerateSettings::default();
+        // overloaded: halve, never below 1
+        assert_eq!(target_framerate(4, 4, 80, 10, &settings), 2);
+        assert_eq!(target_framerate(1, 4, 80, 10, &settings), 1);
+        // headroom: step back up to the configured rate
+        assert_eq!(target_framerate(2, 4, 50, 10, &settings), 3);
+        assert_eq!(target_framerate(4, 4, 50, 10, &settings), 4);
+        // between the thresholds: hold steady
+        assert_eq!(target_framerate(2, 4, 72, 10, &settings), 2);
+    }
+}
@@ -29,6 +29,61 @@ async fn main() -> Result<()> {
 
     let worker = NatsSubscriber::<NatsEvent, NatsRequest, NatsReply>::new(&args);
 
+    // load third-party request handler plugins registered in settings
+    match printnanny_settings::printnanny::PrintNannySettings::new().await {
+        Ok(settings) => {
+            printnanny_nats_apps::plugin::load_plugins(&settings);
+        }
+        Err(e) => {
+            log::error!("Failed to load PrintNannySettings, skipping plugins: {}", e);
+        }
+    };
+
+    // internal event bus consumers: producers publish typed events on the bus
+    // instead of holding their own NATS client
+    tokio::spawn(printnanny_nats_apps::bus::run_nats_publisher());
+    tokio::spawn(printnanny_nats_apps::bus::run_recording_trigger());
+    tokio::spawn(printnanny_nats_apps::bus::run_hook_runner());
+
+    // capture clip + snapshot evidence bundles for detection failure events
+    tokio::spawn(printnanny_nats_apps::bus::run_evidence_collector());
+
+    // publish gstd element errors as structured cam_error events
+    tokio::spawn(printnanny_nats_apps::cam_error::run_camera_error_monitor());
+
+    // probe camera/encoder/db/settings repo/cloud once and publish the report
+    tokio::spawn(printnanny_nats_apps::self_test::run_boot_self_test());
+
+    // execute cloud commands scheduled for later (reboot, swupdate, restart)
+    tokio::spawn(printnanny_nats_apps::device_command::run_command_executor());
+
+    // run cron-scheduled tasks (backups, retention pruning, cloud sync)
+    tokio::spawn(printnanny_nats_apps::schedule::run_scheduler());
+
+    // relay unsent cloud event outbox rows in the background
+    tokio::spawn(printnanny_nats_apps::outbox::run_cloud_event_outbox_relay());
+
+    // publish per-service resource usage heartbeats in the background
+    tokio::spawn(printnanny_nats_apps::heartbeat::run_heartbeat());
+
+    // adapt the inference framerate to thermal/load pressure in the background
+    tokio::spawn(printnanny_nats_apps::adaptive_framerate::run_adaptive_framerate_controller());
+
+    // pause the stream legs when nobody has watched for the configured timeout
+    tokio::spawn(printnanny_nats_apps::viewers::run_viewer_idle_monitor());
+
+    // drive the status LED / e-ink display from bus events
+    tokio::spawn(printnanny_nats_apps::display::run_display_driver());
+
+    // detect power loss, pause the print, and shut down before battery depletion
+    tokio::spawn(printnanny_nats_apps::power::run_power_monitor());
+
+    // rotate and cap log_dir in the background
+    tokio::spawn(printnanny_services::log_rotation::run_log_rotation());
+
+    // ONVIF Profile S compatibility layer (WS-Discovery + device/media service)
+    tokio::spawn(printnanny_services::onvif::run_onvif_service());
+
     worker.run().await?;
     Ok(())
 }
@@ -0,0 +1,429 @@
+// Internal broadcast bus between edge subsystems. Producers (the detection
+// dataframe handler, the print state classifier, system monitors) publish
+// typed events here instead of each holding their own NATS client; consumers
+// (the NATS publisher, the recording trigger, the hook runner) run as
+// independent tasks, so a slow or failing consumer can no longer block or
+// abort the producer that observed the event.
+use std::sync::Mutex;
+
+use anyhow::Result;
+use log::{debug, error, info, warn};
+use tokio::sync::broadcast;
+
+use printnanny_nats_client::client::try_init_nats_client;
+use printnanny_services::print_state::PrintState;
+use printnanny_settings::hooks::HookEvent;
+use printnanny_settings::printnanny::PrintNannySettings;
+use printnanny_settings::sys_info;
+
+use crate::event::PrintStateChanged;
+use crate::heartbeat::{EnclosureAlertEvent, HeartbeatEvent, SwapAlertEvent};
+use crate::power::PowerStateChangedEvent;
+use crate::request_reply::NatsRequest;
+use crate::self_test::SelfTestReport;
+
+// events are dropped for a consumer once it falls this far behind
+pub const BUS_CAPACITY: usize = 64;
+
+#[derive(Clone, Debug)]
+pub enum BusEvent {
+    PrintStateChanged(PrintStateChanged),
+    // new latched failure episode from the windowed detection dataframe
+    PrintFailureDetected {
+        rt: Option<i64>,
+        spaghetti_mean: Option<f64>,
+        adhesion_mean: Option<f64>,
+    },
+    // evidence bundle (pre/post-roll clip + snapshots) captured for a
+    // failure episode by the evidence collector
+    EvidenceBundleReady(printnanny_edge_db::evidence::EvidenceBundle),
+    // element error read from the gstd bus by the camera error monitor
+    CameraElementError(crate::cam_error::CameraErrorEvent),
+    SystemHeartbeat(HeartbeatEvent),
+    SwapAlert(SwapAlertEvent),
+    // enclosure sensor threshold breach or smoke detection
+    EnclosureAlert(EnclosureAlertEvent),
+    // power lost/restored, or recovery reported after a power-loss reboot
+    PowerStateChanged(PowerStateChangedEvent),
+    // startup self-test pass/fail matrix
+    BootSelfTest(SelfTestReport),
+}
+
+// process-wide bus sender, created on first use
+static SENDER: Mutex<Option<broadcast::Sender<BusEvent>>> = Mutex::new(None);
+
+pub fn sender() -> broadcast::Sender<BusEvent> {
+    let mut sender = SENDER.lock().unwrap();
+    sender
+        .get_or_insert_with(|| broadcast::channel(BUS_CAPACITY).0)
+        .clone()
+}
+
+pub fn subscribe() -> broadcast::Receiver<BusEvent> {
+    sender().subscribe()
+}
+
+// fire-and-forget: an event with no subscribed consumers is dropped, which is
+// expected in one-shot CLI invocations that never spawn the consumer tasks
+pub fn publish(event: BusEvent) {
+    match sender().send(event) {
+        Ok(consumers) => debug!("Published bus event to {} consumers", consumers),
+        Err(broadcast::error::SendError(event)) => {
+            debug!("Dropped bus event with no consumers: {:?}", event)
+        }
+    }
+}
+
+// NATS subject for a bus event, mirroring the subjects producers used to
+// publish on directly
+pub fn nats_subject(hostname: &str, event: &BusEvent) -> String {
+    match event {
+        BusEvent::PrintStateChanged(_) => format!("pi.{hostname}.event.print.state"),
+        BusEvent::PrintFailureDetected { .. } => format!("pi.{hostname}.event.print.failure"),
+        BusEvent::EvidenceBundleReady(_) => format!("pi.{hostname}.event.print.evidence"),
+        BusEvent::CameraElementError(_) => format!("pi.{hostname}.event.cam_error"),
+        BusEvent::SystemHeartbeat(_) => format!("pi.{hostname}.event.system.heartbeat"),
+        BusEvent::SwapAlert(_) => format!("pi.{hostname}.event.system.swap_alert"),
+        BusEvent::EnclosureAlert(_) => format!("pi.{hostname}.event.system.enclosure_alert"),
+        BusEvent::PowerStateChanged(_) => format!("pi.{hostname}.event.system.power"),
+        BusEvent::BootSelfTest(_) => format!("pi.{hostname}.status.boot"),
+    }
+}
+
+// wire payload for events forwarded to NATS; None for events that stay
+// internal (failure episodes are surfaced through hooks, not published)
+pub fn nats_payload(event: &BusEvent) -> Result<Option<Vec<u8>>> {
+    let payload = match event {
+        BusEvent::PrintStateChanged(event) => Some(serde_json::to_vec(event)?),
+        BusEvent::PrintFailureDetected { .. } => None,
+        BusEvent::EvidenceBundleReady(bundle) => Some(serde_json::to_vec(bundle)?),
+        BusEvent::CameraElementError(event) => Some(serde_json::to_vec(event)?),
+        BusEvent::SystemHeartbeat(event) => Some(serde_json::to_vec(event)?),
+        BusEvent::SwapAlert(event) => Some(serde_json::to_vec(event)?),
+        BusEvent::EnclosureAlert(event) => Some(serde_json::to_vec(event)?),
+        BusEvent::PowerStateChanged(event) => Some(serde_json::to_vec(event)?),
+        BusEvent::BootSelfTest(report) => Some(serde_json::to_vec(report)?),
+    };
+    Ok(payload)
+}
+
+// hook event and payload for a bus event; None when no hook fires
+pub fn hook_payload(event: &BusEvent) -> Option<(HookEvent, serde_json::Value)> {
+    match event {
+        BusEvent::PrintStateChanged(event) if event.state == PrintState::Printing => Some((
+            HookEvent::PrintStarted,
+            serde_json::json!({ "state": event.state, "rt": event.rt }),
+        )),
+        BusEvent::PrintFailureDetected {
+            rt,
+            spaghetti_mean,
+            adhesion_mean,
+        } => Some((
+            HookEvent::PrintFailedDetected,
+            serde_json::json!({
+                "rt": rt,
+                "spaghetti_mean": spaghetti_mean,
+                "adhesion_mean": adhesion_mean,
+            }),
+        )),
+        // references to the captured evidence, so a notification can link the
+        // clip and snapshots for the alert it follows up on
+        BusEvent::EvidenceBundleReady(bundle) => Some((
+            HookEvent::EvidenceBundleReady,
+            serde_json::json!({
+                "bundle_id": bundle.id,
+                "dir": bundle.dir,
+                "rt": bundle.event_rt,
+                "video_recording_id": bundle.video_recording_id,
+                "snapshot_files": bundle.snapshot_file_list(),
+                "clip_files": bundle.clip_file_list(),
+            }),
+        )),
+        BusEvent::EnclosureAlert(event) => {
+            let hook_event = match event.smoke {
+                true => HookEvent::SmokeDetected,
+                false => HookEvent::EnclosureAlert,
+            };
+            Some((
+                hook_event,
+                serde_json::json!({
+                    "readings": event.readings,
+                    "breaches": event.breaches,
+                }),
+            ))
+        }
+        BusEvent::CameraElementError(event) => Some((
+            HookEvent::CameraError,
+            serde_json::json!({
+                "pipeline": event.pipeline,
+                "element": event.element,
+                "message": event.message,
+                "remediation": event.remediation,
+            }),
+        )),
+        // recovery events inform, they don't re-fire power hooks
+        BusEvent::PowerStateChanged(event) if !event.recovered_from_power_loss => {
+            let hook_event = match event.on_battery {
+                true => HookEvent::PowerLoss,
+                false => HookEvent::PowerRestored,
+            };
+            Some((
+                hook_event,
+                serde_json::json!({
+                    "on_battery": event.on_battery,
+                    "charge_percent": event.charge_percent,
+                }),
+            ))
+        }
+        _ => None,
+    }
+}
+
+// next event for a consumer task, skipping over lag instead of aborting
+async fn next_event(
+    receiver: &mut broadcast::Receiver<BusEvent>,
+    consumer: &str,
+) -> Option<BusEvent> {
+    loop {
+        match receiver.recv().await {
+            Ok(event) => return Some(event),
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                warn!(
+                    "{} lagged behind the event bus, skipped {} events",
+                    consumer, skipped
+                );
+            }
+            Err(broadcast::error::RecvError::Closed) => return None,
+        }
+    }
+}
+
+async fn try_publish_nats_event(subject: &str, payload: &[u8]) -> Result<()> {
+    let settings = PrintNannySettings::new().await?;
+    let nats_client = try_init_nats_client(
+        &settings.video_stream.detection.nats_server_uri,
+        &None,
+        false,
+    )
+    .await?;
+    // sign the payload so the cloud can verify it originated from this device
+    let headers =
+        printnanny_nats_client::identity::try_sign_headers(&settings.paths, None, payload);
+    match headers {
+        Some(headers) => {
+            nats_client
+                .publish_with_headers(subject.to_string(), headers, payload.to_vec().into())
+                .await?
+        }
+        None => {
+            nats_client
+                .publish(subject.to_string(), payload.to_vec().into())
+                .await?
+        }
+    };
+    Ok(())
+}
+
+async fn publish_nats_event(hostname: &str, event: &BusEvent) -> Result<()> {
+    let payload = match nats_payload(event)? {
+        Some(payload) => payload,
+        None => return Ok(()),
+    };
+    let subject = nats_subject(hostname, event);
+    match try_publish_nats_event(&subject, &payload).await {
+        Ok(_) => {
+            info!("Published {}", subject);
+        }
+        Err(e) => {
+            // hold the event for the subscriber's reconnect replay instead of
+            // dropping it while the connection is down
+            warn!(
+                "Failed to publish {} error={}, buffered for replay after reconnect",
+                subject, e
+            );
+            printnanny_nats_client::subscriber::buffer_offline_publish(subject, payload);
+        }
+    }
+    Ok(())
+}
+
+// forward bus events to their NATS subjects, replacing the per-producer
+// nats_client.publish calls
+pub async fn run_nats_publisher() {
+    let hostname = sys_info::hostname().unwrap_or_else(|_| "localhost".to_string());
+    let mut receiver = subscribe();
+    while let Some(event) = next_event(&mut receiver, "NATS publisher").await {
+        if let Err(e) = publish_nats_event(&hostname, &event).await {
+            error!("Failed to publish bus event to NATS error={}", e);
+        }
+    }
+}
+
+// fallback recording trigger for setups without an OctoPrint/Moonraker
+// integration publishing job events
+pub async fn run_recording_trigger() {
+    let mut receiver = subscribe();
+    while let Some(event) = next_event(&mut receiver, "Recording trigger").await {
+        let state = match event {
+            BusEvent::PrintStateChanged(event) => event.state,
+            _ => continue,
+        };
+        let auto_start = match PrintNannySettings::new().await {
+            Ok(settings) => settings.video_stream.recording.auto_start,
+            Err(e) => {
+                error!("Failed to load PrintNannySettings error={}", e);
+                continue;
+            }
+        };
+        if !auto_start {
+            continue;
+        }
+        let result = match state {
+            PrintState::Printing => NatsRequest::handle_camera_recording_start().await,
+            PrintState::Idle => NatsRequest::handle_camera_recording_stop().await,
+        };
+        if let Err(e) = result {
+            error!(
+                "Failed to toggle recording for print state {:?} error={}",
+                state, e
+            );
+        }
+    }
+}
+
+// run user-configured hooks for bus events that map to a hook event
+pub async fn run_hook_runner() {
+    let mut receiver = subscribe();
+    while let Some(event) = next_event(&mut receiver, "Hook runner").await {
+        let (hook_event, payload) = match hook_payload(&event) {
+            Some(hook) => hook,
+            None => continue,
+        };
+        match PrintNannySettings::new().await {
+            Ok(settings) => {
+                printnanny_services::hooks::run_hooks(&settings, hook_event, &payload).await;
+            }
+            Err(e) => {
+                error!("Failed to load PrintNannySettings, skipping hooks: {}", e);
+            }
+        }
+    }
+}
+
+// capture an evidence bundle (pre/post-roll clip + snapshots) for each
+// failure episode, then announce it on the bus so the NATS publisher and
+// hook runner can reference it in notifications
+pub async fn run_evidence_collector() {
+    let mut receiver = subscribe();
+    while let Some(event) = next_event(&mut receiver, "Evidence collector").await {
+        let (rt, spaghetti_mean, adhesion_mean) = match event {
+            BusEvent::PrintFailureDetected {
+                rt,
+                spaghetti_mean,
+                adhesion_mean,
+            } => (rt, spaghetti_mean, adhesion_mean),
+            _ => continue,
+        };
+        let settings = match PrintNannySettings::new().await {
+            Ok(settings) => settings,
+            Err(e) => {
+                error!("Failed to load PrintNannySettings error={}", e);
+                continue;
+            }
+        };
+        match printnanny_services::evidence::capture_evidence_bundle(
+            &settings,
+            rt,
+            spaghetti_mean,
+            adhesion_mean,
+        )
+        .await
+        {
+            Ok(bundle) => publish(BusEvent::EvidenceBundleReady(bundle)),
+            Err(e) => error!("Failed to capture evidence bundle error={}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use printnanny_services::metadata::EventMetadata;
+
+    fn print_state_changed(state: PrintState) -> BusEvent {
+        BusEvent::PrintStateChanged(PrintStateChanged {
+            metadata: EventMetadata::new(),
+            state,
+            rt: Some(1000),
+        })
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_bus_roundtrip() {
+        let mut receiver = subscribe();
+        publish(print_state_changed(PrintState::Printing));
+        match receiver.recv().await.unwrap() {
+            BusEvent::PrintStateChanged(event) => {
+                assert_eq!(event.state, PrintState::Printing);
+                assert_eq!(event.rt, Some(1000));
+            }
+            other => panic!("Expected PrintStateChanged, got {:?}", other),
+        }
+    }
+
+    #[test_log::test]
+    fn test_hook_payload_mapping() {
+        let (hook_event, _) = hook_payload(&print_state_changed(PrintState::Printing)).unwrap();
+        assert_eq!(hook_event, HookEvent::PrintStarted);
+        assert!(hook_payload(&print_state_changed(PrintState::Idle)).is_none());
+
+        let (hook_event, payload) = hook_payload(&BusEvent::PrintFailureDetected {
+            rt: Some(2000),
+            spaghetti_mean: Some(0.8),
+            adhesion_mean: None,
+        })
+        .unwrap();
+        assert_eq!(hook_event, HookEvent::PrintFailedDetected);
+        assert_eq!(payload["rt"], 2000);
+    }
+
+    #[test_log::test]
+    fn test_evidence_bundle_subject_and_hook() {
+        let bundle = printnanny_edge_db::evidence::EvidenceBundle {
+            id: "bundle-1".to_string(),
+            created_dt: chrono::Utc::now(),
+            dir: "/home/printnanny/.local/share/printnanny/evidence/bundle-1".to_string(),
+            event_rt: Some(2000),
+            spaghetti_mean: Some(0.8),
+            adhesion_mean: None,
+            video_recording_id: Some("recording-1".to_string()),
+            snapshot_files: r#"["/tmp/snapshot-0.jpg"]"#.to_string(),
+            clip_files: r#"["/tmp/part-0.mp4"]"#.to_string(),
+        };
+        let event = BusEvent::EvidenceBundleReady(bundle);
+        assert_eq!(
+            nats_subject("pi123", &event),
+            "pi.pi123.event.print.evidence"
+        );
+        assert!(nats_payload(&event).unwrap().is_some());
+        let (hook_event, payload) = hook_payload(&event).unwrap();
+        assert_eq!(hook_event, HookEvent::EvidenceBundleReady);
+        assert_eq!(payload["bundle_id"], "bundle-1");
+        assert_eq!(payload["snapshot_files"][0], "/tmp/snapshot-0.jpg");
+        assert_eq!(payload["clip_files"][0], "/tmp/part-0.mp4");
+    }
+
+    #[test_log::test]
+    fn test_nats_subject_matches_legacy_subjects() {
+        let event = print_state_changed(PrintState::Idle);
+        assert_eq!(nats_subject("pi123", &event), "pi.pi123.event.print.state");
+        // failure episodes fire hooks but are not forwarded to NATS
+        assert!(nats_payload(&BusEvent::PrintFailureDetected {
+            rt: None,
+            spaghetti_mean: None,
+            adhesion_mean: None,
+        })
+        .unwrap()
+        .is_none());
+    }
+}
@@ -0,0 +1,125 @@
+// Surface element errors from the gstd bus as structured cam_error events:
+// the monitor polls every managed pipeline, attributes each error to the
+// failing element and attaches a suggested remediation, then publishes the
+// event on the internal bus so the NATS publisher forwards it on
+// pi.{pi_id}.event.cam_error and the hook runner fires camera_error hooks —
+// instead of the failure sitting silently in gstd logs.
+use std::time::Duration;
+
+use log::debug;
+use serde::{Deserialize, Serialize};
+
+use printnanny_gst_pipelines::factory::{PrintNannyPipelineFactory, MANAGED_PIPELINES};
+use printnanny_gst_pipelines::handle::PipelineElementError;
+use printnanny_services::metadata::EventMetadata;
+
+// gstd buffers bus messages, so a relaxed poll interval loses nothing
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CameraErrorEvent {
+    pub metadata: EventMetadata,
+    pub pipeline: String,
+    // gst element the error originated from
+    pub element: String,
+    pub message: String,
+    pub debug: String,
+    // human-readable next step matching the error class
+    pub remediation: String,
+}
+
+// map the common element error classes to a concrete next step; the raw
+// message and debug text stay in the event for everything else
+pub fn remediation(message: &str, debug: &str) -> String {
+    let haystack = format!("{} {}", message, debug).to_lowercase();
+    if haystack.contains("busy") {
+        return "Another process is holding the camera or encoder device. Stop it (or re-run `printnanny cam start --takeover`) and restart the pipelines.".to_string();
+    }
+    if haystack.contains("not-negotiated") || haystack.contains("not negotiated") {
+        return "The camera does not support the configured resolution/format. Re-run camera autodetect or lower the configured resolution in PrintNannySettings.video_stream.camera.".to_string();
+    }
+    if haystack.contains("no such device")
+        || haystack.contains("cannot identify device")
+        || haystack.contains("device was removed")
+    {
+        return "The camera device disappeared. Check the cable/connector and power, then restart the pipelines.".to_string();
+    }
+    if haystack.contains("v4l2h264enc") || haystack.contains("encoder") {
+        return "The hardware H.264 encoder failed. Stop other programs using the encoder; if the error persists, reboot the device.".to_string();
+    }
+    "Check `journalctl -u printnanny-gst` for details and restart the pipelines with `printnanny cam start`.".to_string()
+}
+
+fn to_event(pipeline: &str, error: &PipelineElementError) -> CameraErrorEvent {
+    CameraErrorEvent {
+        metadata: EventMetadata::new(),
+        pipeline: pipeline.to_string(),
+        element: error.source.clone(),
+        message: error.message.clone(),
+        debug: error.debug.clone(),
+        remediation: remediation(&error.message, &error.debug),
+    }
+}
+
+// poll the gstd bus of every managed pipeline and publish each element error
+// as a structured cam_error event
+pub async fn run_camera_error_monitor() {
+    let factory = PrintNannyPipelineFactory::default();
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    loop {
+        interval.tick().await;
+        for pipeline_name in MANAGED_PIPELINES {
+            match factory.pipeline(pipeline_name).poll_element_errors().await {
+                Ok(errors) => {
+                    for error in &errors {
+                        crate::bus::publish(crate::bus::BusEvent::CameraElementError(to_event(
+                            pipeline_name,
+                            error,
+                        )));
+                    }
+                }
+                // optional legs that are not instantiated 404 here
+                Err(e) => debug!(
+                    "Skipping element error poll for pipeline={} error={}",
+                    pipeline_name, e
+                ),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_remediation_classes() {
+        assert!(remediation("Device '/dev/video0' is busy", "").contains("--takeover"));
+        assert!(remediation(
+            "Internal data stream error.",
+            "streaming stopped, reason not-negotiated (-4)"
+        )
+        .contains("resolution"));
+        assert!(remediation("Cannot identify device '/dev/video0'.", "").contains("cable"));
+        assert!(remediation(
+            "GStreamer encountered a general stream error.",
+            "v4l2h264enc0: poll error"
+        )
+        .contains("encoder"));
+        assert!(remediation("something unexpected", "").contains("printnanny cam start"));
+    }
+
+    #[test_log::test]
+    fn test_to_event_attributes_element() {
+        let error = PipelineElementError {
+            source: "v4l2src0".to_string(),
+            message: "Device '/dev/video0' is busy".to_string(),
+            debug: "v4l2_calls.c".to_string(),
+            timestamp: "0:00:01.0".to_string(),
+        };
+        let event = to_event("camera", &error);
+        assert_eq!(event.pipeline, "camera");
+        assert_eq!(event.element, "v4l2src0");
+        assert!(event.remediation.contains("--takeover"));
+    }
+}
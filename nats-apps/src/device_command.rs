@@ -0,0 +1,172 @@
+// Delayed execution for device commands (reboot, swupdate, unit restart): the
+// cloud can attach execute_at / delay_seconds to a command so "reboot tonight
+// at 3am" survives worker restarts. Pending commands are persisted in
+// printnanny-edge-db and this executor polls for due rows.
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+
+use printnanny_dbus::zbus;
+use printnanny_dbus::zbus_systemd;
+use printnanny_edge_db::scheduled_command::ScheduledCommand;
+use printnanny_settings::printnanny::PrintNannySettings;
+
+const COMMAND_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+// command payload persisted as JSON in the scheduled_commands table
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DeviceCommand {
+    Reboot,
+    Swupdate { swu_url: String, version: String },
+    RestartUnit { unit_name: String },
+}
+
+// resolve the requested execution time: execute_at wins over delay_seconds;
+// neither set means "run now"
+pub fn resolve_execute_at(
+    now: DateTime<Utc>,
+    execute_at: &Option<DateTime<Utc>>,
+    delay_seconds: &Option<u64>,
+) -> DateTime<Utc> {
+    match (execute_at, delay_seconds) {
+        (Some(execute_at), _) => *execute_at,
+        (None, Some(delay)) => now + chrono::Duration::seconds(*delay as i64),
+        (None, None) => now,
+    }
+}
+
+pub async fn execute(command: &DeviceCommand) -> Result<String> {
+    match command {
+        DeviceCommand::Reboot => {
+            let connection = zbus::Connection::system().await?;
+            let proxy = zbus_systemd::systemd1::ManagerProxy::new(&connection).await?;
+            // same transition systemctl reboot queues
+            let job = proxy
+                .start_unit("reboot.target".into(), "replace-irreversibly".into())
+                .await?;
+            Ok(format!("reboot requested, job: {}", job))
+        }
+        DeviceCommand::Swupdate { swu_url, version } => {
+            let swupdate =
+                printnanny_services::swupdate::Swupdate::new(swu_url.clone(), version.clone());
+            let output = swupdate.run().await?;
+            Ok(format!(
+                "swupdate version={} exited with code {:?}",
+                version,
+                output.status.code()
+            ))
+        }
+        DeviceCommand::RestartUnit { unit_name } => {
+            let connection = zbus::Connection::system().await?;
+            let proxy = zbus_systemd::systemd1::ManagerProxy::new(&connection).await?;
+            let job = proxy
+                .restart_unit(unit_name.clone(), "replace".into())
+                .await?;
+            Ok(format!("restarted {}, job: {}", unit_name, job))
+        }
+    }
+}
+
+// persist a command for later execution, returning the row
+pub fn schedule(
+    settings: &PrintNannySettings,
+    command: &DeviceCommand,
+    execute_at: &DateTime<Utc>,
+) -> Result<ScheduledCommand> {
+    let sqlite_connection = settings.paths.db().display().to_string();
+    let command_json = serde_json::to_string(command)?;
+    let row = ScheduledCommand::create(&sqlite_connection, execute_at, &command_json)?;
+    Ok(row)
+}
+
+// run every due command and mark the row executed with the outcome; a failed
+// command is not retried, the result records the error for the operator
+async fn execute_due_commands() -> Result<()> {
+    let settings = PrintNannySettings::new().await?;
+    let sqlite_connection = settings.paths.db().display().to_string();
+    for row in ScheduledCommand::get_due(&sqlite_connection)? {
+        let command = match serde_json::from_str::<DeviceCommand>(&row.command) {
+            Ok(command) => command,
+            Err(e) => {
+                error!("Failed to parse ScheduledCommand id={} error={}", row.id, e);
+                ScheduledCommand::mark_executed(
+                    &sqlite_connection,
+                    &row.id,
+                    &format!("unparseable command: {}", e),
+                )?;
+                continue;
+            }
+        };
+        info!(
+            "Executing ScheduledCommand id={} command={:?} scheduled for {}",
+            row.id, command, row.execute_at_dt
+        );
+        // preflight passed when the swupdate command was accepted, but
+        // conditions change between acceptance and a delayed execution
+        if let DeviceCommand::Swupdate { swu_url, .. } = &command {
+            let report = printnanny_services::upgrade_advisor::preflight(&settings, swu_url).await;
+            if !report.passed {
+                let detail = format!("preflight failed: {}", report.failed_summary());
+                error!("ScheduledCommand id={} {}", row.id, detail);
+                ScheduledCommand::mark_executed(&sqlite_connection, &row.id, &detail)?;
+                continue;
+            }
+        }
+        // mark the row first so a command that reboots the device (or kills
+        // this worker) is not run again on the next boot
+        ScheduledCommand::mark_executed(&sqlite_connection, &row.id, "started")?;
+        let result = match execute(&command).await {
+            Ok(detail) => detail,
+            Err(e) => {
+                error!("ScheduledCommand id={} failed error={}", row.id, e);
+                format!("failed: {}", e)
+            }
+        };
+        ScheduledCommand::mark_executed(&sqlite_connection, &row.id, &result)?;
+    }
+    Ok(())
+}
+
+pub async fn run_command_executor() {
+    let mut interval = tokio::time::interval(COMMAND_POLL_INTERVAL);
+    loop {
+        interval.tick().await;
+        if let Err(e) = execute_due_commands().await {
+            error!("Failed to execute scheduled commands error={}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_resolve_execute_at_precedence() {
+        let now = Utc::now();
+        let at = now + chrono::Duration::hours(6);
+        assert_eq!(resolve_execute_at(now, &Some(at), &Some(60)), at);
+        assert_eq!(
+            resolve_execute_at(now, &None, &Some(60)),
+            now + chrono::Duration::seconds(60)
+        );
+        assert_eq!(resolve_execute_at(now, &None, &None), now);
+    }
+
+    #[test_log::test]
+    fn test_device_command_json_roundtrip() {
+        let command = DeviceCommand::Swupdate {
+            swu_url: "https://example.com/printnanny.swu".to_string(),
+            version: "1.2.3".to_string(),
+        };
+        let json = serde_json::to_string(&command).unwrap();
+        assert_eq!(
+            serde_json::from_str::<DeviceCommand>(&json).unwrap(),
+            command
+        );
+    }
+}
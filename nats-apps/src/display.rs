@@ -0,0 +1,144 @@
+// Drive the status display (GPIO LEDs or SSD1306/e-ink HAT) from the internal
+// event bus: the printing LED follows the print state classifier, the alert
+// LED latches on a detected failure episode until the print state changes,
+// and the connectivity LED stays lit while system heartbeats keep arriving.
+use std::time::{Duration, Instant};
+
+use log::error;
+
+use printnanny_services::display::DisplayStatus;
+use printnanny_services::print_state::PrintState;
+use printnanny_settings::printnanny::PrintNannySettings;
+
+use crate::bus::{self, BusEvent};
+
+// heartbeats are published every 60s; two missed beats mean the worker (or
+// the system under it) is unhealthy
+const HEARTBEAT_STALE_AFTER: Duration = Duration::from_secs(150);
+// re-evaluate heartbeat staleness between bus events
+const REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+// fold a bus event into the display status; returns the last heartbeat
+// instant to carry forward
+fn apply_event(status: &mut DisplayStatus, event: &BusEvent, last_heartbeat: Instant) -> Instant {
+    match event {
+        BusEvent::PrintStateChanged(event) => {
+            status.printing = event.state == PrintState::Printing;
+            // a state transition ends the latched failure episode
+            status.alert = false;
+        }
+        BusEvent::PrintFailureDetected { .. } => {
+            status.alert = true;
+        }
+        BusEvent::SystemHeartbeat(_) => return Instant::now(),
+        // smoke lights the alert LED immediately; threshold breaches don't
+        BusEvent::EnclosureAlert(event) if event.smoke => {
+            status.alert = true;
+        }
+        BusEvent::SwapAlert(_)
+        | BusEvent::EnclosureAlert(_)
+        | BusEvent::PowerStateChanged(_)
+        | BusEvent::BootSelfTest(_)
+        | BusEvent::EvidenceBundleReady(_)
+        | BusEvent::CameraElementError(_) => (),
+    };
+    last_heartbeat
+}
+
+async fn render(status: &DisplayStatus) {
+    let settings = match PrintNannySettings::new().await {
+        Ok(settings) => settings.display,
+        Err(e) => {
+            error!("Failed to load PrintNannySettings, skipping display: {}", e);
+            return;
+        }
+    };
+    if !settings.enabled {
+        return;
+    }
+    if let Err(e) = printnanny_services::display::render(&settings, status).await {
+        error!("Failed to render display status error={}", e);
+    }
+}
+
+pub async fn run_display_driver() {
+    let mut receiver = bus::subscribe();
+    let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+    let mut status = DisplayStatus::default();
+    // the worker just started, so count boot as a heartbeat
+    let mut last_heartbeat = Instant::now();
+    loop {
+        tokio::select! {
+            result = receiver.recv() => {
+                match result {
+                    Ok(event) => {
+                        last_heartbeat = apply_event(&mut status, &event, last_heartbeat);
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => return,
+                }
+            }
+            _ = interval.tick() => {}
+        };
+        let connectivity = last_heartbeat.elapsed() < HEARTBEAT_STALE_AFTER;
+        let next = DisplayStatus {
+            connectivity,
+            ..status
+        };
+        // only touch gpio / re-render the panel when something changed
+        if next != status {
+            status = next;
+            render(&status).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use printnanny_services::metadata::EventMetadata;
+
+    #[test_log::test]
+    fn test_apply_event_latches_alert_until_state_change() {
+        let mut status = DisplayStatus::default();
+        let last_heartbeat = Instant::now();
+
+        apply_event(
+            &mut status,
+            &BusEvent::PrintFailureDetected {
+                rt: Some(1000),
+                spaghetti_mean: Some(0.8),
+                adhesion_mean: None,
+            },
+            last_heartbeat,
+        );
+        assert!(status.alert);
+
+        apply_event(
+            &mut status,
+            &BusEvent::PrintStateChanged(crate::event::PrintStateChanged {
+                metadata: EventMetadata::new(),
+                state: PrintState::Idle,
+                rt: Some(2000),
+            }),
+            last_heartbeat,
+        );
+        assert!(!status.alert);
+        assert!(!status.printing);
+    }
+
+    #[test_log::test]
+    fn test_apply_event_tracks_print_state() {
+        let mut status = DisplayStatus::default();
+        apply_event(
+            &mut status,
+            &BusEvent::PrintStateChanged(crate::event::PrintStateChanged {
+                metadata: EventMetadata::new(),
+                state: PrintState::Printing,
+                rt: Some(1000),
+            }),
+            Instant::now(),
+        );
+        assert!(status.printing);
+    }
+}
@@ -8,12 +8,25 @@ use printnanny_api_client::models;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use std::sync::Mutex;
+
 use printnanny_nats_client::event::NatsEventHandler;
 use printnanny_octoprint_models::{self, Job, JobProgress};
+use printnanny_services::metadata::EventMetadata;
+use printnanny_services::model_evaluation;
+use printnanny_services::print_state::{
+    PrintFailureDetector, PrintState, PrintStateClassifier, WindowedDetectionFrame,
+};
 use printnanny_services::printnanny_api::ApiService;
 use printnanny_settings::printnanny::PrintNannySettings;
 use tokio::io::AsyncWriteExt;
 
+// debounced print/idle classifier state, shared across dataframe events
+static PRINT_STATE_CLASSIFIER: Mutex<Option<PrintStateClassifier>> = Mutex::new(None);
+
+// latched failure detector state, shared across dataframe events
+static PRINT_FAILURE_DETECTOR: Mutex<Option<PrintFailureDetector>> = Mutex::new(None);
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(tag = "subject_pattern")]
 pub enum NatsEvent {
@@ -34,6 +47,25 @@ pub enum NatsEvent {
 
     #[serde(rename = "pi.{pi_id}.octoprint.event.gcode")]
     OctoPrintGcode(printnanny_octoprint_models::OctoPrintGcode),
+
+    // windowed detection dataframes published by the dataframe_agg element
+    #[serde(rename = "pi.{pi_id}.qc.df")]
+    DetectionDataframe(Vec<WindowedDetectionFrame>),
+
+    // candidate model output during A/B evaluation; logged for comparison
+    // but never fed into the print state classifier
+    #[serde(rename = "pi.{pi_id}.qc.df.candidate")]
+    CandidateDetectionDataframe(Vec<WindowedDetectionFrame>),
+}
+
+// print state transition inferred from the video feed, published on
+// pi.{pi_id}.event.print.state
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PrintStateChanged {
+    pub metadata: EventMetadata,
+    pub state: PrintState,
+    // pipeline running time of the last windowed frame (nanoseconds)
+    pub rt: Option<i64>,
 }
 
 impl NatsEvent {
@@ -147,6 +179,73 @@ impl NatsEvent {
         info!("handle_octoprint_gcode event={:?}", event);
         Ok(())
     }
+
+    fn handle_print_state_transition(state: PrintState, rt: Option<i64>) {
+        // the NATS publisher, recording trigger and hook runner consume this
+        // event from the bus (see crate::bus)
+        info!("Print state transition state={:?} rt={:?}", state, rt);
+        crate::bus::publish(crate::bus::BusEvent::PrintStateChanged(PrintStateChanged {
+            metadata: EventMetadata::new(),
+            state,
+            rt,
+        }));
+    }
+
+    async fn handle_detection_dataframe(frames: &[WindowedDetectionFrame]) -> Result<()> {
+        // during A/B evaluation, log the primary model output tagged by model
+        // id so it can be compared against the candidate leg
+        let settings = PrintNannySettings::new().await?;
+        if settings.video_stream.model_evaluation.enabled {
+            let model_id = model_evaluation::model_id(&settings.video_stream.detection.model_file);
+            model_evaluation::record_windowed_frames(&settings, &model_id, frames)?;
+        }
+        let failure_frame = {
+            let mut detector = PRINT_FAILURE_DETECTOR.lock().unwrap();
+            let detector = detector.get_or_insert_with(PrintFailureDetector::default);
+            frames
+                .iter()
+                .filter(|frame| detector.observe(frame))
+                .last()
+                .cloned()
+        };
+        if let Some(frame) = failure_frame {
+            crate::bus::publish(crate::bus::BusEvent::PrintFailureDetected {
+                rt: frame.rt_max,
+                spaghetti_mean: frame.spaghetti_mean,
+                adhesion_mean: frame.adhesion_mean,
+            });
+        }
+
+        let transition = {
+            let mut classifier = PRINT_STATE_CLASSIFIER.lock().unwrap();
+            let classifier = classifier.get_or_insert_with(PrintStateClassifier::default);
+            frames
+                .iter()
+                .filter_map(|frame| classifier.observe(frame).map(|state| (state, frame.rt_max)))
+                .last()
+        };
+        if let Some((state, rt)) = transition {
+            Self::handle_print_state_transition(state, rt);
+        }
+        Ok(())
+    }
+
+    async fn handle_candidate_detection_dataframe(frames: &[WindowedDetectionFrame]) -> Result<()> {
+        let settings = PrintNannySettings::new().await?;
+        if !settings.video_stream.model_evaluation.enabled {
+            // stale pipeline output after evaluation mode was switched off
+            return Ok(());
+        }
+        let model_id = model_evaluation::model_id(
+            &settings.video_stream.model_evaluation.candidate_model_file,
+        );
+        let inserted = model_evaluation::record_windowed_frames(&settings, &model_id, frames)?;
+        info!(
+            "Logged {} candidate detection frames for model_id={}",
+            inserted, model_id
+        );
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -203,6 +302,16 @@ impl NatsEventHandler for NatsEvent {
                 )?))
             }
 
+            "pi.{pi_id}.qc.df" => Ok(NatsEvent::DetectionDataframe(serde_json::from_slice::<
+                Vec<WindowedDetectionFrame>,
+            >(
+                payload.as_ref()
+            )?)),
+
+            "pi.{pi_id}.qc.df.candidate" => Ok(NatsEvent::CandidateDetectionDataframe(
+                serde_json::from_slice::<Vec<WindowedDetectionFrame>>(payload.as_ref())?,
+            )),
+
             _ => Err(anyhow!(
                 " NatsEventHandler not implemented for subject pattern {}",
                 subject_pattern
@@ -230,6 +339,12 @@ impl NatsEventHandler for NatsEvent {
             }
 
             NatsEvent::OctoPrintGcode(event) => Self::handle_octoprint_gcode(event),
+
+            NatsEvent::DetectionDataframe(frames) => Self::handle_detection_dataframe(frames).await,
+
+            NatsEvent::CandidateDetectionDataframe(frames) => {
+                Self::handle_candidate_detection_dataframe(frames).await
+            }
         }
     }
 }
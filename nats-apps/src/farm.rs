@@ -0,0 +1,78 @@
+// Local farm overview for multi-printer setups: fan out a status summary
+// request to every peer in PrintNannySettings.farm.peers over the shared NATS
+// server and aggregate the replies, so one device (or the local dashboard)
+// can show the whole farm without any of it touching the cloud. Unreachable
+// peers are reported in the overview instead of failing the aggregation.
+use std::time::Duration;
+
+use anyhow::Result;
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+
+use printnanny_nats_client::client::try_init_nats_client;
+use printnanny_settings::printnanny::PrintNannySettings;
+
+use crate::heartbeat::HeartbeatEvent;
+use crate::request_reply::{NatsReply, NatsRequest};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FarmDevice {
+    pub hostname: String,
+    pub reachable: bool,
+    // the peer's heartbeat summary; None when the peer did not answer
+    pub summary: Option<HeartbeatEvent>,
+    pub error: Option<String>,
+}
+
+fn unreachable(hostname: &str, error: String) -> FarmDevice {
+    FarmDevice {
+        hostname: hostname.to_string(),
+        reachable: false,
+        summary: None,
+        error: Some(error),
+    }
+}
+
+async fn query_peer(client: &async_nats::Client, peer: &str, timeout: Duration) -> FarmDevice {
+    let subject = format!("pi.{peer}.status.summary");
+    let payload = match serde_json::to_vec(&NatsRequest::StatusSummaryRequest) {
+        Ok(payload) => payload,
+        Err(e) => return unreachable(peer, e.to_string()),
+    };
+    let response =
+        match tokio::time::timeout(timeout, client.request(subject, payload.into())).await {
+            Err(_) => {
+                return unreachable(peer, format!("timed out after {}s", timeout.as_secs()));
+            }
+            Ok(Err(e)) => return unreachable(peer, e.to_string()),
+            Ok(Ok(response)) => response,
+        };
+    match serde_json::from_slice::<NatsReply>(response.payload.as_ref()) {
+        Ok(NatsReply::StatusSummaryReply(reply)) => FarmDevice {
+            hostname: reply.hostname,
+            reachable: true,
+            summary: Some(reply.heartbeat),
+            error: None,
+        },
+        Ok(other) => unreachable(peer, format!("unexpected reply: {:?}", other)),
+        Err(e) => unreachable(peer, e.to_string()),
+    }
+}
+
+// query every configured peer concurrently; each peer resolves to a
+// reachable/unreachable entry within the configured timeout
+pub async fn farm_overview(settings: &PrintNannySettings) -> Result<Vec<FarmDevice>> {
+    let client = try_init_nats_client(
+        &settings.video_stream.detection.nats_server_uri,
+        &None,
+        false,
+    )
+    .await?;
+    let timeout = Duration::from_secs(settings.farm.request_timeout_secs);
+    let queries = settings
+        .farm
+        .peers
+        .iter()
+        .map(|peer| query_peer(&client, peer, timeout));
+    Ok(join_all(queries).await)
+}
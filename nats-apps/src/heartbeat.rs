@@ -0,0 +1,262 @@
+// Periodic heartbeat with per-service CPU/RSS from cgroup accounting, for
+// diagnosing resource complaints (Pi 4 vs Pi 3) without shell access. When a
+// printnanny-owned unit swaps past the configured threshold a separate swap
+// alert is published so subscribers can alert without parsing every heartbeat.
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::Utc;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+
+use printnanny_edge_db::heartbeat_samples::{self, HeartbeatSample, NewHeartbeatSample};
+use printnanny_services::capabilities::{self, CapabilityReport};
+use printnanny_services::enclosure::{self, EnclosureReadings};
+use printnanny_services::latency::{measure_latency, LatencyReport};
+use printnanny_services::lifecycle::{self, DeviceLifecycleState};
+use printnanny_services::metadata::EventMetadata;
+use printnanny_services::resource_monitor::{
+    printnanny_unit_usage, swapping_units, UnitResourceUsage,
+};
+use printnanny_settings::printnanny::PrintNannySettings;
+use printnanny_settings::sys_info;
+
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(60);
+
+// capabilities depend on the installed image and build, not runtime state,
+// so they are probed once per process and reused for every heartbeat
+static CAPABILITIES: Mutex<Option<CapabilityReport>> = Mutex::new(None);
+
+// published on pi.{pi_id}.event.system.heartbeat
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HeartbeatEvent {
+    pub metadata: EventMetadata,
+    // persisted device lifecycle state (see printnanny_services::lifecycle)
+    pub lifecycle: DeviceLifecycleState,
+    pub units: Vec<UnitResourceUsage>,
+    // glass-to-glass latency estimates for the live view legs
+    pub latency: LatencyReport,
+    // None when no external data drive is configured, otherwise whether the
+    // configured mountpoint is currently mounted
+    pub external_data_mounted: Option<bool>,
+    // None when the enclosure sensor suite is disabled
+    pub enclosure: Option<EnclosureReadings>,
+    // which features this device/build supports, so the cloud UI can hide
+    // unsupported controls (see printnanny_services::capabilities)
+    pub capabilities: CapabilityReport,
+}
+
+// published on pi.{pi_id}.event.system.swap_alert
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SwapAlertEvent {
+    pub metadata: EventMetadata,
+    pub swap_alert_threshold_bytes: u64,
+    pub units: Vec<UnitResourceUsage>,
+}
+
+// published on pi.{pi_id}.event.system.enclosure_alert
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EnclosureAlertEvent {
+    pub metadata: EventMetadata,
+    pub readings: EnclosureReadings,
+    // human-readable threshold breaches, empty for a smoke alert
+    pub breaches: Vec<String>,
+    pub smoke: bool,
+}
+
+fn build_features() -> Vec<String> {
+    let mut features = vec![];
+    if cfg!(feature = "systemd") {
+        features.push("systemd".to_string());
+    }
+    features
+}
+
+// cached capability probe shared by the heartbeat and the pi.capabilities
+// request handler
+pub async fn capability_summary(settings: &PrintNannySettings) -> CapabilityReport {
+    if let Some(report) = CAPABILITIES.lock().unwrap().clone() {
+        return report;
+    }
+    let report = capabilities::probe(settings, build_features()).await;
+    *CAPABILITIES.lock().unwrap() = Some(report.clone());
+    report
+}
+
+// on-demand heartbeat for status summary / farm overview requests: the same
+// shape as the periodic heartbeat, without the alert side effects
+pub async fn summary(settings: &PrintNannySettings) -> Result<HeartbeatEvent> {
+    Ok(HeartbeatEvent {
+        metadata: EventMetadata::new(),
+        lifecycle: lifecycle::load(&settings.paths).state,
+        units: printnanny_unit_usage()?,
+        latency: measure_latency(settings),
+        external_data_mounted: settings.paths.external_data_status(),
+        enclosure: match settings.enclosure.enabled {
+            true => Some(enclosure::read_sensors(&settings.enclosure).await),
+            false => None,
+        },
+        capabilities: capability_summary(settings).await,
+    })
+}
+
+pub async fn publish_heartbeat() -> Result<()> {
+    let settings = PrintNannySettings::new().await?;
+    let hostname = sys_info::hostname().unwrap_or_else(|_| "localhost".to_string());
+    let units = printnanny_unit_usage()?;
+
+    let swapping = swapping_units(&units, settings.swap_alert_threshold_bytes);
+    if !swapping.is_empty()
+        && settings
+            .telemetry
+            .allows_subject(".event.system.swap_alert")
+    {
+        let event = SwapAlertEvent {
+            metadata: EventMetadata::new(),
+            swap_alert_threshold_bytes: settings.swap_alert_threshold_bytes,
+            units: swapping,
+        };
+        warn!("Swap alert units={:?}", event.units);
+        crate::bus::publish(crate::bus::BusEvent::SwapAlert(event));
+    }
+
+    let enclosure = match settings.enclosure.enabled {
+        true => {
+            let readings = enclosure::read_sensors(&settings.enclosure).await;
+            if readings.smoke_detected == Some(true) {
+                // cut printer power first, then alert subscribers
+                enclosure::emergency_power_off(&settings.enclosure).await;
+                crate::bus::publish(crate::bus::BusEvent::EnclosureAlert(EnclosureAlertEvent {
+                    metadata: EventMetadata::new(),
+                    readings: readings.clone(),
+                    breaches: vec![],
+                    smoke: true,
+                }));
+            }
+            let breaches = enclosure::threshold_breaches(&settings.enclosure, &readings);
+            if !breaches.is_empty()
+                && settings
+                    .telemetry
+                    .allows_subject(".event.system.enclosure_alert")
+            {
+                warn!("Enclosure alert breaches={:?}", breaches);
+                crate::bus::publish(crate::bus::BusEvent::EnclosureAlert(EnclosureAlertEvent {
+                    metadata: EventMetadata::new(),
+                    readings: readings.clone(),
+                    breaches,
+                    smoke: false,
+                }));
+            }
+            Some(readings)
+        }
+        false => None,
+    };
+
+    let external_data_mounted = settings.paths.external_data_status();
+    if external_data_mounted == Some(false) {
+        warn!(
+            "External data dir {:?} is configured but not mounted, falling back to {}",
+            settings.paths.external_data_dir,
+            settings.paths.state_dir.display()
+        );
+    }
+
+    let latency = measure_latency(&settings);
+    // local trend history is kept regardless of the telemetry setting, which
+    // only gates what leaves the device
+    if let Err(e) = persist_sample(&settings, &units, &enclosure, &latency) {
+        warn!("Failed to persist heartbeat sample error={}", e);
+    }
+
+    let subject = format!("pi.{hostname}.event.system.heartbeat");
+    if !settings.telemetry.allows_subject(&subject) {
+        info!("Suppressed {} (telemetry.usage_metrics=false)", subject);
+        return Ok(());
+    }
+    let event = HeartbeatEvent {
+        metadata: EventMetadata::new(),
+        lifecycle: lifecycle::load(&settings.paths).state,
+        units,
+        latency,
+        external_data_mounted,
+        enclosure,
+        capabilities: capability_summary(&settings).await,
+    };
+    info!("Heartbeat units={}", event.units.len());
+    crate::bus::publish(crate::bus::BusEvent::SystemHeartbeat(event));
+    Ok(())
+}
+
+// record a raw trend sample for the heartbeat_samples ring buffer; cpu_percent
+// is the delta of the cumulative cgroup counter against the previous raw sample
+fn persist_sample(
+    settings: &PrintNannySettings,
+    units: &[UnitResourceUsage],
+    enclosure: &Option<EnclosureReadings>,
+    latency: &LatencyReport,
+) -> Result<()> {
+    let sqlite_connection = settings.paths.db().display().to_string();
+    let now = Utc::now();
+    let cpu_usage_usec: i64 = units.iter().map(|u| u.cpu_usage_usec as i64).sum();
+    let rss_bytes: i64 = units.iter().map(|u| u.memory_rss_bytes as i64).sum();
+    let swap_bytes: i64 = units.iter().map(|u| u.swap_bytes as i64).sum();
+
+    let cpu_percent = match HeartbeatSample::latest_raw(&sqlite_connection)? {
+        Some(prev) => prev.cpu_usage_usec.and_then(|prev_usec| {
+            let elapsed_usec = (now - prev.sample_dt).num_microseconds().unwrap_or(0);
+            let delta_usec = cpu_usage_usec - prev_usec;
+            // a negative delta means a unit restarted and its counter reset;
+            // skip the percentage rather than report garbage
+            match elapsed_usec > 0 && delta_usec >= 0 {
+                true => Some(delta_usec as f64 / elapsed_usec as f64 * 100.0),
+                false => None,
+            }
+        }),
+        None => None,
+    };
+
+    let row = NewHeartbeatSample {
+        sample_dt: now,
+        resolution: heartbeat_samples::RESOLUTION_RAW,
+        cpu_usage_usec: Some(cpu_usage_usec),
+        cpu_percent,
+        rss_bytes,
+        swap_bytes,
+        enclosure_temperature: enclosure.as_ref().and_then(|r| r.temperature_celsius),
+        hls_latency_ms: latency
+            .hls
+            .as_ref()
+            .map(|h| h.glass_to_glass_estimate_ms as i64),
+    };
+    HeartbeatSample::insert(&sqlite_connection, &row)?;
+    Ok(())
+}
+
+pub async fn run_heartbeat() {
+    let mut interval = tokio::time::interval(HEARTBEAT_INTERVAL);
+    // roll up / prune once an hour; the exact cadence is not important as long
+    // as the table cannot grow without bound
+    let mut ticks: u64 = 0;
+    loop {
+        interval.tick().await;
+        if let Err(e) = publish_heartbeat().await {
+            error!("Failed to publish heartbeat error={}", e);
+        }
+        ticks += 1;
+        if ticks % 60 == 0 {
+            match PrintNannySettings::new().await {
+                Ok(settings) => {
+                    let sqlite_connection = settings.paths.db().display().to_string();
+                    let now = Utc::now();
+                    if let Err(e) = HeartbeatSample::rollup(&sqlite_connection, now)
+                        .and_then(|_| HeartbeatSample::prune(&sqlite_connection, now))
+                    {
+                        error!("Failed to roll up heartbeat samples error={}", e);
+                    }
+                }
+                Err(e) => error!("Failed to load settings for heartbeat rollup error={}", e),
+            }
+        }
+    }
+}
@@ -1,2 +1,20 @@
+pub mod adaptive_framerate;
+pub mod bus;
+pub mod cam_error;
+pub mod device_command;
+pub mod display;
 pub mod event;
+pub mod farm;
+pub mod heartbeat;
+pub mod outbox;
+pub mod plugin;
+pub mod power;
 pub mod request_reply;
+pub mod schedule;
+pub mod self_test;
+pub mod shell;
+pub mod tunnel;
+pub mod viewers;
+
+// nats worker version, reported by `printnanny version --all`
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -0,0 +1,113 @@
+// Relay half of the transactional outbox: printnanny-edge-db enqueues rows in
+// the same sqlite transaction as the state change they describe, then this task
+// publishes each unsent row to NATS and marks it sent.
+use std::time::Duration;
+
+use anyhow::Result;
+use log::{error, info};
+
+use serde::{Deserialize, Serialize};
+
+use printnanny_edge_db::outbox::CloudEventOutbox;
+use printnanny_nats_client::client::try_init_nats_client;
+use printnanny_services::metadata::EventMetadata;
+use printnanny_services::time_sync::TimeSyncStatus;
+use printnanny_settings::printnanny::PrintNannySettings;
+use printnanny_settings::sys_info;
+
+const OUTBOX_RELAY_INTERVAL: Duration = Duration::from_secs(30);
+
+// system warning published on pi.{pi_id}.event.system.clock_skew
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ClockSkewEvent {
+    pub metadata: EventMetadata,
+    pub status: Option<TimeSyncStatus>,
+}
+
+// publish all unsent outbox rows, oldest first by monotonic seq
+pub async fn relay_cloud_event_outbox() -> Result<()> {
+    let settings = PrintNannySettings::new().await?;
+    let sqlite_connection = settings.paths.db().display().to_string();
+
+    // probe chrony each cycle; rows enqueued while the clock is suspect are
+    // flagged clock_suspect and a system warning is published to subscribers
+    let time_sync = match printnanny_services::time_sync::check_clock_skew().await {
+        Ok(status) => Some(status),
+        Err(e) => {
+            error!("Failed to probe time sync error={}", e);
+            None
+        }
+    };
+    let clock_suspect = time_sync
+        .as_ref()
+        .map(|status| status.clock_suspect())
+        .unwrap_or(false);
+
+    let rows = CloudEventOutbox::get_unsent(&sqlite_connection)?;
+    if rows.is_empty() && !clock_suspect {
+        return Ok(());
+    }
+    let hostname = sys_info::hostname().unwrap_or_else(|_| "localhost".to_string());
+    let nats_client = try_init_nats_client(
+        &settings.video_stream.detection.nats_server_uri,
+        &None,
+        false,
+    )
+    .await?;
+    if clock_suspect
+        && settings
+            .telemetry
+            .allows_subject(".event.system.clock_skew")
+    {
+        let subject = format!("pi.{hostname}.event.system.clock_skew");
+        let event = ClockSkewEvent {
+            metadata: EventMetadata::new(),
+            status: time_sync,
+        };
+        let payload = serde_json::to_vec(&event)?;
+        nats_client.publish(subject.clone(), payload.into()).await?;
+        info!("Published {} status={:?}", subject, event.status);
+    }
+    let mut relayed_bytes: i64 = 0;
+    for row in rows {
+        let subject = row.subject.replace("{pi_id}", &hostname);
+        // central telemetry opt-out: suppressed rows are marked sent so they
+        // don't queue up forever waiting for the toggle to flip
+        if !settings.telemetry.allows_subject(&subject) {
+            CloudEventOutbox::mark_sent(&sqlite_connection, &row.id)?;
+            info!(
+                "Suppressed CloudEventOutbox id={} subject={} (telemetry opt-out)",
+                row.id, subject
+            );
+            continue;
+        }
+        relayed_bytes += row.payload.len() as i64;
+        nats_client
+            .publish(subject.clone(), row.payload.clone().into_bytes().into())
+            .await?;
+        CloudEventOutbox::mark_sent(&sqlite_connection, &row.id)?;
+        info!("Relayed CloudEventOutbox id={} subject={}", row.id, subject);
+    }
+    if relayed_bytes > 0 {
+        // meter relayed events against the telemetry bandwidth rollup
+        if let Err(e) = printnanny_edge_db::bandwidth::BandwidthUsage::record(
+            &sqlite_connection,
+            printnanny_edge_db::bandwidth::BandwidthSubsystem::Telemetry,
+            relayed_bytes,
+            0,
+        ) {
+            error!("Failed to record telemetry bandwidth usage error={}", e);
+        }
+    }
+    Ok(())
+}
+
+pub async fn run_cloud_event_outbox_relay() {
+    let mut interval = tokio::time::interval(OUTBOX_RELAY_INTERVAL);
+    loop {
+        interval.tick().await;
+        if let Err(e) = relay_cloud_event_outbox().await {
+            error!("Failed to relay cloud event outbox error={}", e);
+        }
+    }
+}
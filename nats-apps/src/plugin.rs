@@ -0,0 +1,290 @@
+// Third-party NATS request handlers loaded from cdylibs, so integrations can
+// register handlers under pi.{pi_id}.plugin.<name>.> without forking this
+// crate. The ABI is a versioned set of C symbols:
+//
+//   uint32_t printnanny_plugin_abi_version(void);
+//   const char* printnanny_plugin_name(void);
+//   // handle one request; returns a malloc'd response buffer and writes its
+//   // length to out_len; NULL signals a handler error
+//   uint8_t* printnanny_plugin_handle(const char* subject,
+//                                     const uint8_t* payload, size_t payload_len,
+//                                     const char* context_json, size_t* out_len);
+//   void printnanny_plugin_free(uint8_t* ptr, size_t len);
+//
+// Plugins only ever see requests under their own subject namespace, and the
+// context_json document only carries data the plugin's configured
+// capabilities grant (see printnanny_settings::plugins::PluginCapability).
+use std::collections::HashMap;
+use std::os::raw::c_char;
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Result};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+
+use printnanny_settings::plugins::{PluginCapability, PluginSettings};
+use printnanny_settings::printnanny::PrintNannySettings;
+use printnanny_settings::sys_info;
+
+// bump when the printnanny_plugin_* symbol contract changes
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+type AbiVersionFn = unsafe extern "C" fn() -> u32;
+type NameFn = unsafe extern "C" fn() -> *const c_char;
+type HandleFn = unsafe extern "C" fn(
+    subject: *const c_char,
+    payload: *const u8,
+    payload_len: usize,
+    context_json: *const c_char,
+    out_len: *mut usize,
+) -> *mut u8;
+type FreeFn = unsafe extern "C" fn(ptr: *mut u8, len: usize);
+
+struct LoadedPlugin {
+    settings: PluginSettings,
+    library: libloading::Library,
+}
+
+// loaded plugin registry, keyed by plugin name
+static PLUGINS: Mutex<Option<HashMap<String, LoadedPlugin>>> = Mutex::new(None);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PluginLoadResult {
+    pub name: String,
+    pub path: String,
+    pub loaded: bool,
+    pub detail: String,
+}
+
+// plugin name from a subject pattern like pi.{pi_id}.plugin.<name>.<method>
+pub fn plugin_name_from_subject(subject_pattern: &str) -> Option<&str> {
+    subject_pattern
+        .strip_prefix("pi.{pi_id}.plugin.")
+        .and_then(|rest| rest.split('.').next())
+        .filter(|name| !name.is_empty())
+}
+
+// context document built from the plugin's granted capabilities
+pub fn build_context(settings: &PrintNannySettings, plugin: &PluginSettings) -> serde_json::Value {
+    let mut context = serde_json::json!({ "abi_version": PLUGIN_ABI_VERSION });
+    if plugin.capabilities.contains(&PluginCapability::DeviceInfo) {
+        context["device_info"] = serde_json::json!({
+            "hostname": sys_info::hostname().unwrap_or_else(|_| "localhost".to_string()),
+        });
+    }
+    if plugin
+        .capabilities
+        .contains(&PluginCapability::SettingsRead)
+    {
+        // video/paths config only; cloud credentials are never exposed
+        context["settings"] = serde_json::json!({
+            "video_stream": &settings.video_stream,
+            "paths": &settings.paths,
+        });
+    }
+    context
+}
+
+fn try_load(plugin: &PluginSettings) -> Result<LoadedPlugin> {
+    let library = unsafe { libloading::Library::new(&plugin.path)? };
+    let abi_version = unsafe {
+        let symbol: libloading::Symbol<AbiVersionFn> =
+            library.get(b"printnanny_plugin_abi_version")?;
+        symbol()
+    };
+    if abi_version != PLUGIN_ABI_VERSION {
+        return Err(anyhow!(
+            "Plugin {} was built against ABI v{}, expected v{}",
+            plugin.name,
+            abi_version,
+            PLUGIN_ABI_VERSION
+        ));
+    }
+    let reported_name = unsafe {
+        let symbol: libloading::Symbol<NameFn> = library.get(b"printnanny_plugin_name")?;
+        std::ffi::CStr::from_ptr(symbol())
+            .to_string_lossy()
+            .to_string()
+    };
+    if reported_name != plugin.name {
+        return Err(anyhow!(
+            "Plugin at {} reports name {:?}, but is registered as {:?}",
+            plugin.path,
+            reported_name,
+            plugin.name
+        ));
+    }
+    // fail fast when the handler symbols are missing instead of at first request
+    unsafe {
+        library.get::<HandleFn>(b"printnanny_plugin_handle")?;
+        library.get::<FreeFn>(b"printnanny_plugin_free")?;
+    }
+    Ok(LoadedPlugin {
+        settings: plugin.clone(),
+        library,
+    })
+}
+
+// load every enabled plugin from settings, replacing the current registry
+pub fn load_plugins(settings: &PrintNannySettings) -> Vec<PluginLoadResult> {
+    let mut results = vec![];
+    let mut registry = HashMap::new();
+    for plugin in settings.plugins.iter().filter(|plugin| plugin.enabled) {
+        match try_load(plugin) {
+            Ok(loaded) => {
+                info!(
+                    "Loaded plugin name={} path={} abi=v{}",
+                    plugin.name, plugin.path, PLUGIN_ABI_VERSION
+                );
+                results.push(PluginLoadResult {
+                    name: plugin.name.clone(),
+                    path: plugin.path.clone(),
+                    loaded: true,
+                    detail: format!("ABI v{}", PLUGIN_ABI_VERSION),
+                });
+                registry.insert(plugin.name.clone(), loaded);
+            }
+            Err(e) => {
+                error!(
+                    "Failed to load plugin name={} path={}: {}",
+                    plugin.name, plugin.path, e
+                );
+                results.push(PluginLoadResult {
+                    name: plugin.name.clone(),
+                    path: plugin.path.clone(),
+                    loaded: false,
+                    detail: e.to_string(),
+                });
+            }
+        }
+    }
+    let mut plugins = PLUGINS.lock().unwrap();
+    *plugins = Some(registry);
+    results
+}
+
+// drop a plugin from the registry, unloading its library
+pub fn unload_plugin(name: &str) -> bool {
+    let mut plugins = PLUGINS.lock().unwrap();
+    match plugins.get_or_insert_with(HashMap::new).remove(name) {
+        Some(_) => {
+            warn!("Unloaded plugin name={}", name);
+            true
+        }
+        None => false,
+    }
+}
+
+pub fn loaded_plugin_names() -> Vec<String> {
+    let mut plugins = PLUGINS.lock().unwrap();
+    let mut names: Vec<String> = plugins
+        .get_or_insert_with(HashMap::new)
+        .keys()
+        .cloned()
+        .collect();
+    names.sort();
+    names
+}
+
+// dispatch one request to the named plugin, returning its response bytes
+pub fn call_plugin(
+    name: &str,
+    subject_pattern: &str,
+    payload: &[u8],
+    context_json: &str,
+) -> Result<Vec<u8>> {
+    let plugins = PLUGINS.lock().unwrap();
+    let plugins = plugins
+        .as_ref()
+        .ok_or_else(|| anyhow!("No plugins loaded"))?;
+    let plugin = plugins
+        .get(name)
+        .ok_or_else(|| anyhow!("Plugin {} is not loaded", name))?;
+
+    let subject = std::ffi::CString::new(subject_pattern)?;
+    let context = std::ffi::CString::new(context_json)?;
+    let mut out_len: usize = 0;
+    unsafe {
+        let handle: libloading::Symbol<HandleFn> =
+            plugin.library.get(b"printnanny_plugin_handle")?;
+        let free: libloading::Symbol<FreeFn> = plugin.library.get(b"printnanny_plugin_free")?;
+        let ptr = handle(
+            subject.as_ptr(),
+            payload.as_ptr(),
+            payload.len(),
+            context.as_ptr(),
+            &mut out_len,
+        );
+        if ptr.is_null() {
+            return Err(anyhow!(
+                "Plugin {} returned an error for subject {}",
+                plugin.settings.name,
+                subject_pattern
+            ));
+        }
+        let response = std::slice::from_raw_parts(ptr, out_len).to_vec();
+        free(ptr, out_len);
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_plugin_name_from_subject() {
+        assert_eq!(
+            plugin_name_from_subject("pi.{pi_id}.plugin.spoolman.query"),
+            Some("spoolman")
+        );
+        assert_eq!(
+            plugin_name_from_subject("pi.{pi_id}.plugin.spoolman"),
+            Some("spoolman")
+        );
+        assert_eq!(plugin_name_from_subject("pi.{pi_id}.plugin."), None);
+        assert_eq!(
+            plugin_name_from_subject("pi.{pi_id}.settings.file.load"),
+            None
+        );
+    }
+
+    #[test_log::test]
+    fn test_build_context_gates_on_capabilities() {
+        let settings = PrintNannySettings::default();
+        let plugin = PluginSettings {
+            enabled: true,
+            name: "spoolman".to_string(),
+            path: "/usr/lib/printnanny/plugins/libspoolman.so".to_string(),
+            capabilities: vec![],
+        };
+        let context = build_context(&settings, &plugin);
+        assert!(context.get("device_info").is_none());
+        assert!(context.get("settings").is_none());
+
+        let plugin = PluginSettings {
+            capabilities: vec![PluginCapability::DeviceInfo],
+            ..plugin
+        };
+        let context = build_context(&settings, &plugin);
+        assert!(context.get("device_info").is_some());
+        assert!(context.get("settings").is_none());
+    }
+
+    #[test_log::test]
+    fn test_missing_plugin_library_reports_load_failure() {
+        let settings = PrintNannySettings {
+            plugins: vec![PluginSettings {
+                enabled: true,
+                name: "ghost".to_string(),
+                path: "/nonexistent/libghost.so".to_string(),
+                capabilities: vec![],
+            }],
+            ..PrintNannySettings::default()
+        };
+        let results = load_plugins(&settings);
+        assert_eq!(results.len(), 1);
+        assert!(!results[0].loaded);
+        assert!(loaded_plugin_names().is_empty());
+    }
+}
@@ -0,0 +1,196 @@
+// UPS / power-loss monitor: polls the configured power source (UPS HAT GPIO
+// line or NUT server), publishes transitions on the bus, optionally pauses
+// the active print, and performs a clean shutdown before battery depletion.
+// A marker left by printnanny_services::power lets the first event after a
+// reboot report "recovered from power loss".
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use log::{error, warn};
+use serde::{Deserialize, Serialize};
+
+use printnanny_services::metadata::EventMetadata;
+use printnanny_services::power::{self, PowerLossRecord, PowerStatus};
+use printnanny_settings::printnanny::PrintNannySettings;
+use printnanny_settings::ups::UpsSettings;
+
+const MONITOR_INTERVAL: Duration = Duration::from_secs(15);
+
+// published on pi.{pi_id}.event.system.power
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PowerStateChangedEvent {
+    pub metadata: EventMetadata,
+    pub on_battery: bool,
+    pub charge_percent: Option<f64>,
+    // set on the first event after a boot that followed a power loss
+    pub recovered_from_power_loss: bool,
+}
+
+// should a clean shutdown start, given the status and time spent on battery?
+fn should_shutdown(
+    settings: &UpsSettings,
+    status: &PowerStatus,
+    on_battery_since: Instant,
+) -> bool {
+    if !status.on_battery {
+        return false;
+    }
+    if let Some(charge) = status.charge_percent {
+        if charge < settings.shutdown_below_charge_percent as f64 {
+            return true;
+        }
+    }
+    settings.shutdown_after_battery_seconds > 0
+        && on_battery_since.elapsed()
+            >= Duration::from_secs(settings.shutdown_after_battery_seconds)
+}
+
+fn publish_power_event(status: &PowerStatus, recovered_from_power_loss: bool) {
+    crate::bus::publish(crate::bus::BusEvent::PowerStateChanged(
+        PowerStateChangedEvent {
+            metadata: EventMetadata::new(),
+            on_battery: status.on_battery,
+            charge_percent: status.charge_percent,
+            recovered_from_power_loss,
+        },
+    ));
+}
+
+pub async fn run_power_monitor() {
+    let mut interval = tokio::time::interval(MONITOR_INTERVAL);
+    // on-battery state carried between cycles; None until the first
+    // successful read so a flaky source doesn't fabricate transitions
+    let mut on_battery: Option<bool> = None;
+    let mut on_battery_since = Instant::now();
+    let mut shutdown_initiated = false;
+    let mut reported_recovery = false;
+    loop {
+        interval.tick().await;
+        let settings = match PrintNannySettings::new().await {
+            Ok(settings) => settings,
+            Err(e) => {
+                error!("Failed to load PrintNannySettings error={}", e);
+                continue;
+            }
+        };
+        if !settings.ups.enabled {
+            continue;
+        }
+        // consume the marker left by a previous boot exactly once
+        if !reported_recovery {
+            reported_recovery = true;
+            if let Some(record) = power::take_power_loss_record(&settings.paths) {
+                warn!(
+                    "Recovered from power loss detected at {} (clean shutdown: {})",
+                    record.detected_dt, record.shutdown_initiated
+                );
+                publish_power_event(
+                    &PowerStatus {
+                        on_battery: false,
+                        charge_percent: record.charge_percent,
+                    },
+                    true,
+                );
+            }
+        }
+        let status = match power::read_status(&settings.ups).await {
+            Some(status) => status,
+            None => continue,
+        };
+        match (on_battery, status.on_battery) {
+            // power lost
+            (Some(false) | None, true) => {
+                warn!(
+                    "Power loss detected, running on battery charge_percent={:?}",
+                    status.charge_percent
+                );
+                on_battery_since = Instant::now();
+                shutdown_initiated = false;
+                power::record_power_loss(
+                    &settings.paths,
+                    &PowerLossRecord {
+                        detected_dt: Utc::now(),
+                        charge_percent: status.charge_percent,
+                        shutdown_initiated: false,
+                    },
+                );
+                publish_power_event(&status, false);
+                if settings.ups.pause_print_on_battery {
+                    if let Err(e) = printnanny_services::octoprint::octoprint_pause_print().await {
+                        error!("Failed to pause print on power loss error={}", e);
+                    }
+                }
+            }
+            // power restored without a shutdown
+            (Some(true), false) => {
+                warn!("Power restored");
+                power::clear_power_loss_record(&settings.paths);
+                publish_power_event(&status, false);
+            }
+            _ => (),
+        };
+        on_battery = Some(status.on_battery);
+        if !shutdown_initiated && should_shutdown(&settings.ups, &status, on_battery_since) {
+            shutdown_initiated = true;
+            // update the marker so the next boot knows the shutdown was clean
+            power::record_power_loss(
+                &settings.paths,
+                &PowerLossRecord {
+                    detected_dt: Utc::now(),
+                    charge_percent: status.charge_percent,
+                    shutdown_initiated: true,
+                },
+            );
+            power::clean_shutdown(&settings.ups).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_should_shutdown_on_low_charge() {
+        let settings = UpsSettings::default();
+        let now = Instant::now();
+        let low = PowerStatus {
+            on_battery: true,
+            charge_percent: Some(10.0),
+        };
+        assert!(should_shutdown(&settings, &low, now));
+
+        let healthy = PowerStatus {
+            on_battery: true,
+            charge_percent: Some(80.0),
+        };
+        assert!(!should_shutdown(&settings, &healthy, now));
+
+        // never shut down while on mains, whatever the charge reads
+        let mains = PowerStatus {
+            on_battery: false,
+            charge_percent: Some(5.0),
+        };
+        assert!(!should_shutdown(&settings, &mains, now));
+    }
+
+    #[test_log::test]
+    fn test_should_shutdown_after_battery_timer() {
+        let settings = UpsSettings::default();
+        let no_telemetry = PowerStatus {
+            on_battery: true,
+            charge_percent: None,
+        };
+        let long_ago =
+            Instant::now() - Duration::from_secs(settings.shutdown_after_battery_seconds + 1);
+        assert!(should_shutdown(&settings, &no_telemetry, long_ago));
+        assert!(!should_shutdown(&settings, &no_telemetry, Instant::now()));
+
+        // a zero timer disables the time-based shutdown entirely
+        let timer_disabled = UpsSettings {
+            shutdown_after_battery_seconds: 0,
+            ..UpsSettings::default()
+        };
+        assert!(!should_shutdown(&timer_disabled, &no_telemetry, long_ago));
+    }
+}
@@ -1,12 +1,14 @@
 use std::fmt::Debug;
-use std::time::SystemTime;
 
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use bytes::Bytes;
 use log::{error, info, warn};
 use printnanny_services::video_recording_sync::sync_all_video_recordings;
-use printnanny_settings::cam::CameraVideoSource;
+use printnanny_settings::cam::{CameraVideoSource, RtpDestination};
+use printnanny_settings::camera_controls::{CameraControlSettings, V4l2Control};
+use printnanny_settings::lighting::LightingSettings;
+use printnanny_settings::octoprint::PipPackage;
 use serde::{Deserialize, Serialize};
 use tokio::fs;
 
@@ -28,17 +30,25 @@ use printnanny_dbus::zbus;
 use printnanny_dbus::zbus_systemd;
 
 use printnanny_settings::git2;
-use printnanny_settings::printnanny::PrintNannySettings;
+use printnanny_settings::printnanny::{PrintNannySettings, ReplyDetailLevel};
 use printnanny_settings::vcs::VersionControlledSettings;
 
+use printnanny_services::metadata::EventMetadata;
 use printnanny_services::printnanny_api::ApiService;
 
 use printnanny_gst_pipelines::factory::{
-    GstPipelineState, PrintNannyPipelineFactory, H264_RECORDING_PIPELINE,
+    GstPipelineState, PrintNannyPipelineFactory, H264_RECORDING_PIPELINE, PREROLL_RING_SECONDS,
 };
 
 use printnanny_nats_client::request_reply::NatsRequestHandler;
 
+use printnanny_services::decommission::DecommissionReport;
+use printnanny_services::upgrade_advisor;
+
+use crate::device_command::{self, DeviceCommand};
+use crate::shell::{self, ShellSession};
+use crate::tunnel::{self, TunnelHttpReply, TunnelHttpRequest, TunnelSession};
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(tag = "subject_pattern")]
 pub enum NatsRequest {
@@ -58,6 +68,66 @@ pub enum NatsRequest {
     #[serde(rename = "pi.{pi_id}.cameras.load")]
     CameraLoadRequest,
 
+    // pi.{pi_id}.command.camera.privacy.enable
+    #[serde(rename = "pi.{pi_id}.command.camera.privacy.enable")]
+    CameraPrivacyEnableRequest,
+
+    // pi.{pi_id}.command.camera.privacy.disable
+    #[serde(rename = "pi.{pi_id}.command.camera.privacy.disable")]
+    CameraPrivacyDisableRequest,
+
+    // pi.{pi_id}.command.camera.overlay.enable
+    #[serde(rename = "pi.{pi_id}.command.camera.overlay.enable")]
+    CameraOverlayEnableRequest,
+
+    // pi.{pi_id}.command.camera.overlay.disable
+    #[serde(rename = "pi.{pi_id}.command.camera.overlay.disable")]
+    CameraOverlayDisableRequest,
+
+    // pi.{pi_id}.command.camera.stream.pause
+    #[serde(rename = "pi.{pi_id}.command.camera.stream.pause")]
+    CameraStreamPauseRequest,
+
+    // pi.{pi_id}.command.camera.stream.resume
+    #[serde(rename = "pi.{pi_id}.command.camera.stream.resume")]
+    CameraStreamResumeRequest,
+
+    // pi.{pi_id}.camera.stream.viewers
+    #[serde(rename = "pi.{pi_id}.camera.stream.viewers")]
+    CameraStreamViewersRequest(CameraStreamViewersRequest),
+
+    // pi.{pi_id}.camera.rtp.destinations.get
+    #[serde(rename = "pi.{pi_id}.camera.rtp.destinations.get")]
+    CameraRtpDestinationsGetRequest,
+
+    // pi.{pi_id}.camera.rtp.destinations.set
+    #[serde(rename = "pi.{pi_id}.camera.rtp.destinations.set")]
+    CameraRtpDestinationsSetRequest(CameraRtpDestinationsRequest),
+
+    // pi.{pi_id}.camera.controls.get
+    #[serde(rename = "pi.{pi_id}.camera.controls.get")]
+    CameraControlsGetRequest,
+
+    // pi.{pi_id}.camera.controls.set
+    #[serde(rename = "pi.{pi_id}.camera.controls.set")]
+    CameraControlsSetRequest(CameraControlSettings),
+
+    // pi.{pi_id}.camera.clip
+    #[serde(rename = "pi.{pi_id}.camera.clip")]
+    CameraClipRequest(CameraClipRequest),
+
+    // pi.{pi_id}.lights.on
+    #[serde(rename = "pi.{pi_id}.lights.on")]
+    LightsOnRequest,
+
+    // pi.{pi_id}.lights.off
+    #[serde(rename = "pi.{pi_id}.lights.off")]
+    LightsOffRequest,
+
+    // pi.{pi_id}.identity.pubkey
+    #[serde(rename = "pi.{pi_id}.identity.pubkey")]
+    DeviceIdentityRequest(DeviceIdentityRequest),
+
     #[serde(rename = "pi.{pi_id}.command.cloud.sync")]
     PrintNannyCloudSyncRequest,
 
@@ -69,6 +139,115 @@ pub enum NatsRequest {
     #[serde(rename = "pi.{pi_id}.device_info.load")]
     DeviceInfoLoadRequest,
 
+    // pi.{pi_id}.capabilities
+    #[serde(rename = "pi.{pi_id}.capabilities")]
+    CapabilitiesRequest,
+
+    // pi.{pi_id}.command.device.decommission
+    #[serde(rename = "pi.{pi_id}.command.device.decommission")]
+    DeviceDecommissionRequest(DeviceDecommissionRequest),
+
+    // pi.{pi_id}.command.device.schedule
+    #[serde(rename = "pi.{pi_id}.command.device.schedule")]
+    DeviceCommandRequest(DeviceCommandRequest),
+
+    // pi.{pi_id}.status.summary
+    #[serde(rename = "pi.{pi_id}.status.summary")]
+    StatusSummaryRequest,
+
+    // pi.{pi_id}.farm.overview
+    #[serde(rename = "pi.{pi_id}.farm.overview")]
+    FarmOverviewRequest,
+
+    // pi.{pi_id}.schedule.list
+    #[serde(rename = "pi.{pi_id}.schedule.list")]
+    ScheduleListRequest,
+
+    // pi.{pi_id}.jobs.*
+    #[serde(rename = "pi.{pi_id}.jobs.get")]
+    JobsGetRequest(JobsGetRequest),
+    #[serde(rename = "pi.{pi_id}.jobs.list")]
+    JobsListRequest(JobsListRequest),
+
+    // pi.{pi_id}.network.wol
+    #[serde(rename = "pi.{pi_id}.network.wol")]
+    WolRequest(WolRequest),
+
+    // pi.{pi_id}.command.nats.rotate_creds
+    #[serde(rename = "pi.{pi_id}.command.nats.rotate_creds")]
+    NatsCredsRotateRequest,
+
+    // pi.{pi_id}.debug.trace.*
+    #[serde(rename = "pi.{pi_id}.debug.trace.enable")]
+    DebugTraceEnableRequest(DebugTraceEnableRequest),
+    #[serde(rename = "pi.{pi_id}.debug.trace.disable")]
+    DebugTraceDisableRequest,
+    #[serde(rename = "pi.{pi_id}.debug.trace.dump")]
+    DebugTraceDumpRequest,
+
+    // pi.{pi_id}.detections.feedback.*
+    #[serde(rename = "pi.{pi_id}.detections.feedback")]
+    DetectionFeedbackRequest(DetectionFeedbackRequest),
+    #[serde(rename = "pi.{pi_id}.detections.feedback.sync")]
+    DetectionFeedbackSyncRequest,
+    #[serde(rename = "pi.{pi_id}.detections.evaluation_report")]
+    ModelEvaluationReportRequest(ModelEvaluationReportRequest),
+
+    // pi.{pi_id}.detection.tune
+    #[serde(rename = "pi.{pi_id}.detection.tune")]
+    DetectionTuneRequest(DetectionTuneRequest),
+
+    // pi.{pi_id}.detection.calibrate
+    #[serde(rename = "pi.{pi_id}.detection.calibrate")]
+    DetectionCalibrateRequest(DetectionCalibrateRequest),
+
+    // pi.{pi_id}.octoprint.plugins.*
+    #[serde(rename = "pi.{pi_id}.octoprint.plugins.list")]
+    OctoPrintPluginsListRequest,
+    #[serde(rename = "pi.{pi_id}.octoprint.plugins.install")]
+    OctoPrintPluginInstallRequest(OctoPrintPluginInstallRequest),
+    #[serde(rename = "pi.{pi_id}.octoprint.plugins.uninstall")]
+    OctoPrintPluginUninstallRequest(OctoPrintPluginUninstallRequest),
+
+    // pi.{pi_id}.octoprint.backup.*
+    #[serde(rename = "pi.{pi_id}.octoprint.backup.create")]
+    OctoPrintBackupCreateRequest(OctoPrintBackupCreateRequest),
+    #[serde(rename = "pi.{pi_id}.octoprint.backup.restore")]
+    OctoPrintBackupRestoreRequest(OctoPrintBackupRestoreRequest),
+
+    // pi.{pi_id}.plugin.<name>.* — third-party plugin handlers; the concrete
+    // subject pattern is carried in the payload since plugin names are dynamic
+    #[serde(rename = "pi.{pi_id}.plugin")]
+    PluginRequest(PluginRequest),
+
+    // pi.{pi_id}.stats.bandwidth
+    #[serde(rename = "pi.{pi_id}.stats.bandwidth")]
+    BandwidthStatsRequest(BandwidthStatsRequest),
+
+    // pi.{pi_id}.stats.storage
+    #[serde(rename = "pi.{pi_id}.stats.storage")]
+    StorageStatsRequest,
+
+    // pi.{pi_id}.stats.trends
+    #[serde(rename = "pi.{pi_id}.stats.trends")]
+    StatsTrendsRequest(StatsTrendsRequest),
+
+    // pi.{pi_id}.tunnel.*
+    #[serde(rename = "pi.{pi_id}.tunnel.session.open")]
+    TunnelSessionOpenRequest(TunnelSessionOpenRequest),
+    #[serde(rename = "pi.{pi_id}.tunnel.session.close")]
+    TunnelSessionCloseRequest(TunnelSessionCloseRequest),
+    #[serde(rename = "pi.{pi_id}.tunnel.http")]
+    TunnelHttpRequest(TunnelHttpRequest),
+
+    // pi.{pi_id}.shell.*
+    #[serde(rename = "pi.{pi_id}.shell.session.open")]
+    ShellSessionOpenRequest(ShellSessionOpenRequest),
+    #[serde(rename = "pi.{pi_id}.shell.session.close")]
+    ShellSessionCloseRequest(ShellSessionCloseRequest),
+    #[serde(rename = "pi.{pi_id}.shell.exec")]
+    ShellExecRequest(ShellExecRequest),
+
     // pi.{pi_id}.settings.*
     #[serde(rename = "pi.{pi_id}.settings.printnanny.cloud.auth")]
     PrintNannyCloudAuthRequest(PrintNannyCloudAuthRequest),
@@ -125,6 +304,42 @@ pub enum NatsReply {
     #[serde(rename = "pi.{pi_id}.cameras.load")]
     CameraLoadReply(CamerasLoadReply),
 
+    // pi.{pi_id}.command.camera.privacy.enable / pi.{pi_id}.command.camera.privacy.disable
+    #[serde(rename = "pi.{pi_id}.command.camera.privacy")]
+    CameraPrivacyReply(CameraPrivacyReply),
+
+    // pi.{pi_id}.command.camera.overlay.enable / pi.{pi_id}.command.camera.overlay.disable
+    #[serde(rename = "pi.{pi_id}.command.camera.overlay")]
+    CameraOverlayReply(CameraOverlayReply),
+
+    // pi.{pi_id}.command.camera.stream.pause / pi.{pi_id}.command.camera.stream.resume
+    #[serde(rename = "pi.{pi_id}.command.camera.stream")]
+    CameraStreamStateReply(CameraStreamStateReply),
+
+    // pi.{pi_id}.camera.stream.viewers
+    #[serde(rename = "pi.{pi_id}.camera.stream.viewers")]
+    CameraStreamViewersReply(CameraStreamViewersReply),
+
+    // pi.{pi_id}.camera.rtp.destinations.get / pi.{pi_id}.camera.rtp.destinations.set
+    #[serde(rename = "pi.{pi_id}.camera.rtp.destinations")]
+    CameraRtpDestinationsReply(CameraRtpDestinationsReply),
+
+    // pi.{pi_id}.camera.controls.get / pi.{pi_id}.camera.controls.set
+    #[serde(rename = "pi.{pi_id}.camera.controls")]
+    CameraControlsReply(CameraControlsReply),
+
+    // pi.{pi_id}.camera.clip
+    #[serde(rename = "pi.{pi_id}.camera.clip")]
+    CameraClipReply(CameraClipReply),
+
+    // pi.{pi_id}.lights.on / pi.{pi_id}.lights.off
+    #[serde(rename = "pi.{pi_id}.lights")]
+    LightsReply(LightsReply),
+
+    // pi.{pi_id}.identity.pubkey
+    #[serde(rename = "pi.{pi_id}.identity.pubkey")]
+    DeviceIdentityReply(DeviceIdentityReply),
+
     #[serde(rename = "pi.{pi_id}.command.cloud.sync")]
     PrintNannyCloudSyncReply(PrintNannyCloudSyncReply),
 
@@ -136,6 +351,107 @@ pub enum NatsReply {
     #[serde(rename = "pi.{pi_id}.device_info.load")]
     DeviceInfoLoadReply(DeviceInfoLoadReply),
 
+    // pi.{pi_id}.capabilities
+    #[serde(rename = "pi.{pi_id}.capabilities")]
+    CapabilitiesReply(CapabilitiesReply),
+
+    // pi.{pi_id}.command.device.decommission
+    #[serde(rename = "pi.{pi_id}.command.device.decommission")]
+    DeviceDecommissionReply(DeviceDecommissionReply),
+
+    // pi.{pi_id}.command.device.schedule
+    #[serde(rename = "pi.{pi_id}.command.device.schedule")]
+    DeviceCommandReply(DeviceCommandReply),
+
+    // pi.{pi_id}.status.summary
+    #[serde(rename = "pi.{pi_id}.status.summary")]
+    StatusSummaryReply(StatusSummaryReply),
+
+    // pi.{pi_id}.farm.overview
+    #[serde(rename = "pi.{pi_id}.farm.overview")]
+    FarmOverviewReply(FarmOverviewReply),
+
+    // pi.{pi_id}.schedule.list
+    #[serde(rename = "pi.{pi_id}.schedule.list")]
+    ScheduleListReply(ScheduleListReply),
+
+    // pi.{pi_id}.jobs.*
+    #[serde(rename = "pi.{pi_id}.jobs.get")]
+    JobsGetReply(JobsGetReply),
+    #[serde(rename = "pi.{pi_id}.jobs.list")]
+    JobsListReply(JobsListReply),
+
+    // pi.{pi_id}.network.wol
+    #[serde(rename = "pi.{pi_id}.network.wol")]
+    WolReply(WolReply),
+
+    // pi.{pi_id}.command.nats.rotate_creds
+    #[serde(rename = "pi.{pi_id}.command.nats.rotate_creds")]
+    NatsCredsRotateReply(NatsCredsRotateReply),
+
+    // pi.{pi_id}.debug.trace.enable / pi.{pi_id}.debug.trace.disable
+    #[serde(rename = "pi.{pi_id}.debug.trace")]
+    DebugTraceStateReply(DebugTraceStateReply),
+    // pi.{pi_id}.debug.trace.dump
+    #[serde(rename = "pi.{pi_id}.debug.trace.dump")]
+    DebugTraceDumpReply(DebugTraceDumpReply),
+
+    // pi.{pi_id}.detections.feedback.*
+    #[serde(rename = "pi.{pi_id}.detections.feedback")]
+    DetectionFeedbackReply(DetectionFeedbackReply),
+    #[serde(rename = "pi.{pi_id}.detections.feedback.sync")]
+    DetectionFeedbackSyncReply(DetectionFeedbackSyncReply),
+    #[serde(rename = "pi.{pi_id}.detections.evaluation_report")]
+    ModelEvaluationReportReply(ModelEvaluationReportReply),
+
+    // pi.{pi_id}.detection.tune
+    #[serde(rename = "pi.{pi_id}.detection.tune")]
+    DetectionTuneReply(DetectionTuneReply),
+
+    // pi.{pi_id}.detection.calibrate
+    #[serde(rename = "pi.{pi_id}.detection.calibrate")]
+    DetectionCalibrateReply(DetectionCalibrateReply),
+
+    // pi.{pi_id}.octoprint.plugins.*
+    #[serde(rename = "pi.{pi_id}.octoprint.plugins.list")]
+    OctoPrintPluginsListReply(OctoPrintPluginsListReply),
+    #[serde(rename = "pi.{pi_id}.octoprint.plugins")]
+    OctoPrintPluginChangedReply(OctoPrintPluginChangedReply),
+
+    // pi.{pi_id}.octoprint.backup.*
+    #[serde(rename = "pi.{pi_id}.octoprint.backup.create")]
+    OctoPrintBackupCreateReply(OctoPrintBackupCreateReply),
+    #[serde(rename = "pi.{pi_id}.octoprint.backup.restore")]
+    OctoPrintBackupRestoreReply(OctoPrintBackupRestoreReply),
+
+    // pi.{pi_id}.plugin.<name>.*
+    #[serde(rename = "pi.{pi_id}.plugin")]
+    PluginReply(PluginReply),
+
+    // pi.{pi_id}.stats.bandwidth
+    #[serde(rename = "pi.{pi_id}.stats.bandwidth")]
+    BandwidthStatsReply(BandwidthStatsReply),
+
+    // pi.{pi_id}.stats.storage
+    #[serde(rename = "pi.{pi_id}.stats.storage")]
+    StorageStatsReply(StorageStatsReply),
+
+    // pi.{pi_id}.stats.trends
+    #[serde(rename = "pi.{pi_id}.stats.trends")]
+    StatsTrendsReply(StatsTrendsReply),
+
+    // pi.{pi_id}.tunnel.*
+    #[serde(rename = "pi.{pi_id}.tunnel.session")]
+    TunnelSessionReply(TunnelSessionReply),
+    #[serde(rename = "pi.{pi_id}.tunnel.http")]
+    TunnelHttpReply(TunnelHttpReply),
+
+    // pi.{pi_id}.shell.*
+    #[serde(rename = "pi.{pi_id}.shell.session")]
+    ShellSessionReply(ShellSessionReply),
+    #[serde(rename = "pi.{pi_id}.shell.exec")]
+    ShellExecReply(ShellExecReply),
+
     // pi.{pi_id}.settings.*
     #[serde(rename = "pi.{pi_id}.settings.printnanny.cloud.auth")]
     PrintNannyCloudAuthReply(PrintNannyCloudAuthReply),
@@ -173,132 +489,1496 @@ pub enum NatsReply {
     SystemdManagerStopUnitReply(SystemdManagerStopUnitReply),
 }
 
-impl NatsRequest {
-    pub async fn handle_camera_recording_load() -> Result<NatsReply> {
+// detection feedback payloads are device-local, so they are not part of the
+// generated printnanny-os-models crate (yet)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DetectionFeedbackRequest {
+    // nanosecond pipeline timestamp of the detection event being labeled
+    pub detection_ts: i64,
+    // false_positive | false_negative | confirmed
+    pub label: String,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DetectionFeedbackReply {
+    pub feedback: printnanny_edge_db::detection_feedback::DetectionFeedback,
+    // labeled samples waiting for upload
+    pub pending: usize,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DetectionFeedbackSyncReply {
+    // samples enqueued for the cloud training pipeline
+    pub synced: usize,
+    // false when telemetry.video_snippets consent is off
+    pub enabled: bool,
+}
+
+// model evaluation payloads are device-local, so they are not part of the
+// generated printnanny-os-models crate (yet)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ModelEvaluationReportRequest {
+    // comparison window in hours, default 24, max 30 days
+    #[serde(default)]
+    pub hours: Option<i64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ModelEvaluationReportReply {
+    // false when video_stream.model_evaluation.enabled is off; the report
+    // still covers whatever rows were logged during earlier evaluation runs
+    pub enabled: bool,
+    pub report: printnanny_services::model_evaluation::ModelEvaluationReport,
+}
+
+// detection tuning is device-local runtime state, so the payloads are not
+// part of the generated printnanny-os-models crate (yet)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DetectionTuneRequest {
+    // detection score threshold as an 0-100 integer, matching
+    // DetectionSettings.nms_threshold
+    pub nms_threshold: i32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DetectionTuneReply {
+    pub nms_threshold: i32,
+    // pipelines retuned in place; legs that don't exist in the current
+    // configuration (e.g. model evaluation disabled) are omitted
+    pub pipelines: Vec<String>,
+}
+
+// calibration payloads are device-local, so they are not part of the
+// generated printnanny-os-models crate (yet)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DetectionCalibrateRequest {
+    // short recording of the user's idle printer
+    pub idle_clip: String,
+    // defaults to the failure sample shipped alongside the model
+    #[serde(default)]
+    pub failure_clip: Option<String>,
+    // two-phase confirmation: false returns the report for review, true
+    // writes the suggested threshold to detection settings and retunes the
+    // running pipelines
+    #[serde(default)]
+    pub apply: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DetectionCalibrateReply {
+    pub report: printnanny_services::calibration::CalibrationReport,
+    // true when a suggested threshold was written to detection settings
+    pub applied: bool,
+}
+
+// plugin management payloads are device-local, so they are not part of the
+// generated printnanny-os-models crate (yet)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OctoPrintPluginInstallRequest {
+    // pip package name, e.g. OctoPrint-Nanny
+    pub package: String,
+    // exact version to pin; latest when omitted
+    #[serde(default)]
+    pub version: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OctoPrintPluginUninstallRequest {
+    pub package: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OctoPrintPluginsListReply {
+    pub plugins: Vec<PipPackage>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OctoPrintPluginChangedReply {
+    pub package: String,
+    // pip output for the install/uninstall run
+    pub detail: String,
+    // plugin list after the change
+    pub plugins: Vec<PipPackage>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OctoPrintBackupCreateRequest {
+    // folders the backup plugin should skip, e.g. ["timelapse", "uploads"]
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OctoPrintBackupCreateReply {
+    // backup zip downloaded into paths.recovery()
+    pub path: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OctoPrintBackupRestoreRequest {
+    // device-local path of a backup zip, e.g. a previous create reply's path
+    pub path: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OctoPrintBackupRestoreReply {
+    // OctoPrint restores asynchronously and restarts itself when done
+    pub started: bool,
+}
+
+// plugin payloads are opaque to this crate; raw request bytes are carried
+// base64-encoded so the worker can hand them to the plugin ABI untouched
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PluginRequest {
+    pub subject_pattern: String,
+    pub payload_b64: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PluginReply {
+    pub plugin: String,
+    // plugin response parsed as JSON, or {"response_b64": ...} for raw bytes
+    pub response: serde_json::Value,
+}
+
+// bandwidth rollups are device-local state, so the payloads are not part of
+// the generated printnanny-os-models crate (yet)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BandwidthStatsRequest {
+    // number of daily rollups to return, default 7, max 90
+    #[serde(default)]
+    pub days: Option<i64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BandwidthStatsReply {
+    pub usage: Vec<printnanny_edge_db::bandwidth::BandwidthUsage>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StorageStatsReply {
+    // per-category usage against the quotas in PrintNannySettings.storage
+    pub categories: Vec<printnanny_services::schedule::StorageCategoryUsage>,
+    pub total_used_bytes: u64,
+}
+
+// heartbeat trend samples are device-local state, so the payloads are not part
+// of the generated printnanny-os-models crate (yet)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StatsTrendsRequest {
+    // lookback window in hours, default 24, max 30 days; windows past 24 hours
+    // return 5-minute rollups instead of raw samples (see
+    // printnanny_edge_db::heartbeat_samples)
+    #[serde(default)]
+    pub hours: Option<i64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StatsTrendsReply {
+    pub samples: Vec<printnanny_edge_db::heartbeat_samples::HeartbeatSample>,
+}
+
+// tunnel sessions are device-local state, so the payloads are not part of the
+// generated printnanny-os-models crate (yet)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TunnelSessionOpenRequest {
+    // session lifetime; defaults to tunnel::DEFAULT_SESSION_TTL_SECONDS and is
+    // clamped to tunnel::MAX_SESSION_TTL_SECONDS
+    #[serde(default)]
+    pub ttl_seconds: Option<u64>,
+    // per-session bandwidth cap; defaults to tunnel::DEFAULT_SESSION_MAX_BYTES
+    // and is clamped to tunnel::MAX_SESSION_MAX_BYTES
+    #[serde(default)]
+    pub max_bytes: Option<u64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TunnelSessionCloseRequest {
+    pub session_id: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TunnelSessionReply {
+    // whether PrintNannySettings.tunnel_enabled allows tunnel sessions
+    pub enabled: bool,
+    // the granted session; None when tunneling is disabled or the session was closed
+    pub session: Option<TunnelSession>,
+}
+
+// shell sessions are device-local state, so the payloads are not part of the
+// generated printnanny-os-models crate (yet)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ShellSessionOpenRequest {
+    // session lifetime; defaults to shell::DEFAULT_SESSION_TTL_SECONDS and is
+    // clamped to shell::MAX_SESSION_TTL_SECONDS
+    #[serde(default)]
+    pub ttl_seconds: Option<u64>,
+    // dashboard identity of whoever the owner approved, recorded in the transcript
+    pub opened_by: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ShellSessionCloseRequest {
+    pub session_id: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ShellSessionReply {
+    // whether PrintNannySettings.remote_shell_enabled allows shell sessions
+    pub enabled: bool,
+    // the granted session; None when the mode is disabled or the session was closed
+    pub session: Option<ShellSession>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ShellExecRequest {
+    pub session_id: String,
+    pub command: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ShellExecReply {
+    pub session_id: String,
+    // None when the command was killed by a signal or never ran
+    pub exit_code: Option<i32>,
+    // base64-encoded stdout / stderr, each capped at shell::MAX_OUTPUT_BYTES
+    pub stdout: String,
+    pub stderr: String,
+    pub truncated: bool,
+    // in-band rejection (unknown/expired session, unwritable transcript)
+    pub rejected: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StatusSummaryReply {
+    pub hostname: String,
+    pub heartbeat: crate::heartbeat::HeartbeatEvent,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CapabilitiesReply {
+    pub capabilities: printnanny_services::capabilities::CapabilityReport,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FarmOverviewReply {
+    pub devices: Vec<crate::farm::FarmDevice>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScheduleListReply {
+    pub tasks: Vec<crate::schedule::ScheduledTaskStatus>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct JobsGetRequest {
+    pub id: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct JobsGetReply {
+    // None when no job row matches the requested id
+    pub job: Option<printnanny_edge_db::jobs::Job>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct JobsListRequest {
+    // only return jobs still running
+    #[serde(default)]
+    pub running_only: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct JobsListReply {
+    pub jobs: Vec<printnanny_edge_db::jobs::Job>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DebugTraceEnableRequest {
+    // NATS subject patterns to record (* matches one token, > the rest)
+    pub subjects: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DebugTraceStateReply {
+    pub enabled: bool,
+    pub subjects: Vec<String>,
+    // JSON-lines mirror of the ring buffer
+    pub trace_file: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DebugTraceDumpReply {
+    pub enabled: bool,
+    pub subjects: Vec<String>,
+    // ring buffer snapshot, oldest entry first, secrets already redacted
+    pub entries: Vec<printnanny_nats_client::trace::TraceEntry>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NatsCredsRotateReply {
+    // path the freshly issued credentials were written to
+    pub creds_file: String,
+    // expiry of the new user JWT (rfc3339), if the issuer set one
+    pub expires_at: Option<String>,
+    // the subscriber reconnects with the new credentials after this reply is
+    // published on the old connection
+    pub reconnect_scheduled: bool,
+}
+
+// expiry claim of the user JWT embedded in a NATS .creds file
+fn nats_creds_expiry(creds: &str) -> Option<String> {
+    let jwt = creds
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with("ey") && line.matches('.').count() == 2)?;
+    let payload = jwt.split('.').nth(1)?;
+    let payload = base64::decode_config(payload, base64::URL_SAFE_NO_PAD).ok()?;
+    let claims: serde_json::Value = serde_json::from_slice(&payload).ok()?;
+    let exp = claims.get("exp")?.as_i64()?;
+    let naive = chrono::NaiveDateTime::from_timestamp_opt(exp, 0)?;
+    Some(chrono::DateTime::<chrono::Utc>::from_utc(naive, chrono::Utc).to_rfc3339())
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WolRequest {
+    // name of a stored profile in NetworkSettings.wol_profiles; wins over the
+    // ad-hoc fields below
+    pub profile: Option<String>,
+    pub mac_address: Option<String>,
+    pub broadcast_addr: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WolReply {
+    pub mac_address: Option<String>,
+    pub broadcast_addr: Option<String>,
+    pub sent: bool,
+    // in-band rejection (unknown profile, missing/invalid MAC)
+    pub rejected: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DeviceDecommissionRequest {
+    // wipes are irreversible; the dashboard sets this after the owner types
+    // the device name to confirm
+    pub confirm: bool,
+    // also overwrite free space so deleted flash blocks cannot be recovered
+    #[serde(default)]
+    pub overwrite_free_space: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DeviceCommandRequest {
+    pub command: DeviceCommand,
+    // absolute execution time; wins over delay_seconds when both are set
+    pub execute_at: Option<chrono::DateTime<chrono::Utc>>,
+    // seconds from receipt; ignored when execute_at is set
+    pub delay_seconds: Option<u64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DeviceCommandReply {
+    // persisted row id when the command was scheduled for later; None when it
+    // ran immediately
+    pub command_id: Option<String>,
+    pub execute_at_dt: chrono::DateTime<chrono::Utc>,
+    pub scheduled: bool,
+    // outcome of an immediate execution
+    pub result: Option<String>,
+    // preflight checks run for swupdate commands; the command is refused
+    // (scheduled=false, command_id=None) when any check failed
+    #[serde(default)]
+    pub preflight: Option<printnanny_services::upgrade_advisor::UpdatePreflightReport>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DeviceDecommissionReply {
+    // signed completion report; None when the request was rejected
+    pub report: Option<DecommissionReport>,
+    pub rejected: Option<String>,
+}
+
+// privacy mode is device-local state, so the reply is not part of the generated
+// printnanny-os-models crate (yet)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CameraPrivacyReply {
+    pub privacy_mode: bool,
+}
+
+// the detection overlay toggle is device-local state, so the reply is not part
+// of the generated printnanny-os-models crate (yet)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CameraOverlayReply {
+    pub hls_detection_overlay: bool,
+}
+
+// active viewer count reported by the WebSocket gateway; feeds the viewer
+// idle monitor (see crate::viewers)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CameraStreamViewersRequest {
+    pub active_viewers: i32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CameraStreamViewersReply {
+    pub active_viewers: i32,
+    // stream pipelines resumed for a returning viewer after an idle pause
+    pub resumed: Vec<String>,
+}
+
+// stream pause state is device-local runtime state, so the reply is not part
+// of the generated printnanny-os-models crate (yet)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CameraStreamStateReply {
+    pub paused: bool,
+    // stream pipelines whose state was changed; legs that were already in the
+    // requested state (or don't exist, e.g. hls disabled) are omitted
+    pub pipelines: Vec<String>,
+}
+
+// rtp fan-out destinations are device-local state, so the payloads are not
+// part of the generated printnanny-os-models crate (yet)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CameraRtpDestinationsRequest {
+    pub destinations: Vec<RtpDestination>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CameraRtpDestinationsReply {
+    // extra destinations beyond the default local video_udp_port
+    pub destinations: Vec<RtpDestination>,
+}
+
+// camera controls are device-local state, so the reply is not part of the generated
+// printnanny-os-models crate (yet)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CameraControlsReply {
+    // persisted control settings
+    pub settings: CameraControlSettings,
+    // current control values reported by the v4l2 device
+    pub controls: Vec<V4l2Control>,
+}
+
+// "clip last N seconds": flush the circular pre-roll ring into a standalone
+// mp4 registered as a VideoRecording
+pub const DEFAULT_CLIP_SECONDS: u64 = 30;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CameraClipRequest {
+    // defaults to DEFAULT_CLIP_SECONDS, capped by the pre-roll ring length
+    pub seconds: Option<u64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CameraClipReply {
+    pub recording: Box<printnanny_os_models::VideoRecording>,
+    pub file_name: String,
+    pub seconds: u64,
+}
+
+// lighting is device-local state, so the reply is not part of the generated
+// printnanny-os-models crate (yet)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LightsReply {
+    pub lighting: LightingSettings,
+    pub on: bool,
+}
+
+// the identity keypair is device-local state (seed in the secrets dir), so
+// these are not part of the generated printnanny-os-models crate
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DeviceIdentityRequest {
+    // rotate the keypair before replying with the (new) public key
+    pub rotate: Option<bool>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DeviceIdentityReply {
+    // base64-encoded public key; messages signed before a rotation verify
+    // against the previously reported key
+    pub pubkey: String,
+    pub algorithm: String,
+    pub rotated: bool,
+}
+
+impl NatsRequest {
+    pub async fn handle_camera_privacy(enabled: bool) -> Result<NatsReply> {
+        let mut settings = PrintNannySettings::new().await?;
+        settings.video_stream.privacy_mode = enabled;
+        let content = settings.to_toml_string()?;
+        let ts = EventMetadata::new().ts;
+        let commit_msg =
+            format!("Set PrintNannySettings.video_stream.privacy_mode={enabled} @ {ts}");
+        settings.save_and_commit(&content, Some(commit_msg)).await?;
+
+        // swap the camera source leg without tearing down downstream pipelines
+        let factory = PrintNannyPipelineFactory::default();
+        factory.set_privacy_mode(enabled).await?;
+        Ok(NatsReply::CameraPrivacyReply(CameraPrivacyReply {
+            privacy_mode: enabled,
+        }))
+    }
+
+    pub async fn handle_camera_overlay(enabled: bool) -> Result<NatsReply> {
+        let mut settings = PrintNannySettings::new().await?;
+        settings.video_stream.hls_detection_overlay = enabled;
+        let content = settings.to_toml_string()?;
+        let ts = EventMetadata::new().ts;
+        let commit_msg =
+            format!("Set PrintNannySettings.video_stream.hls_detection_overlay={enabled} @ {ts}");
+        settings.save_and_commit(&content, Some(commit_msg)).await?;
+
+        // rebuild the overlay legs and repoint hls without touching the camera leg
+        let factory = PrintNannyPipelineFactory::default();
+        factory.set_hls_detection_overlay(enabled).await?;
+        Ok(NatsReply::CameraOverlayReply(CameraOverlayReply {
+            hls_detection_overlay: enabled,
+        }))
+    }
+
+    pub async fn handle_camera_stream_pause(paused: bool) -> Result<NatsReply> {
+        let factory = PrintNannyPipelineFactory::default();
+        let pipelines = factory.set_stream_paused(paused).await?;
+        Ok(NatsReply::CameraStreamStateReply(CameraStreamStateReply {
+            paused,
+            pipelines,
+        }))
+    }
+
+    pub async fn handle_camera_stream_viewers(
+        request: &CameraStreamViewersRequest,
+    ) -> Result<NatsReply> {
+        let resume = crate::viewers::record_viewer_count(request.active_viewers);
+        let resumed = match resume {
+            true => {
+                let factory = PrintNannyPipelineFactory::default();
+                factory.set_stream_paused(false).await?
+            }
+            false => vec![],
+        };
+        Ok(NatsReply::CameraStreamViewersReply(
+            CameraStreamViewersReply {
+                active_viewers: request.active_viewers,
+                resumed,
+            },
+        ))
+    }
+
+    // handle messages sent to: "pi.{pi_id}.camera.clip"
+    pub async fn handle_camera_clip(request: &CameraClipRequest) -> Result<NatsReply> {
+        let settings = PrintNannySettings::new().await?;
+        let sqlite_connection = settings.paths.db().display().to_string();
+        let seconds = request
+            .seconds
+            .unwrap_or(DEFAULT_CLIP_SECONDS)
+            .min(PREROLL_RING_SECONDS);
+
+        let recording = printnanny_edge_db::video_recording::VideoRecording::start_new(
+            &sqlite_connection,
+            settings.paths.video(),
+        )?;
+        let dir = std::path::PathBuf::from(&recording.dir);
+
+        // stage the newest ring fragments; mpegts byte-concatenates cleanly
+        let fragments = PrintNannyPipelineFactory::flush_preroll_clip(seconds, &dir)?;
+        if fragments.is_empty() {
+            return Err(anyhow!("Pre-roll ring is empty, no clip to save"));
+        }
+        let concat_path = dir.join("clip.ts");
+        let mut concat = Vec::new();
+        for fragment in &fragments {
+            concat.extend(fs::read(fragment).await?);
+        }
+        fs::write(&concat_path, &concat).await?;
+        for fragment in &fragments {
+            fs::remove_file(fragment).await?;
+        }
+
+        // remux to mp4 without re-encoding; same one-shot gst-launch approach
+        // as the anonymize transcode
+        let file_name = dir.join("0.mp4");
+        let description = format!(
+            "filesrc location={} ! tsdemux ! h264parse ! mp4mux ! filesink location={}",
+            concat_path.display(),
+            file_name.display()
+        );
+        let output = async_process::Command::new("sh")
+            .args(["-c", &format!("gst-launch-1.0 -e {}", description)])
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Failed to remux pre-roll clip: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        fs::remove_file(&concat_path).await?;
+
+        let file_name = file_name.display().to_string();
+        let size = fs::metadata(&file_name).await?.len() as i64;
+        let part_id = printnanny_edge_db::video_recording::VideoRecordingPart::row_id_from_filename(
+            &file_name,
+        );
+        printnanny_edge_db::video_recording::VideoRecordingPart::insert(
+            &sqlite_connection,
+            printnanny_edge_db::video_recording::NewVideoRecordingPart {
+                id: &part_id,
+                size: &size,
+                buffer_index: &0,
+                buffer_runningtime: &0,
+                deleted: &false,
+                file_name: &file_name,
+                video_recording_id: &recording.id,
+            },
+        )?;
+
+        // the clip covers [now - seconds, now]; mark it finished immediately
+        // so the next cloud sync pass picks it up
+        let recording_end = chrono::Utc::now();
+        let recording_start = recording_end - chrono::Duration::seconds(seconds as i64);
+        printnanny_edge_db::video_recording::VideoRecording::update(
+            &sqlite_connection,
+            &recording.id,
+            printnanny_edge_db::video_recording::UpdateVideoRecording {
+                cloud_sync_done: None,
+                dir: None,
+                recording_start: Some(&recording_start),
+                recording_end: Some(&recording_end),
+                gcode_file_name: None,
+            },
+        )?;
+        let recording = printnanny_edge_db::video_recording::VideoRecording::get_by_id(
+            &sqlite_connection,
+            &recording.id,
+        )?;
+        Ok(NatsReply::CameraClipReply(CameraClipReply {
+            recording: Box::new(recording.into()),
+            file_name,
+            seconds,
+        }))
+    }
+
+    pub async fn handle_camera_rtp_destinations_get() -> Result<NatsReply> {
+        let settings = PrintNannySettings::new().await?;
+        Ok(NatsReply::CameraRtpDestinationsReply(
+            CameraRtpDestinationsReply {
+                destinations: settings.video_stream.rtp_destinations,
+            },
+        ))
+    }
+
+    pub async fn handle_camera_rtp_destinations_set(
+        request: &CameraRtpDestinationsRequest,
+    ) -> Result<NatsReply> {
+        let mut settings = PrintNannySettings::new().await?;
+        settings.video_stream.rtp_destinations = request.destinations.clone();
+        let content = settings.to_toml_string()?;
+        let ts = EventMetadata::new().ts;
+        let commit_msg = format!("Updated PrintNannySettings.video_stream.rtp_destinations @ {ts}");
+        settings.save_and_commit(&content, Some(commit_msg)).await?;
+
+        // live-update the multiudpsink client list; no pipeline restart needed
+        let factory = PrintNannyPipelineFactory::default();
+        factory.set_rtp_destinations(&settings.video_stream).await?;
+        Ok(NatsReply::CameraRtpDestinationsReply(
+            CameraRtpDestinationsReply {
+                destinations: request.destinations.clone(),
+            },
+        ))
+    }
+
+    pub async fn handle_camera_controls_get() -> Result<NatsReply> {
+        let settings = PrintNannySettings::new().await?;
+        let control_settings = settings.video_stream.controls;
+        let controls = control_settings.list_controls().await?;
+        Ok(NatsReply::CameraControlsReply(CameraControlsReply {
+            settings: control_settings,
+            controls,
+        }))
+    }
+
+    pub async fn handle_camera_controls_set(
+        control_settings: &CameraControlSettings,
+    ) -> Result<NatsReply> {
+        let mut settings = PrintNannySettings::new().await?;
+        settings.video_stream.controls = control_settings.clone();
+        let content = settings.to_toml_string()?;
+        let ts = EventMetadata::new().ts;
+        let commit_msg = format!("Updated PrintNannySettings.video_stream.controls @ {ts}");
+        settings.save_and_commit(&content, Some(commit_msg)).await?;
+
+        control_settings.apply().await?;
+        let controls = control_settings.list_controls().await?;
+        Ok(NatsReply::CameraControlsReply(CameraControlsReply {
+            settings: control_settings.clone(),
+            controls,
+        }))
+    }
+
+    pub async fn handle_camera_recording_load() -> Result<NatsReply> {
+        let settings = PrintNannySettings::new().await?;
+        let sqlite_connection = settings.paths.db().display().to_string();
+        let current =
+            printnanny_edge_db::video_recording::VideoRecording::get_current(&sqlite_connection)?;
+        match current {
+            Some(current) => {
+                // get parts for recording
+                let parts =  printnanny_edge_db::video_recording::VideoRecordingPart::get_parts_by_video_recording_id(&sqlite_connection, &current.id)?.into_iter().map(|v| v.into()).collect();
+                Ok(NatsReply::CameraRecordingLoadReply(
+                    CameraRecordingLoadReply {
+                        recording: Some(Box::new(current.into())),
+                        parts: Some(parts),
+                    },
+                ))
+            }
+            None => Ok(NatsReply::CameraRecordingLoadReply(
+                CameraRecordingLoadReply {
+                    recording: None,
+                    parts: None,
+                },
+            )),
+        }
+    }
+
+    pub async fn handle_lights(on: bool) -> Result<NatsReply> {
+        let settings = PrintNannySettings::new().await?;
+        printnanny_services::lighting::set_power(&settings.lighting, on).await?;
+        Ok(NatsReply::LightsReply(LightsReply {
+            lighting: settings.lighting,
+            on,
+        }))
+    }
+
+    // handle messages sent to: "pi.{pi_id}.identity.pubkey"
+    pub async fn handle_device_identity(request: &DeviceIdentityRequest) -> Result<NatsReply> {
+        let settings = PrintNannySettings::new().await?;
+        let rotated = request.rotate.unwrap_or(false);
+        let keypair = match rotated {
+            true => printnanny_nats_client::identity::rotate(&settings.paths)?,
+            false => printnanny_nats_client::identity::keypair(&settings.paths)?,
+        };
+        Ok(NatsReply::DeviceIdentityReply(DeviceIdentityReply {
+            pubkey: printnanny_nats_client::identity::public_key_b64(&keypair),
+            algorithm: printnanny_nats_client::identity::SIGNATURE_ALGORITHM.to_string(),
+            rotated,
+        }))
+    }
+
+    // best-effort lighting toggle alongside print/recording lifecycle; a lighting
+    // failure should never fail the recording itself
+    async fn try_set_lights(settings: &PrintNannySettings, on: bool) {
+        match printnanny_services::lighting::set_power(&settings.lighting, on).await {
+            Ok(_) => info!("Set lights on={}", on),
+            Err(e) => error!("Failed to set lights on={} error={}", on, e),
+        }
+    }
+
+    pub async fn handle_camera_recording_start() -> Result<NatsReply> {
+        let settings = PrintNannySettings::new().await?;
+        if settings.lighting.enabled && settings.lighting.on_print_start {
+            Self::try_set_lights(&settings, true).await;
+        }
+        let sqlite_connection = settings.paths.db().display().to_string();
+        printnanny_edge_db::video_recording::VideoRecording::finish_all(&sqlite_connection)?;
+
+        let api = ApiService::new(settings.cloud, sqlite_connection);
+        let recording = api.video_recordings_create(settings.paths.video()).await?;
+        Ok(NatsReply::CameraRecordingStartReply(
+            CameraRecordingStarted {
+                recording: Box::new(recording.into()),
+            },
+        ))
+    }
+
+    pub async fn handle_camera_recording_stop() -> Result<NatsReply> {
+        let settings = PrintNannySettings::new().await?;
+        if settings.lighting.enabled && settings.lighting.off_print_done {
+            Self::try_set_lights(&settings, false).await;
+        }
+        let sqlite_connection = settings.paths.db().display().to_string();
+
+        // get the active recording
+        let recording =
+            printnanny_edge_db::video_recording::VideoRecording::get_current(&sqlite_connection)?;
+        let factory = PrintNannyPipelineFactory::default();
+
+        // send EOS signal to gstreamer
+        factory.stop_video_recording_pipeline().await?;
+
+        // sync all video recording parts
+        sync_all_video_recordings().await?;
+
+        match &recording {
+            Some(current) => {
+                // send finalization request to cloud api
+                let api = ApiService::new(settings.cloud, sqlite_connection);
+                api.video_recording_finalize(&current.id).await?;
+            }
+            None => {
+                warn!("handle_camera_recording_stop called, but no active recording was found");
+            }
+        };
+
+        Ok(NatsReply::CameraRecordingStopReply(
+            CameraRecordingStopped {
+                recording: recording.map(|v| Box::new(v.into())),
+            },
+        ))
+    }
+
+    pub async fn handle_cloud_sync() -> Result<NatsReply> {
+        let start = chrono::offset::Utc::now().to_rfc3339();
+
+        let settings = PrintNannySettings::new().await?;
+        let api = ApiService::from(&settings);
+        // sync cloud models to edge db
+        api.sync().await?;
+        // set optional pipelines to correct state
+        let gst_pipelines = PrintNannyPipelineFactory::default();
+        gst_pipelines
+            .sync_optional_pipelines(settings.video_stream)
+            .await?;
+        let end = chrono::offset::Utc::now().to_rfc3339();
+
+        Ok(NatsReply::PrintNannyCloudSyncReply(
+            PrintNannyCloudSyncReply { start, end },
+        ))
+    }
+
+    // message messages sent to: "pi.{pi_id}.device_info.load"
+    pub async fn handle_device_info_load() -> Result<NatsReply> {
+        let settings = PrintNannySettings::new().await?;
+        let issue = fs::read_to_string(settings.paths.issue_txt).await?;
+        let os_release = fs::read_to_string(settings.paths.os_release).await?;
+
+        let ifaddrs = tokio::task::spawn_blocking(|| match nix::ifaddrs::getifaddrs() {
+            Ok(result) => result
+                .map(
+                    |v| printnanny_settings::printnanny_os_models::NetworkInterfaceAddress {
+                        interface_name: v.interface_name,
+                        flags: v.flags.bits(),
+                        address: v.address.map(|v| v.to_string()),
+                        netmask: v.netmask.map(|v| v.to_string()),
+                        destination: v.destination.map(|v| v.to_string()),
+                        broadcast: v.broadcast.map(|v| v.to_string()),
+                    },
+                )
+                .collect(),
+            Err(e) => {
+                error!("Error loading ifaddrs {}", e.to_string());
+                vec![]
+            }
+        })
+        .await?;
+        Ok(NatsReply::DeviceInfoLoadReply(DeviceInfoLoadReply {
+            issue,
+            os_release,
+            printnanny_cli_version: "".into(), // TODO
+            ifaddrs,
+        }))
+    }
+
+    // handle messages sent to: "pi.{pi_id}.capabilities"
+    pub async fn handle_capabilities() -> Result<NatsReply> {
+        let settings = PrintNannySettings::new().await?;
+        let capabilities = crate::heartbeat::capability_summary(&settings).await;
+        Ok(NatsReply::CapabilitiesReply(CapabilitiesReply {
+            capabilities,
+        }))
+    }
+
+    // handle messages sent to: "pi.{pi_id}.command.device.decommission"
+    pub async fn handle_device_decommission(
+        request: &DeviceDecommissionRequest,
+    ) -> Result<NatsReply> {
+        if !request.confirm {
+            warn!("Rejected decommission request without confirm=true");
+            return Ok(NatsReply::DeviceDecommissionReply(
+                DeviceDecommissionReply {
+                    report: None,
+                    rejected: Some("Decommission requires confirm=true".to_string()),
+                },
+            ));
+        }
+        let settings = PrintNannySettings::new().await?;
+        let overwrite = request.overwrite_free_space;
+        // the wipe (and especially the free-space overwrite) can run for
+        // minutes, so keep it off the async runtime
+        let report = tokio::task::spawn_blocking(move || {
+            printnanny_services::decommission::decommission(&settings, overwrite)
+        })
+        .await??;
+        Ok(NatsReply::DeviceDecommissionReply(
+            DeviceDecommissionReply {
+                report: Some(report),
+                rejected: None,
+            },
+        ))
+    }
+
+    // handle messages sent to: "pi.{pi_id}.command.device.schedule"
+    pub async fn handle_device_command(request: &DeviceCommandRequest) -> Result<NatsReply> {
+        let now = chrono::Utc::now();
+        let execute_at =
+            device_command::resolve_execute_at(now, &request.execute_at, &request.delay_seconds);
+        // swupdate commands are gated on preflight checks: a refusal lists the
+        // failed preconditions instead of starting an update that will fail
+        // halfway. Scheduled updates are re-checked by the executor when due.
+        let mut preflight = None;
+        if let DeviceCommand::Swupdate { swu_url, .. } = &request.command {
+            let settings = PrintNannySettings::new().await?;
+            let report = upgrade_advisor::preflight(&settings, swu_url).await;
+            if !report.passed {
+                warn!(
+                    "Refusing swupdate command, preflight failed: {}",
+                    report.failed_summary()
+                );
+                return Ok(NatsReply::DeviceCommandReply(DeviceCommandReply {
+                    command_id: None,
+                    execute_at_dt: now,
+                    scheduled: false,
+                    result: None,
+                    preflight: Some(report),
+                }));
+            }
+            preflight = Some(report);
+        }
+        if execute_at > now {
+            // persist the command so it survives worker restarts
+            let settings = PrintNannySettings::new().await?;
+            let row = device_command::schedule(&settings, &request.command, &execute_at)?;
+            return Ok(NatsReply::DeviceCommandReply(DeviceCommandReply {
+                command_id: Some(row.id),
+                execute_at_dt: row.execute_at_dt,
+                scheduled: true,
+                result: None,
+                preflight,
+            }));
+        }
+        let result = device_command::execute(&request.command).await?;
+        Ok(NatsReply::DeviceCommandReply(DeviceCommandReply {
+            command_id: None,
+            execute_at_dt: now,
+            scheduled: false,
+            result: Some(result),
+            preflight,
+        }))
+    }
+
+    // handle messages sent to: "pi.{pi_id}.status.summary"
+    pub async fn handle_status_summary() -> Result<NatsReply> {
+        let settings = PrintNannySettings::new().await?;
+        let hostname =
+            printnanny_settings::sys_info::hostname().unwrap_or_else(|_| "localhost".to_string());
+        let heartbeat = crate::heartbeat::summary(&settings).await?;
+        Ok(NatsReply::StatusSummaryReply(StatusSummaryReply {
+            hostname,
+            heartbeat,
+        }))
+    }
+
+    // handle messages sent to: "pi.{pi_id}.farm.overview"
+    pub async fn handle_farm_overview() -> Result<NatsReply> {
+        let settings = PrintNannySettings::new().await?;
+        let devices = crate::farm::farm_overview(&settings).await?;
+        Ok(NatsReply::FarmOverviewReply(FarmOverviewReply { devices }))
+    }
+
+    // handle messages sent to: "pi.{pi_id}.schedule.list"
+    pub async fn handle_schedule_list() -> Result<NatsReply> {
+        let settings = PrintNannySettings::new().await?;
+        let tasks = crate::schedule::task_statuses(&settings);
+        Ok(NatsReply::ScheduleListReply(ScheduleListReply { tasks }))
+    }
+
+    // handle messages sent to: "pi.{pi_id}.jobs.get"
+    pub async fn handle_jobs_get(request: &JobsGetRequest) -> Result<NatsReply> {
+        let settings = PrintNannySettings::new().await?;
+        let sqlite_connection = settings.paths.db().display().to_string();
+        let job = match printnanny_edge_db::jobs::Job::get_by_id(&sqlite_connection, &request.id) {
+            Ok(job) => Some(job),
+            Err(printnanny_edge_db::diesel::result::Error::NotFound) => None,
+            Err(e) => return Err(e.into()),
+        };
+        Ok(NatsReply::JobsGetReply(JobsGetReply { job }))
+    }
+
+    // handle messages sent to: "pi.{pi_id}.jobs.list"
+    pub async fn handle_jobs_list(request: &JobsListRequest) -> Result<NatsReply> {
+        let settings = PrintNannySettings::new().await?;
+        let sqlite_connection = settings.paths.db().display().to_string();
+        let jobs = match request.running_only {
+            true => printnanny_edge_db::jobs::Job::get_running(&sqlite_connection)?,
+            false => printnanny_edge_db::jobs::Job::get_all(&sqlite_connection)?,
+        };
+        Ok(NatsReply::JobsListReply(JobsListReply { jobs }))
+    }
+
+    // handle messages sent to: "pi.{pi_id}.command.nats.rotate_creds"
+    pub async fn handle_nats_creds_rotate() -> Result<NatsReply> {
+        let settings = PrintNannySettings::new().await?;
+        let sqlite_connection = settings.paths.db().display().to_string();
+        let creds_file = settings.paths.cloud_nats_creds();
+
+        // fetch freshly issued credentials from the cloud, backing up the old
+        // bundle; unpack_license writes the creds file atomically
+        let api = ApiService::new(settings.cloud.clone(), sqlite_connection.clone());
+        let pi_id = printnanny_edge_db::cloud::Pi::get_id(&sqlite_connection)?;
+        api.pi_download_license(pi_id, true).await?;
+
+        let creds = fs::read_to_string(&creds_file).await?;
+        let expires_at = nats_creds_expiry(&creds);
+
+        // end the current subscription stream once in-flight replies (this one
+        // included) are published; run() reconnects with the new creds and
+        // replays the offline buffer, so no buffered events are dropped
+        printnanny_nats_client::subscriber::request_reconnect();
+
+        Ok(NatsReply::NatsCredsRotateReply(NatsCredsRotateReply {
+            creds_file: creds_file.display().to_string(),
+            expires_at,
+            reconnect_scheduled: true,
+        }))
+    }
+
+    fn debug_trace_state_reply() -> NatsReply {
+        NatsReply::DebugTraceStateReply(DebugTraceStateReply {
+            enabled: printnanny_nats_client::trace::is_enabled(),
+            subjects: printnanny_nats_client::trace::patterns(),
+            trace_file: printnanny_nats_client::trace::trace_file()
+                .map(|f| f.display().to_string()),
+        })
+    }
+
+    // handle messages sent to: "pi.{pi_id}.debug.trace.enable"
+    pub async fn handle_debug_trace_enable(request: &DebugTraceEnableRequest) -> Result<NatsReply> {
+        let hostname = printnanny_settings::sys_info::hostname()
+            .unwrap_or_else(|_| "localhost".to_string())
+            .to_lowercase();
+        // accept templated patterns so support runbooks can be copy-pasted
+        let subjects: Vec<String> = request
+            .subjects
+            .iter()
+            .map(|s| s.replacen("{pi_id}", &hostname, 1))
+            .collect();
+        printnanny_nats_client::trace::enable(subjects, None);
+        Ok(Self::debug_trace_state_reply())
+    }
+
+    // handle messages sent to: "pi.{pi_id}.debug.trace.disable"
+    pub async fn handle_debug_trace_disable() -> Result<NatsReply> {
+        printnanny_nats_client::trace::disable();
+        Ok(Self::debug_trace_state_reply())
+    }
+
+    // handle messages sent to: "pi.{pi_id}.debug.trace.dump"
+    pub async fn handle_debug_trace_dump() -> Result<NatsReply> {
+        Ok(NatsReply::DebugTraceDumpReply(DebugTraceDumpReply {
+            enabled: printnanny_nats_client::trace::is_enabled(),
+            subjects: printnanny_nats_client::trace::patterns(),
+            entries: printnanny_nats_client::trace::dump(),
+        }))
+    }
+
+    // handle messages sent to: "pi.{pi_id}.network.wol"
+    pub async fn handle_wol(request: &WolRequest) -> Result<NatsReply> {
+        let settings = PrintNannySettings::new().await?;
+        let (mac_address, broadcast_addr) = match &request.profile {
+            Some(profile_name) => match settings.network.wol_profile(profile_name) {
+                Some(profile) => (profile.mac_address.clone(), profile.broadcast_addr.clone()),
+                None => {
+                    warn!("Rejected WoL request for unknown profile {}", profile_name);
+                    return Ok(NatsReply::WolReply(WolReply {
+                        mac_address: None,
+                        broadcast_addr: None,
+                        sent: false,
+                        rejected: Some(format!("Unknown WoL profile {:?}", profile_name)),
+                    }));
+                }
+            },
+            None => match &request.mac_address {
+                Some(mac_address) => (
+                    mac_address.clone(),
+                    request
+                        .broadcast_addr
+                        .clone()
+                        .unwrap_or_else(|| "255.255.255.255:9".to_string()),
+                ),
+                None => {
+                    return Ok(NatsReply::WolReply(WolReply {
+                        mac_address: None,
+                        broadcast_addr: None,
+                        sent: false,
+                        rejected: Some("WoL request requires a profile or mac_address".to_string()),
+                    }));
+                }
+            },
+        };
+        if let Err(e) = printnanny_services::network::send_wol(&mac_address, &broadcast_addr) {
+            warn!("Rejected WoL request error={}", e);
+            return Ok(NatsReply::WolReply(WolReply {
+                mac_address: Some(mac_address),
+                broadcast_addr: Some(broadcast_addr),
+                sent: false,
+                rejected: Some(e.to_string()),
+            }));
+        }
+        Ok(NatsReply::WolReply(WolReply {
+            mac_address: Some(mac_address),
+            broadcast_addr: Some(broadcast_addr),
+            sent: true,
+            rejected: None,
+        }))
+    }
+
+    // handle messages sent to: "pi.{pi_id}.settings.printnanny.cloud.auth"
+    // pi.{pi_id}.octoprint.plugins.list
+    pub async fn handle_octoprint_plugins_list() -> Result<NatsReply> {
+        let settings = PrintNannySettings::new().await?;
+        let plugins = settings.to_octoprint_settings().list_plugins().await?;
+        Ok(NatsReply::OctoPrintPluginsListReply(
+            OctoPrintPluginsListReply { plugins },
+        ))
+    }
+
+    async fn restart_octoprint() -> Result<()> {
+        let connection = zbus::Connection::system().await?;
+        let proxy = zbus_systemd::systemd1::ManagerProxy::new(&connection).await?;
+        let job = proxy
+            .restart_unit("octoprint.service".into(), "replace".into())
+            .await?;
+        info!("Restarted octoprint.service, job: {:?}", job);
+        Ok(())
+    }
+
+    // pi.{pi_id}.octoprint.plugins.install
+    pub async fn handle_octoprint_plugin_install(
+        request: &OctoPrintPluginInstallRequest,
+    ) -> Result<NatsReply> {
+        let settings = PrintNannySettings::new().await?;
+        let octoprint = settings.to_octoprint_settings();
+        let detail = octoprint
+            .install_plugin(&request.package, request.version.as_deref())
+            .await?;
+        Self::restart_octoprint().await?;
+        let plugins = octoprint.list_plugins().await?;
+        Ok(NatsReply::OctoPrintPluginChangedReply(
+            OctoPrintPluginChangedReply {
+                package: request.package.clone(),
+                detail,
+                plugins,
+            },
+        ))
+    }
+
+    // pi.{pi_id}.octoprint.plugins.uninstall
+    pub async fn handle_octoprint_plugin_uninstall(
+        request: &OctoPrintPluginUninstallRequest,
+    ) -> Result<NatsReply> {
+        let settings = PrintNannySettings::new().await?;
+        let octoprint = settings.to_octoprint_settings();
+        let detail = octoprint.uninstall_plugin(&request.package).await?;
+        Self::restart_octoprint().await?;
+        let plugins = octoprint.list_plugins().await?;
+        Ok(NatsReply::OctoPrintPluginChangedReply(
+            OctoPrintPluginChangedReply {
+                package: request.package.clone(),
+                detail,
+                plugins,
+            },
+        ))
+    }
+
+    // pi.{pi_id}.octoprint.backup.create
+    pub async fn handle_octoprint_backup_create(
+        request: &OctoPrintBackupCreateRequest,
+    ) -> Result<NatsReply> {
+        let path =
+            printnanny_services::octoprint::octoprint_create_backup(&request.exclude).await?;
+        Ok(NatsReply::OctoPrintBackupCreateReply(
+            OctoPrintBackupCreateReply {
+                path: path.display().to_string(),
+            },
+        ))
+    }
+
+    // pi.{pi_id}.octoprint.backup.restore
+    pub async fn handle_octoprint_backup_restore(
+        request: &OctoPrintBackupRestoreRequest,
+    ) -> Result<NatsReply> {
+        printnanny_services::octoprint::octoprint_restore_backup(std::path::Path::new(
+            &request.path,
+        ))
+        .await?;
+        Ok(NatsReply::OctoPrintBackupRestoreReply(
+            OctoPrintBackupRestoreReply { started: true },
+        ))
+    }
+
+    pub async fn handle_detection_feedback(
+        request: &DetectionFeedbackRequest,
+    ) -> Result<NatsReply> {
         let settings = PrintNannySettings::new().await?;
+        let label = request
+            .label
+            .parse()
+            .map_err(|e: String| anyhow!("{}", e))?;
+        let feedback = printnanny_services::detection_feedback::capture_feedback(
+            &settings,
+            request.detection_ts,
+            label,
+            request.note.as_deref(),
+        )?;
         let sqlite_connection = settings.paths.db().display().to_string();
-        let current =
-            printnanny_edge_db::video_recording::VideoRecording::get_current(&sqlite_connection)?;
-        match current {
-            Some(current) => {
-                // get parts for recording
-                let parts =  printnanny_edge_db::video_recording::VideoRecordingPart::get_parts_by_video_recording_id(&sqlite_connection, &current.id)?.into_iter().map(|v| v.into()).collect();
-                Ok(NatsReply::CameraRecordingLoadReply(
-                    CameraRecordingLoadReply {
-                        recording: Some(Box::new(current.into())),
-                        parts: Some(parts),
+        let pending = printnanny_edge_db::detection_feedback::DetectionFeedback::get_pending(
+            &sqlite_connection,
+        )?
+        .len();
+        Ok(NatsReply::DetectionFeedbackReply(DetectionFeedbackReply {
+            feedback,
+            pending,
+        }))
+    }
+
+    pub async fn handle_detection_feedback_sync() -> Result<NatsReply> {
+        let settings = PrintNannySettings::new().await?;
+        match printnanny_services::detection_feedback::sync_pending_feedback(&settings) {
+            Ok(synced) => Ok(NatsReply::DetectionFeedbackSyncReply(
+                DetectionFeedbackSyncReply {
+                    synced,
+                    enabled: true,
+                },
+            )),
+            Err(printnanny_services::error::ServiceError::TelemetryDisabled { .. }) => {
+                warn!("Refused detection feedback sync: telemetry.video_snippets=false");
+                Ok(NatsReply::DetectionFeedbackSyncReply(
+                    DetectionFeedbackSyncReply {
+                        synced: 0,
+                        enabled: false,
                     },
                 ))
             }
-            None => Ok(NatsReply::CameraRecordingLoadReply(
-                CameraRecordingLoadReply {
-                    recording: None,
-                    parts: None,
-                },
-            )),
+            Err(e) => Err(e.into()),
         }
     }
 
-    pub async fn handle_camera_recording_start() -> Result<NatsReply> {
+    pub async fn handle_model_evaluation_report(
+        request: &ModelEvaluationReportRequest,
+    ) -> Result<NatsReply> {
         let settings = PrintNannySettings::new().await?;
-        let sqlite_connection = settings.paths.db().display().to_string();
-        printnanny_edge_db::video_recording::VideoRecording::finish_all(&sqlite_connection)?;
-
-        let api = ApiService::new(settings.cloud, sqlite_connection);
-        let recording = api.video_recordings_create(settings.paths.video()).await?;
-        Ok(NatsReply::CameraRecordingStartReply(
-            CameraRecordingStarted {
-                recording: Box::new(recording.into()),
+        let hours = request.hours.unwrap_or(24).clamp(1, 24 * 30);
+        let report = printnanny_services::model_evaluation::comparison_report(&settings, hours)?;
+        Ok(NatsReply::ModelEvaluationReportReply(
+            ModelEvaluationReportReply {
+                enabled: settings.video_stream.model_evaluation.enabled,
+                report,
             },
         ))
     }
 
-    pub async fn handle_camera_recording_stop() -> Result<NatsReply> {
-        let settings = PrintNannySettings::new().await?;
-        let sqlite_connection = settings.paths.db().display().to_string();
+    pub async fn handle_detection_tune(request: &DetectionTuneRequest) -> Result<NatsReply> {
+        let nms_threshold = request.nms_threshold.clamp(1, 100);
+        let mut settings = PrintNannySettings::new().await?;
+        settings.video_stream.detection.nms_threshold = nms_threshold;
+        let content = settings.to_toml_string()?;
+        let ts = EventMetadata::new().ts;
+        let commit_msg = format!(
+            "Set PrintNannySettings.video_stream.detection.nms_threshold={nms_threshold} @ {ts}"
+        );
+        settings.save_and_commit(&content, Some(commit_msg)).await?;
 
-        // get the active recording
-        let recording =
-            printnanny_edge_db::video_recording::VideoRecording::get_current(&sqlite_connection)?;
+        // retune the running detection elements in place via gstd
         let factory = PrintNannyPipelineFactory::default();
+        let pipelines = factory.set_detection_threshold(nms_threshold).await?;
+        Ok(NatsReply::DetectionTuneReply(DetectionTuneReply {
+            nms_threshold,
+            pipelines,
+        }))
+    }
 
-        // send EOS signal to gstreamer
-        factory.stop_video_recording_pipeline().await?;
-
-        // sync all video recording parts
-        sync_all_video_recordings().await?;
+    pub async fn handle_detection_calibrate(
+        request: &DetectionCalibrateRequest,
+    ) -> Result<NatsReply> {
+        let settings = PrintNannySettings::new().await?;
+        let idle_clip = std::path::PathBuf::from(&request.idle_clip);
+        let failure_clip = std::path::PathBuf::from(
+            request
+                .failure_clip
+                .as_deref()
+                .unwrap_or(printnanny_services::calibration::DEFAULT_FAILURE_SAMPLE),
+        );
+        let report =
+            printnanny_services::calibration::calibrate(&settings, &idle_clip, &failure_clip)
+                .await?;
 
-        match &recording {
-            Some(current) => {
-                // send finalization request to cloud api
-                let api = ApiService::new(settings.cloud, sqlite_connection);
-                api.video_recording_finalize(&current.id).await?;
-            }
-            None => {
-                warn!("handle_camera_recording_stop called, but no active recording was found");
+        let applied = match (request.apply, report.suggested_nms_threshold) {
+            (true, Some(nms_threshold)) => {
+                Self::handle_detection_tune(&DetectionTuneRequest { nms_threshold }).await?;
+                true
             }
+            _ => false,
         };
-
-        Ok(NatsReply::CameraRecordingStopReply(
-            CameraRecordingStopped {
-                recording: recording.map(|v| Box::new(v.into())),
-            },
+        Ok(NatsReply::DetectionCalibrateReply(
+            DetectionCalibrateReply { report, applied },
         ))
     }
 
-    pub async fn handle_cloud_sync() -> Result<NatsReply> {
-        let start = chrono::offset::Utc::now().to_rfc3339();
+    pub async fn handle_plugin(request: &PluginRequest) -> Result<NatsReply> {
+        let name = crate::plugin::plugin_name_from_subject(&request.subject_pattern)
+            .ok_or_else(|| anyhow!("Invalid plugin subject {}", request.subject_pattern))?;
+        let settings = PrintNannySettings::new().await?;
+        let config = settings
+            .plugins
+            .iter()
+            .find(|plugin| plugin.name == name && plugin.enabled)
+            .ok_or_else(|| anyhow!("No enabled plugin registered for name {}", name))?;
+        let context = crate::plugin::build_context(&settings, config);
+        let payload = base64::decode(&request.payload_b64)?;
+        let response = crate::plugin::call_plugin(
+            name,
+            &request.subject_pattern,
+            &payload,
+            &context.to_string(),
+        )?;
+        // plugins usually speak JSON; fall back to base64 for binary replies
+        let response = serde_json::from_slice::<serde_json::Value>(&response)
+            .unwrap_or_else(|_| serde_json::json!({ "response_b64": base64::encode(&response) }));
+        Ok(NatsReply::PluginReply(PluginReply {
+            plugin: name.to_string(),
+            response,
+        }))
+    }
 
+    pub async fn handle_bandwidth_stats(request: &BandwidthStatsRequest) -> Result<NatsReply> {
         let settings = PrintNannySettings::new().await?;
-        let api = ApiService::from(&settings);
-        // sync cloud models to edge db
-        api.sync().await?;
-        // set optional pipelines to correct state
-        let gst_pipelines = PrintNannyPipelineFactory::default();
-        gst_pipelines
-            .sync_optional_pipelines(settings.video_stream)
-            .await?;
-        let end = chrono::offset::Utc::now().to_rfc3339();
+        let sqlite_connection = settings.paths.db().display().to_string();
+        let days = request.days.unwrap_or(7).clamp(1, 90);
+        let usage =
+            printnanny_edge_db::bandwidth::BandwidthUsage::list_recent(&sqlite_connection, days)?;
+        Ok(NatsReply::BandwidthStatsReply(BandwidthStatsReply {
+            usage,
+        }))
+    }
 
-        Ok(NatsReply::PrintNannyCloudSyncReply(
-            PrintNannyCloudSyncReply { start, end },
-        ))
+    // handle messages sent to: "pi.{pi_id}.stats.storage"
+    pub async fn handle_storage_stats() -> Result<NatsReply> {
+        let settings = PrintNannySettings::new().await?;
+        let categories = printnanny_services::schedule::storage_usage(&settings);
+        let total_used_bytes = categories.iter().map(|category| category.used_bytes).sum();
+        Ok(NatsReply::StorageStatsReply(StorageStatsReply {
+            categories,
+            total_used_bytes,
+        }))
     }
 
-    // message messages sent to: "pi.{pi_id}.device_info.load"
-    pub async fn handle_device_info_load() -> Result<NatsReply> {
+    // handle messages sent to: "pi.{pi_id}.stats.trends"
+    pub async fn handle_stats_trends(request: &StatsTrendsRequest) -> Result<NatsReply> {
         let settings = PrintNannySettings::new().await?;
-        let issue = fs::read_to_string(settings.paths.issue_txt).await?;
-        let os_release = fs::read_to_string(settings.paths.os_release).await?;
+        let sqlite_connection = settings.paths.db().display().to_string();
+        let hours = request.hours.unwrap_or(24).clamp(
+            1,
+            printnanny_edge_db::heartbeat_samples::ROLLUP_RETENTION_DAYS * 24,
+        );
+        let since = chrono::Utc::now() - chrono::Duration::hours(hours);
+        let samples = printnanny_edge_db::heartbeat_samples::HeartbeatSample::list_since(
+            &sqlite_connection,
+            since,
+        )?;
+        Ok(NatsReply::StatsTrendsReply(StatsTrendsReply { samples }))
+    }
 
-        let ifaddrs = tokio::task::spawn_blocking(|| match nix::ifaddrs::getifaddrs() {
-            Ok(result) => result
-                .map(
-                    |v| printnanny_settings::printnanny_os_models::NetworkInterfaceAddress {
-                        interface_name: v.interface_name,
-                        flags: v.flags.bits(),
-                        address: v.address.map(|v| v.to_string()),
-                        netmask: v.netmask.map(|v| v.to_string()),
-                        destination: v.destination.map(|v| v.to_string()),
-                        broadcast: v.broadcast.map(|v| v.to_string()),
-                    },
-                )
-                .collect(),
+    pub async fn handle_tunnel_session_open(
+        request: &TunnelSessionOpenRequest,
+    ) -> Result<NatsReply> {
+        let settings = PrintNannySettings::new().await?;
+        if !settings.tunnel_enabled {
+            warn!("Rejected tunnel session: PrintNannySettings.tunnel_enabled=false");
+            return Ok(NatsReply::TunnelSessionReply(TunnelSessionReply {
+                enabled: false,
+                session: None,
+            }));
+        }
+        let session = tunnel::open_session(request.ttl_seconds, request.max_bytes);
+        Ok(NatsReply::TunnelSessionReply(TunnelSessionReply {
+            enabled: true,
+            session: Some(session),
+        }))
+    }
+
+    pub async fn handle_tunnel_session_close(
+        request: &TunnelSessionCloseRequest,
+    ) -> Result<NatsReply> {
+        let settings = PrintNannySettings::new().await?;
+        tunnel::close_session(&request.session_id);
+        Ok(NatsReply::TunnelSessionReply(TunnelSessionReply {
+            enabled: settings.tunnel_enabled,
+            session: None,
+        }))
+    }
+
+    pub async fn handle_tunnel_http(request: &TunnelHttpRequest) -> Result<NatsReply> {
+        let reply = tunnel::forward_http(request).await?;
+        Ok(NatsReply::TunnelHttpReply(reply))
+    }
+
+    pub async fn handle_shell_session_open(request: &ShellSessionOpenRequest) -> Result<NatsReply> {
+        let settings = PrintNannySettings::new().await?;
+        if !settings.remote_shell_enabled {
+            warn!("Rejected shell session: PrintNannySettings.remote_shell_enabled=false");
+            return Ok(NatsReply::ShellSessionReply(ShellSessionReply {
+                enabled: false,
+                session: None,
+            }));
+        }
+        let session =
+            shell::open_session(&settings.paths, request.ttl_seconds, &request.opened_by)?;
+        Ok(NatsReply::ShellSessionReply(ShellSessionReply {
+            enabled: true,
+            session: Some(session),
+        }))
+    }
+
+    pub async fn handle_shell_session_close(
+        request: &ShellSessionCloseRequest,
+    ) -> Result<NatsReply> {
+        let settings = PrintNannySettings::new().await?;
+        shell::close_session(&request.session_id);
+        Ok(NatsReply::ShellSessionReply(ShellSessionReply {
+            enabled: settings.remote_shell_enabled,
+            session: None,
+        }))
+    }
+
+    // session/transcript errors are reported in-band so the dashboard can
+    // render them without a second error channel
+    pub async fn handle_shell_exec(request: &ShellExecRequest) -> Result<NatsReply> {
+        let reply = match shell::exec(&request.session_id, &request.command).await {
+            Ok(output) => ShellExecReply {
+                session_id: request.session_id.clone(),
+                exit_code: output.exit_code,
+                stdout: base64::encode(&output.stdout),
+                stderr: base64::encode(&output.stderr),
+                truncated: output.truncated,
+                rejected: None,
+            },
             Err(e) => {
-                error!("Error loading ifaddrs {}", e.to_string());
-                vec![]
+                warn!("Rejected shell command: {}", e);
+                ShellExecReply {
+                    session_id: request.session_id.clone(),
+                    exit_code: None,
+                    stdout: "".to_string(),
+                    stderr: "".to_string(),
+                    truncated: false,
+                    rejected: Some(e.to_string()),
+                }
             }
-        })
-        .await?;
-        Ok(NatsReply::DeviceInfoLoadReply(DeviceInfoLoadReply {
-            issue,
-            os_release,
-            printnanny_cli_version: "".into(), // TODO
-            ifaddrs,
-        }))
+        };
+        Ok(NatsReply::ShellExecReply(reply))
     }
 
-    // handle messages sent to: "pi.{pi_id}.settings.printnanny.cloud.auth"
     pub async fn handle_printnanny_cloud_auth(
         request: &PrintNannyCloudAuthRequest,
     ) -> Result<NatsReply> {
@@ -397,7 +2077,7 @@ impl NatsRequest {
         let oid = git2::Oid::from_str(&request.git_commit)?;
         settings.git_revert_hooks(Some(oid)).await?;
         let files = vec![settings.to_payload(SettingsApp::Printnanny).await?];
-        Self::build_settings_revert_reply(request, &settings, files)
+        Self::build_settings_revert_reply(request, &settings, files).await
     }
 
     async fn handle_octoprint_settings_revert(
@@ -413,7 +2093,7 @@ impl NatsRequest {
                 .to_payload(SettingsApp::Octoprint)
                 .await?,
         ];
-        Self::build_settings_revert_reply(request, &settings, files)
+        Self::build_settings_revert_reply(request, &settings, files).await
     }
 
     async fn handle_moonraker_settings_revert(
@@ -430,7 +2110,7 @@ impl NatsRequest {
                 .to_payload(SettingsApp::Moonraker)
                 .await?,
         ];
-        Self::build_settings_revert_reply(request, &settings, files)
+        Self::build_settings_revert_reply(request, &settings, files).await
     }
 
     async fn handle_klipper_settings_revert(
@@ -442,17 +2122,21 @@ impl NatsRequest {
         let klipper_settings = settings.to_klipper_settings();
         klipper_settings.git_revert_hooks(Some(oid)).await?;
         let files = vec![klipper_settings.to_payload(SettingsApp::Klipper).await?];
-        Self::build_settings_revert_reply(request, &settings, files)
+        Self::build_settings_revert_reply(request, &settings, files).await
     }
 
-    fn build_settings_revert_reply(
+    async fn build_settings_revert_reply(
         request: &SettingsFileRevertRequest,
         settings: &PrintNannySettings,
         files: Vec<SettingsFile>,
     ) -> Result<NatsReply> {
-        let git_head_commit = settings.get_git_head_commit()?.oid;
-        let git_history: Vec<printnanny_os_models::GitCommit> =
-            settings.get_rev_list()?.iter().map(|r| r.into()).collect();
+        let git_head_commit = settings.get_git_head_commit_async().await?.oid;
+        let git_history: Vec<printnanny_os_models::GitCommit> = settings
+            .get_rev_list_async()
+            .await?
+            .iter()
+            .map(|r| r.into())
+            .collect();
         Ok(NatsReply::SettingsFileRevertReply(
             SettingsFileRevertReply {
                 app: request.app.clone(),
@@ -463,34 +2147,55 @@ impl NatsRequest {
         ))
     }
 
+    // settings writes are refused mid-update, so a rollback can't clobber a
+    // concurrently applied change
+    fn refuse_settings_apply_while_updating(settings: &PrintNannySettings) -> Result<()> {
+        let lifecycle = printnanny_services::lifecycle::load(&settings.paths);
+        match lifecycle.state {
+            printnanny_services::lifecycle::DeviceLifecycleState::Updating => Err(anyhow!(
+                "Refusing to apply settings while an OS update is in progress"
+            )),
+            _ => Ok(()),
+        }
+    }
+
     async fn handle_printnanny_settings_apply(
         request: &SettingsFileApplyRequest,
     ) -> Result<NatsReply> {
         let settings = PrintNannySettings::new().await?;
+        Self::refuse_settings_apply_while_updating(&settings)?;
+
+        // lint the candidate file before committing it: cross-field
+        // validation plus the custom graph fragment linter (element denylist,
+        // existence checks, gst::parse_launch dry-run)
+        let candidate = PrintNannySettings::from_toml_string(&request.file.content).await?;
+        printnanny_settings::validation::validate_settings(&candidate)?;
 
         settings
             .save_and_commit(&request.file.content, Some(request.git_commit_msg.clone()))
             .await?;
         let file = settings.to_payload(SettingsApp::Printnanny).await?;
-        Self::build_settings_apply_reply(request, settings, file)
+        Self::build_settings_apply_reply(request, settings, file).await
     }
 
     async fn handle_octoprint_settings_apply(
         request: &SettingsFileApplyRequest,
     ) -> Result<NatsReply> {
         let settings = PrintNannySettings::new().await?;
+        Self::refuse_settings_apply_while_updating(&settings)?;
         let octoprint_setting = settings.to_octoprint_settings();
         octoprint_setting
             .save_and_commit(&request.file.content, Some(request.git_commit_msg.clone()))
             .await?;
         let file = octoprint_setting.to_payload(SettingsApp::Octoprint).await?;
-        Self::build_settings_apply_reply(request, settings, file)
+        Self::build_settings_apply_reply(request, settings, file).await
     }
 
     async fn handle_moonraker_settings_apply(
         request: &SettingsFileApplyRequest,
     ) -> Result<NatsReply> {
         let settings = PrintNannySettings::new().await?;
+        Self::refuse_settings_apply_while_updating(&settings)?;
         let moonraker_settings = settings.to_moonraker_settings();
         moonraker_settings
             .save_and_commit(&request.file.content, Some(request.git_commit_msg.clone()))
@@ -498,29 +2203,34 @@ impl NatsRequest {
         let file = moonraker_settings
             .to_payload(SettingsApp::Moonraker)
             .await?;
-        Self::build_settings_apply_reply(request, settings, file)
+        Self::build_settings_apply_reply(request, settings, file).await
     }
 
     async fn handle_klipper_settings_apply(
         request: &SettingsFileApplyRequest,
     ) -> Result<NatsReply> {
         let settings = PrintNannySettings::new().await?;
+        Self::refuse_settings_apply_while_updating(&settings)?;
         let klipper_settings = settings.to_klipper_settings();
         klipper_settings
             .save_and_commit(&request.file.content, Some(request.git_commit_msg.clone()))
             .await?;
         let file = klipper_settings.to_payload(SettingsApp::Klipper).await?;
-        Self::build_settings_apply_reply(request, settings, file)
+        Self::build_settings_apply_reply(request, settings, file).await
     }
 
-    fn build_settings_apply_reply(
+    async fn build_settings_apply_reply(
         _request: &SettingsFileApplyRequest,
         settings: PrintNannySettings,
         file: SettingsFile,
     ) -> Result<NatsReply> {
-        let git_head_commit = settings.get_git_head_commit()?.oid;
-        let git_history: Vec<printnanny_os_models::GitCommit> =
-            settings.get_rev_list()?.iter().map(|r| r.into()).collect();
+        let git_head_commit = settings.get_git_head_commit_async().await?.oid;
+        let git_history: Vec<printnanny_os_models::GitCommit> = settings
+            .get_rev_list_async()
+            .await?
+            .iter()
+            .map(|r| r.into())
+            .collect();
         Ok(NatsReply::SettingsFileApplyReply(SettingsFileApplyReply {
             file: Box::new(file),
             git_head_commit,
@@ -566,9 +2276,13 @@ impl NatsRequest {
     pub async fn handle_settings_load() -> Result<NatsReply> {
         let settings = PrintNannySettings::new().await?;
 
-        let git_head_commit = settings.get_git_head_commit()?.oid;
-        let git_history: Vec<printnanny_os_models::GitCommit> =
-            settings.get_rev_list()?.iter().map(|r| r.into()).collect();
+        let git_head_commit = settings.get_git_head_commit_async().await?.oid;
+        let git_history: Vec<printnanny_os_models::GitCommit> = settings
+            .get_rev_list_async()
+            .await?
+            .iter()
+            .map(|r| r.into())
+            .collect();
 
         let mut files = Self::handle_printnanny_settings_load().await?;
         files.extend(Self::handle_octoprint_settings_load().await?);
@@ -598,8 +2312,8 @@ impl NatsRequest {
         if settings.video_stream != old_video_stream_settings {
             warn!("handle_cameras_load detected a hotplug change in camera settings. Saving detected configuration");
             let content = settings.to_toml_string()?;
-            let ts = SystemTime::now();
-            let commit_msg = format!("[HOTPLUG] Updated PrintNannySettings.camera @ {ts:?}");
+            let ts = EventMetadata::new().ts;
+            let commit_msg = format!("[HOTPLUG] Updated PrintNannySettings.camera @ {ts}");
             settings.save_and_commit(&content, Some(commit_msg)).await?;
             settings = PrintNannySettings::new().await?;
         }
@@ -612,10 +2326,13 @@ impl NatsRequest {
         info!("Received request: {:#?}", request);
         let mut settings = PrintNannySettings::new().await?;
 
+        let privacy_mode = settings.video_stream.privacy_mode;
         settings.video_stream = request.clone().into();
+        // privacy_mode is device-local state, preserve it across cloud settings applies
+        settings.video_stream.privacy_mode = privacy_mode;
         let content = settings.to_toml_string()?;
-        let ts = SystemTime::now();
-        let commit_msg = format!("Updated PrintNannySettings.camera @ {ts:?}");
+        let ts = EventMetadata::new().ts;
+        let commit_msg = format!("Updated PrintNannySettings.camera @ {ts}");
         settings.save_and_commit(&content, Some(commit_msg)).await?;
         // stop gstreamer pipelines
         let factory: PrintNannyPipelineFactory = PrintNannyPipelineFactory::default();
@@ -845,6 +2562,48 @@ impl NatsRequest {
     }
 }
 
+impl NatsReply {
+    // strip payload echoes and git history from bulky replies, leaving minimal
+    // status; applied when PrintNannySettings.reply_detail is "terse"
+    fn into_terse(self) -> NatsReply {
+        match self {
+            NatsReply::SettingsFileLoadReply(mut reply) => {
+                for file in reply.files.iter_mut() {
+                    file.content = "".to_string();
+                }
+                reply.git_history = vec![];
+                NatsReply::SettingsFileLoadReply(reply)
+            }
+            NatsReply::SettingsFileApplyReply(mut reply) => {
+                reply.file.content = "".to_string();
+                reply.git_history = vec![];
+                NatsReply::SettingsFileApplyReply(reply)
+            }
+            NatsReply::SettingsFileRevertReply(mut reply) => {
+                for file in reply.files.iter_mut() {
+                    file.content = "".to_string();
+                }
+                reply.git_history = vec![];
+                NatsReply::SettingsFileRevertReply(reply)
+            }
+            NatsReply::CameraRecordingLoadReply(mut reply) => {
+                reply.parts = None;
+                NatsReply::CameraRecordingLoadReply(reply)
+            }
+            NatsReply::CameraControlsReply(mut reply) => {
+                reply.controls = vec![];
+                NatsReply::CameraControlsReply(reply)
+            }
+            NatsReply::OctoPrintPluginChangedReply(mut reply) => {
+                // pip output runs to hundreds of lines
+                reply.detail = "".to_string();
+                NatsReply::OctoPrintPluginChangedReply(reply)
+            }
+            other => other,
+        }
+    }
+}
+
 #[async_trait]
 impl NatsRequestHandler for NatsRequest {
     type Request = NatsRequest;
@@ -866,7 +2625,156 @@ impl NatsRequestHandler for NatsRequest {
                 serde_json::from_slice::<CrashReportOsLogsRequest>(payload.as_ref())?,
             )),
             "pi.{pi_id}.cameras.load" => Ok(NatsRequest::CameraLoadRequest),
+            "pi.{pi_id}.command.camera.privacy.enable" => {
+                Ok(NatsRequest::CameraPrivacyEnableRequest)
+            }
+            "pi.{pi_id}.command.camera.privacy.disable" => {
+                Ok(NatsRequest::CameraPrivacyDisableRequest)
+            }
+            "pi.{pi_id}.command.camera.overlay.enable" => {
+                Ok(NatsRequest::CameraOverlayEnableRequest)
+            }
+            "pi.{pi_id}.command.camera.overlay.disable" => {
+                Ok(NatsRequest::CameraOverlayDisableRequest)
+            }
+            "pi.{pi_id}.command.camera.stream.pause" => Ok(NatsRequest::CameraStreamPauseRequest),
+            "pi.{pi_id}.command.camera.stream.resume" => Ok(NatsRequest::CameraStreamResumeRequest),
+            "pi.{pi_id}.camera.stream.viewers" => Ok(NatsRequest::CameraStreamViewersRequest(
+                serde_json::from_slice::<CameraStreamViewersRequest>(payload.as_ref())?,
+            )),
+            "pi.{pi_id}.camera.rtp.destinations.get" => {
+                Ok(NatsRequest::CameraRtpDestinationsGetRequest)
+            }
+            "pi.{pi_id}.camera.rtp.destinations.set" => {
+                Ok(NatsRequest::CameraRtpDestinationsSetRequest(
+                    serde_json::from_slice::<CameraRtpDestinationsRequest>(payload.as_ref())?,
+                ))
+            }
+            "pi.{pi_id}.camera.controls.get" => Ok(NatsRequest::CameraControlsGetRequest),
+            "pi.{pi_id}.camera.controls.set" => Ok(NatsRequest::CameraControlsSetRequest(
+                serde_json::from_slice::<CameraControlSettings>(payload.as_ref())?,
+            )),
+            "pi.{pi_id}.camera.clip" => {
+                Ok(NatsRequest::CameraClipRequest(serde_json::from_slice::<
+                    CameraClipRequest,
+                >(
+                    payload.as_ref()
+                )?))
+            }
+            "pi.{pi_id}.lights.on" => Ok(NatsRequest::LightsOnRequest),
+            "pi.{pi_id}.lights.off" => Ok(NatsRequest::LightsOffRequest),
+            "pi.{pi_id}.identity.pubkey" => Ok(NatsRequest::DeviceIdentityRequest(
+                serde_json::from_slice::<DeviceIdentityRequest>(payload.as_ref())?,
+            )),
             "pi.{pi_id}.device_info.load" => Ok(NatsRequest::DeviceInfoLoadRequest),
+            "pi.{pi_id}.capabilities" => Ok(NatsRequest::CapabilitiesRequest),
+            "pi.{pi_id}.command.device.decommission" => Ok(NatsRequest::DeviceDecommissionRequest(
+                serde_json::from_slice::<DeviceDecommissionRequest>(payload.as_ref())?,
+            )),
+            "pi.{pi_id}.command.device.schedule" => {
+                Ok(NatsRequest::DeviceCommandRequest(serde_json::from_slice::<
+                    DeviceCommandRequest,
+                >(
+                    payload.as_ref()
+                )?))
+            }
+            "pi.{pi_id}.status.summary" => Ok(NatsRequest::StatusSummaryRequest),
+            "pi.{pi_id}.farm.overview" => Ok(NatsRequest::FarmOverviewRequest),
+            "pi.{pi_id}.schedule.list" => Ok(NatsRequest::ScheduleListRequest),
+            "pi.{pi_id}.jobs.get" => Ok(NatsRequest::JobsGetRequest(serde_json::from_slice::<
+                JobsGetRequest,
+            >(
+                payload.as_ref()
+            )?)),
+            "pi.{pi_id}.jobs.list" => Ok(NatsRequest::JobsListRequest(serde_json::from_slice::<
+                JobsListRequest,
+            >(
+                payload.as_ref()
+            )?)),
+            "pi.{pi_id}.network.wol" => {
+                Ok(NatsRequest::WolRequest(
+                    serde_json::from_slice::<WolRequest>(payload.as_ref())?,
+                ))
+            }
+            "pi.{pi_id}.command.nats.rotate_creds" => Ok(NatsRequest::NatsCredsRotateRequest),
+            "pi.{pi_id}.debug.trace.enable" => Ok(NatsRequest::DebugTraceEnableRequest(
+                serde_json::from_slice::<DebugTraceEnableRequest>(payload.as_ref())?,
+            )),
+            "pi.{pi_id}.debug.trace.disable" => Ok(NatsRequest::DebugTraceDisableRequest),
+            "pi.{pi_id}.debug.trace.dump" => Ok(NatsRequest::DebugTraceDumpRequest),
+            "pi.{pi_id}.detections.feedback" => Ok(NatsRequest::DetectionFeedbackRequest(
+                serde_json::from_slice::<DetectionFeedbackRequest>(payload.as_ref())?,
+            )),
+            "pi.{pi_id}.detections.feedback.sync" => Ok(NatsRequest::DetectionFeedbackSyncRequest),
+            "pi.{pi_id}.detections.evaluation_report" => {
+                Ok(NatsRequest::ModelEvaluationReportRequest(
+                    serde_json::from_slice::<ModelEvaluationReportRequest>(payload.as_ref())?,
+                ))
+            }
+            "pi.{pi_id}.detection.tune" => {
+                Ok(NatsRequest::DetectionTuneRequest(serde_json::from_slice::<
+                    DetectionTuneRequest,
+                >(
+                    payload.as_ref()
+                )?))
+            }
+            "pi.{pi_id}.detection.calibrate" => Ok(NatsRequest::DetectionCalibrateRequest(
+                serde_json::from_slice::<DetectionCalibrateRequest>(payload.as_ref())?,
+            )),
+            "pi.{pi_id}.octoprint.plugins.list" => Ok(NatsRequest::OctoPrintPluginsListRequest),
+            "pi.{pi_id}.octoprint.plugins.install" => {
+                Ok(NatsRequest::OctoPrintPluginInstallRequest(
+                    serde_json::from_slice::<OctoPrintPluginInstallRequest>(payload.as_ref())?,
+                ))
+            }
+            "pi.{pi_id}.octoprint.plugins.uninstall" => {
+                Ok(NatsRequest::OctoPrintPluginUninstallRequest(
+                    serde_json::from_slice::<OctoPrintPluginUninstallRequest>(payload.as_ref())?,
+                ))
+            }
+            "pi.{pi_id}.octoprint.backup.create" => Ok(NatsRequest::OctoPrintBackupCreateRequest(
+                serde_json::from_slice::<OctoPrintBackupCreateRequest>(payload.as_ref())?,
+            )),
+            "pi.{pi_id}.octoprint.backup.restore" => {
+                Ok(NatsRequest::OctoPrintBackupRestoreRequest(
+                    serde_json::from_slice::<OctoPrintBackupRestoreRequest>(payload.as_ref())?,
+                ))
+            }
+            "pi.{pi_id}.stats.bandwidth" => Ok(NatsRequest::BandwidthStatsRequest(
+                serde_json::from_slice::<BandwidthStatsRequest>(payload.as_ref())?,
+            )),
+            "pi.{pi_id}.stats.storage" => Ok(NatsRequest::StorageStatsRequest),
+            "pi.{pi_id}.stats.trends" => {
+                Ok(NatsRequest::StatsTrendsRequest(serde_json::from_slice::<
+                    StatsTrendsRequest,
+                >(
+                    payload.as_ref()
+                )?))
+            }
+            "pi.{pi_id}.tunnel.session.open" => Ok(NatsRequest::TunnelSessionOpenRequest(
+                serde_json::from_slice::<TunnelSessionOpenRequest>(payload.as_ref())?,
+            )),
+            "pi.{pi_id}.tunnel.session.close" => Ok(NatsRequest::TunnelSessionCloseRequest(
+                serde_json::from_slice::<TunnelSessionCloseRequest>(payload.as_ref())?,
+            )),
+            "pi.{pi_id}.tunnel.http" => {
+                Ok(NatsRequest::TunnelHttpRequest(serde_json::from_slice::<
+                    TunnelHttpRequest,
+                >(
+                    payload.as_ref()
+                )?))
+            }
+            "pi.{pi_id}.shell.session.open" => Ok(NatsRequest::ShellSessionOpenRequest(
+                serde_json::from_slice::<ShellSessionOpenRequest>(payload.as_ref())?,
+            )),
+            "pi.{pi_id}.shell.session.close" => Ok(NatsRequest::ShellSessionCloseRequest(
+                serde_json::from_slice::<ShellSessionCloseRequest>(payload.as_ref())?,
+            )),
+            "pi.{pi_id}.shell.exec" => Ok(NatsRequest::ShellExecRequest(serde_json::from_slice::<
+                ShellExecRequest,
+            >(
+                payload.as_ref()
+            )?)),
             "pi.{pi_id}.settings.printnanny.cloud.auth" => {
                 Ok(NatsRequest::PrintNannyCloudAuthRequest(
                     serde_json::from_slice::<PrintNannyCloudAuthRequest>(payload.as_ref())?,
@@ -920,6 +2828,13 @@ impl NatsRequestHandler for NatsRequest {
                     serde_json::from_slice::<SystemdManagerStopUnitRequest>(payload.as_ref())?,
                 ))
             }
+            // third-party handlers registered under pi.{pi_id}.plugin.<name>.>
+            other if other.starts_with("pi.{pi_id}.plugin.") => {
+                Ok(NatsRequest::PluginRequest(PluginRequest {
+                    subject_pattern: other.to_string(),
+                    payload_b64: base64::encode(payload),
+                }))
+            }
             _ => Err(anyhow!(
                 "NATS message handler not implemented for subject pattern {}",
                 subject_pattern
@@ -929,7 +2844,7 @@ impl NatsRequestHandler for NatsRequest {
 
     // Request handlers with blocking I/O should be run with tokio::task::spawn_blocking
     async fn handle(&self) -> Result<Self::Reply> {
-        match self {
+        let reply = match self {
             // pi.{pi_id}.command.camera.recording.start
             NatsRequest::CameraRecordingStartRequest => Self::handle_camera_recording_start().await,
             // pi.{pi_id}.command.camera.recording.stop
@@ -940,6 +2855,46 @@ impl NatsRequestHandler for NatsRequest {
             NatsRequest::PrintNannyCloudSyncRequest => Self::handle_cloud_sync().await,
             // pi.{pi_id}.cameras.load
             NatsRequest::CameraLoadRequest => Self::handle_cameras_load().await,
+            // pi.{pi_id}.command.camera.privacy.enable
+            NatsRequest::CameraPrivacyEnableRequest => Self::handle_camera_privacy(true).await,
+            // pi.{pi_id}.command.camera.privacy.disable
+            NatsRequest::CameraPrivacyDisableRequest => Self::handle_camera_privacy(false).await,
+            // pi.{pi_id}.command.camera.overlay.enable
+            NatsRequest::CameraOverlayEnableRequest => Self::handle_camera_overlay(true).await,
+            // pi.{pi_id}.command.camera.overlay.disable
+            NatsRequest::CameraOverlayDisableRequest => Self::handle_camera_overlay(false).await,
+            // pi.{pi_id}.command.camera.stream.pause
+            NatsRequest::CameraStreamPauseRequest => Self::handle_camera_stream_pause(true).await,
+            // pi.{pi_id}.command.camera.stream.resume
+            NatsRequest::CameraStreamResumeRequest => Self::handle_camera_stream_pause(false).await,
+            // pi.{pi_id}.camera.stream.viewers
+            NatsRequest::CameraStreamViewersRequest(request) => {
+                Self::handle_camera_stream_viewers(request).await
+            }
+            // pi.{pi_id}.camera.rtp.destinations.get
+            NatsRequest::CameraRtpDestinationsGetRequest => {
+                Self::handle_camera_rtp_destinations_get().await
+            }
+            // pi.{pi_id}.camera.rtp.destinations.set
+            NatsRequest::CameraRtpDestinationsSetRequest(request) => {
+                Self::handle_camera_rtp_destinations_set(request).await
+            }
+            // pi.{pi_id}.camera.controls.get
+            NatsRequest::CameraControlsGetRequest => Self::handle_camera_controls_get().await,
+            // pi.{pi_id}.camera.controls.set
+            NatsRequest::CameraControlsSetRequest(request) => {
+                Self::handle_camera_controls_set(request).await
+            }
+            // pi.{pi_id}.camera.clip
+            NatsRequest::CameraClipRequest(request) => Self::handle_camera_clip(request).await,
+            // pi.{pi_id}.lights.on
+            NatsRequest::LightsOnRequest => Self::handle_lights(true).await,
+            // pi.{pi_id}.lights.off
+            NatsRequest::LightsOffRequest => Self::handle_lights(false).await,
+            // pi.{pi_id}.identity.pubkey
+            NatsRequest::DeviceIdentityRequest(request) => {
+                Self::handle_device_identity(request).await
+            }
             // pi.{pi_id}.settings.camera.status
             NatsRequest::CameraStatusRequest => Self::handle_camera_status().await,
             // "pi.{pi_id}.crash_reports.os"
@@ -948,6 +2903,93 @@ impl NatsRequestHandler for NatsRequest {
             }
             // pi.{pi_id}.device_info.load
             NatsRequest::DeviceInfoLoadRequest => Self::handle_device_info_load().await,
+            NatsRequest::CapabilitiesRequest => Self::handle_capabilities().await,
+            NatsRequest::DeviceDecommissionRequest(request) => {
+                Self::handle_device_decommission(request).await
+            }
+            NatsRequest::DeviceCommandRequest(request) => {
+                Self::handle_device_command(request).await
+            }
+            NatsRequest::StatusSummaryRequest => Self::handle_status_summary().await,
+            NatsRequest::FarmOverviewRequest => Self::handle_farm_overview().await,
+            NatsRequest::ScheduleListRequest => Self::handle_schedule_list().await,
+
+            // pi.{pi_id}.jobs.*
+            NatsRequest::JobsGetRequest(request) => Self::handle_jobs_get(request).await,
+            NatsRequest::JobsListRequest(request) => Self::handle_jobs_list(request).await,
+            NatsRequest::WolRequest(request) => Self::handle_wol(request).await,
+            NatsRequest::NatsCredsRotateRequest => Self::handle_nats_creds_rotate().await,
+            NatsRequest::DebugTraceEnableRequest(request) => {
+                Self::handle_debug_trace_enable(request).await
+            }
+            NatsRequest::DebugTraceDisableRequest => Self::handle_debug_trace_disable().await,
+            NatsRequest::DebugTraceDumpRequest => Self::handle_debug_trace_dump().await,
+
+            // pi.{pi_id}.detections.feedback.*
+            NatsRequest::DetectionFeedbackRequest(request) => {
+                Self::handle_detection_feedback(request).await
+            }
+            NatsRequest::DetectionFeedbackSyncRequest => {
+                Self::handle_detection_feedback_sync().await
+            }
+            NatsRequest::ModelEvaluationReportRequest(request) => {
+                Self::handle_model_evaluation_report(request).await
+            }
+
+            // pi.{pi_id}.detection.tune
+            NatsRequest::DetectionTuneRequest(request) => {
+                Self::handle_detection_tune(request).await
+            }
+
+            // pi.{pi_id}.detection.calibrate
+            NatsRequest::DetectionCalibrateRequest(request) => {
+                Self::handle_detection_calibrate(request).await
+            }
+
+            // pi.{pi_id}.octoprint.plugins.*
+            NatsRequest::OctoPrintPluginsListRequest => Self::handle_octoprint_plugins_list().await,
+            NatsRequest::OctoPrintPluginInstallRequest(request) => {
+                Self::handle_octoprint_plugin_install(request).await
+            }
+            NatsRequest::OctoPrintPluginUninstallRequest(request) => {
+                Self::handle_octoprint_plugin_uninstall(request).await
+            }
+
+            // pi.{pi_id}.octoprint.backup.*
+            NatsRequest::OctoPrintBackupCreateRequest(request) => {
+                Self::handle_octoprint_backup_create(request).await
+            }
+            NatsRequest::OctoPrintBackupRestoreRequest(request) => {
+                Self::handle_octoprint_backup_restore(request).await
+            }
+
+            // pi.{pi_id}.plugin.<name>.*
+            NatsRequest::PluginRequest(request) => Self::handle_plugin(request).await,
+
+            // pi.{pi_id}.stats.bandwidth
+            NatsRequest::BandwidthStatsRequest(request) => {
+                Self::handle_bandwidth_stats(request).await
+            }
+            NatsRequest::StorageStatsRequest => Self::handle_storage_stats().await,
+            NatsRequest::StatsTrendsRequest(request) => Self::handle_stats_trends(request).await,
+
+            // pi.{pi_id}.tunnel.*
+            NatsRequest::TunnelSessionOpenRequest(request) => {
+                Self::handle_tunnel_session_open(request).await
+            }
+            NatsRequest::TunnelSessionCloseRequest(request) => {
+                Self::handle_tunnel_session_close(request).await
+            }
+            NatsRequest::TunnelHttpRequest(request) => Self::handle_tunnel_http(request).await,
+
+            // pi.{pi_id}.shell.*
+            NatsRequest::ShellSessionOpenRequest(request) => {
+                Self::handle_shell_session_open(request).await
+            }
+            NatsRequest::ShellSessionCloseRequest(request) => {
+                Self::handle_shell_session_close(request).await
+            }
+            NatsRequest::ShellExecRequest(request) => Self::handle_shell_exec(request).await,
 
             // pi.{pi_id}.settings.*
             NatsRequest::PrintNannyCloudAuthRequest(request) => {
@@ -988,7 +3030,14 @@ impl NatsRequestHandler for NatsRequest {
             NatsRequest::SystemdManagerStopUnitRequest(request) => {
                 Self::handle_stop_unit_request(request).await
             }
-        }
+        }?;
+        // replies can echo full settings files plus git history; terse mode
+        // strips them to save bandwidth on metered connections
+        let reply = match PrintNannySettings::new().await?.reply_detail {
+            ReplyDetailLevel::Terse => reply.into_terse(),
+            ReplyDetailLevel::Verbose => reply,
+        };
+        Ok(reply)
     }
 }
 
@@ -0,0 +1,221 @@
+// Cron-style scheduler inside the edge worker: every minute, tasks from
+// PrintNannySettings.schedule.tasks whose cron expression matches are run.
+// Built-in actions (settings backup, retention prune, cloud sync) come from
+// printnanny_services::schedule; user-defined tasks publish an arbitrary NATS
+// payload. Outcomes are kept in a registry so pi.{pi_id}.schedule.list can
+// report the next run and the last result per task.
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, Local, Timelike, Utc};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+
+use printnanny_nats_client::client::try_init_nats_client;
+use printnanny_services::printnanny_api::ApiService;
+use printnanny_settings::printnanny::PrintNannySettings;
+use printnanny_settings::schedule::{CronExpr, ScheduledAction};
+
+const TICK_INTERVAL: Duration = Duration::from_secs(60);
+
+// last outcome per task name, for the schedule.list reply
+static TASK_OUTCOMES: Mutex<Option<HashMap<String, TaskOutcome>>> = Mutex::new(None);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TaskOutcome {
+    pub last_run_dt: DateTime<Utc>,
+    pub success: bool,
+    pub detail: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScheduledTaskStatus {
+    pub name: String,
+    pub cron: String,
+    // RFC3339 local time of the next run; None for an invalid cron expression
+    pub next_run_dt: Option<String>,
+    pub last_outcome: Option<TaskOutcome>,
+}
+
+fn record_outcome(name: &str, success: bool, detail: String) {
+    let mut outcomes = TASK_OUTCOMES.lock().unwrap();
+    outcomes.get_or_insert_with(HashMap::new).insert(
+        name.to_string(),
+        TaskOutcome {
+            last_run_dt: Utc::now(),
+            success,
+            detail,
+        },
+    );
+}
+
+fn last_outcome(name: &str) -> Option<TaskOutcome> {
+    let mut outcomes = TASK_OUTCOMES.lock().unwrap();
+    outcomes.get_or_insert_with(HashMap::new).get(name).cloned()
+}
+
+fn expr_matches(expr: &CronExpr, dt: &DateTime<Local>) -> bool {
+    expr.matches(
+        dt.minute(),
+        dt.hour(),
+        dt.day(),
+        dt.month(),
+        dt.weekday().num_days_from_sunday(),
+    )
+}
+
+// truncate to the start of the minute the scheduler ticks on
+fn minute_of(dt: DateTime<Local>) -> DateTime<Local> {
+    dt.with_second(0).unwrap().with_nanosecond(0).unwrap()
+}
+
+// scan forward minute by minute; any valid 5-field expression fires within a
+// year, so the scan is bounded to 366 days
+pub fn next_match(expr: &CronExpr, from: DateTime<Local>) -> Option<DateTime<Local>> {
+    let start = minute_of(from) + chrono::Duration::minutes(1);
+    (0..366 * 24 * 60)
+        .map(|offset| start + chrono::Duration::minutes(offset))
+        .find(|dt| expr_matches(expr, dt))
+}
+
+async fn run_action(
+    settings: &PrintNannySettings,
+    action: &ScheduledAction,
+) -> anyhow::Result<String> {
+    match action {
+        ScheduledAction::SettingsBackup => {
+            let output = printnanny_services::schedule::settings_backup(settings)?;
+            Ok(format!("wrote {}", output.display()))
+        }
+        ScheduledAction::RetentionPrune { days } => {
+            let removed = printnanny_services::schedule::retention_prune(settings, *days);
+            // per-category quotas are enforced on the same cadence as age pruning
+            let evicted = printnanny_services::schedule::quota_prune(settings);
+            Ok(format!(
+                "removed {} files older than {} days, evicted {} over-quota files",
+                removed, days, evicted
+            ))
+        }
+        ScheduledAction::CloudSync => {
+            ApiService::from(settings).sync().await?;
+            Ok("cloud sync completed".to_string())
+        }
+        ScheduledAction::NatsPublish { subject, payload } => {
+            let nats_client = try_init_nats_client(
+                &settings.video_stream.detection.nats_server_uri,
+                &None,
+                false,
+            )
+            .await?;
+            nats_client
+                .publish(subject.clone(), payload.clone().into_bytes().into())
+                .await?;
+            Ok(format!("published {}", subject))
+        }
+    }
+}
+
+// schedule.list view: configured tasks with their next run and last outcome
+pub fn task_statuses(settings: &PrintNannySettings) -> Vec<ScheduledTaskStatus> {
+    let now = Local::now();
+    settings
+        .schedule
+        .tasks
+        .iter()
+        .map(|task| {
+            let next_run_dt = CronExpr::parse(&task.cron)
+                .ok()
+                .and_then(|expr| next_match(&expr, now))
+                .map(|dt| dt.to_rfc3339());
+            ScheduledTaskStatus {
+                name: task.name.clone(),
+                cron: task.cron.clone(),
+                next_run_dt,
+                last_outcome: last_outcome(&task.name),
+            }
+        })
+        .collect()
+}
+
+async fn tick(now: &DateTime<Local>) {
+    let settings = match PrintNannySettings::new().await {
+        Ok(settings) => settings,
+        Err(e) => {
+            error!("Failed to load PrintNannySettings error={}", e);
+            return;
+        }
+    };
+    for task in &settings.schedule.tasks {
+        let expr = match CronExpr::parse(&task.cron) {
+            Ok(expr) => expr,
+            Err(e) => {
+                warn!("Skipping scheduled task {}: {}", task.name, e);
+                continue;
+            }
+        };
+        if !expr_matches(&expr, now) {
+            continue;
+        }
+        info!("Running scheduled task {}", task.name);
+        match run_action(&settings, &task.action).await {
+            Ok(detail) => {
+                info!("Scheduled task {} finished: {}", task.name, detail);
+                record_outcome(&task.name, true, detail);
+            }
+            Err(e) => {
+                error!("Scheduled task {} failed: {}", task.name, e);
+                record_outcome(&task.name, false, e.to_string());
+            }
+        }
+    }
+}
+
+pub async fn run_scheduler() {
+    let mut interval = tokio::time::interval(TICK_INTERVAL);
+    // guard against firing twice within one wall-clock minute if ticks drift
+    let mut last_minute: Option<DateTime<Local>> = None;
+    loop {
+        interval.tick().await;
+        let now = minute_of(Local::now());
+        if last_minute == Some(now) {
+            continue;
+        }
+        last_minute = Some(now);
+        tick(&now).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test_log::test]
+    fn test_next_match_daily_expression() {
+        let expr = CronExpr::parse("0 3 * * *").unwrap();
+        let from = Local.with_ymd_and_hms(2024, 6, 10, 12, 30, 45).unwrap();
+        let next = next_match(&expr, from).unwrap();
+        assert_eq!(next, Local.with_ymd_and_hms(2024, 6, 11, 3, 0, 0).unwrap());
+    }
+
+    #[test_log::test]
+    fn test_next_match_skips_current_minute() {
+        let expr = CronExpr::parse("*/5 * * * *").unwrap();
+        let from = Local.with_ymd_and_hms(2024, 6, 10, 12, 5, 0).unwrap();
+        let next = next_match(&expr, from).unwrap();
+        assert_eq!(
+            next,
+            Local.with_ymd_and_hms(2024, 6, 10, 12, 10, 0).unwrap()
+        );
+    }
+
+    #[test_log::test]
+    fn test_outcome_registry_roundtrip() {
+        record_outcome("backup", true, "wrote /tmp/backup.zip".to_string());
+        let outcome = last_outcome("backup").unwrap();
+        assert!(outcome.success);
+        assert_eq!(outcome.detail, "wrote /tmp/backup.zip");
+        assert!(last_outcome("no-such-task").is_none());
+    }
+}
@@ -0,0 +1,218 @@
+// Boot-time self-test: probes the camera, the V4L2 encoder, the sqlite
+// database, the settings git repo, and PrintNanny Cloud once at worker
+// startup, then publishes the pass/fail matrix on pi.{pi_id}.status.boot so
+// fleet dashboards can spot sick devices right after a reboot.
+use std::time::Duration;
+
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+
+use printnanny_gst_pipelines::factory::V4L2_H264_ENCODER_DEVICE;
+use printnanny_services::doctor;
+use printnanny_services::metadata::EventMetadata;
+use printnanny_settings::cam::CameraVideoSource;
+use printnanny_settings::printnanny::PrintNannySettings;
+use printnanny_settings::vcs::VersionControlledSettings;
+
+// an unreachable cloud should not hold up the rest of the report
+const CLOUD_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SelfTestCheck {
+    pub name: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+impl SelfTestCheck {
+    fn new(name: &str, passed: bool, detail: String) -> Self {
+        Self {
+            name: name.to_string(),
+            passed,
+            detail,
+        }
+    }
+}
+
+// published on pi.{pi_id}.status.boot; device-local report shape, there is no
+// boot status model in the generated printnanny-os-models crate (yet)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SelfTestReport {
+    pub metadata: EventMetadata,
+    pub passed: bool,
+    pub checks: Vec<SelfTestCheck>,
+}
+
+fn report_from_checks(checks: Vec<SelfTestCheck>) -> SelfTestReport {
+    let passed = checks.iter().all(|check| check.passed);
+    SelfTestReport {
+        metadata: EventMetadata::new(),
+        passed,
+        checks,
+    }
+}
+
+async fn check_camera() -> SelfTestCheck {
+    match CameraVideoSource::from_libcamera_list().await {
+        Ok(cameras) if cameras.is_empty() => SelfTestCheck::new(
+            "camera_present",
+            false,
+            "list-cameras detected no cameras".to_string(),
+        ),
+        Ok(cameras) => SelfTestCheck::new(
+            "camera_present",
+            true,
+            format!("detected {} camera(s)", cameras.len()),
+        ),
+        Err(e) => SelfTestCheck::new(
+            "camera_present",
+            false,
+            format!("list-cameras failed: {}", e),
+        ),
+    }
+}
+
+fn check_encoder() -> SelfTestCheck {
+    let present = std::path::Path::new(V4L2_H264_ENCODER_DEVICE).exists();
+    let detail = match present {
+        true => format!("{} present", V4L2_H264_ENCODER_DEVICE),
+        false => format!(
+            "{} missing, pipelines will fall back to software encoding",
+            V4L2_H264_ENCODER_DEVICE
+        ),
+    };
+    SelfTestCheck::new("h264_encoder", present, detail)
+}
+
+fn check_db(settings: &PrintNannySettings) -> SelfTestCheck {
+    let sqlite_connection = settings.paths.db().display().to_string();
+    match printnanny_edge_db::connection::sqlite_connection_pool(&sqlite_connection).get() {
+        Ok(_) => SelfTestCheck::new("db_opens", true, sqlite_connection),
+        Err(e) => SelfTestCheck::new("db_opens", false, format!("{}: {}", sqlite_connection, e)),
+    }
+}
+
+fn check_settings_repo(settings: &PrintNannySettings) -> SelfTestCheck {
+    match settings.git_diff() {
+        Ok(diff) if diff.trim().is_empty() => SelfTestCheck::new(
+            "settings_repo_clean",
+            true,
+            "no uncommitted changes".to_string(),
+        ),
+        Ok(_) => SelfTestCheck::new(
+            "settings_repo_clean",
+            false,
+            "uncommitted changes in settings repo".to_string(),
+        ),
+        Err(e) => SelfTestCheck::new(
+            "settings_repo_clean",
+            false,
+            format!("git diff failed: {}", e),
+        ),
+    }
+}
+
+// any HTTP response counts as reachable; authentication is a separate concern
+async fn check_cloud(settings: &PrintNannySettings) -> SelfTestCheck {
+    let base_path = &settings.cloud.api_base_path;
+    let client = match reqwest::Client::builder().timeout(CLOUD_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            return SelfTestCheck::new(
+                "cloud_reachable",
+                false,
+                format!("failed to build http client: {}", e),
+            )
+        }
+    };
+    match client.get(base_path).send().await {
+        Ok(res) => SelfTestCheck::new(
+            "cloud_reachable",
+            true,
+            format!("{} responded with {}", base_path, res.status()),
+        ),
+        Err(e) => SelfTestCheck::new(
+            "cloud_reachable",
+            false,
+            format!("{} unreachable: {}", base_path, e),
+        ),
+    }
+}
+
+fn check_paths(settings: &PrintNannySettings) -> SelfTestCheck {
+    let unwritable: Vec<String> = doctor::audit_writable_paths(settings)
+        .into_iter()
+        .filter(|check| !check.writable && check.fallback.is_none())
+        .map(|check| format!("{} ({})", check.name, check.path.display()))
+        .collect();
+    match unwritable.is_empty() {
+        true => SelfTestCheck::new(
+            "paths_writable",
+            true,
+            "all service paths are writable".to_string(),
+        ),
+        false => SelfTestCheck::new("paths_writable", false, unwritable.join(", ")),
+    }
+}
+
+pub async fn build_report(settings: &PrintNannySettings) -> SelfTestReport {
+    let checks = vec![
+        check_camera().await,
+        check_encoder(),
+        check_db(settings),
+        check_settings_repo(settings),
+        check_cloud(settings).await,
+        check_paths(settings),
+    ];
+    report_from_checks(checks)
+}
+
+// one-shot startup task: run every check and publish the report on the bus
+pub async fn run_boot_self_test() {
+    let settings = match PrintNannySettings::new().await {
+        Ok(settings) => settings,
+        Err(e) => {
+            error!(
+                "Failed to load PrintNannySettings, skipping boot self-test: {}",
+                e
+            );
+            return;
+        }
+    };
+    let report = build_report(&settings).await;
+    for check in report.checks.iter().filter(|check| !check.passed) {
+        warn!(
+            "Boot self-test check {} failed: {}",
+            check.name, check.detail
+        );
+    }
+    if report.passed {
+        info!("Boot self-test passed all {} checks", report.checks.len());
+    }
+    crate::bus::publish(crate::bus::BusEvent::BootSelfTest(report));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_report_fails_when_any_check_fails() {
+        let report = report_from_checks(vec![
+            SelfTestCheck::new("camera_present", true, "detected 1 camera(s)".to_string()),
+            SelfTestCheck::new(
+                "db_opens",
+                false,
+                "unable to open database file".to_string(),
+            ),
+        ]);
+        assert!(!report.passed);
+
+        let report = report_from_checks(vec![SelfTestCheck::new(
+            "camera_present",
+            true,
+            "detected 1 camera(s)".to_string(),
+        )]);
+        assert!(report.passed);
+    }
+}
@@ -0,0 +1,257 @@
+// Brokered remote support shell. Instead of asking users to expose SSH, the
+// cloud dashboard requests a session after the device owner approves it; the
+// grant is time-limited, each command runs through `sh -c` with its output
+// capped, and every command + result is appended to a local transcript before
+// it runs, so the owner can audit exactly what support did. The master opt-in
+// is PrintNannySettings.remote_shell_enabled (default off).
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::Utc;
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use printnanny_settings::paths::PrintNannyPaths;
+
+// support sessions default to 30 minutes and are capped at 4 hours
+pub const DEFAULT_SESSION_TTL_SECONDS: u64 = 30 * 60;
+pub const MAX_SESSION_TTL_SECONDS: u64 = 4 * 60 * 60;
+// per-stream output cap so one command cannot blow the NATS payload limit
+pub const MAX_OUTPUT_BYTES: usize = 256 * 1024;
+
+static SHELL_SESSIONS: Mutex<Option<HashMap<String, ShellSession>>> = Mutex::new(None);
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShellSession {
+    pub id: String,
+    // unix seconds
+    pub expires_at: u64,
+    // dashboard identity of whoever the owner approved, recorded for the audit trail
+    pub opened_by: String,
+    pub transcript: PathBuf,
+}
+
+impl ShellSession {
+    pub fn expired(&self, now: u64) -> bool {
+        now >= self.expires_at
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ShellSessionError {
+    #[error("Shell session {session_id} not found or expired")]
+    NotFound { session_id: String },
+    // commands are logged before they run; an unwritable transcript means no execution
+    #[error("Failed to write shell session transcript {path}: {error}")]
+    TranscriptIoError { path: String, error: std::io::Error },
+    #[error("Failed to spawn shell command: {error}")]
+    SpawnError { error: std::io::Error },
+}
+
+pub struct ShellExecOutput {
+    pub exit_code: Option<i32>,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+    // set when stdout or stderr was cut at MAX_OUTPUT_BYTES
+    pub truncated: bool,
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn append_transcript(path: &PathBuf, entry: &str) -> Result<(), ShellSessionError> {
+    let result = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut f| writeln!(f, "{}", entry));
+    result.map_err(|error| ShellSessionError::TranscriptIoError {
+        path: path.display().to_string(),
+        error,
+    })
+}
+
+pub fn open_session(
+    paths: &PrintNannyPaths,
+    ttl_seconds: Option<u64>,
+    opened_by: &str,
+) -> Result<ShellSession, ShellSessionError> {
+    let ttl = ttl_seconds
+        .unwrap_or(DEFAULT_SESSION_TTL_SECONDS)
+        .min(MAX_SESSION_TTL_SECONDS);
+    let id = uuid::Uuid::new_v4().to_string();
+    let transcript_dir = paths.shell_transcripts();
+    std::fs::create_dir_all(&transcript_dir).map_err(|error| {
+        ShellSessionError::TranscriptIoError {
+            path: transcript_dir.display().to_string(),
+            error,
+        }
+    })?;
+    let session = ShellSession {
+        transcript: transcript_dir.join(format!("{id}.log")),
+        id,
+        expires_at: unix_now() + ttl,
+        opened_by: opened_by.to_string(),
+    };
+    append_transcript(
+        &session.transcript,
+        &format!(
+            "[{}] session {} opened by {} (ttl={}s)",
+            Utc::now().to_rfc3339(),
+            session.id,
+            session.opened_by,
+            ttl
+        ),
+    )?;
+    let mut sessions = SHELL_SESSIONS.lock().unwrap();
+    let sessions = sessions.get_or_insert_with(HashMap::new);
+    // drop expired sessions so the registry doesn't grow unbounded
+    let now = unix_now();
+    sessions.retain(|_, s| !s.expired(now));
+    sessions.insert(session.id.clone(), session.clone());
+    info!(
+        "Opened shell session id={} opened_by={} ttl={}s transcript={}",
+        session.id,
+        session.opened_by,
+        ttl,
+        session.transcript.display()
+    );
+    Ok(session)
+}
+
+pub fn close_session(session_id: &str) -> bool {
+    let mut sessions = SHELL_SESSIONS.lock().unwrap();
+    let session = sessions.get_or_insert_with(HashMap::new).remove(session_id);
+    match session {
+        Some(session) => {
+            // best-effort footer; the session is gone either way
+            let _ = append_transcript(
+                &session.transcript,
+                &format!(
+                    "[{}] session {} closed",
+                    Utc::now().to_rfc3339(),
+                    session.id
+                ),
+            );
+            info!("Closed shell session id={}", session_id);
+            true
+        }
+        None => false,
+    }
+}
+
+fn lookup_session(session_id: &str) -> Result<ShellSession, ShellSessionError> {
+    let mut sessions = SHELL_SESSIONS.lock().unwrap();
+    let sessions = sessions.get_or_insert_with(HashMap::new);
+    match sessions.get(session_id) {
+        Some(session) if !session.expired(unix_now()) => Ok(session.clone()),
+        _ => Err(ShellSessionError::NotFound {
+            session_id: session_id.to_string(),
+        }),
+    }
+}
+
+fn truncate_output(mut output: Vec<u8>) -> (Vec<u8>, bool) {
+    match output.len() > MAX_OUTPUT_BYTES {
+        true => {
+            output.truncate(MAX_OUTPUT_BYTES);
+            (output, true)
+        }
+        false => (output, false),
+    }
+}
+
+// run one command in the session; the command line is written to the
+// transcript before execution, so refusal-to-log means refusal-to-run
+pub async fn exec(session_id: &str, command: &str) -> Result<ShellExecOutput, ShellSessionError> {
+    let session = lookup_session(session_id)?;
+    append_transcript(
+        &session.transcript,
+        &format!("[{}] $ {}", Utc::now().to_rfc3339(), command),
+    )?;
+    let output = tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .output()
+        .await
+        .map_err(|error| ShellSessionError::SpawnError { error })?;
+    let (stdout, stdout_truncated) = truncate_output(output.stdout);
+    let (stderr, stderr_truncated) = truncate_output(output.stderr);
+    let exit_code = output.status.code();
+    let _ = append_transcript(
+        &session.transcript,
+        &format!(
+            "[{}] exit={:?}\n{}{}",
+            Utc::now().to_rfc3339(),
+            exit_code,
+            String::from_utf8_lossy(&stdout),
+            String::from_utf8_lossy(&stderr)
+        ),
+    );
+    Ok(ShellExecOutput {
+        exit_code,
+        stdout,
+        stderr,
+        truncated: stdout_truncated || stderr_truncated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_paths() -> (tempfile::TempDir, PrintNannyPaths) {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = PrintNannyPaths {
+            state_dir: dir.path().join("state"),
+            ..PrintNannyPaths::default()
+        };
+        (dir, paths)
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_exec_records_transcript() {
+        let (_dir, paths) = test_paths();
+        let session = open_session(&paths, None, "support@printnanny.ai").unwrap();
+        let output = exec(&session.id, "echo hello").await.unwrap();
+        assert_eq!(output.exit_code, Some(0));
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "hello");
+        assert!(!output.truncated);
+
+        let transcript = std::fs::read_to_string(&session.transcript).unwrap();
+        assert!(transcript.contains("opened by support@printnanny.ai"));
+        assert!(transcript.contains("$ echo hello"));
+        assert!(transcript.contains("hello"));
+        assert!(close_session(&session.id));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_exec_rejects_unknown_and_closed_sessions() {
+        let (_dir, paths) = test_paths();
+        assert!(matches!(
+            exec("no-such-session", "true").await,
+            Err(ShellSessionError::NotFound { .. })
+        ));
+        let session = open_session(&paths, None, "support@printnanny.ai").unwrap();
+        assert!(close_session(&session.id));
+        assert!(matches!(
+            exec(&session.id, "true").await,
+            Err(ShellSessionError::NotFound { .. })
+        ));
+    }
+
+    #[test_log::test]
+    fn test_open_session_clamps_ttl() {
+        let (_dir, paths) = test_paths();
+        let session = open_session(&paths, Some(MAX_SESSION_TTL_SECONDS * 10), "support").unwrap();
+        assert!(session.expires_at <= unix_now() + MAX_SESSION_TTL_SECONDS);
+        assert!(close_session(&session.id));
+    }
+}
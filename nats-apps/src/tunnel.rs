@@ -0,0 +1,258 @@
+// Reverse tunnel for remote access without port forwarding. The device
+// already holds an authenticated NATS connection to PrintNanny Cloud, so the
+// cloud UI tunnels HTTP requests for the local dashboard/stream over that
+// connection instead of requiring a port forward (users behind CGNAT have no
+// other option). Access is granted one session at a time: the cloud UI opens
+// a session with a TTL and bandwidth cap, every tunneled request must name a
+// live session, and usage is metered against the cap. The master opt-in is
+// PrintNannySettings.tunnel_enabled (default off).
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_TUNNEL_UPSTREAM: &str = "http://127.0.0.1:80";
+// sessions default to 15 minutes and are capped at 4 hours
+pub const DEFAULT_SESSION_TTL_SECONDS: u64 = 15 * 60;
+pub const MAX_SESSION_TTL_SECONDS: u64 = 4 * 60 * 60;
+// per-session bandwidth cap; enough for dashboard use, low enough that a
+// forgotten stream tab doesn't saturate a metered uplink
+pub const DEFAULT_SESSION_MAX_BYTES: u64 = 64 * 1024 * 1024;
+// hard ceiling a session request cannot raise the cap past
+pub const MAX_SESSION_MAX_BYTES: u64 = 1024 * 1024 * 1024;
+
+static TUNNEL_SESSIONS: Mutex<Option<HashMap<String, TunnelSession>>> = Mutex::new(None);
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TunnelSession {
+    pub id: String,
+    // unix seconds
+    pub expires_at: u64,
+    pub max_bytes: u64,
+    pub bytes_used: u64,
+}
+
+impl TunnelSession {
+    pub fn expired(&self, now: u64) -> bool {
+        now >= self.expires_at
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TunnelSessionError {
+    #[error("Tunnel session {session_id} not found or expired")]
+    NotFound { session_id: String },
+    #[error("Tunnel session {session_id} exceeded bandwidth cap of {max_bytes} bytes")]
+    BandwidthCapExceeded { session_id: String, max_bytes: u64 },
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+pub fn open_session(ttl_seconds: Option<u64>, max_bytes: Option<u64>) -> TunnelSession {
+    let ttl = ttl_seconds
+        .unwrap_or(DEFAULT_SESSION_TTL_SECONDS)
+        .min(MAX_SESSION_TTL_SECONDS);
+    let session = TunnelSession {
+        id: uuid::Uuid::new_v4().to_string(),
+        expires_at: unix_now() + ttl,
+        max_bytes: max_bytes
+            .unwrap_or(DEFAULT_SESSION_MAX_BYTES)
+            .min(MAX_SESSION_MAX_BYTES),
+        bytes_used: 0,
+    };
+    let mut sessions = TUNNEL_SESSIONS.lock().unwrap();
+    let sessions = sessions.get_or_insert_with(HashMap::new);
+    // drop expired sessions so the registry doesn't grow unbounded
+    let now = unix_now();
+    sessions.retain(|_, s| !s.expired(now));
+    sessions.insert(session.id.clone(), session.clone());
+    info!(
+        "Opened tunnel session id={} ttl={}s max_bytes={}",
+        session.id, ttl, session.max_bytes
+    );
+    session
+}
+
+pub fn close_session(session_id: &str) -> bool {
+    let mut sessions = TUNNEL_SESSIONS.lock().unwrap();
+    let closed = sessions
+        .get_or_insert_with(HashMap::new)
+        .remove(session_id)
+        .is_some();
+    if closed {
+        info!("Closed tunnel session id={}", session_id);
+    }
+    closed
+}
+
+// validate the session and meter bytes against its bandwidth cap; returns the
+// updated session so callers can report remaining budget
+pub fn record_usage(session_id: &str, bytes: u64) -> Result<TunnelSession, TunnelSessionError> {
+    let mut sessions = TUNNEL_SESSIONS.lock().unwrap();
+    let sessions = sessions.get_or_insert_with(HashMap::new);
+    let now = unix_now();
+    let session = match sessions.get_mut(session_id) {
+        Some(session) if !session.expired(now) => session,
+        _ => {
+            return Err(TunnelSessionError::NotFound {
+                session_id: session_id.to_string(),
+            })
+        }
+    };
+    if session.bytes_used.saturating_add(bytes) > session.max_bytes {
+        let max_bytes = session.max_bytes;
+        sessions.remove(session_id);
+        return Err(TunnelSessionError::BandwidthCapExceeded {
+            session_id: session_id.to_string(),
+            max_bytes,
+        });
+    }
+    session.bytes_used += bytes;
+    Ok(session.clone())
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TunnelHttpRequest {
+    pub session_id: String,
+    pub method: String,
+    // path and query relative to the local dashboard, e.g. /api/status
+    pub path: String,
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    // base64-encoded request body
+    #[serde(default)]
+    pub body: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TunnelHttpReply {
+    pub session_id: String,
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    // base64-encoded response body
+    pub body: String,
+    // remaining bandwidth budget for this session
+    pub bytes_remaining: u64,
+}
+
+// forward one tunneled request to the local dashboard. Authorization errors
+// are reported in-band as synthesized 403/429 responses so the cloud UI can
+// render them without a second error channel.
+pub async fn forward_http(request: &TunnelHttpRequest) -> Result<TunnelHttpReply> {
+    let request_bytes = request.body.len() as u64;
+    if let Err(e) = record_usage(&request.session_id, request_bytes) {
+        warn!("Rejected tunneled request: {}", e);
+        let status = match e {
+            TunnelSessionError::NotFound { .. } => 403,
+            TunnelSessionError::BandwidthCapExceeded { .. } => 429,
+        };
+        return Ok(TunnelHttpReply {
+            session_id: request.session_id.clone(),
+            status,
+            headers: vec![],
+            body: base64::encode(e.to_string()),
+            bytes_remaining: 0,
+        });
+    }
+
+    let url = format!("{}{}", DEFAULT_TUNNEL_UPSTREAM, request.path);
+    let method = reqwest::Method::from_bytes(request.method.as_bytes())?;
+    let client = reqwest::Client::new();
+    let mut builder = client.request(method, &url);
+    for (name, value) in &request.headers {
+        builder = builder.header(name, value);
+    }
+    if !request.body.is_empty() {
+        builder = builder.body(base64::decode(&request.body)?);
+    }
+    let response = builder.send().await?;
+    let status = response.status().as_u16();
+    let headers: Vec<(String, String)> = response
+        .headers()
+        .iter()
+        .map(|(name, value)| {
+            (
+                name.to_string(),
+                String::from_utf8_lossy(value.as_bytes()).to_string(),
+            )
+        })
+        .collect();
+    let body = response.bytes().await?;
+    let session = match record_usage(&request.session_id, body.len() as u64) {
+        Ok(session) => session,
+        Err(e) => {
+            warn!("Dropped tunneled response: {}", e);
+            return Ok(TunnelHttpReply {
+                session_id: request.session_id.clone(),
+                status: 429,
+                headers: vec![],
+                body: base64::encode(e.to_string()),
+                bytes_remaining: 0,
+            });
+        }
+    };
+    Ok(TunnelHttpReply {
+        session_id: request.session_id.clone(),
+        status,
+        headers,
+        body: base64::encode(&body),
+        bytes_remaining: session.max_bytes - session.bytes_used,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_open_session_clamps_ttl() {
+        let session = open_session(Some(MAX_SESSION_TTL_SECONDS * 10), None);
+        assert!(session.expires_at <= unix_now() + MAX_SESSION_TTL_SECONDS);
+        assert_eq!(session.max_bytes, DEFAULT_SESSION_MAX_BYTES);
+        assert!(close_session(&session.id));
+
+        // the bandwidth cap cannot be disabled by requesting a huge budget
+        let session = open_session(None, Some(u64::MAX));
+        assert_eq!(session.max_bytes, MAX_SESSION_MAX_BYTES);
+        assert!(close_session(&session.id));
+    }
+
+    #[test_log::test]
+    fn test_record_usage_meters_bandwidth_cap() {
+        let session = open_session(None, Some(100));
+        let session = record_usage(&session.id, 60).unwrap();
+        assert_eq!(session.bytes_used, 60);
+        let err = record_usage(&session.id, 50).unwrap_err();
+        assert!(matches!(
+            err,
+            TunnelSessionError::BandwidthCapExceeded { max_bytes: 100, .. }
+        ));
+        // the session is revoked once the cap is exceeded
+        assert!(matches!(
+            record_usage(&session.id, 1),
+            Err(TunnelSessionError::NotFound { .. })
+        ));
+    }
+
+    #[test_log::test]
+    fn test_unknown_and_closed_sessions_are_rejected() {
+        assert!(matches!(
+            record_usage("no-such-session", 1),
+            Err(TunnelSessionError::NotFound { .. })
+        ));
+        let session = open_session(None, None);
+        assert!(close_session(&session.id));
+        assert!(matches!(
+            record_usage(&session.id, 1),
+            Err(TunnelSessionError::NotFound { .. })
+        ));
+    }
+}
@@ -0,0 +1,148 @@
+// Viewer presence tracking: the WebSocket gateway reports active
+// HLS/WebRTC/RTSP viewer counts on pi.{pi_id}.camera.stream.viewers. When
+// nobody has watched for the configured timeout, the viewer-facing stream
+// legs are paused (preserving encoder state, see
+// PrintNannyPipelineFactory::set_stream_paused) and resumed as soon as a
+// viewer returns, reducing steady-state CPU/heat on always-on devices.
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use log::{debug, error, warn};
+
+use printnanny_gst_pipelines::factory::PrintNannyPipelineFactory;
+use printnanny_settings::printnanny::PrintNannySettings;
+
+const MONITOR_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Copy, Debug)]
+struct ViewerState {
+    active_viewers: i32,
+    last_active: Instant,
+    paused_by_idle: bool,
+}
+
+impl ViewerState {
+    // the idle clock starts when the worker boots, so a device nobody ever
+    // connects to still idles its stream legs after the timeout
+    fn new() -> Self {
+        Self {
+            active_viewers: 0,
+            last_active: Instant::now(),
+            paused_by_idle: false,
+        }
+    }
+}
+
+static VIEWER_STATE: Mutex<Option<ViewerState>> = Mutex::new(None);
+
+// record a viewer count report from the gateway; returns true when the stream
+// legs were paused by the idle monitor and should be resumed for the
+// returning viewer
+pub fn record_viewer_count(active_viewers: i32) -> bool {
+    let mut guard = VIEWER_STATE.lock().unwrap();
+    let state = guard.get_or_insert_with(ViewerState::new);
+    state.active_viewers = active_viewers;
+    if active_viewers > 0 {
+        state.last_active = Instant::now();
+        if state.paused_by_idle {
+            state.paused_by_idle = false;
+            return true;
+        }
+    }
+    false
+}
+
+fn should_pause(state: &ViewerState, timeout: Duration) -> bool {
+    !state.paused_by_idle && state.active_viewers == 0 && state.last_active.elapsed() >= timeout
+}
+
+async fn run_monitor_cycle() -> Result<()> {
+    let settings = PrintNannySettings::new().await?;
+    let viewer_idle = &settings.video_stream.viewer_idle;
+    if !viewer_idle.enabled {
+        return Ok(());
+    }
+    let timeout = Duration::from_secs(viewer_idle.idle_timeout_seconds);
+    let pause = {
+        let mut guard = VIEWER_STATE.lock().unwrap();
+        let state = guard.get_or_insert_with(ViewerState::new);
+        should_pause(state, timeout)
+    };
+    if !pause {
+        debug!("Viewer idle monitor: stream legs left unchanged");
+        return Ok(());
+    }
+
+    let factory = PrintNannyPipelineFactory::default();
+    let changed = factory.set_stream_paused(true).await?;
+    warn!(
+        "No viewers for {}s, paused stream pipelines: {:?}",
+        viewer_idle.idle_timeout_seconds, changed
+    );
+    if let Some(state) = VIEWER_STATE.lock().unwrap().as_mut() {
+        state.paused_by_idle = true;
+    }
+    Ok(())
+}
+
+pub async fn run_viewer_idle_monitor() {
+    let mut interval = tokio::time::interval(MONITOR_INTERVAL);
+    loop {
+        interval.tick().await;
+        if let Err(e) = run_monitor_cycle().await {
+            error!("Viewer idle monitor cycle failed error={}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_should_pause_requires_idle_timeout() {
+        let timeout = Duration::from_secs(300);
+        let fresh = ViewerState::new();
+        assert!(!should_pause(&fresh, timeout));
+
+        let idle = ViewerState {
+            active_viewers: 0,
+            last_active: Instant::now() - Duration::from_secs(301),
+            paused_by_idle: false,
+        };
+        assert!(should_pause(&idle, timeout));
+
+        // an active viewer blocks pausing regardless of the idle clock
+        let watching = ViewerState {
+            active_viewers: 1,
+            ..idle
+        };
+        assert!(!should_pause(&watching, timeout));
+
+        // already paused: nothing to do
+        let paused = ViewerState {
+            paused_by_idle: true,
+            ..idle
+        };
+        assert!(!should_pause(&paused, timeout));
+    }
+
+    #[test_log::test]
+    fn test_record_viewer_count_resumes_after_idle_pause() {
+        // first report initializes state; no resume needed
+        assert!(!record_viewer_count(0));
+        assert!(!record_viewer_count(2));
+
+        // simulate the idle monitor pausing the stream legs
+        if let Some(state) = VIEWER_STATE.lock().unwrap().as_mut() {
+            state.active_viewers = 0;
+            state.paused_by_idle = true;
+        }
+        // a zero-count report does not resume
+        assert!(!record_viewer_count(0));
+        // a returning viewer does, exactly once
+        assert!(record_viewer_count(1));
+        assert!(!record_viewer_count(1));
+    }
+}
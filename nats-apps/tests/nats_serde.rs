@@ -0,0 +1,818 @@
+// Round-trip and backward-compatibility coverage for NatsRequest / NatsReply
+// serde, in two layers:
+//
+//  - in-memory round-trips over a sample of every variant (plus
+//    proptest-generated data for the device-local payload structs), so field
+//    renames and type changes are caught at PR time
+//  - a committed fixture corpus under fixtures/nats/, so payloads produced by
+//    already-shipped releases keep deserializing; regenerate after an
+//    intentional wire change with
+//    `cargo test -p printnanny-nats-apps --test nats_serde -- --ignored`
+use std::path::PathBuf;
+
+use chrono::Utc;
+use proptest::prelude::*;
+
+use printnanny_dbus::printnanny_os_models::{
+    CameraRecordingLoadReply, CameraRecordingStarted, CameraRecordingStopped, CameraStatus,
+    CamerasLoadReply, CrashReportOsLogsReply, CrashReportOsLogsRequest, DeviceInfoLoadReply,
+    PrintNannyCloudAuthReply, PrintNannyCloudAuthRequest, PrintNannyCloudSyncReply, SettingsApp,
+    SettingsFile, SettingsFileApplyReply, SettingsFileApplyRequest, SettingsFileLoadReply,
+    SettingsFileRevertReply, SettingsFileRevertRequest, SettingsFormat,
+    SystemdManagerDisableUnitsReply, SystemdManagerEnableUnitsReply,
+    SystemdManagerGetUnitFileStateReply, SystemdManagerGetUnitReply, SystemdManagerGetUnitRequest,
+    SystemdManagerRestartUnitReply, SystemdManagerRestartUnitRequest, SystemdManagerStartUnitReply,
+    SystemdManagerStartUnitRequest, SystemdManagerStopUnitReply, SystemdManagerStopUnitRequest,
+    SystemdManagerUnitFilesRequest, SystemdUnit, SystemdUnitActiveState, SystemdUnitFileState,
+    SystemdUnitLoadState, VideoRecording,
+};
+use printnanny_nats_apps::request_reply::{
+    BandwidthStatsReply, BandwidthStatsRequest, CameraClipReply, CameraClipRequest,
+    CameraControlsReply, CameraOverlayReply, CameraPrivacyReply, CameraRtpDestinationsReply,
+    CameraRtpDestinationsRequest, CameraStreamStateReply, CameraStreamViewersReply,
+    CameraStreamViewersRequest, CapabilitiesReply, DebugTraceDumpReply, DebugTraceEnableRequest,
+    DebugTraceStateReply, DetectionCalibrateReply, DetectionCalibrateRequest,
+    DetectionFeedbackReply, DetectionFeedbackRequest, DetectionFeedbackSyncReply,
+    DetectionTuneReply, DetectionTuneRequest, DeviceCommandReply, DeviceCommandRequest,
+    DeviceDecommissionReply, DeviceDecommissionRequest, DeviceIdentityReply, DeviceIdentityRequest,
+    FarmOverviewReply, JobsGetReply, JobsGetRequest, JobsListReply, JobsListRequest, LightsReply,
+    ModelEvaluationReportReply, ModelEvaluationReportRequest, NatsCredsRotateReply, NatsReply,
+    NatsRequest, OctoPrintBackupCreateReply, OctoPrintBackupCreateRequest,
+    OctoPrintBackupRestoreReply, OctoPrintBackupRestoreRequest, OctoPrintPluginChangedReply,
+    OctoPrintPluginInstallRequest, OctoPrintPluginUninstallRequest, OctoPrintPluginsListReply,
+    PluginReply, PluginRequest, ScheduleListReply, ShellExecReply, ShellExecRequest,
+    ShellSessionCloseRequest, ShellSessionOpenRequest, ShellSessionReply, StatsTrendsReply,
+    StatsTrendsRequest, StatusSummaryReply, StorageStatsReply, TunnelSessionCloseRequest,
+    TunnelSessionOpenRequest, TunnelSessionReply, WolReply, WolRequest,
+};
+use printnanny_nats_apps::shell::ShellSession;
+use printnanny_nats_apps::tunnel::{TunnelHttpReply, TunnelHttpRequest, TunnelSession};
+use printnanny_nats_client::request_reply::NatsRequestHandler;
+use printnanny_services::calibration::{CalibrationReport, ClipScoreStats};
+use printnanny_services::model_evaluation::ModelEvaluationReport;
+use printnanny_services::upgrade_advisor::{PreflightCheck, UpdatePreflightReport};
+use printnanny_settings::cam::RtpDestination;
+use printnanny_settings::camera_controls::CameraControlSettings;
+use printnanny_settings::lighting::LightingSettings;
+
+fn fixtures_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("fixtures")
+        .join("nats")
+}
+
+fn settings_file() -> SettingsFile {
+    SettingsFile::new(
+        SettingsApp::Printnanny,
+        "[tmp]\npath = \"/tmp\"\n".to_string(),
+        "printnanny.toml".to_string(),
+        SettingsFormat::Toml,
+    )
+}
+
+fn heartbeat_event() -> printnanny_nats_apps::heartbeat::HeartbeatEvent {
+    printnanny_nats_apps::heartbeat::HeartbeatEvent {
+        metadata: printnanny_services::metadata::EventMetadata::new(),
+        lifecycle: printnanny_services::lifecycle::DeviceLifecycleState::CloudConnected,
+        units: vec![],
+        latency: printnanny_services::latency::LatencyReport {
+            hls: None,
+            webrtc: None,
+        },
+        external_data_mounted: None,
+        enclosure: None,
+        capabilities: capability_report(),
+    }
+}
+
+fn capability_report() -> printnanny_services::capabilities::CapabilityReport {
+    printnanny_services::capabilities::CapabilityReport {
+        detection: true,
+        webrtc: true,
+        rtsp: false,
+        cloud: true,
+        multi_camera: false,
+        camera_count: 1,
+        edgetpu: false,
+        build_features: vec!["systemd".to_string()],
+    }
+}
+
+fn video_stream_settings() -> printnanny_dbus::printnanny_os_models::VideoStreamSettings {
+    printnanny_settings::cam::VideoStreamSettings::default().into()
+}
+
+fn systemd_unit() -> SystemdUnit {
+    SystemdUnit::new(
+        "octoprint.service".to_string(),
+        "/lib/systemd/system/octoprint.service".to_string(),
+        SystemdUnitActiveState::Active,
+        SystemdUnitLoadState::Loaded,
+        SystemdUnitFileState::Enabled,
+    )
+}
+
+fn unit_files_request() -> SystemdManagerUnitFilesRequest {
+    SystemdManagerUnitFilesRequest::new(vec!["octoprint.service".to_string()])
+}
+
+fn video_recording() -> VideoRecording {
+    VideoRecording::new(
+        "6eb7a225-84a1-4a8c-8ab6-91d1bed46cb4".to_string(),
+        false,
+        "/home/printnanny/.local/share/printnanny/video".to_string(),
+        Some("benchy.gcode".to_string()),
+        None,
+        None,
+    )
+}
+
+// one sample per NatsRequest variant
+fn sample_requests() -> Vec<NatsRequest> {
+    vec![
+        NatsRequest::CameraRecordingLoadRequest,
+        NatsRequest::CameraRecordingStartRequest,
+        NatsRequest::CameraRecordingStopRequest,
+        NatsRequest::CameraLoadRequest,
+        NatsRequest::CameraPrivacyEnableRequest,
+        NatsRequest::CameraPrivacyDisableRequest,
+        NatsRequest::CameraOverlayEnableRequest,
+        NatsRequest::CameraOverlayDisableRequest,
+        NatsRequest::CameraStreamPauseRequest,
+        NatsRequest::CameraStreamResumeRequest,
+        NatsRequest::CameraStreamViewersRequest(CameraStreamViewersRequest { active_viewers: 1 }),
+        NatsRequest::CameraRtpDestinationsGetRequest,
+        NatsRequest::CameraRtpDestinationsSetRequest(CameraRtpDestinationsRequest {
+            destinations: vec![RtpDestination {
+                host: "192.168.1.50".to_string(),
+                port: 20001,
+            }],
+        }),
+        NatsRequest::CameraControlsGetRequest,
+        NatsRequest::CameraControlsSetRequest(CameraControlSettings::default()),
+        NatsRequest::CameraClipRequest(CameraClipRequest { seconds: Some(30) }),
+        NatsRequest::LightsOnRequest,
+        NatsRequest::LightsOffRequest,
+        NatsRequest::DeviceIdentityRequest(DeviceIdentityRequest { rotate: None }),
+        NatsRequest::PrintNannyCloudSyncRequest,
+        NatsRequest::CrashReportOsLogsRequest(CrashReportOsLogsRequest::new(
+            "9ad01a36-4dcc-4712-8c32-c1b3a6543a0a".to_string(),
+        )),
+        NatsRequest::DeviceInfoLoadRequest,
+        NatsRequest::CapabilitiesRequest,
+        NatsRequest::DeviceDecommissionRequest(DeviceDecommissionRequest {
+            confirm: true,
+            overwrite_free_space: false,
+        }),
+        NatsRequest::DeviceCommandRequest(DeviceCommandRequest {
+            command: printnanny_nats_apps::device_command::DeviceCommand::Reboot,
+            execute_at: Some(Utc::now()),
+            delay_seconds: None,
+        }),
+        NatsRequest::StatusSummaryRequest,
+        NatsRequest::FarmOverviewRequest,
+        NatsRequest::ScheduleListRequest,
+        NatsRequest::JobsGetRequest(JobsGetRequest {
+            id: "f9d8e3a1-2b45-4c67-9d01-3a2b1c4d5e6f".to_string(),
+        }),
+        NatsRequest::JobsListRequest(JobsListRequest {
+            running_only: false,
+        }),
+        NatsRequest::WolRequest(WolRequest {
+            profile: Some("slicer-workstation".to_string()),
+            mac_address: None,
+            broadcast_addr: None,
+        }),
+        NatsRequest::NatsCredsRotateRequest,
+        NatsRequest::DebugTraceEnableRequest(DebugTraceEnableRequest {
+            subjects: vec!["pi.{pi_id}.settings.>".to_string()],
+        }),
+        NatsRequest::DebugTraceDisableRequest,
+        NatsRequest::DebugTraceDumpRequest,
+        NatsRequest::DetectionFeedbackRequest(DetectionFeedbackRequest {
+            detection_ts: 12_000_000_000,
+            label: "false_positive".to_string(),
+            note: Some("shadow on the print bed".to_string()),
+        }),
+        NatsRequest::DetectionFeedbackSyncRequest,
+        NatsRequest::ModelEvaluationReportRequest(ModelEvaluationReportRequest { hours: Some(24) }),
+        NatsRequest::DetectionTuneRequest(DetectionTuneRequest { nms_threshold: 50 }),
+        NatsRequest::DetectionCalibrateRequest(DetectionCalibrateRequest {
+            idle_clip: "/home/printnanny/.local/share/printnanny/video/idle.mp4".to_string(),
+            failure_clip: None,
+            apply: false,
+        }),
+        NatsRequest::OctoPrintPluginsListRequest,
+        NatsRequest::OctoPrintPluginInstallRequest(OctoPrintPluginInstallRequest {
+            package: "OctoPrint-Nanny".to_string(),
+            version: Some("0.5.1".to_string()),
+        }),
+        NatsRequest::OctoPrintPluginUninstallRequest(OctoPrintPluginUninstallRequest {
+            package: "OctoPrint-Nanny".to_string(),
+        }),
+        NatsRequest::OctoPrintBackupCreateRequest(OctoPrintBackupCreateRequest {
+            exclude: vec!["timelapse".to_string()],
+        }),
+        NatsRequest::OctoPrintBackupRestoreRequest(OctoPrintBackupRestoreRequest {
+            path: "/var/lib/printnanny/recovery/octoprint-backup-20240611-120000.zip".to_string(),
+        }),
+        // NatsRequest::PluginRequest is deliberately absent: plugin subjects
+        // are dynamic, so they route through the deserialize_payload subject
+        // guard instead of the serde tag (covered below)
+        NatsRequest::BandwidthStatsRequest(BandwidthStatsRequest { days: Some(7) }),
+        NatsRequest::StorageStatsRequest,
+        NatsRequest::StatsTrendsRequest(StatsTrendsRequest { hours: Some(24) }),
+        NatsRequest::TunnelSessionOpenRequest(TunnelSessionOpenRequest {
+            ttl_seconds: Some(600),
+            max_bytes: None,
+        }),
+        NatsRequest::TunnelSessionCloseRequest(TunnelSessionCloseRequest {
+            session_id: "a33721f6-8a12-4b5c-9a76-fb2a75f2ad2e".to_string(),
+        }),
+        NatsRequest::TunnelHttpRequest(TunnelHttpRequest {
+            session_id: "a33721f6-8a12-4b5c-9a76-fb2a75f2ad2e".to_string(),
+            method: "GET".to_string(),
+            path: "/api/status".to_string(),
+            headers: vec![("accept".to_string(), "application/json".to_string())],
+            body: "".to_string(),
+        }),
+        NatsRequest::ShellSessionOpenRequest(ShellSessionOpenRequest {
+            ttl_seconds: Some(1800),
+            opened_by: "support@printnanny.ai".to_string(),
+        }),
+        NatsRequest::ShellSessionCloseRequest(ShellSessionCloseRequest {
+            session_id: "7f5b0a6e-4c21-4f4e-93a1-0d6f2b8f01c4".to_string(),
+        }),
+        NatsRequest::ShellExecRequest(ShellExecRequest {
+            session_id: "7f5b0a6e-4c21-4f4e-93a1-0d6f2b8f01c4".to_string(),
+            command: "journalctl -u printnanny-edge-worker -n 50".to_string(),
+        }),
+        NatsRequest::PrintNannyCloudAuthRequest(PrintNannyCloudAuthRequest::new(
+            "leigh@printnanny.ai".to_string(),
+            "abc123".to_string(),
+            "https://printnanny.ai".to_string(),
+        )),
+        NatsRequest::SettingsFileLoadRequest,
+        NatsRequest::SettingsFileApplyRequest(SettingsFileApplyRequest::new(
+            settings_file(),
+            "3f786850e387550fdab836ed7e6dc881de23001b".to_string(),
+            "Updated printnanny.toml".to_string(),
+        )),
+        NatsRequest::SettingsFileRevertRequest(SettingsFileRevertRequest::new(
+            SettingsApp::Printnanny,
+            vec![settings_file()],
+            "3f786850e387550fdab836ed7e6dc881de23001b".to_string(),
+        )),
+        NatsRequest::CameraSettingsFileApplyRequest(video_stream_settings()),
+        NatsRequest::CameraSettingsFileLoadRequest,
+        NatsRequest::CameraStatusRequest,
+        NatsRequest::SystemdManagerDisableUnitsRequest(unit_files_request()),
+        NatsRequest::SystemdManagerEnableUnitsRequest(unit_files_request()),
+        NatsRequest::SystemdManagerGetUnitRequest(SystemdManagerGetUnitRequest::new(
+            "octoprint.service".to_string(),
+        )),
+        NatsRequest::SystemdManagerGetUnitFileStateRequest(SystemdManagerGetUnitRequest::new(
+            "octoprint.service".to_string(),
+        )),
+        NatsRequest::SystemdManagerRestartUnitRequest(SystemdManagerRestartUnitRequest::new(
+            "octoprint.service".to_string(),
+        )),
+        NatsRequest::SystemdManagerStartUnitRequest(SystemdManagerStartUnitRequest::new(
+            "octoprint.service".to_string(),
+        )),
+        NatsRequest::SystemdManagerStopUnitRequest(SystemdManagerStopUnitRequest::new(
+            "octoprint.service".to_string(),
+        )),
+    ]
+}
+
+// one sample per NatsReply variant
+fn sample_replies() -> Vec<NatsReply> {
+    vec![
+        NatsReply::CameraRecordingLoadReply(CameraRecordingLoadReply::new(None, None)),
+        NatsReply::CameraRecordingStartReply(CameraRecordingStarted::new(video_recording())),
+        NatsReply::CameraRecordingStopReply(CameraRecordingStopped::new(Some(video_recording()))),
+        NatsReply::CameraLoadReply(CamerasLoadReply::new(vec![])),
+        NatsReply::CameraPrivacyReply(CameraPrivacyReply { privacy_mode: true }),
+        NatsReply::CameraOverlayReply(CameraOverlayReply {
+            hls_detection_overlay: true,
+        }),
+        NatsReply::CameraStreamStateReply(CameraStreamStateReply {
+            paused: true,
+            pipelines: vec!["rtp".to_string(), "hls".to_string()],
+        }),
+        NatsReply::CameraStreamViewersReply(CameraStreamViewersReply {
+            active_viewers: 1,
+            resumed: vec!["rtp".to_string(), "hls".to_string()],
+        }),
+        NatsReply::CameraRtpDestinationsReply(CameraRtpDestinationsReply {
+            destinations: vec![RtpDestination {
+                host: "192.168.1.50".to_string(),
+                port: 20001,
+            }],
+        }),
+        NatsReply::CameraControlsReply(CameraControlsReply {
+            settings: CameraControlSettings::default(),
+            controls: vec![],
+        }),
+        NatsReply::CameraClipReply(CameraClipReply {
+            recording: Box::new(video_recording()),
+            file_name: "/home/printnanny/.local/share/printnanny/video/6eb7a225-84a1-4a8c-8ab6-91d1bed46cb4/0.mp4".to_string(),
+            seconds: 30,
+        }),
+        NatsReply::LightsReply(LightsReply {
+            lighting: LightingSettings::default(),
+            on: true,
+        }),
+        NatsReply::DeviceIdentityReply(DeviceIdentityReply {
+            pubkey: "aHb7Kjf8b0V4nN6cQoDkXK3P9P3S9U3P1P4mY9YjO1A=".to_string(),
+            algorithm: "ed25519".to_string(),
+            rotated: false,
+        }),
+        NatsReply::PrintNannyCloudSyncReply(PrintNannyCloudSyncReply::new(
+            "2023-04-19T09:30:00Z".to_string(),
+            "2023-04-19T09:30:02Z".to_string(),
+        )),
+        NatsReply::CrashReportOsLogsReply(CrashReportOsLogsReply::new(
+            "9ad01a36-4dcc-4712-8c32-c1b3a6543a0a".to_string(),
+            "2023-04-19T09:30:00Z".to_string(),
+        )),
+        NatsReply::DeviceInfoLoadReply(DeviceInfoLoadReply::new(
+            "PrintNanny OS v0.33.1".to_string(),
+            "printnanny".to_string(),
+            "0.33.1".to_string(),
+            vec![],
+        )),
+        NatsReply::CapabilitiesReply(CapabilitiesReply {
+            capabilities: capability_report(),
+        }),
+        NatsReply::DeviceDecommissionReply(DeviceDecommissionReply {
+            report: Some(printnanny_services::decommission::DecommissionReport {
+                hostname: "printnanny".to_string(),
+                started_dt: Utc::now(),
+                finished_dt: Utc::now(),
+                overwrote_free_space: false,
+                steps: vec![printnanny_services::decommission::DecommissionStep {
+                    name: "cloud_credentials".to_string(),
+                    path: std::path::PathBuf::from("/var/lib/printnanny/creds"),
+                    completed: true,
+                    detail: "shredded and removed".to_string(),
+                }],
+                completed: true,
+                signature: Some("eyJhbGciOiJIUzI1NiJ9".to_string()),
+            }),
+            rejected: None,
+        }),
+        NatsReply::DeviceCommandReply(DeviceCommandReply {
+            command_id: Some("a2e4f3c8-9b67-4df2-8ef3-e2a1a87f3a42".to_string()),
+            execute_at_dt: Utc::now(),
+            scheduled: true,
+            result: None,
+            preflight: Some(UpdatePreflightReport {
+                passed: true,
+                checks: vec![
+                    PreflightCheck {
+                        name: "rootfs free space".to_string(),
+                        passed: true,
+                        detail: "2048MB free, 1000MB required".to_string(),
+                    },
+                    PreflightCheck {
+                        name: "power".to_string(),
+                        passed: true,
+                        detail: "on mains power".to_string(),
+                    },
+                ],
+            }),
+        }),
+        NatsReply::StatusSummaryReply(StatusSummaryReply {
+            hostname: "printnanny".to_string(),
+            heartbeat: heartbeat_event(),
+        }),
+        NatsReply::FarmOverviewReply(FarmOverviewReply {
+            devices: vec![
+                printnanny_nats_apps::farm::FarmDevice {
+                    hostname: "printnanny".to_string(),
+                    reachable: true,
+                    summary: Some(heartbeat_event()),
+                    error: None,
+                },
+                printnanny_nats_apps::farm::FarmDevice {
+                    hostname: "printnanny-2".to_string(),
+                    reachable: false,
+                    summary: None,
+                    error: Some("timed out after 5s".to_string()),
+                },
+            ],
+        }),
+        NatsReply::WolReply(WolReply {
+            mac_address: Some("aa:bb:cc:dd:ee:ff".to_string()),
+            broadcast_addr: Some("255.255.255.255:9".to_string()),
+            sent: true,
+            rejected: None,
+        }),
+        NatsReply::DebugTraceStateReply(DebugTraceStateReply {
+            enabled: true,
+            subjects: vec!["pi.aurora.settings.>".to_string()],
+            trace_file: Some("/var/run/printnanny/nats-trace.jsonl".to_string()),
+        }),
+        NatsReply::DebugTraceDumpReply(DebugTraceDumpReply {
+            enabled: true,
+            subjects: vec!["pi.aurora.settings.>".to_string()],
+            entries: vec![printnanny_nats_client::trace::TraceEntry {
+                ts: Utc::now(),
+                direction: "request".to_string(),
+                subject: "pi.aurora.settings.file.load".to_string(),
+                payload: serde_json::json!({ "app": "printnanny", "api_key": "[redacted]" }),
+            }],
+        }),
+        NatsReply::NatsCredsRotateReply(NatsCredsRotateReply {
+            creds_file: "/var/lib/printnanny/creds/printnanny-cloud-nats.creds".to_string(),
+            expires_at: Some("2024-09-10T00:00:00+00:00".to_string()),
+            reconnect_scheduled: true,
+        }),
+        NatsReply::ScheduleListReply(ScheduleListReply {
+            tasks: vec![
+                printnanny_nats_apps::schedule::ScheduledTaskStatus {
+                    name: "nightly-backup".to_string(),
+                    cron: "0 3 * * *".to_string(),
+                    next_run_dt: Some("2024-06-11T03:00:00+00:00".to_string()),
+                    last_outcome: Some(printnanny_nats_apps::schedule::TaskOutcome {
+                        last_run_dt: Utc::now(),
+                        success: true,
+                        detail: "wrote /var/lib/printnanny/recovery/settings-backup.zip"
+                            .to_string(),
+                    }),
+                },
+                printnanny_nats_apps::schedule::ScheduledTaskStatus {
+                    name: "prune-recordings".to_string(),
+                    cron: "30 4 * * 0".to_string(),
+                    next_run_dt: Some("2024-06-16T04:30:00+00:00".to_string()),
+                    last_outcome: None,
+                },
+            ],
+        }),
+        NatsReply::JobsGetReply(JobsGetReply {
+            job: Some(printnanny_edge_db::jobs::Job {
+                id: "f9d8e3a1-2b45-4c67-9d01-3a2b1c4d5e6f".to_string(),
+                created_dt: Utc::now(),
+                updated_dt: Utc::now(),
+                job_type: "ota".to_string(),
+                status: "running".to_string(),
+                progress_percent: 25,
+                detail: Some("downloading update".to_string()),
+                finished_dt: None,
+                result: None,
+            }),
+        }),
+        NatsReply::JobsListReply(JobsListReply {
+            jobs: vec![printnanny_edge_db::jobs::Job {
+                id: "a1b2c3d4-5e6f-4a7b-8c9d-0e1f2a3b4c5d".to_string(),
+                created_dt: Utc::now(),
+                updated_dt: Utc::now(),
+                job_type: "video_sync".to_string(),
+                status: "success".to_string(),
+                progress_percent: 100,
+                detail: Some("synced 4 of 4 parts".to_string()),
+                finished_dt: Some(Utc::now()),
+                result: Some("synced 4 of 4 parts, 0 failed".to_string()),
+            }],
+        }),
+        NatsReply::DetectionFeedbackReply(DetectionFeedbackReply {
+            feedback: printnanny_edge_db::detection_feedback::DetectionFeedback {
+                id: "b4b0e3e8-4a67-41f2-8ef3-e2a1a87f3a11".to_string(),
+                created_dt: Utc::now(),
+                detection_ts: 12_000_000_000,
+                label: "false_positive".to_string(),
+                note: None,
+                frame_path: None,
+                uploaded_dt: None,
+            },
+            pending: 1,
+        }),
+        NatsReply::DetectionFeedbackSyncReply(DetectionFeedbackSyncReply {
+            synced: 3,
+            enabled: true,
+        }),
+        NatsReply::ModelEvaluationReportReply(ModelEvaluationReportReply {
+            enabled: true,
+            report: ModelEvaluationReport {
+                generated_dt: Utc::now(),
+                since: Utc::now(),
+                models: vec![],
+            },
+        }),
+        NatsReply::DetectionTuneReply(DetectionTuneReply {
+            nms_threshold: 50,
+            pipelines: vec!["df".to_string(), "bounding_boxes".to_string()],
+        }),
+        NatsReply::DetectionCalibrateReply(DetectionCalibrateReply {
+            report: CalibrationReport {
+                generated_dt: Utc::now(),
+                idle: ClipScoreStats {
+                    clip: "/home/printnanny/.local/share/printnanny/video/idle.mp4".to_string(),
+                    windows: 40,
+                    failure_windows: 2,
+                    max_failure_score: Some(0.3),
+                    median_failure_score: Some(0.25),
+                },
+                failure: ClipScoreStats {
+                    clip: "/usr/share/printnanny/model/failure-sample.mp4".to_string(),
+                    windows: 38,
+                    failure_windows: 30,
+                    max_failure_score: Some(0.92),
+                    median_failure_score: Some(0.8),
+                },
+                current_nms_threshold: 66,
+                suggested_nms_threshold: Some(55),
+            },
+            applied: false,
+        }),
+        NatsReply::OctoPrintPluginsListReply(OctoPrintPluginsListReply { plugins: vec![] }),
+        NatsReply::OctoPrintPluginChangedReply(OctoPrintPluginChangedReply {
+            package: "OctoPrint-Nanny".to_string(),
+            detail: "Successfully installed OctoPrint-Nanny-0.5.1".to_string(),
+            plugins: vec![],
+        }),
+        NatsReply::OctoPrintBackupCreateReply(OctoPrintBackupCreateReply {
+            path: "/var/lib/printnanny/recovery/octoprint-backup-20240611-120000.zip"
+                .to_string(),
+        }),
+        NatsReply::OctoPrintBackupRestoreReply(OctoPrintBackupRestoreReply { started: true }),
+        NatsReply::PluginReply(PluginReply {
+            plugin: "spoolman".to_string(),
+            response: serde_json::json!({ "spools": [] }),
+        }),
+        NatsReply::BandwidthStatsReply(BandwidthStatsReply { usage: vec![] }),
+        NatsReply::StorageStatsReply(StorageStatsReply {
+            categories: vec![printnanny_services::schedule::StorageCategoryUsage {
+                category: printnanny_settings::storage::StorageCategory::Recordings,
+                dir: "/home/printnanny/.local/share/printnanny/video".to_string(),
+                used_bytes: 1048576,
+                files: 2,
+                quota_bytes: 0,
+            }],
+            total_used_bytes: 1048576,
+        }),
+        NatsReply::StatsTrendsReply(StatsTrendsReply {
+            samples: vec![printnanny_edge_db::heartbeat_samples::HeartbeatSample {
+                id: 1,
+                sample_dt: Utc::now(),
+                resolution: printnanny_edge_db::heartbeat_samples::RESOLUTION_RAW.to_string(),
+                cpu_usage_usec: Some(123456789),
+                cpu_percent: Some(42.5),
+                rss_bytes: 268435456,
+                swap_bytes: 0,
+                enclosure_temperature: Some(31.2),
+                hls_latency_ms: Some(4200),
+            }],
+        }),
+        NatsReply::TunnelSessionReply(TunnelSessionReply {
+            enabled: true,
+            session: Some(TunnelSession {
+                id: "a33721f6-8a12-4b5c-9a76-fb2a75f2ad2e".to_string(),
+                expires_at: 1_700_000_000,
+                max_bytes: 10_485_760,
+                bytes_used: 0,
+            }),
+        }),
+        NatsReply::TunnelHttpReply(TunnelHttpReply {
+            session_id: "a33721f6-8a12-4b5c-9a76-fb2a75f2ad2e".to_string(),
+            status: 200,
+            headers: vec![("content-type".to_string(), "application/json".to_string())],
+            body: "eyJvayI6dHJ1ZX0=".to_string(),
+            bytes_remaining: 10_485_760,
+        }),
+        NatsReply::ShellSessionReply(ShellSessionReply {
+            enabled: true,
+            session: Some(ShellSession {
+                id: "7f5b0a6e-4c21-4f4e-93a1-0d6f2b8f01c4".to_string(),
+                expires_at: 1_700_000_000,
+                opened_by: "support@printnanny.ai".to_string(),
+                transcript: std::path::PathBuf::from(
+                    "/var/lib/printnanny/shell/7f5b0a6e-4c21-4f4e-93a1-0d6f2b8f01c4.log",
+                ),
+            }),
+        }),
+        NatsReply::ShellExecReply(ShellExecReply {
+            session_id: "7f5b0a6e-4c21-4f4e-93a1-0d6f2b8f01c4".to_string(),
+            exit_code: Some(0),
+            stdout: "aGVsbG8K".to_string(),
+            stderr: "".to_string(),
+            truncated: false,
+            rejected: None,
+        }),
+        NatsReply::PrintNannyCloudAuthReply(PrintNannyCloudAuthReply::new(
+            200,
+            "Success".to_string(),
+        )),
+        NatsReply::SettingsFileLoadReply(SettingsFileLoadReply::new(
+            vec![settings_file()],
+            "3f786850e387550fdab836ed7e6dc881de23001b".to_string(),
+            vec![],
+        )),
+        NatsReply::SettingsFileApplyReply(SettingsFileApplyReply::new(
+            settings_file(),
+            "3f786850e387550fdab836ed7e6dc881de23001b".to_string(),
+            vec![],
+        )),
+        NatsReply::SettingsFileRevertReply(SettingsFileRevertReply::new(
+            SettingsApp::Printnanny,
+            vec![settings_file()],
+            "3f786850e387550fdab836ed7e6dc881de23001b".to_string(),
+            vec![],
+        )),
+        NatsReply::CameraSettingsFileApplyReply(video_stream_settings()),
+        NatsReply::CameraSettingsFileLoadReply(video_stream_settings()),
+        NatsReply::CameraStatusReply(CameraStatus::new(true, false)),
+        NatsReply::SystemdManagerDisableUnitsReply(SystemdManagerDisableUnitsReply::new(
+            unit_files_request(),
+            vec![],
+        )),
+        NatsReply::SystemdManagerEnableUnitsReply(SystemdManagerEnableUnitsReply::new(
+            unit_files_request(),
+            vec![],
+        )),
+        NatsReply::SystemdManagerGetUnitReply(SystemdManagerGetUnitReply::new(systemd_unit())),
+        NatsReply::SystemdManagerGetUnitFileStateReply(SystemdManagerGetUnitFileStateReply::new(
+            SystemdManagerGetUnitRequest::new("octoprint.service".to_string()),
+            SystemdUnitFileState::Enabled,
+        )),
+        NatsReply::SystemdManagerRestartUnitReply(SystemdManagerRestartUnitReply::new(
+            "/org/freedesktop/systemd1/job/1".to_string(),
+            systemd_unit(),
+        )),
+        NatsReply::SystemdManagerStartUnitReply(SystemdManagerStartUnitReply::new(
+            "/org/freedesktop/systemd1/job/1".to_string(),
+            systemd_unit(),
+        )),
+        NatsReply::SystemdManagerStopUnitReply(SystemdManagerStopUnitReply::new(
+            "/org/freedesktop/systemd1/job/1".to_string(),
+            systemd_unit(),
+        )),
+    ]
+}
+
+fn subject_pattern(value: &serde_json::Value) -> &str {
+    value["subject_pattern"]
+        .as_str()
+        .expect("serialized variant is missing the subject_pattern tag")
+}
+
+#[test_log::test]
+fn test_request_samples_roundtrip() {
+    let mut tags = std::collections::HashSet::new();
+    for request in sample_requests() {
+        let value = serde_json::to_value(&request).unwrap();
+        let tag = subject_pattern(&value).to_string();
+        assert!(tags.insert(tag.clone()), "Duplicate subject_pattern {tag}");
+        let roundtripped: NatsRequest = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(value, serde_json::to_value(&roundtripped).unwrap());
+    }
+}
+
+#[test_log::test]
+fn test_reply_samples_roundtrip() {
+    let mut tags = std::collections::HashSet::new();
+    for reply in sample_replies() {
+        let value = serde_json::to_value(&reply).unwrap();
+        let tag = subject_pattern(&value).to_string();
+        assert!(tags.insert(tag.clone()), "Duplicate subject_pattern {tag}");
+        let roundtripped: NatsReply = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(value, serde_json::to_value(&roundtripped).unwrap());
+    }
+}
+
+// payloads produced by shipped releases must keep deserializing; regenerate
+// the corpus with the ignored test below after an intentional wire change
+#[test_log::test]
+fn test_fixture_corpus_backward_compatible() {
+    let requests: Vec<serde_json::Value> = serde_json::from_str(
+        &std::fs::read_to_string(fixtures_dir().join("requests.json")).unwrap(),
+    )
+    .unwrap();
+    let replies: Vec<serde_json::Value> = serde_json::from_str(
+        &std::fs::read_to_string(fixtures_dir().join("replies.json")).unwrap(),
+    )
+    .unwrap();
+    assert!(!requests.is_empty());
+    assert!(!replies.is_empty());
+
+    for value in requests {
+        let request: NatsRequest = serde_json::from_value(value.clone())
+            .unwrap_or_else(|e| panic!("Fixture {} no longer deserializes: {}", value, e));
+        let reserialized = serde_json::to_value(&request).unwrap();
+        assert_eq!(subject_pattern(&value), subject_pattern(&reserialized));
+    }
+    for value in replies {
+        let reply: NatsReply = serde_json::from_value(value.clone())
+            .unwrap_or_else(|e| panic!("Fixture {} no longer deserializes: {}", value, e));
+        let reserialized = serde_json::to_value(&reply).unwrap();
+        assert_eq!(subject_pattern(&value), subject_pattern(&reserialized));
+    }
+}
+
+#[ignore]
+#[test_log::test]
+fn regenerate_fixture_corpus() {
+    std::fs::create_dir_all(fixtures_dir()).unwrap();
+    let requests: Vec<serde_json::Value> = sample_requests()
+        .iter()
+        .map(|request| serde_json::to_value(request).unwrap())
+        .collect();
+    let replies: Vec<serde_json::Value> = sample_replies()
+        .iter()
+        .map(|reply| serde_json::to_value(reply).unwrap())
+        .collect();
+    std::fs::write(
+        fixtures_dir().join("requests.json"),
+        serde_json::to_string_pretty(&requests).unwrap(),
+    )
+    .unwrap();
+    std::fs::write(
+        fixtures_dir().join("replies.json"),
+        serde_json::to_string_pretty(&replies).unwrap(),
+    )
+    .unwrap();
+}
+
+fn roundtrip_request(request: &NatsRequest) -> Result<(), TestCaseError> {
+    let value = serde_json::to_value(request).unwrap();
+    let roundtripped: NatsRequest = serde_json::from_value(value.clone()).unwrap();
+    prop_assert_eq!(value, serde_json::to_value(&roundtripped).unwrap());
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn test_detection_feedback_request_roundtrip(
+        detection_ts in any::<i64>(),
+        label in "[a-z_]{1,24}",
+        note in proptest::option::of("[ -~]{0,64}"),
+    ) {
+        roundtrip_request(&NatsRequest::DetectionFeedbackRequest(DetectionFeedbackRequest {
+            detection_ts,
+            label,
+            note,
+        }))?;
+    }
+
+    // plugin subjects are dynamic and bypass the serde tag entirely; assert
+    // the deserialize_payload subject guard wraps any payload losslessly
+    #[test]
+    fn test_plugin_request_subject_guard(
+        name in "[a-z0-9_]{1,16}",
+        method in "[a-z0-9_.]{1,24}",
+        payload in proptest::collection::vec(any::<u8>(), 0..64),
+    ) {
+        let subject = format!("pi.{{pi_id}}.plugin.{name}.{method}");
+        let request =
+            NatsRequest::deserialize_payload(&subject, &bytes::Bytes::from(payload.clone()))
+                .unwrap();
+        match request {
+            NatsRequest::PluginRequest(PluginRequest {
+                subject_pattern,
+                payload_b64,
+            }) => {
+                prop_assert_eq!(subject_pattern, subject);
+                prop_assert_eq!(base64::decode(payload_b64).unwrap(), payload);
+            }
+            other => prop_assert!(false, "Expected PluginRequest, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_tunnel_http_request_roundtrip(
+        session_id in "[a-f0-9-]{1,36}",
+        method in "GET|POST|PUT|DELETE",
+        path in "/[a-z0-9/_-]{0,40}",
+        headers in proptest::collection::vec(("[a-z-]{1,16}", "[ -~]{0,32}"), 0..4),
+        body in "[A-Za-z0-9+/]{0,64}",
+    ) {
+        roundtrip_request(&NatsRequest::TunnelHttpRequest(TunnelHttpRequest {
+            session_id,
+            method,
+            path,
+            headers,
+            body,
+        }))?;
+    }
+
+    #[test]
+    fn test_window_parameter_requests_roundtrip(
+        hours in proptest::option::of(any::<i64>()),
+        days in proptest::option::of(any::<i64>()),
+        ttl_seconds in proptest::option::of(any::<u64>()),
+        max_bytes in proptest::option::of(any::<u64>()),
+    ) {
+        roundtrip_request(&NatsRequest::ModelEvaluationReportRequest(
+            ModelEvaluationReportRequest { hours },
+        ))?;
+        roundtrip_request(&NatsRequest::BandwidthStatsRequest(BandwidthStatsRequest {
+            days,
+        }))?;
+        roundtrip_request(&NatsRequest::TunnelSessionOpenRequest(TunnelSessionOpenRequest {
+            ttl_seconds,
+            max_bytes,
+        }))?;
+    }
+}
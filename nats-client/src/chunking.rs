@@ -0,0 +1,251 @@
+// Chunked transfer protocol for payloads above the NATS max message size
+// (settings file echoes, log bundles). Oversized payloads are split into
+// sequence-numbered chunks tagged with chunk headers, and reassembled
+// transparently on the receiving side. Chunks may arrive out of order; partial
+// transfers are evicted after CHUNK_TTL so a lost chunk can not leak memory.
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_nats::HeaderMap;
+use log::warn;
+use uuid::Uuid;
+
+use super::compression::header_value;
+
+pub const CHUNK_ID_HEADER: &str = "X-PrintNanny-Chunk-Id";
+pub const CHUNK_INDEX_HEADER: &str = "X-PrintNanny-Chunk-Index";
+pub const CHUNK_COUNT_HEADER: &str = "X-PrintNanny-Chunk-Count";
+
+// stay safely below the NATS default max_payload of 1MB
+pub const MAX_CHUNK_BYTES: usize = 512 * 1024;
+
+const CHUNK_TTL: Duration = Duration::from_secs(60);
+
+// process-wide reassembler shared by the subscriber loop
+static REASSEMBLER: Mutex<Option<ChunkReassembler>> = Mutex::new(None);
+
+#[derive(Debug)]
+pub enum ReassemblyResult {
+    // payload was not chunked, handle as-is
+    NotChunked,
+    // chunk accepted, transfer not yet complete
+    Partial,
+    // all chunks received, payload reassembled
+    Complete(Vec<u8>),
+}
+
+#[derive(Debug)]
+struct PartialTransfer {
+    count: usize,
+    chunks: HashMap<usize, Vec<u8>>,
+    started: Instant,
+}
+
+#[derive(Debug)]
+pub struct ChunkReassembler {
+    ttl: Duration,
+    transfers: HashMap<String, PartialTransfer>,
+}
+
+impl Default for ChunkReassembler {
+    fn default() -> Self {
+        Self::with_ttl(CHUNK_TTL)
+    }
+}
+
+impl ChunkReassembler {
+    pub fn with_ttl(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            transfers: HashMap::new(),
+        }
+    }
+
+    fn evict_stale(&mut self) {
+        let ttl = self.ttl;
+        self.transfers.retain(|chunk_id, transfer| {
+            let stale = transfer.started.elapsed() > ttl;
+            if stale {
+                warn!(
+                    "Evicting stale chunk transfer id={} received {}/{} chunks",
+                    chunk_id,
+                    transfer.chunks.len(),
+                    transfer.count
+                );
+            }
+            !stale
+        });
+    }
+
+    // accept one chunk; returns the reassembled payload once all chunks arrived
+    pub fn insert(
+        &mut self,
+        chunk_id: &str,
+        index: usize,
+        count: usize,
+        payload: &[u8],
+    ) -> Option<Vec<u8>> {
+        self.evict_stale();
+        let transfer = self
+            .transfers
+            .entry(chunk_id.to_string())
+            .or_insert_with(|| PartialTransfer {
+                count,
+                chunks: HashMap::new(),
+                started: Instant::now(),
+            });
+        // duplicate chunks are ignored
+        transfer
+            .chunks
+            .entry(index)
+            .or_insert_with(|| payload.to_vec());
+        if transfer.chunks.len() < transfer.count {
+            return None;
+        }
+        let transfer = self.transfers.remove(chunk_id)?;
+        let mut result = Vec::new();
+        for index in 0..transfer.count {
+            match transfer.chunks.get(&index) {
+                Some(chunk) => result.extend_from_slice(chunk),
+                None => {
+                    warn!(
+                        "Chunk transfer id={} is missing chunk index={}",
+                        chunk_id, index
+                    );
+                    return None;
+                }
+            }
+        }
+        Some(result)
+    }
+}
+
+fn chunk_headers(headers: &Option<HeaderMap>) -> Option<(String, usize, usize)> {
+    let chunk_id = header_value(headers, CHUNK_ID_HEADER)?;
+    let index: usize = header_value(headers, CHUNK_INDEX_HEADER)?.parse().ok()?;
+    let count: usize = header_value(headers, CHUNK_COUNT_HEADER)?.parse().ok()?;
+    Some((chunk_id, index, count))
+}
+
+// split a payload into chunks, carrying over base headers (e.g. content-encoding)
+pub fn split_payload(
+    payload: &[u8],
+    base_headers: &Option<HeaderMap>,
+) -> Vec<(HeaderMap, Vec<u8>)> {
+    // ceiling division; usize::div_ceil needs Rust 1.73, above the workspace MSRV
+    let count = (payload.len() + MAX_CHUNK_BYTES - 1) / MAX_CHUNK_BYTES;
+    let chunk_id = Uuid::new_v4().to_string();
+    payload
+        .chunks(MAX_CHUNK_BYTES)
+        .enumerate()
+        .map(|(index, chunk)| {
+            let mut headers = base_headers.clone().unwrap_or_default();
+            headers.insert(CHUNK_ID_HEADER, chunk_id.as_str());
+            headers.insert(CHUNK_INDEX_HEADER, index.to_string().as_str());
+            headers.insert(CHUNK_COUNT_HEADER, count.to_string().as_str());
+            (headers, chunk.to_vec())
+        })
+        .collect()
+}
+
+// feed an incoming message through the process-wide reassembler
+pub fn try_reassemble(headers: &Option<HeaderMap>, payload: &[u8]) -> ReassemblyResult {
+    let (chunk_id, index, count) = match chunk_headers(headers) {
+        Some(chunk) => chunk,
+        None => return ReassemblyResult::NotChunked,
+    };
+    let mut guard = REASSEMBLER.lock().unwrap();
+    let reassembler = guard.get_or_insert_with(ChunkReassembler::default);
+    match reassembler.insert(&chunk_id, index, count, payload) {
+        Some(payload) => ReassemblyResult::Complete(payload),
+        None => ReassemblyResult::Partial,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_small_payload_single_chunk() {
+        let payload = vec![42u8; 16];
+        let chunks = split_payload(&payload, &None);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].1, payload);
+    }
+
+    #[test_log::test]
+    fn test_split_and_reassemble_in_order() {
+        let payload: Vec<u8> = (0..MAX_CHUNK_BYTES * 2 + 100).map(|i| i as u8).collect();
+        let chunks = split_payload(&payload, &None);
+        assert_eq!(chunks.len(), 3);
+        let mut reassembler = ChunkReassembler::default();
+        let mut result = None;
+        for (headers, chunk) in &chunks {
+            let (chunk_id, index, count) = chunk_headers(&Some(headers.clone())).unwrap();
+            result = reassembler.insert(&chunk_id, index, count, chunk);
+        }
+        assert_eq!(result.unwrap(), payload);
+    }
+
+    #[test_log::test]
+    fn test_reassemble_out_of_order() {
+        let payload: Vec<u8> = (0..MAX_CHUNK_BYTES * 2 + 100).map(|i| i as u8).collect();
+        let mut chunks = split_payload(&payload, &None);
+        chunks.reverse();
+        let mut reassembler = ChunkReassembler::default();
+        let mut result = None;
+        for (headers, chunk) in &chunks {
+            let (chunk_id, index, count) = chunk_headers(&Some(headers.clone())).unwrap();
+            result = reassembler.insert(&chunk_id, index, count, chunk);
+        }
+        assert_eq!(result.unwrap(), payload);
+    }
+
+    #[test_log::test]
+    fn test_incomplete_transfer_returns_none() {
+        let payload: Vec<u8> = (0..MAX_CHUNK_BYTES * 2 + 100).map(|i| i as u8).collect();
+        let chunks = split_payload(&payload, &None);
+        let mut reassembler = ChunkReassembler::default();
+        // drop the middle chunk
+        for (headers, chunk) in [&chunks[0], &chunks[2]] {
+            let (chunk_id, index, count) = chunk_headers(&Some(headers.clone())).unwrap();
+            assert!(reassembler.insert(&chunk_id, index, count, chunk).is_none());
+        }
+    }
+
+    #[test_log::test]
+    fn test_duplicate_chunk_ignored() {
+        let payload: Vec<u8> = (0..MAX_CHUNK_BYTES + 100).map(|i| i as u8).collect();
+        let chunks = split_payload(&payload, &None);
+        let mut reassembler = ChunkReassembler::default();
+        let (chunk_id, index, count) = chunk_headers(&Some(chunks[0].0.clone())).unwrap();
+        assert!(reassembler
+            .insert(&chunk_id, index, count, &chunks[0].1)
+            .is_none());
+        assert!(reassembler
+            .insert(&chunk_id, index, count, &chunks[0].1)
+            .is_none());
+        let (chunk_id, index, count) = chunk_headers(&Some(chunks[1].0.clone())).unwrap();
+        let result = reassembler.insert(&chunk_id, index, count, &chunks[1].1);
+        assert_eq!(result.unwrap(), payload);
+    }
+
+    #[test_log::test]
+    fn test_stale_transfer_evicted() {
+        let payload: Vec<u8> = (0..MAX_CHUNK_BYTES + 100).map(|i| i as u8).collect();
+        let chunks = split_payload(&payload, &None);
+        let mut reassembler = ChunkReassembler::with_ttl(Duration::from_millis(0));
+        let (chunk_id, index, count) = chunk_headers(&Some(chunks[0].0.clone())).unwrap();
+        assert!(reassembler
+            .insert(&chunk_id, index, count, &chunks[0].1)
+            .is_none());
+        std::thread::sleep(Duration::from_millis(5));
+        // first chunk was evicted, so the transfer can not complete
+        let (chunk_id, index, count) = chunk_headers(&Some(chunks[1].0.clone())).unwrap();
+        assert!(reassembler
+            .insert(&chunk_id, index, count, &chunks[1].1)
+            .is_none());
+    }
+}
@@ -1,52 +1,132 @@
 use std::path::PathBuf;
 
 use log::warn;
+use serde::{Deserialize, Serialize};
 use tokio::time::{sleep, Duration};
 
-pub async fn try_init_nats_client(
+use printnanny_settings::printnanny::NatsConfig;
+
+// TLS material applied to the NATS connection, for users running their own
+// hardened NATS servers on the LAN with a private CA and/or mutual TLS
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NatsTlsOptions {
+    pub require_tls: bool,
+    // PEM CA bundle used to verify the server certificate
+    pub ca_cert: Option<PathBuf>,
+    // PEM client certificate/key pair presented for mutual TLS
+    pub client_cert: Option<PathBuf>,
+    pub client_key: Option<PathBuf>,
+    pub verify_hostname: bool,
+}
+
+impl Default for NatsTlsOptions {
+    fn default() -> Self {
+        Self {
+            require_tls: false,
+            ca_cert: None,
+            client_cert: None,
+            client_key: None,
+            verify_hostname: true,
+        }
+    }
+}
+
+impl From<&NatsConfig> for NatsTlsOptions {
+    fn from(config: &NatsConfig) -> Self {
+        Self {
+            require_tls: config.require_tls,
+            ca_cert: config.tls_ca_cert.clone(),
+            client_cert: config.tls_client_cert.clone(),
+            client_key: config.tls_client_key.clone(),
+            verify_hostname: config.tls_verify_hostname,
+        }
+    }
+}
+
+fn apply_tls(
+    mut options: async_nats::ConnectOptions,
+    tls: &NatsTlsOptions,
+) -> async_nats::ConnectOptions {
+    // any explicit certificate material implies TLS is required
+    let require_tls = tls.require_tls || tls.ca_cert.is_some() || tls.client_cert.is_some();
+    options = options.require_tls(require_tls);
+    if let Some(ca_cert) = &tls.ca_cert {
+        options = options.add_root_certificates(ca_cert.clone());
+    }
+    match (&tls.client_cert, &tls.client_key) {
+        (Some(cert), Some(key)) => {
+            options = options.add_client_certificate(cert.clone(), key.clone());
+        }
+        (None, None) => (),
+        _ => warn!(
+            "Mutual TLS requires both tls_client_cert and tls_client_key; ignoring partial configuration"
+        ),
+    }
+    if !tls.verify_hostname {
+        // async-nats does not expose a hostname verification toggle; verification
+        // stays enabled rather than silently weakening the connection
+        warn!(
+            "tls_verify_hostname=false is not supported, hostname verification stays enabled; add the server's LAN name or IP to the certificate SAN instead"
+        );
+    }
+    options
+}
+
+pub async fn try_init_nats_client_with_tls(
     nats_server_uri: &str,
     nats_creds: &Option<PathBuf>,
-    require_tls: bool,
+    tls: &NatsTlsOptions,
 ) -> Result<async_nats::Client, std::io::Error> {
     match nats_creds {
         Some(nats_creds) => match nats_creds.exists() {
             true => {
-                async_nats::ConnectOptions::with_credentials_file(nats_creds.clone())
-                    .await?
-                    .require_tls(require_tls)
-                    .connect(nats_server_uri)
-                    .await
+                apply_tls(
+                    async_nats::ConnectOptions::with_credentials_file(nats_creds.clone()).await?,
+                    tls,
+                )
+                .connect(nats_server_uri)
+                .await
             }
             false => {
                 warn!(
                     "Failed to read {}. Initializing NATS client without credentials",
                     nats_creds.display()
                 );
-                async_nats::ConnectOptions::new()
-                    .require_tls(require_tls)
+                apply_tls(async_nats::ConnectOptions::new(), tls)
                     .connect(nats_server_uri)
                     .await
             }
         },
         None => {
-            async_nats::ConnectOptions::new()
-                .require_tls(require_tls)
+            apply_tls(async_nats::ConnectOptions::new(), tls)
                 .connect(nats_server_uri)
                 .await
         }
     }
 }
 
-pub async fn wait_for_nats_client(
+pub async fn try_init_nats_client(
     nats_server_uri: &str,
     nats_creds: &Option<PathBuf>,
     require_tls: bool,
+) -> Result<async_nats::Client, std::io::Error> {
+    let tls = NatsTlsOptions {
+        require_tls,
+        ..NatsTlsOptions::default()
+    };
+    try_init_nats_client_with_tls(nats_server_uri, nats_creds, &tls).await
+}
+
+pub async fn wait_for_nats_client_with_tls(
+    nats_server_uri: &str,
+    nats_creds: &Option<PathBuf>,
+    tls: &NatsTlsOptions,
     wait: u64,
 ) -> Result<async_nats::Client, std::io::Error> {
     // wait for NATS to be available
     let mut nats_client: Option<async_nats::Client> = None;
     while nats_client.is_none() {
-        match try_init_nats_client(nats_server_uri, nats_creds, require_tls).await {
+        match try_init_nats_client_with_tls(nats_server_uri, nats_creds, tls).await {
             Ok(nc) => {
                 nats_client = Some(nc);
             }
@@ -58,3 +138,16 @@ pub async fn wait_for_nats_client(
     }
     Ok(nats_client.unwrap())
 }
+
+pub async fn wait_for_nats_client(
+    nats_server_uri: &str,
+    nats_creds: &Option<PathBuf>,
+    require_tls: bool,
+    wait: u64,
+) -> Result<async_nats::Client, std::io::Error> {
+    let tls = NatsTlsOptions {
+        require_tls,
+        ..NatsTlsOptions::default()
+    };
+    wait_for_nats_client_with_tls(nats_server_uri, nats_creds, &tls, wait).await
+}
@@ -0,0 +1,125 @@
+// Transparent gzip compression for large NATS payloads (settings file echoes,
+// log excerpts, diffs). Requesters advertise support via the accept-encoding
+// header; replies above COMPRESSION_THRESHOLD_BYTES are gzipped and tagged with
+// the content-encoding header. Untagged payloads pass through unchanged, so
+// subscribers without compression support keep working.
+use std::io::{Read, Write};
+
+use async_nats::HeaderMap;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+pub const ACCEPT_ENCODING_HEADER: &str = "X-PrintNanny-Accept-Encoding";
+pub const CONTENT_ENCODING_HEADER: &str = "X-PrintNanny-Content-Encoding";
+pub const GZIP_ENCODING: &str = "gzip";
+pub const COMPRESSION_THRESHOLD_BYTES: usize = 4096;
+
+pub(crate) fn header_value(headers: &Option<HeaderMap>, name: &str) -> Option<String> {
+    headers
+        .as_ref()
+        .and_then(|headers| headers.get(name))
+        .map(String::from)
+}
+
+// did the requester advertise gzip support?
+pub fn accepts_gzip(headers: &Option<HeaderMap>) -> bool {
+    header_value(headers, ACCEPT_ENCODING_HEADER)
+        .map(|value| value.contains(GZIP_ENCODING))
+        .unwrap_or(false)
+}
+
+// is the payload tagged as gzip-compressed?
+pub fn is_gzip(headers: &Option<HeaderMap>) -> bool {
+    header_value(headers, CONTENT_ENCODING_HEADER)
+        .map(|value| value == GZIP_ENCODING)
+        .unwrap_or(false)
+}
+
+pub fn gzip_compress(payload: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(payload)?;
+    encoder.finish()
+}
+
+pub fn gzip_decompress(payload: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut decoder = GzDecoder::new(payload);
+    let mut result = Vec::new();
+    decoder.read_to_end(&mut result)?;
+    Ok(result)
+}
+
+// gzip the reply when the requester accepts it and the payload exceeds the
+// threshold; returns the (possibly compressed) payload and headers to attach
+pub fn maybe_compress_reply(
+    payload: Vec<u8>,
+    request_headers: &Option<HeaderMap>,
+) -> std::io::Result<(Vec<u8>, Option<HeaderMap>)> {
+    if payload.len() > COMPRESSION_THRESHOLD_BYTES && accepts_gzip(request_headers) {
+        let compressed = gzip_compress(&payload)?;
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_ENCODING_HEADER, GZIP_ENCODING);
+        return Ok((compressed, Some(headers)));
+    }
+    Ok((payload, None))
+}
+
+// decompress an incoming payload if tagged, otherwise pass through unchanged
+pub fn maybe_decompress(payload: &[u8], headers: &Option<HeaderMap>) -> std::io::Result<Vec<u8>> {
+    match is_gzip(headers) {
+        true => gzip_decompress(payload),
+        false => Ok(payload.to_vec()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accept_gzip_headers() -> Option<HeaderMap> {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT_ENCODING_HEADER, GZIP_ENCODING);
+        Some(headers)
+    }
+
+    #[test_log::test]
+    fn test_gzip_roundtrip() {
+        let payload = vec![42u8; 10000];
+        let compressed = gzip_compress(&payload).unwrap();
+        assert!(compressed.len() < payload.len());
+        assert_eq!(gzip_decompress(&compressed).unwrap(), payload);
+    }
+
+    #[test_log::test]
+    fn test_large_reply_compressed_when_accepted() {
+        let payload = vec![42u8; COMPRESSION_THRESHOLD_BYTES + 1];
+        let (compressed, headers) =
+            maybe_compress_reply(payload.clone(), &accept_gzip_headers()).unwrap();
+        assert!(compressed.len() < payload.len());
+        assert!(is_gzip(&headers));
+        assert_eq!(maybe_decompress(&compressed, &headers).unwrap(), payload);
+    }
+
+    #[test_log::test]
+    fn test_small_reply_not_compressed() {
+        let payload = vec![42u8; 16];
+        let (result, headers) =
+            maybe_compress_reply(payload.clone(), &accept_gzip_headers()).unwrap();
+        assert_eq!(result, payload);
+        assert!(headers.is_none());
+    }
+
+    #[test_log::test]
+    fn test_reply_not_compressed_without_negotiation() {
+        let payload = vec![42u8; COMPRESSION_THRESHOLD_BYTES + 1];
+        let (result, headers) = maybe_compress_reply(payload.clone(), &None).unwrap();
+        assert_eq!(result, payload);
+        assert!(headers.is_none());
+    }
+
+    #[test_log::test]
+    fn test_plain_payload_passes_through() {
+        let payload = b"plain json".to_vec();
+        assert_eq!(maybe_decompress(&payload, &None).unwrap(), payload);
+    }
+}
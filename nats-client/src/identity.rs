@@ -0,0 +1,184 @@
+// Device identity keys for signing outgoing NATS traffic. An Ed25519 seed is
+// generated on first use and stored in the secrets dir (paths.creds());
+// status events and command replies carry a detached signature over the
+// (uncompressed) payload in message headers, so the cloud can verify a
+// message originated from this device rather than from anything holding the
+// transport credentials. Unsigned messages keep working: verification is
+// strictly additive, and a signing failure downgrades to an unsigned publish.
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Result};
+use async_nats::HeaderMap;
+use log::{info, warn};
+use ring::rand::SecureRandom;
+use ring::signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519};
+
+use printnanny_settings::paths::PrintNannyPaths;
+
+pub const IDENTITY_SEED_FILENAME: &str = "identity-ed25519.seed";
+pub const SIGNATURE_HEADER: &str = "X-PrintNanny-Signature";
+pub const PUBKEY_HEADER: &str = "X-PrintNanny-Pubkey";
+// reported in pi.{pi_id}.identity.pubkey replies so the verifying side knows
+// what to do with the key material
+pub const SIGNATURE_ALGORITHM: &str = "ed25519";
+
+const SEED_LENGTH: usize = 32;
+
+fn seed_path(paths: &PrintNannyPaths) -> PathBuf {
+    paths.creds().join(IDENTITY_SEED_FILENAME)
+}
+
+// load the device identity keypair, generating a seed on first use
+pub fn keypair(paths: &PrintNannyPaths) -> Result<Ed25519KeyPair> {
+    let seed_file = seed_path(paths);
+    let seed = match std::fs::read(&seed_file) {
+        Ok(seed) => seed,
+        Err(_) => {
+            let mut seed = vec![0u8; SEED_LENGTH];
+            ring::rand::SystemRandom::new()
+                .fill(&mut seed)
+                .map_err(|_| anyhow!("Failed to gather entropy for identity key"))?;
+            printnanny_settings::atomic_write::atomic_write_sync(&seed_file, &seed)?;
+            info!("Generated device identity key {}", seed_file.display());
+            seed
+        }
+    };
+    Ed25519KeyPair::from_seed_unchecked(&seed)
+        .map_err(|e| anyhow!("Invalid identity seed {}: {}", seed_file.display(), e))
+}
+
+// retire the current seed and generate a fresh keypair; the retired seed is
+// kept alongside (timestamped) so an operator can still verify older messages
+pub fn rotate(paths: &PrintNannyPaths) -> Result<Ed25519KeyPair> {
+    let seed_file = seed_path(paths);
+    if seed_file.exists() {
+        let ts = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let retired = seed_file.with_extension(format!("seed.retired.{}", ts));
+        std::fs::rename(&seed_file, &retired)?;
+        info!("Retired device identity key to {}", retired.display());
+    }
+    keypair(paths)
+}
+
+pub fn public_key_b64(keypair: &Ed25519KeyPair) -> String {
+    base64::encode(keypair.public_key().as_ref())
+}
+
+pub fn sign_b64(keypair: &Ed25519KeyPair, payload: &[u8]) -> String {
+    base64::encode(keypair.sign(payload).as_ref())
+}
+
+// cloud/gateway-side check of a detached signature
+pub fn verify_b64(pubkey_b64: &str, payload: &[u8], signature_b64: &str) -> Result<()> {
+    let pubkey = base64::decode(pubkey_b64)?;
+    let signature = base64::decode(signature_b64)?;
+    UnparsedPublicKey::new(&ED25519, &pubkey)
+        .verify(payload, &signature)
+        .map_err(|_| anyhow!("Identity signature verification failed"))
+}
+
+// add signature + public key headers for `payload` onto the outgoing headers,
+// creating the map when the message had none; a signing failure is logged and
+// the message goes out unsigned
+pub fn try_sign_headers(
+    paths: &PrintNannyPaths,
+    headers: Option<HeaderMap>,
+    payload: &[u8],
+) -> Option<HeaderMap> {
+    match keypair(paths) {
+        Ok(keypair) => {
+            let mut headers = headers.unwrap_or_default();
+            headers.insert(SIGNATURE_HEADER, sign_b64(&keypair, payload).as_str());
+            headers.insert(PUBKEY_HEADER, public_key_b64(&keypair).as_str());
+            Some(headers)
+        }
+        Err(e) => {
+            warn!(
+                "Failed to sign outgoing payload, publishing unsigned: {}",
+                e
+            );
+            headers
+        }
+    }
+}
+
+// verify the signature headers attached to a payload (the reassembled,
+// decompressed bytes); used by tests and by cloud-side consumers built on
+// this crate
+pub fn verify_headers(headers: &Option<HeaderMap>, payload: &[u8]) -> Result<()> {
+    let signature = crate::compression::header_value(headers, SIGNATURE_HEADER)
+        .ok_or_else(|| anyhow!("Missing {} header", SIGNATURE_HEADER))?;
+    let pubkey = crate::compression::header_value(headers, PUBKEY_HEADER)
+        .ok_or_else(|| anyhow!("Missing {} header", PUBKEY_HEADER))?;
+    verify_b64(&pubkey, payload, &signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_paths(dir: &tempfile::TempDir) -> PrintNannyPaths {
+        PrintNannyPaths {
+            state_dir: dir.path().to_path_buf(),
+            ..PrintNannyPaths::default()
+        }
+    }
+
+    #[test_log::test]
+    fn test_sign_and_verify_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = test_paths(&dir);
+        std::fs::create_dir_all(paths.creds()).unwrap();
+        let payload = b"{\"state\": \"Printing\"}";
+        let headers = try_sign_headers(&paths, None, payload);
+        assert!(headers.is_some());
+        verify_headers(&headers, payload).unwrap();
+        // the seed persists, so the same key signs across processes
+        assert!(seed_path(&paths).exists());
+    }
+
+    #[test_log::test]
+    fn test_tampered_payload_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = test_paths(&dir);
+        std::fs::create_dir_all(paths.creds()).unwrap();
+        let headers = try_sign_headers(&paths, None, b"original payload");
+        assert!(verify_headers(&headers, b"tampered payload").is_err());
+    }
+
+    #[test_log::test]
+    fn test_rotate_changes_pubkey_and_retires_seed() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = test_paths(&dir);
+        std::fs::create_dir_all(paths.creds()).unwrap();
+        let before = public_key_b64(&keypair(&paths).unwrap());
+        let after = public_key_b64(&rotate(&paths).unwrap());
+        assert_ne!(before, after);
+        // the retired seed stays alongside the new one
+        let retired = std::fs::read_dir(paths.creds())
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().contains("seed.retired"))
+            .count();
+        assert_eq!(retired, 1);
+    }
+
+    #[test_log::test]
+    fn test_foreign_signature_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let other_dir = tempfile::tempdir().unwrap();
+        let paths = test_paths(&dir);
+        let other_paths = test_paths(&other_dir);
+        std::fs::create_dir_all(paths.creds()).unwrap();
+        std::fs::create_dir_all(other_paths.creds()).unwrap();
+        let payload = b"payload";
+        let signature = sign_b64(&keypair(&other_paths).unwrap(), payload);
+        let pubkey = public_key_b64(&keypair(&paths).unwrap());
+        // signed by a different device key
+        assert!(verify_b64(&pubkey, payload, &signature).is_err());
+    }
+}
@@ -1,6 +1,10 @@
+pub mod chunking;
 pub mod client;
+pub mod compression;
 pub mod error;
 pub mod event;
+pub mod identity;
 pub mod request_reply;
 pub mod subscriber;
+pub mod trace;
 pub mod util;
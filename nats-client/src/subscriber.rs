@@ -11,7 +11,9 @@ use serde::{Deserialize, Serialize};
 
 use printnanny_settings::sys_info;
 
-use super::client::wait_for_nats_client;
+use super::chunking;
+use super::client::{wait_for_nats_client_with_tls, NatsTlsOptions};
+use super::compression;
 use super::event::NatsEventHandler;
 use super::request_reply::NatsRequestHandler;
 use crate::error::{NatsError, RequestErrorMsg};
@@ -40,6 +42,76 @@ const DEFAULT_NATS_URI: &str = "nats://localhost:4223";
 pub const DEFAULT_NATS_EDGE_APP_NAME: &str = "nats-edge-worker";
 pub const DEFAULT_NATS_EDGE_SUBJECT: &str = "pi.localhost.>";
 
+// oldest entries are dropped once the offline publish buffer reaches this size
+pub const OFFLINE_BUFFER_MAX: usize = 256;
+
+// publishes buffered while the NATS connection is down, flushed FIFO on
+// reconnect
+static OFFLINE_BUFFER: std::sync::Mutex<Vec<(String, Vec<u8>)>> = std::sync::Mutex::new(Vec::new());
+
+// queue a publish for replay after the connection is re-established
+pub fn buffer_offline_publish(subject: String, payload: Vec<u8>) {
+    let mut buffer = OFFLINE_BUFFER.lock().unwrap();
+    if buffer.len() >= OFFLINE_BUFFER_MAX {
+        let (dropped, _) = buffer.remove(0);
+        warn!("Offline publish buffer is full, dropped oldest entry subject={dropped}");
+    }
+    buffer.push((subject, payload));
+}
+
+fn drain_offline_buffer() -> Vec<(String, Vec<u8>)> {
+    let mut buffer = OFFLINE_BUFFER.lock().unwrap();
+    std::mem::take(&mut *buffer)
+}
+
+// signal used by request handlers (credential rotation) to end the current
+// subscription stream; run() then reconnects, re-reading the creds file and
+// replaying the offline buffer, so no buffered events are lost
+static RECONNECT_SIGNAL: std::sync::Mutex<Option<std::sync::Arc<tokio::sync::Notify>>> =
+    std::sync::Mutex::new(None);
+
+fn reconnect_signal() -> std::sync::Arc<tokio::sync::Notify> {
+    let mut guard = RECONNECT_SIGNAL.lock().unwrap();
+    guard
+        .get_or_insert_with(|| std::sync::Arc::new(tokio::sync::Notify::new()))
+        .clone()
+}
+
+// ask the running subscriber to drop its connection and reconnect; in-flight
+// requests (including the one that triggered the reconnect) finish and their
+// replies are published on the old connection first
+pub fn request_reconnect() {
+    info!("NATS subscriber reconnect requested");
+    reconnect_signal().notify_one();
+}
+
+// connectivity transition, published on pi.{hostname}.event.nats.connectivity
+// after every (re)connect
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NatsConnectivityEvent {
+    pub state: String,
+    // number of mid-run disconnects observed since the worker started
+    pub disconnects: u64,
+    pub ts: chrono::DateTime<chrono::Utc>,
+}
+
+// combine compression and identity signature headers onto one outgoing map
+fn merge_headers(
+    base: Option<async_nats::HeaderMap>,
+    extra: Option<async_nats::HeaderMap>,
+) -> Option<async_nats::HeaderMap> {
+    match (base, extra) {
+        (Some(mut base), Some(extra)) => {
+            for (name, value) in extra.iter() {
+                base.insert(name.clone(), value.clone());
+            }
+            Some(base)
+        }
+        (base, None) => base,
+        (None, extra) => extra,
+    }
+}
+
 pub fn get_default_nats_subject() -> String {
     let hostname = sys_info::hostname().unwrap();
     format!("pi.{}.>", hostname)
@@ -94,6 +166,13 @@ where
                     .long("socket")
                     .takes_value(true)
                     .default_value(DEFAULT_NATS_SOCKET_PATH),
+            )
+            .arg(
+                Arg::new("trace_subject")
+                    .long("trace-subject")
+                    .takes_value(true)
+                    .multiple_occurrences(true)
+                    .help("Record redacted request/reply payloads for this subject pattern (repeatable)"),
             );
         app
     }
@@ -131,6 +210,12 @@ where
             // see https://github.com/bitsy-ai/printnanny-os/issues/238
             .to_lowercase();
         let workers: usize = args.value_of_t("workers").unwrap_or(8);
+
+        // --trace-subject enables message tracing from startup; the
+        // pi.{pi_id}.debug.trace.* requests toggle it at runtime
+        if let Some(trace_subjects) = args.values_of("trace_subject") {
+            crate::trace::enable(trace_subjects.map(String::from).collect(), None);
+        }
         Self {
             hostname,
             subject,
@@ -143,14 +228,7 @@ where
             _response: PhantomData,
         }
     }
-    pub async fn subscribe_nats_subject(&self) -> Result<()> {
-        let nats_client = wait_for_nats_client(
-            &self.nats_server_uri,
-            &self.nats_creds,
-            self.require_tls,
-            2000,
-        )
-        .await?;
+    pub async fn subscribe_nats_subject(&self, nats_client: &async_nats::Client) -> Result<()> {
         warn!(
             "Subscribing to subject {} with nats client {:?}",
             self.subject, nats_client
@@ -161,6 +239,12 @@ where
             &self.nats_server_uri, &self.subject
         );
 
+        // end the stream when a reconnect is requested (credential rotation);
+        // for_each_concurrent drains in-flight handlers before returning, so
+        // the triggering request's reply still goes out on this connection
+        let signal = reconnect_signal();
+        let subscriber = subscriber.take_until(Box::pin(async move { signal.notified().await }));
+
         subscriber
             .for_each_concurrent(self.workers, |message| async {
                 let subject_pattern =
@@ -170,15 +254,89 @@ where
                     &subject_pattern, &message.subject, &self.hostname
                 );
                 debug!("Attempting to handle NATS Message: {:?}", message);
+                // reassemble chunked transfers before handling; intermediate
+                // chunks carry no handleable payload yet
+                let payload = match chunking::try_reassemble(&message.headers, &message.payload) {
+                    chunking::ReassemblyResult::NotChunked => message.payload.to_vec(),
+                    chunking::ReassemblyResult::Partial => return,
+                    chunking::ReassemblyResult::Complete(payload) => payload,
+                };
+                // transparently decompress payloads tagged with the content-encoding header
+                let payload = match compression::maybe_decompress(&payload, &message.headers) {
+                    Ok(payload) => bytes::Bytes::from(payload),
+                    Err(e) => {
+                        error!("Error decompressing NATS payload error={}", e);
+                        return;
+                    }
+                };
+                // support-session tracing; no-op unless enabled for this subject
+                crate::trace::record("request", &message.subject, &payload);
                 match message.reply {
                     // request / reply pattern
                     Some(reply_inbox) => {
-                        let payload = self
-                            .handle_request(&message.payload, &subject_pattern)
-                            .await;
-                        match payload {
-                            Some(payload) => {
-                                match &nats_client.publish(reply_inbox, payload.into()).await {
+                        let reply_payload = self.handle_request(&payload, &subject_pattern).await;
+                        if let Some(reply_payload) = &reply_payload {
+                            crate::trace::record("reply", &message.subject, reply_payload);
+                        }
+                        match reply_payload {
+                            Some(reply_payload) => {
+                                // sign the uncompressed reply so the requester can verify
+                                // it originated from this device
+                                let signature_headers = crate::identity::try_sign_headers(
+                                    &printnanny_settings::paths::PrintNannyPaths::default(),
+                                    None,
+                                    &reply_payload,
+                                );
+                                // gzip large replies when the requester advertised support
+                                let (reply_payload, headers) =
+                                    match compression::maybe_compress_reply(
+                                        reply_payload,
+                                        &message.headers,
+                                    ) {
+                                        Ok(result) => result,
+                                        Err(e) => {
+                                            error!("Error compressing reply payload error={}", e);
+                                            return;
+                                        }
+                                    };
+                                let headers = merge_headers(headers, signature_headers);
+                                // split oversized replies into sequence-numbered chunks
+                                let result = if reply_payload.len() > chunking::MAX_CHUNK_BYTES {
+                                    let mut result = Ok(());
+                                    for (chunk_headers, chunk) in
+                                        chunking::split_payload(&reply_payload, &headers)
+                                    {
+                                        result = nats_client
+                                            .publish_with_headers(
+                                                reply_inbox.clone(),
+                                                chunk_headers,
+                                                chunk.into(),
+                                            )
+                                            .await;
+                                        if result.is_err() {
+                                            break;
+                                        }
+                                    }
+                                    result
+                                } else {
+                                    match headers {
+                                        Some(headers) => {
+                                            nats_client
+                                                .publish_with_headers(
+                                                    reply_inbox,
+                                                    headers,
+                                                    reply_payload.into(),
+                                                )
+                                                .await
+                                        }
+                                        None => {
+                                            nats_client
+                                                .publish(reply_inbox, reply_payload.into())
+                                                .await
+                                        }
+                                    }
+                                };
+                                match &result {
                                     Ok(_) => (),
                                     Err(e) => {
                                         error!("Error publishing msg: {}", e);
@@ -195,7 +353,7 @@ where
                     }
                     // one-way event handler
                     None => {
-                        self.handle_event(&message.payload, &subject_pattern).await;
+                        self.handle_event(&payload, &subject_pattern).await;
                     }
                 }
             })
@@ -203,25 +361,38 @@ where
         Ok(())
     }
     // FIFO buffer flush
+    // publish each buffered entry individually; entries that still fail are
+    // re-queued for the next reconnect instead of being discarded along with
+    // everything behind them
     pub async fn try_flush_buffer(
         &self,
         request_buffer: &[(String, Vec<u8>)],
         nats_client: &async_nats::Client,
     ) -> Result<(), NatsError> {
-        for request in request_buffer.iter() {
-            let (subject, payload) = request;
-            match nats_client
+        let mut failed: usize = 0;
+        for (subject, payload) in request_buffer.iter() {
+            if let Err(e) = nats_client
                 .publish(subject.to_string(), payload.clone().into())
                 .await
             {
-                Ok(_) => Ok(()),
-                Err(e) => Err(NatsError::PublishError {
-                    error: e.to_string(),
-                }),
-            }?;
+                warn!(
+                    "Failed to replay buffered publish subject={} error={}, re-queued",
+                    subject, e
+                );
+                buffer_offline_publish(subject.clone(), payload.clone());
+                failed += 1;
+            }
+        }
+        match failed {
+            0 => Ok(()),
+            _ => Err(NatsError::PublishError {
+                error: format!(
+                    "{} of {} buffered publishes failed and were re-queued",
+                    failed,
+                    request_buffer.len()
+                ),
+            }),
         }
-
-        Ok(())
     }
 
     async fn handle_request(
@@ -260,8 +431,118 @@ where
         }
     }
 
+    async fn publish_connectivity_event(
+        &self,
+        nats_client: &async_nats::Client,
+        state: &str,
+        disconnects: u64,
+    ) {
+        let subject = format!("pi.{}.event.nats.connectivity", self.hostname);
+        let event = NatsConnectivityEvent {
+            state: state.to_string(),
+            disconnects,
+            ts: chrono::Utc::now(),
+        };
+        let payload = serde_json::to_vec(&event).unwrap();
+        match nats_client
+            .publish(subject.clone(), payload.clone().into())
+            .await
+        {
+            Ok(_) => info!("Published {} state={}", subject, state),
+            Err(e) => {
+                // the connection dropped again before the transition got out;
+                // replay it after the next reconnect so subscribers still see
+                // the full disconnect history
+                error!(
+                    "Error publishing {} error={}, buffered for replay",
+                    subject, e
+                );
+                buffer_offline_publish(subject, payload);
+            }
+        }
+    }
+
+    // (re)connect, replay the offline buffer and consume the subscription
+    // stream until the connection drops; a mid-run disconnect previously ended
+    // the stream silently and left the worker sitting idle
     pub async fn run(&self) -> Result<()> {
-        self.subscribe_nats_subject().await?;
-        Ok(())
+        let mut disconnects: u64 = 0;
+        loop {
+            // TLS material comes from the [nats] settings table; re-read on
+            // every (re)connect so edits take effect without a restart
+            let tls = match printnanny_settings::printnanny::PrintNannySettings::new().await {
+                Ok(settings) => {
+                    let mut tls = NatsTlsOptions::from(&settings.nats);
+                    tls.require_tls = tls.require_tls || self.require_tls;
+                    tls
+                }
+                Err(e) => {
+                    warn!(
+                        "Failed to load PrintNannySettings error={}, connecting with default TLS options",
+                        e
+                    );
+                    NatsTlsOptions {
+                        require_tls: self.require_tls,
+                        ..NatsTlsOptions::default()
+                    }
+                }
+            };
+            let nats_client =
+                wait_for_nats_client_with_tls(&self.nats_server_uri, &self.nats_creds, &tls, 2000)
+                    .await?;
+
+            // replay publishes buffered while the connection was down
+            let buffered = drain_offline_buffer();
+            if !buffered.is_empty() {
+                match self.try_flush_buffer(&buffered, &nats_client).await {
+                    Ok(_) => info!("Flushed {} buffered publishes", buffered.len()),
+                    Err(e) => error!("Error flushing offline publish buffer error={}", e),
+                }
+            }
+
+            let state = match disconnects {
+                0 => "connected",
+                _ => "reconnected",
+            };
+            self.publish_connectivity_event(&nats_client, state, disconnects)
+                .await;
+
+            match self.subscribe_nats_subject(&nats_client).await {
+                Ok(_) => warn!(
+                    "NATS subscription stream ended (connection to {} lost), reconnecting",
+                    self.nats_server_uri
+                ),
+                Err(e) => error!(
+                    "NATS subscription error={}, reconnecting to {}",
+                    e, self.nats_server_uri
+                ),
+            };
+            disconnects += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the buffer is one process-wide static, so the fill, cap, FIFO order,
+    // and drain-once behavior are covered by a single test to avoid
+    // cross-test interference
+    #[test_log::test]
+    fn test_offline_buffer_replays_fifo_once() {
+        for i in 0..(OFFLINE_BUFFER_MAX + 1) {
+            buffer_offline_publish(format!("pi.test.event.{i}"), vec![i as u8]);
+        }
+        let drained = drain_offline_buffer();
+        assert_eq!(drained.len(), OFFLINE_BUFFER_MAX);
+        // the oldest entry was dropped when the buffer hit its cap
+        assert_eq!(drained[0].0, "pi.test.event.1");
+        assert_eq!(
+            drained.last().unwrap().0,
+            format!("pi.test.event.{}", OFFLINE_BUFFER_MAX)
+        );
+        // a second drain has nothing left to replay
+        assert!(drain_offline_buffer().is_empty());
     }
 }
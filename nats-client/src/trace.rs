@@ -0,0 +1,234 @@
+// Per-subject message tracing for support sessions: when enabled, full
+// request/reply payloads for matching subject patterns are recorded to an
+// in-memory ring buffer and mirrored to a JSON-lines file, with secret-looking
+// fields redacted before anything is stored. Dumped via pi.{pi_id}.debug.trace.dump.
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+
+// oldest entries are dropped once the ring buffer reaches this size
+pub const TRACE_BUFFER_MAX: usize = 256;
+
+pub const DEFAULT_TRACE_FILE: &str = "/var/run/printnanny/nats-trace.jsonl";
+
+// payload fields whose values are replaced before an entry is stored
+const REDACTED_KEYS: &[&str] = &[
+    "api_key",
+    "api_token",
+    "access_token",
+    "authorization",
+    "bearer",
+    "creds",
+    "credentials",
+    "jwt",
+    "password",
+    "secret",
+    "token",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceEntry {
+    pub ts: chrono::DateTime<chrono::Utc>,
+    // "request" or "reply"
+    pub direction: String,
+    pub subject: String,
+    // JSON payloads are stored structurally (post-redaction); anything else is
+    // stored as a lossy string
+    pub payload: serde_json::Value,
+}
+
+#[derive(Debug)]
+struct TraceState {
+    patterns: Vec<String>,
+    file: PathBuf,
+    entries: VecDeque<TraceEntry>,
+}
+
+static TRACE: Mutex<Option<TraceState>> = Mutex::new(None);
+
+// NATS wildcard match: * matches one token, > matches the rest of the subject
+fn subject_matches(pattern: &str, subject: &str) -> bool {
+    let mut pattern_tokens = pattern.split('.');
+    let mut subject_tokens = subject.split('.');
+    loop {
+        match (pattern_tokens.next(), subject_tokens.next()) {
+            (Some(">"), _) => return true,
+            (Some("*"), Some(_)) => continue,
+            (Some(p), Some(s)) if p == s => continue,
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
+
+// replace secret-looking field values in place, recursing into nested objects
+fn redact(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, v) in map.iter_mut() {
+                let key = key.to_lowercase();
+                if REDACTED_KEYS.iter().any(|r| key.contains(r)) {
+                    *v = serde_json::Value::String("[redacted]".to_string());
+                } else {
+                    redact(v);
+                }
+            }
+        }
+        serde_json::Value::Array(values) => {
+            for v in values.iter_mut() {
+                redact(v);
+            }
+        }
+        _ => (),
+    }
+}
+
+pub fn enable(patterns: Vec<String>, file: Option<PathBuf>) {
+    let file = file.unwrap_or_else(|| PathBuf::from(DEFAULT_TRACE_FILE));
+    info!(
+        "NATS message tracing enabled for patterns={:?} file={}",
+        patterns,
+        file.display()
+    );
+    let mut guard = TRACE.lock().unwrap();
+    *guard = Some(TraceState {
+        patterns,
+        file,
+        entries: VecDeque::with_capacity(TRACE_BUFFER_MAX),
+    });
+}
+
+pub fn disable() {
+    let mut guard = TRACE.lock().unwrap();
+    if guard.take().is_some() {
+        info!("NATS message tracing disabled");
+    }
+}
+
+pub fn is_enabled() -> bool {
+    TRACE.lock().unwrap().is_some()
+}
+
+pub fn patterns() -> Vec<String> {
+    TRACE
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|state| state.patterns.clone())
+        .unwrap_or_default()
+}
+
+pub fn trace_file() -> Option<PathBuf> {
+    TRACE
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|state| state.file.clone())
+}
+
+// snapshot of the ring buffer, oldest entry first
+pub fn dump() -> Vec<TraceEntry> {
+    TRACE
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|state| state.entries.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+// record one payload if tracing is enabled and the subject matches; called on
+// the subscriber hot path, so the disabled case is a single mutex probe
+pub fn record(direction: &str, subject: &str, payload: &[u8]) {
+    let mut guard = TRACE.lock().unwrap();
+    let state = match guard.as_mut() {
+        Some(state) => state,
+        None => return,
+    };
+    if !state
+        .patterns
+        .iter()
+        .any(|pattern| subject_matches(pattern, subject))
+    {
+        return;
+    }
+    let mut payload = match serde_json::from_slice::<serde_json::Value>(payload) {
+        Ok(value) => value,
+        Err(_) => serde_json::Value::String(String::from_utf8_lossy(payload).to_string()),
+    };
+    redact(&mut payload);
+    let entry = TraceEntry {
+        ts: chrono::Utc::now(),
+        direction: direction.to_string(),
+        subject: subject.to_string(),
+        payload,
+    };
+    if state.entries.len() >= TRACE_BUFFER_MAX {
+        state.entries.pop_front();
+    }
+    state.entries.push_back(entry.clone());
+
+    // mirror to the trace file; rewritten from the ring once it grows past the
+    // cap so the on-disk copy stays bounded like the in-memory buffer
+    let result = match std::fs::metadata(&state.file) {
+        Ok(_) if state.entries.len() >= TRACE_BUFFER_MAX => {
+            let lines: Vec<String> = state
+                .entries
+                .iter()
+                .map(|e| serde_json::to_string(e).unwrap())
+                .collect();
+            std::fs::write(&state.file, lines.join("\n") + "\n")
+        }
+        _ => std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&state.file)
+            .and_then(|mut f| writeln!(f, "{}", serde_json::to_string(&entry).unwrap())),
+    };
+    if let Err(e) = result {
+        error!(
+            "Error writing NATS trace entry to {} error={}",
+            state.file.display(),
+            e
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subject_matches_wildcards() {
+        assert!(subject_matches(
+            "pi.printnanny.>",
+            "pi.printnanny.settings.file.load"
+        ));
+        assert!(subject_matches("pi.*.lights", "pi.printnanny.lights"));
+        assert!(subject_matches(
+            "pi.printnanny.lights",
+            "pi.printnanny.lights"
+        ));
+        assert!(!subject_matches("pi.*.lights", "pi.printnanny.lights.on"));
+        assert!(!subject_matches("pi.other.>", "pi.printnanny.lights"));
+    }
+
+    #[test]
+    fn test_redact_masks_secret_fields() {
+        let mut payload = serde_json::json!({
+            "api_key": "abc123",
+            "nested": { "Authorization": "Bearer xyz", "detail": "ok" },
+            "items": [ { "password": "hunter2" } ],
+            "plain": "visible"
+        });
+        redact(&mut payload);
+        assert_eq!(payload["api_key"], "[redacted]");
+        assert_eq!(payload["nested"]["Authorization"], "[redacted]");
+        assert_eq!(payload["items"][0]["password"], "[redacted]");
+        assert_eq!(payload["nested"]["detail"], "ok");
+        assert_eq!(payload["plain"], "visible");
+    }
+}
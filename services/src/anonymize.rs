@@ -0,0 +1,92 @@
+// Export-time anonymization: before a recording leaves the device (cloud
+// sync), the user-defined region from AnonymizeSettings is blurred or cropped
+// by a one-shot gst-launch transcode. Running outside gstd keeps the live
+// pipeline graph untouched; local previews and detection always see the full
+// frame.
+use std::path::{Path, PathBuf};
+
+use async_process::Command;
+use log::info;
+
+use printnanny_settings::printnanny::PrintNannySettings;
+
+use crate::error::VideoRecordingSyncError;
+
+pub fn transcode_pipeline_description(
+    settings: &PrintNannySettings,
+    input: &Path,
+    output: &Path,
+) -> String {
+    let video = &settings.video_stream;
+    // recordings are encoded at the configured camera resolution
+    let fragment = video
+        .anonymize
+        .gst_anonymize_fragment(video.camera.width, video.camera.height);
+    format!(
+        "filesrc location={input} ! qtdemux ! h264parse ! v4l2h264dec ! videoconvert \
+        {fragment}! videoconvert \
+        ! v4l2h264enc extra-controls=controls,repeat_sequence_header=1 \
+        ! h264parse ! mp4mux ! filesink location={output}",
+        input = input.display(),
+        output = output.display(),
+    )
+}
+
+// replace the recording with its anonymized transcode, returning whether a
+// transcode ran; a no-op while the setting is disabled
+pub async fn anonymize_in_place(
+    settings: &PrintNannySettings,
+    file_name: &str,
+) -> Result<bool, VideoRecordingSyncError> {
+    if !settings.video_stream.anonymize.is_active() {
+        return Ok(false);
+    }
+    let input = PathBuf::from(file_name);
+    let output = input.with_extension("anonymized.mp4");
+    let description = transcode_pipeline_description(settings, &input, &output);
+    info!("Anonymizing {} with pipeline: {}", file_name, description);
+    let result = Command::new("sh")
+        .args(["-c", &format!("gst-launch-1.0 -e {}", description)])
+        .output()
+        .await?;
+    if !result.status.success() {
+        // leave the original untouched; the caller decides whether to upload
+        tokio::fs::remove_file(&output).await.ok();
+        return Err(VideoRecordingSyncError::AnonymizeError {
+            file_name: file_name.to_string(),
+            detail: String::from_utf8_lossy(&result.stderr).to_string(),
+        });
+    }
+    tokio::fs::rename(&output, &input).await?;
+    info!("Anonymized {}", file_name);
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use printnanny_settings::cam::{AnonymizeMode, AnonymizeRegion, AnonymizeSettings};
+
+    #[test_log::test]
+    fn test_transcode_pipeline_description() {
+        let mut settings = PrintNannySettings::default();
+        settings.video_stream.anonymize = AnonymizeSettings {
+            enabled: true,
+            mode: AnonymizeMode::Crop,
+            region: AnonymizeRegion {
+                x: 0,
+                y: 0,
+                width: 320,
+                height: 480,
+            },
+        };
+        let description = transcode_pipeline_description(
+            &settings,
+            Path::new("/tmp/in.mp4"),
+            Path::new("/tmp/out.mp4"),
+        );
+        assert!(description.starts_with("filesrc location=/tmp/in.mp4"));
+        assert!(description.contains("! videocrop top=0 left=0 right=320 bottom=0 "));
+        assert!(description.ends_with("filesink location=/tmp/out.mp4"));
+    }
+}
@@ -0,0 +1,172 @@
+// Local authentication for the dashboard REST API and WebSocket gateway.
+// Credentials live in the edge sqlite db (printnanny_edge_db::local_auth) as
+// argon2id PHC hashes; logging in mints an opaque session token with a
+// server-side expiry that the gateway resolves back to a user + role on each
+// request. The first user must be created with `printnanny user add` (or by
+// the dash setup flow) since shipping a default password would defeat the
+// point.
+use argon2::password_hash::{rand_core::OsRng, PasswordHash, SaltString};
+use argon2::{Argon2, PasswordHasher, PasswordVerifier};
+use chrono::Duration;
+use log::{info, warn};
+
+use printnanny_edge_db::local_auth::{LocalSession, LocalUser, LocalUserRole};
+
+use crate::error::ServiceError;
+
+pub const DEFAULT_SESSION_TTL_HOURS: i64 = 24;
+
+pub fn hash_password(password: &str) -> Result<String, ServiceError> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| ServiceError::PasswordHashError { msg: e.to_string() })
+}
+
+pub fn verify_password(password: &str, hash: &str) -> bool {
+    match PasswordHash::new(hash) {
+        Ok(parsed) => Argon2::default()
+            .verify_password(password.as_bytes(), &parsed)
+            .is_ok(),
+        Err(e) => {
+            warn!("Failed to parse stored password hash error={}", e);
+            false
+        }
+    }
+}
+
+pub fn create_user(
+    connection_str: &str,
+    username: &str,
+    password: &str,
+    role: LocalUserRole,
+) -> Result<LocalUser, ServiceError> {
+    let password_hash = hash_password(password)?;
+    Ok(LocalUser::create(
+        connection_str,
+        username,
+        &password_hash,
+        role,
+    )?)
+}
+
+pub fn set_password(
+    connection_str: &str,
+    username: &str,
+    password: &str,
+) -> Result<(), ServiceError> {
+    let password_hash = hash_password(password)?;
+    LocalUser::update_password_hash(connection_str, username, &password_hash)?;
+    Ok(())
+}
+
+// verify credentials and mint a session token. Unknown usernames and wrong
+// passwords return the same error so login attempts can not enumerate users.
+pub fn login(
+    connection_str: &str,
+    username: &str,
+    password: &str,
+) -> Result<LocalSession, ServiceError> {
+    let user = LocalUser::get_by_username(connection_str, username)?;
+    match user {
+        Some(user) if verify_password(password, &user.password_hash) => {
+            let session = LocalSession::create(
+                connection_str,
+                user.id,
+                Duration::hours(DEFAULT_SESSION_TTL_HOURS),
+            )?;
+            info!("LocalUser username={} logged in", username);
+            Ok(session)
+        }
+        _ => Err(ServiceError::InvalidCredentials),
+    }
+}
+
+// resolve a session token to its user; expired/revoked tokens resolve to None
+pub fn authenticate_session(
+    connection_str: &str,
+    session_id: &str,
+) -> Result<Option<LocalUser>, ServiceError> {
+    Ok(LocalSession::get_user(connection_str, session_id)?)
+}
+
+// logout: revoke a single session token
+pub fn logout(connection_str: &str, session_id: &str) -> Result<(), ServiceError> {
+    LocalSession::delete(connection_str, session_id)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use printnanny_edge_db::connection::run_migrations;
+
+    fn test_db() -> (tempfile::TempDir, String) {
+        let dir = tempfile::tempdir().unwrap();
+        let connection_str = dir.path().join("db.sqlite").display().to_string();
+        run_migrations(&connection_str).unwrap();
+        (dir, connection_str)
+    }
+
+    #[test_log::test]
+    fn test_hash_and_verify_roundtrip() {
+        let hash = hash_password("correct horse battery staple").unwrap();
+        assert!(verify_password("correct horse battery staple", &hash));
+        assert!(!verify_password("tr0ub4dor&3", &hash));
+        // PHC string, not the password itself
+        assert!(hash.starts_with("$argon2"));
+    }
+
+    #[test_log::test]
+    fn test_login_mints_session_resolving_to_user() {
+        let (_dir, connection_str) = test_db();
+        create_user(&connection_str, "leigh", "hunter2", LocalUserRole::Admin).unwrap();
+
+        let session = login(&connection_str, "leigh", "hunter2").unwrap();
+        let user = authenticate_session(&connection_str, &session.id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(user.username, "leigh");
+        assert_eq!(user.role(), Some(LocalUserRole::Admin));
+    }
+
+    #[test_log::test]
+    fn test_bad_credentials_are_indistinguishable() {
+        let (_dir, connection_str) = test_db();
+        create_user(&connection_str, "leigh", "hunter2", LocalUserRole::Viewer).unwrap();
+
+        let wrong_password = login(&connection_str, "leigh", "wrong");
+        let unknown_user = login(&connection_str, "nobody", "hunter2");
+        assert!(matches!(
+            wrong_password,
+            Err(ServiceError::InvalidCredentials)
+        ));
+        assert!(matches!(
+            unknown_user,
+            Err(ServiceError::InvalidCredentials)
+        ));
+    }
+
+    #[test_log::test]
+    fn test_logout_revokes_session() {
+        let (_dir, connection_str) = test_db();
+        create_user(&connection_str, "leigh", "hunter2", LocalUserRole::Operator).unwrap();
+        let session = login(&connection_str, "leigh", "hunter2").unwrap();
+
+        logout(&connection_str, &session.id).unwrap();
+        assert!(authenticate_session(&connection_str, &session.id)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test_log::test]
+    fn test_set_password_invalidates_old_password() {
+        let (_dir, connection_str) = test_db();
+        create_user(&connection_str, "leigh", "hunter2", LocalUserRole::Admin).unwrap();
+
+        set_password(&connection_str, "leigh", "new password").unwrap();
+        assert!(login(&connection_str, "leigh", "hunter2").is_err());
+        assert!(login(&connection_str, "leigh", "new password").is_ok());
+    }
+}
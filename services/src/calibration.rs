@@ -0,0 +1,234 @@
+// Detection threshold calibration: run the detector offline against a short
+// clip of the user's idle printer plus a known-failure sample clip, then
+// suggest an nms_threshold that separates the two for this camera angle and
+// lighting. Like anonymize, the one-shot gst-launch run happens outside gstd
+// so the live pipeline graph is untouched.
+use std::path::Path;
+
+use async_process::Command;
+use chrono::{DateTime, Utc};
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use printnanny_settings::printnanny::PrintNannySettings;
+
+use crate::error::CalibrationError;
+use crate::print_state::WindowedDetectionFrame;
+
+// failure sample shipped alongside the model so every install can calibrate
+// without having printed a failure first
+pub const DEFAULT_FAILURE_SAMPLE: &str = "/usr/share/printnanny/model/failure-sample.mp4";
+
+// same preprocessing and tensor chain as the live inference pipeline, fed from
+// a recorded clip instead of the camera interpipe; filter-threshold is near
+// zero so the dataframe windows carry the raw score distribution
+pub fn calibration_pipeline_description(
+    settings: &PrintNannySettings,
+    clip: &Path,
+    output: &Path,
+) -> String {
+    let detection = &*settings.video_stream.detection;
+    format!(
+        "filesrc location={clip} ! qtdemux ! h264parse ! v4l2h264dec ! videoconvert \
+        ! videoscale ! capsfilter caps=video/x-raw,format=RGB,width={tensor_width},height={tensor_height} \
+        ! tensor_converter \
+        ! tensor_transform mode=arithmetic option=typecast:uint8,add:0,div:1 \
+        ! capsfilter caps=other/tensors,format=static \
+        ! tensor_filter framework=tensorflow2-lite model={tflite_model_file} \
+        ! tensor_decoder mode=custom-code option1=printnanny_bb_dataframe_decoder \
+        ! dataframe_agg filter-threshold=0.0001 output-type=json \
+        ! filesink location={output}",
+        clip = clip.display(),
+        output = output.display(),
+        tensor_width = detection.tensor_width,
+        tensor_height = detection.tensor_height,
+        tflite_model_file = detection.model_file,
+    )
+}
+
+// dataframe_agg emits one JSON array per window; filesink concatenates them,
+// so the raw output is a stream of JSON values rather than a single document
+pub fn parse_windowed_frames(raw: &str) -> Result<Vec<WindowedDetectionFrame>, CalibrationError> {
+    let mut frames = Vec::new();
+    for window in serde_json::Deserializer::from_str(raw).into_iter::<Vec<WindowedDetectionFrame>>()
+    {
+        frames.extend(window?);
+    }
+    Ok(frames)
+}
+
+// highest failure-class (spaghetti/adhesion) mean score in a window, None when
+// no failure-class detections were present
+fn failure_score(frame: &WindowedDetectionFrame) -> Option<f64> {
+    let spaghetti = match frame.spaghetti_count.unwrap_or(0) > 0 {
+        true => frame.spaghetti_mean,
+        false => None,
+    };
+    let adhesion = match frame.adhesion_count.unwrap_or(0) > 0 {
+        true => frame.adhesion_mean,
+        false => None,
+    };
+    match (spaghetti, adhesion) {
+        (Some(s), Some(a)) => Some(s.max(a)),
+        (Some(s), None) => Some(s),
+        (None, Some(a)) => Some(a),
+        (None, None) => None,
+    }
+}
+
+// failure-class score distribution observed over one clip
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ClipScoreStats {
+    pub clip: String,
+    // detection windows produced by the clip
+    pub windows: usize,
+    // windows containing at least one failure-class detection
+    pub failure_windows: usize,
+    // false-positive ceiling for the idle clip, true-positive floor context
+    // for the failure clip
+    pub max_failure_score: Option<f64>,
+    pub median_failure_score: Option<f64>,
+}
+
+pub fn clip_stats(clip: &str, frames: &[WindowedDetectionFrame]) -> ClipScoreStats {
+    let mut scores: Vec<f64> = frames.iter().filter_map(failure_score).collect();
+    scores.sort_by(|a, b| a.partial_cmp(b).expect("detection scores are finite"));
+    let max_failure_score = scores.last().copied();
+    let median_failure_score = match scores.is_empty() {
+        true => None,
+        false => Some(scores[scores.len() / 2]),
+    };
+    ClipScoreStats {
+        clip: clip.to_string(),
+        windows: frames.len(),
+        failure_windows: scores.len(),
+        max_failure_score,
+        median_failure_score,
+    }
+}
+
+// midpoint between the idle clip's false-positive ceiling and the failure
+// clip's median score, as the 0-100 integer DetectionSettings.nms_threshold
+// uses; None when the clips don't separate (threshold tuning can't fix a
+// detector that scores the idle printer above real failures)
+pub fn suggest_threshold(idle: &ClipScoreStats, failure: &ClipScoreStats) -> Option<i32> {
+    let failure_median = failure.median_failure_score?;
+    let idle_ceiling = idle.max_failure_score.unwrap_or(0.0);
+    if failure_median <= idle_ceiling {
+        return None;
+    }
+    let midpoint = (idle_ceiling + failure_median) / 2.0;
+    Some(((midpoint * 100.0).round() as i32).clamp(1, 99))
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CalibrationReport {
+    pub generated_dt: DateTime<Utc>,
+    pub idle: ClipScoreStats,
+    pub failure: ClipScoreStats,
+    pub current_nms_threshold: i32,
+    // None when the idle and failure clips don't separate
+    pub suggested_nms_threshold: Option<i32>,
+}
+
+// run the detector over one clip and collect its windowed dataframes
+pub async fn run_clip(
+    settings: &PrintNannySettings,
+    clip: &Path,
+) -> Result<Vec<WindowedDetectionFrame>, CalibrationError> {
+    if !clip.exists() {
+        return Err(CalibrationError::ClipNotFound {
+            path: clip.display().to_string(),
+        });
+    }
+    let output = tempfile::Builder::new()
+        .prefix("printnanny-calibration")
+        .suffix(".json")
+        .tempfile()?;
+    let description = calibration_pipeline_description(settings, clip, output.path());
+    info!(
+        "Calibrating against {} with pipeline: {}",
+        clip.display(),
+        description
+    );
+    let result = Command::new("sh")
+        .args(["-c", &format!("gst-launch-1.0 -e {}", description)])
+        .output()
+        .await?;
+    if !result.status.success() {
+        return Err(CalibrationError::DetectorError {
+            path: clip.display().to_string(),
+            detail: String::from_utf8_lossy(&result.stderr).to_string(),
+        });
+    }
+    let raw = tokio::fs::read_to_string(output.path()).await?;
+    parse_windowed_frames(&raw)
+}
+
+pub async fn calibrate(
+    settings: &PrintNannySettings,
+    idle_clip: &Path,
+    failure_clip: &Path,
+) -> Result<CalibrationReport, CalibrationError> {
+    let idle_frames = run_clip(settings, idle_clip).await?;
+    let failure_frames = run_clip(settings, failure_clip).await?;
+    let idle = clip_stats(&idle_clip.display().to_string(), &idle_frames);
+    let failure = clip_stats(&failure_clip.display().to_string(), &failure_frames);
+    let suggested_nms_threshold = suggest_threshold(&idle, &failure);
+    Ok(CalibrationReport {
+        generated_dt: Utc::now(),
+        idle,
+        failure,
+        current_nms_threshold: settings.video_stream.detection.nms_threshold,
+        suggested_nms_threshold,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(spaghetti_mean: Option<f64>) -> WindowedDetectionFrame {
+        WindowedDetectionFrame {
+            spaghetti_count: spaghetti_mean.map(|_| 3),
+            spaghetti_mean,
+            ..WindowedDetectionFrame::default()
+        }
+    }
+
+    #[test_log::test]
+    fn test_parse_concatenated_windows() {
+        let raw = concat!(
+            r#"[{"spaghetti__count":2,"spaghetti__mean":0.81}]"#,
+            r#"[{"print__count":4,"print__mean":0.7},{"spaghetti__count":1,"spaghetti__mean":0.77}]"#,
+        );
+        let frames = parse_windowed_frames(raw).unwrap();
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[0].spaghetti_mean, Some(0.81));
+        assert_eq!(frames[1].print_mean, Some(0.7));
+    }
+
+    #[test_log::test]
+    fn test_suggest_threshold_midpoint() {
+        let idle = clip_stats(
+            "idle.mp4",
+            &[frame(Some(0.2)), frame(None), frame(Some(0.3))],
+        );
+        let failure = clip_stats(
+            "failure.mp4",
+            &[frame(Some(0.8)), frame(Some(0.9)), frame(Some(0.7))],
+        );
+        assert_eq!(idle.failure_windows, 2);
+        assert_eq!(idle.max_failure_score, Some(0.3));
+        assert_eq!(failure.median_failure_score, Some(0.8));
+        // midpoint of the 0.3 idle ceiling and 0.8 failure median
+        assert_eq!(suggest_threshold(&idle, &failure), Some(55));
+    }
+
+    #[test_log::test]
+    fn test_suggest_threshold_rejects_overlapping_clips() {
+        let idle = clip_stats("idle.mp4", &[frame(Some(0.85))]);
+        let failure = clip_stats("failure.mp4", &[frame(Some(0.8))]);
+        assert_eq!(suggest_threshold(&idle, &failure), None);
+    }
+}
@@ -0,0 +1,164 @@
+// Other camera daemons shipped by popular printer images (crowsnest,
+// ustreamer, motion) hold /dev/video* exclusively, which makes libcamerasrc
+// fail with an opaque "Device or resource busy". Detect both the systemd
+// units and the processes actually holding a video device so
+// `printnanny doctor --camera` can name the conflict, and offer a takeover
+// that stops the conflicting units before PrintNanny pipelines start.
+use std::path::{Path, PathBuf};
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use printnanny_dbus::systemd1::models::{SystemdActiveState, SystemdUnit};
+use printnanny_dbus::zbus;
+use printnanny_dbus::zbus_systemd;
+
+use crate::error::ServiceError;
+
+// units belonging to other camera daemons commonly found on printer images
+pub const KNOWN_CAMERA_DAEMON_UNITS: [&str; 4] = [
+    "crowsnest.service",
+    "ustreamer.service",
+    "motion.service",
+    "webcamd.service",
+];
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeviceHolder {
+    pub pid: i32,
+    pub comm: String,
+    pub device: PathBuf,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CameraConflictReport {
+    // known camera daemon units that are active/activating
+    pub conflicting_units: Vec<String>,
+    // every process holding an open fd on a /dev/video* device
+    pub device_holders: Vec<DeviceHolder>,
+}
+
+impl CameraConflictReport {
+    pub fn is_clear(&self) -> bool {
+        self.conflicting_units.is_empty()
+    }
+}
+
+// scan /proc/<pid>/fd for open handles on devices under prefix. Takes the
+// prefix as an argument (production: /dev/video) so tests can point it at a
+// tempdir instead of real video devices.
+pub fn processes_holding_devices(prefix: &Path) -> Result<Vec<DeviceHolder>, ServiceError> {
+    let mut result = vec![];
+    for process in procfs::process::all_processes()? {
+        // fds of other users' processes are unreadable without root; skip them
+        let fds = match process.fd() {
+            Ok(fds) => fds,
+            Err(_) => continue,
+        };
+        for fd in fds {
+            if let procfs::process::FDTarget::Path(path) = fd.target {
+                if path_matches_prefix(&path, prefix) {
+                    result.push(DeviceHolder {
+                        pid: process.pid,
+                        comm: process.stat.comm.clone(),
+                        device: path,
+                    });
+                }
+            }
+        }
+    }
+    Ok(result)
+}
+
+// /dev/video0 is a file prefix match, not a directory component match, so
+// Path::starts_with alone is not enough
+fn path_matches_prefix(path: &Path, prefix: &Path) -> bool {
+    path.to_string_lossy()
+        .starts_with(&*prefix.to_string_lossy())
+}
+
+async fn unit_is_active(unit_name: &str) -> bool {
+    let connection = match zbus::Connection::system().await {
+        Ok(connection) => connection,
+        Err(e) => {
+            warn!("Failed to connect to system dbus: {}", e);
+            return false;
+        }
+    };
+    let proxy = match zbus_systemd::systemd1::ManagerProxy::new(&connection).await {
+        Ok(proxy) => proxy,
+        Err(e) => {
+            warn!("Failed to initialize systemd1.Manager proxy: {}", e);
+            return false;
+        }
+    };
+    let unit_path = match proxy.load_unit(unit_name.to_string()).await {
+        Ok(unit_path) => unit_path,
+        // unit is not installed on this image
+        Err(_) => return false,
+    };
+    match SystemdUnit::from_owned_object_path(unit_path).await {
+        Ok(unit) => matches!(
+            unit.active_state,
+            SystemdActiveState::Active | SystemdActiveState::Activating
+        ),
+        Err(_) => false,
+    }
+}
+
+pub async fn detect_camera_conflicts() -> Result<CameraConflictReport, ServiceError> {
+    let mut conflicting_units = vec![];
+    for unit_name in KNOWN_CAMERA_DAEMON_UNITS {
+        if unit_is_active(unit_name).await {
+            conflicting_units.push(unit_name.to_string());
+        }
+    }
+    let device_holders = processes_holding_devices(Path::new("/dev/video"))?;
+    Ok(CameraConflictReport {
+        conflicting_units,
+        device_holders,
+    })
+}
+
+// takeover mode: stop every conflicting unit so PrintNanny pipelines can
+// claim the camera. Units are stopped, not disabled - a reboot restores them.
+pub async fn stop_conflicting_units(report: &CameraConflictReport) -> Result<(), ServiceError> {
+    if report.is_clear() {
+        return Ok(());
+    }
+    let connection = zbus::Connection::system().await?;
+    let proxy = zbus_systemd::systemd1::ManagerProxy::new(&connection).await?;
+    for unit_name in &report.conflicting_units {
+        info!("Stopping conflicting camera daemon unit {}", unit_name);
+        proxy
+            .stop_unit(unit_name.to_string(), "replace".to_string())
+            .await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_own_open_fd_is_detected() {
+        let dir = tempfile::tempdir().unwrap();
+        let device = dir.path().join("video0");
+        let _handle = std::fs::File::create(&device).unwrap();
+        let holders = processes_holding_devices(&dir.path().join("video")).unwrap();
+        let own_pid = std::process::id() as i32;
+        let holder = holders
+            .iter()
+            .find(|h| h.pid == own_pid && h.device == device)
+            .unwrap_or_else(|| panic!("expected pid {} to hold {:?}", own_pid, device));
+        assert!(!holder.comm.is_empty());
+    }
+
+    #[test_log::test]
+    fn test_unrelated_paths_are_not_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let holders = processes_holding_devices(&dir.path().join("video")).unwrap();
+        assert_eq!(holders, vec![]);
+    }
+}
@@ -0,0 +1,88 @@
+// Self-describing capability report so the cloud UI can hide controls the
+// device cannot honor (an EdgeTPU toggle without the accelerator, a
+// second-camera picker on a single-camera rig) instead of surfacing errors
+// after the fact. Everything here is derived from compile-time features and
+// cheap runtime probes of the OS image; no network calls.
+use std::path::Path;
+
+use async_process::Command;
+use serde::{Deserialize, Serialize};
+
+use printnanny_settings::cam::CameraVideoSource;
+use printnanny_settings::printnanny::PrintNannySettings;
+
+// Coral EdgeTPU accelerator device node created by the apex driver
+pub const EDGETPU_DEVICE: &str = "/dev/apex_0";
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapabilityReport {
+    // failure detection: the printnanny gst plugin is installed and the
+    // configured tflite model file is present
+    pub detection: bool,
+    // janus-gateway is installed on the image
+    pub webrtc: bool,
+    // an RTSP server is installed for the ONVIF/NVR integration
+    pub rtsp: bool,
+    // a PrintNanny Cloud API token is configured
+    pub cloud: bool,
+    pub multi_camera: bool,
+    // cameras enumerated by libcamera; 0 when the probe fails
+    pub camera_count: usize,
+    pub edgetpu: bool,
+    // cargo features the calling binary was built with, passed in by the
+    // caller: this crate cannot see the features of the binary linking it
+    pub build_features: Vec<String>,
+}
+
+// search PATH the way the shell would
+pub fn binary_on_path(name: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).any(|dir| dir.join(name).is_file()))
+        .unwrap_or(false)
+}
+
+// `gst-inspect-1.0 --exists` exits 0 when the plugin is in the registry
+async fn gst_plugin_installed(name: &str) -> bool {
+    match Command::new("gst-inspect-1.0")
+        .args(["--exists", name])
+        .status()
+        .await
+    {
+        Ok(status) => status.success(),
+        Err(_) => false,
+    }
+}
+
+pub async fn probe(settings: &PrintNannySettings, build_features: Vec<String>) -> CapabilityReport {
+    let camera_count = CameraVideoSource::from_libcamera_list()
+        .await
+        .map(|cameras| cameras.len())
+        .unwrap_or(0);
+    let detection = gst_plugin_installed("printnanny").await
+        && Path::new(&settings.video_stream.detection.model_file).exists();
+    CapabilityReport {
+        detection,
+        webrtc: binary_on_path("janus"),
+        rtsp: binary_on_path("mediamtx") || binary_on_path("rtsp-simple-server"),
+        cloud: settings.cloud.api_bearer_access_token.is_some(),
+        multi_camera: camera_count > 1,
+        camera_count,
+        edgetpu: Path::new(EDGETPU_DEVICE).exists(),
+        build_features,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_binary_on_path_finds_sh() {
+        assert!(binary_on_path("sh"));
+    }
+
+    #[test_log::test]
+    fn test_binary_on_path_rejects_missing_binary() {
+        assert!(!binary_on_path("printnanny-no-such-binary"));
+    }
+}
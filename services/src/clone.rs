@@ -0,0 +1,165 @@
+// Clone/seed archives for multi-printer rollouts: bundle the settings
+// profile from a configured device so an additional Pi can be provisioned
+// with the same tuning, minus everything that identifies this device. Cloud
+// credentials are stripped from the bundled settings, and keys/creds are
+// never included — the new device requests its own identity from the cloud
+// during `printnanny init`.
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use zip::write::FileOptions;
+
+use printnanny_settings::printnanny::PrintNannySettings;
+use printnanny_settings::sys_info;
+use printnanny_settings::toml;
+
+use crate::error::ServiceError;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CloneManifest {
+    pub created_dt: DateTime<Utc>,
+    pub source_hostname: String,
+    // files bundled under settings/ in the archive
+    pub files: Vec<String>,
+}
+
+// strip everything that ties the profile to this device; the clone target
+// connects its own cloud account and mints its own local keys
+fn sanitize(settings: &PrintNannySettings) -> PrintNannySettings {
+    let mut sanitized = settings.clone();
+    sanitized.cloud.api_bearer_access_token = None;
+    sanitized
+}
+
+// write the seed archive to `output`, returning the embedded manifest
+pub fn export_seed(
+    settings: &PrintNannySettings,
+    output: &Path,
+) -> Result<CloneManifest, ServiceError> {
+    let settings_dir = settings
+        .paths
+        .settings_file()
+        .parent()
+        .map(|p| p.to_path_buf())
+        .unwrap_or_default();
+    let settings_filename = settings
+        .paths
+        .settings_file()
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "printnanny.toml".to_string());
+
+    let file = File::create(output).map_err(|e| crate::error::IoError::WriteIOError {
+        path: output.display().to_string(),
+        error: e,
+    })?;
+    let mut zip = zip::ZipWriter::new(&file);
+    let options = FileOptions::default().unix_permissions(0o644);
+    let mut files: Vec<String> = vec![];
+
+    // the sanitized profile replaces the on-disk printnanny.toml
+    let archive_path = format!("settings/{}", settings_filename);
+    zip.start_file(&archive_path, options)?;
+    zip.write_all(toml::ser::to_string_pretty(&sanitize(settings))?.as_bytes())
+        .map_err(|e| crate::error::IoError::WriteIOError {
+            path: output.display().to_string(),
+            error: e,
+        })?;
+    files.push(archive_path);
+
+    // companion settings files (octoprint, klipper, moonraker) are copied
+    // verbatim; the .git history stays behind with the source device
+    if let Ok(entries) = std::fs::read_dir(&settings_dir) {
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if path.is_dir() || name == settings_filename {
+                continue;
+            }
+            match std::fs::read(&path) {
+                Ok(contents) => {
+                    let archive_path = format!("settings/{}", name);
+                    zip.start_file(&archive_path, options)?;
+                    zip.write_all(&contents)
+                        .map_err(|e| crate::error::IoError::WriteIOError {
+                            path: output.display().to_string(),
+                            error: e,
+                        })?;
+                    files.push(archive_path);
+                }
+                Err(e) => {
+                    warn!("Skipping {}, failed to read: {}", path.display(), e);
+                }
+            }
+        }
+    }
+
+    let manifest = CloneManifest {
+        created_dt: Utc::now(),
+        source_hostname: sys_info::hostname().unwrap_or_else(|_| "localhost".to_string()),
+        files,
+    };
+    zip.start_file("manifest.json", options)?;
+    zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())
+        .map_err(|e| crate::error::IoError::WriteIOError {
+            path: output.display().to_string(),
+            error: e,
+        })?;
+    zip.finish()?;
+    info!(
+        "Exported clone seed archive {} with {} settings files",
+        output.display(),
+        manifest.files.len()
+    );
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test_log::test]
+    fn test_export_seed_strips_cloud_token() {
+        figment::Jail::expect_with(|jail| {
+            let settings_dir = jail.directory().join("printnanny");
+            std::fs::create_dir_all(&settings_dir).unwrap();
+            std::fs::write(settings_dir.join("octoprint.yaml"), "plugins: {}").unwrap();
+            jail.set_env(
+                "PRINTNANNY_SETTINGS",
+                settings_dir.join("printnanny.toml").display().to_string(),
+            );
+
+            let settings = PrintNannySettings {
+                cloud: printnanny_settings::printnanny::PrintNannyApiConfig {
+                    api_base_path: "https://printnanny.ai".to_string(),
+                    api_bearer_access_token: Some("secret-token".to_string()),
+                },
+                ..PrintNannySettings::default()
+            };
+            let output = jail.directory().join("seed.zip");
+            let manifest = export_seed(&settings, &output).unwrap();
+            assert!(manifest
+                .files
+                .contains(&"settings/printnanny.toml".to_string()));
+            assert!(manifest
+                .files
+                .contains(&"settings/octoprint.yaml".to_string()));
+
+            let mut archive = zip::ZipArchive::new(File::open(&output).unwrap()).unwrap();
+            let mut bundled = String::new();
+            archive
+                .by_name("settings/printnanny.toml")
+                .unwrap()
+                .read_to_string(&mut bundled)
+                .unwrap();
+            assert!(!bundled.contains("secret-token"));
+            assert!(bundled.contains("https://printnanny.ai"));
+            Ok(())
+        });
+    }
+}
@@ -0,0 +1,216 @@
+// Dataset export for offline labeling/training workflows: samples frames
+// written by the snapshot pipeline, pairs them with printer/recording
+// metadata (gcode file names, detection scores), and bundles everything into
+// a zip archive with a manifest.json. Recorded h264 footage is indexed in the
+// manifest rather than re-encoded, so the archive stays small enough to copy
+// off a Pi over wifi.
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use zip::write::FileOptions;
+
+use printnanny_edge_db::detections::Detection;
+use printnanny_edge_db::diesel;
+use printnanny_edge_db::video_recording::{VideoRecording, VideoRecordingPart};
+use printnanny_settings::printnanny::PrintNannySettings;
+use printnanny_settings::sys_info;
+
+use crate::error::ServiceError;
+use crate::model_evaluation;
+
+// one sampled frame bundled under frames/ in the archive
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DatasetFrame {
+    // filename within the archive's frames/ directory
+    pub filename: String,
+    // original path the frame was sampled from
+    pub source: String,
+}
+
+// recordings are indexed by path instead of bundled; offline tooling can
+// pull the referenced files separately when full footage is needed
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DatasetRecording {
+    pub recording: VideoRecording,
+    pub parts: Vec<VideoRecordingPart>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DatasetManifest {
+    pub exported_dt: DateTime<Utc>,
+    pub hostname: String,
+    pub camera_label: String,
+    // file stem of the active tflite model, matches detections.model_id
+    pub model_id: String,
+    // every Nth snapshot frame was sampled
+    pub sample_every: usize,
+    pub frames: Vec<DatasetFrame>,
+    // windowed detection scores logged within the export window
+    pub detections: Vec<Detection>,
+    pub recordings: Vec<DatasetRecording>,
+}
+
+// every Nth jpeg from the snapshot pipeline directory, oldest first
+fn sample_snapshot_frames(snapshot_dir: &Path, sample_every: usize) -> Vec<PathBuf> {
+    let mut frames: Vec<PathBuf> = match std::fs::read_dir(snapshot_dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().map(|ext| ext == "jpg").unwrap_or(false))
+            .collect(),
+        Err(e) => {
+            warn!(
+                "Failed to read snapshot dir {}: {}",
+                snapshot_dir.display(),
+                e
+            );
+            vec![]
+        }
+    };
+    frames.sort();
+    frames.into_iter().step_by(sample_every.max(1)).collect()
+}
+
+fn list_recordings(connection_str: &str) -> Result<Vec<DatasetRecording>, diesel::result::Error> {
+    let recordings = VideoRecording::get_all(connection_str)?;
+    recordings
+        .into_iter()
+        .map(|recording| {
+            let parts =
+                VideoRecordingPart::get_parts_by_video_recording_id(connection_str, &recording.id)?;
+            Ok(DatasetRecording { recording, parts })
+        })
+        .collect()
+}
+
+// write the dataset archive to `output`, returning the manifest that was
+// embedded as manifest.json
+pub fn export_dataset(
+    settings: &PrintNannySettings,
+    output: &Path,
+    sample_every: usize,
+    hours: i64,
+) -> Result<DatasetManifest, ServiceError> {
+    let sqlite_connection = settings.paths.db().display().to_string();
+    let since = Utc::now() - chrono::Duration::hours(hours);
+
+    let sampled = sample_snapshot_frames(&settings.paths.snapshot_dir, sample_every);
+    let detections = Detection::list_since(&sqlite_connection, since)?;
+    let recordings = list_recordings(&sqlite_connection)?;
+
+    let file = File::create(output).map_err(|e| crate::error::IoError::WriteIOError {
+        path: output.display().to_string(),
+        error: e,
+    })?;
+    let mut zip = zip::ZipWriter::new(&file);
+    let options = FileOptions::default().unix_permissions(0o644);
+
+    let mut frames: Vec<DatasetFrame> = Vec::with_capacity(sampled.len());
+    for (index, source) in sampled.iter().enumerate() {
+        let filename = format!("frames/{:06}.jpg", index);
+        match std::fs::read(source) {
+            Ok(contents) => {
+                zip.start_file(&filename, options)?;
+                zip.write_all(&contents)
+                    .map_err(|e| crate::error::IoError::WriteIOError {
+                        path: output.display().to_string(),
+                        error: e,
+                    })?;
+                frames.push(DatasetFrame {
+                    filename,
+                    source: source.display().to_string(),
+                });
+            }
+            Err(e) => {
+                warn!("Skipping frame {}, failed to read: {}", source.display(), e);
+            }
+        }
+    }
+
+    let manifest = DatasetManifest {
+        exported_dt: Utc::now(),
+        hostname: sys_info::hostname().unwrap_or_else(|_| "localhost".to_string()),
+        camera_label: settings.video_stream.camera.label.clone(),
+        model_id: model_evaluation::model_id(&settings.video_stream.detection.model_file),
+        sample_every,
+        frames,
+        detections,
+        recordings,
+    };
+    zip.start_file("manifest.json", options)?;
+    zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())
+        .map_err(|e| crate::error::IoError::WriteIOError {
+            path: output.display().to_string(),
+            error: e,
+        })?;
+    zip.finish()?;
+    info!(
+        "Exported dataset archive {} with {} frames",
+        output.display(),
+        manifest.frames.len()
+    );
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use printnanny_edge_db::connection::run_migrations;
+    use printnanny_settings::paths::PrintNannyPaths;
+
+    async fn test_settings(dir: &tempfile::TempDir) -> PrintNannySettings {
+        let settings = PrintNannySettings {
+            paths: PrintNannyPaths {
+                state_dir: dir.path().join("state"),
+                snapshot_dir: dir.path().join("snapshot"),
+                ..PrintNannyPaths::default()
+            },
+            ..PrintNannySettings::default()
+        };
+        std::fs::create_dir_all(settings.paths.data()).unwrap();
+        std::fs::create_dir_all(&settings.paths.snapshot_dir).unwrap();
+        run_migrations(&settings.paths.db().display().to_string()).unwrap();
+        settings
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_export_dataset_samples_every_nth_frame() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = test_settings(&dir).await;
+        for i in 0..10 {
+            std::fs::write(
+                settings
+                    .paths
+                    .snapshot_dir
+                    .join(format!("snapshot-{:03}.jpg", i)),
+                b"jpeg",
+            )
+            .unwrap();
+        }
+
+        let output = dir.path().join("dataset.zip");
+        let manifest = export_dataset(&settings, &output, 3, 24).unwrap();
+        // frames 0, 3, 6, 9
+        assert_eq!(manifest.frames.len(), 4);
+        assert!(output.exists());
+
+        let archive = zip::ZipArchive::new(File::open(&output).unwrap()).unwrap();
+        let names: Vec<&str> = archive.file_names().collect();
+        assert!(names.contains(&"manifest.json"));
+        assert!(names.contains(&"frames/000000.jpg"));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_export_dataset_empty_snapshot_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = test_settings(&dir).await;
+        let output = dir.path().join("dataset.zip");
+        let manifest = export_dataset(&settings, &output, 1, 24).unwrap();
+        assert!(manifest.frames.is_empty());
+        assert!(manifest.detections.is_empty());
+        assert!(output.exists());
+    }
+}
@@ -0,0 +1,306 @@
+// Secure decommission for resold/returned devices: removes cloud credentials
+// and secrets, the settings repo, the edge database, and recorded media, and
+// optionally overwrites free space so deleted flash blocks are harder to
+// recover. Every step lands in a completion report signed with the
+// device-local stream-token key (held in memory across the wipe), so the
+// report is tamper-evident after the key itself is destroyed. Cloud-side
+// token invalidation still happens when the Pi is deleted from the dashboard.
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Utc};
+use jsonwebtoken::{encode, EncodingKey, Header};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use printnanny_settings::printnanny::PrintNannySettings;
+
+use crate::error::ServiceError;
+use crate::stream_token::STREAM_TOKEN_KEY_FILENAME;
+
+// zero-fill chunk for shredding and free-space overwrite
+const ZERO_CHUNK: usize = 4 * 1024 * 1024;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecommissionStep {
+    pub name: String,
+    pub path: PathBuf,
+    pub completed: bool,
+    pub detail: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DecommissionReport {
+    pub hostname: String,
+    pub started_dt: DateTime<Utc>,
+    pub finished_dt: DateTime<Utc>,
+    pub overwrote_free_space: bool,
+    pub steps: Vec<DecommissionStep>,
+    pub completed: bool,
+    // HS256 JWT over the sha256 of the steps, signed with the (now destroyed)
+    // device stream-token key
+    pub signature: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct DecommissionClaims {
+    sub: String,
+    iat: u64,
+    exp: u64,
+    hostname: String,
+    steps_sha256: String,
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// overwrite a file with zeros before unlinking it. Wear-leveled flash may
+// keep stale copies of the old blocks; --overwrite-free-space exists to
+// reclaim those as well.
+fn shred_file(path: &Path) -> Result<(), std::io::Error> {
+    let len = std::fs::metadata(path)?.len() as usize;
+    let mut file = std::fs::OpenOptions::new().write(true).open(path)?;
+    let zeros = vec![0u8; ZERO_CHUNK];
+    let mut remaining = len;
+    while remaining > 0 {
+        let chunk = remaining.min(ZERO_CHUNK);
+        file.write_all(&zeros[..chunk])?;
+        remaining -= chunk;
+    }
+    file.sync_all()?;
+    drop(file);
+    std::fs::remove_file(path)
+}
+
+fn shred_dir(dir: &Path) -> Result<(), std::io::Error> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        match path.is_dir() {
+            true => shred_dir(&path)?,
+            false => shred_file(&path)?,
+        }
+    }
+    std::fs::remove_dir(dir)
+}
+
+// remove a path (shredding file contents for secret-bearing paths), recording
+// the outcome as a report step; a missing path counts as already done
+fn remove_step(name: &str, path: PathBuf, shred: bool) -> DecommissionStep {
+    let result = match (path.exists(), path.is_dir(), shred) {
+        (false, _, _) => Ok("already absent".to_string()),
+        (true, true, true) => shred_dir(&path).map(|_| "shredded and removed".to_string()),
+        (true, true, false) => std::fs::remove_dir_all(&path).map(|_| "removed".to_string()),
+        (true, false, true) => shred_file(&path).map(|_| "shredded and removed".to_string()),
+        (true, false, false) => std::fs::remove_file(&path).map(|_| "removed".to_string()),
+    };
+    let (completed, detail) = match result {
+        Ok(detail) => (true, detail),
+        Err(e) => (false, e.to_string()),
+    };
+    if !completed {
+        warn!(
+            "Decommission step {} failed for {}: {}",
+            name,
+            path.display(),
+            detail
+        );
+    }
+    DecommissionStep {
+        name: name.to_string(),
+        path,
+        completed,
+        detail,
+    }
+}
+
+// fill the filesystem holding `dir` with zeros until it reports full, then
+// delete the fill file, so previously freed blocks no longer hold user data
+fn overwrite_free_space(dir: &Path) -> Result<u64, std::io::Error> {
+    std::fs::create_dir_all(dir)?;
+    let fill_path = dir.join("printnanny-decommission.fill");
+    let mut file = std::fs::File::create(&fill_path)?;
+    let zeros = vec![0u8; ZERO_CHUNK];
+    let mut written: u64 = 0;
+    // ENOSPC is the expected exit
+    while file.write_all(&zeros).is_ok() {
+        written += ZERO_CHUNK as u64;
+    }
+    file.sync_all()?;
+    drop(file);
+    std::fs::remove_file(&fill_path)?;
+    Ok(written)
+}
+
+fn sign_report(
+    key: Option<Vec<u8>>,
+    hostname: &str,
+    steps: &[DecommissionStep],
+) -> Result<Option<String>, ServiceError> {
+    let key = match key {
+        Some(key) => key,
+        None => return Ok(None),
+    };
+    let steps_json = serde_json::to_vec(steps)?;
+    let steps_sha256 = format!("{:x}", Sha256::digest(&steps_json));
+    let iat = unix_now();
+    let claims = DecommissionClaims {
+        sub: "printnanny-decommission".to_string(),
+        iat,
+        // the report should stay verifiable for the life of the resold device
+        exp: iat + 10 * 365 * 24 * 60 * 60,
+        hostname: hostname.to_string(),
+        steps_sha256,
+    };
+    let token = encode(&Header::default(), &claims, &EncodingKey::from_secret(&key))?;
+    Ok(Some(token))
+}
+
+pub fn decommission(
+    settings: &PrintNannySettings,
+    overwrite: bool,
+) -> Result<DecommissionReport, ServiceError> {
+    let paths = &settings.paths;
+    let hostname =
+        printnanny_settings::sys_info::hostname().unwrap_or_else(|_| "localhost".to_string());
+    let started_dt = Utc::now();
+    // hold the signing key in memory: the creds wipe destroys it on disk, but
+    // the completion report still needs a signature
+    let signing_key = std::fs::read(paths.creds().join(STREAM_TOKEN_KEY_FILENAME)).ok();
+    if signing_key.is_none() {
+        warn!("No stream-token key found; the decommission report will be unsigned");
+    }
+    let settings_repo = paths
+        .settings_file()
+        .parent()
+        .map(|p| p.to_path_buf())
+        .unwrap_or_default();
+    let mut steps = vec![
+        // cloud license, NATS creds, and every other device secret
+        remove_step("cloud_credentials", paths.creds(), true),
+        remove_step("settings_repo", settings_repo, false),
+        remove_step("edge_db", paths.db(), true),
+        remove_step("media_video", paths.video(), false),
+        remove_step("media_snapshots", paths.snapshot_dir.clone(), false),
+        remove_step("logs", paths.log_dir.clone(), false),
+        // shell transcripts, lifecycle/power markers, recovery bundles, data
+        remove_step("state_dir", paths.state_dir.clone(), false),
+    ];
+    if overwrite {
+        let (completed, detail) = match overwrite_free_space(&paths.state_dir) {
+            Ok(written) => (true, format!("overwrote {} bytes of free space", written)),
+            Err(e) => (false, e.to_string()),
+        };
+        steps.push(DecommissionStep {
+            name: "overwrite_free_space".to_string(),
+            path: paths.state_dir.clone(),
+            completed,
+            detail,
+        });
+    }
+    let completed = steps.iter().all(|step| step.completed);
+    let signature = sign_report(signing_key, &hostname, &steps)?;
+    info!(
+        "Decommission finished completed={} steps={}",
+        completed,
+        steps.len()
+    );
+    Ok(DecommissionReport {
+        hostname,
+        started_dt,
+        finished_dt: Utc::now(),
+        overwrote_free_space: overwrite,
+        steps,
+        completed,
+        signature,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use printnanny_settings::paths::PrintNannyPaths;
+
+    fn test_settings(dir: &tempfile::TempDir) -> PrintNannySettings {
+        PrintNannySettings {
+            paths: PrintNannyPaths {
+                state_dir: dir.path().join("state"),
+                snapshot_dir: dir.path().join("snapshot"),
+                log_dir: dir.path().join("log"),
+                ..PrintNannyPaths::default()
+            },
+            ..PrintNannySettings::default()
+        }
+    }
+
+    #[test_log::test]
+    fn test_decommission_wipes_state_and_signs_report() {
+        figment::Jail::expect_with(|jail| {
+            // keep the settings_repo step inside the jail
+            let settings_repo = jail.directory().join("printnanny");
+            std::fs::create_dir_all(&settings_repo).unwrap();
+            std::fs::write(settings_repo.join("printnanny.toml"), "").unwrap();
+            jail.set_env(
+                "PRINTNANNY_SETTINGS",
+                settings_repo.join("printnanny.toml").display().to_string(),
+            );
+
+            let dir = tempfile::tempdir().unwrap();
+            let settings = test_settings(&dir);
+            let paths = &settings.paths;
+            paths.try_init_all().unwrap();
+            std::fs::write(
+                paths.creds().join(STREAM_TOKEN_KEY_FILENAME),
+                b"test-signing-key",
+            )
+            .unwrap();
+            std::fs::write(paths.db(), b"sqlite data").unwrap();
+            std::fs::write(paths.video().join("recording.mp4"), b"frames").unwrap();
+
+            let report = decommission(&settings, false).unwrap();
+            assert!(report.completed);
+            assert!(report.signature.is_some());
+            assert!(!paths.creds().exists());
+            assert!(!paths.db().exists());
+            assert!(!paths.state_dir.exists());
+            assert!(!settings_repo.exists());
+            let db_step = report.steps.iter().find(|s| s.name == "edge_db").unwrap();
+            assert_eq!(db_step.detail, "shredded and removed");
+            Ok(())
+        });
+    }
+
+    #[test_log::test]
+    fn test_decommission_without_key_is_unsigned() {
+        figment::Jail::expect_with(|jail| {
+            jail.set_env(
+                "PRINTNANNY_SETTINGS",
+                jail.directory()
+                    .join("printnanny/printnanny.toml")
+                    .display()
+                    .to_string(),
+            );
+            let dir = tempfile::tempdir().unwrap();
+            let settings = test_settings(&dir);
+            let report = decommission(&settings, false).unwrap();
+            // nothing existed, so every step reports already absent
+            assert!(report.completed);
+            assert!(report.signature.is_none());
+            Ok(())
+        });
+    }
+
+    #[test_log::test]
+    fn test_shred_file_zeroes_before_unlink() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secret.key");
+        std::fs::write(&path, b"very secret").unwrap();
+        shred_file(&path).unwrap();
+        assert!(!path.exists());
+    }
+}
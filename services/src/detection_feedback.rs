@@ -0,0 +1,205 @@
+// Detection feedback loop: the dashboard marks a detection event as
+// false positive/negative, the frame on screen is captured from the snapshot
+// pipeline, and the labeled sample is queued locally. Uploads to the cloud
+// training pipeline go through the cloud event outbox on a snippet subject,
+// so they only happen with the telemetry.video_snippets consent toggle on.
+use std::path::PathBuf;
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use printnanny_edge_db::detection_feedback::{DetectionFeedback, DetectionFeedbackLabel};
+use printnanny_edge_db::outbox::CloudEventOutbox;
+use printnanny_settings::printnanny::PrintNannySettings;
+
+use crate::error::ServiceError;
+
+// subject is under .snippet so the transport-layer telemetry check applies
+// the video_snippets toggle
+pub const DETECTION_FEEDBACK_SUBJECT: &str = "pi.{pi_id}.event.snippet.detection_feedback";
+
+// payload published to the cloud training pipeline for one labeled sample
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DetectionFeedbackSample {
+    pub id: String,
+    pub detection_ts: i64,
+    pub label: String,
+    pub note: Option<String>,
+    // base64-encoded jpeg of the frame the user labeled
+    pub frame_jpeg: Option<String>,
+}
+
+// newest jpeg written by the snapshot pipeline
+fn latest_snapshot_frame(settings: &PrintNannySettings) -> Option<PathBuf> {
+    let entries = std::fs::read_dir(&settings.paths.snapshot_dir).ok()?;
+    entries.last()?.ok().map(|entry| entry.path())
+}
+
+// capture the current frame and persist a labeled feedback row
+pub fn capture_feedback(
+    settings: &PrintNannySettings,
+    detection_ts: i64,
+    label: DetectionFeedbackLabel,
+    note: Option<&str>,
+) -> Result<DetectionFeedback, ServiceError> {
+    let sqlite_connection = settings.paths.db().display().to_string();
+    let feedback_dir = settings.paths.data().join("detection-feedback");
+    std::fs::create_dir_all(&feedback_dir).map_err(|e| crate::error::IoError::WriteIOError {
+        path: feedback_dir.display().to_string(),
+        error: e,
+    })?;
+
+    let frame_path = match latest_snapshot_frame(settings) {
+        Some(src) => {
+            let dest = feedback_dir.join(format!("{}.jpg", uuid::Uuid::new_v4()));
+            match std::fs::copy(&src, &dest) {
+                Ok(_) => Some(dest.display().to_string()),
+                Err(e) => {
+                    warn!("Failed to capture frame {}: {}", src.display(), e);
+                    None
+                }
+            }
+        }
+        None => {
+            warn!(
+                "No snapshot frame available in {}",
+                settings.paths.snapshot_dir.display()
+            );
+            None
+        }
+    };
+    let row = DetectionFeedback::create(
+        &sqlite_connection,
+        detection_ts,
+        label,
+        note,
+        frame_path.as_deref(),
+    )?;
+    Ok(row)
+}
+
+// enqueue all pending labeled samples for the cloud training pipeline.
+// Requires the telemetry.video_snippets consent toggle; returns how many
+// samples were enqueued.
+pub fn sync_pending_feedback(settings: &PrintNannySettings) -> Result<usize, ServiceError> {
+    if !settings.telemetry.video_snippets {
+        return Err(ServiceError::TelemetryDisabled {
+            category: "video_snippets".to_string(),
+        });
+    }
+    let sqlite_connection = settings.paths.db().display().to_string();
+    let pending = DetectionFeedback::get_pending(&sqlite_connection)?;
+    let mut synced = 0;
+    for row in pending {
+        let frame_jpeg = match &row.frame_path {
+            Some(path) => match std::fs::read(path) {
+                Ok(bytes) => Some(base64::encode(bytes)),
+                Err(e) => {
+                    warn!("Failed to read captured frame {}: {}", path, e);
+                    None
+                }
+            },
+            None => None,
+        };
+        let sample = DetectionFeedbackSample {
+            id: row.id.clone(),
+            detection_ts: row.detection_ts,
+            label: row.label.clone(),
+            note: row.note.clone(),
+            frame_jpeg,
+        };
+        let payload = serde_json::to_string(&sample)?;
+        let connection =
+            &mut printnanny_edge_db::connection::establish_sqlite_connection(&sqlite_connection);
+        CloudEventOutbox::insert(connection, DETECTION_FEEDBACK_SUBJECT, &payload)?;
+        DetectionFeedback::mark_uploaded(&sqlite_connection, &row.id)?;
+        // the frame now lives in the outbox payload; free the local copy
+        if let Some(path) = &row.frame_path {
+            if let Err(e) = std::fs::remove_file(path) {
+                warn!("Failed to remove captured frame {}: {}", path, e);
+            }
+        }
+        info!("Enqueued DetectionFeedback id={} for upload", row.id);
+        synced += 1;
+    }
+    Ok(synced)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use printnanny_edge_db::connection::run_migrations;
+    use printnanny_settings::paths::PrintNannyPaths;
+
+    async fn test_settings(dir: &tempfile::TempDir) -> PrintNannySettings {
+        let settings = PrintNannySettings {
+            paths: PrintNannyPaths {
+                state_dir: dir.path().join("state"),
+                snapshot_dir: dir.path().join("snapshot"),
+                ..PrintNannyPaths::default()
+            },
+            ..PrintNannySettings::default()
+        };
+        std::fs::create_dir_all(settings.paths.data()).unwrap();
+        std::fs::create_dir_all(&settings.paths.snapshot_dir).unwrap();
+        run_migrations(&settings.paths.db().display().to_string()).unwrap();
+        settings
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_capture_feedback_copies_frame() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = test_settings(&dir).await;
+        std::fs::write(settings.paths.snapshot_dir.join("00001.jpg"), b"jpeg").unwrap();
+
+        let row = capture_feedback(
+            &settings,
+            123456789,
+            DetectionFeedbackLabel::FalsePositive,
+            Some("spaghetti alert during normal print"),
+        )
+        .unwrap();
+        assert_eq!(row.label, "false_positive");
+        assert!(row.uploaded_dt.is_none());
+        let frame_path = row.frame_path.unwrap();
+        assert_eq!(std::fs::read(frame_path).unwrap(), b"jpeg");
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_sync_requires_video_snippets_consent() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = test_settings(&dir).await;
+        assert!(!settings.telemetry.video_snippets);
+        assert!(matches!(
+            sync_pending_feedback(&settings),
+            Err(ServiceError::TelemetryDisabled { .. })
+        ));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_sync_enqueues_outbox_row_and_marks_uploaded() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut settings = test_settings(&dir).await;
+        settings.telemetry.video_snippets = true;
+        std::fs::write(settings.paths.snapshot_dir.join("00001.jpg"), b"jpeg").unwrap();
+        let row = capture_feedback(
+            &settings,
+            123456789,
+            DetectionFeedbackLabel::FalseNegative,
+            None,
+        )
+        .unwrap();
+
+        let synced = sync_pending_feedback(&settings).unwrap();
+        assert_eq!(synced, 1);
+        let sqlite_connection = settings.paths.db().display().to_string();
+        assert!(DetectionFeedback::get_pending(&sqlite_connection)
+            .unwrap()
+            .is_empty());
+        let row = DetectionFeedback::get_by_id(&sqlite_connection, &row.id).unwrap();
+        assert!(row.uploaded_dt.is_some());
+        let outbox = CloudEventOutbox::get_unsent(&sqlite_connection).unwrap();
+        assert_eq!(outbox.len(), 1);
+        assert_eq!(outbox[0].subject, DETECTION_FEEDBACK_SUBJECT);
+    }
+}
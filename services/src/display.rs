@@ -0,0 +1,90 @@
+// Render device status to a physical display: discrete GPIO status LEDs (via
+// gpioset, like the lighting actuator) or an SSD1306 OLED / e-ink HAT through
+// an external renderer script.
+use log::info;
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+use printnanny_settings::display::{DisplayDriver, DisplaySettings};
+
+use crate::error::DisplayError;
+
+// status surfaced on the display, maintained by the bus consumer task
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct DisplayStatus {
+    // edge worker alive and heartbeating
+    pub connectivity: bool,
+    pub printing: bool,
+    // latched failure episode; cleared when the print state changes
+    pub alert: bool,
+}
+
+async fn set_led(settings: &DisplaySettings, pin: i32, on: bool) -> Result<(), DisplayError> {
+    let value = match on {
+        true => 1,
+        false => 0,
+    };
+    let pin_value = format!("{}={}", pin, value);
+    let output = Command::new("gpioset")
+        .args([&settings.gpio_chip, &pin_value])
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(DisplayError::CommandError {
+            cmd: format!("gpioset {} {}", settings.gpio_chip, pin_value),
+            code: output.status.code(),
+            stdout: String::from_utf8(output.stdout)?,
+            stderr: String::from_utf8(output.stderr)?,
+        });
+    }
+    Ok(())
+}
+
+async fn render_status_leds(
+    settings: &DisplaySettings,
+    status: &DisplayStatus,
+) -> Result<(), DisplayError> {
+    set_led(settings, settings.led_connectivity_pin, status.connectivity).await?;
+    set_led(settings, settings.led_printing_pin, status.printing).await?;
+    set_led(settings, settings.led_alert_pin, status.alert).await?;
+    Ok(())
+}
+
+async fn render_external(
+    settings: &DisplaySettings,
+    status: &DisplayStatus,
+) -> Result<(), DisplayError> {
+    if settings.render_command.is_empty() {
+        return Err(DisplayError::RenderCommandNotSet);
+    }
+    let payload = serde_json::to_string(status)?;
+    let output = Command::new(&settings.render_command)
+        .arg(&payload)
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(DisplayError::CommandError {
+            cmd: format!("{} {}", settings.render_command, payload),
+            code: output.status.code(),
+            stdout: String::from_utf8(output.stdout)?,
+            stderr: String::from_utf8(output.stderr)?,
+        });
+    }
+    Ok(())
+}
+
+// render the status through the configured display driver
+pub async fn render(
+    settings: &DisplaySettings,
+    status: &DisplayStatus,
+) -> Result<(), DisplayError> {
+    match settings.driver {
+        DisplayDriver::StatusLeds => render_status_leds(settings, status).await?,
+        DisplayDriver::Ssd1306 => render_external(settings, status).await?,
+    };
+    info!(
+        "Rendered display status driver={:?} connectivity={} printing={} alert={}",
+        settings.driver, status.connectivity, status.printing, status.alert
+    );
+    Ok(())
+}
@@ -0,0 +1,127 @@
+// Write audit for read-only-root images. Overlayfs images make directories
+// look present and mode 0755 while silently rejecting writes, so every
+// directory the services write to is probed with a real write at startup and
+// on demand via `printnanny doctor --paths`. Paths with a tmpfs fallback
+// (log dir -> run dir) report where writes are redirected to.
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use printnanny_settings::paths::dir_is_writable;
+use printnanny_settings::printnanny::PrintNannySettings;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathWriteCheck {
+    // which settings path this directory came from, e.g. "paths.log_dir"
+    pub name: String,
+    pub path: PathBuf,
+    pub writable: bool,
+    // where writes are redirected when the preferred path is read-only
+    pub fallback: Option<PathBuf>,
+}
+
+pub fn audit_writable_paths(settings: &PrintNannySettings) -> Vec<PathWriteCheck> {
+    let paths = &settings.paths;
+    let settings_dir = settings
+        .paths
+        .settings_file()
+        .parent()
+        .map(|p| p.to_path_buf())
+        .unwrap_or_default();
+    let audited: Vec<(&str, PathBuf, Option<PathBuf>)> = vec![
+        ("settings dir", settings_dir, None),
+        ("paths.state_dir", paths.state_dir.clone(), None),
+        ("paths.state_dir data", paths.data(), None),
+        ("paths.state_dir creds", paths.creds(), None),
+        ("paths.state_dir recovery", paths.recovery(), None),
+        ("paths.state_dir video", paths.video(), None),
+        ("paths.run_dir", paths.run_dir.clone(), None),
+        (
+            "paths.log_dir",
+            paths.log_dir.clone(),
+            Some(paths.run_dir.join("log")),
+        ),
+        ("paths.snapshot_dir", paths.snapshot_dir.clone(), None),
+    ];
+    audited
+        .into_iter()
+        .map(|(name, path, fallback)| {
+            let writable = dir_is_writable(&path);
+            PathWriteCheck {
+                name: name.to_string(),
+                fallback: match writable {
+                    true => None,
+                    false => fallback,
+                },
+                path,
+                writable,
+            }
+        })
+        .collect()
+}
+
+// startup entrypoint: warn about every unwritable path so overlayfs images
+// fail loudly instead of dropping writes on the floor
+pub fn warn_unwritable_paths(settings: &PrintNannySettings) -> Vec<PathWriteCheck> {
+    let checks = audit_writable_paths(settings);
+    for check in checks.iter().filter(|c| !c.writable) {
+        match &check.fallback {
+            Some(fallback) => log::warn!(
+                "{} {} is not writable, redirecting writes to {}",
+                check.name,
+                check.path.display(),
+                fallback.display()
+            ),
+            None => log::warn!(
+                "{} {} is not writable and has no fallback; writes will fail",
+                check.name,
+                check.path.display()
+            ),
+        }
+    }
+    checks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_audit_reports_writable_tempdirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = PrintNannySettings {
+            paths: printnanny_settings::paths::PrintNannyPaths {
+                state_dir: dir.path().join("state"),
+                run_dir: dir.path().join("run"),
+                log_dir: dir.path().join("log"),
+                snapshot_dir: dir.path().join("snapshot"),
+                ..printnanny_settings::paths::PrintNannyPaths::default()
+            },
+            ..PrintNannySettings::default()
+        };
+        let checks = audit_writable_paths(&settings);
+        for check in checks.iter().filter(|c| c.name != "settings dir") {
+            assert!(check.writable, "{} should be writable", check.name);
+            assert_eq!(check.fallback, None);
+        }
+    }
+
+    #[test_log::test]
+    fn test_unwritable_log_dir_reports_run_dir_fallback() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = PrintNannySettings {
+            paths: printnanny_settings::paths::PrintNannyPaths {
+                state_dir: dir.path().join("state"),
+                run_dir: dir.path().join("run"),
+                // /proc is a reliably read-only mount for exercising the probe
+                log_dir: PathBuf::from("/proc/printnanny-doctor-test"),
+                ..printnanny_settings::paths::PrintNannyPaths::default()
+            },
+            ..PrintNannySettings::default()
+        };
+        let checks = audit_writable_paths(&settings);
+        let log_check = checks.iter().find(|c| c.name == "paths.log_dir").unwrap();
+        assert!(!log_check.writable);
+        assert_eq!(log_check.fallback, Some(dir.path().join("run").join("log")));
+    }
+}
@@ -0,0 +1,241 @@
+// Read the enclosure sensor suite: BME280 temperature/humidity through the
+// kernel iio sysfs interface, an optional DS18B20 1-Wire probe, and a smoke
+// detector alarm line polled via gpioget. Failed reads log and yield None so
+// a flaky sensor degrades the heartbeat instead of aborting it.
+use log::{error, warn};
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+use printnanny_settings::enclosure::EnclosureSettings;
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct EnclosureReadings {
+    // BME280 enclosure climate
+    pub temperature_celsius: Option<f64>,
+    pub humidity_percent: Option<f64>,
+    // DS18B20 auxiliary probe (e.g. taped to the PSU or stepper driver)
+    pub aux_temperature_celsius: Option<f64>,
+    pub smoke_detected: Option<bool>,
+}
+
+// iio channels report millidegrees / millipercent as a bare integer
+fn parse_iio_milli(raw: &str) -> Option<f64> {
+    raw.trim().parse::<f64>().ok().map(|v| v / 1000.0)
+}
+
+// w1_slave format: two lines, a crc check ending in YES/NO and a t= reading
+// in millidegrees:
+//   53 01 4b 46 7f ff 0c 10 e9 : crc=e9 YES
+//   53 01 4b 46 7f ff 0c 10 e9 t=21187
+fn parse_w1_slave(raw: &str) -> Option<f64> {
+    let mut lines = raw.lines();
+    let crc_line = lines.next()?;
+    if !crc_line.trim_end().ends_with("YES") {
+        return None;
+    }
+    let t = lines.next()?.rsplit("t=").next()?;
+    t.trim().parse::<f64>().ok().map(|v| v / 1000.0)
+}
+
+async fn read_iio_channel(device: &str, channel: &str) -> Option<f64> {
+    let path = format!("{device}/{channel}");
+    match tokio::fs::read_to_string(&path).await {
+        Ok(raw) => {
+            let value = parse_iio_milli(&raw);
+            if value.is_none() {
+                warn!("Failed to parse iio reading path={} raw={}", path, raw);
+            }
+            value
+        }
+        Err(e) => {
+            warn!("Failed to read iio channel path={} error={}", path, e);
+            None
+        }
+    }
+}
+
+async fn read_ds18b20(device: &str) -> Option<f64> {
+    match tokio::fs::read_to_string(device).await {
+        Ok(raw) => {
+            let value = parse_w1_slave(&raw);
+            if value.is_none() {
+                warn!(
+                    "Failed to parse w1_slave reading path={} raw={}",
+                    device, raw
+                );
+            }
+            value
+        }
+        Err(e) => {
+            warn!("Failed to read DS18B20 path={} error={}", device, e);
+            None
+        }
+    }
+}
+
+async fn read_smoke_gpio(settings: &EnclosureSettings) -> Option<bool> {
+    let pin = settings.smoke_gpio_pin.to_string();
+    let output = match Command::new("gpioget")
+        .args([&settings.smoke_gpio_chip, &pin])
+        .output()
+        .await
+    {
+        Ok(output) => output,
+        Err(e) => {
+            warn!("Failed to run gpioget error={}", e);
+            return None;
+        }
+    };
+    if !output.status.success() {
+        warn!(
+            "gpioget {} {} exited with code {:?}",
+            settings.smoke_gpio_chip,
+            pin,
+            output.status.code()
+        );
+        return None;
+    }
+    match String::from_utf8_lossy(&output.stdout).trim() {
+        "1" => Some(true),
+        "0" => Some(false),
+        other => {
+            warn!("Unexpected gpioget output: {}", other);
+            None
+        }
+    }
+}
+
+// read every configured sensor; disabled sensors yield None
+pub async fn read_sensors(settings: &EnclosureSettings) -> EnclosureReadings {
+    let (temperature_celsius, humidity_percent) = match settings.bme280_iio_device.is_empty() {
+        true => (None, None),
+        false => (
+            read_iio_channel(&settings.bme280_iio_device, "in_temp_input").await,
+            read_iio_channel(&settings.bme280_iio_device, "in_humidityrelative_input").await,
+        ),
+    };
+    let aux_temperature_celsius = match settings.ds18b20_device.is_empty() {
+        true => None,
+        false => read_ds18b20(&settings.ds18b20_device).await,
+    };
+    let smoke_detected = match settings.smoke_enabled {
+        true => read_smoke_gpio(settings).await,
+        false => None,
+    };
+    EnclosureReadings {
+        temperature_celsius,
+        humidity_percent,
+        aux_temperature_celsius,
+        smoke_detected,
+    }
+}
+
+// human-readable threshold breaches for the current readings; smoke is
+// handled separately because it also triggers the emergency power-off
+pub fn threshold_breaches(
+    settings: &EnclosureSettings,
+    readings: &EnclosureReadings,
+) -> Vec<String> {
+    let mut breaches = Vec::new();
+    let temperature = [
+        readings.temperature_celsius,
+        readings.aux_temperature_celsius,
+    ]
+    .into_iter()
+    .flatten()
+    .fold(None::<f64>, |max, v| Some(max.map_or(v, |m| m.max(v))));
+    if let Some(temperature) = temperature {
+        if temperature > settings.temperature_alert_celsius as f64 {
+            breaches.push(format!(
+                "temperature {temperature:.1}C exceeds {}C",
+                settings.temperature_alert_celsius
+            ));
+        }
+    }
+    if let Some(humidity) = readings.humidity_percent {
+        if humidity > settings.humidity_alert_percent as f64 {
+            breaches.push(format!(
+                "humidity {humidity:.1}% exceeds {}%",
+                settings.humidity_alert_percent
+            ));
+        }
+    }
+    breaches
+}
+
+// emergency PSU cutoff on smoke detection; errors are logged because there is
+// nothing more urgent to do than attempt the cutoff
+pub async fn emergency_power_off(settings: &EnclosureSettings) {
+    if settings.smoke_power_off_command.is_empty() {
+        warn!("Smoke detected but no smoke_power_off_command is configured");
+        return;
+    }
+    error!(
+        "Smoke detected, running emergency power off command: {}",
+        settings.smoke_power_off_command
+    );
+    match Command::new("sh")
+        .args(["-c", &settings.smoke_power_off_command])
+        .output()
+        .await
+    {
+        Ok(output) if output.status.success() => {
+            error!("Emergency power off command succeeded");
+        }
+        Ok(output) => error!(
+            "Emergency power off command exited with code {:?} stderr={}",
+            output.status.code(),
+            String::from_utf8_lossy(&output.stderr)
+        ),
+        Err(e) => error!("Failed to run emergency power off command error={}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_parse_iio_milli() {
+        assert_eq!(parse_iio_milli("23125\n"), Some(23.125));
+        assert_eq!(parse_iio_milli("-1500"), Some(-1.5));
+        assert_eq!(parse_iio_milli("garbage"), None);
+    }
+
+    #[test_log::test]
+    fn test_parse_w1_slave() {
+        let raw = "53 01 4b 46 7f ff 0c 10 e9 : crc=e9 YES\n53 01 4b 46 7f ff 0c 10 e9 t=21187\n";
+        assert_eq!(parse_w1_slave(raw), Some(21.187));
+
+        // failed crc is discarded rather than reported as a bogus temperature
+        let bad_crc =
+            "53 01 4b 46 7f ff 0c 10 e9 : crc=e9 NO\n53 01 4b 46 7f ff 0c 10 e9 t=21187\n";
+        assert_eq!(parse_w1_slave(bad_crc), None);
+        assert_eq!(parse_w1_slave(""), None);
+    }
+
+    #[test_log::test]
+    fn test_threshold_breaches() {
+        let settings = EnclosureSettings::default();
+        let readings = EnclosureReadings {
+            temperature_celsius: Some(25.0),
+            humidity_percent: Some(40.0),
+            ..EnclosureReadings::default()
+        };
+        assert!(threshold_breaches(&settings, &readings).is_empty());
+
+        // the hotter of the two probes is compared against the threshold
+        let hot = EnclosureReadings {
+            temperature_celsius: Some(25.0),
+            aux_temperature_celsius: Some(61.5),
+            humidity_percent: Some(90.0),
+            ..EnclosureReadings::default()
+        };
+        let breaches = threshold_breaches(&settings, &hot);
+        assert_eq!(breaches.len(), 2);
+        assert!(breaches[0].contains("61.5"));
+
+        // no readings, no breaches
+        assert!(threshold_breaches(&settings, &EnclosureReadings::default()).is_empty());
+    }
+}
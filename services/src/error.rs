@@ -56,6 +56,9 @@ pub enum VideoRecordingSyncError {
 
     #[error("mp4 upload url was not set for VideoRecording with id={id} file_name={file_name}")]
     UploadUrlNotSet { id: String, file_name: String },
+
+    #[error("Anonymize transcode failed for {file_name}: {detail}")]
+    AnonymizeError { file_name: String, detail: String },
     #[error(transparent)]
     ReqwestError(#[from] reqwest::Error),
 
@@ -69,6 +72,87 @@ pub enum VideoRecordingSyncError {
     VideoRecordingsUpdateOrCreateError(#[from] VideoRecordingError),
 }
 
+#[derive(Error, Debug)]
+pub enum CalibrationError {
+    #[error("Calibration clip not found: {path}")]
+    ClipNotFound { path: String },
+
+    #[error("Detector run failed for {path}: {detail}")]
+    DetectorError { path: String, detail: String },
+
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+}
+
+#[derive(Error, Debug)]
+pub enum LightingError {
+    #[error("Command {cmd} exited with code {code:?} stdout: {stdout} stderr: {stderr}")]
+    CommandError {
+        cmd: String,
+        code: Option<i32>,
+        stdout: String,
+        stderr: String,
+    },
+
+    #[error("smart plug url is not configured for action={action}")]
+    SmartPlugUrlNotSet { action: String },
+
+    #[error(transparent)]
+    ReqwestError(#[from] reqwest::Error),
+
+    #[error(transparent)]
+    FromUtf8Error(#[from] std::string::FromUtf8Error),
+
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+}
+
+#[derive(Error, Debug)]
+pub enum DisplayError {
+    #[error("Command {cmd} exited with code {code:?} stdout: {stdout} stderr: {stderr}")]
+    CommandError {
+        cmd: String,
+        code: Option<i32>,
+        stdout: String,
+        stderr: String,
+    },
+
+    #[error("display render_command is not configured")]
+    RenderCommandNotSet,
+
+    #[error(transparent)]
+    SerdeJsonError(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    FromUtf8Error(#[from] std::string::FromUtf8Error),
+
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+}
+
+#[derive(Error, Debug)]
+pub enum TimeSyncError {
+    #[error("Command {cmd} exited with code {code:?} stdout: {stdout} stderr: {stderr}")]
+    CommandError {
+        cmd: String,
+        code: Option<i32>,
+        stdout: String,
+        stderr: String,
+    },
+
+    #[error("Failed to parse chronyc tracking output: {output}")]
+    ParseError { output: String },
+
+    #[error(transparent)]
+    FromUtf8Error(#[from] std::string::FromUtf8Error),
+
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+}
+
 #[derive(Error, Debug)]
 pub enum PrintNannyCamSettingsError {
     #[error(transparent)]
@@ -174,6 +258,12 @@ pub enum ServiceError {
     #[error(transparent)]
     ProcError(#[from] procfs::ProcError),
 
+    #[error(transparent)]
+    ZbusError(#[from] printnanny_dbus::zbus::Error),
+
+    #[error(transparent)]
+    ZipError(#[from] zip::result::ZipError),
+
     #[error(transparent)]
     SysInfoError(#[from] sys_info::Error),
 
@@ -190,6 +280,36 @@ pub enum ServiceError {
         field: String,
     },
 
+    #[error(transparent)]
+    JsonWebTokenError(#[from] jsonwebtoken::errors::Error),
+
+    #[error("Password hashing failed: {msg}")]
+    PasswordHashError { msg: String },
+
+    #[error("Invalid username or password")]
+    InvalidCredentials,
+
+    #[error("Stream token does not grant access to endpoint {endpoint}")]
+    StreamTokenScopeError { endpoint: String },
+
+    #[error("Telemetry category {category} is disabled in PrintNannySettings.telemetry")]
+    TelemetryDisabled { category: String },
+
+    #[error("OctoPrint backup failed: {detail}")]
+    OctoPrintBackupError { detail: String },
+
+    #[error("OctoPi import failed: {detail}")]
+    OctoPiImportError { detail: String },
+
+    #[error("Klipper import failed: {detail}")]
+    KlipperImportError { detail: String },
+
+    #[error("Provision file {path} is invalid: {detail}")]
+    ProvisionConfigError { path: String, detail: String },
+
+    #[error("Failed to render timelapse: {detail}")]
+    TimelapseRenderError { detail: String },
+
     #[error(transparent)]
     VersionControlledSettingsError(#[from] VersionControlledSettingsError),
 
@@ -204,6 +324,9 @@ pub enum ServiceError {
     #[error("Error running diesel SQLIte migrations: {msg}")]
     SQLiteMigrationError { msg: String },
 
+    #[error("Invalid device lifecycle transition from {from} to {to}")]
+    InvalidLifecycleTransition { from: String, to: String },
+
     #[error(transparent)]
     TaskJoinError(#[from] tokio::task::JoinError),
 }
@@ -0,0 +1,172 @@
+// Evidence bundle capture for detection failure events: when the failure
+// detector latches, grab a handful of snapshots plus the recording fragments
+// covering a short pre/post-roll window around the event, copy them into a
+// per-bundle directory and persist an EvidenceBundle row linked to the event,
+// so a user can judge the alert without scrubbing the full recording.
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use log::{info, warn};
+use tokio::fs;
+
+use printnanny_edge_db::evidence::{EvidenceBundle, NewEvidenceBundle};
+use printnanny_edge_db::video_recording::{VideoRecording, VideoRecordingPart};
+use printnanny_gst_pipelines::factory::PrintNannyPipelineFactory;
+use printnanny_settings::printnanny::PrintNannySettings;
+use printnanny_snapshot::client::SnapshotClient;
+
+use crate::error::ServiceError;
+
+// 3-5 snapshots per bundle, spaced far enough apart to show progression
+pub const EVIDENCE_SNAPSHOT_COUNT: usize = 4;
+pub const EVIDENCE_SNAPSHOT_INTERVAL: tokio::time::Duration = tokio::time::Duration::from_secs(2);
+// recording fragments whose running time falls inside this window around the
+// event are copied into the bundle as the pre/post-roll clip
+pub const EVIDENCE_PRE_ROLL_NS: i64 = 15_000_000_000;
+pub const EVIDENCE_POST_ROLL_NS: i64 = 15_000_000_000;
+// wait for the splitmuxsink to finalize the fragments covering the post-roll
+// window before copying them
+pub const EVIDENCE_POST_ROLL_WAIT: tokio::time::Duration = tokio::time::Duration::from_secs(20);
+
+async fn copy_into_bundle(src: &Path, bundle_dir: &Path) -> Result<PathBuf, ServiceError> {
+    let file_name = src.file_name().unwrap_or_default();
+    let dest = bundle_dir.join(file_name);
+    fs::copy(src, &dest)
+        .await
+        .map_err(|error| crate::error::IoError::CopyIOError {
+            src: src.to_path_buf(),
+            dest: dest.clone(),
+            error,
+        })?;
+    Ok(dest)
+}
+
+// snapshots are captured first, before the post-roll wait, so they show the
+// scene as close to the event as possible
+async fn capture_snapshots(bundle_dir: &Path) -> Vec<String> {
+    let client = SnapshotClient::default();
+    let mut files = Vec::new();
+    for i in 0..EVIDENCE_SNAPSHOT_COUNT {
+        match client.get_latest_snapshot().await {
+            Ok(jpeg_data) => {
+                let path = bundle_dir.join(format!("snapshot-{i}.jpg"));
+                match fs::write(&path, &jpeg_data).await {
+                    Ok(()) => files.push(path.display().to_string()),
+                    Err(e) => warn!("Failed to write snapshot {}: {}", path.display(), e),
+                }
+            }
+            Err(e) => warn!("Failed to capture evidence snapshot: {}", e),
+        }
+        if i + 1 < EVIDENCE_SNAPSHOT_COUNT {
+            tokio::time::sleep(EVIDENCE_SNAPSHOT_INTERVAL).await;
+        }
+    }
+    files
+}
+
+// recording fragments overlapping the pre/post-roll window around event_rt
+fn clip_parts(
+    sqlite_connection: &str,
+    recording: &VideoRecording,
+    event_rt: Option<i64>,
+) -> Result<Vec<VideoRecordingPart>, ServiceError> {
+    let mut parts =
+        VideoRecordingPart::get_parts_by_video_recording_id(sqlite_connection, &recording.id)?;
+    parts.sort_by_key(|part| part.buffer_runningtime);
+    let parts = match event_rt {
+        Some(rt) => parts
+            .into_iter()
+            .filter(|part| {
+                part.buffer_runningtime >= rt - EVIDENCE_PRE_ROLL_NS
+                    && part.buffer_runningtime <= rt + EVIDENCE_POST_ROLL_NS
+            })
+            .collect(),
+        // without a running time, fall back to the newest fragments
+        None => {
+            let skip = parts.len().saturating_sub(3);
+            parts.into_iter().skip(skip).collect::<Vec<_>>()
+        }
+    };
+    Ok(parts)
+}
+
+pub async fn capture_evidence_bundle(
+    settings: &PrintNannySettings,
+    event_rt: Option<i64>,
+    spaghetti_mean: Option<f64>,
+    adhesion_mean: Option<f64>,
+) -> Result<EvidenceBundle, ServiceError> {
+    let sqlite_connection = settings.paths.db().display().to_string();
+    let bundle_id = uuid::Uuid::new_v4().to_string();
+    let bundle_dir = settings.paths.evidence().join(&bundle_id);
+    fs::create_dir_all(&bundle_dir)
+        .await
+        .map_err(|error| crate::error::IoError::WriteIOError {
+            path: bundle_dir.display().to_string(),
+            error,
+        })?;
+
+    let snapshot_files = capture_snapshots(&bundle_dir).await;
+
+    // flush the circular pre-roll ring first, before it rolls past the
+    // moments leading up to the event; covers setups with no active recording
+    let mut clip_files: Vec<String> = match PrintNannyPipelineFactory::flush_preroll_clip(
+        (EVIDENCE_PRE_ROLL_NS / 1_000_000_000) as u64,
+        &bundle_dir,
+    ) {
+        Ok(fragments) => fragments
+            .iter()
+            .map(|fragment| fragment.display().to_string())
+            .collect(),
+        Err(e) => {
+            warn!("Failed to flush pre-roll ring for evidence bundle: {}", e);
+            Vec::new()
+        }
+    };
+
+    // let the recording pipeline finalize the fragments covering the
+    // post-roll window, then copy them; a missing or still-open fragment is
+    // skipped rather than failing the whole bundle
+    let recording = VideoRecording::get_current(&sqlite_connection)?;
+    if let Some(recording) = &recording {
+        tokio::time::sleep(EVIDENCE_POST_ROLL_WAIT).await;
+        for part in clip_parts(&sqlite_connection, recording, event_rt)? {
+            let src = PathBuf::from(&part.file_name);
+            if !src.exists() {
+                warn!(
+                    "Skipping missing recording fragment {} for evidence bundle {}",
+                    part.file_name, bundle_id
+                );
+                continue;
+            }
+            match copy_into_bundle(&src, &bundle_dir).await {
+                Ok(dest) => clip_files.push(dest.display().to_string()),
+                Err(e) => warn!("Failed to copy recording fragment: {}", e),
+            }
+        }
+    }
+
+    let created_dt = Utc::now();
+    let dir = bundle_dir.display().to_string();
+    let snapshot_files_json = serde_json::to_string(&snapshot_files)?;
+    let clip_files_json = serde_json::to_string(&clip_files)?;
+    let row = NewEvidenceBundle {
+        id: &bundle_id,
+        created_dt: &created_dt,
+        dir: &dir,
+        event_rt,
+        spaghetti_mean,
+        adhesion_mean,
+        video_recording_id: recording.as_ref().map(|recording| recording.id.as_str()),
+        snapshot_files: &snapshot_files_json,
+        clip_files: &clip_files_json,
+    };
+    let bundle = EvidenceBundle::insert(&sqlite_connection, row)?;
+    info!(
+        "Captured evidence bundle id={} snapshots={} clip_fragments={}",
+        bundle.id,
+        snapshot_files.len(),
+        clip_files.len()
+    );
+    Ok(bundle)
+}
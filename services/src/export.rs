@@ -0,0 +1,248 @@
+// Analysis-ready exports of the edge db for data-minded users: job history
+// (video recordings), model evaluation detections, and daily bandwidth
+// rollups. CSV is written directly; Parquet is reserved until a writer
+// dependency lands, the flag is accepted so dashboards can probe support.
+use std::path::PathBuf;
+
+use chrono::Utc;
+use log::info;
+use thiserror::Error;
+
+use printnanny_settings::printnanny::PrintNannySettings;
+
+#[derive(Error, Debug)]
+pub enum ExportError {
+    #[error("Invalid export table {table:?}, expected jobs|detections|bandwidth")]
+    InvalidTable { table: String },
+    #[error("Invalid export format {format:?}, expected csv|parquet")]
+    InvalidFormat { format: String },
+    #[error("Parquet export is not supported yet, use --format csv")]
+    ParquetUnsupported,
+    #[error(transparent)]
+    DieselError(#[from] printnanny_edge_db::diesel::result::Error),
+    #[error("Failed to write {path}: {error}")]
+    WriteIOError { path: String, error: std::io::Error },
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ExportTable {
+    // print job history via finalized video recordings
+    Jobs,
+    Detections,
+    Bandwidth,
+}
+
+impl ExportTable {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportTable::Jobs => "jobs",
+            ExportTable::Detections => "detections",
+            ExportTable::Bandwidth => "bandwidth",
+        }
+    }
+}
+
+impl std::str::FromStr for ExportTable {
+    type Err = ExportError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "jobs" => Ok(ExportTable::Jobs),
+            "detections" => Ok(ExportTable::Detections),
+            "bandwidth" => Ok(ExportTable::Bandwidth),
+            other => Err(ExportError::InvalidTable {
+                table: other.to_string(),
+            }),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Parquet,
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = ExportError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(ExportFormat::Csv),
+            "parquet" => Ok(ExportFormat::Parquet),
+            other => Err(ExportError::InvalidFormat {
+                format: other.to_string(),
+            }),
+        }
+    }
+}
+
+// quote a CSV field when it contains a delimiter, quote, or newline
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn csv_row(fields: &[String]) -> String {
+    let quoted: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+    quoted.join(",")
+}
+
+// render an optional column; None becomes an empty field
+fn opt<T: std::fmt::Display>(value: &Option<T>) -> String {
+    value.as_ref().map(|v| v.to_string()).unwrap_or_default()
+}
+
+fn jobs_csv(sqlite_connection: &str) -> Result<Vec<String>, ExportError> {
+    let mut lines =
+        vec!["id,recording_start,recording_end,gcode_file_name,dir,cloud_sync_done".to_string()];
+    for row in printnanny_edge_db::video_recording::VideoRecording::get_all(sqlite_connection)? {
+        lines.push(csv_row(&[
+            row.id,
+            opt(&row.recording_start),
+            opt(&row.recording_end),
+            opt(&row.gcode_file_name),
+            row.dir,
+            row.cloud_sync_done.to_string(),
+        ]));
+    }
+    Ok(lines)
+}
+
+fn detections_csv(sqlite_connection: &str) -> Result<Vec<String>, ExportError> {
+    let mut lines = vec![
+        "id,created_dt,model_id,rt,nozzle_count,nozzle_mean,print_count,print_mean,raft_count,raft_mean"
+            .to_string(),
+    ];
+    let since = chrono::DateTime::<Utc>::MIN_UTC;
+    for row in printnanny_edge_db::detections::Detection::list_since(sqlite_connection, since)? {
+        lines.push(csv_row(&[
+            row.id.to_string(),
+            row.created_dt.to_rfc3339(),
+            row.model_id,
+            opt(&row.rt),
+            opt(&row.nozzle_count),
+            opt(&row.nozzle_mean),
+            opt(&row.print_count),
+            opt(&row.print_mean),
+            opt(&row.raft_count),
+            opt(&row.raft_mean),
+        ]));
+    }
+    Ok(lines)
+}
+
+fn bandwidth_csv(sqlite_connection: &str) -> Result<Vec<String>, ExportError> {
+    let mut lines = vec!["day,subsystem,bytes_sent,bytes_received".to_string()];
+    // every rollup the retention window can hold
+    for row in printnanny_edge_db::bandwidth::BandwidthUsage::list_recent(sqlite_connection, 3650)?
+    {
+        lines.push(csv_row(&[
+            row.day,
+            row.subsystem,
+            row.bytes_sent.to_string(),
+            row.bytes_received.to_string(),
+        ]));
+    }
+    Ok(lines)
+}
+
+// write the requested table to output_dir (default paths.data()/export),
+// returning the file path
+pub fn export(
+    settings: &PrintNannySettings,
+    table: ExportTable,
+    format: ExportFormat,
+    output_dir: Option<PathBuf>,
+) -> Result<PathBuf, ExportError> {
+    if format == ExportFormat::Parquet {
+        return Err(ExportError::ParquetUnsupported);
+    }
+    let sqlite_connection = settings.paths.db().display().to_string();
+    let lines = match table {
+        ExportTable::Jobs => jobs_csv(&sqlite_connection)?,
+        ExportTable::Detections => detections_csv(&sqlite_connection)?,
+        ExportTable::Bandwidth => bandwidth_csv(&sqlite_connection)?,
+    };
+    let dir = output_dir.unwrap_or_else(|| settings.paths.data().join("export"));
+    std::fs::create_dir_all(&dir).map_err(|error| ExportError::WriteIOError {
+        path: dir.display().to_string(),
+        error,
+    })?;
+    let output = dir.join(format!(
+        "{}-{}.csv",
+        table.as_str(),
+        Utc::now().format("%Y%m%dT%H%M%SZ")
+    ));
+    let contents = format!("{}\n", lines.join("\n"));
+    std::fs::write(&output, contents).map_err(|error| ExportError::WriteIOError {
+        path: output.display().to_string(),
+        error,
+    })?;
+    info!(
+        "Exported {} rows from {} to {}",
+        lines.len() - 1,
+        table.as_str(),
+        output.display()
+    );
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use printnanny_settings::paths::PrintNannyPaths;
+
+    #[test_log::test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("line\nbreak"), "\"line\nbreak\"");
+    }
+
+    #[test_log::test]
+    fn test_export_bandwidth_csv() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = PrintNannySettings {
+            paths: PrintNannyPaths {
+                state_dir: dir.path().join("state"),
+                ..PrintNannyPaths::default()
+            },
+            ..PrintNannySettings::default()
+        };
+        std::fs::create_dir_all(settings.paths.data()).unwrap();
+        let sqlite_connection = settings.paths.db().display().to_string();
+        printnanny_edge_db::connection::run_migrations(&sqlite_connection).unwrap();
+        printnanny_edge_db::bandwidth::BandwidthUsage::record(
+            &sqlite_connection,
+            printnanny_edge_db::bandwidth::BandwidthSubsystem::Telemetry,
+            1024,
+            256,
+        )
+        .unwrap();
+
+        let output = export(&settings, ExportTable::Bandwidth, ExportFormat::Csv, None).unwrap();
+        let contents = std::fs::read_to_string(&output).unwrap();
+        let mut lines = contents.lines();
+        assert_eq!(
+            lines.next(),
+            Some("day,subsystem,bytes_sent,bytes_received")
+        );
+        let row = lines.next().unwrap();
+        assert!(row.contains("telemetry"));
+        assert!(row.ends_with("1024,256"));
+
+        // parquet stays rejected until a writer dependency lands
+        let parquet = export(
+            &settings,
+            ExportTable::Bandwidth,
+            ExportFormat::Parquet,
+            None,
+        );
+        assert!(matches!(parquet, Err(ExportError::ParquetUnsupported)));
+    }
+}
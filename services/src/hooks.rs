@@ -0,0 +1,188 @@
+// Scriptable hooks: user-registered shell commands run on lifecycle events
+// (print_started, print_failed_detected, update_applied, camera_error).
+// Hooks run via `sh -c` with a cleared environment — only PATH and the
+// PRINTNANNY_HOOK_* variables are passed — and are killed on timeout, so a
+// misbehaving script can't wedge the worker or leak credentials from the
+// parent environment.
+use std::time::Duration;
+
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+
+use printnanny_settings::hooks::{HookEvent, HookSettings};
+use printnanny_settings::printnanny::PrintNannySettings;
+
+// minimal PATH passed to hook subprocesses
+const HOOK_PATH: &str = "/usr/local/bin:/usr/bin:/bin";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HookResult {
+    pub event: HookEvent,
+    pub command: String,
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+}
+
+async fn run_hook(hook: &HookSettings, event: HookEvent, payload_json: &str) -> HookResult {
+    let mut command = tokio::process::Command::new("sh");
+    command
+        .arg("-c")
+        .arg(&hook.command)
+        .env_clear()
+        .env("PATH", HOOK_PATH)
+        .env("PRINTNANNY_HOOK_EVENT", event.as_str())
+        .env("PRINTNANNY_HOOK_PAYLOAD", payload_json)
+        .kill_on_drop(true);
+    let timeout = Duration::from_secs(hook.timeout_seconds.max(1));
+    match tokio::time::timeout(timeout, command.output()).await {
+        Ok(Ok(output)) => {
+            match output.status.success() {
+                true => info!(
+                    "Hook event={} command={:?} succeeded",
+                    event.as_str(),
+                    hook.command
+                ),
+                false => warn!(
+                    "Hook event={} command={:?} exited with {} stderr={}",
+                    event.as_str(),
+                    hook.command,
+                    output.status,
+                    String::from_utf8_lossy(&output.stderr)
+                ),
+            };
+            HookResult {
+                event,
+                command: hook.command.clone(),
+                exit_code: output.status.code(),
+                timed_out: false,
+            }
+        }
+        Ok(Err(e)) => {
+            error!(
+                "Hook event={} command={:?} failed to spawn: {}",
+                event.as_str(),
+                hook.command,
+                e
+            );
+            HookResult {
+                event,
+                command: hook.command.clone(),
+                exit_code: None,
+                timed_out: false,
+            }
+        }
+        Err(_) => {
+            warn!(
+                "Hook event={} command={:?} killed after {}s timeout",
+                event.as_str(),
+                hook.command,
+                hook.timeout_seconds
+            );
+            HookResult {
+                event,
+                command: hook.command.clone(),
+                exit_code: None,
+                timed_out: true,
+            }
+        }
+    }
+}
+
+// run every enabled hook registered for `event`, sequentially. Hook failures
+// are logged, never propagated — a broken user script must not break the
+// event pipeline that triggered it.
+pub async fn run_hooks(
+    settings: &PrintNannySettings,
+    event: HookEvent,
+    payload: &serde_json::Value,
+) -> Vec<HookResult> {
+    if !settings.hooks.enabled {
+        return vec![];
+    }
+    let payload_json = payload.to_string();
+    let mut results = vec![];
+    for hook in settings
+        .hooks
+        .hooks
+        .iter()
+        .filter(|hook| hook.enabled && hook.event == event)
+    {
+        results.push(run_hook(hook, event, &payload_json).await);
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_settings(hooks: Vec<HookSettings>) -> PrintNannySettings {
+        PrintNannySettings {
+            hooks: printnanny_settings::hooks::HooksSettings {
+                enabled: true,
+                hooks,
+            },
+            ..PrintNannySettings::default()
+        }
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_hook_receives_event_env_in_sandbox() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("hook-out");
+        let settings = test_settings(vec![HookSettings {
+            enabled: true,
+            event: HookEvent::PrintStarted,
+            command: format!(
+                "printf '%s %s' \"$PRINTNANNY_HOOK_EVENT\" \"$HOME\" > {}",
+                out.display()
+            ),
+            timeout_seconds: 10,
+        }]);
+
+        let results = run_hooks(
+            &settings,
+            HookEvent::PrintStarted,
+            &serde_json::json!({"state": "printing"}),
+        )
+        .await;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].exit_code, Some(0));
+        // env is cleared, so $HOME expands to nothing after the event name
+        assert_eq!(std::fs::read_to_string(&out).unwrap(), "print_started ");
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_hook_killed_on_timeout() {
+        let settings = test_settings(vec![HookSettings {
+            enabled: true,
+            event: HookEvent::CameraError,
+            command: "sleep 30".to_string(),
+            timeout_seconds: 1,
+        }]);
+        let results = run_hooks(&settings, HookEvent::CameraError, &serde_json::json!({})).await;
+        assert_eq!(results.len(), 1);
+        assert!(results[0].timed_out);
+        assert_eq!(results[0].exit_code, None);
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_disabled_and_mismatched_hooks_skipped() {
+        let settings = test_settings(vec![
+            HookSettings {
+                enabled: false,
+                event: HookEvent::PrintStarted,
+                command: "true".to_string(),
+                timeout_seconds: 10,
+            },
+            HookSettings {
+                enabled: true,
+                event: HookEvent::UpdateApplied,
+                command: "true".to_string(),
+                timeout_seconds: 10,
+            },
+        ]);
+        let results = run_hooks(&settings, HookEvent::PrintStarted, &serde_json::json!({})).await;
+        assert!(results.is_empty());
+    }
+}
@@ -0,0 +1,248 @@
+// Import wizard for users migrating from an existing Klipper/Mainsail setup:
+// `printnanny import klipper --source <path>` reads printer.cfg,
+// moonraker.conf, and any included cfg fragments (mainsail.cfg, macros) from a
+// mounted SD card or an scp-style user@host:path source, lays them into the
+// version-controlled settings repo, and commits the result so the migration
+// starts with a clean revision instead of manual copy-paste.
+use std::path::{Path, PathBuf};
+
+use log::info;
+use tokio::fs;
+use tokio::process::Command;
+
+use printnanny_settings::printnanny::PrintNannySettings;
+use printnanny_settings::vcs::VersionControlledSettings;
+
+use crate::error::ServiceError;
+use crate::octopi_import::SkippedOption;
+
+// config dir locations relative to a mounted source root; covers a directly
+// mounted config partition, a printer_data tree, and a whole mounted rootfs
+const CONFIG_DIR_CANDIDATES: &[&str] = &[
+    "",
+    "printer_data/config",
+    "klipper_config",
+    "home/pi/printer_data/config",
+    "home/pi/klipper_config",
+    "home/printnanny/printer_data/config",
+];
+
+fn skip_reason(file_name: &str) -> Option<String> {
+    if file_name.ends_with(".log") {
+        return Some("log files are not settings".to_string());
+    }
+    if file_name.ends_with(".bak") || file_name.contains(".cfg-") {
+        return Some("editor/rollback backup".to_string());
+    }
+    // Mainsail/Fluidd write timestamped printer-YYYYMMDD_HHMMSS.cfg rollbacks
+    if file_name.starts_with("printer-") && file_name.ends_with(".cfg") {
+        return Some("timestamped printer.cfg rollback backup".to_string());
+    }
+    if !file_name.ends_with(".cfg") && !file_name.ends_with(".conf") {
+        return Some("not a Klipper/Moonraker config file".to_string());
+    }
+    None
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct KlipperImportReport {
+    pub source: String,
+    // files committed into the settings repo, relative to the repo root
+    pub imported_files: Vec<String>,
+    // files present in the source config dir that were not imported
+    pub skipped: Vec<SkippedOption>,
+}
+
+// locate the directory containing printer.cfg under a mounted source root
+fn find_config_dir(root: &Path) -> Option<PathBuf> {
+    CONFIG_DIR_CANDIDATES
+        .iter()
+        .map(|candidate| root.join(candidate))
+        .find(|dir| dir.join("printer.cfg").exists())
+}
+
+// scp-style sources (user@host:path) are staged into a tempdir first, so the
+// rest of the import treats remote and mounted sources identically
+async fn stage_remote_source(source: &str, dest: &Path) -> Result<(), ServiceError> {
+    let output = Command::new("scp")
+        .arg("-r")
+        .arg(source)
+        .arg(dest)
+        .output()
+        .await
+        .map_err(|e| crate::error::IoError::WriteIOError {
+            path: dest.display().to_string(),
+            error: e,
+        })?;
+    if !output.status.success() {
+        return Err(ServiceError::KlipperImportError {
+            detail: format!(
+                "scp from {} failed: {}",
+                source,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        });
+    }
+    Ok(())
+}
+
+pub async fn import_klipper(source: &str) -> Result<KlipperImportReport, ServiceError> {
+    let settings = PrintNannySettings::new().await?;
+    let klipper_settings = settings.to_klipper_settings();
+    let moonraker_settings = settings.to_moonraker_settings();
+
+    // user@host:path sources are copied locally before scanning
+    let _staged: Option<tempfile::TempDir>;
+    let root = if source.contains(':') {
+        let tmp = tempfile::tempdir()
+            .map_err(|e| crate::error::IoError::TempFileError { msg: e.to_string() })?;
+        stage_remote_source(source, tmp.path()).await?;
+        let root = tmp.path().to_path_buf();
+        _staged = Some(tmp);
+        root
+    } else {
+        _staged = None;
+        PathBuf::from(source)
+    };
+
+    let config_dir = find_config_dir(&root).ok_or_else(|| ServiceError::KlipperImportError {
+        detail: format!(
+            "no printer.cfg found under {} (looked in {})",
+            source,
+            CONFIG_DIR_CANDIDATES.join(", ")
+        ),
+    })?;
+    info!("Importing Klipper config from {}", config_dir.display());
+
+    let mut imported_files = Vec::new();
+    let mut skipped = Vec::new();
+    let mut moonraker_conf: Option<String> = None;
+
+    // extra cfg fragments (mainsail.cfg, macros) are copied next to printer.cfg
+    // before save_and_commit runs, so a single commit captures the whole set
+    let klipper_dir = klipper_settings
+        .settings_file
+        .parent()
+        .unwrap()
+        .to_path_buf();
+    fs::create_dir_all(&klipper_dir)
+        .await
+        .map_err(|e| crate::error::IoError::WriteIOError {
+            path: klipper_dir.display().to_string(),
+            error: e,
+        })?;
+
+    let mut entries =
+        fs::read_dir(&config_dir)
+            .await
+            .map_err(|e| crate::error::IoError::ReadIOError {
+                path: config_dir.display().to_string(),
+                error: e,
+            })?;
+    while let Some(entry) =
+        entries
+            .next_entry()
+            .await
+            .map_err(|e| crate::error::IoError::ReadIOError {
+                path: config_dir.display().to_string(),
+                error: e,
+            })?
+    {
+        let path = entry.path();
+        if path.is_dir() {
+            continue;
+        }
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if file_name == "printer.cfg" {
+            // committed last, via save_and_commit
+            continue;
+        }
+        if let Some(reason) = skip_reason(&file_name) {
+            skipped.push(SkippedOption {
+                section: file_name,
+                reason,
+            });
+            continue;
+        }
+        let content =
+            fs::read_to_string(&path)
+                .await
+                .map_err(|e| crate::error::IoError::ReadIOError {
+                    path: path.display().to_string(),
+                    error: e,
+                })?;
+        if file_name == "moonraker.conf" {
+            moonraker_conf = Some(content);
+            continue;
+        }
+        let dest = klipper_dir.join(&file_name);
+        fs::write(&dest, content)
+            .await
+            .map_err(|e| crate::error::IoError::WriteIOError {
+                path: dest.display().to_string(),
+                error: e,
+            })?;
+        imported_files.push(format!("klipper/{}", file_name));
+    }
+
+    if let Some(content) = moonraker_conf {
+        moonraker_settings
+            .save_and_commit(
+                &content,
+                Some(format!("Imported moonraker.conf from {}", source)),
+            )
+            .await?;
+        imported_files.push("moonraker/moonraker.conf".to_string());
+    }
+
+    let printer_cfg = config_dir.join("printer.cfg");
+    let content =
+        fs::read_to_string(&printer_cfg)
+            .await
+            .map_err(|e| crate::error::IoError::ReadIOError {
+                path: printer_cfg.display().to_string(),
+                error: e,
+            })?;
+    klipper_settings
+        .save_and_commit(
+            &content,
+            Some(format!("Imported Klipper config from {}", source)),
+        )
+        .await?;
+    imported_files.push("klipper/printer.cfg".to_string());
+    info!(
+        "Imported {} Klipper/Moonraker config files from {}",
+        imported_files.len(),
+        source
+    );
+
+    Ok(KlipperImportReport {
+        source: source.to_string(),
+        imported_files,
+        skipped,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_find_config_dir_in_mounted_rootfs() {
+        let root = tempfile::tempdir().unwrap();
+        let config_dir = root.path().join("home/pi/printer_data/config");
+        std::fs::create_dir_all(&config_dir).unwrap();
+        std::fs::write(config_dir.join("printer.cfg"), "[printer]\n").unwrap();
+        assert_eq!(find_config_dir(root.path()), Some(config_dir));
+    }
+
+    #[test_log::test]
+    fn test_skip_reason_filters_backups_and_logs() {
+        assert!(skip_reason("printer.cfg").is_none());
+        assert!(skip_reason("mainsail.cfg").is_none());
+        assert!(skip_reason("moonraker.conf").is_none());
+        assert!(skip_reason("printer-20230101_120000.cfg").is_some());
+        assert!(skip_reason("klippy.log").is_some());
+        assert!(skip_reason("printer.cfg.bak").is_some());
+    }
+}
@@ -0,0 +1,135 @@
+// Glass-to-glass latency probes for the live view legs. gstd exposes no
+// buffer PTS query, so latency is measured from the pipeline output artifacts:
+// the camera leg from the age of the newest snapshot JPEG, the HLS leg from
+// segment freshness plus the player-side playlist buffer, and the WebRTC leg
+// from the camera leg plus the RTP jitter buffer.
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use printnanny_settings::printnanny::PrintNannySettings;
+
+// default jitter buffer applied by the WebRTC gateway before playout
+pub const RTP_JITTER_BUFFER_MS: u64 = 200;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HlsLatency {
+    /// Age of the newest HLS segment in milliseconds
+    pub newest_segment_age_ms: u64,
+    /// Player-side buffer: playlist length x target segment duration
+    pub playlist_buffer_ms: u64,
+    pub glass_to_glass_estimate_ms: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebRtcLatency {
+    /// Camera leg latency, measured from the age of the newest snapshot JPEG
+    pub camera_leg_latency_ms: u64,
+    pub jitter_buffer_ms: u64,
+    pub glass_to_glass_estimate_ms: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatencyReport {
+    /// None when the corresponding pipeline leg has produced no output yet
+    pub hls: Option<HlsLatency>,
+    pub webrtc: Option<WebRtcLatency>,
+}
+
+// target segment duration (seconds) and segment count from an HLS playlist
+fn parse_playlist(content: &str) -> (Option<u64>, u64) {
+    let target_duration = content.lines().find_map(|line| {
+        line.strip_prefix("#EXT-X-TARGETDURATION:")?
+            .trim()
+            .parse()
+            .ok()
+    });
+    let segment_count = content
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .count() as u64;
+    (target_duration, segment_count)
+}
+
+// age of the most recently modified file in the directory, in milliseconds
+fn newest_file_age_ms(dir: &Path) -> Option<u64> {
+    let newest = fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok()?.metadata().ok()?.modified().ok())
+        .max()?;
+    let age = SystemTime::now().duration_since(newest).ok()?;
+    Some(age.as_millis() as u64)
+}
+
+fn measure_hls_latency(settings: &PrintNannySettings) -> Option<HlsLatency> {
+    let hls = &*settings.video_stream.hls;
+    let segments_dir = Path::new(hls.segments.as_str()).parent()?;
+    let newest_segment_age_ms = newest_file_age_ms(segments_dir)?;
+    let playlist = fs::read_to_string(hls.playlist.as_str()).ok()?;
+    let (target_duration, segment_count) = parse_playlist(&playlist);
+    let playlist_buffer_ms = target_duration? * segment_count * 1000;
+    Some(HlsLatency {
+        newest_segment_age_ms,
+        playlist_buffer_ms,
+        glass_to_glass_estimate_ms: newest_segment_age_ms + playlist_buffer_ms,
+    })
+}
+
+fn measure_webrtc_latency(settings: &PrintNannySettings) -> Option<WebRtcLatency> {
+    let snapshot_dir = Path::new(settings.video_stream.snapshot.path.as_str()).parent()?;
+    let camera_leg_latency_ms = newest_file_age_ms(snapshot_dir)?;
+    Some(WebRtcLatency {
+        camera_leg_latency_ms,
+        jitter_buffer_ms: RTP_JITTER_BUFFER_MS,
+        glass_to_glass_estimate_ms: camera_leg_latency_ms + RTP_JITTER_BUFFER_MS,
+    })
+}
+
+pub fn measure_latency(settings: &PrintNannySettings) -> LatencyReport {
+    LatencyReport {
+        hls: measure_hls_latency(settings),
+        webrtc: measure_webrtc_latency(settings),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAYLIST: &str = r#"#EXTM3U
+#EXT-X-VERSION:3
+#EXT-X-TARGETDURATION:4
+#EXT-X-MEDIA-SEQUENCE:12
+#EXTINF:4.0,
+segment00012.ts
+#EXTINF:4.0,
+segment00013.ts
+#EXTINF:4.0,
+segment00014.ts
+"#;
+
+    #[test_log::test]
+    fn test_parse_playlist() {
+        let (target_duration, segment_count) = parse_playlist(PLAYLIST);
+        assert_eq!(target_duration, Some(4));
+        assert_eq!(segment_count, 3);
+    }
+
+    #[test_log::test]
+    fn test_parse_playlist_without_target_duration() {
+        let (target_duration, segment_count) = parse_playlist("#EXTM3U\n");
+        assert_eq!(target_duration, None);
+        assert_eq!(segment_count, 0);
+    }
+
+    #[test_log::test]
+    fn test_newest_file_age_ms() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(newest_file_age_ms(dir.path()), None);
+        std::fs::write(dir.path().join("segment00012.ts"), b"segment").unwrap();
+        let age = newest_file_age_ms(dir.path()).unwrap();
+        assert!(age < 5000);
+    }
+}
@@ -1,11 +1,52 @@
+// re-export for downstream FluentArgs construction
+pub use fluent;
+
+pub mod anonymize;
 pub mod cpuinfo;
 pub mod crash_report;
+pub mod auth;
+pub mod calibration;
+pub mod camera_conflict;
+pub mod capabilities;
+pub mod clone;
+pub mod dataset;
+pub mod decommission;
+pub mod detection_feedback;
+pub mod display;
+pub mod doctor;
+pub mod enclosure;
 pub mod error;
+pub mod evidence;
+pub mod export;
 pub mod file;
+pub mod hooks;
 pub mod janus;
+pub mod klipper_import;
+pub mod latency;
+pub mod lifecycle;
+pub mod lighting;
+pub mod localization;
+pub mod log_rotation;
 pub mod metadata;
+pub mod model_evaluation;
+pub mod network;
+pub mod octopi_import;
 pub mod octoprint;
+pub mod onvif;
+pub mod power;
+pub mod pre_update;
+pub mod print_state;
+pub mod provision;
+pub mod resource_monitor;
+pub mod schedule;
+pub mod soak;
+pub mod stream_token;
+pub mod time_sync;
+pub mod timelapse;
+pub mod upgrade_advisor;
+pub mod version;
 pub mod video_recording_sync;
+pub mod video_timeline;
 
 pub mod os_release;
 pub mod printnanny_api;
@@ -0,0 +1,192 @@
+// Explicit device lifecycle state machine, persisted in the state dir so
+// handlers consult one place instead of inferring state from scattered
+// signals (cloud creds on disk, swupdate running, ...). Transitions are
+// validated against the expected progression:
+//
+//   unprovisioned -> provisioned -> cloud_connected
+//
+// with degraded/updating reachable from any provisioned state and restored
+// to the previous state when the episode ends.
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use printnanny_settings::paths::PrintNannyPaths;
+
+use crate::error::ServiceError;
+use crate::printnanny_api::{read_model_json, save_model_json};
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceLifecycleState {
+    #[default]
+    Unprovisioned,
+    Provisioned,
+    CloudConnected,
+    Degraded,
+    Updating,
+}
+
+impl DeviceLifecycleState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeviceLifecycleState::Unprovisioned => "unprovisioned",
+            DeviceLifecycleState::Provisioned => "provisioned",
+            DeviceLifecycleState::CloudConnected => "cloud_connected",
+            DeviceLifecycleState::Degraded => "degraded",
+            DeviceLifecycleState::Updating => "updating",
+        }
+    }
+}
+
+// persisted as lifecycle.json in the state dir
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DeviceLifecycle {
+    pub state: DeviceLifecycleState,
+    // state active before the current one, restored when a degraded/updating
+    // episode ends
+    pub previous: Option<DeviceLifecycleState>,
+    pub updated_dt: DateTime<Utc>,
+    // human-readable reason for the last transition
+    pub detail: String,
+}
+
+impl Default for DeviceLifecycle {
+    fn default() -> Self {
+        Self {
+            state: DeviceLifecycleState::default(),
+            previous: None,
+            updated_dt: Utc::now(),
+            detail: "".to_string(),
+        }
+    }
+}
+
+pub fn transition_allowed(from: DeviceLifecycleState, to: DeviceLifecycleState) -> bool {
+    use DeviceLifecycleState::*;
+    match (from, to) {
+        // re-entering the current state refreshes updated_dt/detail
+        (from, to) if from == to => true,
+        // updates can begin from any state, and recovery (or a factory
+        // reset shipped as an update) may land anywhere
+        (_, Updating) | (Updating, _) => true,
+        (Unprovisioned, Provisioned) => true,
+        // cloud account connection implies provisioning completed
+        (Unprovisioned | Provisioned, CloudConnected) => true,
+        // connectivity loss demotes cloud-connected back to provisioned
+        (CloudConnected, Provisioned) => true,
+        (Provisioned | CloudConnected, Degraded) => true,
+        (Degraded, Provisioned | CloudConnected) => true,
+        _ => false,
+    }
+}
+
+// current lifecycle; a missing or unreadable file means the device has
+// never completed provisioning
+pub fn load(paths: &PrintNannyPaths) -> DeviceLifecycle {
+    match read_model_json::<DeviceLifecycle>(&paths.lifecycle()) {
+        Ok(lifecycle) => lifecycle,
+        Err(e) => {
+            if paths.lifecycle().exists() {
+                warn!(
+                    "Failed to read {}, assuming unprovisioned: {}",
+                    paths.lifecycle().display(),
+                    e
+                );
+            }
+            DeviceLifecycle::default()
+        }
+    }
+}
+
+// validate and persist a transition, returning the new lifecycle
+pub fn transition(
+    paths: &PrintNannyPaths,
+    to: DeviceLifecycleState,
+    detail: &str,
+) -> Result<DeviceLifecycle, ServiceError> {
+    let current = load(paths);
+    if !transition_allowed(current.state, to) {
+        return Err(ServiceError::InvalidLifecycleTransition {
+            from: current.state.as_str().to_string(),
+            to: to.as_str().to_string(),
+        });
+    }
+    let lifecycle = DeviceLifecycle {
+        state: to,
+        previous: Some(current.state),
+        updated_dt: Utc::now(),
+        detail: detail.to_string(),
+    };
+    save_model_json(&lifecycle, &paths.lifecycle()).map_err(|error| {
+        crate::error::IoError::WriteIOError {
+            path: paths.lifecycle().display().to_string(),
+            error,
+        }
+    })?;
+    info!(
+        "Device lifecycle {} -> {} detail={}",
+        current.state.as_str(),
+        to.as_str(),
+        detail
+    );
+    Ok(lifecycle)
+}
+
+// end a degraded/updating episode by restoring the previous state
+pub fn restore_previous(
+    paths: &PrintNannyPaths,
+    detail: &str,
+) -> Result<DeviceLifecycle, ServiceError> {
+    let current = load(paths);
+    let to = current
+        .previous
+        .unwrap_or(DeviceLifecycleState::Provisioned);
+    transition(paths, to, detail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_paths(dir: &tempfile::TempDir) -> PrintNannyPaths {
+        let paths = PrintNannyPaths {
+            state_dir: dir.path().join("state"),
+            ..PrintNannyPaths::default()
+        };
+        std::fs::create_dir_all(&paths.state_dir).unwrap();
+        paths
+    }
+
+    #[test_log::test]
+    fn test_missing_file_is_unprovisioned() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = test_paths(&dir);
+        let lifecycle = load(&paths);
+        assert_eq!(lifecycle.state, DeviceLifecycleState::Unprovisioned);
+    }
+
+    #[test_log::test]
+    fn test_transition_persists_and_restores_previous() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = test_paths(&dir);
+        transition(&paths, DeviceLifecycleState::Provisioned, "init").unwrap();
+        transition(&paths, DeviceLifecycleState::CloudConnected, "connected").unwrap();
+        transition(&paths, DeviceLifecycleState::Updating, "update started").unwrap();
+        assert_eq!(load(&paths).state, DeviceLifecycleState::Updating);
+
+        let lifecycle = restore_previous(&paths, "update applied").unwrap();
+        assert_eq!(lifecycle.state, DeviceLifecycleState::CloudConnected);
+        assert_eq!(load(&paths).state, DeviceLifecycleState::CloudConnected);
+    }
+
+    #[test_log::test]
+    fn test_invalid_transition_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = test_paths(&dir);
+        let result = transition(&paths, DeviceLifecycleState::Degraded, "no reason");
+        assert!(result.is_err());
+        // rejected transitions leave the persisted state untouched
+        assert_eq!(load(&paths).state, DeviceLifecycleState::Unprovisioned);
+    }
+}
@@ -0,0 +1,60 @@
+// Toggle an LED light / relay alongside camera activity, either through a GPIO
+// pin (via gpioset) or an HTTP smart plug.
+use log::info;
+use tokio::process::Command;
+
+use printnanny_settings::lighting::{LightingMode, LightingSettings};
+
+use crate::error::LightingError;
+
+async fn set_gpio_power(settings: &LightingSettings, on: bool) -> Result<(), LightingError> {
+    let value = match on {
+        true => 1,
+        false => 0,
+    };
+    let pin_value = format!("{}={}", settings.gpio_pin, value);
+    let output = Command::new("gpioset")
+        .args([&settings.gpio_chip, &pin_value])
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(LightingError::CommandError {
+            cmd: format!("gpioset {} {}", settings.gpio_chip, pin_value),
+            code: output.status.code(),
+            stdout: String::from_utf8(output.stdout)?,
+            stderr: String::from_utf8(output.stderr)?,
+        });
+    }
+    info!(
+        "Set lighting gpio_chip={} gpio_pin={} value={}",
+        settings.gpio_chip, settings.gpio_pin, value
+    );
+    Ok(())
+}
+
+async fn set_smart_plug_power(settings: &LightingSettings, on: bool) -> Result<(), LightingError> {
+    let url = match on {
+        true => &settings.smart_plug_on_url,
+        false => &settings.smart_plug_off_url,
+    };
+    if url.is_empty() {
+        return Err(LightingError::SmartPlugUrlNotSet {
+            action: match on {
+                true => "on".into(),
+                false => "off".into(),
+            },
+        });
+    }
+    let res = reqwest::get(url).await?;
+    res.error_for_status()?;
+    info!("Set lighting smart plug on={} url={}", on, url);
+    Ok(())
+}
+
+// switch the configured lighting actuator on or off
+pub async fn set_power(settings: &LightingSettings, on: bool) -> Result<(), LightingError> {
+    match settings.mode {
+        LightingMode::Gpio => set_gpio_power(settings, on).await,
+        LightingMode::SmartPlug => set_smart_plug_power(settings, on).await,
+    }
+}
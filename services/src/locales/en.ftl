@@ -0,0 +1,17 @@
+crash-report-submitted = Submitted crash report:
+restore-pre-update-done = Restored pre-update snapshot:
+restore-requires-flag = restore requires --pre-update
+doctor-requires-flag = doctor requires --profile
+doctor-camera-conflict = { $unit } is active and may be holding the camera device. Stop it with: sudo systemctl stop { $unit }
+decommission-requires-force = Decommission wipes credentials, settings, database, and media and cannot be undone. Re-run with --force to proceed.
+config-set = Set { $key }={ $value }
+privacy-mode = privacy_mode={ $enabled }
+events-subject = --- subject={ $subject }
+events-reply = reply={ $reply }
+import-skipped = Skipped { $section }: { $reason }
+import-plugin-not-bundled = Plugin { $plugin } is installed on { $host } but not bundled with OctoPrint - install it via the OctoPrint plugin manager
+soak-check-failed = Soak check { $name } failed: { $detail }
+user-password-prompt = Password:
+user-deleted = Deleted { $count } user(s)
+user-password-updated = Updated password for { $username }
+version-incompatible = { $component } { $version } is known-incompatible with { $other } { $other_version }: { $reason }
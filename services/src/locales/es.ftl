@@ -0,0 +1,17 @@
+crash-report-submitted = Informe de error enviado:
+restore-pre-update-done = Instantánea previa a la actualización restaurada:
+restore-requires-flag = restore requiere --pre-update
+doctor-requires-flag = doctor requiere --profile
+doctor-camera-conflict = { $unit } está activo y puede estar reteniendo la cámara. Deténlo con: sudo systemctl stop { $unit }
+decommission-requires-force = La retirada borra credenciales, configuración, base de datos y multimedia, y no se puede deshacer. Vuelve a ejecutar con --force para continuar.
+config-set = { $key }={ $value } configurado
+privacy-mode = privacy_mode={ $enabled }
+events-subject = --- subject={ $subject }
+events-reply = reply={ $reply }
+import-skipped = Se omitió { $section }: { $reason }
+import-plugin-not-bundled = El plugin { $plugin } está instalado en { $host } pero no viene incluido con OctoPrint - instálalo desde el gestor de plugins de OctoPrint
+soak-check-failed = La comprobación de soak { $name } falló: { $detail }
+user-password-prompt = Contraseña:
+user-deleted = { $count } usuario(s) eliminado(s)
+user-password-updated = Contraseña actualizada para { $username }
+version-incompatible = { $component } { $version } es incompatible con { $other } { $other_version }: { $reason }
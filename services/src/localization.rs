@@ -0,0 +1,146 @@
+// Fluent-based localization for user-facing strings (CLI output, reply detail
+// text). Locale is selected via PrintNannySettings.locale; unknown locales and
+// missing messages fall back to English so the UI never shows a blank string.
+use fluent::{FluentArgs, FluentBundle, FluentResource};
+use log::{error, warn};
+use unic_langid::LanguageIdentifier;
+
+use printnanny_settings::printnanny::PrintNannySettings;
+
+pub const DEFAULT_LOCALE: &str = "en";
+
+const EN_FTL: &str = include_str!("locales/en.ftl");
+const ES_FTL: &str = include_str!("locales/es.ftl");
+
+pub fn supported_locales() -> Vec<&'static str> {
+    vec!["en", "es"]
+}
+
+fn ftl_source(locale: &str) -> &'static str {
+    match locale {
+        "es" => ES_FTL,
+        _ => EN_FTL,
+    }
+}
+
+pub struct Localizer {
+    bundle: FluentBundle<FluentResource>,
+}
+
+impl Localizer {
+    pub fn new(locale: &str) -> Self {
+        let langid: LanguageIdentifier = locale
+            .parse()
+            .unwrap_or_else(|_| DEFAULT_LOCALE.parse().unwrap());
+        if !supported_locales().contains(&locale) && locale != DEFAULT_LOCALE {
+            warn!(
+                "Locale {} is not supported, falling back to {}",
+                locale, DEFAULT_LOCALE
+            );
+        }
+        let mut bundle = FluentBundle::new(vec![langid]);
+        // skip the unicode isolation marks fluent inserts around placeables;
+        // output goes to terminals and log files, not bidi-aware UIs
+        bundle.set_use_isolating(false);
+        let resource = FluentResource::try_new(ftl_source(locale).to_string())
+            .expect("Failed to parse fluent resource");
+        bundle
+            .add_resource(resource)
+            .expect("Failed to add fluent resource to bundle");
+        Self { bundle }
+    }
+
+    // load locale from PrintNannySettings, falling back to English on error
+    pub async fn from_settings() -> Self {
+        match PrintNannySettings::new().await {
+            Ok(settings) => Self::new(&settings.locale),
+            Err(e) => {
+                error!(
+                    "Failed to initialize PrintNannySettings with error={}. Falling back to locale={}",
+                    e, DEFAULT_LOCALE
+                );
+                Self::new(DEFAULT_LOCALE)
+            }
+        }
+    }
+
+    pub fn msg(&self, id: &str) -> String {
+        self.msg_with_args(id, None)
+    }
+
+    pub fn msg_with_args(&self, id: &str, args: Option<&FluentArgs>) -> String {
+        let message = match self.bundle.get_message(id) {
+            Some(message) => message,
+            None => {
+                warn!("No fluent message found for id={}", id);
+                return id.to_string();
+            }
+        };
+        let pattern = match message.value() {
+            Some(pattern) => pattern,
+            None => {
+                warn!("Fluent message id={} has no value", id);
+                return id.to_string();
+            }
+        };
+        let mut errors = vec![];
+        let result = self
+            .bundle
+            .format_pattern(pattern, args, &mut errors)
+            .to_string();
+        for e in errors {
+            warn!("Fluent formatting error for id={}: {}", id, e);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_en_message() {
+        let localizer = Localizer::new("en");
+        assert_eq!(
+            localizer.msg("crash-report-submitted"),
+            "Submitted crash report:"
+        );
+    }
+
+    #[test_log::test]
+    fn test_es_message() {
+        let localizer = Localizer::new("es");
+        assert_eq!(
+            localizer.msg("crash-report-submitted"),
+            "Informe de error enviado:"
+        );
+    }
+
+    #[test_log::test]
+    fn test_message_args() {
+        let localizer = Localizer::new("en");
+        let mut args = FluentArgs::new();
+        args.set("key", "video_stream.detection.nms_threshold");
+        args.set("value", "40");
+        assert_eq!(
+            localizer.msg_with_args("config-set", Some(&args)),
+            "Set video_stream.detection.nms_threshold=40"
+        );
+    }
+
+    #[test_log::test]
+    fn test_unknown_locale_falls_back_to_en() {
+        let localizer = Localizer::new("xx");
+        assert_eq!(
+            localizer.msg("crash-report-submitted"),
+            "Submitted crash report:"
+        );
+    }
+
+    #[test_log::test]
+    fn test_missing_message_returns_id() {
+        let localizer = Localizer::new("en");
+        assert_eq!(localizer.msg("no-such-message"), "no-such-message");
+    }
+}
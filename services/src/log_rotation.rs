@@ -0,0 +1,217 @@
+// Built-in rotation for files under paths.log_dir. Pipeline and worker logs
+// grow unbounded on long-running devices; logrotate is not shipped on every
+// image, so the edge worker rotates oversized/old files itself (copy +
+// truncate so foreign writers keep their open file descriptors), compresses
+// the rotated copy with gzip, and enforces a total size cap by deleting the
+// oldest rotated archives first.
+use std::fs;
+use std::io::Seek;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use log::{info, warn};
+
+// rotate a live log once it grows past this
+pub const ROTATE_SIZE_BYTES: u64 = 16 * 1024 * 1024;
+// rotate a live log once it has not been written to for this long
+pub const ROTATE_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+fn is_rotated_archive(path: &Path) -> bool {
+    path.extension().map(|ext| ext == "gz").unwrap_or(false)
+}
+
+// copy + truncate rotation: gzip the current contents to {name}.{unix_ts}.gz,
+// then truncate the live file in place so processes holding it open keep
+// logging to the same inode
+fn rotate_file(path: &Path) -> Result<PathBuf, std::io::Error> {
+    let ts = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let mut filename = path.file_name().unwrap_or_default().to_os_string();
+    filename.push(format!(".{}.gz", ts));
+    let archive_path = path.with_file_name(filename);
+
+    let mut src = fs::OpenOptions::new().read(true).write(true).open(path)?;
+    let mut encoder = GzEncoder::new(fs::File::create(&archive_path)?, Compression::default());
+    std::io::copy(&mut src, &mut encoder)?;
+    encoder.finish()?.sync_all()?;
+    src.set_len(0)?;
+    src.seek(std::io::SeekFrom::Start(0))?;
+    Ok(archive_path)
+}
+
+// one rotation pass over log_dir: rotate oversized/stale live logs, then
+// delete the oldest rotated archives until the total size fits under the cap.
+// Live logs are never deleted, so the directory can still exceed the cap if a
+// single live file outgrows it.
+pub fn rotate_logs(log_dir: &Path, max_total_bytes: u64) -> Result<Vec<PathBuf>, std::io::Error> {
+    let mut rotated = vec![];
+    if !log_dir.exists() {
+        return Ok(rotated);
+    }
+
+    for entry in fs::read_dir(log_dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let metadata = entry.metadata()?;
+        if !metadata.is_file() || is_rotated_archive(&path) || metadata.len() == 0 {
+            continue;
+        }
+        let age = metadata
+            .modified()
+            .ok()
+            .and_then(|mtime| mtime.elapsed().ok())
+            .unwrap_or_default();
+        if metadata.len() > ROTATE_SIZE_BYTES || age > ROTATE_AGE {
+            match rotate_file(&path) {
+                Ok(archive) => {
+                    info!("Rotated {} to {}", path.display(), archive.display());
+                    rotated.push(path);
+                }
+                Err(e) => warn!("Failed to rotate {} error={}", path.display(), e),
+            }
+        }
+    }
+
+    // oldest rotated archives go first when the directory is over the cap
+    let mut archives: Vec<(PathBuf, u64, SystemTime)> = vec![];
+    let mut total_bytes = 0;
+    for entry in fs::read_dir(log_dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if !metadata.is_file() {
+            continue;
+        }
+        total_bytes += metadata.len();
+        if is_rotated_archive(&entry.path()) {
+            archives.push((
+                entry.path(),
+                metadata.len(),
+                metadata.modified().unwrap_or(UNIX_EPOCH),
+            ));
+        }
+    }
+    archives.sort_by_key(|(_, _, mtime)| *mtime);
+    for (path, len, _) in archives {
+        if total_bytes <= max_total_bytes {
+            break;
+        }
+        fs::remove_file(&path)?;
+        warn!(
+            "Deleted rotated log {} to enforce max_log_size_bytes={}",
+            path.display(),
+            max_total_bytes
+        );
+        total_bytes -= len;
+    }
+    Ok(rotated)
+}
+
+async fn run_rotation_pass() -> Result<(), crate::error::ServiceError> {
+    let settings = printnanny_settings::printnanny::PrintNannySettings::new().await?;
+    let log_dir = settings.paths.effective_log_dir();
+    let max_total_bytes = settings.max_log_size_bytes;
+    tokio::task::spawn_blocking(move || {
+        rotate_logs(&log_dir, max_total_bytes).map_err(|error| {
+            crate::error::IoError::WriteIOError {
+                path: log_dir.display().to_string(),
+                error,
+            }
+        })
+    })
+    .await??;
+    Ok(())
+}
+
+// hourly rotation loop, spawned by the edge worker
+pub async fn run_log_rotation() {
+    let mut interval = tokio::time::interval(Duration::from_secs(60 * 60));
+    loop {
+        interval.tick().await;
+        if let Err(e) = run_rotation_pass().await {
+            warn!("Log rotation pass failed error={}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_oversized_log_is_rotated_and_truncated() {
+        let dir = tempfile::tempdir().unwrap();
+        let live = dir.path().join("pipeline.log");
+        fs::write(&live, vec![b'x'; (ROTATE_SIZE_BYTES + 1) as usize]).unwrap();
+
+        let rotated = rotate_logs(dir.path(), u64::MAX).unwrap();
+        assert_eq!(rotated, vec![live.clone()]);
+        assert_eq!(fs::metadata(&live).unwrap().len(), 0);
+        let archives: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .filter(|p| is_rotated_archive(p))
+            .collect();
+        assert_eq!(archives.len(), 1);
+    }
+
+    #[test_log::test]
+    fn test_small_fresh_log_is_left_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let live = dir.path().join("worker.log");
+        fs::write(&live, b"a few lines").unwrap();
+
+        let rotated = rotate_logs(dir.path(), u64::MAX).unwrap();
+        assert!(rotated.is_empty());
+        assert_eq!(fs::read(&live).unwrap(), b"a few lines");
+    }
+
+    #[test_log::test]
+    fn test_stale_log_is_rotated_by_age() {
+        let dir = tempfile::tempdir().unwrap();
+        let live = dir.path().join("worker.log");
+        fs::write(&live, b"stale contents").unwrap();
+        let stale = SystemTime::now() - ROTATE_AGE - Duration::from_secs(60);
+        fs::OpenOptions::new()
+            .write(true)
+            .open(&live)
+            .unwrap()
+            .set_modified(stale)
+            .unwrap();
+
+        let rotated = rotate_logs(dir.path(), u64::MAX).unwrap();
+        assert_eq!(rotated, vec![live]);
+    }
+
+    #[test_log::test]
+    fn test_total_size_cap_deletes_oldest_archives_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = dir.path().join("pipeline.log.100.gz");
+        let new = dir.path().join("pipeline.log.200.gz");
+        fs::write(&old, vec![b'a'; 1024]).unwrap();
+        fs::write(&new, vec![b'b'; 1024]).unwrap();
+        fs::OpenOptions::new()
+            .write(true)
+            .open(&old)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(3600))
+            .unwrap();
+
+        rotate_logs(dir.path(), 1024).unwrap();
+        assert!(!old.exists());
+        assert!(new.exists());
+    }
+
+    #[test_log::test]
+    fn test_live_logs_are_never_deleted_by_the_cap() {
+        let dir = tempfile::tempdir().unwrap();
+        let live = dir.path().join("worker.log");
+        fs::write(&live, vec![b'x'; 2048]).unwrap();
+
+        rotate_logs(dir.path(), 1024).unwrap();
+        assert!(live.exists());
+    }
+}
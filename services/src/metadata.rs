@@ -1,6 +1,8 @@
 use std::fs::read_to_string;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicI64, Ordering};
 
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use sysinfo::{DiskExt, System, SystemExt};
 
@@ -122,3 +124,57 @@ pub fn system_info() -> Result<SystemInfo, ServiceError> {
     };
     Ok(info)
 }
+
+// boot id changes on every boot, so (boot_id, seq) orders events even when the
+// wall clock jumps (see time_sync.rs)
+pub const BOOT_ID_PATH: &str = "/proc/sys/kernel/random/boot_id";
+
+static EVENT_SEQ: AtomicI64 = AtomicI64::new(0);
+
+// Shared metadata attached to every device-local published event, replacing
+// ad hoc SystemTime::now() formatting for reliable ordering downstream
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventMetadata {
+    // RFC3339 UTC timestamp
+    pub ts: String,
+    // monotonic sequence number, unique within a boot/process
+    pub seq: i64,
+    // populated from /proc/sys/kernel/random/boot_id
+    pub boot_id: String,
+}
+
+impl EventMetadata {
+    pub fn new() -> Self {
+        Self {
+            ts: Utc::now().to_rfc3339(),
+            seq: EVENT_SEQ.fetch_add(1, Ordering::SeqCst) + 1,
+            boot_id: read_to_string(BOOT_ID_PATH)
+                .map(|v| v.trim().to_string())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl Default for EventMetadata {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_event_metadata_seq_monotonic() {
+        let first = EventMetadata::new();
+        let second = EventMetadata::new();
+        assert!(second.seq > first.seq);
+    }
+
+    #[test_log::test]
+    fn test_event_metadata_ts_rfc3339() {
+        let metadata = EventMetadata::new();
+        assert!(chrono::DateTime::parse_from_rfc3339(&metadata.ts).is_ok());
+    }
+}
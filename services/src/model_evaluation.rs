@@ -0,0 +1,199 @@
+// Model A/B evaluation: while evaluation mode is enabled, the primary and
+// candidate tflite models run side by side on the same camera interpipe and
+// their windowed outputs are logged to the detections table tagged by model
+// id. The comparison report summarizes both logs over the same footage so
+// users can decide whether the candidate model is worth switching to.
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use printnanny_edge_db::detections::{Detection, NewDetection};
+use printnanny_settings::printnanny::PrintNannySettings;
+
+use crate::error::ServiceError;
+use crate::print_state::WindowedDetectionFrame;
+
+// file stem of the .tflite path, e.g. "model" or "candidate"
+pub fn model_id(model_file: &str) -> String {
+    Path::new(model_file)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| model_file.to_string())
+}
+
+// log one batch of windowed dataframe rows tagged by model id
+pub fn record_windowed_frames(
+    settings: &PrintNannySettings,
+    detection_model_id: &str,
+    frames: &[WindowedDetectionFrame],
+) -> Result<usize, ServiceError> {
+    let sqlite_connection = settings.paths.db().display().to_string();
+    let created_dt = Utc::now();
+    let rows: Vec<NewDetection> = frames
+        .iter()
+        .map(|frame| NewDetection {
+            created_dt,
+            model_id: detection_model_id,
+            rt: frame.rt_max,
+            nozzle_count: frame.nozzle_count.map(|v| v as i32),
+            nozzle_mean: frame.nozzle_mean,
+            print_count: frame.print_count.map(|v| v as i32),
+            print_mean: frame.print_mean,
+            raft_count: frame.raft_count.map(|v| v as i32),
+            raft_mean: frame.raft_mean,
+        })
+        .collect();
+    let inserted = Detection::create_many(&sqlite_connection, &rows)?;
+    Ok(inserted)
+}
+
+// per-model aggregate over the comparison window
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ModelEvaluationRow {
+    pub model_id: String,
+    // windowed dataframe rows logged for this model
+    pub frames: i64,
+    // windows with at least one print/raft detection
+    pub active_frames: i64,
+    pub detection_rate: f64,
+    pub mean_print_score: Option<f64>,
+    pub mean_nozzle_score: Option<f64>,
+    pub mean_raft_score: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ModelEvaluationReport {
+    pub generated_dt: DateTime<Utc>,
+    pub since: DateTime<Utc>,
+    pub models: Vec<ModelEvaluationRow>,
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    match values.is_empty() {
+        true => None,
+        false => Some(values.iter().sum::<f64>() / values.len() as f64),
+    }
+}
+
+fn summarize(detection_model_id: &str, rows: &[&Detection]) -> ModelEvaluationRow {
+    let frames = rows.len() as i64;
+    let active_frames = rows
+        .iter()
+        .filter(|row| row.print_count.unwrap_or(0) > 0 || row.raft_count.unwrap_or(0) > 0)
+        .count() as i64;
+    let detection_rate = match frames {
+        0 => 0.0,
+        _ => active_frames as f64 / frames as f64,
+    };
+    let print_scores: Vec<f64> = rows.iter().filter_map(|row| row.print_mean).collect();
+    let nozzle_scores: Vec<f64> = rows.iter().filter_map(|row| row.nozzle_mean).collect();
+    let raft_scores: Vec<f64> = rows.iter().filter_map(|row| row.raft_mean).collect();
+    ModelEvaluationRow {
+        model_id: detection_model_id.to_string(),
+        frames,
+        active_frames,
+        detection_rate,
+        mean_print_score: mean(&print_scores),
+        mean_nozzle_score: mean(&nozzle_scores),
+        mean_raft_score: mean(&raft_scores),
+    }
+}
+
+// compare all models logged in the last `hours`, primary model first
+pub fn comparison_report(
+    settings: &PrintNannySettings,
+    hours: i64,
+) -> Result<ModelEvaluationReport, ServiceError> {
+    let sqlite_connection = settings.paths.db().display().to_string();
+    let since = Utc::now() - chrono::Duration::hours(hours);
+    let rows = Detection::list_since(&sqlite_connection, since)?;
+
+    let mut model_ids: Vec<String> = rows.iter().map(|row| row.model_id.clone()).collect();
+    model_ids.sort();
+    model_ids.dedup();
+    // list the primary model first so the report reads as baseline vs candidate
+    let primary = model_id(&settings.video_stream.detection.model_file);
+    model_ids.sort_by_key(|id| *id != primary);
+
+    let models = model_ids
+        .iter()
+        .map(|id| {
+            let model_rows: Vec<&Detection> =
+                rows.iter().filter(|row| &row.model_id == id).collect();
+            summarize(id, &model_rows)
+        })
+        .collect();
+    Ok(ModelEvaluationReport {
+        generated_dt: Utc::now(),
+        since,
+        models,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use printnanny_edge_db::connection::run_migrations;
+    use printnanny_settings::paths::PrintNannyPaths;
+
+    async fn test_settings(dir: &tempfile::TempDir) -> PrintNannySettings {
+        let settings = PrintNannySettings {
+            paths: PrintNannyPaths {
+                state_dir: dir.path().join("state"),
+                ..PrintNannyPaths::default()
+            },
+            ..PrintNannySettings::default()
+        };
+        std::fs::create_dir_all(settings.paths.data()).unwrap();
+        run_migrations(&settings.paths.db().display().to_string()).unwrap();
+        settings
+    }
+
+    fn frame(print_count: u32, print_mean: f64) -> WindowedDetectionFrame {
+        WindowedDetectionFrame {
+            print_count: Some(print_count),
+            print_mean: Some(print_mean),
+            ..WindowedDetectionFrame::default()
+        }
+    }
+
+    #[test_log::test]
+    fn test_model_id_from_path() {
+        assert_eq!(
+            model_id("/usr/share/printnanny/model/model.tflite"),
+            "model"
+        );
+        assert_eq!(
+            model_id("/usr/share/printnanny/model/candidate.tflite"),
+            "candidate"
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_comparison_report_groups_by_model() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = test_settings(&dir).await;
+
+        record_windowed_frames(&settings, "model", &[frame(2, 0.8), frame(0, 0.0)]).unwrap();
+        record_windowed_frames(&settings, "candidate", &[frame(3, 0.9), frame(1, 0.7)]).unwrap();
+
+        let report = comparison_report(&settings, 1).unwrap();
+        assert_eq!(report.models.len(), 2);
+        // primary model listed first
+        assert_eq!(report.models[0].model_id, "model");
+        assert_eq!(report.models[0].frames, 2);
+        assert_eq!(report.models[0].active_frames, 1);
+        assert_eq!(report.models[1].model_id, "candidate");
+        assert_eq!(report.models[1].active_frames, 2);
+        assert_eq!(report.models[1].detection_rate, 1.0);
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_comparison_report_empty_window() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = test_settings(&dir).await;
+        let report = comparison_report(&settings, 1).unwrap();
+        assert!(report.models.is_empty());
+    }
+}
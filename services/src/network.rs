@@ -0,0 +1,94 @@
+// Wake-on-LAN for other machines on the farm LAN: build the standard magic
+// packet (6x 0xFF followed by 16 repetitions of the target MAC) and broadcast
+// it over UDP. Stored MAC profiles live in
+// printnanny_settings::network::NetworkSettings.
+use std::net::UdpSocket;
+
+use log::info;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum WolError {
+    #[error("Invalid MAC address {mac_address:?}: {detail}")]
+    InvalidMacAddress { mac_address: String, detail: String },
+    #[error("Failed to send magic packet to {addr}: {error}")]
+    SendError { addr: String, error: std::io::Error },
+}
+
+// accepts colon- or hyphen-separated hex, e.g. "aa:bb:cc:dd:ee:ff"
+pub fn parse_mac(mac_address: &str) -> Result<[u8; 6], WolError> {
+    let octets: Vec<&str> = mac_address.split([':', '-']).collect();
+    if octets.len() != 6 {
+        return Err(WolError::InvalidMacAddress {
+            mac_address: mac_address.to_string(),
+            detail: format!("expected 6 octets, found {}", octets.len()),
+        });
+    }
+    let mut mac = [0u8; 6];
+    for (i, octet) in octets.iter().enumerate() {
+        mac[i] = u8::from_str_radix(octet, 16).map_err(|e| WolError::InvalidMacAddress {
+            mac_address: mac_address.to_string(),
+            detail: format!("octet {:?}: {}", octet, e),
+        })?;
+    }
+    Ok(mac)
+}
+
+pub fn magic_packet(mac: &[u8; 6]) -> [u8; 102] {
+    let mut packet = [0xffu8; 102];
+    for repetition in 0..16 {
+        packet[6 + repetition * 6..12 + repetition * 6].copy_from_slice(mac);
+    }
+    packet
+}
+
+pub fn send_wol(mac_address: &str, broadcast_addr: &str) -> Result<(), WolError> {
+    let mac = parse_mac(mac_address)?;
+    let packet = magic_packet(&mac);
+    let socket = UdpSocket::bind("0.0.0.0:0").map_err(|error| WolError::SendError {
+        addr: broadcast_addr.to_string(),
+        error,
+    })?;
+    socket
+        .set_broadcast(true)
+        .map_err(|error| WolError::SendError {
+            addr: broadcast_addr.to_string(),
+            error,
+        })?;
+    socket
+        .send_to(&packet, broadcast_addr)
+        .map_err(|error| WolError::SendError {
+            addr: broadcast_addr.to_string(),
+            error,
+        })?;
+    info!(
+        "Sent Wake-on-LAN magic packet mac={} addr={}",
+        mac_address, broadcast_addr
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_parse_mac_separators() {
+        let expected = [0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff];
+        assert_eq!(parse_mac("aa:bb:cc:dd:ee:ff").unwrap(), expected);
+        assert_eq!(parse_mac("AA-BB-CC-DD-EE-FF").unwrap(), expected);
+        assert!(parse_mac("aa:bb:cc:dd:ee").is_err());
+        assert!(parse_mac("aa:bb:cc:dd:ee:zz").is_err());
+    }
+
+    #[test_log::test]
+    fn test_magic_packet_layout() {
+        let mac = [0x01, 0x02, 0x03, 0x04, 0x05, 0x06];
+        let packet = magic_packet(&mac);
+        assert_eq!(packet.len(), 102);
+        assert_eq!(&packet[..6], &[0xff; 6]);
+        for repetition in 0..16 {
+            assert_eq!(&packet[6 + repetition * 6..12 + repetition * 6], &mac);
+        }
+    }
+}
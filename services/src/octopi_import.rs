@@ -0,0 +1,239 @@
+// Import wizard for users migrating from an existing OctoPi/OctoPrint setup:
+// `printnanny import octopi --host <ip>` pulls the effective settings
+// (/api/settings mirrors config.yaml), translates the sections that make sense
+// on PrintNanny OS into the version-controlled octoprint.yaml, and reports
+// everything it skipped so nothing silently disappears during the migration.
+// The plugin list is compared against the local install; missing plugins are
+// reported rather than installed, so the user stays in control of what gets
+// pulled onto the fresh instance.
+use log::info;
+use reqwest::header;
+use serde::{Deserialize, Serialize};
+
+use printnanny_settings::error::PrintNannySettingsError;
+use printnanny_settings::printnanny::PrintNannySettings;
+use printnanny_settings::vcs::VersionControlledSettings;
+
+use crate::error::ServiceError;
+
+// config.yaml sections copied into the settings repo; everything else is
+// host-specific (paths, server binding, webcam stack, system commands) and
+// lands in the skipped list with a reason
+const IMPORTED_SECTIONS: &[&str] = &[
+    "api",
+    "appearance",
+    "feature",
+    "gcodeAnalysis",
+    "plugins",
+    "printer",
+    "printerProfiles",
+    "serial",
+    "temperature",
+    "terminalFilters",
+];
+
+fn skip_reason(section: &str) -> String {
+    match section {
+        "folder" => "folder paths differ on PrintNanny OS".to_string(),
+        "server" => "server binding is managed by PrintNanny OS".to_string(),
+        "webcam" => "PrintNanny OS provides its own camera stack".to_string(),
+        "system" => "system actions/commands are host-specific".to_string(),
+        "accessControl" => "local users are re-created on the new instance".to_string(),
+        "scripts" => "gcode scripts are stored in the folder tree, not config.yaml".to_string(),
+        other => format!("{} is not imported by the wizard", other),
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SkippedOption {
+    pub section: String,
+    pub reason: String,
+}
+
+// one plugin installed on the source instance
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RemotePlugin {
+    pub key: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub version: Option<String>,
+    // bundled plugins ship with OctoPrint and need no install
+    #[serde(default)]
+    pub bundled: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct RemotePluginList {
+    #[serde(default)]
+    plugins: Vec<RemotePlugin>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OctoPiImportReport {
+    pub host: String,
+    // config.yaml sections written into the settings repo
+    pub imported_sections: Vec<String>,
+    // sections present on the source instance that were not imported
+    pub skipped: Vec<SkippedOption>,
+    // non-bundled plugins on the source instance, to be installed via
+    // pi.{pi_id}.octoprint.plugins.install
+    pub plugins_to_install: Vec<RemotePlugin>,
+    // settings repo file the imported sections were written to
+    pub settings_file: String,
+}
+
+fn import_api_client(api_key: &str) -> reqwest::Result<reqwest::Client> {
+    let mut headers = header::HeaderMap::new();
+    let mut value = header::HeaderValue::from_str(api_key)
+        .unwrap_or_else(|_| header::HeaderValue::from_static(""));
+    value.set_sensitive(true);
+    headers.insert("X-Api-Key", value);
+    reqwest::Client::builder().default_headers(headers).build()
+}
+
+// split the pulled settings document into imported sections and skipped
+// sections, merging the imported ones over the existing octoprint.yaml doc
+fn translate_sections(
+    remote: &serde_yaml::Mapping,
+    existing: &mut serde_yaml::Mapping,
+) -> (Vec<String>, Vec<SkippedOption>) {
+    let mut imported = Vec::new();
+    let mut skipped = Vec::new();
+    for (key, value) in remote {
+        let section = match key.as_str() {
+            Some(section) => section.to_string(),
+            None => continue,
+        };
+        if IMPORTED_SECTIONS.contains(&section.as_str()) {
+            existing.insert(key.clone(), value.clone());
+            imported.push(section);
+        } else {
+            skipped.push(SkippedOption {
+                reason: skip_reason(&section),
+                section,
+            });
+        }
+    }
+    (imported, skipped)
+}
+
+pub async fn import_octopi(
+    host: &str,
+    port: u16,
+    api_key: &str,
+) -> Result<OctoPiImportReport, ServiceError> {
+    let settings = PrintNannySettings::new().await?;
+    let base_url = format!("http://{}:{}", host, port);
+    let api_client = import_api_client(api_key)?;
+
+    // /api/settings mirrors the effective config.yaml, including api keys
+    let remote: serde_yaml::Value = api_client
+        .get(format!("{}/api/settings", base_url))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    let remote = remote
+        .as_mapping()
+        .cloned()
+        .ok_or_else(|| ServiceError::OctoPiImportError {
+            detail: format!(
+                "{}/api/settings did not return a settings document",
+                base_url
+            ),
+        })?;
+
+    let remote_plugins: RemotePluginList = api_client
+        .get(format!("{}/plugin/pluginmanager/plugins", base_url))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    // merge imported sections over the version-controlled octoprint.yaml
+    let octoprint_settings = settings.to_octoprint_settings();
+    let existing = octoprint_settings.read_settings().await?;
+    let mut existing: serde_yaml::Mapping = match serde_yaml::from_str(&existing) {
+        Ok(doc) => doc,
+        // a fresh install ships an empty octoprint.yaml
+        Err(_) => serde_yaml::Mapping::new(),
+    };
+    let (imported_sections, skipped) = translate_sections(&remote, &mut existing);
+    let content = serde_yaml::to_string(&serde_yaml::Value::Mapping(existing))
+        .map_err(PrintNannySettingsError::from)?;
+    octoprint_settings
+        .save_and_commit(
+            &content,
+            Some(format!("Imported OctoPrint settings from {}", host)),
+        )
+        .await?;
+    info!(
+        "Imported {} OctoPrint settings sections from {}",
+        imported_sections.len(),
+        host
+    );
+
+    let plugins_to_install = remote_plugins
+        .plugins
+        .into_iter()
+        .filter(|plugin| !plugin.bundled)
+        .collect();
+
+    Ok(OctoPiImportReport {
+        host: host.to_string(),
+        imported_sections,
+        skipped,
+        plugins_to_install,
+        settings_file: octoprint_settings.settings_file.display().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_translate_splits_supported_and_unsupported_sections() {
+        let remote: serde_yaml::Mapping = serde_yaml::from_str(
+            r#"
+api:
+  key: abc123
+serial:
+  port: /dev/ttyUSB0
+webcam:
+  stream: /webcam/?action=stream
+folder:
+  uploads: /home/pi/.octoprint/uploads
+"#,
+        )
+        .unwrap();
+        let mut existing = serde_yaml::Mapping::new();
+        let (imported, skipped) = translate_sections(&remote, &mut existing);
+        assert_eq!(imported, vec!["api".to_string(), "serial".to_string()]);
+        let skipped_sections: Vec<&str> = skipped.iter().map(|s| s.section.as_str()).collect();
+        assert_eq!(skipped_sections, vec!["webcam", "folder"]);
+        assert!(existing.contains_key("api"));
+        assert!(!existing.contains_key("webcam"));
+    }
+
+    #[test_log::test]
+    fn test_translate_merges_over_existing_doc() {
+        let remote: serde_yaml::Mapping =
+            serde_yaml::from_str("serial:\n  port: /dev/ttyUSB0\n").unwrap();
+        let mut existing: serde_yaml::Mapping = serde_yaml::from_str(
+            "serial:\n  port: /dev/ttyACM0\nplugins:\n  octoprint_nanny:\n    enabled: true\n",
+        )
+        .unwrap();
+        translate_sections(&remote, &mut existing);
+        let serial = existing.get("serial").unwrap();
+        assert_eq!(
+            serial.get("port").unwrap().as_str().unwrap(),
+            "/dev/ttyUSB0"
+        );
+        // sections absent from the source instance are left alone
+        assert!(existing.contains_key("plugins"));
+    }
+}
@@ -1,9 +1,22 @@
-use log::warn;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use log::{info, warn};
 
 use reqwest::header;
+use serde::{Deserialize, Serialize};
 // use reqwest::Url;
 
+use printnanny_edge_db::jobs::{Job, JobStatus};
 use printnanny_edge_db::octoprint::OctoPrintServer;
+use printnanny_settings::printnanny::PrintNannySettings;
+
+use crate::error::ServiceError;
+
+// OctoPrint's backup plugin creates backups asynchronously; poll the list
+// endpoint until backup_in_progress clears
+const BACKUP_POLL_INTERVAL: Duration = Duration::from_secs(5);
+const BACKUP_POLL_ATTEMPTS: usize = 60;
 
 fn octoprint_api_headers(octoprint_server: &OctoPrintServer) -> header::HeaderMap {
     let mut headers = header::HeaderMap::new();
@@ -30,6 +43,184 @@ pub fn octoprint_api_client(
     reqwest::Client::builder().default_headers(headers).build()
 }
 
+// pause the active print job through OctoPrint's REST api (a no-op server-side
+// when nothing is printing)
+pub async fn octoprint_pause_print() -> Result<(), ServiceError> {
+    let settings = PrintNannySettings::new().await?;
+    let sqlite_connection = settings.paths.db().display().to_string();
+    let octoprint_server = OctoPrintServer::get(&sqlite_connection)?;
+    let api_client = octoprint_api_client(&octoprint_server)?;
+    let url = format!(
+        "{}/api/job",
+        octoprint_server.octoprint_url.trim_end_matches('/')
+    );
+    let res = api_client
+        .post(&url)
+        .json(&serde_json::json!({ "command": "pause", "action": "pause" }))
+        .send()
+        .await?;
+    res.error_for_status()?;
+    info!("Paused OctoPrint print job via {}", url);
+    Ok(())
+}
+
+// one entry in the backup plugin's list response
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OctoPrintBackupEntry {
+    pub name: String,
+    #[serde(default)]
+    pub size: Option<i64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OctoPrintBackupList {
+    #[serde(default)]
+    pub backups: Vec<OctoPrintBackupEntry>,
+    #[serde(default)]
+    pub backup_in_progress: bool,
+}
+
+async fn octoprint_backup_list(
+    api_client: &reqwest::Client,
+    octoprint_url: &str,
+) -> Result<OctoPrintBackupList, ServiceError> {
+    let url = format!("{}/plugin/backup/backup", octoprint_url);
+    let res = api_client.get(&url).send().await?.error_for_status()?;
+    Ok(res.json::<OctoPrintBackupList>().await?)
+}
+
+// trigger OctoPrint's built-in backup plugin, wait for the backup to finish,
+// and download the resulting zip into paths.recovery() alongside the settings
+// backups. Progress is tracked as a persistent job so a migrating user can
+// watch it from the dashboard.
+pub async fn octoprint_create_backup(exclude: &[String]) -> Result<PathBuf, ServiceError> {
+    let settings = PrintNannySettings::new().await?;
+    let sqlite_connection = settings.paths.db().display().to_string();
+    let octoprint_server = OctoPrintServer::get(&sqlite_connection)?;
+    let api_client = octoprint_api_client(&octoprint_server)?;
+    let octoprint_url = octoprint_server.octoprint_url.trim_end_matches('/');
+
+    let job = Job::create(&sqlite_connection, "octoprint_backup", None)?;
+
+    // names of backups that already exist, so the new one can be identified
+    let existing: Vec<String> = octoprint_backup_list(&api_client, octoprint_url)
+        .await?
+        .backups
+        .into_iter()
+        .map(|b| b.name)
+        .collect();
+
+    let url = format!("{}/plugin/backup/backup", octoprint_url);
+    api_client
+        .post(&url)
+        .json(&serde_json::json!({ "exclude": exclude }))
+        .send()
+        .await?
+        .error_for_status()?;
+    info!("Triggered OctoPrint backup via {}", url);
+    Job::update_progress(&sqlite_connection, &job.id, 25, Some("backup in progress"))?;
+
+    // the plugin creates the backup asynchronously; poll until it finishes
+    let mut backup_name: Option<String> = None;
+    for _ in 0..BACKUP_POLL_ATTEMPTS {
+        tokio::time::sleep(BACKUP_POLL_INTERVAL).await;
+        let list = octoprint_backup_list(&api_client, octoprint_url).await?;
+        if list.backup_in_progress {
+            continue;
+        }
+        backup_name = list
+            .backups
+            .into_iter()
+            .map(|b| b.name)
+            .find(|name| !existing.contains(name));
+        break;
+    }
+    let backup_name = match backup_name {
+        Some(name) => name,
+        None => {
+            let detail = "backup did not finish or produced no new archive".to_string();
+            Job::finish(&sqlite_connection, &job.id, JobStatus::Failed, &detail)?;
+            return Err(ServiceError::OctoPrintBackupError { detail });
+        }
+    };
+    Job::update_progress(
+        &sqlite_connection,
+        &job.id,
+        50,
+        Some(&format!("downloading {}", backup_name)),
+    )?;
+
+    // download the zip into the recovery dir alongside the settings backups
+    let recovery = settings.paths.recovery();
+    tokio::fs::create_dir_all(&recovery).await.map_err(|e| {
+        crate::error::IoError::WriteIOError {
+            path: recovery.display().to_string(),
+            error: e,
+        }
+    })?;
+    let output = recovery.join(format!("octoprint-{}", backup_name));
+    let url = format!("{}/downloads/backup/{}", octoprint_url, backup_name);
+    let content = api_client
+        .get(&url)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+    tokio::fs::write(&output, &content)
+        .await
+        .map_err(|e| crate::error::IoError::WriteIOError {
+            path: output.display().to_string(),
+            error: e,
+        })?;
+    info!("Downloaded OctoPrint backup to {}", output.display());
+
+    Job::finish(
+        &sqlite_connection,
+        &job.id,
+        JobStatus::Success,
+        &output.display().to_string(),
+    )?;
+    Ok(output)
+}
+
+// upload a previously downloaded backup zip to the backup plugin's restore
+// endpoint; OctoPrint restarts itself after a successful restore
+pub async fn octoprint_restore_backup(path: &Path) -> Result<(), ServiceError> {
+    let settings = PrintNannySettings::new().await?;
+    let sqlite_connection = settings.paths.db().display().to_string();
+    let octoprint_server = OctoPrintServer::get(&sqlite_connection)?;
+    let api_client = octoprint_api_client(&octoprint_server)?;
+    let octoprint_url = octoprint_server.octoprint_url.trim_end_matches('/');
+
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "backup.zip".to_string());
+    let content = tokio::fs::read(path)
+        .await
+        .map_err(|e| crate::error::IoError::ReadIOError {
+            path: path.display().to_string(),
+            error: e,
+        })?;
+    let part = reqwest::multipart::Part::bytes(content).file_name(file_name);
+    let form = reqwest::multipart::Form::new().part("file", part);
+
+    let url = format!("{}/plugin/backup/restore", octoprint_url);
+    api_client
+        .post(&url)
+        .multipart(form)
+        .send()
+        .await?
+        .error_for_status()?;
+    info!(
+        "Started OctoPrint restore from {} via {}",
+        path.display(),
+        url
+    );
+    Ok(())
+}
+
 // pub async fn octoprint_get_current_job_filename() -> Result<Option<String>, ServiceError> {
 //     let octoprint_server = OctoPrintServer::get()?;
 //     let api_client = octoprint_api_client(&octoprint_server)?;
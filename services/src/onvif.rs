@@ -0,0 +1,325 @@
+// Minimal ONVIF Profile S compatibility layer so commercial NVRs can
+// discover and record the printer camera like any other IP camera. Covers
+// the subset NVRs actually exercise: WS-Discovery over multicast UDP 3702,
+// plus a SOAP device/media service answering GetSystemDateAndTime,
+// GetDeviceInformation, GetCapabilities, GetProfiles and GetStreamUri with
+// the RTSP URI. Host addresses are filled in per-request from the socket the
+// probe/request arrived on, so replies are reachable from the NVR's subnet.
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use log::{info, warn};
+
+use printnanny_settings::paths::PrintNannyPaths;
+
+use crate::error::ServiceError;
+
+pub const ONVIF_DEVICE_UUID_FILENAME: &str = "onvif-device-uuid";
+pub const WS_DISCOVERY_PORT: u16 = 3702;
+pub const WS_DISCOVERY_MULTICAST_ADDR: Ipv4Addr = Ipv4Addr::new(239, 255, 255, 250);
+pub const DEFAULT_ONVIF_HTTP_PORT: u16 = 8580;
+pub const DEFAULT_RTSP_PORT: u16 = 8554;
+
+#[derive(Clone, Debug)]
+pub struct OnvifConfig {
+    // stable WS-Discovery endpoint reference, persisted under paths.creds()
+    pub device_uuid: String,
+    pub http_port: u16,
+    pub rtsp_port: u16,
+    pub rtsp_path: String,
+    pub model: String,
+    pub firmware_version: String,
+}
+
+impl OnvifConfig {
+    // load the persisted device uuid, generating one on first use
+    pub fn new(paths: &PrintNannyPaths) -> Result<Self, ServiceError> {
+        let uuid_file = paths.creds().join(ONVIF_DEVICE_UUID_FILENAME);
+        let device_uuid = match std::fs::read_to_string(&uuid_file) {
+            Ok(device_uuid) => device_uuid.trim().to_string(),
+            Err(_) => {
+                let device_uuid = uuid::Uuid::new_v4().to_string();
+                printnanny_settings::atomic_write::atomic_write_sync(
+                    &uuid_file,
+                    device_uuid.as_bytes(),
+                )?;
+                info!("Generated ONVIF device uuid {}", uuid_file.display());
+                device_uuid
+            }
+        };
+        Ok(Self {
+            device_uuid,
+            http_port: DEFAULT_ONVIF_HTTP_PORT,
+            rtsp_port: DEFAULT_RTSP_PORT,
+            rtsp_path: "/printnanny".to_string(),
+            model: "PrintNanny Vision".to_string(),
+            firmware_version: env!("CARGO_PKG_VERSION").to_string(),
+        })
+    }
+
+    pub fn device_service_xaddr(&self, host: &str) -> String {
+        format!("http://{}:{}/onvif/device_service", host, self.http_port)
+    }
+
+    pub fn rtsp_uri(&self, host: &str) -> String {
+        format!("rtsp://{}:{}{}", host, self.rtsp_port, self.rtsp_path)
+    }
+}
+
+// extract the text content of the first element whose name ends with tag,
+// tolerating any namespace prefix (<wsa:MessageID>, <a:MessageID>, ...)
+pub fn extract_tag(body: &str, tag: &str) -> Option<String> {
+    let open = format!("{}>", tag);
+    let start = body.find(&open)? + open.len();
+    let end = body[start..].find('<')? + start;
+    Some(body[start..end].trim().to_string())
+}
+
+fn soap_envelope(header: &str, body: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?><s:Envelope xmlns:s="http://www.w3.org/2003/05/soap-envelope" xmlns:a="http://www.w3.org/2005/08/addressing" xmlns:d="http://schemas.xmlsoap.org/ws/2005/04/discovery" xmlns:dn="http://www.onvif.org/ver10/network/wsdl" xmlns:tds="http://www.onvif.org/ver10/device/wsdl" xmlns:trt="http://www.onvif.org/ver10/media/wsdl" xmlns:tt="http://www.onvif.org/ver10/schema"><s:Header>{}</s:Header><s:Body>{}</s:Body></s:Envelope>"#,
+        header, body
+    )
+}
+
+// WS-Discovery ProbeMatch naming this device as a NetworkVideoTransmitter
+pub fn probe_match(config: &OnvifConfig, host: &str, relates_to: &str) -> String {
+    let header = format!(
+        r#"<a:Action>http://schemas.xmlsoap.org/ws/2005/04/discovery/ProbeMatches</a:Action><a:MessageID>urn:uuid:{}</a:MessageID><a:RelatesTo>{}</a:RelatesTo><a:To>http://schemas.xmlsoap.org/ws/2004/08/addressing/role/anonymous</a:To>"#,
+        uuid::Uuid::new_v4(),
+        relates_to
+    );
+    let body = format!(
+        r#"<d:ProbeMatches><d:ProbeMatch><a:EndpointReference><a:Address>urn:uuid:{}</a:Address></a:EndpointReference><d:Types>dn:NetworkVideoTransmitter tds:Device</d:Types><d:Scopes>onvif://www.onvif.org/Profile/Streaming onvif://www.onvif.org/name/PrintNanny onvif://www.onvif.org/hardware/{}</d:Scopes><d:XAddrs>{}</d:XAddrs><d:MetadataVersion>1</d:MetadataVersion></d:ProbeMatch></d:ProbeMatches>"#,
+        config.device_uuid,
+        config.model.replace(' ', "_"),
+        config.device_service_xaddr(host)
+    );
+    soap_envelope(&header, &body)
+}
+
+// dispatch a SOAP request body to the device/media service handlers.
+// Returns None for operations outside the Profile S subset we implement.
+pub fn soap_response(config: &OnvifConfig, host: &str, request_body: &str) -> Option<String> {
+    if request_body.contains("GetSystemDateAndTime") {
+        let now = chrono::Utc::now();
+        let body = format!(
+            r#"<tds:GetSystemDateAndTimeResponse><tds:SystemDateAndTime><tt:DateTimeType>NTP</tt:DateTimeType><tt:DaylightSavings>false</tt:DaylightSavings><tt:UTCDateTime><tt:Time><tt:Hour>{}</tt:Hour><tt:Minute>{}</tt:Minute><tt:Second>{}</tt:Second></tt:Time><tt:Date><tt:Year>{}</tt:Year><tt:Month>{}</tt:Month><tt:Day>{}</tt:Day></tt:Date></tt:UTCDateTime></tds:SystemDateAndTime></tds:GetSystemDateAndTimeResponse>"#,
+            chrono::Timelike::hour(&now),
+            chrono::Timelike::minute(&now),
+            chrono::Timelike::second(&now),
+            chrono::Datelike::year(&now),
+            chrono::Datelike::month(&now),
+            chrono::Datelike::day(&now),
+        );
+        return Some(soap_envelope("", &body));
+    }
+    if request_body.contains("GetDeviceInformation") {
+        let body = format!(
+            r#"<tds:GetDeviceInformationResponse><tds:Manufacturer>PrintNanny</tds:Manufacturer><tds:Model>{}</tds:Model><tds:FirmwareVersion>{}</tds:FirmwareVersion><tds:SerialNumber>{}</tds:SerialNumber><tds:HardwareId>{}</tds:HardwareId></tds:GetDeviceInformationResponse>"#,
+            config.model, config.firmware_version, config.device_uuid, config.device_uuid
+        );
+        return Some(soap_envelope("", &body));
+    }
+    if request_body.contains("GetCapabilities") {
+        let xaddr = config.device_service_xaddr(host);
+        let body = format!(
+            r#"<tds:GetCapabilitiesResponse><tds:Capabilities><tt:Device><tt:XAddr>{}</tt:XAddr></tt:Device><tt:Media><tt:XAddr>{}</tt:XAddr><tt:StreamingCapabilities><tt:RTPMulticast>false</tt:RTPMulticast><tt:RTP_TCP>true</tt:RTP_TCP><tt:RTP_RTSP_TCP>true</tt:RTP_RTSP_TCP></tt:StreamingCapabilities></tt:Media></tds:Capabilities></tds:GetCapabilitiesResponse>"#,
+            xaddr, xaddr
+        );
+        return Some(soap_envelope("", &body));
+    }
+    if request_body.contains("GetProfiles") {
+        let body = r#"<trt:GetProfilesResponse><trt:Profiles token="printnanny-main" fixed="true"><tt:Name>printnanny-main</tt:Name><tt:VideoEncoderConfiguration token="printnanny-h264"><tt:Name>printnanny-h264</tt:Name><tt:UseCount>1</tt:UseCount><tt:Encoding>H264</tt:Encoding></tt:VideoEncoderConfiguration></trt:Profiles></trt:GetProfilesResponse>"#;
+        return Some(soap_envelope("", body));
+    }
+    if request_body.contains("GetStreamUri") {
+        let body = format!(
+            r#"<trt:GetStreamUriResponse><trt:MediaUri><tt:Uri>{}</tt:Uri><tt:InvalidAfterConnect>false</tt:InvalidAfterConnect><tt:InvalidAfterReboot>false</tt:InvalidAfterReboot><tt:Timeout>PT0S</tt:Timeout></trt:MediaUri></trt:GetStreamUriResponse>"#,
+            config.rtsp_uri(host)
+        );
+        return Some(soap_envelope("", &body));
+    }
+    None
+}
+
+// the local address a reply to peer would be sent from; used to build
+// XAddrs/URIs that are reachable from the NVR's subnet
+fn local_ip_for(peer: &SocketAddr) -> Option<IpAddr> {
+    let socket = std::net::UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect(peer).ok()?;
+    Some(socket.local_addr().ok()?.ip())
+}
+
+async fn run_ws_discovery(config: OnvifConfig) -> Result<(), std::io::Error> {
+    let socket =
+        tokio::net::UdpSocket::bind(SocketAddr::from(([0, 0, 0, 0], WS_DISCOVERY_PORT))).await?;
+    socket.join_multicast_v4(WS_DISCOVERY_MULTICAST_ADDR, Ipv4Addr::UNSPECIFIED)?;
+    info!(
+        "ONVIF WS-Discovery responder listening on udp {}:{}",
+        WS_DISCOVERY_MULTICAST_ADDR, WS_DISCOVERY_PORT
+    );
+    let mut buf = vec![0u8; 8192];
+    loop {
+        let (n, peer) = socket.recv_from(&mut buf).await?;
+        let probe = String::from_utf8_lossy(&buf[..n]);
+        if !probe.contains("Probe") {
+            continue;
+        }
+        // answer untyped probes and probes for NetworkVideoTransmitter/Device
+        if probe.contains("Types")
+            && !probe.contains("NetworkVideoTransmitter")
+            && !probe.contains("Device")
+        {
+            continue;
+        }
+        let relates_to = extract_tag(&probe, "MessageID").unwrap_or_default();
+        let host = match local_ip_for(&peer) {
+            Some(ip) => ip.to_string(),
+            None => continue,
+        };
+        let reply = probe_match(&config, &host, &relates_to);
+        if let Err(e) = socket.send_to(reply.as_bytes(), peer).await {
+            warn!("Failed to send ProbeMatch to {}: {}", peer, e);
+        }
+    }
+}
+
+// minimal HTTP/1.1 loop for the SOAP endpoints; ONVIF clients POST one
+// request per connection so no keep-alive handling is needed
+async fn run_soap_service(config: OnvifConfig) -> Result<(), std::io::Error> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener =
+        tokio::net::TcpListener::bind(SocketAddr::from(([0, 0, 0, 0], config.http_port))).await?;
+    info!(
+        "ONVIF device/media service listening on tcp port {}",
+        config.http_port
+    );
+    loop {
+        let (mut stream, peer) = listener.accept().await?;
+        let config = config.clone();
+        tokio::spawn(async move {
+            let mut buf = Vec::with_capacity(8192);
+            let mut chunk = [0u8; 4096];
+            let request = loop {
+                match stream.read(&mut chunk).await {
+                    Ok(0) => return,
+                    Ok(n) => buf.extend_from_slice(&chunk[..n]),
+                    Err(_) => return,
+                }
+                let request = String::from_utf8_lossy(&buf).to_string();
+                // SOAP bodies end with the envelope close tag; headers alone
+                // (GET probes from NVR setup wizards) end with a blank line
+                if request.contains("</s:Envelope>")
+                    || request.contains("</soap:Envelope>")
+                    || request.contains("</SOAP-ENV:Envelope>")
+                    || (request.contains("\r\n\r\n") && !request.contains("Content-Length"))
+                {
+                    break request;
+                }
+            };
+            let host = match stream.local_addr() {
+                Ok(addr) => addr.ip().to_string(),
+                Err(_) => return,
+            };
+            let response = match soap_response(&config, &host, &request) {
+                Some(body) => format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/soap+xml; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                ),
+                None => {
+                    warn!("Unsupported ONVIF request from {}", peer);
+                    "HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                        .to_string()
+                }
+            };
+            if let Err(e) = stream.write_all(response.as_bytes()).await {
+                warn!("Failed to write ONVIF response to {}: {}", peer, e);
+            }
+        });
+    }
+}
+
+pub async fn run_onvif_service() {
+    let paths = match printnanny_settings::printnanny::PrintNannySettings::new().await {
+        Ok(settings) => settings.paths,
+        Err(e) => {
+            warn!("Failed to load PrintNannySettings, disabling ONVIF: {}", e);
+            return;
+        }
+    };
+    let config = match OnvifConfig::new(&paths) {
+        Ok(config) => config,
+        Err(e) => {
+            warn!("Failed to initialize ONVIF config, disabling ONVIF: {}", e);
+            return;
+        }
+    };
+    let discovery = tokio::spawn(run_ws_discovery(config.clone()));
+    let soap = tokio::spawn(run_soap_service(config));
+    if let Ok(Err(e)) = discovery.await {
+        warn!("ONVIF WS-Discovery responder exited: {}", e);
+    }
+    if let Ok(Err(e)) = soap.await {
+        warn!("ONVIF device/media service exited: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> OnvifConfig {
+        OnvifConfig {
+            device_uuid: "11111111-2222-3333-4444-555555555555".to_string(),
+            http_port: DEFAULT_ONVIF_HTTP_PORT,
+            rtsp_port: DEFAULT_RTSP_PORT,
+            rtsp_path: "/printnanny".to_string(),
+            model: "PrintNanny Vision".to_string(),
+            firmware_version: "0.0.0".to_string(),
+        }
+    }
+
+    #[test_log::test]
+    fn test_probe_match_names_device_and_xaddr() {
+        let config = test_config();
+        let reply = probe_match(&config, "192.168.1.5", "urn:uuid:probe-1");
+        assert!(reply.contains("urn:uuid:11111111-2222-3333-4444-555555555555"));
+        assert!(reply.contains("<a:RelatesTo>urn:uuid:probe-1</a:RelatesTo>"));
+        assert!(reply.contains("http://192.168.1.5:8580/onvif/device_service"));
+        assert!(reply.contains("dn:NetworkVideoTransmitter"));
+    }
+
+    #[test_log::test]
+    fn test_get_stream_uri_returns_rtsp_uri() {
+        let config = test_config();
+        let response = soap_response(&config, "192.168.1.5", "<trt:GetStreamUri/>").unwrap();
+        assert!(response.contains("<tt:Uri>rtsp://192.168.1.5:8554/printnanny</tt:Uri>"));
+    }
+
+    #[test_log::test]
+    fn test_get_device_information_identifies_printnanny() {
+        let config = test_config();
+        let response =
+            soap_response(&config, "192.168.1.5", "<tds:GetDeviceInformation/>").unwrap();
+        assert!(response.contains("<tds:Manufacturer>PrintNanny</tds:Manufacturer>"));
+        assert!(response.contains("<tds:FirmwareVersion>0.0.0</tds:FirmwareVersion>"));
+    }
+
+    #[test_log::test]
+    fn test_unsupported_operation_returns_none() {
+        let config = test_config();
+        assert_eq!(
+            soap_response(&config, "192.168.1.5", "<tds:SetSystemFactoryDefault/>"),
+            None
+        );
+    }
+
+    #[test_log::test]
+    fn test_extract_tag_tolerates_namespace_prefixes() {
+        let body = "<wsa:MessageID>urn:uuid:abc</wsa:MessageID>";
+        assert_eq!(extract_tag(body, "MessageID").unwrap(), "urn:uuid:abc");
+        assert_eq!(extract_tag("<d:Probe/>", "MessageID"), None);
+    }
+}
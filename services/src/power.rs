@@ -0,0 +1,234 @@
+// UPS / power-loss detection: poll either a UPS HAT's "on battery" GPIO line
+// or a NUT (Network UPS Tools) server via upsc. A marker file is written when
+// power loss is detected so the next boot can report "recovered from power
+// loss" when the device shut down (cleanly or otherwise) on battery.
+use chrono::{DateTime, Utc};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+use printnanny_settings::paths::PrintNannyPaths;
+use printnanny_settings::ups::{UpsSettings, UpsSource};
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct PowerStatus {
+    pub on_battery: bool,
+    // battery charge percent; None for GPIO HATs without charge telemetry
+    pub charge_percent: Option<f64>,
+}
+
+// persisted as power_loss.json in the state dir while on battery
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PowerLossRecord {
+    pub detected_dt: DateTime<Utc>,
+    pub charge_percent: Option<f64>,
+    // set when the monitor initiated a clean shutdown before depletion
+    pub shutdown_initiated: bool,
+}
+
+// upsc output is one `key: value` per line; on-battery is the OB flag in
+// ups.status (e.g. "OB DISCHRG"), charge comes from battery.charge
+pub fn parse_upsc_output(raw: &str) -> Option<PowerStatus> {
+    let mut status: Option<&str> = None;
+    let mut charge_percent: Option<f64> = None;
+    for line in raw.lines() {
+        let (key, value) = match line.split_once(':') {
+            Some(pair) => pair,
+            None => continue,
+        };
+        match key.trim() {
+            "ups.status" => status = Some(value.trim()),
+            "battery.charge" => charge_percent = value.trim().parse::<f64>().ok(),
+            _ => (),
+        }
+    }
+    let status = status?;
+    Some(PowerStatus {
+        on_battery: status.split_whitespace().any(|flag| flag == "OB"),
+        charge_percent,
+    })
+}
+
+async fn read_gpio_status(settings: &UpsSettings) -> Option<PowerStatus> {
+    let pin = settings.gpio_pin.to_string();
+    let output = match Command::new("gpioget")
+        .args([&settings.gpio_chip, &pin])
+        .output()
+        .await
+    {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => {
+            warn!(
+                "gpioget {} {} exited with code {:?}",
+                settings.gpio_chip,
+                pin,
+                output.status.code()
+            );
+            return None;
+        }
+        Err(e) => {
+            warn!("Failed to run gpioget error={}", e);
+            return None;
+        }
+    };
+    let raised = match String::from_utf8_lossy(&output.stdout).trim() {
+        "1" => true,
+        "0" => false,
+        other => {
+            warn!("Unexpected gpioget output: {}", other);
+            return None;
+        }
+    };
+    Some(PowerStatus {
+        on_battery: raised != settings.gpio_active_low,
+        charge_percent: None,
+    })
+}
+
+async fn read_nut_status(settings: &UpsSettings) -> Option<PowerStatus> {
+    let output = match Command::new("upsc").arg(&settings.nut_ups).output().await {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => {
+            warn!(
+                "upsc {} exited with code {:?} stderr={}",
+                settings.nut_ups,
+                output.status.code(),
+                String::from_utf8_lossy(&output.stderr)
+            );
+            return None;
+        }
+        Err(e) => {
+            warn!("Failed to run upsc error={}", e);
+            return None;
+        }
+    };
+    let raw = String::from_utf8_lossy(&output.stdout);
+    let status = parse_upsc_output(&raw);
+    if status.is_none() {
+        warn!("Failed to parse upsc output for {}", settings.nut_ups);
+    }
+    status
+}
+
+// current power status from the configured source; None when the source
+// can't be read (treated as no change by the monitor)
+pub async fn read_status(settings: &UpsSettings) -> Option<PowerStatus> {
+    match settings.source {
+        UpsSource::Gpio => read_gpio_status(settings).await,
+        UpsSource::Nut => read_nut_status(settings).await,
+    }
+}
+
+// record power loss so the next boot can report it; overwrites any stale marker
+pub fn record_power_loss(paths: &PrintNannyPaths, record: &PowerLossRecord) {
+    if let Err(e) = crate::printnanny_api::save_model_json(record, &paths.power_loss()) {
+        error!(
+            "Failed to write {} error={}",
+            paths.power_loss().display(),
+            e
+        );
+    }
+}
+
+// consume the power-loss marker left by a previous boot, if any
+pub fn take_power_loss_record(paths: &PrintNannyPaths) -> Option<PowerLossRecord> {
+    let path = paths.power_loss();
+    if !path.exists() {
+        return None;
+    }
+    let record = match crate::printnanny_api::read_model_json::<PowerLossRecord>(&path) {
+        Ok(record) => Some(record),
+        Err(e) => {
+            warn!("Failed to read {} error={}", path.display(), e);
+            None
+        }
+    };
+    if let Err(e) = std::fs::remove_file(&path) {
+        warn!("Failed to remove {} error={}", path.display(), e);
+    }
+    record
+}
+
+// clear the marker after power is restored without a shutdown
+pub fn clear_power_loss_record(paths: &PrintNannyPaths) {
+    let path = paths.power_loss();
+    if path.exists() {
+        if let Err(e) = std::fs::remove_file(&path) {
+            warn!("Failed to remove {} error={}", path.display(), e);
+        }
+    }
+}
+
+// clean shutdown before battery depletion, via the configured command
+pub async fn clean_shutdown(settings: &UpsSettings) {
+    info!(
+        "Initiating clean shutdown on battery: {}",
+        settings.shutdown_command
+    );
+    match Command::new("sh")
+        .args(["-c", &settings.shutdown_command])
+        .output()
+        .await
+    {
+        Ok(output) if output.status.success() => (),
+        Ok(output) => error!(
+            "Shutdown command exited with code {:?} stderr={}",
+            output.status.code(),
+            String::from_utf8_lossy(&output.stderr)
+        ),
+        Err(e) => error!("Failed to run shutdown command error={}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_parse_upsc_on_battery() {
+        let raw = "battery.charge: 87\nbattery.runtime: 1200\nups.status: OB DISCHRG\n";
+        let status = parse_upsc_output(raw).unwrap();
+        assert!(status.on_battery);
+        assert_eq!(status.charge_percent, Some(87.0));
+    }
+
+    #[test_log::test]
+    fn test_parse_upsc_on_mains() {
+        let raw = "battery.charge: 100\nups.status: OL\n";
+        let status = parse_upsc_output(raw).unwrap();
+        assert!(!status.on_battery);
+        // OB must match as a whole flag, not a substring of e.g. LOWBATT
+        let raw = "ups.status: OL LOWBATT\n";
+        assert!(!parse_upsc_output(raw).unwrap().on_battery);
+    }
+
+    #[test_log::test]
+    fn test_parse_upsc_missing_status() {
+        assert!(parse_upsc_output("battery.charge: 50\n").is_none());
+        assert!(parse_upsc_output("").is_none());
+    }
+
+    #[test_log::test]
+    fn test_power_loss_record_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = PrintNannyPaths {
+            state_dir: dir.path().to_path_buf(),
+            ..PrintNannyPaths::default()
+        };
+        assert!(take_power_loss_record(&paths).is_none());
+
+        record_power_loss(
+            &paths,
+            &PowerLossRecord {
+                detected_dt: Utc::now(),
+                charge_percent: Some(42.0),
+                shutdown_initiated: true,
+            },
+        );
+        let record = take_power_loss_record(&paths).unwrap();
+        assert!(record.shutdown_initiated);
+        assert_eq!(record.charge_percent, Some(42.0));
+        // the marker is consumed on read
+        assert!(take_power_loss_record(&paths).is_none());
+    }
+}
@@ -0,0 +1,112 @@
+// Snapshot device-local state before applying an OTA update (swupdate), so a
+// post-update incompatibility can be rolled back with:
+//   printnanny restore --pre-update
+//
+// The snapshot commits and tags the version-controlled settings repo, and
+// copies the edge sqlite db alongside its applied schema migration versions.
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use anyhow::{anyhow, Result};
+use log::info;
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+
+use printnanny_settings::printnanny::PrintNannySettings;
+use printnanny_settings::vcs::VersionControlledSettings;
+
+pub const PRE_UPDATE_TAG: &str = "pre-update";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PreUpdateSnapshot {
+    pub created: SystemTime,
+    // settings repo tag pointing at the pre-update commit
+    pub tag: String,
+    // copy of the edge sqlite db taken before the update
+    pub db_backup: PathBuf,
+    // diesel schema migration versions applied to the db at snapshot time
+    pub schema_versions: Vec<String>,
+}
+
+fn db_backup_path(settings: &PrintNannySettings) -> PathBuf {
+    settings.paths.state_dir.join("db.sqlite.pre-update")
+}
+
+fn metadata_path(settings: &PrintNannySettings) -> PathBuf {
+    settings.paths.state_dir.join("pre-update.json")
+}
+
+// commit + tag the settings repo and snapshot the edge db
+pub async fn snapshot() -> Result<PreUpdateSnapshot> {
+    let settings = PrintNannySettings::new().await?;
+    let content = settings.to_toml_string()?;
+    let ts = SystemTime::now();
+    settings
+        .save_and_commit(
+            &content,
+            Some(format!("Pre-update settings snapshot @ {ts:?}")),
+        )
+        .await?;
+    settings.git_tag_async(PRE_UPDATE_TAG).await?;
+
+    let db = settings.paths.db();
+    let db_backup = db_backup_path(&settings);
+    let schema_versions = match db.exists() {
+        true => {
+            fs::copy(&db, &db_backup).await?;
+            info!("Copied edge db {} to {}", db.display(), db_backup.display());
+            printnanny_edge_db::connection::applied_migration_versions(&db.display().to_string())
+                .map_err(|e| anyhow!("Failed to read applied migrations: {}", e))?
+        }
+        false => {
+            info!(
+                "Edge db {} does not exist, skipping db snapshot",
+                db.display()
+            );
+            vec![]
+        }
+    };
+
+    let result = PreUpdateSnapshot {
+        created: ts,
+        tag: PRE_UPDATE_TAG.to_string(),
+        db_backup,
+        schema_versions,
+    };
+    fs::write(
+        metadata_path(&settings),
+        serde_json::to_vec_pretty(&result)?,
+    )
+    .await?;
+    info!("Created pre-update snapshot: {:?}", result);
+    Ok(result)
+}
+
+// roll settings and edge db back to the last pre-update snapshot
+pub async fn restore() -> Result<PreUpdateSnapshot> {
+    let settings = PrintNannySettings::new().await?;
+    let metadata = metadata_path(&settings);
+    let snapshot: PreUpdateSnapshot = match metadata.exists() {
+        true => serde_json::from_slice(&fs::read(&metadata).await?)?,
+        false => {
+            return Err(anyhow!(
+                "No pre-update snapshot found at {}. Run an OTA update to create one.",
+                metadata.display()
+            ))
+        }
+    };
+
+    settings.git_reset_to_tag_async(&snapshot.tag).await?;
+
+    if snapshot.db_backup.exists() {
+        let db = settings.paths.db();
+        fs::copy(&snapshot.db_backup, &db).await?;
+        info!(
+            "Restored edge db {} from {}",
+            db.display(),
+            snapshot.db_backup.display()
+        );
+    }
+    info!("Restored pre-update snapshot: {:?}", snapshot);
+    Ok(snapshot)
+}
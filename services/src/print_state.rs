@@ -0,0 +1,252 @@
+// Infer "printer is actively printing" vs idle from the video feed, using the
+// windowed detection dataframes published by the dataframe_agg element on
+// pi.qc.df. Used as a fallback trigger for recordings/timelapses when no
+// OctoPrint/Moonraker integration is configured.
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum PrintState {
+    #[serde(rename = "idle")]
+    Idle,
+    #[serde(rename = "printing")]
+    Printing,
+}
+
+// one windowed row of dataframe_agg JSON output; only the columns relevant
+// to the print/idle classification are deserialized
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct WindowedDetectionFrame {
+    #[serde(default, rename = "rt__max")]
+    pub rt_max: Option<i64>,
+    #[serde(default, rename = "nozzle__count")]
+    pub nozzle_count: Option<u32>,
+    #[serde(default, rename = "nozzle__mean")]
+    pub nozzle_mean: Option<f64>,
+    #[serde(default, rename = "print__count")]
+    pub print_count: Option<u32>,
+    #[serde(default, rename = "print__mean")]
+    pub print_mean: Option<f64>,
+    #[serde(default, rename = "raft__count")]
+    pub raft_count: Option<u32>,
+    #[serde(default, rename = "raft__mean")]
+    pub raft_mean: Option<f64>,
+    #[serde(default, rename = "spaghetti__count")]
+    pub spaghetti_count: Option<u32>,
+    #[serde(default, rename = "spaghetti__mean")]
+    pub spaghetti_mean: Option<f64>,
+    #[serde(default, rename = "adhesion__count")]
+    pub adhesion_count: Option<u32>,
+    #[serde(default, rename = "adhesion__mean")]
+    pub adhesion_mean: Option<f64>,
+}
+
+// debounced print/idle state machine over windowed detection frames
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PrintStateClassifier {
+    // mean detection score above which a window counts as print activity
+    pub score_threshold: f64,
+    // consecutive agreeing windows required before the state flips
+    pub debounce_windows: u32,
+    state: PrintState,
+    active_windows: u32,
+    idle_windows: u32,
+}
+
+impl Default for PrintStateClassifier {
+    fn default() -> Self {
+        Self {
+            score_threshold: 0.5,
+            debounce_windows: 3,
+            state: PrintState::Idle,
+            active_windows: 0,
+            idle_windows: 0,
+        }
+    }
+}
+
+impl PrintStateClassifier {
+    pub fn state(&self) -> PrintState {
+        self.state
+    }
+
+    // a window counts as print activity when print or raft detections are
+    // present with a mean score above threshold
+    fn window_is_active(&self, frame: &WindowedDetectionFrame) -> bool {
+        let print_active = frame.print_count.unwrap_or(0) > 0
+            && frame.print_mean.unwrap_or(0.0) >= self.score_threshold;
+        let raft_active = frame.raft_count.unwrap_or(0) > 0
+            && frame.raft_mean.unwrap_or(0.0) >= self.score_threshold;
+        print_active || raft_active
+    }
+
+    // observe one windowed frame, returning Some(new_state) when the debounced
+    // state transitions
+    pub fn observe(&mut self, frame: &WindowedDetectionFrame) -> Option<PrintState> {
+        match self.window_is_active(frame) {
+            true => {
+                self.active_windows += 1;
+                self.idle_windows = 0;
+            }
+            false => {
+                self.idle_windows += 1;
+                self.active_windows = 0;
+            }
+        };
+        match self.state {
+            PrintState::Idle if self.active_windows >= self.debounce_windows => {
+                self.state = PrintState::Printing;
+                Some(PrintState::Printing)
+            }
+            PrintState::Printing if self.idle_windows >= self.debounce_windows => {
+                self.state = PrintState::Idle;
+                Some(PrintState::Idle)
+            }
+            _ => None,
+        }
+    }
+}
+
+// latched spaghetti/adhesion failure detector over windowed frames; latching
+// means one failure episode fires once instead of once per window
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PrintFailureDetector {
+    // mean failure-class score above which a window counts as a failure
+    pub score_threshold: f64,
+    latched: bool,
+}
+
+impl Default for PrintFailureDetector {
+    fn default() -> Self {
+        Self {
+            score_threshold: 0.7,
+            latched: false,
+        }
+    }
+}
+
+impl PrintFailureDetector {
+    fn window_is_failure(&self, frame: &WindowedDetectionFrame) -> bool {
+        let spaghetti = frame.spaghetti_count.unwrap_or(0) > 0
+            && frame.spaghetti_mean.unwrap_or(0.0) >= self.score_threshold;
+        let adhesion = frame.adhesion_count.unwrap_or(0) > 0
+            && frame.adhesion_mean.unwrap_or(0.0) >= self.score_threshold;
+        spaghetti || adhesion
+    }
+
+    // observe one windowed frame, returning true when a new failure episode
+    // starts; the latch resets once failure classes drop below threshold
+    pub fn observe(&mut self, frame: &WindowedDetectionFrame) -> bool {
+        match self.window_is_failure(frame) {
+            true => {
+                let new_episode = !self.latched;
+                self.latched = true;
+                new_episode
+            }
+            false => {
+                self.latched = false;
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn active_frame() -> WindowedDetectionFrame {
+        WindowedDetectionFrame {
+            print_count: Some(12),
+            print_mean: Some(0.8),
+            ..WindowedDetectionFrame::default()
+        }
+    }
+
+    fn idle_frame() -> WindowedDetectionFrame {
+        WindowedDetectionFrame {
+            print_count: Some(1),
+            print_mean: Some(0.1),
+            ..WindowedDetectionFrame::default()
+        }
+    }
+
+    #[test]
+    fn test_transition_to_printing_is_debounced() {
+        let mut classifier = PrintStateClassifier::default();
+        assert_eq!(classifier.observe(&active_frame()), None);
+        assert_eq!(classifier.observe(&active_frame()), None);
+        assert_eq!(
+            classifier.observe(&active_frame()),
+            Some(PrintState::Printing)
+        );
+        assert_eq!(classifier.state(), PrintState::Printing);
+        // further active windows do not re-emit the transition
+        assert_eq!(classifier.observe(&active_frame()), None);
+    }
+
+    #[test]
+    fn test_idle_windows_reset_debounce_counter() {
+        let mut classifier = PrintStateClassifier::default();
+        assert_eq!(classifier.observe(&active_frame()), None);
+        assert_eq!(classifier.observe(&active_frame()), None);
+        assert_eq!(classifier.observe(&idle_frame()), None);
+        assert_eq!(classifier.observe(&active_frame()), None);
+        assert_eq!(classifier.state(), PrintState::Idle);
+    }
+
+    #[test]
+    fn test_transition_back_to_idle() {
+        let mut classifier = PrintStateClassifier {
+            debounce_windows: 1,
+            ..PrintStateClassifier::default()
+        };
+        assert_eq!(
+            classifier.observe(&active_frame()),
+            Some(PrintState::Printing)
+        );
+        assert_eq!(classifier.observe(&idle_frame()), Some(PrintState::Idle));
+    }
+
+    #[test]
+    fn test_raft_detections_count_as_activity() {
+        let mut classifier = PrintStateClassifier {
+            debounce_windows: 1,
+            ..PrintStateClassifier::default()
+        };
+        let frame = WindowedDetectionFrame {
+            raft_count: Some(4),
+            raft_mean: Some(0.7),
+            ..WindowedDetectionFrame::default()
+        };
+        assert_eq!(classifier.observe(&frame), Some(PrintState::Printing));
+    }
+
+    #[test]
+    fn test_failure_detector_latches_per_episode() {
+        let mut detector = PrintFailureDetector::default();
+        let failure = WindowedDetectionFrame {
+            spaghetti_count: Some(6),
+            spaghetti_mean: Some(0.9),
+            ..WindowedDetectionFrame::default()
+        };
+        assert!(detector.observe(&failure));
+        // same episode fires only once
+        assert!(!detector.observe(&failure));
+        // latch resets after a clean window
+        assert!(!detector.observe(&active_frame()));
+        assert!(detector.observe(&failure));
+    }
+
+    #[test]
+    fn test_failure_detector_ignores_low_scores() {
+        let mut detector = PrintFailureDetector::default();
+        let frame = WindowedDetectionFrame {
+            spaghetti_count: Some(2),
+            spaghetti_mean: Some(0.3),
+            adhesion_count: Some(1),
+            adhesion_mean: Some(0.2),
+            ..WindowedDetectionFrame::default()
+        };
+        assert!(!detector.observe(&frame));
+    }
+}
@@ -177,6 +177,12 @@ impl ApiService {
             favorite: None,
         };
         self.pi_partial_update(pi_id, req).await?;
+
+        crate::lifecycle::transition(
+            &settings.paths,
+            crate::lifecycle::DeviceLifecycleState::CloudConnected,
+            "PrintNanny Cloud account connected",
+        )?;
         Ok(self)
     }
 
@@ -196,6 +202,14 @@ impl ApiService {
         posthog_session: Option<&str>,
         crash_report_paths: Vec<PathBuf>,
     ) -> Result<models::CrashReport, ServiceError> {
+        let settings = PrintNannySettings::new().await?;
+        // central telemetry opt-out: every crash report submission funnels
+        // through this call, so no per-feature checks are needed
+        if !settings.telemetry.crash_reports {
+            return Err(ServiceError::TelemetryDisabled {
+                category: "crash_reports".to_string(),
+            });
+        }
         let file = tempfile::Builder::new()
             .prefix("crash-report")
             .suffix(".zip")
@@ -203,7 +217,6 @@ impl ApiService {
             .tempfile()
             .map_err(|e| IoError::TempFileError { msg: e.to_string() })?;
         let (file, filename) = &file.keep()?;
-        let settings = PrintNannySettings::new().await?;
 
         write_crash_report_zip(file, &settings).await?;
         warn!("Wrote crash report logs to {}", filename.display());
@@ -249,6 +262,12 @@ impl ApiService {
         id: &str,
         crash_report_paths: Vec<PathBuf>,
     ) -> Result<models::CrashReport, ServiceError> {
+        let settings = PrintNannySettings::new().await?;
+        if !settings.telemetry.crash_reports {
+            return Err(ServiceError::TelemetryDisabled {
+                category: "crash_reports".to_string(),
+            });
+        }
         let os_release = OsRelease::new()?;
         let file = tempfile::Builder::new()
             .prefix("crash-report")
@@ -257,7 +276,6 @@ impl ApiService {
             .tempfile()
             .map_err(|e| IoError::TempFileError { msg: e.to_string() })?;
         let (file, filename) = &file.keep()?;
-        let settings = PrintNannySettings::new().await?;
 
         write_crash_report_zip(file, &settings).await?;
         warn!("Wrote crash report logs to {}", filename.display());
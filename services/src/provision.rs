@@ -0,0 +1,299 @@
+// Cold-start provisioning from a USB config drive or the boot partition:
+// printnanny_os_init scans well-known mount points for a
+// printnanny-provision.toml describing WiFi credentials, a PrintNanny Cloud
+// API token, and PrintNannySettings overrides. Overrides are merged over the
+// current settings document and run through the normal validation path, and
+// the file is renamed to *.applied after a successful run so the next boot
+// does not re-apply it — enabling fully headless setup at fleet scale.
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+use log::info;
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+
+use printnanny_settings::printnanny::PrintNannySettings;
+use printnanny_settings::toml;
+use printnanny_settings::vcs::VersionControlledSettings;
+
+use crate::error::ServiceError;
+use crate::printnanny_api::ApiService;
+
+pub const PROVISION_FILENAME: &str = "printnanny-provision.toml";
+// FAT USB sticks and the boot partition, in the order they are checked
+pub const PROVISION_SOURCE_DIRS: &[&str] = &[
+    "/boot",
+    "/boot/firmware",
+    "/media/usb0",
+    "/media/usb1",
+    "/mnt/usb",
+];
+const NM_CONNECTION_DIR: &str = "/etc/NetworkManager/system-connections";
+const NM_CONNECTION_ID: &str = "printnanny-provision";
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WifiProvision {
+    pub ssid: String,
+    // open networks omit the password
+    pub password: Option<String>,
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CloudProvision {
+    // defaults to the api_base_path already in PrintNannySettings.cloud
+    pub api_url: Option<String>,
+    pub api_token: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProvisionConfig {
+    pub wifi: Option<WifiProvision>,
+    pub cloud: Option<CloudProvision>,
+    // sparse PrintNannySettings overrides, merged over the current document
+    pub settings: Option<toml::Value>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProvisionReport {
+    pub source: String,
+    pub wifi_configured: bool,
+    pub cloud_connected: bool,
+    pub settings_applied: bool,
+    pub renamed_to: String,
+}
+
+fn find_provision_file_in(dirs: &[&str]) -> Option<PathBuf> {
+    dirs.iter()
+        .map(|dir| Path::new(dir).join(PROVISION_FILENAME))
+        .find(|path| path.exists())
+}
+
+// first provision file found across the candidate mount points
+pub fn find_provision_file() -> Option<PathBuf> {
+    find_provision_file_in(PROVISION_SOURCE_DIRS)
+}
+
+async fn read_provision_config(path: &Path) -> Result<ProvisionConfig, ServiceError> {
+    let content =
+        fs::read_to_string(path)
+            .await
+            .map_err(|e| crate::error::IoError::ReadIOError {
+                path: path.display().to_string(),
+                error: e,
+            })?;
+    toml::from_str(&content).map_err(|e| ServiceError::ProvisionConfigError {
+        path: path.display().to_string(),
+        detail: e.to_string(),
+    })
+}
+
+fn render_nm_keyfile(wifi: &WifiProvision) -> String {
+    let mut keyfile = format!(
+        "[connection]\nid={}\ntype=wifi\n\n[wifi]\nssid={}\nmode=infrastructure\n",
+        NM_CONNECTION_ID, wifi.ssid
+    );
+    if wifi.hidden {
+        keyfile.push_str("hidden=true\n");
+    }
+    if let Some(password) = &wifi.password {
+        keyfile.push_str(&format!(
+            "\n[wifi-security]\nkey-mgmt=wpa-psk\npsk={}\n",
+            password
+        ));
+    }
+    keyfile.push_str("\n[ipv4]\nmethod=auto\n\n[ipv6]\nmethod=auto\n");
+    keyfile
+}
+
+// NetworkManager refuses keyfiles readable by other users, so chmod 0600
+async fn write_wifi_connection(wifi: &WifiProvision, dir: &Path) -> Result<PathBuf, ServiceError> {
+    let path = dir.join(format!("{NM_CONNECTION_ID}.nmconnection"));
+    fs::write(&path, render_nm_keyfile(wifi))
+        .await
+        .map_err(|e| crate::error::IoError::WriteIOError {
+            path: path.display().to_string(),
+            error: e,
+        })?;
+    fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))
+        .await
+        .map_err(|e| crate::error::IoError::WriteIOError {
+            path: path.display().to_string(),
+            error: e,
+        })?;
+    Ok(path)
+}
+
+// overlay wins for scalars; tables are merged key-by-key so a sparse
+// [settings.video_stream.hls] override does not clobber sibling keys
+fn merge_toml(base: &mut toml::Value, overlay: &toml::Value) {
+    match (base, overlay) {
+        (toml::Value::Table(base), toml::Value::Table(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(key) {
+                    Some(existing) => merge_toml(existing, value),
+                    None => {
+                        base.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay.clone(),
+    }
+}
+
+// merge the overrides over the current settings document, then run the merged
+// result through the same validation PrintNannySettings::new applies
+async fn apply_settings_overrides(
+    settings: &PrintNannySettings,
+    overrides: &toml::Value,
+) -> Result<(), ServiceError> {
+    let mut doc = toml::Value::try_from(settings)
+        .map_err(printnanny_settings::error::PrintNannySettingsError::from)?;
+    merge_toml(&mut doc, overrides);
+    let merged: PrintNannySettings = doc
+        .try_into()
+        .map_err(printnanny_settings::error::PrintNannySettingsError::from)?;
+    printnanny_settings::validation::validate_settings(&merged)?;
+    let content = merged.to_toml_string()?;
+    settings
+        .save_and_commit(
+            &content,
+            Some("Applied printnanny-provision.toml settings overrides".to_string()),
+        )
+        .await?;
+    Ok(())
+}
+
+// returns None when no provision file is present on any candidate mount
+pub async fn provision_from_config_drive(
+    settings: &PrintNannySettings,
+) -> Result<Option<ProvisionReport>, ServiceError> {
+    let source = match find_provision_file() {
+        Some(source) => source,
+        None => return Ok(None),
+    };
+    info!("Found provision file {}", source.display());
+    let config = read_provision_config(&source).await?;
+
+    let wifi_configured = match &config.wifi {
+        Some(wifi) => {
+            let path = write_wifi_connection(wifi, Path::new(NM_CONNECTION_DIR)).await?;
+            info!("Wrote NetworkManager connection {}", path.display());
+            true
+        }
+        None => false,
+    };
+
+    let settings_applied = match &config.settings {
+        Some(overrides) => {
+            apply_settings_overrides(settings, overrides).await?;
+            true
+        }
+        None => false,
+    };
+
+    // cloud connect runs last so it sees any settings overrides just applied
+    let cloud_connected = match &config.cloud {
+        Some(cloud) => {
+            let settings = PrintNannySettings::new().await?;
+            let api_url = cloud
+                .api_url
+                .clone()
+                .unwrap_or_else(|| settings.cloud.api_base_path.clone());
+            ApiService::from(&settings)
+                .connect_cloud_account(api_url, cloud.api_token.clone())
+                .await?;
+            true
+        }
+        None => false,
+    };
+
+    // rename only after every section applied, so a failed boot retries
+    let renamed_to = source.with_extension("toml.applied");
+    fs::rename(&source, &renamed_to)
+        .await
+        .map_err(|e| crate::error::IoError::WriteIOError {
+            path: renamed_to.display().to_string(),
+            error: e,
+        })?;
+    info!(
+        "Provisioning complete, renamed {} to {}",
+        source.display(),
+        renamed_to.display()
+    );
+
+    Ok(Some(ProvisionReport {
+        source: source.display().to_string(),
+        wifi_configured,
+        cloud_connected,
+        settings_applied,
+        renamed_to: renamed_to.display().to_string(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FULL_PROVISION: &str = r#"
+        [wifi]
+        ssid = "farm-floor"
+        password = "hunter2"
+        hidden = true
+
+        [cloud]
+        api_token = "claim-token"
+
+        [settings.video_stream.hls]
+        enabled = false
+        "#;
+
+    #[test_log::test]
+    fn test_provision_config_parses_full_file() {
+        let config: ProvisionConfig = toml::from_str(FULL_PROVISION).unwrap();
+        let wifi = config.wifi.unwrap();
+        assert_eq!(wifi.ssid, "farm-floor");
+        assert_eq!(wifi.password.as_deref(), Some("hunter2"));
+        assert!(wifi.hidden);
+        let cloud = config.cloud.unwrap();
+        assert_eq!(cloud.api_token, "claim-token");
+        assert!(cloud.api_url.is_none());
+        let keyfile = render_nm_keyfile(&wifi);
+        assert!(keyfile.contains("ssid=farm-floor"));
+        assert!(keyfile.contains("psk=hunter2"));
+        assert!(keyfile.contains("hidden=true"));
+    }
+
+    #[test_log::test]
+    fn test_merge_toml_preserves_sibling_keys() {
+        let mut base: toml::Value =
+            toml::from_str("[video_stream.hls]\nenabled = true\nsegments = \"/var/hls\"\n")
+                .unwrap();
+        let overlay: toml::Value = toml::from_str("[video_stream.hls]\nenabled = false\n").unwrap();
+        merge_toml(&mut base, &overlay);
+        let hls = base.get("video_stream").unwrap().get("hls").unwrap();
+        assert_eq!(hls.get("enabled").unwrap(), &toml::Value::Boolean(false));
+        assert_eq!(
+            hls.get("segments").unwrap(),
+            &toml::Value::String("/var/hls".to_string())
+        );
+    }
+
+    #[test_log::test]
+    fn test_find_provision_file_in_candidate_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("empty");
+        let mounted = dir.path().join("usb0");
+        std::fs::create_dir_all(&missing).unwrap();
+        std::fs::create_dir_all(&mounted).unwrap();
+        std::fs::write(mounted.join(PROVISION_FILENAME), FULL_PROVISION).unwrap();
+
+        let missing = missing.display().to_string();
+        let mounted = mounted.display().to_string();
+        let found = find_provision_file_in(&[missing.as_str(), mounted.as_str()]).unwrap();
+        assert_eq!(found, Path::new(&mounted).join(PROVISION_FILENAME));
+        assert!(find_provision_file_in(&[missing.as_str()]).is_none());
+    }
+}
@@ -0,0 +1,143 @@
+// Per-service CPU/memory usage from cgroup v2 accounting. systemd places each
+// unit under /sys/fs/cgroup/system.slice/<unit>/, where cpu.stat, memory.stat
+// and memory.swap.current expose the counters we care about. Units that are not
+// running simply have no cgroup directory and are skipped.
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::error::ServiceError;
+
+pub const CGROUP_ROOT: &str = "/sys/fs/cgroup/system.slice";
+
+// systemd units owned by PrintNanny OS, monitored in the heartbeat
+pub const PRINTNANNY_UNITS: &[&str] = &[
+    "printnanny-cloud-sync.service",
+    "printnanny-dash.service",
+    "printnanny-edge-nats.service",
+    "printnanny-nats-server.service",
+    "printnanny-vision.service",
+];
+
+// the inference pipeline; swapping here is the usual cause of Pi 3 complaints
+pub const INFERENCE_UNIT: &str = "printnanny-vision.service";
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnitResourceUsage {
+    pub unit: String,
+    /// Cumulative CPU time in microseconds, from cpu.stat usage_usec
+    pub cpu_usage_usec: u64,
+    /// Resident anonymous memory in bytes, from memory.stat anon
+    pub memory_rss_bytes: u64,
+    /// Swap in use in bytes, from memory.swap.current
+    pub swap_bytes: u64,
+}
+
+// extract a counter value from flat keyed cgroup stat output (e.g. cpu.stat)
+fn parse_keyed_stat(content: &str, key: &str) -> Option<u64> {
+    content.lines().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        match fields.next() {
+            Some(field) if field == key => fields.next()?.parse().ok(),
+            _ => None,
+        }
+    })
+}
+
+fn parse_scalar_stat(content: &str) -> Option<u64> {
+    content.trim().parse().ok()
+}
+
+fn read_unit_usage(cgroup_dir: &Path, unit: &str) -> Option<UnitResourceUsage> {
+    let cpu_stat = read_to_string(cgroup_dir.join("cpu.stat")).ok()?;
+    let memory_stat = read_to_string(cgroup_dir.join("memory.stat")).ok()?;
+    // memory.swap.current is absent when the kernel has no swap accounting
+    let swap = read_to_string(cgroup_dir.join("memory.swap.current")).unwrap_or_default();
+    Some(UnitResourceUsage {
+        unit: unit.to_string(),
+        cpu_usage_usec: parse_keyed_stat(&cpu_stat, "usage_usec")?,
+        memory_rss_bytes: parse_keyed_stat(&memory_stat, "anon")?,
+        swap_bytes: parse_scalar_stat(&swap).unwrap_or(0),
+    })
+}
+
+// read usage for all printnanny-owned units; units without a cgroup (not
+// running) are skipped
+pub fn printnanny_unit_usage() -> Result<Vec<UnitResourceUsage>, ServiceError> {
+    let cgroup_root = PathBuf::from(CGROUP_ROOT);
+    Ok(PRINTNANNY_UNITS
+        .iter()
+        .filter_map(|unit| read_unit_usage(&cgroup_root.join(unit), unit))
+        .collect())
+}
+
+// units whose swap usage exceeds the configured alert threshold
+pub fn swapping_units(
+    usage: &[UnitResourceUsage],
+    swap_alert_threshold_bytes: u64,
+) -> Vec<UnitResourceUsage> {
+    usage
+        .iter()
+        .filter(|unit| unit.swap_bytes >= swap_alert_threshold_bytes)
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CPU_STAT: &str = r#"usage_usec 24727562
+user_usec 18101604
+system_usec 6625957
+nr_periods 0
+nr_throttled 0
+throttled_usec 0
+"#;
+
+    const MEMORY_STAT: &str = r#"anon 104857600
+file 6189056
+kernel_stack 163840
+pagetables 1069056
+percpu 0
+sock 8192
+shmem 0
+"#;
+
+    #[test_log::test]
+    fn test_parse_cpu_stat() {
+        assert_eq!(parse_keyed_stat(CPU_STAT, "usage_usec"), Some(24727562));
+        assert_eq!(parse_keyed_stat(CPU_STAT, "nr_periods"), Some(0));
+        assert_eq!(parse_keyed_stat(CPU_STAT, "missing_key"), None);
+    }
+
+    #[test_log::test]
+    fn test_parse_memory_stat() {
+        assert_eq!(parse_keyed_stat(MEMORY_STAT, "anon"), Some(104857600));
+        assert_eq!(parse_scalar_stat("4096\n"), Some(4096));
+        assert_eq!(parse_scalar_stat("not a number"), None);
+    }
+
+    #[test_log::test]
+    fn test_swapping_units_filters_by_threshold() {
+        let usage = vec![
+            UnitResourceUsage {
+                unit: INFERENCE_UNIT.to_string(),
+                cpu_usage_usec: 1000,
+                memory_rss_bytes: 104857600,
+                swap_bytes: 16 * 1024 * 1024,
+            },
+            UnitResourceUsage {
+                unit: "printnanny-dash.service".to_string(),
+                cpu_usage_usec: 1000,
+                memory_rss_bytes: 1024,
+                swap_bytes: 0,
+            },
+        ];
+        let swapping = swapping_units(&usage, 1024 * 1024);
+        assert_eq!(swapping.len(), 1);
+        assert_eq!(swapping[0].unit, INFERENCE_UNIT);
+        assert!(swapping_units(&usage, u64::MAX).is_empty());
+    }
+}
@@ -0,0 +1,368 @@
+// Built-in actions for the cron-style scheduler in the NATS edge worker:
+// settings backups into the recovery dir, age-based retention pruning of
+// recorded media, and per-category storage quota enforcement. The scheduler
+// itself (cron matching, task registry) lives in
+// printnanny_nats_apps::schedule.
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use chrono::Utc;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use zip::write::FileOptions;
+
+use printnanny_settings::printnanny::PrintNannySettings;
+use printnanny_settings::storage::StorageCategory;
+
+use crate::error::ServiceError;
+
+// zip the settings repo (including git history) into paths.recovery(),
+// returning the archive path
+pub fn settings_backup(settings: &PrintNannySettings) -> Result<PathBuf, ServiceError> {
+    let settings_dir = settings
+        .paths
+        .settings_file()
+        .parent()
+        .map(|p| p.to_path_buf())
+        .unwrap_or_default();
+    let recovery = settings.paths.recovery();
+    std::fs::create_dir_all(&recovery).map_err(|e| crate::error::IoError::WriteIOError {
+        path: recovery.display().to_string(),
+        error: e,
+    })?;
+    let output = recovery.join(format!(
+        "settings-backup-{}.zip",
+        Utc::now().format("%Y%m%dT%H%M%SZ")
+    ));
+    let file = File::create(&output).map_err(|e| crate::error::IoError::WriteIOError {
+        path: output.display().to_string(),
+        error: e,
+    })?;
+    let mut zip = zip::ZipWriter::new(&file);
+    let options = FileOptions::default().unix_permissions(0o644);
+    zip_dir(&mut zip, &settings_dir, &settings_dir, options)?;
+    zip.finish()?;
+    info!("Wrote settings backup {}", output.display());
+    Ok(output)
+}
+
+fn zip_dir(
+    zip: &mut zip::ZipWriter<&File>,
+    root: &Path,
+    dir: &Path,
+    options: FileOptions,
+) -> Result<(), ServiceError> {
+    let entries = std::fs::read_dir(dir).map_err(|e| crate::error::IoError::ReadIOError {
+        path: dir.display().to_string(),
+        error: e,
+    })?;
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        if path.is_dir() {
+            zip_dir(zip, root, &path, options)?;
+            continue;
+        }
+        let name = path
+            .strip_prefix(root)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .to_string();
+        match std::fs::read(&path) {
+            Ok(contents) => {
+                zip.start_file(&name, options)?;
+                zip.write_all(&contents)
+                    .map_err(|e| crate::error::IoError::WriteIOError {
+                        path: name.clone(),
+                        error: e,
+                    })?;
+            }
+            Err(e) => warn!("Skipping {}, failed to read: {}", path.display(), e),
+        }
+    }
+    Ok(())
+}
+
+// delete recorded video and snapshot files older than `days`, returning how
+// many files were removed; directories themselves are left in place
+pub fn retention_prune(settings: &PrintNannySettings, days: u32) -> usize {
+    let cutoff = SystemTime::now() - Duration::from_secs(days as u64 * 24 * 60 * 60);
+    let mut removed = 0;
+    for dir in [settings.paths.video(), settings.paths.snapshot_dir.clone()] {
+        removed += prune_dir(&dir, cutoff);
+    }
+    info!(
+        "Retention prune removed {} files older than {} days",
+        removed, days
+    );
+    removed
+}
+
+fn prune_dir(dir: &Path, cutoff: SystemTime) -> usize {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return 0,
+    };
+    let mut removed = 0;
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        if path.is_dir() {
+            removed += prune_dir(&path, cutoff);
+            continue;
+        }
+        let modified = entry.metadata().and_then(|meta| meta.modified());
+        let expired = matches!(modified, Ok(modified) if modified < cutoff);
+        if !expired {
+            continue;
+        }
+        match std::fs::remove_file(&path) {
+            Ok(_) => removed += 1,
+            Err(e) => warn!("Failed to prune {}: {}", path.display(), e),
+        }
+    }
+    removed
+}
+
+// current usage for one storage category, reported via pi.{pi_id}.stats.storage
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StorageCategoryUsage {
+    pub category: StorageCategory,
+    pub dir: String,
+    pub used_bytes: u64,
+    pub files: u64,
+    // 0 means no quota configured for this category
+    pub quota_bytes: u64,
+}
+
+fn dir_usage(dir: &Path) -> (u64, u64) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return (0, 0),
+    };
+    let mut bytes = 0;
+    let mut files = 0;
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        if path.is_dir() {
+            let (sub_bytes, sub_files) = dir_usage(&path);
+            bytes += sub_bytes;
+            files += sub_files;
+            continue;
+        }
+        if let Ok(meta) = entry.metadata() {
+            bytes += meta.len();
+            files += 1;
+        }
+    }
+    (bytes, files)
+}
+
+// per-category usage against the configured quotas
+pub fn storage_usage(settings: &PrintNannySettings) -> Vec<StorageCategoryUsage> {
+    StorageCategory::ALL
+        .iter()
+        .map(|category| {
+            let dir = category.dir(&settings.paths);
+            let (used_bytes, files) = dir_usage(&dir);
+            StorageCategoryUsage {
+                category: *category,
+                dir: dir.display().to_string(),
+                used_bytes,
+                files,
+                quota_bytes: settings.storage.max_bytes(*category),
+            }
+        })
+        .collect()
+}
+
+// all files under a category dir, oldest first
+fn files_by_age(dir: &Path) -> Vec<(PathBuf, SystemTime, u64)> {
+    let mut files = Vec::new();
+    collect_files(dir, &mut files);
+    files.sort_by_key(|(_, modified, _)| *modified);
+    files
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<(PathBuf, SystemTime, u64)>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, files);
+            continue;
+        }
+        if let Ok(meta) = entry.metadata() {
+            let modified = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            files.push((path, modified, meta.len()));
+        }
+    }
+}
+
+// evict the oldest files of every category over its quota until the category
+// fits, returning how many files were removed. Eviction never crosses
+// category boundaries, so a runaway category (e.g. timelapses) cannot evict
+// failure-evidence recordings.
+pub fn quota_prune(settings: &PrintNannySettings) -> usize {
+    let mut removed = 0;
+    for category in StorageCategory::ALL {
+        let quota = settings.storage.max_bytes(category);
+        if quota == 0 {
+            continue;
+        }
+        let dir = category.dir(&settings.paths);
+        let (mut used, _) = dir_usage(&dir);
+        if used <= quota {
+            continue;
+        }
+        warn!(
+            "Storage category {} over quota: {} of {} bytes used, evicting oldest files",
+            category.as_str(),
+            used,
+            quota
+        );
+        for (path, _, size) in files_by_age(&dir) {
+            if used <= quota {
+                break;
+            }
+            match std::fs::remove_file(&path) {
+                Ok(_) => {
+                    used = used.saturating_sub(size);
+                    removed += 1;
+                }
+                Err(e) => warn!("Failed to evict {}: {}", path.display(), e),
+            }
+        }
+    }
+    info!("Quota prune removed {} files", removed);
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use printnanny_settings::paths::PrintNannyPaths;
+
+    #[test_log::test]
+    fn test_retention_prune_removes_only_expired_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = PrintNannySettings {
+            paths: PrintNannyPaths {
+                state_dir: dir.path().join("state"),
+                snapshot_dir: dir.path().join("snapshot"),
+                ..PrintNannyPaths::default()
+            },
+            ..PrintNannySettings::default()
+        };
+        std::fs::create_dir_all(settings.paths.video()).unwrap();
+        std::fs::create_dir_all(&settings.paths.snapshot_dir).unwrap();
+        let old = settings.paths.video().join("old.mp4");
+        let fresh = settings.paths.snapshot_dir.join("fresh.jpg");
+        std::fs::write(&old, b"frames").unwrap();
+        std::fs::write(&fresh, b"jpeg").unwrap();
+        // backdate the old recording past the cutoff
+        let two_days_ago = SystemTime::now() - Duration::from_secs(2 * 24 * 60 * 60);
+        File::options()
+            .write(true)
+            .open(&old)
+            .unwrap()
+            .set_modified(two_days_ago)
+            .unwrap();
+
+        assert_eq!(retention_prune(&settings, 1), 1);
+        assert!(!old.exists());
+        assert!(fresh.exists());
+    }
+
+    #[test_log::test]
+    fn test_quota_prune_evicts_oldest_within_category_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut settings = PrintNannySettings {
+            paths: PrintNannyPaths {
+                state_dir: dir.path().join("state"),
+                snapshot_dir: dir.path().join("snapshot"),
+                ..PrintNannyPaths::default()
+            },
+            ..PrintNannySettings::default()
+        };
+        // timelapses capped at 8 bytes, recordings unlimited
+        settings.storage.timelapses_max_bytes = 8;
+        std::fs::create_dir_all(settings.paths.video()).unwrap();
+        std::fs::create_dir_all(settings.paths.timelapse()).unwrap();
+        let recording = settings.paths.video().join("failure-evidence.mp4");
+        let old_timelapse = settings.paths.timelapse().join("old.mp4");
+        let new_timelapse = settings.paths.timelapse().join("new.mp4");
+        std::fs::write(&recording, b"frames").unwrap();
+        std::fs::write(&old_timelapse, b"12345678").unwrap();
+        std::fs::write(&new_timelapse, b"1234").unwrap();
+        let yesterday = SystemTime::now() - Duration::from_secs(24 * 60 * 60);
+        File::options()
+            .write(true)
+            .open(&old_timelapse)
+            .unwrap()
+            .set_modified(yesterday)
+            .unwrap();
+
+        // the over-quota timelapse category evicts its own oldest file; the
+        // recording is untouched even though it is the oldest file overall
+        assert_eq!(quota_prune(&settings), 1);
+        assert!(!old_timelapse.exists());
+        assert!(new_timelapse.exists());
+        assert!(recording.exists());
+    }
+
+    #[test_log::test]
+    fn test_storage_usage_reports_all_categories() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut settings = PrintNannySettings {
+            paths: PrintNannyPaths {
+                state_dir: dir.path().join("state"),
+                snapshot_dir: dir.path().join("snapshot"),
+                ..PrintNannyPaths::default()
+            },
+            ..PrintNannySettings::default()
+        };
+        settings.storage.recordings_max_bytes = 1024;
+        std::fs::create_dir_all(settings.paths.video()).unwrap();
+        std::fs::write(settings.paths.video().join("a.mp4"), b"frames").unwrap();
+
+        let usage = storage_usage(&settings);
+        assert_eq!(usage.len(), StorageCategory::ALL.len());
+        let recordings = usage
+            .iter()
+            .find(|u| u.category == StorageCategory::Recordings)
+            .unwrap();
+        assert_eq!(recordings.used_bytes, 6);
+        assert_eq!(recordings.files, 1);
+        assert_eq!(recordings.quota_bytes, 1024);
+    }
+
+    #[test_log::test]
+    fn test_settings_backup_archives_settings_dir() {
+        figment::Jail::expect_with(|jail| {
+            let settings_dir = jail.directory().join("printnanny");
+            std::fs::create_dir_all(&settings_dir).unwrap();
+            std::fs::write(settings_dir.join("printnanny.toml"), "[paths]\n").unwrap();
+            jail.set_env(
+                "PRINTNANNY_SETTINGS",
+                settings_dir.join("printnanny.toml").display().to_string(),
+            );
+            let dir = tempfile::tempdir().unwrap();
+            let settings = PrintNannySettings {
+                paths: PrintNannyPaths {
+                    state_dir: dir.path().join("state"),
+                    ..PrintNannyPaths::default()
+                },
+                ..PrintNannySettings::default()
+            };
+            let output = settings_backup(&settings).unwrap();
+            let archive = zip::ZipArchive::new(File::open(&output).unwrap()).unwrap();
+            let names: Vec<&str> = archive.file_names().collect();
+            assert!(names.contains(&"printnanny.toml"));
+            Ok(())
+        });
+    }
+}
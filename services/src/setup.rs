@@ -1,4 +1,5 @@
 use printnanny_edge_db::connection::run_migrations;
+use printnanny_settings::migrations::run_settings_migrations;
 use printnanny_settings::printnanny::PrintNannySettings;
 
 use crate::error::ServiceError;
@@ -6,12 +7,58 @@ use crate::error::ServiceError;
 // one-time PrintNanyn OS setup tasks
 pub async fn printnanny_os_init() -> Result<(), ServiceError> {
     let settings = PrintNannySettings::new().await?;
+    // surface read-only paths before anything writes to them
+    crate::doctor::warn_unwritable_paths(&settings);
     // ensure directory structure exists
     settings.paths.try_init_all()?;
+    // move recordings/db onto a freshly attached external drive before anything opens them
+    let migrated = settings.paths.migrate_data_to_external()?;
+    if !migrated.is_empty() {
+        log::info!(
+            "Migrated {} paths to external data dir {:?}",
+            migrated.len(),
+            settings.paths.external_data_dir
+        );
+    }
+    // bring older settings file layouts up to the current schema
+    run_settings_migrations(&settings.paths).await?;
     let sqlite_connection = settings.paths.db().display().to_string();
     // run any pending migrations
     run_migrations(&sqlite_connection).map_err(|e| ServiceError::SQLiteMigrationError {
         msg: (*e).to_string(),
     })?;
+    // apply a provision file from a USB config drive or the boot partition,
+    // if one is present; a bad file should not block boot, so errors are
+    // logged and the file is left in place for the next attempt
+    match crate::provision::provision_from_config_drive(&settings).await {
+        Ok(Some(report)) => log::info!(
+            "Applied provision file {} (wifi={} cloud={} settings={})",
+            report.source,
+            report.wifi_configured,
+            report.cloud_connected,
+            report.settings_applied
+        ),
+        Ok(None) => {}
+        Err(e) => log::error!("Failed to apply provision file: {}", e),
+    }
+    // generate the device identity keypair before anything publishes signed
+    // messages; like provisioning, a failure here should not block boot
+    match printnanny_nats_client::identity::keypair(&settings.paths) {
+        Ok(keypair) => log::info!(
+            "Device identity pubkey {}",
+            printnanny_nats_client::identity::public_key_b64(&keypair)
+        ),
+        Err(e) => log::error!("Failed to initialize device identity key: {}", e),
+    }
+    // first successful init provisions the device
+    if crate::lifecycle::load(&settings.paths).state
+        == crate::lifecycle::DeviceLifecycleState::Unprovisioned
+    {
+        crate::lifecycle::transition(
+            &settings.paths,
+            crate::lifecycle::DeviceLifecycleState::Provisioned,
+            "printnanny_os_init completed",
+        )?;
+    }
     Ok(())
 }
@@ -0,0 +1,365 @@
+// Synthetic load/soak test used to validate new OS images and hardware
+// batches: `printnanny soak --hours N` exercises the camera/detection
+// pipelines, sends periodic NATS commands, and runs upload sync passes while
+// sampling per-unit resource usage, then grades the run into a pass/fail
+// stability report.
+use std::collections::HashMap;
+
+use chrono::Utc;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tokio::time::{sleep, timeout, Duration};
+
+use printnanny_gst_pipelines::factory::{
+    GstPipelineState, PrintNannyPipelineFactory, CAMERA_PIPELINE, INFERENCE_PIPELINE,
+};
+use printnanny_nats_client::client::try_init_nats_client;
+use printnanny_settings::printnanny::PrintNannySettings;
+use printnanny_settings::sys_info;
+
+use crate::error::ServiceError;
+use crate::resource_monitor::printnanny_unit_usage;
+use crate::video_recording_sync::sync_all_video_recordings;
+
+pub const SOAK_ITERATION_SECONDS: u64 = 60;
+// an upload sync pass runs every Nth iteration
+pub const SOAK_UPLOAD_EVERY: u64 = 10;
+// the worker listens on the local broker
+const SOAK_NATS_URI: &str = "nats://localhost:4223";
+const SOAK_NATS_TIMEOUT: Duration = Duration::from_secs(10);
+// rotated through, one request per iteration; all accept an empty payload
+const SOAK_NATS_SUBJECTS: &[&str] = &["status.summary", "schedule.list", "jobs.list"];
+// RSS growth between the first and last quarter of the run above this
+// threshold fails the memory-growth check
+pub const MAX_RSS_GROWTH_PERCENT: f64 = 25.0;
+// NATS failure rate above this threshold fails the delivery check
+pub const MAX_NATS_FAILURE_RATE: f64 = 0.01;
+const MAX_RECORDED_ERRORS: usize = 50;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SoakCheck {
+    pub name: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UnitSoakSummary {
+    pub unit: String,
+    pub cpu_seconds: f64,
+    pub max_rss_bytes: u64,
+    // RSS growth between the first and last quarter of the run; None when the
+    // run was too short to compare
+    pub rss_growth_percent: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SoakReport {
+    pub started: String,
+    pub finished: String,
+    pub hours: f64,
+    pub iterations: u64,
+    pub nats_requests: u64,
+    pub nats_failures: u64,
+    pub pipeline_restarts: u64,
+    pub upload_runs: u64,
+    pub upload_failures: u64,
+    pub units: Vec<UnitSoakSummary>,
+    pub checks: Vec<SoakCheck>,
+    pub passed: bool,
+    // first errors observed, capped so a flapping service cannot balloon the report
+    pub errors: Vec<String>,
+}
+
+// average of the first vs last quarter of samples; None below 8 samples
+fn rss_growth_percent(samples: &[u64]) -> Option<f64> {
+    if samples.len() < 8 {
+        return None;
+    }
+    let quarter = samples.len() / 4;
+    let first: f64 = samples[..quarter].iter().sum::<u64>() as f64 / quarter as f64;
+    let last: f64 = samples[samples.len() - quarter..].iter().sum::<u64>() as f64 / quarter as f64;
+    if first == 0.0 {
+        return None;
+    }
+    Some((last - first) / first * 100.0)
+}
+
+fn evaluate_checks(report: &SoakReport) -> Vec<SoakCheck> {
+    let nats_failure_rate = match report.nats_requests {
+        0 => 0.0,
+        n => report.nats_failures as f64 / n as f64,
+    };
+    let worst_growth = report
+        .units
+        .iter()
+        .filter_map(|unit| unit.rss_growth_percent)
+        .fold(f64::NEG_INFINITY, f64::max);
+    vec![
+        SoakCheck {
+            name: "nats-delivery".to_string(),
+            passed: nats_failure_rate <= MAX_NATS_FAILURE_RATE,
+            detail: format!(
+                "{} of {} requests failed",
+                report.nats_failures, report.nats_requests
+            ),
+        },
+        SoakCheck {
+            name: "pipeline-stability".to_string(),
+            passed: report.pipeline_restarts == 0,
+            detail: format!("{} pipeline restarts", report.pipeline_restarts),
+        },
+        SoakCheck {
+            name: "upload-reliability".to_string(),
+            passed: report.upload_failures == 0,
+            detail: format!(
+                "{} of {} upload sync passes failed",
+                report.upload_failures, report.upload_runs
+            ),
+        },
+        SoakCheck {
+            name: "memory-growth".to_string(),
+            passed: worst_growth.is_infinite() || worst_growth <= MAX_RSS_GROWTH_PERCENT,
+            detail: match worst_growth.is_infinite() {
+                true => "run too short to compare RSS quarters".to_string(),
+                false => format!("worst unit RSS growth {:.1}%", worst_growth),
+            },
+        },
+    ]
+}
+
+fn record_error(errors: &mut Vec<String>, error: String) {
+    warn!("{}", error);
+    if errors.len() < MAX_RECORDED_ERRORS {
+        errors.push(error);
+    }
+}
+
+pub async fn soak(settings: &PrintNannySettings, hours: f64) -> Result<SoakReport, ServiceError> {
+    let started = Utc::now();
+    let deadline = started + chrono::Duration::seconds((hours * 3600.0) as i64);
+    let hostname = sys_info::hostname()
+        .unwrap_or_else(|_| "localhost".to_string())
+        .to_lowercase();
+    let factory = PrintNannyPipelineFactory::default();
+
+    let nats_creds = settings.paths.cloud_nats_creds();
+    let nats_creds = match nats_creds.exists() {
+        true => Some(nats_creds),
+        false => None,
+    };
+
+    let mut iterations: u64 = 0;
+    let mut nats_requests: u64 = 0;
+    let mut nats_failures: u64 = 0;
+    let mut pipeline_restarts: u64 = 0;
+    let mut upload_runs: u64 = 0;
+    let mut upload_failures: u64 = 0;
+    let mut errors: Vec<String> = vec![];
+    // per-unit RSS samples (one per iteration) and first/last cumulative CPU
+    let mut rss_samples: HashMap<String, Vec<u64>> = HashMap::new();
+    let mut cpu_bounds: HashMap<String, (u64, u64)> = HashMap::new();
+
+    info!(
+        "Starting soak run for {} hours ({} iterations of {}s)",
+        hours,
+        (hours * 3600.0 / SOAK_ITERATION_SECONDS as f64) as u64,
+        SOAK_ITERATION_SECONDS
+    );
+    while Utc::now() < deadline {
+        iterations += 1;
+
+        // camera + detection pipelines must stay in the Playing state; a stall
+        // is counted and nudged back via the factory
+        for pipeline in [CAMERA_PIPELINE, INFERENCE_PIPELINE] {
+            match factory.pipeline_state(pipeline).await {
+                GstPipelineState::Playing => (),
+                state => {
+                    pipeline_restarts += 1;
+                    record_error(
+                        &mut errors,
+                        format!(
+                            "iteration {}: pipeline {} was {:?}, restarting",
+                            iterations, pipeline, state
+                        ),
+                    );
+                    if let Err(e) = factory.start_pipeline(pipeline).await {
+                        record_error(
+                            &mut errors,
+                            format!(
+                                "iteration {}: failed to restart {}: {}",
+                                iterations, pipeline, e
+                            ),
+                        );
+                    }
+                }
+            }
+        }
+
+        // periodic NATS command, rotating through read-only subjects
+        let subject = format!(
+            "pi.{}.{}",
+            hostname,
+            SOAK_NATS_SUBJECTS[(iterations as usize - 1) % SOAK_NATS_SUBJECTS.len()]
+        );
+        nats_requests += 1;
+        let request = async {
+            let nats_client = try_init_nats_client(SOAK_NATS_URI, &nats_creds, false).await?;
+            nats_client
+                .request(subject.clone(), "{}".into())
+                .await
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))
+        };
+        match timeout(SOAK_NATS_TIMEOUT, request).await {
+            Ok(Ok(_)) => (),
+            Ok(Err(e)) => {
+                nats_failures += 1;
+                record_error(
+                    &mut errors,
+                    format!(
+                        "iteration {}: NATS request {} failed: {}",
+                        iterations, subject, e
+                    ),
+                );
+            }
+            Err(_) => {
+                nats_failures += 1;
+                record_error(
+                    &mut errors,
+                    format!(
+                        "iteration {}: NATS request {} timed out after {:?}",
+                        iterations, subject, SOAK_NATS_TIMEOUT
+                    ),
+                );
+            }
+        }
+
+        // upload sync pass
+        if iterations % SOAK_UPLOAD_EVERY == 0 {
+            upload_runs += 1;
+            if let Err(e) = sync_all_video_recordings().await {
+                upload_failures += 1;
+                record_error(
+                    &mut errors,
+                    format!("iteration {}: upload sync failed: {}", iterations, e),
+                );
+            }
+        }
+
+        // resource sample
+        match printnanny_unit_usage() {
+            Ok(units) => {
+                for unit in units {
+                    rss_samples
+                        .entry(unit.unit.clone())
+                        .or_default()
+                        .push(unit.memory_rss_bytes);
+                    let bounds = cpu_bounds
+                        .entry(unit.unit)
+                        .or_insert((unit.cpu_usage_usec, unit.cpu_usage_usec));
+                    bounds.1 = unit.cpu_usage_usec;
+                }
+            }
+            Err(e) => record_error(
+                &mut errors,
+                format!("iteration {}: resource sample failed: {}", iterations, e),
+            ),
+        }
+
+        if Utc::now() >= deadline {
+            break;
+        }
+        sleep(Duration::from_secs(SOAK_ITERATION_SECONDS)).await;
+    }
+
+    let mut units: Vec<UnitSoakSummary> = rss_samples
+        .iter()
+        .map(|(unit, samples)| {
+            let (first_cpu, last_cpu) = cpu_bounds.get(unit).copied().unwrap_or((0, 0));
+            UnitSoakSummary {
+                unit: unit.clone(),
+                cpu_seconds: (last_cpu.saturating_sub(first_cpu)) as f64 / 1_000_000.0,
+                max_rss_bytes: samples.iter().copied().max().unwrap_or(0),
+                rss_growth_percent: rss_growth_percent(samples),
+            }
+        })
+        .collect();
+    units.sort_by(|a, b| a.unit.cmp(&b.unit));
+
+    let mut report = SoakReport {
+        started: started.to_rfc3339(),
+        finished: Utc::now().to_rfc3339(),
+        hours,
+        iterations,
+        nats_requests,
+        nats_failures,
+        pipeline_restarts,
+        upload_runs,
+        upload_failures,
+        units,
+        checks: vec![],
+        passed: false,
+        errors,
+    };
+    report.checks = evaluate_checks(&report);
+    report.passed = report.checks.iter().all(|check| check.passed);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty_report() -> SoakReport {
+        SoakReport {
+            started: Utc::now().to_rfc3339(),
+            finished: Utc::now().to_rfc3339(),
+            hours: 1.0,
+            iterations: 60,
+            nats_requests: 60,
+            nats_failures: 0,
+            pipeline_restarts: 0,
+            upload_runs: 6,
+            upload_failures: 0,
+            units: vec![],
+            checks: vec![],
+            passed: false,
+            errors: vec![],
+        }
+    }
+
+    #[test_log::test]
+    fn test_rss_growth_percent_compares_quarters() {
+        // flat usage: no growth
+        assert_eq!(rss_growth_percent(&[100; 16]), Some(0.0));
+        // last quarter doubled relative to the first
+        let mut samples = vec![100u64; 12];
+        samples.extend([200u64; 4]);
+        assert_eq!(rss_growth_percent(&samples), Some(100.0));
+        // too short to compare
+        assert_eq!(rss_growth_percent(&[100, 200, 300]), None);
+    }
+
+    #[test_log::test]
+    fn test_checks_fail_on_leaks_and_delivery_errors() {
+        let mut report = empty_report();
+        report.nats_failures = 10;
+        report.units = vec![UnitSoakSummary {
+            unit: "printnanny-vision.service".to_string(),
+            cpu_seconds: 10.0,
+            max_rss_bytes: 1024,
+            rss_growth_percent: Some(40.0),
+        }];
+        let checks = evaluate_checks(&report);
+        let failed: Vec<&str> = checks
+            .iter()
+            .filter(|c| !c.passed)
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(failed, vec!["nats-delivery", "memory-growth"]);
+
+        let report = empty_report();
+        let checks = evaluate_checks(&report);
+        assert!(checks.iter().all(|c| c.passed));
+    }
+}
@@ -0,0 +1,206 @@
+// Session-scoped tokens for sharing the live stream. Minting a token grants
+// time-limited access to the named endpoints (HLS playlist, WebRTC signaling,
+// RTSP); the nginx/gateway side validates the signature and expiry, so a link
+// shared with a friend stops working when the token expires instead of
+// exposing the stream permanently on the LAN. Tokens are HS256 JWTs signed
+// with a device-local key generated on first use under paths.creds().
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use log::info;
+use rand::distributions::Alphanumeric;
+use rand::{thread_rng, Rng};
+use serde::{Deserialize, Serialize};
+
+use printnanny_settings::paths::PrintNannyPaths;
+
+use crate::error::ServiceError;
+
+pub const STREAM_TOKEN_KEY_FILENAME: &str = "stream-token.key";
+pub const DEFAULT_STREAM_TOKEN_TTL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StreamEndpoint {
+    Hls,
+    Mjpeg,
+    Webrtc,
+    Rtsp,
+}
+
+impl std::str::FromStr for StreamEndpoint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hls" => Ok(StreamEndpoint::Hls),
+            "mjpeg" => Ok(StreamEndpoint::Mjpeg),
+            "webrtc" => Ok(StreamEndpoint::Webrtc),
+            "rtsp" => Ok(StreamEndpoint::Rtsp),
+            other => Err(format!("Invalid stream endpoint: {}", other)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StreamTokenClaims {
+    pub sub: String,
+    // unix seconds
+    pub iat: u64,
+    pub exp: u64,
+    // endpoints this token grants access to
+    pub endpoints: Vec<StreamEndpoint>,
+}
+
+// the signed token plus everything a caller needs to build a share link
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StreamToken {
+    pub token: String,
+    pub expires_at: u64,
+    pub endpoints: Vec<StreamEndpoint>,
+}
+
+// load the device-local signing key, generating one on first use
+fn signing_key(paths: &PrintNannyPaths) -> Result<Vec<u8>, ServiceError> {
+    let key_file = paths.creds().join(STREAM_TOKEN_KEY_FILENAME);
+    match std::fs::read(&key_file) {
+        Ok(key) => Ok(key),
+        Err(_) => {
+            let key: String = thread_rng()
+                .sample_iter(&Alphanumeric)
+                .take(64)
+                .map(char::from)
+                .collect();
+            printnanny_settings::atomic_write::atomic_write_sync(&key_file, key.as_bytes())?;
+            info!("Generated stream token signing key {}", key_file.display());
+            Ok(key.into_bytes())
+        }
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+pub fn issue_stream_token(
+    paths: &PrintNannyPaths,
+    ttl: Duration,
+    endpoints: Vec<StreamEndpoint>,
+) -> Result<StreamToken, ServiceError> {
+    let key = signing_key(paths)?;
+    let iat = unix_now();
+    let exp = iat + ttl.as_secs();
+    let claims = StreamTokenClaims {
+        sub: "printnanny-stream".to_string(),
+        iat,
+        exp,
+        endpoints: endpoints.clone(),
+    };
+    let token = encode(&Header::default(), &claims, &EncodingKey::from_secret(&key))?;
+    Ok(StreamToken {
+        token,
+        expires_at: exp,
+        endpoints,
+    })
+}
+
+// gateway-side check: signature, expiry, and endpoint scope
+pub fn validate_stream_token(
+    paths: &PrintNannyPaths,
+    token: &str,
+    endpoint: StreamEndpoint,
+) -> Result<StreamTokenClaims, ServiceError> {
+    let key = signing_key(paths)?;
+    let claims = decode::<StreamTokenClaims>(
+        token,
+        &DecodingKey::from_secret(&key),
+        &Validation::default(),
+    )?
+    .claims;
+    match claims.endpoints.contains(&endpoint) {
+        true => Ok(claims),
+        false => Err(ServiceError::StreamTokenScopeError {
+            endpoint: format!("{:?}", endpoint).to_lowercase(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_paths(dir: &tempfile::TempDir) -> PrintNannyPaths {
+        PrintNannyPaths {
+            state_dir: dir.path().to_path_buf(),
+            ..PrintNannyPaths::default()
+        }
+    }
+
+    #[test_log::test]
+    fn test_issue_and_validate_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = test_paths(&dir);
+        let issued = issue_stream_token(
+            &paths,
+            DEFAULT_STREAM_TOKEN_TTL,
+            vec![StreamEndpoint::Hls, StreamEndpoint::Webrtc],
+        )
+        .unwrap();
+        let claims = validate_stream_token(&paths, &issued.token, StreamEndpoint::Hls).unwrap();
+        assert_eq!(claims.endpoints, issued.endpoints);
+        // the key persists, so validation works across processes
+        assert!(dir
+            .path()
+            .join("creds")
+            .join(STREAM_TOKEN_KEY_FILENAME)
+            .exists());
+    }
+
+    #[test_log::test]
+    fn test_out_of_scope_endpoint_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = test_paths(&dir);
+        let issued =
+            issue_stream_token(&paths, DEFAULT_STREAM_TOKEN_TTL, vec![StreamEndpoint::Hls])
+                .unwrap();
+        let result = validate_stream_token(&paths, &issued.token, StreamEndpoint::Rtsp);
+        assert!(matches!(
+            result,
+            Err(ServiceError::StreamTokenScopeError { .. })
+        ));
+    }
+
+    #[test_log::test]
+    fn test_expired_token_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = test_paths(&dir);
+        let key = signing_key(&paths).unwrap();
+        let claims = StreamTokenClaims {
+            sub: "printnanny-stream".to_string(),
+            iat: unix_now() - 7200,
+            exp: unix_now() - 3600,
+            endpoints: vec![StreamEndpoint::Hls],
+        };
+        let token = encode(&Header::default(), &claims, &EncodingKey::from_secret(&key)).unwrap();
+        assert!(validate_stream_token(&paths, &token, StreamEndpoint::Hls).is_err());
+    }
+
+    #[test_log::test]
+    fn test_foreign_signature_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let other_dir = tempfile::tempdir().unwrap();
+        let issued = issue_stream_token(
+            &test_paths(&other_dir),
+            DEFAULT_STREAM_TOKEN_TTL,
+            vec![StreamEndpoint::Hls],
+        )
+        .unwrap();
+        // signed with a different device key
+        assert!(
+            validate_stream_token(&test_paths(&dir), &issued.token, StreamEndpoint::Hls).is_err()
+        );
+    }
+}
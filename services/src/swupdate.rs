@@ -1,11 +1,35 @@
 use anyhow::Result;
 use async_process::{Command, Output};
-use log::info;
+use log::{info, warn};
 use serde::{Deserialize, Serialize};
 use std::fs::File;
 use std::path::PathBuf;
 use tempfile::Builder;
 
+use printnanny_edge_db::jobs::{Job, JobStatus};
+use printnanny_settings::hooks::HookEvent;
+use printnanny_settings::printnanny::PrintNannySettings;
+
+use crate::lifecycle::DeviceLifecycleState;
+
+// job progress updates are best-effort: a missing edge db must not abort an
+// update that is otherwise able to run
+fn track_progress(sqlite_connection: &str, job: &Option<Job>, percent: i32, detail: &str) {
+    if let Some(job) = job {
+        if let Err(e) = Job::update_progress(sqlite_connection, &job.id, percent, Some(detail)) {
+            warn!("Failed to update Job id={} error={}", job.id, e);
+        }
+    }
+}
+
+fn track_finished(sqlite_connection: &str, job: &Option<Job>, status: JobStatus, result: &str) {
+    if let Some(job) = job {
+        if let Err(e) = Job::finish(sqlite_connection, &job.id, status, result) {
+            warn!("Failed to finish Job id={} error={}", job.id, e);
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
 pub struct Swupdate {
     swu_url: String,
@@ -41,12 +65,99 @@ impl Swupdate {
     }
 
     pub async fn run(&self) -> Result<Output> {
+        let settings = PrintNannySettings::new().await?;
+        let sqlite_connection = settings.paths.db().display().to_string();
+        // persist a job row so the outcome survives a requester disconnect
+        // (and the reboot that follows a successful update)
+        let job = match Job::create(
+            &sqlite_connection,
+            "ota",
+            Some(&format!("version={}", self.version)),
+        ) {
+            Ok(job) => Some(job),
+            Err(e) => {
+                warn!("Failed to create ota Job error={}", e);
+                None
+            }
+        };
+        // handlers refuse settings writes while the device is updating
+        crate::lifecycle::transition(
+            &settings.paths,
+            DeviceLifecycleState::Updating,
+            &format!("Applying PrintNanny OS update version={}", self.version),
+        )?;
+        let result = self.run_update(&settings, &sqlite_connection, &job).await;
+        match &result {
+            Ok(output) if output.status.success() => {
+                track_finished(
+                    &sqlite_connection,
+                    &job,
+                    JobStatus::Success,
+                    &format!("Update version={} applied", self.version),
+                );
+                crate::lifecycle::restore_previous(
+                    &settings.paths,
+                    &format!("Update version={} applied", self.version),
+                )?;
+            }
+            Ok(output) => {
+                track_finished(
+                    &sqlite_connection,
+                    &job,
+                    JobStatus::Failed,
+                    &format!("swupdate exited with {}", output.status),
+                );
+                crate::lifecycle::transition(
+                    &settings.paths,
+                    DeviceLifecycleState::Degraded,
+                    &format!("swupdate exited with {}", output.status),
+                )?;
+            }
+            Err(e) => {
+                track_finished(
+                    &sqlite_connection,
+                    &job,
+                    JobStatus::Failed,
+                    &format!("swupdate failed to run: {}", e),
+                );
+                crate::lifecycle::transition(
+                    &settings.paths,
+                    DeviceLifecycleState::Degraded,
+                    &format!("swupdate failed to run: {}", e),
+                )?;
+            }
+        };
+        result
+    }
+
+    async fn run_update(
+        &self,
+        settings: &PrintNannySettings,
+        sqlite_connection: &str,
+        job: &Option<Job>,
+    ) -> Result<Output> {
+        // snapshot settings repo + edge db so the update can be rolled back with
+        // `printnanny restore --pre-update`
+        track_progress(sqlite_connection, job, 10, "pre-update snapshot");
+        crate::pre_update::snapshot().await?;
+
+        track_progress(sqlite_connection, job, 25, "downloading update");
         let (path, _f) = self.download_file().await?;
 
+        track_progress(sqlite_connection, job, 50, "applying update");
         let output = Command::new("swupdate")
             .args(["-v", "-i", path.to_str().unwrap()])
             .output()
             .await?;
+
+        if output.status.success() {
+            crate::hooks::run_hooks(
+                settings,
+                HookEvent::UpdateApplied,
+                &serde_json::json!({ "version": self.version }),
+            )
+            .await;
+        }
         Ok(output)
     }
 }
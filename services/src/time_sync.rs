@@ -0,0 +1,118 @@
+// Clock sanity probe for Pis with dead RTC batteries. JWT auth and event
+// ordering break silently when the system clock drifts, so the outbox relay
+// probes chrony each cycle: rows enqueued while the clock is suspect are
+// flagged clock_suspect and subscribers fall back to the monotonic seq column.
+use log::warn;
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+use crate::error::TimeSyncError;
+
+pub const SKEW_WARNING_THRESHOLD_SECONDS: f64 = 2.0;
+
+// chrony reports reference id 7F7F0101 (local clock) when not synchronized
+const CHRONY_LOCAL_CLOCK_REF_ID: &str = "7F7F0101";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TimeSyncStatus {
+    pub synchronized: bool,
+    pub skew_seconds: f64,
+    pub skew_exceeds_threshold: bool,
+}
+
+impl TimeSyncStatus {
+    pub fn clock_suspect(&self) -> bool {
+        !self.synchronized || self.skew_exceeds_threshold
+    }
+}
+
+// parse `chronyc -c tracking` csv output
+// fields: ref id, ref name, stratum, ref time, system time offset (seconds), ...
+pub fn parse_chronyc_tracking(output: &str) -> Result<TimeSyncStatus, TimeSyncError> {
+    let fields: Vec<&str> = output.trim().split(',').collect();
+    if fields.len() < 5 {
+        return Err(TimeSyncError::ParseError {
+            output: output.to_string(),
+        });
+    }
+    let stratum: u32 = fields[2].parse().map_err(|_| TimeSyncError::ParseError {
+        output: output.to_string(),
+    })?;
+    let skew_seconds: f64 = fields[4].parse().map_err(|_| TimeSyncError::ParseError {
+        output: output.to_string(),
+    })?;
+    let synchronized = stratum > 0 && fields[0] != CHRONY_LOCAL_CLOCK_REF_ID;
+    Ok(TimeSyncStatus {
+        synchronized,
+        skew_seconds,
+        skew_exceeds_threshold: skew_seconds.abs() > SKEW_WARNING_THRESHOLD_SECONDS,
+    })
+}
+
+pub async fn probe_time_sync() -> Result<TimeSyncStatus, TimeSyncError> {
+    let output = Command::new("chronyc")
+        .args(["-c", "tracking"])
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(TimeSyncError::CommandError {
+            cmd: "chronyc -c tracking".to_string(),
+            code: output.status.code(),
+            stdout: String::from_utf8(output.stdout)?,
+            stderr: String::from_utf8(output.stderr)?,
+        });
+    }
+    let stdout = String::from_utf8(output.stdout)?;
+    parse_chronyc_tracking(&stdout)
+}
+
+// probe chrony and propagate the result to the outbox clock_suspect flag
+pub async fn check_clock_skew() -> Result<TimeSyncStatus, TimeSyncError> {
+    let status = probe_time_sync().await?;
+    let suspect = status.clock_suspect();
+    printnanny_edge_db::outbox::set_clock_suspect(suspect);
+    if suspect {
+        warn!(
+            "System clock is suspect: synchronized={} skew_seconds={}",
+            status.synchronized, status.skew_seconds
+        );
+    }
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_parse_chronyc_tracking_synchronized() {
+        let output = "A9FEA97B,169.254.169.123,3,1678112983.356588,-0.000006,0.000086,0.000412,0.000035,-0.004,0.012,0.00013,0.000563,64.2,Normal";
+        let status = parse_chronyc_tracking(output).unwrap();
+        assert!(status.synchronized);
+        assert!(!status.skew_exceeds_threshold);
+        assert!(!status.clock_suspect());
+    }
+
+    #[test_log::test]
+    fn test_parse_chronyc_tracking_unsynchronized() {
+        let output = "7F7F0101,,0,1678112983.356588,0.000000,0.000000,0.000000,0.000000,0.000,0.000,0.00000,0.000000,0.0,Not synchronised";
+        let status = parse_chronyc_tracking(output).unwrap();
+        assert!(!status.synchronized);
+        assert!(status.clock_suspect());
+    }
+
+    #[test_log::test]
+    fn test_parse_chronyc_tracking_skew_exceeds_threshold() {
+        let output = "A9FEA97B,169.254.169.123,3,1678112983.356588,-12.5,0.000086,0.000412,0.000035,-0.004,0.012,0.00013,0.000563,64.2,Normal";
+        let status = parse_chronyc_tracking(output).unwrap();
+        assert!(status.synchronized);
+        assert!(status.skew_exceeds_threshold);
+        assert!(status.clock_suspect());
+    }
+
+    #[test_log::test]
+    fn test_parse_chronyc_tracking_garbage_rejected() {
+        let output = "506 Cannot talk to daemon";
+        assert!(parse_chronyc_tracking(output).is_err());
+    }
+}
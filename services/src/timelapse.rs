@@ -0,0 +1,213 @@
+// Timelapse assembly: map captured JPEG frames onto the output timeline
+// according to the configured speed ramp, then encode them at the configured
+// framerate with optional deflicker/stabilization filters. Rendering runs
+// through a one-shot ffmpeg invocation (already on the OS image for the
+// OctoPrint timelapse plugin) because its deflicker/deshake filters have no
+// gstreamer equivalent; constant-rate assembly without them is jittery on
+// bed-slinger printers.
+use std::path::{Path, PathBuf};
+
+use async_process::Command;
+use log::info;
+
+use printnanny_settings::printnanny::PrintNannySettings;
+use printnanny_settings::timelapse::{SpeedRampProfile, TimelapseRenderSettings};
+
+use crate::error::ServiceError;
+
+// easing curve value for normalized output position t in [0, 1]: the result
+// is the normalized source position, so a slowly-growing curve lingers on
+// early frames
+fn ramp(profile: SpeedRampProfile, t: f64) -> f64 {
+    match profile {
+        SpeedRampProfile::Constant => t,
+        SpeedRampProfile::EaseIn => t * t,
+        SpeedRampProfile::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+        SpeedRampProfile::EaseInOut => t * t * (3.0 - 2.0 * t),
+    }
+}
+
+// source frame index for each output frame; monotonic, covering the full
+// source sequence for every profile
+pub fn frame_mapping(profile: SpeedRampProfile, frame_count: usize) -> Vec<usize> {
+    if frame_count < 2 {
+        return (0..frame_count).collect();
+    }
+    (0..frame_count)
+        .map(|i| {
+            let t = i as f64 / (frame_count - 1) as f64;
+            let source = ramp(profile, t) * (frame_count - 1) as f64;
+            (source.round() as usize).min(frame_count - 1)
+        })
+        .collect()
+}
+
+// captured frames in capture order (zero-padded filenames sort correctly)
+fn list_frames(frames_dir: &Path) -> Result<Vec<PathBuf>, ServiceError> {
+    let mut frames: Vec<PathBuf> = std::fs::read_dir(frames_dir)
+        .map_err(|error| crate::error::IoError::ReadIOError {
+            path: frames_dir.display().to_string(),
+            error,
+        })?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .map(|ext| ext == "jpg" || ext == "jpeg")
+                .unwrap_or(false)
+        })
+        .collect();
+    frames.sort();
+    Ok(frames)
+}
+
+// hard-link the ramped frame sequence into the staging dir as a contiguous
+// %08d.jpg sequence; the ramp repeats/skips source frames, the encode stays
+// constant-rate
+fn stage_frames(
+    frames: &[PathBuf],
+    staging_dir: &Path,
+    profile: SpeedRampProfile,
+) -> Result<usize, ServiceError> {
+    let mapping = frame_mapping(profile, frames.len());
+    for (i, source_index) in mapping.iter().enumerate() {
+        let dest = staging_dir.join(format!("{:08}.jpg", i));
+        let source = &frames[*source_index];
+        // fall back to a copy for staging dirs on a different filesystem
+        if std::fs::hard_link(source, &dest).is_err() {
+            std::fs::copy(source, &dest).map_err(|error| crate::error::IoError::CopyIOError {
+                src: source.clone(),
+                dest: dest.clone(),
+                error,
+            })?;
+        }
+    }
+    Ok(mapping.len())
+}
+
+// ffmpeg invocation for the assembly pass; deflicker smooths auto-exposure
+// changes, deshake compensates frame-to-frame camera movement
+pub fn ffmpeg_command_string(
+    staging_dir: &Path,
+    output: &Path,
+    render: &TimelapseRenderSettings,
+) -> String {
+    let mut filters: Vec<&str> = vec![];
+    if render.deflicker {
+        filters.push("deflicker");
+    }
+    if render.stabilization {
+        filters.push("deshake");
+    }
+    let filter_arg = match filters.is_empty() {
+        true => "".to_string(),
+        false => format!("-vf {} ", filters.join(",")),
+    };
+    format!(
+        "ffmpeg -y -framerate {framerate} -i {staging}/%08d.jpg {filter_arg}-c:v libx264 -pix_fmt yuv420p {output}",
+        framerate = render.framerate,
+        staging = staging_dir.display(),
+        output = output.display(),
+    )
+}
+
+// assemble the frames in `frames_dir` into `output`, using the render options
+// for `profile` (falling back to the configured defaults)
+pub async fn render_timelapse(
+    settings: &PrintNannySettings,
+    frames_dir: &Path,
+    output: &Path,
+    profile: Option<&str>,
+) -> Result<PathBuf, ServiceError> {
+    let render = settings.timelapse.resolve(profile);
+    let frames = list_frames(frames_dir)?;
+    if frames.is_empty() {
+        return Err(ServiceError::TimelapseRenderError {
+            detail: format!("No frames found in {}", frames_dir.display()),
+        });
+    }
+    let staging_dir = settings
+        .paths
+        .timelapse()
+        .join(format!(".staging-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&staging_dir).map_err(|error| crate::error::IoError::WriteIOError {
+        path: staging_dir.display().to_string(),
+        error,
+    })?;
+    let staged = stage_frames(&frames, &staging_dir, render.speed_ramp)?;
+    let cmd = ffmpeg_command_string(&staging_dir, output, render);
+    info!(
+        "Rendering timelapse from {} frames ({} staged) with: {}",
+        frames.len(),
+        staged,
+        cmd
+    );
+    let result = Command::new("sh")
+        .args(["-c", &cmd])
+        .output()
+        .await
+        .map_err(|e| ServiceError::TimelapseRenderError {
+            detail: e.to_string(),
+        })?;
+    // the staging sequence is only needed for the encode
+    std::fs::remove_dir_all(&staging_dir).ok();
+    if !result.status.success() {
+        tokio::fs::remove_file(output).await.ok();
+        return Err(ServiceError::TimelapseRenderError {
+            detail: String::from_utf8_lossy(&result.stderr).to_string(),
+        });
+    }
+    Ok(output.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_frame_mapping_is_monotonic_and_covers_sequence() {
+        for profile in [
+            SpeedRampProfile::Constant,
+            SpeedRampProfile::EaseIn,
+            SpeedRampProfile::EaseOut,
+            SpeedRampProfile::EaseInOut,
+        ] {
+            let mapping = frame_mapping(profile, 100);
+            assert_eq!(mapping.len(), 100);
+            assert_eq!(mapping[0], 0);
+            assert_eq!(mapping[99], 99);
+            assert!(mapping.windows(2).all(|w| w[0] <= w[1]));
+        }
+    }
+
+    #[test_log::test]
+    fn test_ease_in_lingers_on_early_frames() {
+        let mapping = frame_mapping(SpeedRampProfile::EaseIn, 100);
+        // halfway through the output, an ease_in ramp has only covered a
+        // quarter of the print
+        assert!(mapping[50] < 30);
+    }
+
+    #[test_log::test]
+    fn test_ffmpeg_filters_follow_render_options() {
+        let mut render = TimelapseRenderSettings::default();
+        let cmd = ffmpeg_command_string(
+            Path::new("/tmp/staging"),
+            Path::new("/tmp/out.mp4"),
+            &render,
+        );
+        assert!(!cmd.contains("-vf"));
+        assert!(cmd.contains("-framerate 30"));
+
+        render.deflicker = true;
+        render.stabilization = true;
+        render.framerate = 60;
+        let cmd = ffmpeg_command_string(
+            Path::new("/tmp/staging"),
+            Path::new("/tmp/out.mp4"),
+            &render,
+        );
+        assert!(cmd.contains("-vf deflicker,deshake"));
+        assert!(cmd.contains("-framerate 60"));
+    }
+}
@@ -0,0 +1,277 @@
+// Preflight checks gating OTA updates (swupdate). A half-applied update is the
+// most expensive failure mode a device has, so before a swupdate command is
+// accepted the advisor verifies the resources the update will consume:
+// the .swu download lands in a tempdir on the rootfs and the pre-update
+// snapshot is written to the data partition (swupdate writes the inactive
+// slot raw, so these two filesystems are what fill up mid-update), the
+// device must not be running on battery, no print should be recording, and
+// the update server must be reachable with enough bandwidth to finish the
+// download in a reasonable time. Failures are returned as a structured
+// refusal instead of starting an update that will fail halfway.
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use futures::StreamExt;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use sysinfo::{DiskExt, System, SystemExt};
+
+use printnanny_edge_db::video_recording::VideoRecording;
+use printnanny_settings::printnanny::PrintNannySettings;
+
+use crate::power;
+
+// .swu images are ~600MB and are downloaded to a tempdir on the rootfs
+const MIN_ROOTFS_FREE_BYTES: u64 = 1_000_000_000;
+// pre-update settings/db snapshot is written to the data partition
+const MIN_DATAFS_FREE_BYTES: u64 = 256_000_000;
+// below ~2 Mbit/s a 600MB download stalls for over half an hour
+const MIN_BANDWIDTH_BYTES_PER_SEC: u64 = 250_000;
+const BANDWIDTH_SAMPLE_BYTES: u64 = 2_000_000;
+const BANDWIDTH_SAMPLE_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PreflightCheck {
+    pub name: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdatePreflightReport {
+    pub passed: bool,
+    pub checks: Vec<PreflightCheck>,
+}
+
+impl UpdatePreflightReport {
+    fn from_checks(checks: Vec<PreflightCheck>) -> Self {
+        UpdatePreflightReport {
+            passed: checks.iter().all(|c| c.passed),
+            checks,
+        }
+    }
+
+    // one-line summary of failed preconditions for logs and command results
+    pub fn failed_summary(&self) -> String {
+        self.checks
+            .iter()
+            .filter(|c| !c.passed)
+            .map(|c| format!("{}: {}", c.name, c.detail))
+            .collect::<Vec<String>>()
+            .join("; ")
+    }
+}
+
+// free bytes on the filesystem holding path, using the longest matching
+// mount point (same source sysinfo uses for the system-info report)
+fn disk_free_bytes(sys: &System, path: &Path) -> Option<u64> {
+    sys.disks()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| disk.available_space())
+}
+
+fn free_space_check(name: &str, free: Option<u64>, min_bytes: u64) -> PreflightCheck {
+    match free {
+        Some(free) => PreflightCheck {
+            name: name.to_string(),
+            passed: free >= min_bytes,
+            detail: format!(
+                "{}MB free, {}MB required",
+                free / 1_000_000,
+                min_bytes / 1_000_000
+            ),
+        },
+        None => PreflightCheck {
+            name: name.to_string(),
+            passed: false,
+            detail: "filesystem not found".to_string(),
+        },
+    }
+}
+
+async fn power_check(settings: &PrintNannySettings) -> PreflightCheck {
+    let name = "power".to_string();
+    if !settings.ups.enabled {
+        return PreflightCheck {
+            name,
+            passed: true,
+            detail: "ups monitoring disabled".to_string(),
+        };
+    }
+    match power::read_status(&settings.ups).await {
+        Some(status) if status.on_battery => PreflightCheck {
+            name,
+            passed: false,
+            detail: format!(
+                "on battery (charge: {})",
+                status
+                    .charge_percent
+                    .map(|c| format!("{}%", c))
+                    .unwrap_or_else(|| "unknown".to_string())
+            ),
+        },
+        Some(_) => PreflightCheck {
+            name,
+            passed: true,
+            detail: "on mains power".to_string(),
+        },
+        // an unreadable UPS source is treated as no change by the power
+        // monitor; an update should not start on unknown power either
+        None => PreflightCheck {
+            name,
+            passed: false,
+            detail: "ups status could not be read".to_string(),
+        },
+    }
+}
+
+fn print_active_check(settings: &PrintNannySettings) -> PreflightCheck {
+    let name = "print".to_string();
+    let sqlite_connection = settings.paths.db().display().to_string();
+    match VideoRecording::get_current(&sqlite_connection) {
+        Ok(Some(recording)) => PreflightCheck {
+            name,
+            passed: false,
+            detail: format!("recording {} is in progress", recording.id),
+        },
+        Ok(None) => PreflightCheck {
+            name,
+            passed: true,
+            detail: "no print recording in progress".to_string(),
+        },
+        // a fresh or missing db should not block updates
+        Err(e) => {
+            warn!("Preflight print check could not query edge db error={}", e);
+            PreflightCheck {
+                name,
+                passed: true,
+                detail: format!("edge db unavailable: {}", e),
+            }
+        }
+    }
+}
+
+fn bandwidth_check(sampled_bytes: u64, elapsed: Duration) -> PreflightCheck {
+    let rate = (sampled_bytes as f64 / elapsed.as_secs_f64()) as u64;
+    PreflightCheck {
+        name: "bandwidth".to_string(),
+        passed: rate >= MIN_BANDWIDTH_BYTES_PER_SEC,
+        detail: format!(
+            "{}KB/s to update server, {}KB/s required",
+            rate / 1000,
+            MIN_BANDWIDTH_BYTES_PER_SEC / 1000
+        ),
+    }
+}
+
+// sample download throughput from the actual update server: stream the first
+// BANDWIDTH_SAMPLE_BYTES of the .swu and time it
+async fn sample_bandwidth(swu_url: &str) -> PreflightCheck {
+    let started = Instant::now();
+    let sampled = tokio::time::timeout(BANDWIDTH_SAMPLE_TIMEOUT, async {
+        let response = reqwest::get(swu_url).await?;
+        let mut stream = response.bytes_stream();
+        let mut sampled_bytes: u64 = 0;
+        while let Some(chunk) = stream.next().await {
+            sampled_bytes += chunk?.len() as u64;
+            if sampled_bytes >= BANDWIDTH_SAMPLE_BYTES {
+                break;
+            }
+        }
+        Ok::<u64, reqwest::Error>(sampled_bytes)
+    })
+    .await;
+    match sampled {
+        Ok(Ok(sampled_bytes)) => bandwidth_check(sampled_bytes, started.elapsed()),
+        Ok(Err(e)) => PreflightCheck {
+            name: "bandwidth".to_string(),
+            passed: false,
+            detail: format!("update server unreachable: {}", e),
+        },
+        Err(_) => PreflightCheck {
+            name: "bandwidth".to_string(),
+            passed: false,
+            detail: format!(
+                "sampled less than {}MB in {}s",
+                BANDWIDTH_SAMPLE_BYTES / 1_000_000,
+                BANDWIDTH_SAMPLE_TIMEOUT.as_secs()
+            ),
+        },
+    }
+}
+
+pub async fn preflight(settings: &PrintNannySettings, swu_url: &str) -> UpdatePreflightReport {
+    let mut sys = System::new();
+    sys.refresh_disks_list();
+    sys.refresh_disks();
+    let checks = vec![
+        free_space_check(
+            "rootfs free space",
+            disk_free_bytes(&sys, Path::new("/")),
+            MIN_ROOTFS_FREE_BYTES,
+        ),
+        free_space_check(
+            "data partition free space",
+            disk_free_bytes(&sys, &settings.paths.state_dir),
+            MIN_DATAFS_FREE_BYTES,
+        ),
+        power_check(settings).await,
+        print_active_check(settings),
+        sample_bandwidth(swu_url).await,
+    ];
+    UpdatePreflightReport::from_checks(checks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_report_fails_when_any_check_fails() {
+        let report = UpdatePreflightReport::from_checks(vec![
+            PreflightCheck {
+                name: "rootfs free space".to_string(),
+                passed: true,
+                detail: "1000MB free, 1000MB required".to_string(),
+            },
+            PreflightCheck {
+                name: "power".to_string(),
+                passed: false,
+                detail: "on battery (charge: 40%)".to_string(),
+            },
+        ]);
+        assert!(!report.passed);
+        assert_eq!(report.failed_summary(), "power: on battery (charge: 40%)");
+    }
+
+    #[test_log::test]
+    fn test_free_space_check_thresholds() {
+        assert!(
+            free_space_check(
+                "rootfs free space",
+                Some(MIN_ROOTFS_FREE_BYTES),
+                MIN_ROOTFS_FREE_BYTES
+            )
+            .passed
+        );
+        assert!(
+            !free_space_check(
+                "rootfs free space",
+                Some(MIN_ROOTFS_FREE_BYTES - 1),
+                MIN_ROOTFS_FREE_BYTES
+            )
+            .passed
+        );
+        assert!(!free_space_check("rootfs free space", None, MIN_ROOTFS_FREE_BYTES).passed);
+    }
+
+    #[test_log::test]
+    fn test_bandwidth_check_rate() {
+        // 2MB in 2s = 1MB/s, above the floor
+        assert!(bandwidth_check(2_000_000, Duration::from_secs(2)).passed);
+        // 2MB in 20s = 100KB/s, below the floor
+        assert!(!bandwidth_check(2_000_000, Duration::from_secs(20)).passed);
+    }
+}
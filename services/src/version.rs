@@ -0,0 +1,242 @@
+// Structured version report backing `printnanny version --all`. Collects the
+// component versions compiled into this build (cli, nats worker, pipeline
+// descriptions, settings schema, edge db schema), probes the device for the
+// installed gst plugin and OS image, and flags known-incompatible combinations
+// by consulting the compatibility matrix shipped in this crate
+// (compatibility_matrix.toml).
+use async_process::Command;
+use log::warn;
+use printnanny_settings::toml;
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+
+use crate::os_release::OsRelease;
+
+pub const COMPATIBILITY_MATRIX: &str = include_str!("compatibility_matrix.toml");
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompatibilityRule {
+    pub component: String,
+    pub version_req: String,
+    pub other: String,
+    pub other_req: String,
+    pub reason: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompatibilityMatrix {
+    // cloud API compatibility level this build was generated against
+    pub cloud_api_compat: String,
+    #[serde(default, rename = "rule")]
+    pub rules: Vec<CompatibilityRule>,
+}
+
+impl CompatibilityMatrix {
+    pub fn load() -> Result<Self, toml::de::Error> {
+        toml::from_str(COMPATIBILITY_MATRIX)
+    }
+}
+
+// a compatibility rule that matched the versions found on this device
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Incompatibility {
+    pub component: String,
+    pub version: String,
+    pub other: String,
+    pub other_version: String,
+    pub reason: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionReport {
+    pub cli: String,
+    pub git: String,
+    pub nats_worker: String,
+    pub services: String,
+    pub gst_pipelines: String,
+    // from gst-inspect-1.0; None when the plugin is not installed
+    pub gst_plugin: Option<String>,
+    pub settings_schema: String,
+    // newest embedded edge db migration
+    pub db_schema: Option<String>,
+    // VERSION_ID and BUILD_ID from /etc/os-release; None outside a
+    // PrintNanny OS image
+    pub os_image: Option<String>,
+    pub os_build_id: Option<String>,
+    pub cloud_api_compat: String,
+    pub incompatibilities: Vec<Incompatibility>,
+}
+
+// strip pre-release/commit suffixes (gst plugin versions look like
+// 0.6.1-abc123) so versions compare as plain releases
+fn parse_release(version: &str) -> Option<Version> {
+    let release = version.split(['-', '+']).next().unwrap_or(version);
+    Version::parse(release).ok()
+}
+
+fn check_matrix(
+    matrix: &CompatibilityMatrix,
+    versions: &[(&str, Option<&str>)],
+) -> Vec<Incompatibility> {
+    let version_of = |component: &str| -> Option<&str> {
+        versions
+            .iter()
+            .find(|(name, _)| *name == component)
+            .and_then(|(_, version)| *version)
+    };
+    matrix
+        .rules
+        .iter()
+        .filter_map(|rule| {
+            let version = version_of(&rule.component)?;
+            let other_version = version_of(&rule.other)?;
+            let req = match VersionReq::parse(&rule.version_req) {
+                Ok(req) => req,
+                Err(e) => {
+                    warn!(
+                        "Invalid version_req in compatibility matrix: {} {}",
+                        rule.version_req, e
+                    );
+                    return None;
+                }
+            };
+            let other_req = match VersionReq::parse(&rule.other_req) {
+                Ok(req) => req,
+                Err(e) => {
+                    warn!(
+                        "Invalid other_req in compatibility matrix: {} {}",
+                        rule.other_req, e
+                    );
+                    return None;
+                }
+            };
+            if req.matches(&parse_release(version)?)
+                && other_req.matches(&parse_release(other_version)?)
+            {
+                Some(Incompatibility {
+                    component: rule.component.clone(),
+                    version: version.to_string(),
+                    other: rule.other.clone(),
+                    other_version: other_version.to_string(),
+                    reason: rule.reason.clone(),
+                })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+// `gst-inspect-1.0 printnanny` prints a "Version" line for the installed
+// plugin; the plugin ships in the OS image, so the CLI cannot assume it links
+// the same build
+async fn gst_plugin_version() -> Option<String> {
+    let output = Command::new("gst-inspect-1.0")
+        .arg("printnanny")
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout.lines().find_map(|line| {
+        line.trim()
+            .strip_prefix("Version")
+            .map(|rest| rest.trim().to_string())
+    })
+}
+
+// cli and nats worker versions are passed in by the caller: this crate does
+// not depend on those crates, but the cli binary links both
+pub async fn version_report(
+    cli_version: &str,
+    git_version: &str,
+    nats_worker_version: &str,
+) -> VersionReport {
+    let matrix = CompatibilityMatrix::load()
+        .expect("compatibility_matrix.toml shipped in this crate is invalid");
+    let gst_plugin = gst_plugin_version().await;
+    let os_release = OsRelease::new().ok();
+    let os_image = os_release
+        .as_ref()
+        .map(|os| os.version_id.clone())
+        .filter(|v| !v.is_empty());
+    let os_build_id = os_release
+        .as_ref()
+        .map(|os| os.build_id.clone())
+        .filter(|v| !v.is_empty());
+
+    let versions: Vec<(&str, Option<&str>)> = vec![
+        ("cli", Some(cli_version)),
+        ("nats-worker", Some(nats_worker_version)),
+        ("services", Some(env!("CARGO_PKG_VERSION"))),
+        ("gst-pipelines", Some(printnanny_gst_pipelines::VERSION)),
+        ("gst-plugin", gst_plugin.as_deref()),
+        ("settings-schema", Some(printnanny_settings::VERSION)),
+        ("os-image", os_image.as_deref()),
+    ];
+    let incompatibilities = check_matrix(&matrix, &versions);
+
+    VersionReport {
+        cli: cli_version.to_string(),
+        git: git_version.to_string(),
+        nats_worker: nats_worker_version.to_string(),
+        services: env!("CARGO_PKG_VERSION").to_string(),
+        gst_pipelines: printnanny_gst_pipelines::VERSION.to_string(),
+        gst_plugin,
+        settings_schema: printnanny_settings::VERSION.to_string(),
+        db_schema: printnanny_edge_db::connection::embedded_schema_version(),
+        os_image,
+        os_build_id,
+        cloud_api_compat: matrix.cloud_api_compat,
+        incompatibilities,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // guards the matrix file shipped in the crate: every rule must parse and
+    // reference requirements semver understands
+    #[test_log::test]
+    fn test_shipped_matrix_is_valid() {
+        let matrix = CompatibilityMatrix::load().unwrap();
+        assert!(!matrix.cloud_api_compat.is_empty());
+        for rule in &matrix.rules {
+            VersionReq::parse(&rule.version_req)
+                .unwrap_or_else(|e| panic!("{}: {}", rule.version_req, e));
+            VersionReq::parse(&rule.other_req)
+                .unwrap_or_else(|e| panic!("{}: {}", rule.other_req, e));
+        }
+    }
+
+    #[test_log::test]
+    fn test_check_matrix_flags_known_incompatible_pair() {
+        let matrix = CompatibilityMatrix::load().unwrap();
+        let versions = vec![
+            ("gst-plugin", Some("0.5.2-abc123")),
+            ("gst-pipelines", Some("0.2.1")),
+        ];
+        let flagged = check_matrix(&matrix, &versions);
+        assert_eq!(flagged.len(), 1);
+        assert_eq!(flagged[0].component, "gst-plugin");
+        assert_eq!(flagged[0].version, "0.5.2-abc123");
+        assert_eq!(flagged[0].other, "gst-pipelines");
+    }
+
+    #[test_log::test]
+    fn test_check_matrix_skips_missing_components() {
+        let matrix = CompatibilityMatrix::load().unwrap();
+        // plugin not installed: no rule referencing it can fire
+        let versions = vec![("gst-plugin", None), ("gst-pipelines", Some("0.2.1"))];
+        assert_eq!(check_matrix(&matrix, &versions), vec![]);
+    }
+
+    #[test_log::test]
+    fn test_parse_release_strips_commit_suffix() {
+        assert_eq!(parse_release("0.6.1-abc123"), Version::parse("0.6.1").ok());
+        assert_eq!(parse_release("not a version"), None);
+    }
+}
@@ -5,6 +5,7 @@ use tokio::task::JoinSet;
 use crate::error::VideoRecordingSyncError;
 use crate::printnanny_api::ApiService;
 
+use printnanny_edge_db::jobs::{Job, JobStatus};
 use printnanny_edge_db::video_recording;
 use printnanny_settings::printnanny::PrintNannySettings;
 
@@ -15,6 +16,9 @@ pub async fn upload_video_recording_part(
     let settings = PrintNannySettings::new().await?;
     let sqlite_connection = settings.paths.db().display().to_string();
 
+    // apply the export-time anonymization leg before any bytes leave the device
+    crate::anonymize::anonymize_in_place(&settings, &row.file_name).await?;
+
     let api = ApiService::new(settings.cloud, sqlite_connection.clone());
     let result = api.video_recording_part_create(&row).await?;
 
@@ -37,6 +41,18 @@ pub async fn upload_video_recording_part(
         duration.num_milliseconds(),
     );
 
+    // meter the upload against the cloud_sync bandwidth rollup
+    if let Ok(metadata) = tokio::fs::metadata(&row.file_name).await {
+        if let Err(e) = printnanny_edge_db::bandwidth::BandwidthUsage::record(
+            &sqlite_connection,
+            printnanny_edge_db::bandwidth::BandwidthSubsystem::CloudSync,
+            metadata.len() as i64,
+            0,
+        ) {
+            error!("Failed to record cloud_sync bandwidth usage error={}", e);
+        }
+    }
+
     tokio::fs::remove_file(&row.file_name).await?;
     info!(
         "Deleted file VideoRecordingPart id={} file={}",
@@ -58,21 +74,50 @@ pub async fn sync_all_video_recordings() -> Result<(), VideoRecordingSyncError>
     let count = parts.len();
     info!("{} video recording parts ready for cloud sync", count);
 
+    // track sync progress as a persistent job so a requester that disconnects
+    // can still query the outcome via pi.{pi_id}.jobs.get
+    let job = Job::create(
+        &sqlite_connection,
+        "video_sync",
+        Some(&format!("{} parts ready for cloud sync", count)),
+    )?;
+
     let mut set = JoinSet::new();
     for part in parts {
         set.spawn(upload_video_recording_part(part));
     }
 
+    let mut synced: usize = 0;
+    let mut failed: usize = 0;
     while let Some(Ok(res)) = set.join_next().await {
         match res {
             Ok(part) => {
                 info!("Finished syncing video recording part.id={}", part.id);
+                synced += 1;
             }
             Err(e) => {
                 error!("Error syncing video recording part error={}", e);
+                failed += 1;
             }
         }
+        let percent = ((synced + failed) * 100 / std::cmp::max(count, 1)) as i32;
+        Job::update_progress(
+            &sqlite_connection,
+            &job.id,
+            percent,
+            Some(&format!("synced {} of {} parts", synced, count)),
+        )?;
     }
+    let status = match failed {
+        0 => JobStatus::Success,
+        _ => JobStatus::Failed,
+    };
+    Job::finish(
+        &sqlite_connection,
+        &job.id,
+        status,
+        &format!("synced {} of {} parts, {} failed", synced, count, failed),
+    )?;
     info!("Finished syncing {} video recording parts", count);
     Ok(())
 }
@@ -0,0 +1,119 @@
+// Correlate detection events with recorded video, so the UI can seek to the
+// moment a failure was detected.
+//
+// Detection dataframes emitted by the dataframe_agg element carry `rt`, the
+// pipeline running time of each tensor buffer (nanoseconds). Video recording
+// parts persist `buffer_runningtime` for the first buffer in each fragment,
+// which lets us map a detection event back to a media timestamp.
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use printnanny_edge_db::video_recording::VideoRecordingPart;
+
+use crate::error::VideoRecordingError;
+
+pub const NANOS_PER_MS: i64 = 1_000_000;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TimelineEntry {
+    // pipeline running time of the detection event (nanoseconds)
+    pub detection_rt: i64,
+    // offset from the start of the recording (milliseconds), suitable for seeking
+    pub media_timestamp_ms: i64,
+    // recording fragment containing the detection event
+    pub part_id: String,
+    pub part_file_name: String,
+    pub part_buffer_index: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VideoRecordingTimeline {
+    pub video_recording_id: String,
+    pub entries: Vec<TimelineEntry>,
+}
+
+// map detection event running times to media timestamps within a video recording
+pub fn build_timeline(
+    sqlite_connection: &str,
+    video_recording_id: &str,
+    detection_rts: &[i64],
+) -> Result<VideoRecordingTimeline, VideoRecordingError> {
+    let mut parts =
+        VideoRecordingPart::get_parts_by_video_recording_id(sqlite_connection, video_recording_id)?;
+    parts.sort_by_key(|part| part.buffer_runningtime);
+
+    let entries = match parts.first() {
+        Some(first) => {
+            let recording_start_rt = first.buffer_runningtime;
+            detection_rts
+                .iter()
+                .filter_map(|rt| {
+                    let part = find_part(&parts, *rt)?;
+                    Some(TimelineEntry {
+                        detection_rt: *rt,
+                        media_timestamp_ms: (rt - recording_start_rt) / NANOS_PER_MS,
+                        part_id: part.id.clone(),
+                        part_file_name: part.file_name.clone(),
+                        part_buffer_index: part.buffer_index,
+                    })
+                })
+                .collect()
+        }
+        None => {
+            warn!(
+                "No VideoRecordingPart rows found for video_recording_id={}",
+                video_recording_id
+            );
+            vec![]
+        }
+    };
+
+    Ok(VideoRecordingTimeline {
+        video_recording_id: video_recording_id.to_string(),
+        entries,
+    })
+}
+
+// find the last part that started at or before the detection event running time
+fn find_part(parts: &[VideoRecordingPart], rt: i64) -> Option<&VideoRecordingPart> {
+    parts
+        .iter()
+        .rev()
+        .find(|part| part.buffer_runningtime <= rt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_part(id: &str, buffer_index: i64, buffer_runningtime: i64) -> VideoRecordingPart {
+        VideoRecordingPart {
+            id: id.into(),
+            size: 0,
+            buffer_index,
+            buffer_runningtime,
+            deleted: false,
+            sync_start: None,
+            sync_end: None,
+            file_name: format!("{buffer_index}.mp4"),
+            video_recording_id: "test-recording".into(),
+        }
+    }
+
+    #[test]
+    fn test_find_part_selects_containing_fragment() {
+        let parts = vec![
+            make_part("a__0", 0, 0),
+            make_part("a__1", 1, 10 * NANOS_PER_MS),
+            make_part("a__2", 2, 20 * NANOS_PER_MS),
+        ];
+        let result = find_part(&parts, 15 * NANOS_PER_MS).unwrap();
+        assert_eq!(result.id, "a__1");
+    }
+
+    #[test]
+    fn test_find_part_before_recording_start() {
+        let parts = vec![make_part("a__0", 0, 10 * NANOS_PER_MS)];
+        assert!(find_part(&parts, 5 * NANOS_PER_MS).is_none());
+    }
+}
@@ -0,0 +1,127 @@
+// Crash-safe writes for settings and state files. A plain fs::write leaves a
+// truncated file behind if power is cut mid-write, and two writers racing on
+// the same path interleave their output. Every durable settings/state write
+// should go through atomic_write: take an advisory lock on a .lock sibling,
+// write to a temp file in the same directory, fsync, then rename over the
+// destination so readers only ever observe the old or the new contents.
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use file_lock::{FileLock, FileOptions};
+use tokio::task::spawn_blocking;
+
+use crate::error::PrintNannySettingsError;
+
+// path of the advisory lock file guarding writes to `path`
+fn lock_path(path: &Path) -> PathBuf {
+    let mut filename = path.file_name().unwrap_or_default().to_os_string();
+    filename.push(".lock");
+    path.with_file_name(filename)
+}
+
+// path the new contents are staged at before the rename; lives in the same
+// directory as `path` so the rename never crosses a filesystem boundary.
+// The pid + counter suffix keeps concurrent writers (the advisory lock is
+// fcntl-based, so it only excludes other processes) on distinct temp files.
+fn tmp_path(path: &Path) -> PathBuf {
+    static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+    let mut filename = std::ffi::OsString::from(".");
+    filename.push(path.file_name().unwrap_or_default());
+    filename.push(format!(
+        ".tmp.{}.{}",
+        std::process::id(),
+        TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    path.with_file_name(filename)
+}
+
+pub fn atomic_write_sync(path: &Path, content: &[u8]) -> Result<(), PrintNannySettingsError> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    std::fs::create_dir_all(&parent)?;
+
+    // blocking advisory lock serializes concurrent writers on the same path;
+    // released on drop
+    let _lock = FileLock::lock(
+        lock_path(path),
+        true,
+        FileOptions::new().write(true).create(true),
+    )?;
+
+    let tmp = tmp_path(path);
+    let mut file = File::create(&tmp)?;
+    file.write_all(content)?;
+    // flush file contents to disk before the rename makes them visible
+    file.sync_all()?;
+    drop(file);
+    std::fs::rename(&tmp, path)?;
+    // fsync the directory so the rename itself survives power loss
+    File::open(&parent)?.sync_all()?;
+    Ok(())
+}
+
+pub async fn atomic_write(path: &Path, content: &[u8]) -> Result<(), PrintNannySettingsError> {
+    let path = path.to_path_buf();
+    let content = content.to_vec();
+    spawn_blocking(move || atomic_write_sync(&path, &content)).await?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test(tokio::test)]
+    async fn test_repeat_writes_replace_contents() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("printnanny.toml");
+        atomic_write(&path, b"first contents, longer than the second")
+            .await
+            .unwrap();
+        atomic_write(&path, b"second").await.unwrap();
+        // a second save must fully replace the file, never append to it
+        assert_eq!(std::fs::read(&path).unwrap(), b"second");
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_write_creates_missing_parent_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state").join("nested").join("data.json");
+        atomic_write(&path, b"{}").await.unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"{}");
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_stale_tmp_file_does_not_corrupt_destination() {
+        // a temp file left behind by a crashed writer is ignored by later
+        // writes; the destination only ever holds complete content
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("printnanny.toml");
+        std::fs::write(tmp_path(&path), b"torn partial wri").unwrap();
+        atomic_write(&path, b"complete contents").await.unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"complete contents");
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_concurrent_writers_serialize() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("printnanny.toml");
+        let mut tasks = Vec::new();
+        for i in 0..8 {
+            let path = path.clone();
+            tasks.push(tokio::spawn(async move {
+                let content = format!("writer {} was here", i);
+                atomic_write(&path, content.as_bytes()).await
+            }));
+        }
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+        // whichever writer won, the file holds exactly one complete payload
+        let contents = String::from_utf8(std::fs::read(&path).unwrap()).unwrap();
+        assert!(contents.starts_with("writer ") && contents.ends_with(" was here"));
+    }
+}
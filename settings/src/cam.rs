@@ -9,6 +9,7 @@ use tokio::process::Command;
 use gst::prelude::DeviceExt;
 use gst::prelude::DeviceProviderExtManual;
 
+use crate::camera_controls::CameraControlSettings;
 use crate::error::PrintNannySettingsError;
 
 const DEFAULT_COLORIMETRY: &str = "bt709";
@@ -380,8 +381,382 @@ impl From<VideoSource> for printnanny_os_models::Camera {
     }
 }
 
+// lower tensor_framerate when CPU temperature or load crosses these
+// thresholds, so detection degrades gracefully instead of starving the encoder
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct AdaptiveFramerateSettings {
+    pub enabled: bool,
+    pub cpu_temp_threshold_celsius: i32,
+    // 1-minute load average per core, as a percentage
+    pub load_threshold_percent: i32,
+}
+
+impl Default for AdaptiveFramerateSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            cpu_temp_threshold_celsius: 75,
+            load_threshold_percent: 90,
+        }
+    }
+}
+
+// stride/batch controls for the inference leg, decoupling detection rate from
+// the video framerate on low-power devices; device-local, not part of the
+// generated DetectionSettings model (yet)
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct InferenceSettings {
+    // process every Nth frame of the tensor_framerate-limited stream
+    pub frame_stride: i32,
+    // frames per tensor_filter invocation; only raise this when the model
+    // accepts a batched input tensor
+    pub batch_frames: i32,
+}
+
+impl Default for InferenceSettings {
+    fn default() -> Self {
+        Self {
+            frame_stride: 1,
+            batch_frames: 1,
+        }
+    }
+}
+
+// pause the viewer-facing stream legs (rtp, hls) when nobody has watched for
+// idle_timeout_seconds; the camera, encoder, and detection legs keep running
+// so resume is sub-second when a viewer returns
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct ViewerIdleSettings {
+    pub enabled: bool,
+    pub idle_timeout_seconds: u64,
+}
+
+impl Default for ViewerIdleSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            idle_timeout_seconds: 300,
+        }
+    }
+}
+
+// optional textoverlay/clockoverlay leg, so streams and recordings from
+// multi-printer farms are identifiable and timestamped
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct WatermarkSettings {
+    pub enabled_hls: bool,
+    pub enabled_recording: bool,
+    pub show_device_name: bool,
+    // textoverlay/clockoverlay alignment values: top/center/bottom, left/center/right
+    pub valignment: String,
+    pub halignment: String,
+    // strftime format rendered by clockoverlay
+    pub time_format: String,
+}
+
+impl Default for WatermarkSettings {
+    fn default() -> Self {
+        Self {
+            enabled_hls: false,
+            enabled_recording: false,
+            show_device_name: true,
+            valignment: "top".into(),
+            halignment: "left".into(),
+            time_format: "%Y-%m-%d %H:%M:%S".into(),
+        }
+    }
+}
+
+// per-camera geometry corrections applied at the head of the camera leg:
+// crop away the distorted edges wide-angle lenses produce and normalize the
+// mounting orientation before frames reach the encoder and detection legs.
+// Frames are scaled back to the configured camera caps after the transform,
+// so downstream interpipe listeners are unaffected.
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct CameraTransformSettings {
+    // pixels cropped from each edge before scaling back to the configured resolution
+    pub crop_top: i32,
+    pub crop_bottom: i32,
+    pub crop_left: i32,
+    pub crop_right: i32,
+    // clockwise rotation in degrees; one of 0, 90, 180, 270
+    pub rotation: i32,
+    pub flip_horizontal: bool,
+    pub flip_vertical: bool,
+}
+
+// videoflip legs for a clockwise rotation (0/90/180/270) plus mirror flips;
+// empty for the identity orientation
+fn gst_videoflip_fragment(rotation: i32, flip_horizontal: bool, flip_vertical: bool) -> String {
+    let mut fragment = String::new();
+    match rotation.rem_euclid(360) {
+        90 => fragment.push_str("! videoflip video-direction=90r "),
+        180 => fragment.push_str("! videoflip video-direction=180 "),
+        270 => fragment.push_str("! videoflip video-direction=90l "),
+        _ => (),
+    };
+    if flip_horizontal {
+        fragment.push_str("! videoflip video-direction=horiz ");
+    }
+    if flip_vertical {
+        fragment.push_str("! videoflip video-direction=vert ");
+    }
+    fragment
+}
+
+impl CameraTransformSettings {
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+
+    // videocrop/videoflip fragment inserted after the camera source; empty for
+    // the identity transform so untouched configurations produce the exact
+    // pipeline descriptions they did before this setting existed
+    pub fn gst_transform_fragment(&self) -> String {
+        let mut fragment = String::new();
+        if self.crop_top > 0 || self.crop_bottom > 0 || self.crop_left > 0 || self.crop_right > 0 {
+            fragment.push_str(&format!(
+                "! videocrop top={} bottom={} left={} right={} ",
+                self.crop_top, self.crop_bottom, self.crop_left, self.crop_right
+            ));
+        }
+        fragment.push_str(&gst_videoflip_fragment(
+            self.rotation,
+            self.flip_horizontal,
+            self.flip_vertical,
+        ));
+        fragment
+    }
+}
+
+// rotation/flip applied to a single output leg, on top of the camera-wide
+// CameraTransformSettings: an upside-down mounted camera can be corrected
+// for the live stream while recordings keep the sensor orientation, or vice
+// versa. Applied before the output's encoder, so detection is unaffected.
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct OutputTransformSettings {
+    // clockwise rotation in degrees; one of 0, 90, 180, 270
+    pub rotation: i32,
+    pub flip_horizontal: bool,
+    pub flip_vertical: bool,
+}
+
+impl OutputTransformSettings {
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn gst_transform_fragment(&self) -> String {
+        gst_videoflip_fragment(self.rotation, self.flip_horizontal, self.flip_vertical)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnonymizeMode {
+    // blur the region in place, keeping the full frame
+    Blur,
+    // crop the frame down to the region, discarding everything outside it
+    Crop,
+}
+
+// region in frame pixel coordinates, relative to the top-left corner
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct AnonymizeRegion {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+// blur or crop a user-defined region (e.g. the background of a home office)
+// when recordings leave the device: applied by a one-shot transcode at
+// export/cloud-sync time (printnanny_services::anonymize), never on the live
+// pipeline, so the local stream and detections see the full frame.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct AnonymizeSettings {
+    pub enabled: bool,
+    pub mode: AnonymizeMode,
+    // for Blur, the region to obscure; for Crop, the region to keep
+    pub region: AnonymizeRegion,
+}
+
+impl Default for AnonymizeSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: AnonymizeMode::Blur,
+            region: AnonymizeRegion::default(),
+        }
+    }
+}
+
+impl AnonymizeSettings {
+    pub fn is_active(&self) -> bool {
+        self.enabled && self.region.width > 0 && self.region.height > 0
+    }
+
+    // decoded-video fragment for the export transcode; frame dimensions are
+    // needed because videocrop counts pixels from each edge
+    pub fn gst_anonymize_fragment(&self, frame_width: i32, frame_height: i32) -> String {
+        let region = &self.region;
+        let right = (frame_width - region.x - region.width).max(0);
+        let bottom = (frame_height - region.y - region.height).max(0);
+        match self.mode {
+            AnonymizeMode::Crop => format!(
+                "! videocrop top={} left={} right={} bottom={} ",
+                region.y, region.x, right, bottom
+            ),
+            // composite a blurred copy of the region back over the frame
+            AnonymizeMode::Blur => format!(
+                "! tee name=anon_tee \
+                anon_tee. ! queue ! anon_comp.sink_0 \
+                anon_tee. ! queue ! videocrop top={y} left={x} right={right} bottom={bottom} \
+                ! gaussianblur sigma=12.0 ! anon_comp.sink_1 \
+                compositor name=anon_comp sink_1::xpos={x} sink_1::ypos={y} ",
+                x = region.x,
+                y = region.y,
+                right = right,
+                bottom = bottom,
+            ),
+        }
+    }
+}
+
+// run a candidate tflite model side by side with the primary model on
+// sampled frames, logging both outputs tagged by model id so they can be
+// compared over the same footage before switching
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct ModelEvaluationSettings {
+    pub enabled: bool,
+    pub candidate_model_file: String,
+    pub candidate_label_file: String,
+    // candidate inference framerate; kept low so the extra tensor_filter leg
+    // doesn't starve the primary model
+    pub sample_framerate: i32,
+}
+
+impl Default for ModelEvaluationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            candidate_model_file: "/usr/share/printnanny/model/candidate.tflite".into(),
+            candidate_label_file: "/usr/share/printnanny/model/labels.txt".into(),
+            sample_framerate: 1,
+        }
+    }
+}
+
+// an extra RTP fan-out destination on top of the default local Janus port
+// (rtp.video_udp_port): a LAN recorder, a remote relay, etc.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct RtpDestination {
+    pub host: String,
+    pub port: i32,
+}
+
+// SRT relay leg for remote monitoring over lossy networks (OBS, ffplay):
+// unlike raw RTP over UDP, SRT retransmits lost packets within the configured
+// latency window and can encrypt the stream with a passphrase
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct SrtSettings {
+    pub enabled: bool,
+    // srt:// uri; the default listens for an incoming caller connection
+    pub uri: String,
+    // retransmission latency window in milliseconds; raise for higher-rtt links
+    pub latency_ms: i32,
+    // stream encryption passphrase (10-79 characters); empty disables encryption
+    pub passphrase: String,
+}
+
+impl Default for SrtSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            uri: "srt://0.0.0.0:8890?mode=listener".into(),
+            latency_ms: 125,
+            passphrase: "".into(),
+        }
+    }
+}
+
+// auth scheme the proxy enforces on a viewer-facing stream endpoint
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StreamAuthMode {
+    // anyone on the LAN can watch (historical default)
+    None,
+    // HTTP basic auth against the htpasswd file
+    Basic,
+    // HTTP digest auth against the htpasswd file
+    Digest,
+    // signed stream token minted with `printnanny cam share`
+    Token,
+}
+
+impl StreamAuthMode {
+    pub fn as_str(&self) -> &str {
+        match self {
+            StreamAuthMode::None => "none",
+            StreamAuthMode::Basic => "basic",
+            StreamAuthMode::Digest => "digest",
+            StreamAuthMode::Token => "token",
+        }
+    }
+}
+
+// who may watch the camera from the LAN: auth enforced by the nginx proxy in
+// front of the HLS playlist/segments and the MJPEG snapshot endpoint,
+// configurable per output so e.g. snapshots stay open for OctoPrint while the
+// live stream requires credentials
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct StreamAuthSettings {
+    // auth on the HLS playlist/segments endpoint
+    pub hls: StreamAuthMode,
+    // auth on the MJPEG/snapshot endpoint
+    pub mjpeg: StreamAuthMode,
+    // htpasswd/htdigest file consumed by the proxy for basic/digest modes
+    pub htpasswd_file: String,
+    // realm shown in the browser credentials prompt
+    pub realm: String,
+}
+
+impl Default for StreamAuthSettings {
+    fn default() -> Self {
+        Self {
+            hls: StreamAuthMode::None,
+            mjpeg: StreamAuthMode::None,
+            htpasswd_file: "/home/printnanny/.local/share/printnanny/creds/stream-htpasswd".into(),
+            realm: "PrintNanny Camera".into(),
+        }
+    }
+}
+
+// user-supplied gst-launch fragments run as extra pipeline legs alongside the
+// managed graph; every fragment must pass the gst_lint checks before the
+// settings file is saved
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct CustomGraphSettings {
+    pub enabled: bool,
+    // gst-launch syntax, e.g. "interpipesrc listen-to=h264_encode_interpipesink ! queue ! fakesink"
+    pub fragments: Vec<String>,
+}
+
 #[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
 pub struct VideoStreamSettings {
+    // blackout camera source while leaving downstream services running
+    // NOTE: plain values must be serialized before nested tables in TOML
+    #[serde(default)]
+    pub privacy_mode: bool,
+    // composite detection boxes onto the main H.264/HLS stream so the normal
+    // dashboard player shows what the model sees, without a second stream
+    #[serde(default)]
+    pub hls_detection_overlay: bool,
+    // extra udp destinations for the rtp leg, in addition to the local
+    // video_udp_port (an empty list serializes as a plain value, so it stays
+    // up here with privacy_mode)
+    #[serde(default)]
+    pub rtp_destinations: Vec<RtpDestination>,
     #[serde(rename = "camera")]
     pub camera: Box<printnanny_os_models::CameraSettings>,
     #[serde(rename = "detection")]
@@ -394,6 +769,38 @@ pub struct VideoStreamSettings {
     pub rtp: Box<printnanny_os_models::RtpSettings>,
     #[serde(rename = "snapshot")]
     pub snapshot: Box<printnanny_os_models::SnapshotSettings>,
+    #[serde(default)]
+    pub srt: SrtSettings,
+    #[serde(default)]
+    pub watermark: WatermarkSettings,
+    #[serde(default)]
+    pub transform: CameraTransformSettings,
+    // orientation for the live stream legs (rtp, hls without watermark)
+    #[serde(default)]
+    pub stream_transform: OutputTransformSettings,
+    // orientation for outputs fed by the watermark/recording encoder
+    #[serde(default)]
+    pub recording_transform: OutputTransformSettings,
+    #[serde(default)]
+    pub controls: CameraControlSettings,
+    #[serde(default)]
+    pub viewer_idle: ViewerIdleSettings,
+    #[serde(default)]
+    pub adaptive_framerate: AdaptiveFramerateSettings,
+    // frame stride/batch controls for the inference leg
+    #[serde(default)]
+    pub inference: InferenceSettings,
+    #[serde(default)]
+    pub model_evaluation: ModelEvaluationSettings,
+    // blur/crop applied when recordings leave the device
+    #[serde(default)]
+    pub anonymize: AnonymizeSettings,
+    // per-output viewer auth enforced by the stream proxy
+    #[serde(default)]
+    pub stream_auth: StreamAuthSettings,
+    // user-supplied pipeline fragments, linted before save
+    #[serde(default)]
+    pub custom_graph: CustomGraphSettings,
 }
 
 impl From<VideoStreamSettings> for printnanny_os_models::VideoStreamSettings {
@@ -418,6 +825,23 @@ impl From<printnanny_os_models::VideoStreamSettings> for VideoStreamSettings {
             recording: obj.recording,
             snapshot: obj.snapshot,
             rtp: obj.rtp,
+            // privacy_mode, watermark, transform, and controls are device-local state, not part of the cloud model
+            privacy_mode: false,
+            hls_detection_overlay: false,
+            rtp_destinations: vec![],
+            srt: SrtSettings::default(),
+            watermark: WatermarkSettings::default(),
+            transform: CameraTransformSettings::default(),
+            stream_transform: OutputTransformSettings::default(),
+            recording_transform: OutputTransformSettings::default(),
+            controls: CameraControlSettings::default(),
+            viewer_idle: ViewerIdleSettings::default(),
+            adaptive_framerate: AdaptiveFramerateSettings::default(),
+            inference: InferenceSettings::default(),
+            model_evaluation: ModelEvaluationSettings::default(),
+            anonymize: AnonymizeSettings::default(),
+            stream_auth: StreamAuthSettings::default(),
+            custom_graph: CustomGraphSettings::default(),
         }
     }
 }
@@ -478,6 +902,22 @@ impl Default for VideoStreamSettings {
             recording,
             rtp,
             snapshot,
+            privacy_mode: false,
+            hls_detection_overlay: false,
+            rtp_destinations: vec![],
+            srt: SrtSettings::default(),
+            watermark: WatermarkSettings::default(),
+            transform: CameraTransformSettings::default(),
+            stream_transform: OutputTransformSettings::default(),
+            recording_transform: OutputTransformSettings::default(),
+            controls: CameraControlSettings::default(),
+            viewer_idle: ViewerIdleSettings::default(),
+            adaptive_framerate: AdaptiveFramerateSettings::default(),
+            inference: InferenceSettings::default(),
+            model_evaluation: ModelEvaluationSettings::default(),
+            anonymize: AnonymizeSettings::default(),
+            stream_auth: StreamAuthSettings::default(),
+            custom_graph: CustomGraphSettings::default(),
         }
     }
 }
@@ -537,6 +977,28 @@ impl VideoStreamSettings {
         }
     }
 
+    // NV12 variant of gst_camera_caps, used by the zero-copy DMABuf path where
+    // the v4l2 m2m blocks share buffers instead of copying frames
+    pub fn gst_camera_nv12_caps(&self) -> String {
+        if self.camera.device_name.contains("imx219") || self.camera.device_name.contains("imx708") || self.camera.device_name.contains("imx477") {
+            format!(
+                "video/x-raw,width={width},height={height},framerate={framerate_n}/{framerate_d},format=NV12,interlace-mode=progressive,colorimetry=bt709",
+                width = self.camera.width,
+                height = self.camera.height,
+                framerate_n = self.camera.framerate_n,
+                framerate_d = self.camera.framerate_d
+            )
+        } else {
+            format!(
+                "video/x-raw,width={width},height={height},framerate={framerate_n}/{framerate_d},format=NV12,interlace-mode=progressive",
+                width = self.camera.width,
+                height = self.camera.height,
+                framerate_n = self.camera.framerate_n,
+                framerate_d = self.camera.framerate_d
+            )
+        }
+    }
+
     pub async fn hotplug(mut self) -> Result<Self, PrintNannySettingsError> {
         // list available devices
         let camera_sources = CameraVideoSource::from_libcamera_list().await?;
@@ -637,4 +1099,92 @@ mod tests {
         let result = CameraVideoSource::parse_list_cameras_command_output("");
         assert_eq!(result.len(), 0)
     }
+
+    #[test_log::test]
+    fn test_identity_transform_fragment_is_empty() {
+        let transform = CameraTransformSettings::default();
+        assert!(transform.is_identity());
+        assert_eq!(transform.gst_transform_fragment(), "");
+    }
+
+    #[test_log::test]
+    fn test_crop_rotate_flip_transform_fragment() {
+        let transform = CameraTransformSettings {
+            crop_top: 8,
+            crop_bottom: 8,
+            crop_left: 16,
+            crop_right: 16,
+            rotation: 180,
+            flip_horizontal: true,
+            ..CameraTransformSettings::default()
+        };
+        assert!(!transform.is_identity());
+        assert_eq!(
+            transform.gst_transform_fragment(),
+            "! videocrop top=8 bottom=8 left=16 right=16 \
+            ! videoflip video-direction=180 \
+            ! videoflip video-direction=horiz "
+        );
+    }
+
+    #[test_log::test]
+    fn test_output_transform_fragment() {
+        let transform = OutputTransformSettings::default();
+        assert!(transform.is_identity());
+        assert_eq!(transform.gst_transform_fragment(), "");
+
+        let transform = OutputTransformSettings {
+            rotation: 90,
+            flip_vertical: true,
+            ..OutputTransformSettings::default()
+        };
+        assert_eq!(
+            transform.gst_transform_fragment(),
+            "! videoflip video-direction=90r \
+            ! videoflip video-direction=vert "
+        );
+    }
+
+    #[test_log::test]
+    fn test_anonymize_fragment_crop_and_blur() {
+        let inactive = AnonymizeSettings::default();
+        assert!(!inactive.is_active());
+
+        let region = AnonymizeRegion {
+            x: 100,
+            y: 40,
+            width: 200,
+            height: 120,
+        };
+        let crop = AnonymizeSettings {
+            enabled: true,
+            mode: AnonymizeMode::Crop,
+            region: region.clone(),
+        };
+        assert!(crop.is_active());
+        assert_eq!(
+            crop.gst_anonymize_fragment(640, 480),
+            "! videocrop top=40 left=100 right=340 bottom=320 "
+        );
+
+        let blur = AnonymizeSettings {
+            mode: AnonymizeMode::Blur,
+            ..crop
+        };
+        let fragment = blur.gst_anonymize_fragment(640, 480);
+        assert!(fragment.contains("gaussianblur"));
+        assert!(fragment.contains("sink_1::xpos=100 sink_1::ypos=40"));
+    }
+
+    #[test_log::test]
+    fn test_rotation_270_maps_to_90l() {
+        let transform = CameraTransformSettings {
+            rotation: 270,
+            ..CameraTransformSettings::default()
+        };
+        assert_eq!(
+            transform.gst_transform_fragment(),
+            "! videoflip video-direction=90l "
+        );
+    }
 }
@@ -0,0 +1,254 @@
+use std::process::Output;
+
+use log::{error, info};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+use crate::error::PrintNannySettingsError;
+
+// v4l2 control values persisted per-device. Fields set to None are left at the
+// driver default; Some(value) is applied with v4l2-ctl --set-ctrl on demand.
+// Field names mirror the v4l2 control names reported by v4l2-ctl --list-ctrls,
+// which libcamera also exposes through the sensor's v4l2 subdevice.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct CameraControlSettings {
+    // v4l2 device node controls are applied to, e.g. /dev/video0 for USB cameras
+    // or /dev/v4l-subdev0 for CSI sensor controls
+    pub device: String,
+    pub auto_exposure: Option<i64>,
+    pub exposure_time_absolute: Option<i64>,
+    pub analogue_gain: Option<i64>,
+    pub white_balance_automatic: Option<bool>,
+    pub white_balance_temperature: Option<i64>,
+    pub focus_automatic_continuous: Option<bool>,
+    pub focus_absolute: Option<i64>,
+}
+
+impl Default for CameraControlSettings {
+    fn default() -> Self {
+        Self {
+            device: "/dev/v4l-subdev0".into(),
+            auto_exposure: None,
+            exposure_time_absolute: None,
+            analogue_gain: None,
+            white_balance_automatic: None,
+            white_balance_temperature: None,
+            focus_automatic_continuous: None,
+            focus_absolute: None,
+        }
+    }
+}
+
+// one row of v4l2-ctl --list-ctrls output
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct V4l2Control {
+    pub name: String,
+    pub control_type: String,
+    pub min: Option<i64>,
+    pub max: Option<i64>,
+    pub step: Option<i64>,
+    pub default: Option<i64>,
+    pub value: Option<i64>,
+    pub flags: Option<String>,
+}
+
+impl CameraControlSettings {
+    // name=value pairs consumed by v4l2-ctl --set-ctrl
+    pub fn to_ctrl_pairs(&self) -> Vec<String> {
+        let mut result = Vec::new();
+        if let Some(v) = self.auto_exposure {
+            result.push(format!("auto_exposure={}", v));
+        }
+        if let Some(v) = self.exposure_time_absolute {
+            result.push(format!("exposure_time_absolute={}", v));
+        }
+        if let Some(v) = self.analogue_gain {
+            result.push(format!("analogue_gain={}", v));
+        }
+        if let Some(v) = self.white_balance_automatic {
+            result.push(format!("white_balance_automatic={}", v as i64));
+        }
+        if let Some(v) = self.white_balance_temperature {
+            result.push(format!("white_balance_temperature={}", v));
+        }
+        if let Some(v) = self.focus_automatic_continuous {
+            result.push(format!("focus_automatic_continuous={}", v as i64));
+        }
+        if let Some(v) = self.focus_absolute {
+            result.push(format!("focus_absolute={}", v));
+        }
+        result
+    }
+
+    async fn list_ctrls_command_output(&self) -> std::io::Result<Output> {
+        Command::new("v4l2-ctl")
+            .args(["-d", &self.device, "--list-ctrls"])
+            .output()
+            .await
+    }
+
+    pub fn parse_list_ctrls_line(line: &str) -> Option<V4l2Control> {
+        let re = Regex::new(r"^\s*([a-z0-9_]+)\s+0x[0-9a-f]+\s+\((\w+)\)\s*:\s*(.*)$").unwrap();
+        let caps = re.captures(line)?;
+        let name = caps.get(1)?.as_str().to_string();
+        let control_type = caps.get(2)?.as_str().to_string();
+        let fields = caps.get(3)?.as_str();
+
+        let field_re = Regex::new(r"([a-z-]+)=([^\s]+)").unwrap();
+        let mut control = V4l2Control {
+            name,
+            control_type,
+            min: None,
+            max: None,
+            step: None,
+            default: None,
+            value: None,
+            flags: None,
+        };
+        for field in field_re.captures_iter(fields) {
+            let key = field.get(1).unwrap().as_str();
+            let value = field.get(2).unwrap().as_str();
+            match key {
+                "min" => control.min = value.parse().ok(),
+                "max" => control.max = value.parse().ok(),
+                "step" => control.step = value.parse().ok(),
+                "default" => control.default = value.parse().ok(),
+                "value" => control.value = value.parse().ok(),
+                "flags" => control.flags = Some(value.to_string()),
+                _ => (),
+            }
+        }
+        Some(control)
+    }
+
+    pub fn parse_list_ctrls_output(stdout: &str) -> Vec<V4l2Control> {
+        stdout
+            .lines()
+            .filter_map(Self::parse_list_ctrls_line)
+            .collect()
+    }
+
+    // read current control values from the v4l2 device
+    pub async fn list_controls(&self) -> Result<Vec<V4l2Control>, PrintNannySettingsError> {
+        let output = self.list_ctrls_command_output().await?;
+        if !output.status.success() {
+            return Err(PrintNannySettingsError::CommandError {
+                cmd: format!("v4l2-ctl -d {} --list-ctrls", self.device),
+                code: output.status.code(),
+                stdout: String::from_utf8(output.stdout)?,
+                stderr: String::from_utf8(output.stderr)?,
+            });
+        }
+        let stdout = String::from_utf8(output.stdout)?;
+        Ok(Self::parse_list_ctrls_output(&stdout))
+    }
+
+    // apply every Some(value) control to the v4l2 device
+    pub async fn apply(&self) -> Result<(), PrintNannySettingsError> {
+        let ctrl_pairs = self.to_ctrl_pairs();
+        if ctrl_pairs.is_empty() {
+            info!(
+                "No camera controls configured for device={}, leaving driver defaults",
+                self.device
+            );
+            return Ok(());
+        }
+        let set_ctrl = ctrl_pairs.join(",");
+        let output = Command::new("v4l2-ctl")
+            .args(["-d", &self.device, "--set-ctrl", &set_ctrl])
+            .output()
+            .await?;
+        if !output.status.success() {
+            let stderr = String::from_utf8(output.stderr)?;
+            error!(
+                "v4l2-ctl --set-ctrl {} failed on device={} stderr={}",
+                set_ctrl, self.device, stderr
+            );
+            return Err(PrintNannySettingsError::CommandError {
+                cmd: format!("v4l2-ctl -d {} --set-ctrl {}", self.device, set_ctrl),
+                code: output.status.code(),
+                stdout: String::from_utf8(output.stdout)?,
+                stderr,
+            });
+        }
+        info!(
+            "Applied camera controls {} to device={}",
+            set_ctrl, self.device
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIST_CTRLS_OUTPUT: &str = r#"
+User Controls
+
+                     brightness 0x00980900 (int)    : min=0 max=100 step=1 default=50 value=50 flags=slider
+       white_balance_automatic 0x0098090c (bool)   : default=1 value=1
+
+Camera Controls
+
+                  auto_exposure 0x009a0901 (menu)   : min=0 max=3 default=0 value=0 (Auto Mode)
+         exposure_time_absolute 0x009a0902 (int)    : min=1 max=10000 step=1 default=156 value=156 flags=inactive
+"#;
+
+    #[test_log::test]
+    fn test_parse_list_ctrls_output() {
+        let result = CameraControlSettings::parse_list_ctrls_output(LIST_CTRLS_OUTPUT);
+        assert_eq!(result.len(), 4);
+        assert_eq!(
+            *result.first().unwrap(),
+            V4l2Control {
+                name: "brightness".into(),
+                control_type: "int".into(),
+                min: Some(0),
+                max: Some(100),
+                step: Some(1),
+                default: Some(50),
+                value: Some(50),
+                flags: Some("slider".into()),
+            }
+        );
+        assert_eq!(
+            *result.get(1).unwrap(),
+            V4l2Control {
+                name: "white_balance_automatic".into(),
+                control_type: "bool".into(),
+                min: None,
+                max: None,
+                step: None,
+                default: Some(1),
+                value: Some(1),
+                flags: None,
+            }
+        );
+        assert_eq!(result.get(2).unwrap().control_type, "menu");
+        assert_eq!(result.get(3).unwrap().flags, Some("inactive".into()));
+    }
+
+    #[test_log::test]
+    fn test_to_ctrl_pairs() {
+        let settings = CameraControlSettings {
+            exposure_time_absolute: Some(500),
+            white_balance_automatic: Some(false),
+            ..CameraControlSettings::default()
+        };
+        assert_eq!(
+            settings.to_ctrl_pairs(),
+            vec![
+                "exposure_time_absolute=500".to_string(),
+                "white_balance_automatic=0".to_string()
+            ]
+        );
+    }
+
+    #[test_log::test]
+    fn test_parse_no_list_ctrls_output() {
+        let result = CameraControlSettings::parse_list_ctrls_output("");
+        assert_eq!(result.len(), 0)
+    }
+}
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+// Status display for headless farm enclosures: either discrete GPIO LEDs
+// (connectivity / printing / alert, wired to the Pi header) or an SSD1306
+// OLED / e-ink HAT rendered by an external helper script.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum DisplayDriver {
+    #[serde(rename = "status_leds")]
+    StatusLeds,
+    #[serde(rename = "ssd1306")]
+    Ssd1306,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct DisplaySettings {
+    pub enabled: bool,
+    pub driver: DisplayDriver,
+    // gpio character device consumed by gpioset, e.g. gpiochip0
+    pub gpio_chip: String,
+    // status LED pin mapping: lit while the edge worker is healthy / a print
+    // is running / a failure episode is latched
+    pub led_connectivity_pin: i32,
+    pub led_printing_pin: i32,
+    pub led_alert_pin: i32,
+    // external renderer invoked with the status JSON as its only argument
+    // (e.g. a python ssd1306/e-ink script); keeps the i2c driver out-of-process
+    pub render_command: String,
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            driver: DisplayDriver::StatusLeds,
+            gpio_chip: "gpiochip0".into(),
+            led_connectivity_pin: 23,
+            led_printing_pin: 24,
+            led_alert_pin: 25,
+            render_command: "".into(),
+        }
+    }
+}
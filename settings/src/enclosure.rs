@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+// Enclosure sensor suite for farm builds: a BME280 (temperature/humidity,
+// read through the kernel iio driver), an optional DS18B20 1-Wire probe, and
+// a smoke detector alarm line on a GPIO pin. Readings ride along in the
+// system heartbeat; threshold breaches and smoke fire hooks, and smoke can
+// additionally trigger an emergency PSU cutoff.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EnclosureSettings {
+    pub enabled: bool,
+    // iio sysfs directory for a BME280, e.g. /sys/bus/iio/devices/iio:device0
+    // (empty disables the temperature/humidity readings)
+    pub bme280_iio_device: String,
+    // w1_slave file for a DS18B20 probe, e.g.
+    // /sys/bus/w1/devices/28-00000a0b0c0d/w1_slave (empty disables)
+    pub ds18b20_device: String,
+    // gpio line wired to the smoke detector's alarm output, read via gpioget;
+    // alarm is assumed active-high
+    pub smoke_enabled: bool,
+    pub smoke_gpio_chip: String,
+    pub smoke_gpio_pin: i32,
+    // alert thresholds for the enclosure (not hotend/bed) climate
+    pub temperature_alert_celsius: i32,
+    pub humidity_alert_percent: i32,
+    // emergency command run via `sh -c` when smoke is detected, e.g. a smart
+    // plug PSU cutoff or a firmware M81; empty disables
+    pub smoke_power_off_command: String,
+}
+
+impl Default for EnclosureSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bme280_iio_device: "".into(),
+            ds18b20_device: "".into(),
+            smoke_enabled: false,
+            smoke_gpio_chip: "gpiochip0".into(),
+            smoke_gpio_pin: 22,
+            temperature_alert_celsius: 60,
+            humidity_alert_percent: 80,
+            smoke_power_off_command: "".into(),
+        }
+    }
+}
@@ -40,6 +40,12 @@ pub enum PrintNannySettingsError {
     #[error("Failed to handle invalid config value {value:?}")]
     InvalidValue { value: String },
 
+    #[error("Invalid cron expression {expression:?}: {detail}")]
+    InvalidCronExpression { expression: String, detail: String },
+
+    #[error("Invalid PrintNannySettings ({} violations):\n{}", .violations.len(), .violations.join("\n"))]
+    InvalidSettings { violations: Vec<String> },
+
     #[error(transparent)]
     FromUtf8Error(#[from] std::string::FromUtf8Error),
 
@@ -50,6 +56,8 @@ pub enum PrintNannySettingsError {
     #[error(transparent)]
     TomlDeError(#[from] toml::de::Error),
     #[error(transparent)]
+    YamlError(#[from] serde_yaml::Error),
+    #[error(transparent)]
     FigmentError(#[from] figment::error::Error),
     #[error(transparent)]
     ZipError(#[from] zip::result::ZipError),
@@ -80,6 +88,9 @@ pub enum VersionControlledSettingsError {
     #[error(transparent)]
     ZbusError(#[from] zbus::Error),
 
+    #[error(transparent)]
+    TaskJoinError(#[from] tokio::task::JoinError),
+
     #[error(transparent)]
     PrintNannySettingsError(#[from] PrintNannySettingsError),
 }
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+// Peers for the local farm overview. Every listed peer must point at the same
+// NATS server as this device (typical multi-printer setups run one broker);
+// the hostname is the {pi_id} segment of the peer's NATS subjects.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FarmSettings {
+    pub peers: Vec<String>,
+    // per-peer timeout when aggregating the overview
+    pub request_timeout_secs: u64,
+}
+
+impl Default for FarmSettings {
+    fn default() -> Self {
+        Self {
+            peers: vec![],
+            request_timeout_secs: 5,
+        }
+    }
+}
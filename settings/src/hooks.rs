@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+
+// Lifecycle events user hooks can subscribe to. Hooks are an escape hatch
+// for integrations PrintNanny doesn't support natively (buzzers, webhooks,
+// home automation), so the event set is deliberately small and stable.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum HookEvent {
+    #[serde(rename = "print_started")]
+    PrintStarted,
+    #[serde(rename = "print_failed_detected")]
+    PrintFailedDetected,
+    // follow-up to print_failed_detected once the evidence bundle
+    // (pre/post-roll clip + snapshots) for the event has been captured
+    #[serde(rename = "evidence_bundle_ready")]
+    EvidenceBundleReady,
+    #[serde(rename = "update_applied")]
+    UpdateApplied,
+    #[serde(rename = "camera_error")]
+    CameraError,
+    #[serde(rename = "enclosure_alert")]
+    EnclosureAlert,
+    #[serde(rename = "smoke_detected")]
+    SmokeDetected,
+    #[serde(rename = "power_loss")]
+    PowerLoss,
+    #[serde(rename = "power_restored")]
+    PowerRestored,
+}
+
+impl HookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HookEvent::PrintStarted => "print_started",
+            HookEvent::PrintFailedDetected => "print_failed_detected",
+            HookEvent::EvidenceBundleReady => "evidence_bundle_ready",
+            HookEvent::UpdateApplied => "update_applied",
+            HookEvent::CameraError => "camera_error",
+            HookEvent::EnclosureAlert => "enclosure_alert",
+            HookEvent::SmokeDetected => "smoke_detected",
+            HookEvent::PowerLoss => "power_loss",
+            HookEvent::PowerRestored => "power_restored",
+        }
+    }
+}
+
+// one user-registered hook: a shell command run via `sh -c` with a cleared
+// environment (PATH plus PRINTNANNY_HOOK_* variables only) and a hard timeout
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct HookSettings {
+    pub enabled: bool,
+    pub event: HookEvent,
+    pub command: String,
+    // the hook process is killed when it runs longer than this
+    pub timeout_seconds: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct HooksSettings {
+    // master switch; individual hooks also carry their own enable flag
+    pub enabled: bool,
+    #[serde(default)]
+    pub hooks: Vec<HookSettings>,
+}
+
+impl Default for HooksSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            hooks: vec![],
+        }
+    }
+}
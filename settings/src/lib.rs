@@ -1,11 +1,30 @@
+pub mod atomic_write;
 pub mod cam;
+pub mod camera_controls;
+pub mod display;
+pub mod enclosure;
 pub mod error;
+pub mod farm;
+pub mod gst_lint;
+pub mod hooks;
 pub mod klipper;
+pub mod lighting;
 pub mod mainsail;
+pub mod migrations;
 pub mod moonraker;
+pub mod nats_server;
+pub mod network;
 pub mod octoprint;
 pub mod paths;
+pub mod plugins;
 pub mod printnanny;
+pub mod provenance;
+pub mod schedule;
+pub mod storage;
+pub mod stream_proxy;
+pub mod timelapse;
+pub mod ups;
+pub mod validation;
 pub mod vcs;
 
 // re-export crates
@@ -17,6 +36,9 @@ pub use printnanny_os_models; // AsyncAPI 2.x Models
 pub use sys_info;
 pub use toml;
 
+// settings schema version, reported by `printnanny version --all`
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
 use clap::{ArgEnum, PossibleValue};
 use serde::{Deserialize, Serialize};
 
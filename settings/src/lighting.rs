@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+// Lighting actuator toggled alongside camera activity: either a GPIO pin
+// (relay or LED driver wired to the Pi header) or an HTTP smart plug.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum LightingMode {
+    #[serde(rename = "gpio")]
+    Gpio,
+    #[serde(rename = "smart_plug")]
+    SmartPlug,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct LightingSettings {
+    pub enabled: bool,
+    pub mode: LightingMode,
+    // gpio character device consumed by gpioset, e.g. gpiochip0
+    pub gpio_chip: String,
+    pub gpio_pin: i32,
+    // urls requested to switch an HTTP smart plug, e.g. Tasmota:
+    // http://plug.local/cm?cmnd=Power%20On
+    pub smart_plug_on_url: String,
+    pub smart_plug_off_url: String,
+    // switch lights on when a print/recording starts, off when it stops
+    pub on_print_start: bool,
+    pub off_print_done: bool,
+    // switch lights on automatically when detected luminance falls below threshold
+    pub auto_low_luminance: bool,
+    // mean luma (0-255) below which the scene is considered too dark
+    pub luminance_threshold: i32,
+}
+
+impl Default for LightingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: LightingMode::Gpio,
+            gpio_chip: "gpiochip0".into(),
+            gpio_pin: 17,
+            smart_plug_on_url: "".into(),
+            smart_plug_off_url: "".into(),
+            on_print_start: true,
+            off_print_done: true,
+            auto_low_luminance: false,
+            luminance_threshold: 40,
+        }
+    }
+}
+
+impl LightingSettings {
+    // should lights switch on for the given mean luma value?
+    pub fn is_low_luminance(&self, mean_luma: f64) -> bool {
+        self.enabled && self.auto_low_luminance && mean_luma < self.luminance_threshold as f64
+    }
+}
@@ -0,0 +1,209 @@
+// Ordered, versioned migrations for settings files (not just sqlite): each
+// migration transforms an older printnanny.toml layout to the current schema.
+// Pending migrations run on first boot after an OTA update, and the applied
+// list is recorded in the state dir so each migration runs exactly once.
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use log::info;
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+
+use crate::atomic_write::atomic_write;
+use crate::error::PrintNannySettingsError;
+use crate::paths::PrintNannyPaths;
+
+pub const SETTINGS_MIGRATIONS_FILENAME: &str = "settings-migrations.json";
+
+pub struct SettingsMigration {
+    // lexically-ordered version prefix, e.g. "0001"
+    pub version: &'static str,
+    pub name: &'static str,
+    // transform the parsed toml document in place, returning true if changed
+    pub migrate: fn(&mut toml::Value) -> Result<bool, PrintNannySettingsError>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AppliedSettingsMigration {
+    pub version: String,
+    pub name: String,
+    // unix timestamp (seconds) the migration was applied
+    pub applied_at: u64,
+    // whether the migration changed the settings file
+    pub changed: bool,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct AppliedSettingsMigrations {
+    pub applied: Vec<AppliedSettingsMigration>,
+}
+
+// all known settings migrations, in the order they must be applied
+pub fn all_migrations() -> Vec<SettingsMigration> {
+    vec![SettingsMigration {
+        version: "0001",
+        name: "video-stream-tables",
+        migrate: migrate_0001_video_stream_tables,
+    }]
+}
+
+// early layouts kept camera/detection/hls/recording/rtp/snapshot tables at the
+// top level; move them under [video_stream]
+fn migrate_0001_video_stream_tables(
+    doc: &mut toml::Value,
+) -> Result<bool, PrintNannySettingsError> {
+    let table = match doc.as_table_mut() {
+        Some(table) => table,
+        None => return Ok(false),
+    };
+    let legacy_keys = ["camera", "detection", "hls", "recording", "rtp", "snapshot"];
+    let mut changed = false;
+    for key in legacy_keys {
+        if let Some(value) = table.remove(key) {
+            let video_stream = table
+                .entry("video_stream".to_string())
+                .or_insert_with(|| toml::Value::Table(toml::value::Table::new()));
+            if let Some(video_stream) = video_stream.as_table_mut() {
+                video_stream.entry(key.to_string()).or_insert(value);
+                changed = true;
+            }
+        }
+    }
+    Ok(changed)
+}
+
+fn record_file(paths: &PrintNannyPaths) -> std::path::PathBuf {
+    paths.state_dir.join(SETTINGS_MIGRATIONS_FILENAME)
+}
+
+async fn read_applied(
+    paths: &PrintNannyPaths,
+) -> Result<AppliedSettingsMigrations, PrintNannySettingsError> {
+    let record_file = record_file(paths);
+    match record_file.exists() {
+        true => Ok(serde_json::from_slice(&fs::read(&record_file).await?)?),
+        false => Ok(AppliedSettingsMigrations::default()),
+    }
+}
+
+// run all pending settings migrations, returning the versions applied
+pub async fn run_settings_migrations(
+    paths: &PrintNannyPaths,
+) -> Result<Vec<String>, PrintNannySettingsError> {
+    let settings_file = paths.settings_file();
+    if !settings_file.exists() {
+        info!(
+            "Settings file {} does not exist, skipping settings migrations",
+            settings_file.display()
+        );
+        return Ok(vec![]);
+    }
+
+    let mut record = read_applied(paths).await?;
+    let content = fs::read_to_string(&settings_file).await?;
+    let mut doc: toml::Value = toml::from_str(&content)?;
+
+    let mut newly_applied: Vec<String> = vec![];
+    let mut doc_changed = false;
+    for migration in all_migrations() {
+        if record
+            .applied
+            .iter()
+            .any(|applied| applied.version == migration.version)
+        {
+            continue;
+        }
+        let changed = (migration.migrate)(&mut doc)?;
+        info!(
+            "Applied settings migration version={} name={} changed={}",
+            migration.version, migration.name, changed
+        );
+        let applied_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        record.applied.push(AppliedSettingsMigration {
+            version: migration.version.to_string(),
+            name: migration.name.to_string(),
+            applied_at,
+            changed,
+        });
+        newly_applied.push(migration.version.to_string());
+        doc_changed = doc_changed || changed;
+    }
+
+    if !newly_applied.is_empty() {
+        if doc_changed {
+            atomic_write(
+                &settings_file,
+                toml::ser::to_string_pretty(&doc)?.as_bytes(),
+            )
+            .await?;
+            info!("Wrote migrated settings to {}", settings_file.display());
+        }
+        atomic_write(&record_file(paths), &serde_json::to_vec_pretty(&record)?).await?;
+    }
+    Ok(newly_applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEGACY_TOML: &str = r#"
+[camera]
+width = 1280
+height = 720
+
+[hls]
+enabled = true
+
+[video_stream.detection]
+nms_threshold = 50
+"#;
+
+    #[test_log::test]
+    fn test_migrate_0001_moves_legacy_tables() {
+        let mut doc: toml::Value = toml::from_str(LEGACY_TOML).unwrap();
+        let changed = migrate_0001_video_stream_tables(&mut doc).unwrap();
+        assert!(changed);
+        let table = doc.as_table().unwrap();
+        assert!(!table.contains_key("camera"));
+        assert!(!table.contains_key("hls"));
+        let video_stream = table.get("video_stream").unwrap().as_table().unwrap();
+        assert_eq!(
+            video_stream.get("camera").unwrap().get("width").unwrap(),
+            &toml::Value::Integer(1280)
+        );
+        assert_eq!(
+            video_stream.get("hls").unwrap().get("enabled").unwrap(),
+            &toml::Value::Boolean(true)
+        );
+        // existing nested tables are preserved
+        assert_eq!(
+            video_stream
+                .get("detection")
+                .unwrap()
+                .get("nms_threshold")
+                .unwrap(),
+            &toml::Value::Integer(50)
+        );
+    }
+
+    #[test_log::test]
+    fn test_migrate_0001_noop_on_current_layout() {
+        let mut doc: toml::Value = toml::from_str("[video_stream.camera]\nwidth = 640\n").unwrap();
+        let changed = migrate_0001_video_stream_tables(&mut doc).unwrap();
+        assert!(!changed);
+    }
+
+    #[test_log::test]
+    fn test_migrations_are_ordered() {
+        let migrations = all_migrations();
+        let mut versions: Vec<&str> = migrations.iter().map(|m| m.version).collect();
+        versions.sort_unstable();
+        assert_eq!(
+            versions,
+            migrations.iter().map(|m| m.version).collect::<Vec<&str>>()
+        );
+    }
+}
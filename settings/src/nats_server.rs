@@ -0,0 +1,183 @@
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+
+use printnanny_dbus::zbus;
+use printnanny_dbus::zbus_systemd;
+
+use crate::error::VersionControlledSettingsError;
+use crate::printnanny::{GitSettings, PrintNannySettings};
+use crate::vcs::{VersionControlledSettings, DEFAULT_VCS_SETTINGS_DIR};
+use crate::SettingsFormat;
+
+pub const NATS_SERVER_UNIT: &str = "nats-server.service";
+pub const DEFAULT_NATS_SERVER_SETTINGS_FILE: &str = "nats/nats-server.conf";
+
+// renders the embedded/leaf-node NATS server configuration (accounts,
+// leafnode remote to PrintNanny Cloud, local websocket listener) from the
+// [nats] settings table, so the on-device broker topology is reproducible
+// from the settings repo instead of hand-edited config files
+pub fn render_nats_server_conf(settings: &PrintNannySettings) -> String {
+    let hostname = crate::sys_info::hostname().unwrap_or_else(|_| "localhost".to_string());
+    let creds = settings.paths.cloud_nats_creds();
+
+    let mut conf = format!(
+        r#"# Generated by PrintNanny from PrintNannySettings [nats] - do not hand-edit.
+# Re-render with: printnanny nats server-config --apply
+server_name: "{hostname}"
+listen: "127.0.0.1:{listen_port}"
+
+# local websocket listener used by the PrintNanny UI
+websocket {{
+    listen: "127.0.0.1:{websocket_port}"
+    no_tls: true
+}}
+
+# on-device apps publish/subscribe under a single local account
+accounts {{
+    printnanny: {{
+        users: [
+            {{ user: "printnanny" }}
+        ]
+    }}
+}}
+"#,
+        hostname = hostname,
+        listen_port = settings.nats.listen_port,
+        websocket_port = settings.nats.websocket_port,
+    );
+
+    // the leafnode remote is only rendered once cloud credentials exist;
+    // nats-server refuses to start when the credentials file is missing
+    match creds.exists() {
+        true => conf.push_str(&format!(
+            r#"
+leafnodes {{
+    remotes: [
+        {{
+            url: "{url}"
+            account: "printnanny"
+            credentials: "{creds}"
+        }}
+    ]
+}}
+"#,
+            url = settings.nats.leafnode_remote_uri,
+            creds = creds.display(),
+        )),
+        false => conf
+            .push_str("\n# leafnode remote omitted: no PrintNanny Cloud credentials found yet\n"),
+    };
+    conf
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NatsServerSettings {
+    pub enabled: bool,
+    pub settings_file: PathBuf,
+    pub settings_format: SettingsFormat,
+    pub git_settings: GitSettings,
+}
+
+impl Default for NatsServerSettings {
+    fn default() -> Self {
+        let settings_file =
+            PathBuf::from(DEFAULT_VCS_SETTINGS_DIR).join(DEFAULT_NATS_SERVER_SETTINGS_FILE);
+        let git_settings = GitSettings::default();
+        Self {
+            settings_file,
+            enabled: true,
+            settings_format: SettingsFormat::Ini,
+            git_settings,
+        }
+    }
+}
+
+#[async_trait]
+impl VersionControlledSettings for NatsServerSettings {
+    type SettingsModel = NatsServerSettings;
+
+    fn from_dir(settings_dir: &Path) -> Self {
+        let settings_file = settings_dir.join(DEFAULT_NATS_SERVER_SETTINGS_FILE);
+        Self {
+            settings_file,
+            ..Self::default()
+        }
+    }
+    fn get_settings_format(&self) -> SettingsFormat {
+        self.settings_format
+    }
+    fn get_settings_file(&self) -> PathBuf {
+        self.settings_file.clone()
+    }
+
+    fn get_git_repo_path(&self) -> &Path {
+        &self.git_settings.path
+    }
+
+    fn get_git_remote(&self) -> &str {
+        &self.git_settings.remote
+    }
+
+    fn get_git_settings(&self) -> &GitSettings {
+        &self.git_settings
+    }
+
+    async fn pre_save(&self) -> Result<(), VersionControlledSettingsError> {
+        debug!("Running NatsServerSettings pre_save hook");
+        Ok(())
+    }
+
+    async fn post_save(&self) -> Result<(), VersionControlledSettingsError> {
+        debug!("Running NatsServerSettings post_save hook");
+        let connection = zbus::Connection::system().await?;
+        let proxy = zbus_systemd::systemd1::ManagerProxy::new(&connection).await?;
+        let job = proxy
+            .restart_unit(NATS_SERVER_UNIT.into(), "replace".into())
+            .await?;
+        info!("Restarted {}, job: {:?}", NATS_SERVER_UNIT, job);
+        Ok(())
+    }
+    fn validate(&self) -> Result<(), VersionControlledSettingsError> {
+        todo!("NatsServerSettings validate hook is not yet implemented");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_without_cloud_creds_omits_leafnode() {
+        let mut settings = PrintNannySettings::default();
+        let tmp = tempfile::tempdir().unwrap();
+        settings.paths.state_dir = tmp.path().to_path_buf();
+
+        let conf = render_nats_server_conf(&settings);
+        assert!(conf.contains("websocket {"));
+        assert!(conf.contains(&format!(
+            "listen: \"127.0.0.1:{}\"",
+            settings.nats.listen_port
+        )));
+        assert!(conf.contains("accounts {"));
+        assert!(!conf.contains("leafnodes {"));
+        assert!(conf.contains("leafnode remote omitted"));
+    }
+
+    #[test]
+    fn test_render_with_cloud_creds_includes_leafnode_remote() {
+        let mut settings = PrintNannySettings::default();
+        let tmp = tempfile::tempdir().unwrap();
+        settings.paths.state_dir = tmp.path().to_path_buf();
+        let creds = settings.paths.cloud_nats_creds();
+        std::fs::create_dir_all(creds.parent().unwrap()).unwrap();
+        std::fs::write(&creds, "-----BEGIN NATS USER JWT-----\n").unwrap();
+
+        let conf = render_nats_server_conf(&settings);
+        assert!(conf.contains("leafnodes {"));
+        assert!(conf.contains(&settings.nats.leafnode_remote_uri));
+        assert!(conf.contains(&creds.display().to_string()));
+    }
+}
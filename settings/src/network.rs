@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+// Wake-on-LAN targets for other machines on the farm LAN (slicer workstation,
+// CNC controller). Profiles are addressed by name in pi.{pi_id}.network.wol
+// requests so the cloud never needs to know MAC addresses.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct WolProfile {
+    pub name: String,
+    // colon- or hyphen-separated hex, e.g. "aa:bb:cc:dd:ee:ff"
+    pub mac_address: String,
+    // UDP destination for the magic packet; discard port 9 on the subnet
+    // broadcast address works for most LANs
+    pub broadcast_addr: String,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct NetworkSettings {
+    #[serde(default)]
+    pub wol_profiles: Vec<WolProfile>,
+}
+
+impl NetworkSettings {
+    pub fn wol_profile(&self, profile_name: &str) -> Option<&WolProfile> {
+        self.wol_profiles
+            .iter()
+            .find(|profile| profile.name == profile_name)
+    }
+}
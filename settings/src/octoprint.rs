@@ -135,6 +135,78 @@ impl Default for OctoPrintSettings {
     }
 }
 
+// typed model of the plugins.octoprint_nanny section inside OctoPrint's
+// config.yaml, so the cloud can configure the plugin through the same
+// version-controlled settings flow as every other settings file. Keys this
+// model does not know about are round-tripped untouched via `extra`, so a
+// newer plugin version can add settings without this crate shipping first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OctoPrintNannyPluginSettings {
+    #[serde(default = "default_plugin_enabled")]
+    pub enabled: bool,
+    // MJPEG/snapshot URL the plugin reports to PrintNanny Cloud
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webcam_url: Option<String>,
+    // OctoPrint event types the plugin forwards, e.g. PrintStarted
+    #[serde(default)]
+    pub events: Vec<String>,
+    #[serde(flatten)]
+    pub extra: std::collections::BTreeMap<String, serde_yaml::Value>,
+}
+
+fn default_plugin_enabled() -> bool {
+    true
+}
+
+impl Default for OctoPrintNannyPluginSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_plugin_enabled(),
+            webcam_url: None,
+            events: vec![],
+            extra: std::collections::BTreeMap::new(),
+        }
+    }
+}
+
+// read the plugins.octoprint_nanny block from a parsed config.yaml document,
+// defaulting when the section is absent
+pub fn plugin_settings_from_doc(
+    doc: &serde_yaml::Value,
+) -> Result<OctoPrintNannyPluginSettings, PrintNannySettingsError> {
+    match doc.get("plugins").and_then(|p| p.get("octoprint_nanny")) {
+        Some(section) => Ok(serde_yaml::from_value(section.clone())?),
+        None => Ok(OctoPrintNannyPluginSettings::default()),
+    }
+}
+
+// replace the plugins.octoprint_nanny block, leaving every other section of
+// config.yaml untouched
+pub fn set_plugin_settings_in_doc(
+    doc: &mut serde_yaml::Value,
+    plugin: &OctoPrintNannyPluginSettings,
+) -> Result<(), PrintNannySettingsError> {
+    if doc.is_null() {
+        *doc = serde_yaml::Value::Mapping(serde_yaml::Mapping::new());
+    }
+    let mapping = doc
+        .as_mapping_mut()
+        .ok_or_else(|| PrintNannySettingsError::InvalidValue {
+            value: "config.yaml root is not a mapping".to_string(),
+        })?;
+    let plugins = mapping
+        .entry("plugins".into())
+        .or_insert_with(|| serde_yaml::Value::Mapping(serde_yaml::Mapping::new()));
+    let plugins =
+        plugins
+            .as_mapping_mut()
+            .ok_or_else(|| PrintNannySettingsError::InvalidValue {
+                value: "config.yaml plugins section is not a mapping".to_string(),
+            })?;
+    plugins.insert("octoprint_nanny".into(), serde_yaml::to_value(plugin)?);
+    Ok(())
+}
+
 pub fn parse_pip_list_json(stdout: &str) -> Result<Vec<PipPackage>, PrintNannySettingsError> {
     let v: Vec<PipPackage> = serde_json::from_str(stdout)?;
     Ok(v)
@@ -241,6 +313,88 @@ impl OctoPrintSettings {
         }
     }
 
+    // read the typed plugins.octoprint_nanny section from config.yaml
+    pub async fn read_plugin_settings(
+        &self,
+    ) -> Result<OctoPrintNannyPluginSettings, VersionControlledSettingsError> {
+        let content = self.read_settings().await?;
+        let doc: serde_yaml::Value =
+            serde_yaml::from_str(&content).map_err(PrintNannySettingsError::from)?;
+        Ok(plugin_settings_from_doc(&doc)?)
+    }
+
+    // write the plugins.octoprint_nanny section, committing config.yaml through
+    // the version-controlled settings flow (service stop/start hooks included)
+    pub async fn write_plugin_settings(
+        &self,
+        plugin: &OctoPrintNannyPluginSettings,
+    ) -> Result<(), VersionControlledSettingsError> {
+        let content = self.read_settings().await.unwrap_or_default();
+        let mut doc: serde_yaml::Value = match content.is_empty() {
+            true => serde_yaml::Value::Null,
+            false => serde_yaml::from_str(&content).map_err(PrintNannySettingsError::from)?,
+        };
+        set_plugin_settings_in_doc(&mut doc, plugin)?;
+        let content = serde_yaml::to_string(&doc).map_err(PrintNannySettingsError::from)?;
+        self.save_and_commit(
+            &content,
+            Some("Updated plugins.octoprint_nanny settings".to_string()),
+        )
+        .await
+    }
+
+    // list OctoPrint plugins installed in the venv (pip packages named
+    // OctoPrint-*; OctoPrint itself is not a plugin)
+    pub async fn list_plugins(&self) -> Result<Vec<PipPackage>, PrintNannySettingsError> {
+        let packages = self.pip_packages().await?;
+        Ok(packages
+            .into_iter()
+            .filter(|p| p.name.starts_with("OctoPrint-"))
+            .collect())
+    }
+
+    async fn run_pip(&self, args: &[&str]) -> Result<String, PrintNannySettingsError> {
+        let python_path = self.python_path();
+        let output = Command::new(&python_path)
+            .arg("-m")
+            .arg("pip")
+            .args(args)
+            .output()
+            .await?;
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        match output.status.success() {
+            true => Ok(stdout),
+            false => Err(PrintNannySettingsError::CommandError {
+                cmd: format!("{} -m pip {}", python_path.display(), args.join(" ")),
+                code: output.status.code(),
+                stdout,
+                stderr,
+            }),
+        }
+    }
+
+    // pip install a plugin into the venv, pinned to `version` when given
+    pub async fn install_plugin(
+        &self,
+        package: &str,
+        version: Option<&str>,
+    ) -> Result<String, PrintNannySettingsError> {
+        let spec = match version {
+            Some(version) => format!("{}=={}", package, version),
+            None => package.to_string(),
+        };
+        let stdout = self.run_pip(&["install", &spec]).await?;
+        info!("Installed {} in venv {}", spec, self.venv.display());
+        Ok(stdout)
+    }
+
+    pub async fn uninstall_plugin(&self, package: &str) -> Result<String, PrintNannySettingsError> {
+        let stdout = self.run_pip(&["uninstall", "--yes", package]).await?;
+        info!("Uninstalled {} from venv {}", package, self.venv.display());
+        Ok(stdout)
+    }
+
     pub fn octoprint_version(&self, packages: &[PipPackage]) -> Option<String> {
         let python_path = self.python_path();
 
@@ -306,4 +460,75 @@ mod tests {
 
         assert_eq!(actual, expected)
     }
+
+    const EXAMPLE_CONFIG_YAML: &str = r#"
+api:
+  key: abc123
+plugins:
+  octoprint_nanny:
+    enabled: true
+    webcam_url: http://localhost:8080/?action=stream
+    events:
+      - PrintStarted
+      - PrintDone
+    auth_token: keep-me
+  softwareupdate:
+    check_providers: {}
+server:
+  port: 5000
+"#;
+
+    #[test]
+    fn test_plugin_settings_from_doc() {
+        let doc: serde_yaml::Value = serde_yaml::from_str(EXAMPLE_CONFIG_YAML).unwrap();
+        let plugin = plugin_settings_from_doc(&doc).unwrap();
+        assert!(plugin.enabled);
+        assert_eq!(
+            plugin.webcam_url,
+            Some("http://localhost:8080/?action=stream".to_string())
+        );
+        assert_eq!(plugin.events, vec!["PrintStarted", "PrintDone"]);
+        // keys this model does not know about are preserved
+        assert_eq!(
+            plugin.extra.get("auth_token"),
+            Some(&serde_yaml::Value::from("keep-me"))
+        );
+    }
+
+    #[test]
+    fn test_plugin_settings_default_when_section_missing() {
+        let doc: serde_yaml::Value = serde_yaml::from_str("server:\n  port: 5000\n").unwrap();
+        let plugin = plugin_settings_from_doc(&doc).unwrap();
+        assert_eq!(plugin, OctoPrintNannyPluginSettings::default());
+    }
+
+    #[test]
+    fn test_set_plugin_settings_preserves_other_sections() {
+        let mut doc: serde_yaml::Value = serde_yaml::from_str(EXAMPLE_CONFIG_YAML).unwrap();
+        let mut plugin = plugin_settings_from_doc(&doc).unwrap();
+        plugin.webcam_url = Some("http://printnanny.local/hls/playlist.m3u8".to_string());
+        set_plugin_settings_in_doc(&mut doc, &plugin).unwrap();
+
+        // other plugins and top-level sections are untouched
+        assert_eq!(doc["server"]["port"], serde_yaml::Value::from(5000_u64));
+        assert!(doc["plugins"]["softwareupdate"].is_mapping());
+        let roundtrip = plugin_settings_from_doc(&doc).unwrap();
+        assert_eq!(roundtrip, plugin);
+        // unknown plugin keys survive the write path too
+        assert_eq!(
+            roundtrip.extra.get("auth_token"),
+            Some(&serde_yaml::Value::from("keep-me"))
+        );
+    }
+
+    #[test]
+    fn test_set_plugin_settings_in_empty_doc() {
+        let mut doc = serde_yaml::Value::Null;
+        let plugin = OctoPrintNannyPluginSettings {
+            events: vec!["PrintFailed".to_string()],
+            ..OctoPrintNannyPluginSettings::default()
+        };
+        set_plugin_settings_in_doc(&mut doc, &plugin).unwrap();
+        assert_eq!(plugin_settings_from_doc(&doc).unwrap(), plugin);
+    }
 }
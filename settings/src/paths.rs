@@ -7,7 +7,6 @@ use std::collections::HashMap;
 use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
-use tokio::fs;
 use zip::ZipArchive;
 
 use crate::printnanny::PrintNannySettings;
@@ -19,6 +18,8 @@ pub const PRINTNANNY_SETTINGS_FILENAME: &str = "printnanny.toml";
 pub const DEFAULT_PRINTNANNY_SETTINGS_FILE: &str =
     "/home/printnanny/.config/printnanny/vcs/printnanny/printnanny.toml";
 pub const DEFAULT_PRINTNANNY_DATA_DIR: &str = "/home/printnanny/.local/share/printnanny";
+// conventional mountpoint for an external USB/SSD data drive
+pub const DEFAULT_EXTERNAL_DATA_MOUNT: &str = "/media/printnanny-data";
 
 #[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
 pub struct PrintNannyPaths {
@@ -29,6 +30,13 @@ pub struct PrintNannyPaths {
 
     pub issue_txt: PathBuf,  // path to /etc/issue
     pub os_release: PathBuf, // oath to /etc/os-release
+
+    // optional mountpoint of an external USB/SSD drive. While the drive is
+    // mounted, media/state heavy paths (video recordings, sqlite db) resolve
+    // onto it; when it disappears they fall back to state_dir so the device
+    // keeps working with whatever fits on the sd card.
+    #[serde(default)]
+    pub external_data_dir: Option<PathBuf>,
 }
 
 impl Default for PrintNannyPaths {
@@ -49,8 +57,56 @@ impl Default for PrintNannyPaths {
             log_dir,
             os_release,
             run_dir,
+            external_data_dir: None,
+        }
+    }
+}
+
+// true when `path` is the root of a mounted filesystem, detected by comparing
+// device ids with the parent directory. A configured-but-unmounted external
+// dir is just an empty directory on the root fs and must not be written to.
+pub fn is_mountpoint(path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    let meta = match std::fs::metadata(path) {
+        Ok(meta) => meta,
+        Err(_) => return false,
+    };
+    match path.parent().and_then(|p| std::fs::metadata(p).ok()) {
+        Some(parent_meta) => meta.dev() != parent_meta.dev(),
+        // no parent means `path` is the filesystem root
+        None => true,
+    }
+}
+
+// true when `dir` accepts writes, probed by creating the directory and writing
+// a throwaway file. On read-only-root images fs metadata alone is misleading:
+// a directory can be mode 0755 yet sit on an immutable overlayfs lower layer.
+pub fn dir_is_writable(dir: &Path) -> bool {
+    if std::fs::create_dir_all(dir).is_err() {
+        return false;
+    }
+    let probe = dir.join(format!(".printnanny-write-probe.{}", std::process::id()));
+    match std::fs::write(&probe, b"probe") {
+        Ok(_) => {
+            let _ = std::fs::remove_file(&probe);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+fn copy_dir_recursive(src: &Path, dest: &Path) -> Result<(), std::io::Error> {
+    std::fs::create_dir_all(dest)?;
+    for entry in std::fs::read_dir(src)? {
+        let entry = entry?;
+        let target = dest.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir_recursive(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), &target)?;
         }
     }
+    Ok(())
 }
 
 impl PrintNannyPaths {
@@ -63,6 +119,23 @@ impl PrintNannyPaths {
         self.run_dir.join("state.lock")
     }
 
+    // persisted device lifecycle state machine (see printnanny_services::lifecycle)
+    pub fn lifecycle(&self) -> PathBuf {
+        self.state_dir.join("lifecycle.json")
+    }
+
+    // marker written when power loss is detected, consumed on the next boot
+    // to report "recovered from power loss" (see printnanny_services::power)
+    pub fn power_loss(&self) -> PathBuf {
+        self.state_dir.join("power_loss.json")
+    }
+
+    // per-session transcripts of brokered remote support shell sessions
+    // (see printnanny_nats_apps::shell)
+    pub fn shell_transcripts(&self) -> PathBuf {
+        self.state_dir.join("shell")
+    }
+
     // user-facing settings file
     pub fn settings_file(&self) -> PathBuf {
         PathBuf::from(Env::var_or(
@@ -86,8 +159,33 @@ impl PrintNannyPaths {
         result
     }
 
+    // true when an external data drive is configured and currently mounted
+    pub fn external_data_mounted(&self) -> bool {
+        match &self.external_data_dir {
+            Some(dir) => is_mountpoint(dir),
+            None => false,
+        }
+    }
+
+    // health check: None when no external drive is configured, otherwise
+    // whether the configured mountpoint is actually mounted right now
+    pub fn external_data_status(&self) -> Option<bool> {
+        self.external_data_dir
+            .as_ref()
+            .map(|dir| is_mountpoint(dir))
+    }
+
+    // root for media/state heavy data: the external drive while mounted,
+    // falling back to state_dir when the drive is absent
+    pub fn data_root(&self) -> PathBuf {
+        match self.external_data_mounted() {
+            true => self.external_data_dir.clone().unwrap(),
+            false => self.state_dir.clone(),
+        }
+    }
+
     pub fn db(&self) -> PathBuf {
-        self.state_dir.join("db.sqlite")
+        self.data_root().join("db.sqlite")
     }
 
     // secrets, keys, credentials dir
@@ -100,6 +198,16 @@ impl PrintNannyPaths {
         self.state_dir.join("data")
     }
 
+    // log dir that is actually writable: log_dir on stock images, redirected
+    // into the runtime dir (tmpfs, always writable) on read-only-root images
+    // where /var/log is immutable
+    pub fn effective_log_dir(&self) -> PathBuf {
+        match dir_is_writable(&self.log_dir) {
+            true => self.log_dir.clone(),
+            false => self.run_dir.join("log"),
+        }
+    }
+
     // event adaptor used to bridge any sender -> cloud NATS
     pub fn events_socket(&self) -> PathBuf {
         self.run_dir.join("events.socket")
@@ -116,7 +224,17 @@ impl PrintNannyPaths {
 
     // media (videos)
     pub fn video(&self) -> PathBuf {
-        self.state_dir.join("video")
+        self.data_root().join("video")
+    }
+
+    // media (timelapse renders)
+    pub fn timelapse(&self) -> PathBuf {
+        self.data_root().join("timelapse")
+    }
+
+    // media (detection event evidence bundles)
+    pub fn evidence(&self) -> PathBuf {
+        self.data_root().join("evidence")
     }
 
     pub fn license_zip(&self) -> PathBuf {
@@ -148,6 +266,45 @@ impl PrintNannyPaths {
         Ok(())
     }
 
+    // move media/state heavy data from state_dir onto the mounted external
+    // drive, returning the migrated source paths. Data already present on the
+    // drive is never overwritten, so an sd card left over from before the
+    // drive was attached cannot clobber newer recordings.
+    pub fn migrate_data_to_external(&self) -> Result<Vec<PathBuf>, PrintNannySettingsError> {
+        match self.external_data_mounted() {
+            true => self.migrate_data_to(&self.external_data_dir.clone().unwrap()),
+            false => Ok(vec![]),
+        }
+    }
+
+    fn migrate_data_to(&self, target_root: &Path) -> Result<Vec<PathBuf>, PrintNannySettingsError> {
+        let mut migrated = vec![];
+
+        let db_src = self.state_dir.join("db.sqlite");
+        let db_dest = target_root.join("db.sqlite");
+        if db_src.exists() && !db_dest.exists() {
+            // rename fails across filesystems, so copy then remove
+            std::fs::copy(&db_src, &db_dest)?;
+            std::fs::remove_file(&db_src)?;
+            info!("Migrated {} to {}", db_src.display(), db_dest.display());
+            migrated.push(db_src);
+        }
+
+        let video_src = self.state_dir.join("video");
+        let video_dest = target_root.join("video");
+        if video_src.exists() && !video_dest.exists() {
+            copy_dir_recursive(&video_src, &video_dest)?;
+            std::fs::remove_dir_all(&video_src)?;
+            info!(
+                "Migrated {} to {}",
+                video_src.display(),
+                video_dest.display()
+            );
+            migrated.push(video_src);
+        }
+        Ok(migrated)
+    }
+
     pub fn try_load_nats_creds(&self) -> Result<String, std::io::Error> {
         std::fs::read_to_string(self.cloud_nats_creds())
     }
@@ -207,16 +364,8 @@ impl PrintNannyPaths {
                 }),
             }?;
 
-            match std::fs::write(dest, &contents) {
-                Ok(_) => {
-                    info!("Wrote seed file {:?}", dest);
-                    Ok(())
-                }
-                Err(error) => Err(PrintNannySettingsError::WriteIOError {
-                    path: PathBuf::from(filename),
-                    error,
-                }),
-            }?;
+            crate::atomic_write::atomic_write_sync(dest, contents.as_bytes())?;
+            info!("Wrote seed file {:?}", dest);
         }
         Ok(results)
     }
@@ -260,7 +409,7 @@ impl PrintNannyPaths {
             }
         }
 
-        fs::write(filename, b).await?;
+        crate::atomic_write::atomic_write(&filename, &b).await?;
 
         Ok(())
     }
@@ -288,3 +437,88 @@ impl PrintNannyPaths {
         ]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_is_mountpoint() {
+        // the filesystem root is always a mountpoint; a fresh temp dir is not
+        assert!(is_mountpoint(Path::new("/")));
+        let dir = tempfile::tempdir().unwrap();
+        assert!(!is_mountpoint(dir.path()));
+        assert!(!is_mountpoint(&dir.path().join("does-not-exist")));
+    }
+
+    #[test_log::test]
+    fn test_data_root_falls_back_when_drive_absent() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = PrintNannyPaths {
+            // configured but not a mountpoint, so it must not be used
+            external_data_dir: Some(dir.path().to_path_buf()),
+            ..PrintNannyPaths::default()
+        };
+        assert_eq!(paths.external_data_status(), Some(false));
+        assert_eq!(paths.data_root(), paths.state_dir);
+        assert_eq!(paths.db(), paths.state_dir.join("db.sqlite"));
+    }
+
+    #[test_log::test]
+    fn test_external_data_status_unconfigured() {
+        let paths = PrintNannyPaths::default();
+        assert_eq!(paths.external_data_status(), None);
+        assert!(!paths.external_data_mounted());
+    }
+
+    #[test_log::test]
+    fn test_migrate_data_moves_db_and_video() {
+        let state = tempfile::tempdir().unwrap();
+        let external = tempfile::tempdir().unwrap();
+        let paths = PrintNannyPaths {
+            state_dir: state.path().to_path_buf(),
+            ..PrintNannyPaths::default()
+        };
+        std::fs::write(state.path().join("db.sqlite"), b"sqlite bytes").unwrap();
+        std::fs::create_dir_all(state.path().join("video").join("camera")).unwrap();
+        std::fs::write(
+            state.path().join("video").join("camera").join("1.mp4"),
+            b"mp4 bytes",
+        )
+        .unwrap();
+
+        let migrated = paths.migrate_data_to(external.path()).unwrap();
+        assert_eq!(migrated.len(), 2);
+        assert!(!state.path().join("db.sqlite").exists());
+        assert!(!state.path().join("video").exists());
+        assert_eq!(
+            std::fs::read(external.path().join("db.sqlite")).unwrap(),
+            b"sqlite bytes"
+        );
+        assert_eq!(
+            std::fs::read(external.path().join("video").join("camera").join("1.mp4")).unwrap(),
+            b"mp4 bytes"
+        );
+    }
+
+    #[test_log::test]
+    fn test_migrate_data_never_overwrites_drive_contents() {
+        let state = tempfile::tempdir().unwrap();
+        let external = tempfile::tempdir().unwrap();
+        let paths = PrintNannyPaths {
+            state_dir: state.path().to_path_buf(),
+            ..PrintNannyPaths::default()
+        };
+        std::fs::write(state.path().join("db.sqlite"), b"stale sd card db").unwrap();
+        std::fs::write(external.path().join("db.sqlite"), b"newer drive db").unwrap();
+
+        let migrated = paths.migrate_data_to(external.path()).unwrap();
+        assert!(migrated.is_empty());
+        assert_eq!(
+            std::fs::read(external.path().join("db.sqlite")).unwrap(),
+            b"newer drive db"
+        );
+        // stale source is left in place for the operator to reconcile
+        assert!(state.path().join("db.sqlite").exists());
+    }
+}
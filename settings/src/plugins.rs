@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+// Context a plugin is allowed to receive with each request. Plugins get no
+// host access beyond the request payload unless a capability is granted here,
+// so a third-party .so can't read cloud credentials just by being installed.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum PluginCapability {
+    // hostname and OS release info included in the request context
+    #[serde(rename = "device_info")]
+    DeviceInfo,
+    // effective settings (minus cloud credentials) included in the request context
+    #[serde(rename = "settings_read")]
+    SettingsRead,
+}
+
+// one third-party NATS handler plugin: a cdylib implementing the versioned
+// printnanny_plugin_* C ABI, registered under pi.{pi_id}.plugin.<name>.>
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PluginSettings {
+    pub enabled: bool,
+    // subject namespace; the plugin only ever sees requests under
+    // pi.{pi_id}.plugin.<name>.>
+    pub name: String,
+    // path to the compiled cdylib
+    pub path: String,
+    #[serde(default)]
+    pub capabilities: Vec<PluginCapability>,
+}
@@ -12,12 +12,24 @@ use tokio::fs;
 
 use printnanny_dbus::zbus;
 
+use crate::atomic_write::atomic_write;
 use crate::cam::VideoStreamSettings;
+use crate::display::DisplaySettings;
+use crate::enclosure::EnclosureSettings;
 use crate::error::{PrintNannySettingsError, VersionControlledSettingsError};
+use crate::farm::FarmSettings;
+use crate::hooks::HooksSettings;
 use crate::klipper::{KlipperSettings, DEFAULT_KLIPPER_SETTINGS_FILE};
+use crate::lighting::LightingSettings;
 use crate::moonraker::{MoonrakerSettings, DEFAULT_MOONRAKER_SETTINGS_FILE};
+use crate::network::NetworkSettings;
 use crate::octoprint::{OctoPrintSettings, DEFAULT_OCTOPRINT_SETTINGS_FILE};
 use crate::paths::{PrintNannyPaths, DEFAULT_PRINTNANNY_SETTINGS_FILE};
+use crate::plugins::PluginSettings;
+use crate::schedule::ScheduleSettings;
+use crate::storage::StorageQuotaSettings;
+use crate::timelapse::TimelapseSettings;
+use crate::ups::UpsSettings;
 use crate::vcs::VersionControlledSettings;
 use crate::SettingsFormat;
 
@@ -48,6 +60,44 @@ impl Default for PrintNannyApiConfig {
 pub struct NatsConfig {
     pub uri: String,
     pub require_tls: bool,
+    // local client listener rendered into the embedded nats-server config
+    #[serde(default = "default_nats_listen_port")]
+    pub listen_port: u16,
+    // local websocket listener used by the PrintNanny UI
+    #[serde(default = "default_nats_websocket_port")]
+    pub websocket_port: u16,
+    // leafnode remote bridging the on-device broker to PrintNanny Cloud
+    #[serde(default = "default_nats_leafnode_remote_uri")]
+    pub leafnode_remote_uri: String,
+    // PEM CA bundle used to verify a self-hosted NATS server certificate;
+    // setting any certificate path implies require_tls
+    #[serde(default)]
+    pub tls_ca_cert: Option<PathBuf>,
+    // PEM client certificate/key pair presented for mutual TLS
+    #[serde(default)]
+    pub tls_client_cert: Option<PathBuf>,
+    #[serde(default)]
+    pub tls_client_key: Option<PathBuf>,
+    // hostname verification toggle for certificates that lack the server's
+    // LAN name/IP in the SAN
+    #[serde(default = "default_tls_verify_hostname")]
+    pub tls_verify_hostname: bool,
+}
+
+fn default_tls_verify_hostname() -> bool {
+    true
+}
+
+fn default_nats_listen_port() -> u16 {
+    4223
+}
+
+fn default_nats_websocket_port() -> u16 {
+    4224
+}
+
+fn default_nats_leafnode_remote_uri() -> String {
+    "tls://leafnodes.printnanny.ai:7422".to_string()
 }
 
 impl Default for NatsConfig {
@@ -55,6 +105,13 @@ impl Default for NatsConfig {
         Self {
             uri: "nats://localhost:4222".to_string(),
             require_tls: false,
+            listen_port: default_nats_listen_port(),
+            websocket_port: default_nats_websocket_port(),
+            leafnode_remote_uri: default_nats_leafnode_remote_uri(),
+            tls_ca_cert: None,
+            tls_client_cert: None,
+            tls_client_key: None,
+            tls_verify_hostname: true,
         }
     }
 }
@@ -72,6 +129,64 @@ pub struct SystemdUnit {
     enabled: bool,
 }
 
+// Fine-grained telemetry opt-outs. Enforcement lives at the transport choke
+// points (cloud event outbox relay, direct NATS event publishes, crash report
+// REST calls) via allows_subject/allows_crash_reports, so individual features
+// never need their own scattered checks.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct TelemetrySettings {
+    // automatic crash report submission
+    #[serde(default = "default_true")]
+    pub crash_reports: bool,
+    // heartbeats, resource usage and other system events
+    #[serde(default = "default_true")]
+    pub usage_metrics: bool,
+    // print quality detection statistics
+    #[serde(default = "default_true")]
+    pub detection_stats: bool,
+    // video snippets shared for model improvement; most sensitive, default off
+    #[serde(default)]
+    pub video_snippets: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for TelemetrySettings {
+    fn default() -> Self {
+        Self {
+            crash_reports: true,
+            usage_metrics: true,
+            detection_stats: true,
+            video_snippets: false,
+        }
+    }
+}
+
+impl TelemetrySettings {
+    // central transport-layer check: classify an outgoing NATS subject and
+    // apply the matching toggle. Functional events (recording lifecycle,
+    // settings, commands) are not telemetry and always pass.
+    pub fn allows_subject(&self, subject: &str) -> bool {
+        if subject.contains("crash_report") {
+            return self.crash_reports;
+        }
+        // snippet subjects carry frames, so the stricter video_snippets
+        // toggle wins even when the subject also mentions detection
+        if subject.contains(".snippet") {
+            return self.video_snippets;
+        }
+        if subject.contains("detection") || subject.contains(".qc.") {
+            return self.detection_stats;
+        }
+        if subject.contains(".event.system.") {
+            return self.usage_metrics;
+        }
+        true
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct GitSettings {
     pub path: PathBuf, // local git repo used to commit/revert changes to user-supplied config
@@ -95,12 +210,96 @@ impl Default for GitSettings {
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct PrintNannySettings {
+    // BCP 47 language tag used for user-facing strings, e.g. "en" or "es"
+    // serialized before the table-valued fields to keep toml output valid
+    #[serde(default = "default_locale")]
+    pub locale: String,
+    // controls whether NATS replies echo full payloads or only minimal status,
+    // for metered connections
+    #[serde(default)]
+    pub reply_detail: ReplyDetailLevel,
+    // publish a swap alert when a printnanny-owned unit swaps more than this
+    #[serde(default = "default_swap_alert_threshold_bytes")]
+    pub swap_alert_threshold_bytes: u64,
+    // cap on the total size of files under paths.log_dir; oldest rotated
+    // files are deleted first when the cap is exceeded
+    #[serde(default = "default_max_log_size_bytes")]
+    pub max_log_size_bytes: u64,
+    // opt-in for the cloud reverse tunnel; sessions are still granted
+    // one-at-a-time with a TTL and bandwidth cap even when enabled
+    #[serde(default)]
+    pub tunnel_enabled: bool,
+    // opt-in for brokered remote support shell sessions; sessions are
+    // time-limited and every command is recorded to a local transcript
+    #[serde(default)]
+    pub remote_shell_enabled: bool,
+    // declared before the table-valued fields: an empty plugin list serializes
+    // as the plain value `plugins = []`, which is invalid TOML after a table
+    #[serde(default)]
+    pub plugins: Vec<PluginSettings>,
     pub video_stream: VideoStreamSettings,
     pub cloud: PrintNannyApiConfig,
     pub git: GitSettings,
+    #[serde(default)]
+    pub hooks: HooksSettings,
+    #[serde(default)]
+    pub lighting: LightingSettings,
+    #[serde(default)]
+    pub display: DisplaySettings,
+    #[serde(default)]
+    pub enclosure: EnclosureSettings,
+    #[serde(default)]
+    pub telemetry: TelemetrySettings,
+    #[serde(default)]
+    pub ups: UpsSettings,
+    #[serde(default)]
+    pub farm: FarmSettings,
+    #[serde(default)]
+    pub network: NetworkSettings,
+    #[serde(default)]
+    pub nats: NatsConfig,
+    #[serde(default)]
+    pub schedule: ScheduleSettings,
+    // per-category storage quotas enforced by the retention engine
+    #[serde(default)]
+    pub storage: StorageQuotaSettings,
+    // timelapse assembly rendering options
+    #[serde(default)]
+    pub timelapse: TimelapseSettings,
     pub paths: PrintNannyPaths,
 }
 
+fn default_locale() -> String {
+    "en".to_string()
+}
+
+fn default_swap_alert_threshold_bytes() -> u64 {
+    // 8 MB: enough to ignore incidental paging, low enough to catch the
+    // inference pipeline thrashing on a Pi 3
+    8 * 1024 * 1024
+}
+
+fn default_max_log_size_bytes() -> u64 {
+    // 256 MB: pipeline and worker logs grow unbounded on long-running
+    // devices and have filled 1 GB /var partitions in the field
+    256 * 1024 * 1024
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReplyDetailLevel {
+    // strip payload echoes, git history, and journal excerpts from replies
+    Terse,
+    // include full payload echoes (default)
+    Verbose,
+}
+
+impl Default for ReplyDetailLevel {
+    fn default() -> Self {
+        ReplyDetailLevel::Verbose
+    }
+}
+
 impl Default for PrintNannySettings {
     fn default() -> Self {
         let git = GitSettings::default();
@@ -108,6 +307,25 @@ impl Default for PrintNannySettings {
 
         Self {
             cloud: PrintNannyApiConfig::default(),
+            hooks: HooksSettings::default(),
+            lighting: LightingSettings::default(),
+            display: DisplaySettings::default(),
+            enclosure: EnclosureSettings::default(),
+            plugins: vec![],
+            locale: default_locale(),
+            reply_detail: ReplyDetailLevel::default(),
+            swap_alert_threshold_bytes: default_swap_alert_threshold_bytes(),
+            max_log_size_bytes: default_max_log_size_bytes(),
+            tunnel_enabled: false,
+            remote_shell_enabled: false,
+            telemetry: TelemetrySettings::default(),
+            ups: UpsSettings::default(),
+            farm: FarmSettings::default(),
+            network: NetworkSettings::default(),
+            nats: NatsConfig::default(),
+            schedule: ScheduleSettings::default(),
+            storage: StorageQuotaSettings::default(),
+            timelapse: TimelapseSettings::default(),
             paths: PrintNannyPaths::default(),
             git,
             video_stream,
@@ -119,6 +337,8 @@ impl PrintNannySettings {
     pub async fn new() -> Result<Self, PrintNannySettingsError> {
         let figment = Self::figment().await?;
         let result: PrintNannySettings = figment.extract()?;
+        // report all cross-field invariant violations at once
+        crate::validation::validate_settings(&result)?;
         debug!("Initialized config {:?}", result);
 
         Ok(result)
@@ -145,6 +365,32 @@ impl PrintNannySettings {
         }
     }
 
+    pub fn to_nats_server_settings(&self) -> crate::nats_server::NatsServerSettings {
+        let git_settings = self.git.clone();
+        let settings_file = self
+            .git
+            .path
+            .join(crate::nats_server::DEFAULT_NATS_SERVER_SETTINGS_FILE);
+        crate::nats_server::NatsServerSettings {
+            git_settings,
+            settings_file,
+            ..crate::nats_server::NatsServerSettings::default()
+        }
+    }
+
+    pub fn to_stream_proxy_settings(&self) -> crate::stream_proxy::StreamProxySettings {
+        let git_settings = self.git.clone();
+        let settings_file = self
+            .git
+            .path
+            .join(crate::stream_proxy::DEFAULT_STREAM_PROXY_SETTINGS_FILE);
+        crate::stream_proxy::StreamProxySettings {
+            git_settings,
+            settings_file,
+            ..crate::stream_proxy::StreamProxySettings::default()
+        }
+    }
+
     pub fn to_klipper_settings(&self) -> KlipperSettings {
         let git_settings = self.git.clone();
         let settings_file = self.git.path.join(DEFAULT_KLIPPER_SETTINGS_FILE);
@@ -231,6 +477,15 @@ impl PrintNannySettings {
         Ok(figment.extract()?)
     }
 
+    // candidate settings from raw file content, without touching the settings
+    // file on disk; used to lint a file before it is applied
+    pub async fn from_toml_string(content: &str) -> Result<Self, PrintNannySettingsError> {
+        let figment = PrintNannySettings::figment()
+            .await?
+            .merge(Toml::string(content));
+        Ok(figment.extract()?)
+    }
+
     pub fn to_toml_string(&self) -> Result<String, PrintNannySettingsError> {
         let result = toml::ser::to_string_pretty(self)?;
         Ok(result)
@@ -245,7 +500,7 @@ impl PrintNannySettings {
     pub async fn try_save(&self) -> Result<(), PrintNannySettingsError> {
         let settings_file = self.paths.settings_file();
         let settings_data = toml::ser::to_string_pretty(self)?;
-        fs::write(settings_file, settings_data).await?;
+        atomic_write(&settings_file, settings_data.as_bytes()).await?;
         Ok(())
     }
     // Save settings to PRINTNANNY_SETTINGS
@@ -266,7 +521,7 @@ impl PrintNannySettings {
             SettingsFormat::Toml => toml::ser::to_string_pretty(self)?,
             _ => unimplemented!("try_init is not implemented for format: {}", format),
         };
-        fs::write(filename, content).await?;
+        atomic_write(Path::new(filename), content.as_bytes()).await?;
         Ok(())
     }
 
@@ -608,4 +863,25 @@ mod tests {
             Ok(())
         });
     }
+
+    #[test_log::test]
+    fn test_telemetry_subject_classification() {
+        let telemetry = TelemetrySettings {
+            crash_reports: false,
+            usage_metrics: false,
+            detection_stats: false,
+            video_snippets: false,
+        };
+        assert!(!telemetry.allows_subject("pi.pi1.crash_reports.os"));
+        assert!(!telemetry.allows_subject("pi.pi1.event.detection.stats"));
+        assert!(!telemetry.allows_subject("pi.pi1.event.system.heartbeat"));
+        assert!(!telemetry.allows_subject("pi.pi1.event.snippet.uploaded"));
+        // functional events are not telemetry and always pass
+        assert!(telemetry.allows_subject("pi.pi1.event.recording.finished"));
+
+        let telemetry = TelemetrySettings::default();
+        assert!(telemetry.allows_subject("pi.pi1.event.system.heartbeat"));
+        // model improvement snippets are opt-in
+        assert!(!telemetry.allows_subject("pi.pi1.event.snippet.uploaded"));
+    }
 }
@@ -0,0 +1,157 @@
+// Provenance snapshot of the effective figment layers, for `printnanny doctor --profile`.
+// Records which provider (defaults, settings file, environment variable) supplied each
+// settings key, with credential-shaped values redacted so the profile is safe to attach
+// to bug reports.
+use serde::{Deserialize, Serialize};
+
+use crate::error::PrintNannySettingsError;
+use crate::printnanny::PrintNannySettings;
+
+pub const REDACTED_VALUE: &str = "<redacted>";
+
+const SENSITIVE_KEY_MARKERS: [&str; 4] = ["token", "password", "secret", "bearer"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SettingsProvenanceEntry {
+    // dotted settings path, e.g. video_stream.detection.nms_threshold
+    pub key: String,
+    // effective value as a TOML literal, or REDACTED_VALUE for credential-shaped keys
+    pub value: String,
+    // figment provider name, e.g. "PrintNannySettings" or "PRINTNANNY_SETTINGS_ environment variable(s)"
+    pub provider: String,
+    // provider source (file path), if the provider has one
+    pub source: Option<String>,
+}
+
+fn is_sensitive_key(key: &str) -> bool {
+    let key = key.to_lowercase();
+    SENSITIVE_KEY_MARKERS
+        .iter()
+        .any(|marker| key.contains(marker))
+}
+
+fn flatten_leaves(prefix: &str, value: &toml::Value, leaves: &mut Vec<(String, String)>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (name, child) in table {
+                let key = match prefix.is_empty() {
+                    true => name.to_string(),
+                    false => format!("{}.{}", prefix, name),
+                };
+                flatten_leaves(&key, child, leaves);
+            }
+        }
+        other => leaves.push((prefix.to_string(), other.to_string())),
+    }
+}
+
+// snapshot the effective settings with per-key provenance metadata
+pub async fn settings_provenance() -> Result<Vec<SettingsProvenanceEntry>, PrintNannySettingsError>
+{
+    let figment = PrintNannySettings::figment().await?;
+    let settings: PrintNannySettings = figment.extract()?;
+    let doc = toml::Value::try_from(&settings)?;
+    let mut leaves: Vec<(String, String)> = vec![];
+    flatten_leaves("", &doc, &mut leaves);
+
+    let result = leaves
+        .into_iter()
+        .map(|(key, value)| {
+            let (provider, source) = match figment.find_metadata(&key) {
+                Some(metadata) => (
+                    metadata.name.to_string(),
+                    metadata.source.as_ref().map(|source| source.to_string()),
+                ),
+                None => ("PrintNannySettings".to_string(), None),
+            };
+            let value = match is_sensitive_key(&key) {
+                true => REDACTED_VALUE.to_string(),
+                false => value,
+            };
+            SettingsProvenanceEntry {
+                key,
+                value,
+                provider,
+                source,
+            }
+        })
+        .collect();
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::runtime::Runtime;
+
+    const PRINTNANNY_SETTINGS_FILENAME: &str = "PrintNannySettingsTest.toml";
+
+    fn find_entry<'a>(
+        entries: &'a [SettingsProvenanceEntry],
+        key: &str,
+    ) -> &'a SettingsProvenanceEntry {
+        entries
+            .iter()
+            .find(|entry| entry.key == key)
+            .unwrap_or_else(|| panic!("Expected provenance entry for key {}", key))
+    }
+
+    #[test_log::test]
+    fn test_provenance_env_overrides_file() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                PRINTNANNY_SETTINGS_FILENAME,
+                r#"
+                [paths]
+                log_dir = "/this/etc/path/gets/overridden"
+                "#,
+            )?;
+            jail.set_env("PRINTNANNY_SETTINGS", PRINTNANNY_SETTINGS_FILENAME);
+            jail.set_env("PRINTNANNY_SETTINGS_PATHS__LOG_DIR", "testing");
+            let entries = Runtime::new()
+                .unwrap()
+                .block_on(settings_provenance())
+                .unwrap();
+            let entry = find_entry(&entries, "paths.log_dir");
+            assert_eq!(entry.value, "\"testing\"");
+            assert!(entry.provider.contains("environment"));
+            Ok(())
+        });
+    }
+
+    #[test_log::test]
+    fn test_provenance_defaults_named() {
+        figment::Jail::expect_with(|jail| {
+            jail.set_env("PRINTNANNY_SETTINGS", PRINTNANNY_SETTINGS_FILENAME);
+            jail.create_file(PRINTNANNY_SETTINGS_FILENAME, "")?;
+            let entries = Runtime::new()
+                .unwrap()
+                .block_on(settings_provenance())
+                .unwrap();
+            let entry = find_entry(&entries, "video_stream.detection.nms_threshold");
+            assert_eq!(entry.provider, "PrintNannySettings");
+            Ok(())
+        });
+    }
+
+    #[test_log::test]
+    fn test_provenance_redacts_credentials() {
+        figment::Jail::expect_with(|jail| {
+            jail.set_env("PRINTNANNY_SETTINGS", PRINTNANNY_SETTINGS_FILENAME);
+            jail.create_file(
+                PRINTNANNY_SETTINGS_FILENAME,
+                r#"
+                [cloud]
+                api_bearer_access_token = "very-secret-token"
+                "#,
+            )?;
+            let entries = Runtime::new()
+                .unwrap()
+                .block_on(settings_provenance())
+                .unwrap();
+            let entry = find_entry(&entries, "cloud.api_bearer_access_token");
+            assert_eq!(entry.value, REDACTED_VALUE);
+            Ok(())
+        });
+    }
+}
@@ -0,0 +1,210 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::PrintNannySettingsError;
+
+// Cron-style scheduled tasks run by the NATS edge worker. Each task pairs a
+// standard 5-field cron expression (minute hour day-of-month month
+// day-of-week) with a built-in action or a user-defined NATS publish.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ScheduleSettings {
+    #[serde(default)]
+    pub tasks: Vec<ScheduledTaskSettings>,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ScheduledTaskSettings {
+    pub name: String,
+    // e.g. "0 3 * * *" for daily at 03:00, "*/15 * * * *" for every 15 minutes
+    pub cron: String,
+    pub action: ScheduledAction,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScheduledAction {
+    // zip the settings repo into paths.recovery()
+    SettingsBackup,
+    // delete recorded video/snapshots older than the given age
+    RetentionPrune { days: u32 },
+    // reconcile local state with PrintNanny Cloud
+    CloudSync,
+    // publish an arbitrary payload, e.g. to trigger a plugin or hook
+    NatsPublish { subject: String, payload: String },
+}
+
+// one parsed cron field: None matches everything ("*"), otherwise the sorted
+// list of values the field accepts
+type CronField = Option<Vec<u32>>;
+
+// a parsed 5-field cron expression; time component extraction is left to the
+// caller so this stays a pure table lookup
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CronExpr {
+    pub minute: CronField,
+    pub hour: CronField,
+    pub day_of_month: CronField,
+    pub month: CronField,
+    pub day_of_week: CronField,
+}
+
+fn invalid(expression: &str, detail: String) -> PrintNannySettingsError {
+    PrintNannySettingsError::InvalidCronExpression {
+        expression: expression.to_string(),
+        detail,
+    }
+}
+
+// one field: "*", "*/step", or a comma list of numbers and ranges ("1,5-10")
+fn parse_field(
+    expression: &str,
+    field: &str,
+    min: u32,
+    max: u32,
+) -> Result<CronField, PrintNannySettingsError> {
+    if field == "*" {
+        return Ok(None);
+    }
+    let mut values: Vec<u32> = vec![];
+    if let Some(step) = field.strip_prefix("*/") {
+        let step: u32 = step
+            .parse()
+            .map_err(|_| invalid(expression, format!("invalid step in {field}")))?;
+        if step == 0 {
+            return Err(invalid(expression, format!("zero step in {field}")));
+        }
+        values.extend((min..=max).step_by(step as usize));
+        return Ok(Some(values));
+    }
+    for part in field.split(',') {
+        let (start, end) = match part.split_once('-') {
+            Some((start, end)) => (
+                start
+                    .parse()
+                    .map_err(|_| invalid(expression, format!("invalid range start in {part}")))?,
+                end.parse()
+                    .map_err(|_| invalid(expression, format!("invalid range end in {part}")))?,
+            ),
+            None => {
+                let value: u32 = part
+                    .parse()
+                    .map_err(|_| invalid(expression, format!("invalid value {part}")))?;
+                (value, value)
+            }
+        };
+        if start > end || start < min || end > max {
+            return Err(invalid(
+                expression,
+                format!("{part} out of range {min}-{max}"),
+            ));
+        }
+        values.extend(start..=end);
+    }
+    values.sort_unstable();
+    values.dedup();
+    Ok(Some(values))
+}
+
+impl CronExpr {
+    pub fn parse(expression: &str) -> Result<Self, PrintNannySettingsError> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(invalid(
+                expression,
+                format!("expected 5 fields, found {}", fields.len()),
+            ));
+        }
+        Ok(Self {
+            minute: parse_field(expression, fields[0], 0, 59)?,
+            hour: parse_field(expression, fields[1], 0, 23)?,
+            day_of_month: parse_field(expression, fields[2], 1, 31)?,
+            month: parse_field(expression, fields[3], 1, 12)?,
+            // 0 and 7 both mean Sunday
+            day_of_week: parse_field(expression, fields[4], 0, 7)?,
+        })
+    }
+
+    // does the expression match the given instant? day_of_week uses cron
+    // numbering (0 or 7 = Sunday). Like cron, when both day fields are
+    // restricted the expression matches if either one does.
+    pub fn matches(
+        &self,
+        minute: u32,
+        hour: u32,
+        day_of_month: u32,
+        month: u32,
+        day_of_week: u32,
+    ) -> bool {
+        let field_matches = |field: &CronField, value: u32| match field {
+            None => true,
+            Some(values) => values.contains(&value),
+        };
+        let dow_matches = |field: &CronField| match field {
+            None => true,
+            Some(values) => {
+                values.contains(&day_of_week) || (day_of_week == 0 && values.contains(&7))
+            }
+        };
+        if !field_matches(&self.minute, minute)
+            || !field_matches(&self.hour, hour)
+            || !field_matches(&self.month, month)
+        {
+            return false;
+        }
+        match (&self.day_of_month, &self.day_of_week) {
+            (Some(_), Some(_)) => {
+                field_matches(&self.day_of_month, day_of_month) || dow_matches(&self.day_of_week)
+            }
+            _ => field_matches(&self.day_of_month, day_of_month) && dow_matches(&self.day_of_week),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_wildcard_and_step() {
+        let expr = CronExpr::parse("*/15 3 * * *").unwrap();
+        assert_eq!(expr.minute, Some(vec![0, 15, 30, 45]));
+        assert_eq!(expr.hour, Some(vec![3]));
+        assert_eq!(expr.day_of_month, None);
+
+        assert!(expr.matches(15, 3, 10, 6, 2));
+        assert!(!expr.matches(16, 3, 10, 6, 2));
+        assert!(!expr.matches(15, 4, 10, 6, 2));
+    }
+
+    #[test]
+    fn test_parse_lists_and_ranges() {
+        let expr = CronExpr::parse("0 8-10,18 * * 1-5").unwrap();
+        assert_eq!(expr.hour, Some(vec![8, 9, 10, 18]));
+        // weekdays only
+        assert!(expr.matches(0, 9, 1, 1, 3));
+        assert!(!expr.matches(0, 9, 1, 1, 0));
+    }
+
+    #[test]
+    fn test_sunday_is_zero_or_seven() {
+        let expr = CronExpr::parse("0 0 * * 7").unwrap();
+        assert!(expr.matches(0, 0, 1, 1, 0));
+    }
+
+    #[test]
+    fn test_both_day_fields_match_either() {
+        // cron semantics: "on the 1st, or on Mondays"
+        let expr = CronExpr::parse("0 0 1 * 1").unwrap();
+        assert!(expr.matches(0, 0, 1, 6, 4));
+        assert!(expr.matches(0, 0, 15, 6, 1));
+        assert!(!expr.matches(0, 0, 15, 6, 4));
+    }
+
+    #[test]
+    fn test_invalid_expressions_are_rejected() {
+        assert!(CronExpr::parse("* * * *").is_err());
+        assert!(CronExpr::parse("61 * * * *").is_err());
+        assert!(CronExpr::parse("*/0 * * * *").is_err());
+        assert!(CronExpr::parse("5-1 * * * *").is_err());
+        assert!(CronExpr::parse("x * * * *").is_err());
+    }
+}
@@ -0,0 +1,77 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::paths::PrintNannyPaths;
+
+// on-device data categories the retention engine accounts for separately, so
+// one category filling up (e.g. timelapses) evicts its own oldest files
+// instead of failure-evidence recordings
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageCategory {
+    Recordings,
+    Timelapses,
+    Snapshots,
+    Logs,
+    Backups,
+}
+
+impl StorageCategory {
+    pub const ALL: [StorageCategory; 5] = [
+        StorageCategory::Recordings,
+        StorageCategory::Timelapses,
+        StorageCategory::Snapshots,
+        StorageCategory::Logs,
+        StorageCategory::Backups,
+    ];
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            StorageCategory::Recordings => "recordings",
+            StorageCategory::Timelapses => "timelapses",
+            StorageCategory::Snapshots => "snapshots",
+            StorageCategory::Logs => "logs",
+            StorageCategory::Backups => "backups",
+        }
+    }
+
+    // directory accounted against this category's quota
+    pub fn dir(&self, paths: &PrintNannyPaths) -> PathBuf {
+        match self {
+            StorageCategory::Recordings => paths.video(),
+            StorageCategory::Timelapses => paths.timelapse(),
+            StorageCategory::Snapshots => paths.snapshot_dir.clone(),
+            StorageCategory::Logs => paths.effective_log_dir(),
+            StorageCategory::Backups => paths.recovery(),
+        }
+    }
+}
+
+// per-category byte quotas enforced by the retention engine; 0 disables the
+// quota for that category (historical unlimited behavior)
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct StorageQuotaSettings {
+    #[serde(default)]
+    pub recordings_max_bytes: u64,
+    #[serde(default)]
+    pub timelapses_max_bytes: u64,
+    #[serde(default)]
+    pub snapshots_max_bytes: u64,
+    #[serde(default)]
+    pub logs_max_bytes: u64,
+    #[serde(default)]
+    pub backups_max_bytes: u64,
+}
+
+impl StorageQuotaSettings {
+    pub fn max_bytes(&self, category: StorageCategory) -> u64 {
+        match category {
+            StorageCategory::Recordings => self.recordings_max_bytes,
+            StorageCategory::Timelapses => self.timelapses_max_bytes,
+            StorageCategory::Snapshots => self.snapshots_max_bytes,
+            StorageCategory::Logs => self.logs_max_bytes,
+            StorageCategory::Backups => self.backups_max_bytes,
+        }
+    }
+}
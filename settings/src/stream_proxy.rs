@@ -0,0 +1,212 @@
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+
+use printnanny_dbus::zbus;
+use printnanny_dbus::zbus_systemd;
+
+use crate::cam::{StreamAuthMode, StreamAuthSettings};
+use crate::error::VersionControlledSettingsError;
+use crate::printnanny::{GitSettings, PrintNannySettings};
+use crate::vcs::{VersionControlledSettings, DEFAULT_VCS_SETTINGS_DIR};
+use crate::SettingsFormat;
+
+pub const NGINX_UNIT: &str = "nginx.service";
+pub const DEFAULT_STREAM_PROXY_SETTINGS_FILE: &str = "nginx/printnanny-stream.conf";
+// internal nginx location used by token mode; the dashboard backend validates
+// the signed stream token (signature, expiry, endpoint scope) on this route
+pub const STREAM_AUTH_REQUEST_PATH: &str = "/printnanny-stream-auth";
+pub const STREAM_AUTH_UPSTREAM: &str = "http://127.0.0.1:9003/auth/stream";
+
+// per-endpoint auth directives rendered inside a location block
+fn auth_fragment(mode: StreamAuthMode, endpoint: &str, auth: &StreamAuthSettings) -> String {
+    match mode {
+        StreamAuthMode::None => "    # open to the LAN (auth mode: none)\n".to_string(),
+        StreamAuthMode::Basic => format!(
+            "    auth_basic \"{realm}\";\n    auth_basic_user_file {htpasswd};\n",
+            realm = auth.realm,
+            htpasswd = auth.htpasswd_file,
+        ),
+        // requires the ngx_http_auth_digest module shipped on PrintNanny OS
+        StreamAuthMode::Digest => format!(
+            "    auth_digest \"{realm}\";\n    auth_digest_user_file {htpasswd};\n",
+            realm = auth.realm,
+            htpasswd = auth.htpasswd_file,
+        ),
+        StreamAuthMode::Token => format!(
+            "    auth_request {path}/{endpoint};\n",
+            path = STREAM_AUTH_REQUEST_PATH,
+        ),
+    }
+}
+
+// renders the nginx location blocks fronting the viewer-facing camera
+// endpoints (HLS playlist/segments, MJPEG snapshots) with the per-output auth
+// configured in VideoStreamSettings.stream_auth, so LAN viewers can be
+// required to present credentials or a signed stream token
+pub fn render_stream_proxy_conf(settings: &PrintNannySettings) -> String {
+    let auth = &settings.video_stream.stream_auth;
+    let hls_root = Path::new(settings.video_stream.hls.segments.as_str())
+        .parent()
+        .unwrap_or_else(|| Path::new("/var/run/printnanny-hls"))
+        .display()
+        .to_string();
+    let snapshot_root = Path::new(settings.video_stream.snapshot.path.as_str())
+        .parent()
+        .unwrap_or_else(|| Path::new("/var/run/printnanny-snapshot"))
+        .display()
+        .to_string();
+
+    let mut conf = format!(
+        r#"# Generated by PrintNanny from PrintNannySettings [video_stream.stream_auth] - do not hand-edit.
+# Re-render with: printnanny cam proxy-config --apply
+
+# HLS playlist + segments
+location {playlist_root} {{
+    alias {hls_root}/;
+    add_header Cache-Control no-cache;
+{hls_auth}}}
+
+# MJPEG snapshot frames
+location /printnanny-snapshot/ {{
+    alias {snapshot_root}/;
+    add_header Cache-Control no-cache;
+{mjpeg_auth}}}
+"#,
+        playlist_root = settings.video_stream.hls.playlist_root,
+        hls_root = hls_root,
+        snapshot_root = snapshot_root,
+        hls_auth = auth_fragment(auth.hls, "hls", auth),
+        mjpeg_auth = auth_fragment(auth.mjpeg, "mjpeg", auth),
+    );
+
+    // the internal validation route is only rendered when some output uses
+    // token mode; ?token= query parameters are forwarded so share links work
+    // in players that cannot set an Authorization header
+    if auth.hls == StreamAuthMode::Token || auth.mjpeg == StreamAuthMode::Token {
+        conf.push_str(&format!(
+            r#"
+# internal endpoint validating stream tokens minted with: printnanny cam share
+location ~ ^{path}/(?<stream_endpoint>hls|mjpeg)$ {{
+    internal;
+    proxy_pass {upstream}/$stream_endpoint;
+    proxy_pass_request_body off;
+    proxy_set_header Content-Length "";
+    proxy_set_header X-Original-URI $request_uri;
+}}
+"#,
+            path = STREAM_AUTH_REQUEST_PATH,
+            upstream = STREAM_AUTH_UPSTREAM,
+        ));
+    }
+    conf
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StreamProxySettings {
+    pub enabled: bool,
+    pub settings_file: PathBuf,
+    pub settings_format: SettingsFormat,
+    pub git_settings: GitSettings,
+}
+
+impl Default for StreamProxySettings {
+    fn default() -> Self {
+        let settings_file =
+            PathBuf::from(DEFAULT_VCS_SETTINGS_DIR).join(DEFAULT_STREAM_PROXY_SETTINGS_FILE);
+        let git_settings = GitSettings::default();
+        Self {
+            settings_file,
+            enabled: true,
+            settings_format: SettingsFormat::Ini,
+            git_settings,
+        }
+    }
+}
+
+#[async_trait]
+impl VersionControlledSettings for StreamProxySettings {
+    type SettingsModel = StreamProxySettings;
+
+    fn from_dir(settings_dir: &Path) -> Self {
+        let settings_file = settings_dir.join(DEFAULT_STREAM_PROXY_SETTINGS_FILE);
+        Self {
+            settings_file,
+            ..Self::default()
+        }
+    }
+    fn get_settings_format(&self) -> SettingsFormat {
+        self.settings_format
+    }
+    fn get_settings_file(&self) -> PathBuf {
+        self.settings_file.clone()
+    }
+
+    fn get_git_repo_path(&self) -> &Path {
+        &self.git_settings.path
+    }
+
+    fn get_git_remote(&self) -> &str {
+        &self.git_settings.remote
+    }
+
+    fn get_git_settings(&self) -> &GitSettings {
+        &self.git_settings
+    }
+
+    async fn pre_save(&self) -> Result<(), VersionControlledSettingsError> {
+        debug!("Running StreamProxySettings pre_save hook");
+        Ok(())
+    }
+
+    async fn post_save(&self) -> Result<(), VersionControlledSettingsError> {
+        debug!("Running StreamProxySettings post_save hook");
+        let connection = zbus::Connection::system().await?;
+        let proxy = zbus_systemd::systemd1::ManagerProxy::new(&connection).await?;
+        let job = proxy
+            .reload_unit(NGINX_UNIT.into(), "replace".into())
+            .await?;
+        info!("Reloaded {}, job: {:?}", NGINX_UNIT, job);
+        Ok(())
+    }
+    fn validate(&self) -> Result<(), VersionControlledSettingsError> {
+        todo!("StreamProxySettings validate hook is not yet implemented");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_default_is_open() {
+        let settings = PrintNannySettings::default();
+        let conf = render_stream_proxy_conf(&settings);
+        assert!(conf.contains(&settings.video_stream.hls.playlist_root));
+        assert!(conf.contains("location /printnanny-snapshot/"));
+        assert!(conf.contains("auth mode: none"));
+        assert!(!conf.contains("auth_basic"));
+        assert!(!conf.contains("auth_request"));
+    }
+
+    #[test]
+    fn test_render_per_output_auth_modes() {
+        let mut settings = PrintNannySettings::default();
+        settings.video_stream.stream_auth.hls = StreamAuthMode::Basic;
+        settings.video_stream.stream_auth.mjpeg = StreamAuthMode::Token;
+
+        let conf = render_stream_proxy_conf(&settings);
+        // basic auth on the HLS block
+        assert!(conf.contains(&format!(
+            "auth_basic \"{}\";",
+            settings.video_stream.stream_auth.realm
+        )));
+        assert!(conf.contains(&settings.video_stream.stream_auth.htpasswd_file));
+        // token auth on the MJPEG block, with the internal validation route
+        assert!(conf.contains(&format!("auth_request {}/mjpeg;", STREAM_AUTH_REQUEST_PATH)));
+        assert!(conf.contains("internal;"));
+        assert!(conf.contains(STREAM_AUTH_UPSTREAM));
+    }
+}
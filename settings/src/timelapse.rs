@@ -0,0 +1,97 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+// speed ramp applied when mapping captured frames onto the output timeline:
+// easing spends more of the final video on one end of the print (e.g. ease_in
+// lingers on the first layers, where adhesion failures show)
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SpeedRampProfile {
+    #[default]
+    Constant,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl SpeedRampProfile {
+    pub fn as_str(&self) -> &str {
+        match self {
+            SpeedRampProfile::Constant => "constant",
+            SpeedRampProfile::EaseIn => "ease_in",
+            SpeedRampProfile::EaseOut => "ease_out",
+            SpeedRampProfile::EaseInOut => "ease_in_out",
+        }
+    }
+}
+
+// rendering options for one timelapse assembly pass
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct TimelapseRenderSettings {
+    // output framerate of the assembled video
+    pub framerate: u32,
+    pub speed_ramp: SpeedRampProfile,
+    // smooth exposure changes between frames (auto-exposure cameras flicker
+    // as the hotend/bed lighting shifts)
+    pub deflicker: bool,
+    // compensate frame-to-frame camera shake; bed-slinger printers rock the
+    // whole frame on every Y move
+    pub stabilization: bool,
+}
+
+impl Default for TimelapseRenderSettings {
+    fn default() -> Self {
+        Self {
+            framerate: 30,
+            speed_ramp: SpeedRampProfile::Constant,
+            deflicker: false,
+            stabilization: false,
+        }
+    }
+}
+
+// timelapse assembly configuration: default render options plus named
+// per-print-profile overrides, selected by profile name at render time
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct TimelapseSettings {
+    #[serde(default)]
+    pub render: TimelapseRenderSettings,
+    // overrides keyed by print profile name, e.g. "petg-slow" or "benchy"
+    #[serde(default)]
+    pub profiles: BTreeMap<String, TimelapseRenderSettings>,
+}
+
+impl TimelapseSettings {
+    // render options for a print profile, falling back to the defaults when
+    // no profile is given or no override exists
+    pub fn resolve(&self, profile: Option<&str>) -> &TimelapseRenderSettings {
+        profile
+            .and_then(|name| self.profiles.get(name))
+            .unwrap_or(&self.render)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_resolve_falls_back_to_default_render() {
+        let mut settings = TimelapseSettings::default();
+        settings.profiles.insert(
+            "benchy".to_string(),
+            TimelapseRenderSettings {
+                framerate: 60,
+                speed_ramp: SpeedRampProfile::EaseIn,
+                deflicker: true,
+                stabilization: true,
+            },
+        );
+        assert_eq!(settings.resolve(Some("benchy")).framerate, 60);
+        assert_eq!(settings.resolve(Some("unknown")).framerate, 30);
+        assert_eq!(settings.resolve(None).framerate, 30);
+    }
+}
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+// Power-loss detection source: either a UPS HAT signalling "on battery" on a
+// GPIO line (Waveshare, PiJuice-style boards in simple mode) or a NUT
+// (Network UPS Tools) server queried via upsc.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum UpsSource {
+    #[serde(rename = "gpio")]
+    Gpio,
+    #[serde(rename = "nut")]
+    Nut,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct UpsSettings {
+    pub enabled: bool,
+    pub source: UpsSource,
+    // gpio line driven by the UPS HAT while running on battery (read via gpioget)
+    pub gpio_chip: String,
+    pub gpio_pin: i32,
+    // set when the HAT pulls the line low on battery instead of high
+    pub gpio_active_low: bool,
+    // upsc target, e.g. "ups@localhost"
+    pub nut_ups: String,
+    // pause the active print through OctoPrint when power is lost, so a
+    // restored-power resume is possible instead of a ruined part
+    pub pause_print_on_battery: bool,
+    // initiate a clean shutdown when battery charge falls below this percent
+    // (NUT only; GPIO HATs report no charge telemetry)
+    pub shutdown_below_charge_percent: i32,
+    // clean shutdown after this long on battery regardless of charge; 0
+    // disables the timer (rely on charge telemetry alone)
+    pub shutdown_after_battery_seconds: u64,
+    pub shutdown_command: String,
+}
+
+impl Default for UpsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            source: UpsSource::Gpio,
+            gpio_chip: "gpiochip0".into(),
+            gpio_pin: 27,
+            gpio_active_low: false,
+            nut_ups: "ups@localhost".into(),
+            pause_print_on_battery: true,
+            shutdown_below_charge_percent: 20,
+            shutdown_after_battery_seconds: 300,
+            shutdown_command: "systemctl poweroff".into(),
+        }
+    }
+}
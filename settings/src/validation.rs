@@ -0,0 +1,213 @@
+// Cross-field validation for PrintNannySettings, run after figment extraction.
+// All violations are collected and reported at once with their field paths,
+// instead of failing on the first serde error deep in a handler.
+use std::path::Path;
+
+use crate::error::PrintNannySettingsError;
+use crate::printnanny::PrintNannySettings;
+
+const VALIGNMENTS: [&str; 3] = ["top", "center", "bottom"];
+const HALIGNMENTS: [&str; 3] = ["left", "center", "right"];
+
+fn check_port(violations: &mut Vec<String>, field: &str, port: i32) {
+    if !(1..=65535).contains(&port) {
+        violations.push(format!("{field}: port {port} is not in range 1-65535"));
+    }
+}
+
+fn check_absolute_writable(violations: &mut Vec<String>, field: &str, path: &str) {
+    let path = Path::new(path);
+    if !path.is_absolute() {
+        violations.push(format!("{field}: path {} is not absolute", path.display()));
+        return;
+    }
+    // check the nearest existing ancestor is writable; missing directories are
+    // created later by try_init_all
+    let mut ancestor = Some(path);
+    while let Some(current) = ancestor {
+        if current.exists() {
+            if let Ok(metadata) = current.metadata() {
+                if metadata.permissions().readonly() {
+                    violations.push(format!(
+                        "{field}: path {} is not writable",
+                        current.display()
+                    ));
+                }
+            }
+            return;
+        }
+        ancestor = current.parent();
+    }
+}
+
+// collect all cross-field invariant violations, with field paths
+pub fn validate_settings(settings: &PrintNannySettings) -> Result<(), PrintNannySettingsError> {
+    let mut violations: Vec<String> = vec![];
+
+    let video_stream = &settings.video_stream;
+    let camera = &*video_stream.camera;
+    let detection = &*video_stream.detection;
+    let hls = &*video_stream.hls;
+    let recording = &*video_stream.recording;
+    let rtp = &*video_stream.rtp;
+    let snapshot = &*video_stream.snapshot;
+
+    // ports must not collide
+    check_port(
+        &mut violations,
+        "video_stream.rtp.video_udp_port",
+        rtp.video_udp_port,
+    );
+    check_port(
+        &mut violations,
+        "video_stream.rtp.overlay_udp_port",
+        rtp.overlay_udp_port,
+    );
+    if rtp.video_udp_port == rtp.overlay_udp_port {
+        violations.push(format!(
+            "video_stream.rtp.video_udp_port: port {} collides with video_stream.rtp.overlay_udp_port",
+            rtp.video_udp_port
+        ));
+    }
+
+    // framerate within camera caps
+    if camera.framerate_n < 1 || camera.framerate_d < 1 {
+        violations.push(format!(
+            "video_stream.camera.framerate_n: framerate {}/{} is not a positive fraction",
+            camera.framerate_n, camera.framerate_d
+        ));
+    } else if camera.framerate_n / camera.framerate_d > 120 {
+        violations.push(format!(
+            "video_stream.camera.framerate_n: framerate {}/{} exceeds 120 fps",
+            camera.framerate_n, camera.framerate_d
+        ));
+    } else if detection.tensor_framerate > camera.framerate_n / camera.framerate_d {
+        violations.push(format!(
+            "video_stream.detection.tensor_framerate: {} exceeds camera framerate {}/{}",
+            detection.tensor_framerate, camera.framerate_n, camera.framerate_d
+        ));
+    }
+
+    // thresholds are percentages
+    if !(0..=100).contains(&detection.nms_threshold) {
+        violations.push(format!(
+            "video_stream.detection.nms_threshold: {} is not in range 0-100",
+            detection.nms_threshold
+        ));
+    }
+    if !(0..=255).contains(&settings.lighting.luminance_threshold) {
+        violations.push(format!(
+            "lighting.luminance_threshold: {} is not in range 0-255",
+            settings.lighting.luminance_threshold
+        ));
+    }
+
+    // paths must be absolute and writable
+    check_absolute_writable(&mut violations, "video_stream.hls.segments", &hls.segments);
+    check_absolute_writable(&mut violations, "video_stream.hls.playlist", &hls.playlist);
+    check_absolute_writable(
+        &mut violations,
+        "video_stream.recording.path",
+        &recording.path,
+    );
+    check_absolute_writable(
+        &mut violations,
+        "video_stream.snapshot.path",
+        &snapshot.path,
+    );
+
+    // user-supplied custom graph fragments: denylist, element existence, and
+    // a gst::parse_launch dry-run
+    for (i, fragment) in video_stream.custom_graph.fragments.iter().enumerate() {
+        violations.extend(crate::gst_lint::lint_fragment(
+            &format!("video_stream.custom_graph.fragments[{i}]"),
+            fragment,
+        ));
+    }
+
+    // watermark overlay alignment values
+    let watermark = &video_stream.watermark;
+    if !VALIGNMENTS.contains(&watermark.valignment.as_str()) {
+        violations.push(format!(
+            "video_stream.watermark.valignment: {} is not one of {:?}",
+            watermark.valignment, VALIGNMENTS
+        ));
+    }
+    if !HALIGNMENTS.contains(&watermark.halignment.as_str()) {
+        violations.push(format!(
+            "video_stream.watermark.halignment: {} is not one of {:?}",
+            watermark.halignment, HALIGNMENTS
+        ));
+    }
+
+    match violations.is_empty() {
+        true => Ok(()),
+        false => Err(PrintNannySettingsError::InvalidSettings { violations }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_default_settings_are_valid() {
+        let settings = PrintNannySettings::default();
+        assert!(validate_settings(&settings).is_ok());
+    }
+
+    #[test_log::test]
+    fn test_all_violations_reported_at_once() {
+        let mut settings = PrintNannySettings::default();
+        settings.video_stream.rtp.overlay_udp_port = settings.video_stream.rtp.video_udp_port;
+        settings.video_stream.detection.nms_threshold = 200;
+        settings.video_stream.watermark.valignment = "upside-down".into();
+        let err = validate_settings(&settings).unwrap_err();
+        match err {
+            PrintNannySettingsError::InvalidSettings { violations } => {
+                assert_eq!(violations.len(), 3);
+                assert!(violations[0].starts_with("video_stream.rtp.video_udp_port"));
+                assert!(violations[1].starts_with("video_stream.detection.nms_threshold"));
+                assert!(violations[2].starts_with("video_stream.watermark.valignment"));
+            }
+            e => panic!("Expected InvalidSettings, got {:?}", e),
+        }
+    }
+
+    #[test_log::test]
+    fn test_relative_path_rejected() {
+        let mut settings = PrintNannySettings::default();
+        settings.video_stream.recording.path = "relative/video".into();
+        let err = validate_settings(&settings).unwrap_err();
+        match err {
+            PrintNannySettingsError::InvalidSettings { violations } => {
+                assert_eq!(violations.len(), 1);
+                assert!(violations[0].starts_with("video_stream.recording.path"));
+            }
+            e => panic!("Expected InvalidSettings, got {:?}", e),
+        }
+    }
+
+    #[test_log::test]
+    fn test_unsafe_custom_graph_fragment_rejected() {
+        let mut settings = PrintNannySettings::default();
+        settings.video_stream.custom_graph.fragments =
+            vec!["interpipesrc listen-to=h264 ! filesink location=/tmp/out".into()];
+        let err = validate_settings(&settings).unwrap_err();
+        match err {
+            PrintNannySettingsError::InvalidSettings { violations } => {
+                assert!(violations[0].starts_with("video_stream.custom_graph.fragments[0]"));
+                assert!(violations[0].contains("filesink"));
+            }
+            e => panic!("Expected InvalidSettings, got {:?}", e),
+        }
+    }
+
+    #[test_log::test]
+    fn test_tensor_framerate_exceeding_camera_framerate_rejected() {
+        let mut settings = PrintNannySettings::default();
+        settings.video_stream.detection.tensor_framerate =
+            settings.video_stream.camera.framerate_n + 1;
+        assert!(validate_settings(&settings).is_err());
+    }
+}
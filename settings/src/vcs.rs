@@ -1,6 +1,10 @@
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
 use tokio::fs;
+use tokio::sync::Mutex as AsyncMutex;
+use tokio::task::spawn_blocking;
 
 use async_trait::async_trait;
 use git2::{DiffFormat, DiffOptions, Repository};
@@ -14,6 +18,16 @@ use crate::SettingsFormat;
 
 pub const DEFAULT_VCS_SETTINGS_DIR: &str = "/home/printnanny/.config/printnanny/vcs";
 
+// per-repo async locks: git2 index operations from concurrent apply requests
+// must not interleave, or the index is corrupted
+static REPO_LOCKS: Mutex<Option<HashMap<PathBuf, Arc<AsyncMutex<()>>>>> = Mutex::new(None);
+
+fn repo_lock(path: &Path) -> Arc<AsyncMutex<()>> {
+    let mut guard = REPO_LOCKS.lock().unwrap();
+    let locks = guard.get_or_insert_with(HashMap::new);
+    locks.entry(path.to_path_buf()).or_default().clone()
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct GitCommit {
     pub oid: String,
@@ -137,12 +151,9 @@ pub trait VersionControlledSettings {
                 }),
             }?;
         }
-        match fs::write(&output, content).await {
+        match crate::atomic_write::atomic_write(&output, content.as_bytes()).await {
             Ok(_) => Ok(()),
-            Err(e) => Err(VersionControlledSettingsError::WriteIOError {
-                path: output.display().to_string(),
-                error: e,
-            }),
+            Err(e) => Err(VersionControlledSettingsError::PrintNannySettingsError(e)),
         }?;
         info!("Wrote settings to {}", output.display());
         Ok(())
@@ -221,6 +232,24 @@ pub trait VersionControlledSettings {
         Ok(result)
     }
 
+    fn git_tag(&self, tag: &str) -> Result<git2::Oid, VersionControlledSettingsError> {
+        let repo = self.get_git_repo()?;
+        let head = repo.head()?.peel(git2::ObjectType::Commit)?;
+        // force-move the tag so repeated snapshots keep only the most recent
+        let oid = repo.tag_lightweight(tag, &head, true)?;
+        info!("Tagged settings repo tag={} oid={}", tag, oid);
+        Ok(oid)
+    }
+
+    fn git_reset_to_tag(&self, tag: &str) -> Result<(), VersionControlledSettingsError> {
+        let repo = self.get_git_repo()?;
+        let reference = repo.find_reference(&format!("refs/tags/{tag}"))?;
+        let commit = reference.peel_to_commit()?;
+        repo.reset(commit.as_object(), git2::ResetType::Hard, None)?;
+        info!("Reset settings repo to tag={} oid={}", tag, commit.id());
+        Ok(())
+    }
+
     fn git_revert(&self, oid: Option<git2::Oid>) -> Result<(), VersionControlledSettingsError> {
         let repo = self.get_git_repo()?;
         let commit = match oid {
@@ -230,12 +259,72 @@ pub trait VersionControlledSettings {
         Ok(repo.revert(&commit, None)?)
     }
 
+    // run a blocking git2 operation on the blocking thread pool, holding the
+    // per-repo lock so concurrent requests serialize instead of interleaving
+    async fn with_git_repo_lock<T, F>(&self, f: F) -> Result<T, VersionControlledSettingsError>
+    where
+        Self: Clone + Send + Sync + 'static,
+        T: Send + 'static,
+        F: FnOnce(Self) -> Result<T, VersionControlledSettingsError> + Send + 'static,
+    {
+        let lock = repo_lock(self.get_git_repo_path());
+        let _guard = lock.lock().await;
+        let this = self.clone();
+        spawn_blocking(move || f(this)).await?
+    }
+
+    async fn git_diff_async(&self) -> Result<String, VersionControlledSettingsError>
+    where
+        Self: Clone + Send + Sync + 'static,
+    {
+        self.with_git_repo_lock(|this| this.git_diff()).await
+    }
+
+    async fn get_git_head_commit_async(&self) -> Result<GitCommit, VersionControlledSettingsError>
+    where
+        Self: Clone + Send + Sync + 'static,
+    {
+        self.with_git_repo_lock(|this| this.get_git_head_commit())
+            .await
+    }
+
+    async fn get_rev_list_async(&self) -> Result<Vec<GitCommit>, VersionControlledSettingsError>
+    where
+        Self: Clone + Send + Sync + 'static,
+    {
+        self.with_git_repo_lock(|this| this.get_rev_list()).await
+    }
+
+    async fn git_tag_async(&self, tag: &str) -> Result<git2::Oid, VersionControlledSettingsError>
+    where
+        Self: Clone + Send + Sync + 'static,
+    {
+        let tag = tag.to_string();
+        self.with_git_repo_lock(move |this| this.git_tag(&tag))
+            .await
+    }
+
+    async fn git_reset_to_tag_async(&self, tag: &str) -> Result<(), VersionControlledSettingsError>
+    where
+        Self: Clone + Send + Sync + 'static,
+    {
+        let tag = tag.to_string();
+        self.with_git_repo_lock(move |this| this.git_reset_to_tag(&tag))
+            .await
+    }
+
     async fn git_revert_hooks(
         &self,
         oid: Option<git2::Oid>,
-    ) -> Result<(), VersionControlledSettingsError> {
+    ) -> Result<(), VersionControlledSettingsError>
+    where
+        Self: Clone + Send + Sync + 'static,
+    {
+        let lock = repo_lock(self.get_git_repo_path());
+        let _guard = lock.lock().await;
         self.pre_save().await?;
-        self.git_revert(oid)?;
+        let this = self.clone();
+        spawn_blocking(move || this.git_revert(oid)).await??;
         self.pre_save().await?;
         Ok(())
     }
@@ -244,16 +333,29 @@ pub trait VersionControlledSettings {
         &self,
         content: &str,
         commit_msg: Option<String>,
-    ) -> Result<(), VersionControlledSettingsError> {
+    ) -> Result<(), VersionControlledSettingsError>
+    where
+        Self: Clone + Send + Sync + 'static,
+    {
+        // hold the per-repo lock for the whole save, so concurrent apply
+        // requests serialize instead of corrupting the index
+        let lock = repo_lock(self.get_git_repo_path());
+        let _guard = lock.lock().await;
         // first, get repo (clone will run if repo is not present, which requires empty path)
-        self.get_git_repo()?;
+        let this = self.clone();
+        spawn_blocking(move || this.get_git_repo().map(|_| ())).await??;
         // then run any pre-save hooks
         self.pre_save().await?;
         // write settings file
         self.write_settings(content).await?;
         // commit changes
-        self.git_add_all()?;
-        self.git_commit(commit_msg)?;
+        let this = self.clone();
+        spawn_blocking(move || {
+            this.git_add_all()?;
+            this.git_commit(commit_msg)?;
+            Ok::<(), VersionControlledSettingsError>(())
+        })
+        .await??;
         // run post-save hooks
         self.post_save().await?;
         Ok(())
@@ -1,26 +1,27 @@
 #[macro_use]
 extern crate rocket;
 use std::fs;
+use std::path::{Path, PathBuf};
 
 use rocket::fs::NamedFile;
+use rocket::http::ContentType;
 use rocket::response::status::NotFound;
+use rocket::response::stream::ByteStream;
 use rocket::State;
 
 use printnanny_settings::printnanny::PrintNannySettings;
 
-#[get("/jpeg")]
-async fn jpeg(state: &State<PrintNannySettings>) -> Result<NamedFile, NotFound<String>> {
-    let settings = state;
-    let dir = settings.paths.snapshot_dir.clone();
-    let dir_entry = fs::read_dir(&dir).map_err(|e| NotFound(e.to_string()))?;
+// matches the boundary mjpg-streamer uses, for clients that hard-code it
+const MJPEG_BOUNDARY: &str = "boundarydonotcross";
+// snapshot pipeline refreshes the jpeg a few times per second
+const MJPEG_FRAME_INTERVAL_MS: u64 = 250;
 
+fn latest_snapshot(dir: &Path) -> Result<PathBuf, NotFound<String>> {
+    let dir_entry = fs::read_dir(dir).map_err(|e| NotFound(e.to_string()))?;
     match dir_entry.last() {
         Some(last) => {
             let last = last.map_err(|e| NotFound(e.to_string()))?;
-            let result = NamedFile::open(last.path())
-                .await
-                .map_err(|e| NotFound(e.to_string()))?;
-            Ok(result)
+            Ok(last.path())
         }
         None => Err(NotFound(format!(
             "Failed to read directory {}",
@@ -29,11 +30,67 @@ async fn jpeg(state: &State<PrintNannySettings>) -> Result<NamedFile, NotFound<S
     }
 }
 
+#[get("/jpeg")]
+async fn jpeg(state: &State<PrintNannySettings>) -> Result<NamedFile, NotFound<String>> {
+    let settings = state;
+    let path = latest_snapshot(&settings.paths.snapshot_dir)?;
+    let result = NamedFile::open(path)
+        .await
+        .map_err(|e| NotFound(e.to_string()))?;
+    Ok(result)
+}
+
+// mjpg-streamer-compatible snapshot endpoint: /webcam/?action=snapshot
+// OctoPrint plugins and slicer print monitors (Cura, PrusaSlicer) expect
+// these URLs and can't be pointed at anything else
+#[get("/?action=snapshot")]
+async fn webcam_snapshot(state: &State<PrintNannySettings>) -> Result<NamedFile, NotFound<String>> {
+    jpeg(state).await
+}
+
+// mjpg-streamer-compatible MJPEG stream: /webcam/?action=stream
+// multipart/x-mixed-replace stream that re-reads the latest snapshot at a
+// fixed interval; runs until the client disconnects
+#[get("/?action=stream")]
+async fn webcam_stream(state: &State<PrintNannySettings>) -> (ContentType, ByteStream![Vec<u8>]) {
+    let snapshot_dir = state.paths.snapshot_dir.clone();
+    let content_type =
+        ContentType::new("multipart", "x-mixed-replace").with_params(("boundary", MJPEG_BOUNDARY));
+    let stream = ByteStream! {
+        loop {
+            if let Ok(path) = latest_snapshot(&snapshot_dir) {
+                if let Ok(frame) = rocket::tokio::fs::read(&path).await {
+                    let mut part = Vec::with_capacity(frame.len() + 128);
+                    part.extend_from_slice(
+                        format!(
+                            "--{}\r\nContent-Type: image/jpeg\r\nContent-Length: {}\r\n\r\n",
+                            MJPEG_BOUNDARY,
+                            frame.len()
+                        )
+                        .as_bytes(),
+                    );
+                    part.extend_from_slice(&frame);
+                    part.extend_from_slice(b"\r\n");
+                    yield part;
+                }
+            }
+            rocket::tokio::time::sleep(std::time::Duration::from_millis(
+                MJPEG_FRAME_INTERVAL_MS,
+            ))
+            .await;
+        }
+    };
+    (content_type, stream)
+}
+
 #[launch]
 async fn rocket() -> _ {
     let settings = PrintNannySettings::new()
         .await
         .expect("Failed to initialize PrintNannySettings");
 
-    rocket::build().manage(settings).mount("/", routes![jpeg])
+    rocket::build()
+        .manage(settings)
+        .mount("/", routes![jpeg])
+        .mount("/webcam", routes![webcam_snapshot, webcam_stream])
 }